@@ -28,6 +28,10 @@ pub enum GitStatus {
 pub struct ApiConfig {
     pub project_root: String,
     pub specs: Vec<ApiSpecInfo>,
+
+    /// Exclude patterns applied to every impl that doesn't opt out
+    #[facet(default)]
+    pub default_excludes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Facet)]
@@ -49,6 +53,49 @@ pub struct ApiSpecInfo {
 
     /// Available implementations for this spec
     pub implementations: Vec<String>,
+
+    /// Policy results for implementations that have a coverage policy
+    #[facet(default)]
+    pub policies: Vec<ApiPolicyStatus>,
+
+    /// Implementations that set `no_default_excludes`
+    #[facet(default)]
+    pub no_default_excludes: Vec<String>,
+
+    /// Code units each implementation leaves out of unmapped coverage
+    #[facet(default)]
+    pub skipped_units: Vec<ApiSkippedUnits>,
+
+    /// Platform or feature-flag variants declared by the implementations
+    #[facet(default)]
+    pub variants: Vec<ApiImplVariant>,
+}
+
+/// The `skip_units` rules of an implementation, described for display
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiSkippedUnits {
+    pub impl_name: String,
+    pub rules: Vec<String>,
+}
+
+/// A variant of an implementation and the files only compiled for it
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiImplVariant {
+    pub impl_name: String,
+    pub name: String,
+    pub include: Vec<String>,
+}
+
+/// Result of checking a spec/impl pair against its coverage policy
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiPolicyStatus {
+    pub impl_name: String,
+    pub passed: bool,
+    /// Human-readable description of each failed check
+    pub violations: Vec<String>,
 }
 
 /// Forward traceability: rules with their code references
@@ -57,13 +104,13 @@ pub struct ApiForwardData {
     pub specs: Vec<ApiSpecForward>,
 }
 
-#[derive(Debug, Clone, Facet)]
+#[derive(Debug, Clone, Hash, Facet)]
 pub struct ApiSpecForward {
     pub name: String,
     pub rules: Vec<ApiRule>,
 }
 
-#[derive(Debug, Clone, Facet)]
+#[derive(Debug, Clone, Hash, Facet)]
 #[facet(rename_all = "camelCase")]
 pub struct ApiRule {
     pub id: RuleId,
@@ -75,18 +122,33 @@ pub struct ApiRule {
     pub status: Option<String>,
     #[facet(default)]
     pub level: Option<String>,
+    /// Tags from the marker's metadata (`tags=a,b`)
+    #[facet(default)]
+    pub tags: Vec<String>,
     #[facet(default)]
     pub source_file: Option<String>,
     #[facet(default)]
     pub source_line: Option<usize>,
     #[facet(default)]
     pub source_column: Option<usize>,
+    /// Link to the definition, built from the spec's `source_url`
+    #[facet(default)]
+    pub permalink: Option<String>,
     /// Section slug (heading ID) that this rule belongs to
     #[facet(default)]
     pub section: Option<String>,
     /// Section title (heading text) that this rule belongs to
     #[facet(default)]
     pub section_title: Option<String>,
+    /// IDs this rule was previously known by (`r[new.id <- old.id]`)
+    #[facet(default)]
+    pub renamed_from: Vec<RuleId>,
+    /// Rules of other specs this rule's text depends on
+    #[facet(default)]
+    pub depends_on: Vec<ApiSpecDependency>,
+    /// Higher-level rules of the same spec this rule refines (`r[refines sys.auth]`)
+    #[facet(default)]
+    pub refines: Vec<RuleId>,
     pub impl_refs: Vec<ApiCodeRef>,
     pub verify_refs: Vec<ApiCodeRef>,
     pub depends_refs: Vec<ApiCodeRef>,
@@ -97,16 +159,83 @@ pub struct ApiRule {
     /// Stale references pointing to older versions of this rule.
     #[facet(default)]
     pub stale_refs: Vec<ApiStaleRef>,
+    /// Teams or people responsible for this rule, from the config's `owners`
+    #[facet(default)]
+    pub owners: Vec<String>,
+    /// Reviewer approvals of this version of the rule's text
+    #[facet(default)]
+    pub approvals: Vec<ApiApproval>,
+    /// How the tests behind `verify_refs` did in the last ingested test
+    /// report (empty without one)
+    #[facet(default)]
+    pub test_results: Vec<ApiTestResult>,
+    /// How much of the code behind `impl_refs` ran, per the last ingested
+    /// line coverage report (empty without one)
+    #[facet(default)]
+    pub line_coverage: Vec<ApiLineCoverage>,
 }
 
-#[derive(Debug, Clone, Facet)]
+/// Executed lines of the code unit an `impl` reference annotates
+#[derive(Debug, Clone, PartialEq, Hash, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiLineCoverage {
+    pub file: String,
+    pub line: usize,
+    /// Name of the code unit, if it has one
+    #[facet(default)]
+    pub unit: Option<String>,
+    pub executed_lines: usize,
+    /// Lines of the unit the report has data for
+    pub total_lines: usize,
+}
+
+/// The test a `verify` reference is in, and its outcome in the last
+/// ingested test report
+#[derive(Debug, Clone, PartialEq, Hash, Facet)]
+pub struct ApiTestResult {
+    pub file: String,
+    pub line: usize,
+    /// Name of the function the reference is in, if it's in one
+    #[facet(default)]
+    pub test: Option<String>,
+    pub outcome: TestOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[facet(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+    /// The report has no test by this name
+    NotRun,
+}
+
+/// A reviewer's sign-off on one version of a rule, from `tracey approve`
+#[derive(Debug, Clone, PartialEq, Hash, Facet)]
+pub struct ApiApproval {
+    pub by: String,
+    /// Date of the approval (YYYY-MM-DD)
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Hash, Facet)]
 pub struct ApiCodeRef {
     pub file: String,
     pub line: usize,
 }
 
+/// A rule of another spec that a rule depends on (`r[depends other:api.fetch]`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Facet)]
+pub struct ApiSpecDependency {
+    /// Name of the spec defining the rule
+    pub spec: String,
+    pub id: RuleId,
+}
+
 /// A stale reference: code points to an older version of a rule.
-#[derive(Debug, Clone, Facet)]
+#[derive(Debug, Clone, Hash, Facet)]
 pub struct ApiStaleRef {
     pub file: String,
     pub line: usize,
@@ -115,7 +244,7 @@ pub struct ApiStaleRef {
 }
 
 /// Reverse traceability: file tree with coverage info
-#[derive(Debug, Clone, Facet)]
+#[derive(Debug, Clone, Hash, Facet)]
 #[facet(rename_all = "camelCase")]
 pub struct ApiReverseData {
     /// Total code units across all files
@@ -126,7 +255,7 @@ pub struct ApiReverseData {
     pub files: Vec<ApiFileEntry>,
 }
 
-#[derive(Debug, Clone, Facet)]
+#[derive(Debug, Clone, Hash, Facet)]
 #[facet(rename_all = "camelCase")]
 pub struct ApiFileEntry {
     pub path: String,
@@ -146,6 +275,30 @@ pub struct ApiFileData {
     pub html: String,
     /// Code units in this file with their coverage
     pub units: Vec<ApiCodeUnit>,
+    /// References in this file to the spec's rules, with how each resolves
+    #[facet(default)]
+    pub references: Vec<ApiFileReference>,
+}
+
+/// A requirement reference in a file, resolved against the current spec
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApiFileReference {
+    /// `impl`, `verify`, `depends`, ...
+    pub verb: String,
+    /// The rule ID as written, with the version it names
+    pub id: RuleId,
+    pub line: usize,
+    /// Byte offset of the reference in the file
+    pub offset: usize,
+    /// Byte length of the reference
+    pub length: usize,
+    /// `ok`, `stale` (names an older version), `renamed` (names an ID the
+    /// rule was renamed from) or `orphaned` (names no rule)
+    pub status: String,
+    /// The rule's current ID, for stale and renamed references
+    #[facet(default)]
+    pub current: Option<RuleId>,
 }
 
 #[derive(Debug, Clone, Facet)]
@@ -239,7 +392,7 @@ pub struct ValidationError {
     /// Related rule IDs (for dependency errors)
     #[facet(default)]
     pub related_rules: Vec<RuleId>,
-    /// The referenced rule ID (for StaleRequirement/UnknownRequirement errors)
+    /// The referenced rule ID (for Stale/Unknown/RenamedRequirement errors)
     #[facet(default)]
     pub reference_rule_id: Option<RuleId>,
     /// Original annotation text for unknown references (for example `r[impl auth.logn]`).
@@ -256,16 +409,43 @@ pub enum ValidationErrorCode {
     CircularDependency,
     /// Requirement ID doesn't follow naming convention
     InvalidNaming,
+    /// Requirement version isn't valid under the configured version scheme
+    InvalidVersion,
     /// Unknown requirement ID referenced
     UnknownRequirement,
     /// Reference points to an older requirement version
     StaleRequirement,
+    /// Reference uses an ID that a requirement was renamed from
+    RenamedRequirement,
+    /// Reference to a requirement that the working tree's specs removed since HEAD
+    RemovedRequirement,
     /// Duplicate requirement ID in the same spec
     DuplicateRequirement,
     /// Unknown prefix in reference
     UnknownPrefix,
     /// Impl annotation in test file (only verify allowed)
     ImplInTestFile,
+    /// Same rule referenced twice with the same verb in one code unit
+    DuplicateReference,
+    /// Error reported by a validation plugin, or a plugin that failed to run
+    PluginError,
+    /// Warning reported by a validation plugin
+    PluginWarning,
+    /// Matched source file the scanner skipped as too large or binary
+    SkippedFile,
+}
+
+impl ValidationErrorCode {
+    /// Whether findings with this code are warnings rather than errors
+    pub fn is_warning(self) -> bool {
+        matches!(
+            self,
+            ValidationErrorCode::DuplicateReference
+                | ValidationErrorCode::RenamedRequirement
+                | ValidationErrorCode::PluginWarning
+                | ValidationErrorCode::SkippedFile
+        )
+    }
 }
 
 /// Validation results for a spec/implementation pair
@@ -0,0 +1,120 @@
+//! Scan, rebuild and query timings on a synthetic project.
+//!
+//! The project is [`SyntheticProject::MONOREPO`] unless `TRACEY_BENCH_FILES`
+//! or `TRACEY_BENCH_RULES` set a smaller one for a quick run. Compare the
+//! numbers against `tracey::bench::TARGETS`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use tracey::bench::SyntheticProject;
+use tracey::config::Config;
+use tracey::data::{BuildCache, DashboardData, FileOverlay};
+use tracey::server::QueryEngine;
+
+fn project_size() -> SyntheticProject {
+    let var = |name: &str, default: usize| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    SyntheticProject {
+        files: var("TRACEY_BENCH_FILES", SyntheticProject::MONOREPO.files),
+        rules: var("TRACEY_BENCH_RULES", SyntheticProject::MONOREPO.rules),
+    }
+}
+
+async fn build(
+    root: &Path,
+    config: &Config,
+    overlay: &FileOverlay,
+    cache: &mut BuildCache,
+    changed_files: &[PathBuf],
+) -> DashboardData {
+    tracey::data::build_dashboard_data_with_overlay_and_cache(
+        root,
+        config,
+        1,
+        true,
+        overlay,
+        cache,
+        changed_files,
+    )
+    .await
+    .expect("build failed")
+}
+
+fn monorepo(c: &mut Criterion) {
+    let temp = tempfile::tempdir().expect("failed to create temp dir");
+    let root = temp.path();
+    project_size()
+        .generate(root)
+        .expect("failed to generate project");
+    let config = tracey::load_config(&root.join(".config/tracey/config.styx"))
+        .expect("failed to load config");
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start runtime");
+
+    let mut group = c.benchmark_group("monorepo");
+    group.sample_size(10);
+
+    group.bench_function("full_build", |b| {
+        b.iter(|| {
+            rt.block_on(build(
+                root,
+                &config,
+                &FileOverlay::new(),
+                &mut BuildCache::default(),
+                &[],
+            ))
+        })
+    });
+
+    group.bench_function("incremental_rebuild", |b| {
+        let mut cache = BuildCache::default();
+        rt.block_on(build(root, &config, &FileOverlay::new(), &mut cache, &[]));
+        let edited = tracey_core::canonical_path(&root.join("src/m0/f0.rs"));
+        let content = std::fs::read_to_string(&edited).expect("failed to read source file");
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for i in 0..iters {
+                // A different edit each time, so the content hash changes.
+                let mut overlay = FileOverlay::new();
+                overlay.insert(edited.clone(), format!("{content}// edit {i}\n"));
+                let start = Instant::now();
+                rt.block_on(build(
+                    root,
+                    &config,
+                    &overlay,
+                    &mut cache,
+                    std::slice::from_ref(&edited),
+                ));
+                total += start.elapsed();
+            }
+            total
+        })
+    });
+
+    let data = rt.block_on(build(
+        root,
+        &config,
+        &FileOverlay::new(),
+        &mut BuildCache::default(),
+        &[],
+    ));
+    group.bench_function("query_status", |b| {
+        b.iter(|| {
+            let query = QueryEngine::new(&data);
+            (query.status(), query.status_by_rule())
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, monorepo);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+//! Write a synthetic project to profile tracey on by hand:
+//!
+//! ```text
+//! cargo run -p tracey-bench -- /tmp/monorepo [FILES] [RULES]
+//! ```
+//!
+//! Sizes default to [`SyntheticProject::MONOREPO`], the scale the
+//! performance targets are set for.
+
+use std::path::PathBuf;
+
+use eyre::{Result, WrapErr, bail};
+use tracey::bench::SyntheticProject;
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let Some(root) = args.next().map(PathBuf::from) else {
+        bail!("usage: tracey-bench <DIR> [FILES] [RULES]");
+    };
+    let mut project = SyntheticProject::MONOREPO;
+    if let Some(files) = args.next() {
+        project.files = files.parse().wrap_err("FILES must be a number")?;
+    }
+    if let Some(rules) = args.next() {
+        project.rules = rules.parse().wrap_err("RULES must be a number")?;
+    }
+
+    project.generate(&root)?;
+    println!(
+        "Wrote {} files and {} rules to {}",
+        project.files,
+        project.rules,
+        root.display()
+    );
+    Ok(())
+}
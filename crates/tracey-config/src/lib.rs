@@ -13,6 +13,155 @@ pub struct Config {
     /// Specifications to track coverage against
     #[facet(default)]
     pub specs: Vec<SpecConfig>,
+
+    /// WebAssembly validation plugins, relative to the project root
+    /// r[impl config.plugins]
+    #[facet(default)]
+    pub plugins: Vec<String>,
+
+    /// Who is responsible for which requirements, CODEOWNERS-style
+    /// r[impl config.owners]
+    #[facet(default)]
+    pub owners: Vec<OwnerRule>,
+
+    /// How requirement versions are numbered: "integer" (default), "date"
+    /// (`+YYYY-MM-DD`) or "free-form"
+    /// r[impl config.version-scheme]
+    #[facet(default)]
+    pub version_scheme: Option<String>,
+
+    /// Jira project that `tracey sync jira` files requirement debt in
+    /// r[impl config.jira]
+    #[facet(default)]
+    pub jira: Option<JiraConfig>,
+
+    /// Whether file walks follow symbolic links (default: true); turn off
+    /// to keep symlinked vendored or shared checkouts out of the scan
+    /// r[impl config.follow-links]
+    #[facet(default)]
+    pub follow_links: Option<bool>,
+
+    /// Limits that keep large or binary files out of the scan
+    /// r[impl config.file-limits]
+    #[facet(default)]
+    pub file_limits: Option<FileLimits>,
+
+    /// Severities of the `tracey lint spec` checks
+    /// r[impl config.spec-lint]
+    #[facet(default)]
+    pub spec_lint: Option<SpecLint>,
+
+    /// What `tracey query --hyperlink` links file:line references to:
+    /// "file" (default), "vscode", "zed", "cursor", or a URL template with
+    /// `{path}` and `{line}`
+    /// r[impl config.editor-links]
+    #[facet(default)]
+    pub editor_links: Option<String>,
+
+    /// Timeouts and retries of `tracey query` and MCP calls to the daemon
+    /// r[impl config.query]
+    #[facet(default)]
+    pub query: Option<QueryConfig>,
+}
+
+/// How long clients wait for the daemon; the `TRACEY_QUERY_TIMEOUT_SECS` and
+/// `TRACEY_CONNECT_RETRIES` environment variables take precedence
+#[derive(Debug, Clone, Default, Facet)]
+pub struct QueryConfig {
+    /// Longest a call may take once connected, in seconds (default: 60, 0
+    /// for no limit)
+    #[facet(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// How many times a failed connect is retried (default: 2)
+    #[facet(default)]
+    pub connect_retries: Option<u32>,
+}
+
+/// How `tracey lint spec` reports each check: "error", "warning" or "off"
+#[derive(Debug, Clone, Default, Facet)]
+pub struct SpecLint {
+    /// Rules with no text after their marker (default: "error")
+    #[facet(default)]
+    pub empty_body: Option<String>,
+
+    /// Headings repeated within one spec file (default: "warning")
+    #[facet(default)]
+    pub duplicate_heading: Option<String>,
+
+    /// Rules whose marker isn't inside a blockquote (default: "off")
+    #[facet(default)]
+    pub outside_blockquote: Option<String>,
+
+    /// Numbered rules (`step.1`, `step.2`, ...) that skip or repeat a number
+    /// (default: "warning")
+    #[facet(default)]
+    pub numbering: Option<String>,
+
+    /// Rule texts longer than `max_words` (default: "warning")
+    #[facet(default)]
+    pub long_text: Option<String>,
+
+    /// Word limit of a rule text for `long_text` (default: 200)
+    #[facet(default)]
+    pub max_words: Option<usize>,
+}
+
+/// Files the scanner skips instead of loading
+#[derive(Debug, Clone, Default, Facet)]
+pub struct FileLimits {
+    /// Skip source files larger than this many megabytes (default: 10, 0 for no limit)
+    #[facet(default)]
+    pub max_file_mb: Option<u64>,
+
+    /// Skip files that contain NUL bytes or aren't valid UTF-8 (default: true)
+    #[facet(default)]
+    pub skip_binary: Option<bool>,
+}
+
+/// Where `tracey sync jira` opens issues for uncovered or stale MUST-level
+/// requirements
+#[derive(Debug, Clone, Facet)]
+pub struct JiraConfig {
+    /// Base URL of the Jira site, e.g. "https://example.atlassian.net"
+    pub url: String,
+
+    /// Key of the project to file issues in, e.g. "PROTO"
+    pub project: String,
+
+    /// API token (Jira Cloud) or personal access token (Data Center);
+    /// usually `${JIRA_TOKEN}`
+    pub token: String,
+
+    /// Account email for Jira Cloud's basic auth; without it the token is
+    /// sent as a bearer token
+    #[facet(default)]
+    pub user: Option<String>,
+
+    /// Issue type of new issues (default: "Task")
+    #[facet(default)]
+    pub issue_type: Option<String>,
+
+    /// Transition that closes an issue once its rule is covered
+    /// (default: "Done")
+    #[facet(default)]
+    pub close_transition: Option<String>,
+
+    /// Transition that reopens a closed issue whose rule regressed
+    /// (default: "To Do")
+    #[facet(default)]
+    pub reopen_transition: Option<String>,
+}
+
+/// Maps requirement IDs to the teams or people who own them
+#[derive(Debug, Clone, Facet)]
+pub struct OwnerRule {
+    /// Glob over base requirement IDs, e.g. "auth.*"
+    pub rules: String,
+
+    /// Owners of the matching requirements, e.g. "@platform-team"
+    #[facet(default)]
+    pub owners: Vec<String>,
 }
 
 /// Configuration for a single specification
@@ -39,12 +188,60 @@ pub struct SpecConfig {
     #[facet(default)]
     pub include: Vec<String>,
 
+    /// URL of a markdown document containing requirement definitions.
+    /// The document is fetched and cached locally, then scanned alongside `include`.
+    /// r[impl config.spec.rules-url]
+    #[facet(default)]
+    pub rules_url: Option<String>,
+
+    /// How long a fetched `rules_url` document stays fresh before it is
+    /// revalidated, e.g. "30m", "6h", "1d" (default: "1h")
+    /// r[impl config.spec.rules-url.refresh]
+    #[facet(default)]
+    pub rules_url_refresh: Option<String>,
+
+    /// Git repository the spec files are fetched from, e.g.
+    /// `source { git "https://github.com/org/spec", rev "v1.2.0", path "spec/**/*.md" }`
+    /// r[impl config.spec.source]
+    #[facet(default)]
+    pub source: Option<SpecSource>,
+
+    /// Coverage policy applied to every impl of this spec
+    /// r[impl config.policy]
+    #[facet(default)]
+    pub policy: Option<Policy>,
+
     /// Implementations of this spec (by language)
     /// Each impl block specifies which source files to scan
     #[facet(default)]
     pub impls: Vec<Impl>,
 }
 
+/// Remote git source for a spec's markdown files
+#[derive(Debug, Clone, Facet)]
+pub struct SpecSource {
+    /// URL of the git repository to clone
+    /// r[impl config.spec.source.git]
+    pub git: String,
+
+    /// Tag, branch, or commit to check out (default: the remote's HEAD).
+    /// Only a full commit hash is never refetched.
+    /// r[impl config.spec.source.rev]
+    #[facet(default)]
+    pub rev: Option<String>,
+
+    /// Glob pattern for spec files, relative to the repository root
+    /// (default: "**/*.md")
+    #[facet(default)]
+    pub path: Option<String>,
+}
+
+/// Exclude patterns applied to every impl unless it sets `no_default_excludes`
+pub const DEFAULT_EXCLUDES: &[&str] = &["**/target/**", "**/node_modules/**", "**/vendor/**"];
+
+/// Test file patterns added to `test_include` when an impl sets `test_conventions`
+pub const TEST_CONVENTION_PATTERNS: &[&str] = &["**/tests/**", "**/*_test.go"];
+
 /// Configuration for a single implementation of a spec
 #[derive(Debug, Clone, Facet)]
 pub struct Impl {
@@ -62,8 +259,98 @@ pub struct Impl {
     #[facet(default)]
     pub exclude: Vec<String>,
 
+    /// Don't apply the built-in default excludes (`target/`, `node_modules/`,
+    /// `vendor/`) to this impl
+    /// r[impl config.impl.default-excludes.opt-out]
+    #[facet(default)]
+    pub no_default_excludes: bool,
+
     /// Glob patterns for test files (only verify annotations allowed)
     /// r[impl config.impl.test_include]
     #[facet(default)]
     pub test_include: Vec<String>,
+
+    /// Also treat conventional test locations as test code: files under a
+    /// `tests/` directory, `*_test.go` files, and Rust `#[cfg(test)]` modules
+    /// r[impl config.impl.test-conventions]
+    #[facet(default)]
+    pub test_conventions: bool,
+
+    /// Coverage policy for this impl; fields set here override the spec's policy
+    /// r[impl config.policy.override]
+    #[facet(default)]
+    pub policy: Option<Policy>,
+
+    /// Which code units count towards unmapped and reverse coverage: `items`
+    /// (the default, every unit), `functions`, `impls`, `modules` or `files`
+    /// r[impl config.impl.code-units]
+    #[facet(default)]
+    pub code_units: Option<String>,
+
+    /// Code units left out of unmapped and reverse coverage, e.g.
+    /// `skip_units {trait_impls (Display Debug), min_function_lines 3}`
+    /// r[impl config.impl.skip-units]
+    #[facet(default)]
+    pub skip_units: Option<SkipUnits>,
+
+    /// Platforms or feature flags the impl is compiled for, each with the
+    /// files only built for it, e.g. `variants ({name linux, include (src/sys/linux/**)})`
+    /// r[impl config.impl.variants]
+    #[facet(default)]
+    pub variants: Vec<Variant>,
+}
+
+/// Trivially-uninteresting code units that don't count as unmapped
+#[derive(Debug, Clone, Default, Facet)]
+pub struct SkipUnits {
+    /// Skip impl blocks marked `#[automatically_derived]`, as derive macros emit them
+    #[facet(default)]
+    pub derived: bool,
+
+    /// Skip impls of these traits and their methods (e.g., "Display", "Debug")
+    #[facet(default)]
+    pub trait_impls: Vec<String>,
+
+    /// Skip code in test files and `#[cfg(test)]` modules
+    #[facet(default)]
+    pub test_helpers: bool,
+
+    /// Skip functions shorter than this many lines
+    #[facet(default)]
+    pub min_function_lines: Option<usize>,
+}
+
+/// A platform or feature-flag variant of an impl
+#[derive(Debug, Clone, Facet)]
+pub struct Variant {
+    /// Name of the variant (e.g., "linux", "wasm")
+    pub name: String,
+
+    /// Glob patterns for the impl's files that are only compiled for this variant
+    #[facet(default)]
+    pub include: Vec<String>,
+}
+
+/// Coverage thresholds and failure rules checked by `tracey check`
+#[derive(Debug, Clone, Default, Facet)]
+pub struct Policy {
+    /// Minimum implementation coverage, e.g. "90%"
+    /// r[impl config.policy.min-impl-coverage]
+    #[facet(default)]
+    pub min_impl_coverage: Option<String>,
+
+    /// Maximum number of stale references allowed
+    /// r[impl config.policy.max-stale]
+    #[facet(default)]
+    pub max_stale: Option<usize>,
+
+    /// Fail if any requirement has no references at all
+    /// r[impl config.policy.forbid-unreferenced]
+    #[facet(default)]
+    pub forbid_unreferenced: Option<bool>,
+
+    /// Fail if a MUST-level requirement's current version isn't approved
+    /// r[impl config.policy.require-approval]
+    #[facet(default)]
+    pub require_approval: Option<bool>,
 }
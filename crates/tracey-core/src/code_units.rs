@@ -16,11 +16,13 @@
 
 use crate::positions::{ByteOffset, LineNumber, RefLocation};
 use crate::{RuleId, parse_rule_id};
-use arborium::tree_sitter::{Node, Parser};
+use arborium::tree_sitter::{Node, Parser, Tree};
+use facet::Facet;
 use std::path::{Path, PathBuf};
 
 /// A semantic unit of code (function, struct, impl, etc.)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CodeUnit {
     /// The kind of code unit (e.g., "function", "struct", "impl")
     pub kind: CodeUnitKind,
@@ -38,10 +40,22 @@ pub struct CodeUnit {
     pub end_byte: usize,
     /// Requirement IDs referenced in comments associated with this code unit
     pub req_refs: Vec<RuleId>,
+    /// Requirement families (e.g., `config.*`) assigned by `impl-scope`
+    /// markers of the enclosing file or module
+    pub scopes: Vec<String>,
+}
+
+impl CodeUnit {
+    /// Whether the unit carries a reference or lies in an `impl-scope`.
+    pub fn is_covered(&self) -> bool {
+        !self.req_refs.is_empty() || !self.scopes.is_empty()
+    }
 }
 
 /// The kind of code unit
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
 pub enum CodeUnitKind {
     /// A function or method
     Function,
@@ -112,14 +126,14 @@ impl CodeUnits {
         self.units.is_empty()
     }
 
-    /// Count of code units with at least one requirement reference
+    /// Count of code units with at least one requirement reference or scope
     pub fn covered_count(&self) -> usize {
-        self.units.iter().filter(|u| !u.req_refs.is_empty()).count()
+        self.units.iter().filter(|u| u.is_covered()).count()
     }
 
-    /// Count of code units without any requirement references
+    /// Count of code units without any requirement references or scopes
     pub fn uncovered_count(&self) -> usize {
-        self.units.iter().filter(|u| u.req_refs.is_empty()).count()
+        self.units.iter().filter(|u| !u.is_covered()).count()
     }
 
     /// Reverse coverage percentage (0.0 to 100.0)
@@ -132,12 +146,12 @@ impl CodeUnits {
 
     /// Get all uncovered code units
     pub fn uncovered(&self) -> impl Iterator<Item = &CodeUnit> {
-        self.units.iter().filter(|u| u.req_refs.is_empty())
+        self.units.iter().filter(|u| !u.is_covered())
     }
 
     /// Get all covered code units
     pub fn covered(&self) -> impl Iterator<Item = &CodeUnit> {
-        self.units.iter().filter(|u| !u.req_refs.is_empty())
+        self.units.iter().filter(|u| u.is_covered())
     }
 
     /// Merge another CodeUnits into this one
@@ -146,6 +160,82 @@ impl CodeUnits {
     }
 }
 
+/// Which code units of a file count towards unmapped and reverse coverage.
+///
+/// Extraction always keeps every unit, so references resolve the same way
+/// whatever the granularity; only the counts change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Granularity {
+    /// Every extracted unit: functions, types, impl blocks, modules, ...
+    #[default]
+    Items,
+    /// Functions and methods only
+    Functions,
+    /// Functions, methods and impl blocks
+    Impls,
+    /// The file and each inline module; a module is covered when any unit
+    /// directly inside it is
+    Modules,
+    /// Each file is one unit, covered when any of its units is
+    Files,
+}
+
+impl Granularity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "items" => Some(Self::Items),
+            "functions" => Some(Self::Functions),
+            "impls" => Some(Self::Impls),
+            "modules" => Some(Self::Modules),
+            "files" => Some(Self::Files),
+            _ => None,
+        }
+    }
+
+    /// Total and covered unit counts for the units of one file.
+    pub fn count(self, units: &[CodeUnit]) -> (usize, usize) {
+        let covered = |u: &&CodeUnit| u.is_covered();
+        match self {
+            Self::Items => (units.len(), units.iter().filter(covered).count()),
+            Self::Functions | Self::Impls => {
+                let counted: Vec<&CodeUnit> = units
+                    .iter()
+                    .filter(|u| {
+                        u.kind == CodeUnitKind::Function
+                            || (self == Self::Impls && u.kind == CodeUnitKind::Impl)
+                    })
+                    .collect();
+                (counted.len(), counted.iter().filter(covered).count())
+            }
+            Self::Modules => {
+                let modules: Vec<&CodeUnit> = units
+                    .iter()
+                    .filter(|u| u.kind == CodeUnitKind::Module)
+                    .collect();
+                // Index 0 is the file itself, then one slot per inline module.
+                let mut module_covered = vec![false; modules.len() + 1];
+                for unit in units.iter().filter(covered) {
+                    let innermost = modules
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, m)| {
+                            m.start_byte <= unit.start_byte && unit.end_byte <= m.end_byte
+                        })
+                        .min_by_key(|(_, m)| m.end_byte - m.start_byte)
+                        .map_or(0, |(i, _)| i + 1);
+                    module_covered[innermost] = true;
+                }
+                (
+                    module_covered.len(),
+                    module_covered.iter().filter(|c| **c).count(),
+                )
+            }
+            Self::Files if units.is_empty() => (0, 0),
+            Self::Files => (1, usize::from(units.iter().any(|u| covered(&u)))),
+        }
+    }
+}
+
 /// Extract code units from source code, auto-detecting language from file extension
 pub fn extract(path: &Path, source: &str) -> CodeUnits {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
@@ -719,6 +809,7 @@ fn extract_elixir_recursive(path: &Path, source: &str, node: Node, units: &mut C
             start_byte,
             end_byte: node.end_byte(),
             req_refs,
+            scopes: Vec::new(),
         });
     }
 
@@ -820,6 +911,7 @@ fn extract_clojure_recursive(path: &Path, source: &str, node: Node, units: &mut
             start_byte,
             end_byte: node.end_byte(),
             req_refs,
+            scopes: Vec::new(),
         });
     }
 
@@ -1125,6 +1217,7 @@ where
         start_byte,
         end_byte: node.end_byte(),
         req_refs,
+        scopes: Vec::new(),
     })
 }
 
@@ -1432,8 +1525,8 @@ pub fn extract_refs(path: &Path, source: &str) -> Vec<FullReqRef> {
     extract_refs_with_warnings(path, source).references
 }
 
-/// Extract all requirement references and malformed-reference warnings.
-pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
+/// Parse a source file with the grammar its extension selects, if any.
+fn parse_source(path: &Path, source: &str) -> Option<Tree> {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
 
     let language = match ext {
@@ -1465,15 +1558,19 @@ pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
         "cmake" => arborium_cmake::language(),
         "ml" | "mli" => arborium_ocaml::language(),
         "sh" | "bash" | "zsh" => arborium_bash::language(),
-        _ => return ExtractedRefs::default(),
+        _ => return None,
     };
 
     let mut parser = Parser::new();
     parser
         .set_language(&language.into())
         .expect("Failed to load grammar");
+    parser.parse(source, None)
+}
 
-    let Some(tree) = parser.parse(source, None) else {
+/// Extract all requirement references and malformed-reference warnings.
+pub fn extract_refs_with_warnings(path: &Path, source: &str) -> ExtractedRefs {
+    let Some(tree) = parse_source(path, source) else {
         return ExtractedRefs::default();
     };
 
@@ -1549,7 +1646,86 @@ fn check_ignore_directives(text: &str, line: LineNumber, state: &mut IgnoreState
         state.ignore_next_line = None;
     }
 
-    true
+    // r[impl ref.ignore.todo-stub]
+    !text.contains(crate::lexer::TODO_STUB)
+}
+
+fn is_comment_node(node: Node) -> bool {
+    matches!(
+        node.kind(),
+        "line_comment"
+            | "block_comment"
+            | "comment"
+            | "multiline_comment"
+            | "bracket_comment"
+            | "documentation_comment"
+            | "line_outer_doc_comment"
+            | "line_inner_doc_comment"
+            | "block_outer_doc_comment"
+            | "block_inner_doc_comment"
+    )
+}
+
+/// A comment of a source file, for tools that rewrite the references in it.
+#[derive(Debug, Clone)]
+pub struct Comment {
+    /// Byte range of the comment, delimiters included
+    pub range: std::ops::Range<usize>,
+    /// Whether `@tracey:ignore-*` directives exclude the comment's references
+    pub ignored: bool,
+    /// For each byte of `range`, whether it is inside markdown code (an
+    /// inline code span or a fenced block), where references are ignored
+    pub code_mask: Vec<bool>,
+}
+
+/// The comments of a source file in order, as reference extraction sees them.
+pub fn comments(path: &Path, source: &str) -> Vec<Comment> {
+    let Some(tree) = parse_source(path, source) else {
+        return Vec::new();
+    };
+    let file_code_mask = crate::markdown::compute_doc_comment_code_mask(source);
+    let mut comments = Vec::new();
+    let mut ignore_state = IgnoreState::default();
+    collect_comments(
+        source,
+        tree.root_node(),
+        &file_code_mask,
+        &mut ignore_state,
+        &mut comments,
+    );
+    comments
+}
+
+fn collect_comments(
+    source: &str,
+    node: Node,
+    file_code_mask: &[bool],
+    ignore_state: &mut IgnoreState,
+    comments: &mut Vec<Comment>,
+) {
+    if is_comment_node(node) {
+        let range = node.byte_range();
+        let text = &source[range.clone()];
+        let line = LineNumber::from_zero_based(node.start_position().row);
+        let ignored = !check_ignore_directives(text, line, ignore_state);
+        let code_mask = crate::markdown::markdown_code_mask(text)
+            .into_iter()
+            .enumerate()
+            .map(|(i, code)| {
+                code || crate::markdown::is_code_index(range.start + i, file_code_mask)
+            })
+            .collect();
+        comments.push(Comment {
+            range,
+            ignored,
+            code_mask,
+        });
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comments(source, child, file_code_mask, ignore_state, comments);
+    }
 }
 
 fn extract_refs_recursive(
@@ -1567,21 +1743,7 @@ fn extract_refs_recursive(
     //         block_outer_doc_comment (/** */), block_inner_doc_comment (/*! */)
     // - Swift/Go/TypeScript: comment
     // - Python: comment
-    let is_comment = matches!(
-        node.kind(),
-        "line_comment"
-            | "block_comment"
-            | "comment"
-            | "multiline_comment"
-            | "bracket_comment"
-            | "documentation_comment"
-            | "line_outer_doc_comment"
-            | "line_inner_doc_comment"
-            | "block_outer_doc_comment"
-            | "block_inner_doc_comment"
-    );
-
-    if is_comment {
+    if is_comment_node(node) {
         let text = &source[node.byte_range()];
         let line = LineNumber::from_zero_based(node.start_position().row);
         let base_offset = ByteOffset::from_usize(node.start_byte());
@@ -1889,6 +2051,40 @@ mod tests {
         parse_rule_id(id).expect("valid rule id")
     }
 
+    #[test]
+    fn granularity_changes_what_is_counted() {
+        let unit = |kind, start, end, covered: bool| CodeUnit {
+            kind,
+            name: None,
+            file: PathBuf::from("src/lib.rs"),
+            start_line: 1,
+            end_line: 1,
+            start_byte: start,
+            end_byte: end,
+            req_refs: if covered { vec![rid("a.b")] } else { vec![] },
+            scopes: vec![],
+        };
+        let units = [
+            unit(CodeUnitKind::Struct, 0, 10, false),
+            unit(CodeUnitKind::Impl, 10, 50, false),
+            unit(CodeUnitKind::Function, 20, 40, true),
+            unit(CodeUnitKind::Module, 50, 100, false),
+            unit(CodeUnitKind::Function, 60, 80, false),
+        ];
+
+        assert_eq!(Granularity::Items.count(&units), (5, 1));
+        assert_eq!(Granularity::Functions.count(&units), (2, 1));
+        assert_eq!(Granularity::Impls.count(&units), (3, 1));
+        assert_eq!(Granularity::Modules.count(&units), (2, 1));
+        assert_eq!(Granularity::Files.count(&units), (1, 1));
+        assert_eq!(Granularity::Files.count(&[]), (0, 0));
+        assert_eq!(
+            Granularity::from_name("modules"),
+            Some(Granularity::Modules)
+        );
+        assert_eq!(Granularity::from_name("classes"), None);
+    }
+
     #[test]
     fn test_extract_refs_doc_comment() {
         let source = r#"
@@ -1913,6 +2109,34 @@ fn do_thing() {}
         assert_eq!(refs[0].verb, "impl");
     }
 
+    #[test]
+    fn test_comments_mark_ignored_and_code() {
+        let source = "\
+// @tracey:ignore-next-line
+// r[impl a.b]
+/// Write `r[impl a.b]` above the function.
+fn f() {}
+";
+        let comments = comments(Path::new("test.rs"), source);
+        let texts: Vec<&str> = comments
+            .iter()
+            .map(|c| source[c.range.clone()].trim_end())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "// @tracey:ignore-next-line",
+                "// r[impl a.b]",
+                "/// Write `r[impl a.b]` above the function."
+            ]
+        );
+        assert!(comments[0].ignored && comments[1].ignored && !comments[2].ignored);
+        let doc = &comments[2];
+        let code_at = |needle: &str| doc.code_mask[source[doc.range.clone()].find(needle).unwrap()];
+        assert!(code_at("r[impl"));
+        assert!(!code_at("above"));
+    }
+
     #[test]
     fn test_extract_refs_byte_span_uses_inclusive_end() {
         let source = "// r[foo.bar]\n";
@@ -4,7 +4,6 @@
 //! It scans comments for patterns like `r[verb rule.id]`.
 
 use crate::RuleId;
-#[cfg(not(feature = "reverse"))]
 use crate::parse_rule_id;
 use crate::positions::ByteSpan;
 #[cfg(not(feature = "reverse"))]
@@ -19,6 +18,7 @@ use std::path::{Path, PathBuf};
 /// r[impl ref.span.offset]
 /// r[impl ref.span.length]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourceSpan {
     /// Byte offset from start of file
     pub offset: usize,
@@ -45,6 +45,7 @@ impl From<ByteSpan> for SourceSpan {
 /// r[impl ref.verb.depends]
 /// r[impl ref.verb.related]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum RefVerb {
     /// Where the requirement is defined (typically in specs/docs)
@@ -94,6 +95,7 @@ impl std::fmt::Display for RefVerb {
 ///
 /// r[impl ref.span.file]
 #[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReqReference {
     /// The prefix identifying which spec this reference belongs to
     pub prefix: String,
@@ -111,6 +113,7 @@ pub struct ReqReference {
 
 /// Warning during parsing
 #[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParseWarning {
     /// File where the warning occurred
     pub file: PathBuf,
@@ -126,6 +129,7 @@ pub struct ParseWarning {
 ///
 /// r[impl ref.verb.unknown]
 #[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum WarningKind {
     /// Unknown verb in `[verb rule.id]`
@@ -223,8 +227,78 @@ pub(crate) fn extract_from_content(path: &Path, content: &str, reqs: &mut Reqs)
         // Fallback: text-based scanning
         extract_from_content_text_based(path, content, reqs);
     }
+
+    if path.extension().is_some_and(|ext| ext == "rs") {
+        extract_verify_attributes(path, content, reqs);
+    }
+}
+
+/// Start of the `#[tracey_runtime::verify(...)]` attribute
+const VERIFY_ATTRIBUTE: &str = "#[tracey_runtime::verify(";
+
+/// Extract a `verify` reference from each string literal of the
+/// `#[tracey_runtime::verify("rule.id", ...)]` attributes that start a line. A
+/// literal is a requirement ID, with the `r` prefix, or a whole
+/// `prefix[rule.id]` marker.
+///
+/// r[impl ref.attribute.verify]
+fn extract_verify_attributes(path: &Path, content: &str, reqs: &mut Reqs) {
+    let mut line_start = 0;
+    for line in content.split_inclusive('\n') {
+        let start = line_start + (line.len() - line.trim_start().len());
+        line_start += line.len();
+        if !content[start..].starts_with(VERIFY_ATTRIBUTE) {
+            continue;
+        }
+        let args_start = start + VERIFY_ATTRIBUTE.len();
+        let Some(args_len) = content[args_start..].find(")]") else {
+            continue;
+        };
+        let args = &content[args_start..args_start + args_len];
+        let mut i = 0;
+        while let Some(open) = args[i..].find('"') {
+            let literal_start = i + open + 1;
+            let Some(literal_len) = args[literal_start..].find('"') else {
+                break;
+            };
+            let offset = args_start + literal_start;
+            let text = &content[offset..offset + literal_len];
+            let line = content[..offset].matches('\n').count() + 1;
+            let span = SourceSpan::new(offset, literal_len);
+            let (prefix, id) = match text.strip_suffix(']').and_then(|t| t.split_once('[')) {
+                Some((prefix, id)) => (prefix, id),
+                None => ("r", text),
+            };
+            let valid_prefix = !prefix.is_empty()
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+            match parse_rule_id(id).filter(|id| valid_prefix && !id.base.ends_with('.')) {
+                Some(req_id) => reqs.references.push(ReqReference {
+                    prefix: prefix.to_string(),
+                    verb: RefVerb::Verify,
+                    req_id,
+                    file: path.to_path_buf(),
+                    line,
+                    span,
+                }),
+                None => reqs.warnings.push(ParseWarning {
+                    file: path.to_path_buf(),
+                    line,
+                    span,
+                    kind: WarningKind::MalformedReference,
+                }),
+            }
+            i = literal_start + literal_len + 1;
+        }
+    }
 }
 
+/// Marker of the comments `tracey todo --emit-stubs` writes, as in
+/// `// TODO(tracey): implement r[impl auth.mfa]`. No references are read
+/// from them, so a stub doesn't cover the rule it names.
+pub const TODO_STUB: &str = "TODO(tracey):";
+
 /// State for tracking ignore directives across lines.
 ///
 /// r[impl ref.ignore.prefix]
@@ -275,7 +349,8 @@ fn check_ignore_directives(text: &str, line: LineNumber, state: &mut IgnoreState
         state.ignore_next_line = None;
     }
 
-    true
+    // r[impl ref.ignore.todo-stub]
+    !text.contains(TODO_STUB)
 }
 
 #[cfg(not(feature = "reverse"))]
@@ -672,6 +747,51 @@ mod tests {
         assert_eq!(reqs.references[1].verb, RefVerb::Verify);
     }
 
+    // r[verify ref.attribute.verify]
+    #[test]
+    fn test_extract_verify_attributes() {
+        // `@` for `#`, so that tracey's own scan doesn't read these
+        let content = r#"
+            @[test]
+            @[tracey_runtime::verify("auth.login", "o[other.thing+2]")]
+            fn logs_in() {}
+
+            /// @[tracey_runtime::verify("in.docs")]
+            @[tracey_runtime::verify(
+                "auth.logout",
+                "Bad.Id",
+            )]
+            fn logs_out() {}
+        "#
+        .replace('@', "#");
+        let content = content.as_str();
+
+        let reqs = Reqs::extract_from_content(Path::new("tests/auth.rs"), content);
+        let refs: Vec<_> = reqs
+            .references
+            .iter()
+            .map(|r| (r.prefix.as_str(), r.req_id.to_string(), r.verb, r.line))
+            .collect();
+        assert_eq!(
+            refs,
+            [
+                ("r", "auth.login".to_string(), RefVerb::Verify, 3),
+                ("o", "other.thing+2".to_string(), RefVerb::Verify, 3),
+                ("r", "auth.logout".to_string(), RefVerb::Verify, 8),
+            ]
+        );
+        let span = reqs.references[0].span;
+        assert_eq!(
+            &content[span.offset..span.offset + span.length],
+            "auth.login"
+        );
+        assert_eq!(reqs.warnings.len(), 1);
+        assert_eq!(reqs.warnings[0].line, 9);
+
+        let other = Reqs::extract_from_content(Path::new("auth.ts"), content);
+        assert!(other.is_empty());
+    }
+
     #[test]
     fn test_extract_versioned_references() {
         let content = r#"
@@ -828,6 +948,20 @@ mod tests {
         assert_eq!(reqs.references[0].req_id, "visible.ref");
     }
 
+    // r[verify ref.ignore.todo-stub]
+    #[test]
+    fn test_ignore_refs_in_todo_stubs() {
+        let content = r#"
+            // TODO(tracey): implement r[impl stubbed.ref]
+            // r[impl visible.ref]
+            fn test() {}
+        "#;
+
+        let reqs = Reqs::extract_from_content(Path::new("test.rs"), content);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs.references[0].req_id, "visible.ref");
+    }
+
     #[test]
     fn test_ignore_refs_inside_fenced_code_in_comments() {
         let content = r#"
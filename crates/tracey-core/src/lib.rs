@@ -3,6 +3,10 @@
 //! This crate provides the building blocks for:
 //! - Extracting requirement references from source code (Rust, Swift, TypeScript, and more)
 //! - Computing coverage statistics
+//!
+//! To embed tracey's scanning in another tool, use [`Project::scan`]: it's
+//! the API covered by semver, see [`project`]. With the `serde` feature, the
+//! model it returns implements `Serialize` and `Deserialize`.
 
 mod coverage;
 mod lexer;
@@ -15,17 +19,25 @@ mod spec;
 #[cfg(feature = "reverse")]
 pub mod code_units;
 
+pub mod project;
+
 pub use coverage::CoverageReport;
-pub use lexer::{ParseWarning, RefVerb, ReqReference, Reqs, SourceSpan, WarningKind};
+pub use lexer::{ParseWarning, RefVerb, ReqReference, Reqs, SourceSpan, TODO_STUB, WarningKind};
+#[cfg(feature = "reverse")]
+pub use project::Unit;
+pub use project::{
+    Coverage, Id, ImplModel, ImplScan, Model, Project, Reference, Rule, ScanConfig, ScanError,
+    SpecModel, SpecScan, Verb, Warning,
+};
 pub use rule_id::{
-    RuleId, RuleIdMatch, classify_reference_for_rule, classify_reference_for_rule_str,
-    parse_rule_id,
+    RuleId, RuleIdMatch, VersionScheme, classify_reference_for_rule,
+    classify_reference_for_rule_str, normalize_date_versions, parse_rule_id,
 };
 pub use sources::{
-    ExtractionResult, MemorySources, PathSources, SUPPORTED_EXTENSIONS, Sources,
-    is_supported_extension,
+    ExtractionResult, IGNORE_FILENAME, MemorySources, PathSources, SUPPORTED_EXTENSIONS, Sources,
+    canonical_path, comment_delimiters, is_supported_extension,
 };
 pub use spec::ReqDefinition;
 
 #[cfg(feature = "walk")]
-pub use sources::WalkSources;
+pub use sources::{WalkSources, symlink_loop};
@@ -0,0 +1,661 @@
+//! The stable scanning API.
+//!
+//! r[impl core.api.scan]
+//!
+//! [`Project::scan`] reads a project's spec and implementation files and
+//! returns a [`Model`]: the rules each spec defines, the references each
+//! implementation makes to them and, with the `reverse` feature, its code
+//! units. This is the API for embedding tracey's scanning in other tools.
+//! It follows semver: the types below only gain fields and variants (they're
+//! `#[non_exhaustive]`) and methods in minor releases. They're copies of
+//! what the CLI works with, made at the end of a scan, so the rest of the
+//! crate stays the CLI's building blocks and may change in any release.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "walk")]
+use std::sync::Mutex;
+
+use facet::Facet;
+
+use crate::{ParseWarning, RefVerb, ReqReference, RuleId, WarningKind, parse_rule_id};
+#[cfg(feature = "walk")]
+use crate::{Reqs, is_supported_extension, lexer::extract_from_content, sources::WalkSources};
+
+#[cfg(feature = "reverse")]
+use crate::code_units::CodeUnit;
+
+/// Why a scan failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ScanError {
+    /// A spec file isn't valid markdown
+    Parse { file: PathBuf, message: String },
+    /// A spec file defines a rule with an invalid ID
+    InvalidRuleId { file: PathBuf, id: String },
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::Parse { file, message } => {
+                write!(f, "failed to parse {}: {message}", file.display())
+            }
+            ScanError::InvalidRuleId { file, id } => {
+                write!(f, "invalid rule id '{id}' in {}", file.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Which files of a project to scan.
+#[derive(Debug, Clone, Facet)]
+#[non_exhaustive]
+pub struct ScanConfig {
+    /// Project root, which include and exclude patterns are relative to
+    pub root: PathBuf,
+    pub specs: Vec<SpecScan>,
+    /// Whether to follow symbolic links (default: true)
+    pub follow_links: bool,
+}
+
+impl ScanConfig {
+    /// Scan nothing under `root` yet
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            specs: Vec::new(),
+            follow_links: true,
+        }
+    }
+
+    /// Add a spec to scan
+    pub fn spec(mut self, spec: SpecScan) -> Self {
+        self.specs.push(spec);
+        self
+    }
+
+    /// Whether to follow symbolic links (default: true)
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+}
+
+/// A spec: the markdown files defining its rules, and the implementations
+/// referencing them.
+#[derive(Debug, Clone, Facet)]
+#[non_exhaustive]
+pub struct SpecScan {
+    pub name: String,
+    /// Patterns of the spec's markdown files (e.g., `["docs/spec/**/*.md"]`)
+    pub include: Vec<String>,
+    pub impls: Vec<ImplScan>,
+}
+
+impl SpecScan {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            include: Vec::new(),
+            impls: Vec::new(),
+        }
+    }
+
+    /// Add patterns of the spec's markdown files
+    pub fn include(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add an implementation of the spec
+    pub fn implementation(mut self, implementation: ImplScan) -> Self {
+        self.impls.push(implementation);
+        self
+    }
+}
+
+/// An implementation of a spec: the source files to read references from.
+#[derive(Debug, Clone, Facet)]
+#[non_exhaustive]
+pub struct ImplScan {
+    pub name: String,
+    /// Patterns of the source files (e.g., `["src/**/*.rs"]`); every
+    /// supported file if empty
+    pub include: Vec<String>,
+    /// Patterns of source files to skip (e.g., `["target/**"]`)
+    pub exclude: Vec<String>,
+}
+
+impl ImplScan {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Add include patterns
+    pub fn include(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add exclude patterns
+    pub fn exclude(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.exclude.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Entry point of the scanning API.
+pub struct Project;
+
+#[cfg(feature = "walk")]
+impl Project {
+    /// Scan the files `config` selects.
+    ///
+    /// Paths in the model are relative to the config's root, except for
+    /// files outside it (cross-workspace patterns like `../other/**/*.rs`).
+    pub async fn scan(config: &ScanConfig) -> Result<Model, ScanError> {
+        let mut model = Model {
+            specs: Vec::new(),
+            warnings: Vec::new(),
+        };
+        for spec in &config.specs {
+            let (rules, warnings) = scan_rules(config, spec).await?;
+            model.warnings.extend(warnings);
+            let mut impls = Vec::new();
+            for implementation in &spec.impls {
+                let (scanned, warnings) = scan_impl(config, implementation);
+                model.warnings.extend(warnings);
+                impls.push(scanned);
+            }
+            model.specs.push(SpecModel {
+                name: spec.name.clone(),
+                rules,
+                impls,
+            });
+        }
+        Ok(model)
+    }
+}
+
+/// Everything a scan found.
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Model {
+    /// In the order of the config
+    pub specs: Vec<SpecModel>,
+    /// Paths that couldn't be scanned
+    pub warnings: Vec<String>,
+}
+
+impl Model {
+    /// The spec named `name`
+    pub fn spec(&self, name: &str) -> Option<&SpecModel> {
+        self.specs.iter().find(|spec| spec.name == name)
+    }
+
+    /// The rules of every spec
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.specs.iter().flat_map(|spec| &spec.rules)
+    }
+
+    /// The references of every implementation of every spec
+    pub fn references(&self) -> impl Iterator<Item = &Reference> {
+        self.specs.iter().flat_map(|spec| spec.references())
+    }
+
+    /// The code units of every implementation of every spec
+    #[cfg(feature = "reverse")]
+    pub fn code_units(&self) -> impl Iterator<Item = &Unit> {
+        self.specs
+            .iter()
+            .flat_map(|spec| &spec.impls)
+            .flat_map(|implementation| &implementation.code_units)
+    }
+}
+
+/// A scanned spec.
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct SpecModel {
+    pub name: String,
+    /// In file order, files sorted by path
+    pub rules: Vec<Rule>,
+    pub impls: Vec<ImplModel>,
+}
+
+impl SpecModel {
+    /// The references of every implementation
+    pub fn references(&self) -> impl Iterator<Item = &Reference> {
+        self.impls
+            .iter()
+            .flat_map(|implementation| &implementation.references)
+    }
+
+    /// Which rules the references of `implementation` cover. References
+    /// with another spec's prefix aren't counted as invalid.
+    pub fn coverage(&self, implementation: &str) -> Option<Coverage> {
+        let implementation = self.impls.iter().find(|i| i.name == implementation)?;
+        let prefixes: HashSet<&str> = self.rules.iter().map(|r| r.prefix.as_str()).collect();
+        let known: BTreeSet<&Id> = self.rules.iter().map(|r| &r.id).collect();
+        let mut covered = BTreeSet::new();
+        let mut invalid = Vec::new();
+        for reference in &implementation.references {
+            if !prefixes.contains(reference.prefix.as_str()) {
+                continue;
+            }
+            if known.contains(&reference.id) {
+                covered.insert(&reference.id);
+            } else {
+                invalid.push(reference.clone());
+            }
+        }
+        Some(Coverage {
+            total_rules: known.len(),
+            uncovered: known.difference(&covered).map(|id| (*id).clone()).collect(),
+            covered: covered.into_iter().cloned().collect(),
+            invalid,
+        })
+    }
+}
+
+/// How much of a spec an implementation covers.
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Coverage {
+    /// Distinct rule IDs the spec defines
+    pub total_rules: usize,
+    /// Rules referenced at least once, sorted
+    pub covered: Vec<Id>,
+    /// Rules never referenced, sorted
+    pub uncovered: Vec<Id>,
+    /// References to rules the spec doesn't define
+    pub invalid: Vec<Reference>,
+}
+
+impl Coverage {
+    /// Percentage of the rules covered (100 for a spec without rules)
+    pub fn coverage_percent(&self) -> f64 {
+        if self.total_rules == 0 {
+            return 100.0;
+        }
+        self.covered.len() as f64 / self.total_rules as f64 * 100.0
+    }
+}
+
+/// A rule ID: `auth.login+2` has the base `auth.login` and version 2.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Id {
+    pub base: String,
+    /// 1 for an unversioned ID; `YYYYMMDD` for a date version
+    pub version: u32,
+}
+
+impl Id {
+    /// Parse an ID as written in a marker, e.g. `auth.login+2`.
+    pub fn parse(id: &str) -> Option<Id> {
+        parse_rule_id(id).map(|id| Id::from(&id))
+    }
+}
+
+impl From<&RuleId> for Id {
+    fn from(id: &RuleId) -> Self {
+        Id {
+            base: id.base.clone(),
+            version: id.version,
+        }
+    }
+}
+
+impl Display for Id {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let id = RuleId {
+            base: self.base.clone(),
+            version: self.version,
+        };
+        Display::fmt(&id, f)
+    }
+}
+
+/// A rule defined in a spec file.
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Rule {
+    pub id: Id,
+    /// Marker prefix (`r` in `r[auth.login]`)
+    pub prefix: String,
+    pub file: PathBuf,
+    /// Line of the marker (1-indexed)
+    pub line: usize,
+    /// The rule's markdown text
+    pub text: String,
+}
+
+impl Rule {
+    /// The rules defined in the markdown `content` of `file`, without
+    /// reading anything from disk.
+    pub async fn extract_from_content(file: &Path, content: &str) -> Result<Vec<Rule>, ScanError> {
+        let normalized = crate::normalize_date_versions(content);
+        let doc = marq::render(&normalized, &marq::RenderOptions::default())
+            .await
+            .map_err(|e| ScanError::Parse {
+                file: file.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        let mut rules = Vec::new();
+        for req in doc.reqs {
+            let id = Id::parse(&req.id.to_string()).ok_or_else(|| ScanError::InvalidRuleId {
+                file: file.to_path_buf(),
+                id: req.id.to_string(),
+            })?;
+            let marker = content
+                .get(req.marker_span.offset..req.marker_span.offset + req.marker_span.length)
+                .unwrap_or("");
+            let prefix = marker.split('[').next().unwrap_or("").trim().to_string();
+            rules.push(Rule {
+                id,
+                prefix,
+                file: file.to_path_buf(),
+                line: req.line,
+                text: req.raw,
+            });
+        }
+        Ok(rules)
+    }
+}
+
+/// A scanned implementation.
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct ImplModel {
+    pub name: String,
+    /// Sorted by file and position
+    pub references: Vec<Reference>,
+    pub warnings: Vec<Warning>,
+    /// Sorted by file and position
+    #[cfg(feature = "reverse")]
+    pub code_units: Vec<Unit>,
+}
+
+/// A reference to a rule in a source file, as in `r[impl auth.login]`.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Reference {
+    /// Marker prefix, which names the spec
+    pub prefix: String,
+    pub verb: Verb,
+    pub id: Id,
+    pub file: PathBuf,
+    /// Line of the reference (1-indexed)
+    pub line: usize,
+    /// Byte offset of the reference in the file
+    pub offset: usize,
+    /// Length of the reference in bytes
+    pub length: usize,
+}
+
+impl From<&ReqReference> for Reference {
+    fn from(reference: &ReqReference) -> Self {
+        Reference {
+            prefix: reference.prefix.clone(),
+            verb: reference.verb.into(),
+            id: Id::from(&reference.req_id),
+            file: reference.file.clone(),
+            line: reference.line,
+            offset: reference.span.offset,
+            length: reference.span.length,
+        }
+    }
+}
+
+/// What a [`Reference`] says about its rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum Verb {
+    Define,
+    Impl,
+    Verify,
+    Depends,
+    Related,
+}
+
+impl Verb {
+    /// As written in a marker, e.g. `impl`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verb::Define => "define",
+            Verb::Impl => "impl",
+            Verb::Verify => "verify",
+            Verb::Depends => "depends",
+            Verb::Related => "related",
+        }
+    }
+}
+
+impl From<RefVerb> for Verb {
+    fn from(verb: RefVerb) -> Self {
+        match verb {
+            RefVerb::Define => Verb::Define,
+            RefVerb::Impl => Verb::Impl,
+            RefVerb::Verify => Verb::Verify,
+            RefVerb::Depends => Verb::Depends,
+            RefVerb::Related => Verb::Related,
+        }
+    }
+}
+
+impl Display for Verb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Something in a source file that looks like a reference but isn't one.
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Warning {
+    pub file: PathBuf,
+    /// Line of the text (1-indexed)
+    pub line: usize,
+    /// Byte offset of the text in the file
+    pub offset: usize,
+    /// Length of the text in bytes
+    pub length: usize,
+    /// What's wrong, e.g. `unknown verb 'implements'`
+    pub message: String,
+}
+
+impl From<&ParseWarning> for Warning {
+    fn from(warning: &ParseWarning) -> Self {
+        Warning {
+            file: warning.file.clone(),
+            line: warning.line,
+            offset: warning.span.offset,
+            length: warning.span.length,
+            message: match &warning.kind {
+                WarningKind::UnknownVerb(verb) => format!("unknown verb '{verb}'"),
+                WarningKind::MalformedReference => "malformed reference".to_string(),
+            },
+        }
+    }
+}
+
+/// A function, type, module or other item of a source file.
+#[cfg(feature = "reverse")]
+#[derive(Debug, Clone, PartialEq, Eq, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Unit {
+    /// E.g. `function`, `struct` or `impl`
+    pub kind: String,
+    pub name: Option<String>,
+    pub file: PathBuf,
+    /// First line (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed)
+    pub end_line: usize,
+    /// Rules referenced in the comments attached to the unit
+    pub references: Vec<Id>,
+}
+
+#[cfg(feature = "reverse")]
+impl From<&CodeUnit> for Unit {
+    fn from(unit: &CodeUnit) -> Self {
+        Unit {
+            kind: unit.kind.as_str().to_string(),
+            name: unit.name.clone(),
+            file: unit.file.clone(),
+            start_line: unit.start_line,
+            end_line: unit.end_line,
+            references: unit.req_refs.iter().map(Id::from).collect(),
+        }
+    }
+}
+
+/// `path` relative to `root`, if it's under it.
+#[cfg(feature = "walk")]
+fn relative(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
+}
+
+#[cfg(feature = "walk")]
+async fn scan_rules(
+    config: &ScanConfig,
+    spec: &SpecScan,
+) -> Result<(Vec<Rule>, Vec<String>), ScanError> {
+    let files = Mutex::new(Vec::new());
+    let warnings = WalkSources::new(&config.root)
+        .follow_links(config.follow_links)
+        .include(spec.include.iter().cloned())
+        .visit(
+            |ext| ext == "md",
+            |path, content| {
+                let file = relative(&config.root, path);
+                files.lock().unwrap().push((file, content.to_string()));
+            },
+        );
+    let mut files = files.into_inner().unwrap();
+    files.sort();
+
+    let mut rules = Vec::new();
+    for (file, content) in files {
+        rules.extend(Rule::extract_from_content(&file, &content).await?);
+    }
+    Ok((rules, warnings))
+}
+
+#[cfg(feature = "walk")]
+fn scan_impl(config: &ScanConfig, implementation: &ImplScan) -> (ImplModel, Vec<String>) {
+    #[derive(Default)]
+    struct Scanned {
+        references: Vec<ReqReference>,
+        warnings: Vec<ParseWarning>,
+        #[cfg(feature = "reverse")]
+        code_units: Vec<CodeUnit>,
+    }
+
+    let scanned = Mutex::new(Scanned::default());
+    let warnings = WalkSources::new(&config.root)
+        .follow_links(config.follow_links)
+        .include(implementation.include.iter().cloned())
+        .exclude(implementation.exclude.iter().cloned())
+        .visit(is_supported_extension, |path, content| {
+            let file = relative(&config.root, path);
+            let mut reqs = Reqs::new();
+            extract_from_content(&file, content, &mut reqs);
+            #[cfg(feature = "reverse")]
+            let units = crate::code_units::extract(&file, content).units;
+            let mut scanned = scanned.lock().unwrap();
+            scanned.references.extend(reqs.references);
+            scanned.warnings.extend(reqs.warnings);
+            #[cfg(feature = "reverse")]
+            scanned.code_units.extend(units);
+        });
+    let mut scanned = scanned.into_inner().unwrap();
+    scanned
+        .references
+        .sort_by(|a, b| (&a.file, a.span.offset).cmp(&(&b.file, b.span.offset)));
+    scanned
+        .warnings
+        .sort_by(|a, b| (&a.file, a.span.offset).cmp(&(&b.file, b.span.offset)));
+    #[cfg(feature = "reverse")]
+    scanned
+        .code_units
+        .sort_by(|a, b| (&a.file, a.start_byte).cmp(&(&b.file, b.start_byte)));
+    let model = ImplModel {
+        name: implementation.name.clone(),
+        references: scanned.references.iter().map(Reference::from).collect(),
+        warnings: scanned.warnings.iter().map(Warning::from).collect(),
+        #[cfg(feature = "reverse")]
+        code_units: scanned.code_units.iter().map(Unit::from).collect(),
+    };
+    (model, warnings)
+}
+
+#[cfg(all(test, feature = "walk"))]
+mod tests {
+    use super::*;
+
+    // r[verify core.api.scan]
+    #[tokio::test]
+    async fn scans_rules_and_references() {
+        let temp = tempfile::tempdir().unwrap();
+        let temp = temp.path();
+        std::fs::create_dir_all(temp.join("docs")).unwrap();
+        std::fs::create_dir_all(temp.join("src")).unwrap();
+        std::fs::write(
+            temp.join("docs/spec.md"),
+            "# Auth\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MAY log out.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn login() {}\n\n// o[impl other.thing]\nfn other() {}\n",
+        )
+        .unwrap();
+
+        let config = ScanConfig::new(temp).spec(
+            SpecScan::new("auth")
+                .include(["docs/**/*.md"])
+                .implementation(ImplScan::new("rust").include(["src/**/*.rs"])),
+        );
+        let model = Project::scan(&config).await.unwrap();
+
+        let ids: Vec<String> = model.rules().map(|r| r.id.to_string()).collect();
+        assert_eq!(ids, ["auth.login", "auth.logout"]);
+        let login = model.rules().next().unwrap();
+        assert_eq!(
+            (login.prefix.as_str(), login.file.as_path()),
+            ("r", Path::new("docs/spec.md"))
+        );
+        assert_eq!(model.references().count(), 2);
+        assert_eq!(
+            model.references().next().unwrap().file,
+            Path::new("src/lib.rs")
+        );
+
+        let coverage = model.spec("auth").unwrap().coverage("rust").unwrap();
+        assert_eq!(coverage.total_rules, 2);
+        assert_eq!(coverage.covered, [Id::parse("auth.login").unwrap()]);
+        assert_eq!(coverage.uncovered, [Id::parse("auth.logout").unwrap()]);
+        assert!(coverage.invalid.is_empty());
+    }
+}
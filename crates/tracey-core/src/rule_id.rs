@@ -1,8 +1,10 @@
 use facet::Facet;
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
 
 /// Structured rule ID representation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RuleId {
     /// Base rule ID without version suffix.
     pub base: String,
@@ -22,7 +24,8 @@ impl RuleId {
         Some(Self { base, version })
     }
 
-    /// Canonical string form (`base` for v1, `base+N` otherwise).
+    /// Canonical string form (`base` for v1, `base+YYYY-MM-DD` for versions
+    /// that are dates, `base+N` otherwise).
     pub fn canonical(&self) -> String {
         self.to_string()
     }
@@ -35,10 +38,20 @@ impl RuleId {
 
 impl Display for RuleId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.version == 1 {
+        let version = self.version;
+        if version == 1 {
             f.write_str(&self.base)
+        } else if is_date_version(version) {
+            write!(
+                f,
+                "{}+{:04}-{:02}-{:02}",
+                self.base,
+                version / 10_000,
+                version / 100 % 100,
+                version % 100
+            )
         } else {
-            write!(f, "{}+{}", self.base, self.version)
+            write!(f, "{}+{}", self.base, version)
         }
     }
 }
@@ -61,6 +74,86 @@ impl AsRef<str> for RuleId {
     }
 }
 
+/// How requirement versions are chosen, from the config's `version_scheme`.
+///
+/// Versions are always positive integers compared numerically, which is what
+/// stale detection relies on. A date version `+2024-11-03` is the integer
+/// 20241103, so revision dates compare in order. The scheme only restricts
+/// which versions are valid and decides how `tracey bump` picks the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VersionScheme {
+    /// `+2`, `+3`, ...; bumping adds one
+    #[default]
+    Integer,
+    /// Revision dates written `+YYYY-MM-DD`; bumping uses the current date
+    Date,
+    /// Any increasing integers, picked by hand; bumping is left to the author
+    FreeForm,
+}
+
+impl VersionScheme {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "integer" => Some(Self::Integer),
+            "date" => Some(Self::Date),
+            "free-form" => Some(Self::FreeForm),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Integer => "integer",
+            Self::Date => "date",
+            Self::FreeForm => "free-form",
+        }
+    }
+
+    /// Why `version` isn't valid under this scheme, if it isn't.
+    ///
+    /// Unversioned IDs (version 1) are valid under every scheme.
+    pub fn check(self, version: u32) -> Result<(), String> {
+        match self {
+            Self::Date if version != 1 && !is_date_version(version) => Err(format!(
+                "version {} is not a date (the date version scheme uses +YYYY-MM-DD)",
+                version
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The version to bump `current` to, given today's date as `YYYYMMDD`.
+    pub fn next(self, current: u32, today: u32) -> Result<u32, String> {
+        match self {
+            Self::Integer => current
+                .checked_add(1)
+                .ok_or_else(|| format!("version {} can't be incremented", current)),
+            Self::Date if today > current => Ok(today),
+            Self::Date => Err(format!(
+                "already at version {}; the date version scheme allows one revision per day",
+                current
+            )),
+            Self::FreeForm => {
+                Err("the free-form version scheme leaves picking the next version to you".into())
+            }
+        }
+    }
+}
+
+/// Whether `version` reads as a valid `YYYYMMDD` date.
+fn is_date_version(version: u32) -> bool {
+    let (year, month, day) = (version / 10_000, version / 100 % 100, version % 100);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1000..=9999).contains(&year) && (1..=days_in_month).contains(&day)
+}
+
 /// Relationship between a reference ID and a rule definition ID.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuleIdMatch {
@@ -72,7 +165,7 @@ pub enum RuleIdMatch {
     NoMatch,
 }
 
-/// Parse a rule ID with optional `+N` suffix.
+/// Parse a rule ID with optional `+N` or `+YYYY-MM-DD` suffix.
 ///
 /// r[impl ref.syntax.version]
 /// r[impl ref.syntax.version.implicit]
@@ -85,7 +178,7 @@ pub fn parse_rule_id(id: &str) -> Option<RuleId> {
         if base.is_empty() || base.contains('+') || version_str.is_empty() {
             return None;
         }
-        let version = version_str.parse::<u32>().ok()?;
+        let version = parse_version(version_str)?;
         RuleId::new(base, version)
     } else if id.contains('+') {
         None
@@ -94,6 +187,72 @@ pub fn parse_rule_id(id: &str) -> Option<RuleId> {
     }
 }
 
+/// Parse a version suffix: digits, or a `YYYY-MM-DD` date read as the
+/// integer `YYYYMMDD`.
+fn parse_version(version: &str) -> Option<u32> {
+    if version.bytes().all(|b| b.is_ascii_digit()) {
+        return version.parse().ok();
+    }
+    let date = dashed_date(version.as_bytes())?;
+    if date.len() != version.len() {
+        return None;
+    }
+    date.replace('-', "").parse().ok()
+}
+
+/// The `YYYY-MM-DD` date at the start of `bytes`, if it is a valid one.
+fn dashed_date(bytes: &[u8]) -> Option<&str> {
+    let date = bytes.get(..10)?;
+    let shape = date.iter().enumerate().all(|(i, b)| {
+        if i == 4 || i == 7 {
+            *b == b'-'
+        } else {
+            b.is_ascii_digit()
+        }
+    });
+    let date = std::str::from_utf8(date).ok().filter(|_| shape)?;
+    let version: u32 = date.replace('-', "").parse().ok()?;
+    is_date_version(version).then_some(date)
+}
+
+/// Markdown with the date versions of its markers (`r[auth.login+2024-11-03]`)
+/// spelled as integers of the same length (`r[auth.login+0020241103]`), for
+/// markdown parsers that only read integer versions. Byte offsets into the
+/// document stay the same.
+pub fn normalize_date_versions(content: &str) -> Cow<'_, str> {
+    let bytes = content.as_bytes();
+    let mut out: Option<Vec<u8>> = None;
+    let mut line_start = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'\n' {
+            line_start = i + 1;
+            continue;
+        }
+        if b != b'+' || i == line_start {
+            continue;
+        }
+        let before = bytes[i - 1];
+        let after = bytes.get(i + 11).copied();
+        let id_char = |c: u8| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'.' | b'_');
+        let line = &bytes[line_start..i];
+        let in_brackets =
+            line.iter().rposition(|&c| c == b'[') > line.iter().rposition(|&c| c == b']');
+        if !id_char(before) || after.is_some_and(id_char) || !in_brackets {
+            continue;
+        }
+        let Some(date) = dashed_date(&bytes[i + 1..]) else {
+            continue;
+        };
+        let digits = format!("00{}", date.replace('-', ""));
+        let out = out.get_or_insert_with(|| bytes.to_vec());
+        out[i + 1..i + 11].copy_from_slice(digits.as_bytes());
+    }
+    match out {
+        Some(out) => Cow::Owned(String::from_utf8(out).expect("only ASCII was replaced")),
+        None => Cow::Borrowed(content),
+    }
+}
+
 /// Compare two structured rule IDs.
 pub fn classify_reference_for_rule(rule_id: &RuleId, reference_id: &RuleId) -> RuleIdMatch {
     if rule_id.base != reference_id.base {
@@ -145,6 +304,65 @@ mod tests {
         assert!(parse_rule_id("auth+login+2").is_none());
     }
 
+    #[test]
+    fn parse_rule_id_supports_date_versions() {
+        let dashed = parse_rule_id("auth.login+2024-11-03").expect("must parse");
+        assert_eq!(dashed.version, 20241103);
+        assert_eq!(parse_rule_id("auth.login+20241103"), Some(dashed.clone()));
+        assert_eq!(dashed.to_string(), "auth.login+2024-11-03");
+        assert_eq!(parse_rule_id(&dashed.to_string()), Some(dashed.clone()));
+
+        let later = parse_rule_id("auth.login+2025-01-15").expect("must parse");
+        assert_eq!(
+            classify_reference_for_rule(&later, &dashed),
+            RuleIdMatch::Stale
+        );
+
+        assert!(parse_rule_id("auth.login+2024-13-03").is_none());
+        assert!(parse_rule_id("auth.login+2024-11-3").is_none());
+        assert!(parse_rule_id("auth.login+2024-11-03-1").is_none());
+    }
+
+    #[test]
+    fn normalizes_date_versions_in_markers() {
+        let content = "r[auth.login+2024-11-03]\nSee r[auth.logout+2023-02-30] and a+2024-11-03.\n";
+        let normalized = normalize_date_versions(content);
+        assert_eq!(normalized.len(), content.len());
+        assert_eq!(
+            normalized,
+            "r[auth.login+0020241103]\nSee r[auth.logout+2023-02-30] and a+2024-11-03.\n"
+        );
+        assert!(matches!(
+            normalize_date_versions("r[auth.login+2]\n"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn version_schemes_check_and_bump() {
+        assert_eq!(VersionScheme::Integer.next(1, 20241103), Ok(2));
+        assert!(VersionScheme::Integer.check(20241103).is_ok());
+
+        let date = VersionScheme::Date;
+        assert!(date.check(1).is_ok(), "unversioned IDs are always valid");
+        assert!(date.check(20241103).is_ok());
+        assert!(date.check(20240229).is_ok());
+        assert!(date.check(20230229).is_err());
+        assert!(date.check(20241301).is_err());
+        assert!(date.check(2).is_err());
+        assert_eq!(date.next(1, 20241103), Ok(20241103));
+        assert_eq!(date.next(20240101, 20241103), Ok(20241103));
+        assert!(date.next(20241103, 20241103).is_err());
+
+        assert!(VersionScheme::FreeForm.check(7).is_ok());
+        assert!(VersionScheme::FreeForm.next(7, 20241103).is_err());
+
+        for scheme in [VersionScheme::Integer, date, VersionScheme::FreeForm] {
+            assert_eq!(VersionScheme::from_name(scheme.name()), Some(scheme));
+        }
+        assert_eq!(VersionScheme::from_name("semver"), None);
+    }
+
     #[test]
     fn classify_reference_detects_stale() {
         let rule = parse_rule_id("auth.login+2").expect("must parse");
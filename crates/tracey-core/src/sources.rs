@@ -3,9 +3,7 @@
 use crate::lexer::{Reqs, extract_from_content};
 use eyre::Result;
 use std::ffi::OsStr;
-#[cfg(feature = "walk")]
-use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// r[impl ref.cross-workspace.missing-paths]
 /// Result of extracting requirements, including any warnings about missing files
@@ -15,6 +13,42 @@ pub struct ExtractionResult {
     pub warnings: Vec<String>,
 }
 
+/// Name of the gitignore-syntax files that hide paths from tracey but not
+/// from git. They may appear in any directory, like `.gitignore`.
+pub const IGNORE_FILENAME: &str = ".tracey-ignore";
+
+/// The canonical form of `path`, so a file reached through a symlink and
+/// through its real location is one file. A path that no longer exists keeps
+/// its canonicalized parent directory, so a deleted file still matches the
+/// path it was scanned under.
+///
+/// r[impl walk.symlinks]
+pub fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The link and the ancestor it points back to, if a walk error reports a
+/// symlink loop.
+#[cfg(feature = "walk")]
+pub fn symlink_loop(err: &ignore::Error) -> Option<(&Path, &Path)> {
+    match err {
+        ignore::Error::Loop { ancestor, child } => Some((child, ancestor)),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => symlink_loop(err),
+        _ => None,
+    }
+}
+
 /// File extensions that tracey knows how to scan for requirement references.
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     "rs",     // Rust
@@ -89,6 +123,30 @@ pub fn is_supported_extension(ext: &OsStr) -> bool {
         .unwrap_or(false)
 }
 
+/// How to write a one-line comment in a file with a supported extension:
+/// the text before it and the text after it, e.g. `("//", "")` or
+/// `("(*", "*)")`. `None` for other extensions.
+pub fn comment_delimiters(ext: &str) -> Option<(&'static str, &'static str)> {
+    let delimiters = match ext {
+        "rs" | "swift" | "ts" | "tsx" | "js" | "jsx" | "go" | "c" | "h" | "cpp" | "hpp" | "cc"
+        | "cxx" | "m" | "mm" | "java" | "kt" | "kts" | "scala" | "groovy" | "cs" | "zig"
+        | "php" | "dart" | "fs" | "fsi" | "fsx" | "d" => ("//", ""),
+        // `.S` goes through the C preprocessor, which would read `#` as a
+        // directive
+        "S" => ("//", ""),
+        "py" | "rb" | "r" | "R" | "pl" | "pm" | "ex" | "exs" | "jl" | "ps1" | "psm1" | "psd1"
+        | "cmake" | "sh" | "bash" | "zsh" | "s" => ("#", ""),
+        "lua" | "hs" | "lhs" => ("--", ""),
+        "asm" | "clj" | "cljs" | "cljc" | "edn" => (";", ""),
+        "erl" | "hrl" => ("%", ""),
+        "vb" | "vbs" => ("'", ""),
+        "cob" | "cbl" | "cpy" => ("*>", ""),
+        "ml" | "mli" => ("(*", "*)"),
+        _ => return None,
+    };
+    Some(delimiters)
+}
+
 /// Trait for providing source files to extract requirements from
 pub trait Sources {
     /// Extract requirements from all sources
@@ -186,6 +244,7 @@ pub struct WalkSources {
     root: PathBuf,
     include: Vec<String>,
     exclude: Vec<String>,
+    follow_links: bool,
 }
 
 #[cfg(feature = "walk")]
@@ -196,9 +255,16 @@ impl WalkSources {
             root: root.into(),
             include: Vec::new(),
             exclude: Vec::new(),
+            follow_links: true,
         }
     }
 
+    /// Whether to follow symbolic links (default: true)
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
     /// Add include patterns (e.g., `["**/*.rs"]`)
     pub fn include(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
         self.include.extend(patterns.into_iter().map(Into::into));
@@ -213,13 +279,23 @@ impl WalkSources {
 }
 
 #[cfg(feature = "walk")]
-impl Sources for WalkSources {
-    fn extract(self) -> Result<ExtractionResult> {
+impl WalkSources {
+    /// Call `visit` with the path and content of every file the walk
+    /// selects whose extension `accepts`, in parallel. Returns warnings
+    /// about paths that were skipped.
+    pub(crate) fn visit(
+        &self,
+        accepts: fn(&OsStr) -> bool,
+        visit: impl Fn(&Path, &str) + Sync,
+    ) -> Vec<String> {
         use ignore::WalkBuilder;
+        use std::collections::HashSet;
         use std::sync::Mutex;
 
-        let reqs = Mutex::new(Reqs::new());
+        let visit = &visit;
         let warnings = Mutex::new(Vec::new());
+        // Files reachable through several links are only read once.
+        let seen = Mutex::new(HashSet::new());
 
         // r[impl ref.cross-workspace.paths]
         // Separate include patterns into local and cross-workspace
@@ -232,17 +308,21 @@ impl Sources for WalkSources {
                                   exclude_patterns: &[String]| {
             // Build the walker
             // r[impl walk.gitignore]
+            // r[impl walk.tracey-ignore]
+            // r[impl walk.symlinks]
             let walker = WalkBuilder::new(root)
-                .follow_links(true)
+                .follow_links(self.follow_links)
                 .hidden(false) // Don't skip hidden files (but .git is in .gitignore)
                 .git_ignore(true)
                 .git_global(true)
                 .git_exclude(true)
+                .add_custom_ignore_filename(IGNORE_FILENAME)
                 .build_parallel();
 
             // Process files in parallel using ignore's parallel walker
             walker.run(|| {
-                let reqs_ref = &reqs;
+                let warnings_ref = &warnings;
+                let seen_ref = &seen;
                 let include_patterns = include_patterns.to_vec();
                 let exclude_patterns = exclude_patterns.to_vec();
                 let root = root.to_path_buf();
@@ -250,16 +330,22 @@ impl Sources for WalkSources {
                 Box::new(move |entry| {
                     let entry = match entry {
                         Ok(e) => e,
-                        Err(_) => return ignore::WalkState::Continue,
+                        Err(err) => {
+                            if let Some((link, target)) = symlink_loop(&err) {
+                                warnings_ref.lock().unwrap().push(format!(
+                                    "Warning: Skipping symlink loop: {} points to {}",
+                                    link.display(),
+                                    target.display()
+                                ));
+                            }
+                            return ignore::WalkState::Continue;
+                        }
                     };
 
                     let path = entry.path();
 
                     // Only supported file extensions
-                    if path
-                        .extension()
-                        .is_none_or(|ext| !is_supported_extension(ext))
-                    {
+                    if path.extension().is_none_or(|ext| !accepts(ext)) {
                         return ignore::WalkState::Continue;
                     }
 
@@ -274,13 +360,12 @@ impl Sources for WalkSources {
                         return ignore::WalkState::Continue;
                     }
 
-                    // Read and extract
-                    if let Ok(content) = std::fs::read_to_string(path) {
-                        let mut file_reqs = Reqs::new();
-                        extract_from_content(path, &content, &mut file_reqs);
+                    if !seen_ref.lock().unwrap().insert(canonical_path(path)) {
+                        return ignore::WalkState::Continue;
+                    }
 
-                        let mut guard = reqs_ref.lock().unwrap();
-                        guard.extend(file_reqs);
+                    if let Ok(content) = std::fs::read_to_string(path) {
+                        visit(path, &content);
                     }
 
                     ignore::WalkState::Continue
@@ -319,9 +404,22 @@ impl Sources for WalkSources {
             walk_with_patterns(&resolved_path, &[adjusted_pattern], &self.exclude);
         }
 
+        warnings.into_inner().unwrap()
+    }
+}
+
+#[cfg(feature = "walk")]
+impl Sources for WalkSources {
+    fn extract(self) -> Result<ExtractionResult> {
+        let reqs = std::sync::Mutex::new(Reqs::new());
+        let warnings = self.visit(is_supported_extension, |path, content| {
+            let mut file_reqs = Reqs::new();
+            extract_from_content(path, content, &mut file_reqs);
+            reqs.lock().unwrap().extend(file_reqs);
+        });
         Ok(ExtractionResult {
             reqs: reqs.into_inner().unwrap(),
-            warnings: warnings.into_inner().unwrap(),
+            warnings,
         })
     }
 }
@@ -509,6 +607,14 @@ mod tests {
         assert_eq!(result.reqs.len(), 3);
     }
 
+    #[test]
+    fn every_supported_extension_has_comment_delimiters() {
+        for ext in SUPPORTED_EXTENSIONS {
+            assert!(comment_delimiters(ext).is_some(), "{ext}");
+        }
+        assert_eq!(comment_delimiters("md"), None);
+    }
+
     #[test]
     fn test_supported_extensions() {
         use std::ffi::OsStr;
@@ -0,0 +1,22 @@
+//! Build script for tracey-ffi - generates the C header
+//!
+//! The header goes to `OUT_DIR`, so builds never write into the source
+//! tree. `just ffi-header` regenerates the checked-in `include/tracey.h`.
+
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(Path::new(crate_dir).join("cbindgen.toml"))
+        .expect("Failed to read cbindgen.toml");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate C header")
+        .write_to_file(Path::new(&out_dir).join("tracey.h"));
+}
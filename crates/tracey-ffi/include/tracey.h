@@ -0,0 +1,190 @@
+#ifndef TRACEY_H
+#define TRACEY_H
+
+/* This file is auto-generated from tracey-ffi by cbindgen. DO NOT EDIT MANUALLY. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Which files to scan, built with `tracey_config_*` functions.
+typedef struct TraceyConfig TraceyConfig;
+
+// The result of a scan.
+typedef struct TraceyModel TraceyModel;
+
+// A rule defined in a spec.
+typedef struct TraceyRule {
+  const char *spec;
+  // With its version, e.g. `auth.login+2`
+  const char *id;
+  // Marker prefix, e.g. `r`
+  const char *prefix;
+  // Relative to the project root
+  const char *file;
+  // 1-indexed
+  uintptr_t line;
+  // Markdown text
+  const char *text;
+} TraceyRule;
+
+// A reference to a rule in an implementation.
+typedef struct TraceyReference {
+  const char *spec;
+  const char *implementation;
+  const char *prefix;
+  // `impl`, `verify`, `depends`, `related` or `define`
+  const char *verb;
+  const char *rule_id;
+  // Relative to the project root
+  const char *file;
+  // 1-indexed
+  uintptr_t line;
+} TraceyReference;
+
+// How much of a spec an implementation covers.
+typedef struct TraceyCoverage {
+  uintptr_t total_rules;
+  uintptr_t covered_rules;
+  // References to rules the spec doesn't define
+  uintptr_t invalid_references;
+} TraceyCoverage;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The message of the last error on this thread, valid until the next
+// failing call on it. Empty if nothing failed.
+const char *tracey_last_error(void);
+
+// A new config scanning the project at `root`, or `NULL` on error.
+//
+// # Safety
+//
+// `root` must be `NULL` or a NUL-terminated string.
+TraceyConfig *tracey_config_new(const char *root);
+
+// Add a spec named `name`, whose rules are in the markdown files matching
+// the `include_len` patterns at `include`. Returns 0, or -1 on error.
+//
+// # Safety
+//
+// `config` must come from [`tracey_config_new`] and not be freed. `name`
+// must be `NULL` or a NUL-terminated string, and `include` an array of
+// `include_len` of them.
+int tracey_config_add_spec(TraceyConfig *config,
+                           const char *name,
+                           const char *const *include,
+                           uintptr_t include_len);
+
+// Add an implementation named `name` to the spec `spec`, reading
+// references from the source files matching the `include_len` patterns at
+// `include` (every supported file if none) and not the `exclude_len` ones
+// at `exclude`. Returns 0, or -1 on error.
+//
+// # Safety
+//
+// `config` must come from [`tracey_config_new`] and not be freed. `spec`
+// and `name` must be `NULL` or NUL-terminated strings, and `include` and
+// `exclude` arrays of `include_len` and `exclude_len` of them.
+int tracey_config_add_impl(TraceyConfig *config,
+                           const char *spec,
+                           const char *name,
+                           const char *const *include,
+                           uintptr_t include_len,
+                           const char *const *exclude,
+                           uintptr_t exclude_len);
+
+// Free a config.
+//
+// # Safety
+//
+// `config` must be `NULL` or come from [`tracey_config_new`], and not be
+// used afterwards.
+void tracey_config_free(TraceyConfig *config);
+
+// Scan the files `config` selects. Returns the model, or `NULL` on error.
+//
+// # Safety
+//
+// `config` must come from [`tracey_config_new`] and not be freed.
+TraceyModel *tracey_scan(const TraceyConfig *config);
+
+// The number of rules of every spec, or 0 if `model` is `NULL`.
+//
+// # Safety
+//
+// `model` must be `NULL` or come from [`tracey_scan`] and not be freed.
+uintptr_t tracey_model_rule_count(const TraceyModel *model);
+
+// Write the rule at `index` (in spec, file and line order) to `out`.
+// Returns 0, or -1 on error.
+//
+// # Safety
+//
+// `model` must come from [`tracey_scan`] and not be freed, and `out` must
+// point to a writable `TraceyRule`.
+int tracey_model_rule(const TraceyModel *model, uintptr_t index, TraceyRule *out);
+
+// The number of references of every implementation, or 0 if `model` is
+// `NULL`.
+//
+// # Safety
+//
+// `model` must be `NULL` or come from [`tracey_scan`] and not be freed.
+uintptr_t tracey_model_reference_count(const TraceyModel *model);
+
+// Write the reference at `index` (in spec, implementation, file and
+// position order) to `out`. Returns 0, or -1 on error.
+//
+// # Safety
+//
+// `model` must come from [`tracey_scan`] and not be freed, and `out` must
+// point to a writable `TraceyReference`.
+int tracey_model_reference(const TraceyModel *model, uintptr_t index, TraceyReference *out);
+
+// Write how much of `spec` the implementation `implementation` covers to
+// `out`. Returns 0, or -1 on error.
+//
+// # Safety
+//
+// `model` must come from [`tracey_scan`] and not be freed, `spec` and
+// `implementation` must be `NULL` or NUL-terminated strings, and `out`
+// must point to a writable `TraceyCoverage`.
+int tracey_model_coverage(const TraceyModel *model,
+                          const char *spec,
+                          const char *implementation,
+                          TraceyCoverage *out);
+
+// The whole model as JSON, the format of `tracey_core::Model`, or `NULL`
+// on error. Free it with [`tracey_string_free`].
+//
+// # Safety
+//
+// `model` must come from [`tracey_scan`] and not be freed.
+char *tracey_model_to_json(const TraceyModel *model);
+
+// Free a model. The strings of its rules and references are freed with it.
+//
+// # Safety
+//
+// `model` must be `NULL` or come from [`tracey_scan`], and neither it nor
+// its strings may be used afterwards.
+void tracey_model_free(TraceyModel *model);
+
+// Free a string returned by [`tracey_model_to_json`].
+//
+// # Safety
+//
+// `s` must be `NULL` or come from [`tracey_model_to_json`], and not be
+// used afterwards.
+void tracey_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TRACEY_H */
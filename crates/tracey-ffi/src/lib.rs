@@ -0,0 +1,614 @@
+//! tracey-ffi - C ABI bindings for tracey's scanner
+//!
+//! r[impl ffi.scan]
+//!
+//! Wraps [`tracey_core::Project::scan`] in functions a C, Python (`ctypes`,
+//! `cffi`) or any other FFI caller can use: build a config, scan, then read
+//! rules, references and coverage out of the model, or get it all as JSON.
+//! The header is checked in as `include/tracey.h`; `just ffi-header`
+//! regenerates it.
+//!
+//! Every object returned by a `tracey_*_new` or `tracey_scan` function is
+//! owned by the caller and freed with the matching `tracey_*_free`. Strings
+//! in a [`TraceyRule`] or [`TraceyReference`] belong to the model and stay
+//! valid until it's freed. Failing functions return `NULL` or a negative
+//! number, and [`tracey_last_error`] says why. A panic inside tracey never
+//! unwinds into the caller: it fails the call the same way.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use tracey_core::{ImplScan, Model, Project, ScanConfig, SpecScan};
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_error(message: impl Into<String>) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = c_string(message));
+}
+
+/// A `CString` of `s`, without the NUL bytes it can't hold.
+fn c_string(s: impl Into<String>) -> CString {
+    let mut s = s.into();
+    s.retain(|c| c != '\0');
+    CString::new(s).expect("NUL bytes were removed")
+}
+
+/// The string at `s`, or an error naming `what`.
+unsafe fn str_arg<'a>(s: *const c_char, what: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{what} is NULL"));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| format!("{what} is not valid UTF-8"))
+}
+
+/// The `len` strings at `strings`.
+unsafe fn str_array_arg(
+    strings: *const *const c_char,
+    len: usize,
+    what: &str,
+) -> Result<Vec<String>, String> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    if strings.is_null() {
+        return Err(format!("{what} is NULL"));
+    }
+    unsafe { std::slice::from_raw_parts(strings, len) }
+        .iter()
+        .map(|s| unsafe { str_arg(*s, what) }.map(str::to_string))
+        .collect()
+}
+
+/// The message of the last error on this thread, valid until the next
+/// failing call on it. Empty if nothing failed.
+#[unsafe(no_mangle)]
+pub extern "C" fn tracey_last_error() -> *const c_char {
+    guard(ptr::null(), || {
+        LAST_ERROR.with(|last| last.borrow().as_ptr())
+    })
+}
+
+/// Which files to scan, built with `tracey_config_*` functions.
+pub struct TraceyConfig(ScanConfig);
+
+/// A new config scanning the project at `root`, or `NULL` on error.
+///
+/// # Safety
+///
+/// `root` must be `NULL` or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_config_new(root: *const c_char) -> *mut TraceyConfig {
+    guard(ptr::null_mut(), || match unsafe { str_arg(root, "root") } {
+        Ok(root) => Box::into_raw(Box::new(TraceyConfig(ScanConfig::new(root)))),
+        Err(e) => {
+            set_error(e);
+            ptr::null_mut()
+        }
+    })
+}
+
+/// Add a spec named `name`, whose rules are in the markdown files matching
+/// the `include_len` patterns at `include`. Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `config` must come from [`tracey_config_new`] and not be freed. `name`
+/// must be `NULL` or a NUL-terminated string, and `include` an array of
+/// `include_len` of them.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_config_add_spec(
+    config: *mut TraceyConfig,
+    name: *const c_char,
+    include: *const *const c_char,
+    include_len: usize,
+) -> c_int {
+    catch(|| {
+        let config = unsafe { config.as_mut() }.ok_or("config is NULL")?;
+        let name = unsafe { str_arg(name, "name") }?;
+        let include = unsafe { str_array_arg(include, include_len, "include") }?;
+        if config.0.specs.iter().any(|spec| spec.name == name) {
+            return Err(format!("spec '{name}' was already added"));
+        }
+        config.0.specs.push(SpecScan::new(name).include(include));
+        Ok(())
+    })
+}
+
+/// Add an implementation named `name` to the spec `spec`, reading
+/// references from the source files matching the `include_len` patterns at
+/// `include` (every supported file if none) and not the `exclude_len` ones
+/// at `exclude`. Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `config` must come from [`tracey_config_new`] and not be freed. `spec`
+/// and `name` must be `NULL` or NUL-terminated strings, and `include` and
+/// `exclude` arrays of `include_len` and `exclude_len` of them.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_config_add_impl(
+    config: *mut TraceyConfig,
+    spec: *const c_char,
+    name: *const c_char,
+    include: *const *const c_char,
+    include_len: usize,
+    exclude: *const *const c_char,
+    exclude_len: usize,
+) -> c_int {
+    catch(|| {
+        let config = unsafe { config.as_mut() }.ok_or("config is NULL")?;
+        let spec = unsafe { str_arg(spec, "spec") }?;
+        let name = unsafe { str_arg(name, "name") }?;
+        let include = unsafe { str_array_arg(include, include_len, "include") }?;
+        let exclude = unsafe { str_array_arg(exclude, exclude_len, "exclude") }?;
+        let spec = config
+            .0
+            .specs
+            .iter_mut()
+            .find(|s| s.name == spec)
+            .ok_or_else(|| format!("no spec '{spec}' was added"))?;
+        spec.impls
+            .push(ImplScan::new(name).include(include).exclude(exclude));
+        Ok(())
+    })
+}
+
+/// Run `f`, returning 0 if it succeeds and -1 with the error recorded if
+/// not.
+fn catch(f: impl FnOnce() -> Result<(), String>) -> c_int {
+    guard(-1, || match f() {
+        Ok(()) => 0,
+        Err(e) => {
+            set_error(e);
+            -1
+        }
+    })
+}
+
+/// Run `f`, returning `failed` with the panic recorded as the error if it
+/// panics, so no unwind crosses the C ABI.
+fn guard<T>(failed: T, f: impl FnOnce() -> T) -> T {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_error(format!("tracey panicked: {message}"));
+            failed
+        }
+    }
+}
+
+/// Free a config.
+///
+/// # Safety
+///
+/// `config` must be `NULL` or come from [`tracey_config_new`], and not be
+/// used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_config_free(config: *mut TraceyConfig) {
+    guard((), || {
+        if !config.is_null() {
+            drop(unsafe { Box::from_raw(config) });
+        }
+    })
+}
+
+/// A rule defined in a spec.
+#[repr(C)]
+pub struct TraceyRule {
+    pub spec: *const c_char,
+    /// With its version, e.g. `auth.login+2`
+    pub id: *const c_char,
+    /// Marker prefix, e.g. `r`
+    pub prefix: *const c_char,
+    /// Relative to the project root
+    pub file: *const c_char,
+    /// 1-indexed
+    pub line: usize,
+    /// Markdown text
+    pub text: *const c_char,
+}
+
+/// A reference to a rule in an implementation.
+#[repr(C)]
+pub struct TraceyReference {
+    pub spec: *const c_char,
+    pub implementation: *const c_char,
+    pub prefix: *const c_char,
+    /// `impl`, `verify`, `depends`, `related` or `define`
+    pub verb: *const c_char,
+    pub rule_id: *const c_char,
+    /// Relative to the project root
+    pub file: *const c_char,
+    /// 1-indexed
+    pub line: usize,
+}
+
+/// How much of a spec an implementation covers.
+#[repr(C)]
+pub struct TraceyCoverage {
+    pub total_rules: usize,
+    pub covered_rules: usize,
+    /// References to rules the spec doesn't define
+    pub invalid_references: usize,
+}
+
+/// The result of a scan.
+pub struct TraceyModel {
+    model: Model,
+    /// spec, id, prefix, file, text
+    rules: Vec<([CString; 5], usize)>,
+    /// spec, implementation, prefix, verb, rule id, file
+    references: Vec<([CString; 6], usize)>,
+}
+
+impl TraceyModel {
+    fn new(model: Model) -> Self {
+        let mut rules = Vec::new();
+        let mut references = Vec::new();
+        for spec in &model.specs {
+            for rule in &spec.rules {
+                rules.push((
+                    [
+                        c_string(spec.name.as_str()),
+                        c_string(rule.id.to_string()),
+                        c_string(rule.prefix.as_str()),
+                        c_string(rule.file.display().to_string()),
+                        c_string(rule.text.as_str()),
+                    ],
+                    rule.line,
+                ));
+            }
+            for implementation in &spec.impls {
+                for reference in &implementation.references {
+                    references.push((
+                        [
+                            c_string(spec.name.as_str()),
+                            c_string(implementation.name.as_str()),
+                            c_string(reference.prefix.as_str()),
+                            c_string(reference.verb.as_str()),
+                            c_string(reference.id.to_string()),
+                            c_string(reference.file.display().to_string()),
+                        ],
+                        reference.line,
+                    ));
+                }
+            }
+        }
+        Self {
+            model,
+            rules,
+            references,
+        }
+    }
+}
+
+/// Scan the files `config` selects. Returns the model, or `NULL` on error.
+///
+/// # Safety
+///
+/// `config` must come from [`tracey_config_new`] and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_scan(config: *const TraceyConfig) -> *mut TraceyModel {
+    guard(ptr::null_mut(), || {
+        let Some(config) = (unsafe { config.as_ref() }) else {
+            set_error("config is NULL");
+            return ptr::null_mut();
+        };
+        let runtime = match tokio::runtime::Builder::new_current_thread().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                set_error(format!("failed to start the runtime: {e}"));
+                return ptr::null_mut();
+            }
+        };
+        match runtime.block_on(Project::scan(&config.0)) {
+            Ok(model) => Box::into_raw(Box::new(TraceyModel::new(model))),
+            Err(e) => {
+                set_error(e.to_string());
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// The number of rules of every spec, or 0 if `model` is `NULL`.
+///
+/// # Safety
+///
+/// `model` must be `NULL` or come from [`tracey_scan`] and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_rule_count(model: *const TraceyModel) -> usize {
+    guard(0, || {
+        unsafe { model.as_ref() }.map_or(0, |model| model.rules.len())
+    })
+}
+
+/// Write the rule at `index` (in spec, file and line order) to `out`.
+/// Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `model` must come from [`tracey_scan`] and not be freed, and `out` must
+/// point to a writable `TraceyRule`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_rule(
+    model: *const TraceyModel,
+    index: usize,
+    out: *mut TraceyRule,
+) -> c_int {
+    catch(|| {
+        let model = unsafe { model.as_ref() }.ok_or("model is NULL")?;
+        let out = unsafe { out.as_mut() }.ok_or("out is NULL")?;
+        let ([spec, id, prefix, file, text], line) = model
+            .rules
+            .get(index)
+            .ok_or_else(|| format!("no rule {index}"))?;
+        *out = TraceyRule {
+            spec: spec.as_ptr(),
+            id: id.as_ptr(),
+            prefix: prefix.as_ptr(),
+            file: file.as_ptr(),
+            line: *line,
+            text: text.as_ptr(),
+        };
+        Ok(())
+    })
+}
+
+/// The number of references of every implementation, or 0 if `model` is
+/// `NULL`.
+///
+/// # Safety
+///
+/// `model` must be `NULL` or come from [`tracey_scan`] and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_reference_count(model: *const TraceyModel) -> usize {
+    guard(0, || {
+        unsafe { model.as_ref() }.map_or(0, |model| model.references.len())
+    })
+}
+
+/// Write the reference at `index` (in spec, implementation, file and
+/// position order) to `out`. Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `model` must come from [`tracey_scan`] and not be freed, and `out` must
+/// point to a writable `TraceyReference`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_reference(
+    model: *const TraceyModel,
+    index: usize,
+    out: *mut TraceyReference,
+) -> c_int {
+    catch(|| {
+        let model = unsafe { model.as_ref() }.ok_or("model is NULL")?;
+        let out = unsafe { out.as_mut() }.ok_or("out is NULL")?;
+        let ([spec, implementation, prefix, verb, rule_id, file], line) = model
+            .references
+            .get(index)
+            .ok_or_else(|| format!("no reference {index}"))?;
+        *out = TraceyReference {
+            spec: spec.as_ptr(),
+            implementation: implementation.as_ptr(),
+            prefix: prefix.as_ptr(),
+            verb: verb.as_ptr(),
+            rule_id: rule_id.as_ptr(),
+            file: file.as_ptr(),
+            line: *line,
+        };
+        Ok(())
+    })
+}
+
+/// Write how much of `spec` the implementation `implementation` covers to
+/// `out`. Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `model` must come from [`tracey_scan`] and not be freed, `spec` and
+/// `implementation` must be `NULL` or NUL-terminated strings, and `out`
+/// must point to a writable `TraceyCoverage`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_coverage(
+    model: *const TraceyModel,
+    spec: *const c_char,
+    implementation: *const c_char,
+    out: *mut TraceyCoverage,
+) -> c_int {
+    catch(|| {
+        let model = unsafe { model.as_ref() }.ok_or("model is NULL")?;
+        let spec = unsafe { str_arg(spec, "spec") }?;
+        let implementation = unsafe { str_arg(implementation, "implementation") }?;
+        let out = unsafe { out.as_mut() }.ok_or("out is NULL")?;
+        let report = model
+            .model
+            .spec(spec)
+            .ok_or_else(|| format!("no spec '{spec}'"))?
+            .coverage(implementation)
+            .ok_or_else(|| format!("spec '{spec}' has no implementation '{implementation}'"))?;
+        *out = TraceyCoverage {
+            total_rules: report.total_rules,
+            covered_rules: report.covered.len(),
+            invalid_references: report.invalid.len(),
+        };
+        Ok(())
+    })
+}
+
+/// The whole model as JSON, the format of `tracey_core::Model`, or `NULL`
+/// on error. Free it with [`tracey_string_free`].
+///
+/// # Safety
+///
+/// `model` must come from [`tracey_scan`] and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_to_json(model: *const TraceyModel) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let Some(model) = (unsafe { model.as_ref() }) else {
+            set_error("model is NULL");
+            return ptr::null_mut();
+        };
+        match facet_json::to_string(&model.model) {
+            Ok(json) => c_string(json).into_raw(),
+            Err(e) => {
+                set_error(format!("failed to serialize the model: {e}"));
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Free a model. The strings of its rules and references are freed with it.
+///
+/// # Safety
+///
+/// `model` must be `NULL` or come from [`tracey_scan`], and neither it nor
+/// its strings may be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_model_free(model: *mut TraceyModel) {
+    guard((), || {
+        if !model.is_null() {
+            drop(unsafe { Box::from_raw(model) });
+        }
+    })
+}
+
+/// Free a string returned by [`tracey_model_to_json`].
+///
+/// # Safety
+///
+/// `s` must be `NULL` or come from [`tracey_model_to_json`], and not be
+/// used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tracey_string_free(s: *mut c_char) {
+    guard((), || {
+        if !s.is_null() {
+            drop(unsafe { CString::from_raw(s) });
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify ffi.scan]
+    #[test]
+    fn scans_through_the_c_abi() {
+        let root = std::env::temp_dir().join(format!("tracey-ffi-scan-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("docs/spec.md"),
+            "r[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MAY log out.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// r[impl auth.login]\nfn login() {}\n",
+        )
+        .unwrap();
+
+        let root_c = c_string(root.display().to_string());
+        let name = c_string("auth");
+        let rust = c_string("rust");
+        let docs = [c_string("docs/**/*.md")];
+        let docs: Vec<*const c_char> = docs.iter().map(|s| s.as_ptr()).collect();
+        let src = [c_string("src/**/*.rs")];
+        let src: Vec<*const c_char> = src.iter().map(|s| s.as_ptr()).collect();
+        unsafe {
+            let config = tracey_config_new(root_c.as_ptr());
+            assert_eq!(
+                tracey_config_add_spec(config, name.as_ptr(), docs.as_ptr(), docs.len()),
+                0
+            );
+            assert_eq!(
+                tracey_config_add_impl(
+                    config,
+                    name.as_ptr(),
+                    rust.as_ptr(),
+                    src.as_ptr(),
+                    src.len(),
+                    ptr::null(),
+                    0
+                ),
+                0
+            );
+            assert_eq!(
+                tracey_config_add_impl(
+                    config,
+                    rust.as_ptr(),
+                    rust.as_ptr(),
+                    ptr::null(),
+                    0,
+                    ptr::null(),
+                    0
+                ),
+                -1
+            );
+            let error = CStr::from_ptr(tracey_last_error()).to_str().unwrap();
+            assert_eq!(error, "no spec 'rust' was added");
+
+            let model = tracey_scan(config);
+            tracey_config_free(config);
+            std::fs::remove_dir_all(&root).unwrap();
+            assert!(!model.is_null());
+
+            assert_eq!(tracey_model_rule_count(model), 2);
+            let mut rule = std::mem::MaybeUninit::<TraceyRule>::uninit();
+            assert_eq!(tracey_model_rule(model, 0, rule.as_mut_ptr()), 0);
+            let rule = rule.assume_init();
+            assert_eq!(CStr::from_ptr(rule.id).to_str().unwrap(), "auth.login");
+            assert_eq!(CStr::from_ptr(rule.file).to_str().unwrap(), "docs/spec.md");
+            assert_eq!(rule.line, 1);
+
+            assert_eq!(tracey_model_reference_count(model), 1);
+            let mut reference = std::mem::MaybeUninit::<TraceyReference>::uninit();
+            assert_eq!(tracey_model_reference(model, 0, reference.as_mut_ptr()), 0);
+            let reference = reference.assume_init();
+            assert_eq!(CStr::from_ptr(reference.verb).to_str().unwrap(), "impl");
+            let mut missing = std::mem::MaybeUninit::<TraceyReference>::uninit();
+            assert_eq!(tracey_model_reference(model, 1, missing.as_mut_ptr()), -1);
+
+            let mut coverage = TraceyCoverage {
+                total_rules: 0,
+                covered_rules: 0,
+                invalid_references: 0,
+            };
+            assert_eq!(
+                tracey_model_coverage(model, name.as_ptr(), rust.as_ptr(), &mut coverage),
+                0
+            );
+            assert_eq!((coverage.total_rules, coverage.covered_rules), (2, 1));
+
+            let json = tracey_model_to_json(model);
+            assert!(
+                CStr::from_ptr(json)
+                    .to_str()
+                    .unwrap()
+                    .contains("auth.logout")
+            );
+            tracey_string_free(json);
+            tracey_model_free(model);
+        }
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        assert_eq!(catch(|| panic!("boom")), -1);
+        let error = unsafe { CStr::from_ptr(tracey_last_error()) };
+        assert_eq!(error.to_str().unwrap(), "tracey panicked: boom");
+        assert!(guard(ptr::null_mut::<TraceyModel>(), || panic!("again")).is_null());
+    }
+}
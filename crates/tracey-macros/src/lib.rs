@@ -0,0 +1,126 @@
+//! tracey-macros - the `#[tracey_runtime::verify]` attribute
+//!
+//! Use it through the `tracey-runtime` crate, which re-exports it along with
+//! the runtime it calls. The scanner reads
+//! `#[tracey_runtime::verify("auth.login")]` as a `verify` reference to
+//! `auth.login`; the attribute itself keeps the function as it is, except
+//! for a call at the start of its body that hands the test's path and rule
+//! IDs to `tracey_runtime::verified::enter`.
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+/// Mark a test as verifying requirements:
+///
+/// ```ignore
+/// #[test]
+/// #[tracey_runtime::verify("auth.login", "auth.session+2")]
+/// fn logs_in() { /* ... */ }
+/// ```
+///
+/// Each argument is a string literal with a requirement ID, or a whole
+/// `prefix[rule.id]` marker for a spec whose prefix isn't `r`.
+#[proc_macro_attribute]
+pub fn verify(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(attr, item) {
+        Ok(tokens) => tokens,
+        Err((span, message)) => compile_error(span, &message),
+    }
+}
+
+fn expand(attr: TokenStream, item: TokenStream) -> Result<TokenStream, (Span, String)> {
+    let mut rules = Vec::new();
+    for token in attr {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {}
+            TokenTree::Literal(literal) => {
+                let text = literal.to_string();
+                if text.len() < 2 || !text.starts_with('"') || text.contains('\\') {
+                    return Err((token.span(), "expected a requirement ID string".into()));
+                }
+                rules.push(text);
+            }
+            _ => return Err((token.span(), "expected a requirement ID string".into())),
+        }
+    }
+    if rules.is_empty() {
+        return Err((
+            Span::call_site(),
+            "expected at least one requirement ID, as in #[tracey_runtime::verify(\"auth.login\")]"
+                .into(),
+        ));
+    }
+
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+    let name = tokens
+        .windows(2)
+        .find_map(|pair| match pair {
+            [TokenTree::Ident(keyword), TokenTree::Ident(name)] if keyword.to_string() == "fn" => {
+                Some(name.to_string())
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            (
+                Span::call_site(),
+                "#[tracey_runtime::verify] goes on a function".into(),
+            )
+        })?;
+    let body = tokens
+        .iter()
+        .rposition(|t| matches!(t, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace))
+        .ok_or_else(|| {
+            (
+                Span::call_site(),
+                "#[tracey_runtime::verify] needs a function body".into(),
+            )
+        })?;
+
+    let test = format!("::core::concat!(::core::module_path!(), \"::{name}\")");
+    let enter: TokenStream = format!(
+        "::tracey_runtime::verified::enter({test}, &[{}]);",
+        rules.join(", ")
+    )
+    .parse()
+    .expect("the enter call is valid Rust");
+    let TokenTree::Group(group) = &tokens[body] else {
+        unreachable!("found as a brace group");
+    };
+    let mut stream = enter;
+    stream.extend(group.stream());
+    let mut new_group = Group::new(Delimiter::Brace, stream);
+    new_group.set_span(group.span());
+    tokens[body] = TokenTree::Group(new_group);
+    Ok(tokens.into_iter().collect())
+}
+
+/// `compile_error!(message)`, pointing at `span`.
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let tokens: TokenStream = format!("::core::compile_error!({message:?});")
+        .parse()
+        .expect("compile_error! call is valid Rust");
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            set_span(&mut token, span);
+            token
+        })
+        .collect()
+}
+
+fn set_span(token: &mut TokenTree, span: Span) {
+    match token {
+        TokenTree::Group(group) => {
+            let stream = group
+                .stream()
+                .into_iter()
+                .map(|mut token| {
+                    set_span(&mut token, span);
+                    token
+                })
+                .collect();
+            *group = Group::new(group.delimiter(), stream);
+            group.set_span(span);
+        }
+        other => other.set_span(span),
+    }
+}
@@ -14,7 +14,7 @@ pub use tracey_api::*;
 /// Protocol version — bump this whenever any RPC method is added, removed, or changed.
 /// The daemon writes this into its PID file; connectors compare it before connecting
 /// to detect stale daemons running an incompatible build.
-pub const PROTOCOL_VERSION: u32 = 4;
+pub const PROTOCOL_VERSION: u32 = 37;
 
 // ============================================================================
 // Request/Response types for the TraceyDaemon service
@@ -33,6 +33,31 @@ pub struct UncoveredRequest {
     /// Filter rules by ID prefix (case-insensitive)
     #[facet(default)]
     pub prefix: Option<String>,
+    /// Only rules owned by this team or person (from the config's `owners`)
+    #[facet(default)]
+    pub owner: Option<String>,
+    /// Ordering of the listed rules (default: by section)
+    #[facet(default)]
+    pub sort: Option<RuleListSort>,
+    /// Return at most this many rules
+    #[facet(default)]
+    pub limit: Option<u32>,
+    /// Skip this many rules before applying `limit`
+    #[facet(default)]
+    pub offset: Option<u32>,
+}
+
+/// Ordering for rule list queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum RuleListSort {
+    /// By rule ID
+    Id,
+    /// By section title, in spec order within a section
+    Section,
+    /// Rules with the most references first
+    Refs,
 }
 
 /// Response for uncovered rules query
@@ -42,6 +67,7 @@ pub struct UncoveredResponse {
     pub spec: String,
     pub impl_name: String,
     pub total_rules: usize,
+    /// All matching uncovered rules, including those cut by `offset`/`limit`
     pub uncovered_count: usize,
     /// Rules grouped by section
     pub by_section: Vec<SectionRules>,
@@ -61,6 +87,9 @@ pub struct RuleRef {
     pub id: RuleId,
     #[facet(default)]
     pub text: Option<String>,
+    /// Teams or people responsible for the rule
+    #[facet(default)]
+    pub owners: Vec<String>,
 }
 
 /// Request for untested rules query
@@ -73,17 +102,50 @@ pub struct UntestedRequest {
     pub impl_name: Option<String>,
     #[facet(default)]
     pub prefix: Option<String>,
+    /// Ordering of the listed rules (default: by section)
+    #[facet(default)]
+    pub sort: Option<RuleListSort>,
+    /// Return at most this many rules
+    #[facet(default)]
+    pub limit: Option<u32>,
+    /// Skip this many rules before applying `limit`
+    #[facet(default)]
+    pub offset: Option<u32>,
+    /// Leave out rules that have no impl references either
+    #[facet(default)]
+    pub only_implemented: bool,
 }
 
 /// Response for untested rules query
+///
+/// Untested rules come in two buckets: implemented but unverified
+/// (`by_section`), and neither implemented nor verified
+/// (`unimplemented_by_section`). With an ingested test report, a third
+/// bucket holds verified rules whose tests failed or never ran
+/// (`unproven_by_section`). `offset`/`limit` page through the buckets in
+/// that order.
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
 pub struct UntestedResponse {
     pub spec: String,
     pub impl_name: String,
     pub total_rules: usize,
+    /// All matching untested rules in both buckets, including those cut by `offset`/`limit`
     pub untested_count: usize,
+    /// How many of `untested_count` have impl references
+    #[facet(default)]
+    pub implemented_count: usize,
+    /// Implemented but unverified rules
     pub by_section: Vec<SectionRules>,
+    /// Rules with neither impl nor verify references (empty with `only_implemented`)
+    #[facet(default)]
+    pub unimplemented_by_section: Vec<SectionRules>,
+    /// How many verified rules have no passing test, including those cut by `offset`/`limit`
+    #[facet(default)]
+    pub unproven_count: usize,
+    /// Verified rules without a passing test in the last ingested test report
+    #[facet(default)]
+    pub unproven_by_section: Vec<SectionRules>,
 }
 
 /// Request for stale references query
@@ -99,6 +161,15 @@ pub struct StaleRequest {
     /// Filter rules by ID prefix (case-insensitive)
     #[facet(default)]
     pub prefix: Option<String>,
+    /// Ordering of the listed references (default: by file and line)
+    #[facet(default)]
+    pub sort: Option<RuleListSort>,
+    /// Return at most this many references
+    #[facet(default)]
+    pub limit: Option<u32>,
+    /// Skip this many references before applying `limit`
+    #[facet(default)]
+    pub offset: Option<u32>,
 }
 
 /// Response for stale references query
@@ -108,6 +179,7 @@ pub struct StaleResponse {
     pub spec: String,
     pub impl_name: String,
     pub total_rules: usize,
+    /// All matching stale references, including those cut by `offset`/`limit`
     pub stale_count: usize,
     /// Flat list of stale entries (sorted by file, then line)
     pub refs: Vec<StaleEntry>,
@@ -127,6 +199,215 @@ pub struct StaleEntry {
     pub reference_id: RuleId,
 }
 
+/// Request for traceability matrix query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct MatrixRequest {
+    /// Spec name (all specs if omitted)
+    #[facet(default)]
+    pub spec: Option<String>,
+    /// Filter rules by ID prefix (case-insensitive)
+    #[facet(default)]
+    pub prefix: Option<String>,
+}
+
+/// Response for traceability matrix query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct MatrixResponse {
+    pub specs: Vec<SpecMatrix>,
+}
+
+/// Rules × impls of one spec
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecMatrix {
+    pub spec: String,
+    /// Column order of every row's `cells`
+    pub impls: Vec<String>,
+    pub rows: Vec<MatrixRow>,
+}
+
+/// One rule's coverage across impls
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct MatrixRow {
+    pub id: RuleId,
+    pub cells: Vec<MatrixCell>,
+    /// Whether the rule's current version has a reviewer approval
+    #[facet(default)]
+    pub approved: bool,
+}
+
+/// Reference counts of one rule in one impl
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct MatrixCell {
+    pub impl_refs: usize,
+    pub verify_refs: usize,
+    pub stale_refs: usize,
+}
+
+/// Request for dead rules query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DeadRequest {
+    #[facet(default)]
+    pub spec: Option<String>,
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    /// Minimum age in days (defaults to 90)
+    #[facet(default)]
+    pub days: Option<u32>,
+}
+
+/// Response for dead rules query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DeadResponse {
+    pub spec: String,
+    pub impl_name: String,
+    pub total_rules: usize,
+    /// Age threshold that was applied
+    pub days: u32,
+    /// Dead rules, oldest first
+    pub rules: Vec<DeadRule>,
+    /// Unreferenced rules whose definition has no git history
+    pub unknown_age: usize,
+}
+
+/// A rule without any references, with the age of its definition
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DeadRule {
+    pub id: RuleId,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+    /// Days since the definition line was last changed, per `git blame`
+    pub age_days: u32,
+}
+
+/// Request for high-level rules without refinements
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnrefinedRequest {
+    #[facet(default)]
+    pub spec: Option<String>,
+    #[facet(default)]
+    pub impl_name: Option<String>,
+}
+
+/// Response for the unrefined rules query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnrefinedResponse {
+    pub spec: String,
+    pub impl_name: String,
+    /// First ID segments of rules that other rules refine, e.g. `sys`
+    pub levels: Vec<String>,
+    /// How many rules belong to one of `levels`
+    pub high_level_rules: usize,
+    /// Rules of those levels that no rule refines, in spec order
+    pub rules: Vec<UnrefinedRule>,
+}
+
+/// A high-level rule that no lower-level rule refines
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnrefinedRule {
+    pub id: RuleId,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+}
+
+/// Request for a spec's section outline
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecOutlineRequest {
+    #[facet(default)]
+    pub spec: Option<String>,
+    #[facet(default)]
+    pub impl_name: Option<String>,
+}
+
+/// A spec's headings as a section tree, with the rules defined under each
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecOutlineResponse {
+    pub spec: String,
+    pub impl_name: String,
+    /// Sections in document order; each names its parent by index
+    pub sections: Vec<OutlineSection>,
+    /// Rules defined before the first heading
+    pub unsectioned: Vec<RuleId>,
+}
+
+/// A heading in the section tree
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct OutlineSection {
+    pub title: String,
+    pub slug: String,
+    /// Heading level (1-6)
+    pub level: u8,
+    /// Index of the enclosing section in `sections`, `None` at the top
+    #[facet(default)]
+    pub parent: Option<usize>,
+    /// Rules defined directly under this heading
+    pub rules: Vec<RuleId>,
+    /// Coverage of the rules directly under this heading
+    pub coverage: OutlineCoverage,
+    /// Coverage including all nested sections
+    pub aggregated: OutlineCoverage,
+}
+
+/// Request for unexecuted rules query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnexecutedRequest {
+    #[facet(default)]
+    pub spec: Option<String>,
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    #[facet(default)]
+    pub prefix: Option<String>,
+}
+
+/// Response for unexecuted rules query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnexecutedResponse {
+    pub spec: String,
+    pub impl_name: String,
+    pub total_rules: usize,
+    /// Whether a line coverage report has been ingested
+    pub has_report: bool,
+    /// Rules whose implementing code the report has data for
+    pub measured_rules: usize,
+    /// Rules whose implementing code never ran, by section
+    pub by_section: Vec<UnexecutedSection>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnexecutedSection {
+    pub section: String,
+    pub rules: Vec<UnexecutedRule>,
+}
+
+/// A rule whose implementing code was never executed
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct UnexecutedRule {
+    pub id: RuleId,
+    /// The unexecuted code units behind its `impl` references
+    pub line_coverage: Vec<ApiLineCoverage>,
+}
+
 /// Request for unmapped code query
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -176,11 +457,225 @@ pub struct UnmappedUnit {
     pub end_line: usize,
 }
 
+/// Request for code-unit coverage rolled up per directory
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DirCoverageRequest {
+    #[facet(default)]
+    pub spec: Option<String>,
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    /// Only roll up directories at most this many levels deep
+    #[facet(default)]
+    pub depth: Option<u32>,
+    /// Order of the returned directories (default: lowest coverage first)
+    #[facet(default)]
+    pub sort: Option<DirCoverageSort>,
+}
+
+/// Ordering for directory coverage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum DirCoverageSort {
+    /// Lowest coverage percentage first
+    Coverage,
+    /// Most unmapped code units first
+    Unmapped,
+    /// Alphabetical by path
+    Path,
+}
+
+/// Response for directory coverage query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DirCoverageResponse {
+    pub spec: String,
+    pub impl_name: String,
+    pub total_units: usize,
+    pub covered_units: usize,
+    pub dirs: Vec<DirCoverage>,
+}
+
+/// Code-unit coverage of one directory, including its subdirectories
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DirCoverage {
+    pub path: String,
+    pub total_units: usize,
+    pub covered_units: usize,
+}
+
+impl DirCoverage {
+    pub fn coverage_percent(&self) -> f64 {
+        if self.total_units == 0 {
+            100.0
+        } else {
+            (self.covered_units as f64 / self.total_units as f64) * 100.0
+        }
+    }
+}
+
+/// Request for the references in one source file
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct FileRefsRequest {
+    /// Absolute path, or path relative to the project root
+    pub path: String,
+}
+
+/// Response for file references query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct FileRefsResponse {
+    /// Path relative to the project root
+    pub path: String,
+    /// Whether the file was scanned at all
+    pub scanned: bool,
+    pub refs: Vec<FileRef>,
+}
+
+/// A reference in a source file and the rule it resolves to
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct FileRef {
+    pub verb: String,
+    pub prefix: String,
+    /// Rule ID as written in the reference
+    pub rule_id: RuleId,
+    pub line: usize,
+    pub status: FileRefStatus,
+    /// Current version of the rule when the reference is stale
+    #[facet(default)]
+    pub current_id: Option<RuleId>,
+    /// Raw markdown text of the referenced rule (current version if stale)
+    #[facet(default)]
+    pub text: Option<String>,
+}
+
+/// How a reference resolves against the spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum FileRefStatus {
+    /// Points at the current version of a rule
+    Current,
+    /// Points at an older version of a rule
+    Stale,
+    /// Unknown prefix or rule
+    Unknown,
+}
+
+/// Request for coverage trend query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TrendRequest {
+    /// Spec name (all specs if omitted)
+    #[facet(default)]
+    pub spec: Option<String>,
+    /// Implementation name (all impls if omitted)
+    #[facet(default)]
+    pub impl_name: Option<String>,
+    /// Only include snapshots recorded within this many seconds
+    #[facet(default)]
+    pub since_secs: Option<u64>,
+}
+
+/// Response for coverage trend query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TrendResponse {
+    /// Snapshot points, grouped by spec/impl and oldest first within each
+    pub points: Vec<TrendPoint>,
+}
+
+/// Coverage of one spec/impl pair in one recorded snapshot
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// When the snapshot was recorded (Unix seconds)
+    pub recorded_at: u64,
+    pub spec: String,
+    pub impl_name: String,
+    pub total_rules: usize,
+    pub implemented_rules: usize,
+    pub verified_rules: usize,
+    pub stale_rules: usize,
+}
+
+/// Request for recently changed rules
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RecentRequest {
+    /// A snapshot version (`42`), an age (`30d`, `12h`) or a UTC date
+    /// (`2026-10-01`)
+    pub since: String,
+}
+
+/// Rules that changed since a recorded history snapshot
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RecentResponse {
+    pub since: String,
+    /// Version of the snapshot the current rules were compared with
+    pub baseline_version: u64,
+    /// When that snapshot was recorded (Unix seconds)
+    pub baseline_recorded_at: u64,
+    /// Version of the latest snapshot, to pass as `since` next time
+    pub latest_version: u64,
+    /// In spec and rule ID order
+    pub rules: Vec<RecentRule>,
+}
+
+/// A rule whose definition or coverage changed
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RecentRule {
+    pub spec: String,
+    /// Current ID, with its version (the last one, for removed rules)
+    pub id: String,
+    /// The ID at the baseline, if the version changed since
+    #[facet(default)]
+    pub previous_id: Option<String>,
+    pub change: RecentChange,
+    /// Impls whose coverage of the rule changed
+    pub coverage: Vec<RecentCoverage>,
+}
+
+/// What changed about a recently changed rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "lowercase")]
+#[repr(u8)]
+pub enum RecentChange {
+    /// Defined now but not at the baseline
+    Added,
+    /// Defined at the baseline but not now
+    Removed,
+    /// New version or text
+    Changed,
+    /// Only its coverage by some impl changed
+    Coverage,
+}
+
+/// How one impl's coverage of a rule changed
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RecentCoverage {
+    pub impl_name: String,
+    pub was_implemented: bool,
+    pub implemented: bool,
+    pub was_verified: bool,
+    pub verified: bool,
+}
+
 /// Coverage status response
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
 pub struct StatusResponse {
     pub impls: Vec<ImplStatus>,
+    /// Which impls cover each rule, for the specs with more than one impl
+    #[facet(default)]
+    pub by_rule: Vec<RuleImplsStatus>,
 }
 
 /// Status for a single spec/impl combination
@@ -196,6 +691,33 @@ pub struct ImplStatus {
     /// Not included in covered_rules. covered_rules + stale_rules + uncovered = total.
     pub stale_rules: usize,
     pub verified_rules: usize,
+    /// Coverage of each spec file, sorted by path. Only filled in when the
+    /// spec's rules come from more than one file.
+    #[facet(default)]
+    pub by_file: Vec<FileStatus>,
+}
+
+/// Coverage of the rules defined in one spec file
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct FileStatus {
+    pub file: String,
+    pub total_rules: usize,
+    pub covered_rules: usize,
+    pub stale_rules: usize,
+    pub verified_rules: usize,
+}
+
+/// Which of a spec's impls cover one of its rules
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RuleImplsStatus {
+    pub spec: String,
+    pub id: RuleId,
+    /// Impls with a current (non-stale) implementation reference
+    pub covered_in: Vec<String>,
+    /// Impls without one
+    pub missing_in: Vec<String>,
 }
 
 /// Information about a specific rule
@@ -210,12 +732,72 @@ pub struct RuleInfo {
     pub source_file: Option<String>,
     #[facet(default)]
     pub source_line: Option<usize>,
+    /// Link to the definition, built from the spec's `source_url`
+    #[facet(default)]
+    pub permalink: Option<String>,
+    /// Teams or people responsible for the rule
+    #[facet(default)]
+    pub owners: Vec<String>,
+    /// Reviewer approvals of the rule's current version
+    #[facet(default)]
+    pub approvals: Vec<ApiApproval>,
     /// Coverage across all implementations
     pub coverage: Vec<RuleCoverage>,
     /// Diff from the previous rule version (N-1 → N), if version > 1 and git history is available.
     /// Inline markdown format: ~~removed~~ / **added**.
     #[facet(default)]
     pub version_diff: Option<String>,
+    /// Rules of other specs the rule's text depends on
+    #[facet(default)]
+    pub depends_on: Vec<ApiSpecDependency>,
+    /// Higher-level rules this rule refines
+    #[facet(default)]
+    pub refines: Vec<RuleId>,
+    /// Lower-level rules that refine this rule
+    #[facet(default)]
+    pub refined_by: Vec<RuleId>,
+}
+
+/// Who last touched a line, from `git blame`
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BlameInfo {
+    pub commit: String,
+    pub author: String,
+    pub author_mail: String,
+    /// Author time (Unix seconds)
+    pub author_time: i64,
+    pub summary: String,
+}
+
+/// `git blame` for a rule: its text and every reference to it
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RuleBlameResponse {
+    pub id: RuleId,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+    /// Latest commit touching the rule's text (None if uncommitted or untracked)
+    #[facet(default)]
+    pub text: Option<BlameInfo>,
+    pub refs: Vec<RefBlame>,
+}
+
+/// `git blame` for one reference to a rule
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RefBlame {
+    pub spec: String,
+    pub impl_name: String,
+    /// `impl`, `verify` or `stale`
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+    /// None if the line is uncommitted or untracked
+    #[facet(default)]
+    pub blame: Option<BlameInfo>,
 }
 
 /// Coverage of a rule in a specific implementation
@@ -226,6 +808,12 @@ pub struct RuleCoverage {
     pub impl_name: String,
     pub impl_refs: Vec<ApiCodeRef>,
     pub verify_refs: Vec<ApiCodeRef>,
+    /// How the tests behind `verify_refs` did in the last ingested test report
+    #[facet(default)]
+    pub test_results: Vec<ApiTestResult>,
+    /// How much of the code behind `impl_refs` ran, per the last ingested line coverage report
+    #[facet(default)]
+    pub line_coverage: Vec<ApiLineCoverage>,
 }
 
 /// Response from reload command
@@ -236,6 +824,47 @@ pub struct ReloadResponse {
     pub rebuild_time_ms: u64,
 }
 
+/// Drift between a remote spec's locked copy and its upstream
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecDrift {
+    pub spec: String,
+    /// Remote source (git URL or `rules_url`)
+    pub source: String,
+    /// Revision recorded in the lockfile (commit hash or content hash)
+    #[facet(default)]
+    pub locked: Option<String>,
+    /// Latest upstream revision
+    #[facet(default)]
+    pub upstream: Option<String>,
+    /// Rules whose upstream version is newer than the locked one
+    pub bumped: Vec<RuleVersionDrift>,
+    /// Rules that exist upstream but not in the locked copy
+    pub added: Vec<String>,
+    /// Rules in the locked copy that no longer exist upstream
+    pub removed: Vec<String>,
+    /// Error encountered while checking upstream
+    #[facet(default)]
+    pub error: Option<String>,
+}
+
+/// A rule whose upstream version moved past the locked version
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RuleVersionDrift {
+    /// Base rule ID (without version)
+    pub id: String,
+    pub locked_version: u32,
+    pub upstream_version: u32,
+}
+
+/// Response from spec drift check
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecDriftResponse {
+    pub specs: Vec<SpecDrift>,
+}
+
 /// Request for file content
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -263,6 +892,12 @@ pub struct SearchResult {
     #[facet(default)]
     pub highlighted: Option<String>,
     pub score: f32,
+    /// For rules: spec file the rule is defined in
+    #[facet(default)]
+    pub source_file: Option<String>,
+    /// For rules: line of the definition in `source_file`
+    #[facet(default)]
+    pub source_line: Option<usize>,
 }
 
 /// Request to update a file range (for inline editing)
@@ -301,6 +936,10 @@ pub struct DataUpdate {
     pub version: u64,
     #[facet(default)]
     pub delta: Option<DeltaSummary>,
+    /// Project-relative files whose changes triggered the rebuild
+    /// (empty for a full rebuild)
+    #[facet(default)]
+    pub changed_files: Vec<String>,
 }
 
 /// Response for health check query.
@@ -333,10 +972,63 @@ pub struct HealthResponse {
     /// Directories currently being watched
     pub watched_directories: Vec<String>,
 
+    /// Whether a git operation (checkout, rebase, merge, ...) is in progress
+    /// and rebuilds are deferred until it settles
+    #[facet(default)]
+    pub git_operation_in_progress: bool,
+
+    /// Matched source files the last build skipped as too large or binary
+    /// r[impl config.file-limits]
+    #[facet(default)]
+    pub skipped_files: usize,
+
+    /// Rebuild passes running or queued: at most one of each, since queued
+    /// requests coalesce
+    /// r[impl daemon.state.rebuild-queue]
+    #[facet(default)]
+    pub rebuild_queue_depth: usize,
+
     /// Daemon uptime in seconds
     pub uptime_secs: u64,
 }
 
+/// Request for several queries answered in one round trip
+#[derive(Debug, Clone, Default, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BatchRequest {
+    /// Include the coverage status
+    #[facet(default)]
+    pub status: bool,
+    /// Include the configuration
+    #[facet(default)]
+    pub config: bool,
+    /// Include the daemon's health
+    #[facet(default)]
+    pub health: bool,
+    /// Uncovered-rule queries, answered in order
+    #[facet(default)]
+    pub uncovered: Vec<UncoveredRequest>,
+    /// Stale-reference queries, answered in order
+    #[facet(default)]
+    pub stale: Vec<StaleRequest>,
+}
+
+/// Response to a batch request, with one answer per requested query
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BatchResponse {
+    #[facet(default)]
+    pub status: Option<StatusResponse>,
+    #[facet(default)]
+    pub config: Option<ApiConfig>,
+    #[facet(default)]
+    pub health: Option<HealthResponse>,
+    #[facet(default)]
+    pub uncovered: Vec<UncoveredResponse>,
+    #[facet(default)]
+    pub stale: Vec<StaleResponse>,
+}
+
 /// Summary of what changed in a rebuild
 #[derive(Debug, Clone, Facet)]
 #[facet(rename_all = "camelCase")]
@@ -368,12 +1060,14 @@ pub struct LspPosition {
     pub character: u32,
 }
 
-/// A location in a file
+/// A range in a file (0-indexed, end exclusive)
 #[derive(Debug, Clone, Facet)]
 pub struct LspLocation {
     pub path: String,
     pub line: u32,
     pub character: u32,
+    pub end_line: u32,
+    pub end_character: u32,
 }
 
 /// A code reference location for hover links
@@ -399,6 +1093,9 @@ pub struct HoverInfo {
     /// Spec source URL (if configured)
     #[facet(default)]
     pub spec_url: Option<String>,
+    /// Link to the definition, built from the spec's `source_url`
+    #[facet(default)]
+    pub permalink: Option<String>,
     /// Source file where the rule is defined
     #[facet(default)]
     pub source_file: Option<String>,
@@ -569,6 +1266,9 @@ pub struct LspCodeAction {
     /// Is this the preferred action?
     #[facet(default)]
     pub is_preferred: bool,
+    /// Edits to the requesting document, applied instead of running `command`
+    #[facet(default)]
+    pub edits: Vec<LspTextEdit>,
 }
 
 /// Request for inlay hints
@@ -656,17 +1356,52 @@ pub trait TraceyDaemon {
     /// Get stale references (code pointing to older rule versions)
     async fn stale(&self, req: StaleRequest) -> StaleResponse;
 
+    /// Get a rules × impls traceability matrix
+    async fn matrix(&self, req: MatrixRequest) -> MatrixResponse;
+
+    /// Get dead rules (no references, defined more than N days ago)
+    async fn dead(&self, req: DeadRequest) -> DeadResponse;
+
+    /// Get high-level rules that no lower-level rule refines
+    async fn unrefined(&self, req: UnrefinedRequest) -> UnrefinedResponse;
+
+    /// Get rules whose implementing code never ran, per the ingested line coverage
+    async fn unexecuted(&self, req: UnexecutedRequest) -> UnexecutedResponse;
+
     /// Get unmapped code (code units without requirement references)
     async fn unmapped(&self, req: UnmappedRequest) -> UnmappedResponse;
 
+    /// Get code-unit coverage rolled up per directory
+    async fn dir_coverage(&self, req: DirCoverageRequest) -> DirCoverageResponse;
+
+    /// List every reference in a source file with the rule it resolves to
+    async fn file_refs(&self, req: FileRefsRequest) -> FileRefsResponse;
+
+    /// Get coverage over time from the recorded history snapshots
+    async fn coverage_trend(&self, req: TrendRequest) -> Result<TrendResponse, String>;
+
+    /// List rules whose definition or coverage changed since a history snapshot
+    async fn recent(&self, req: RecentRequest) -> Result<RecentResponse, String>;
+
     /// Get details for a specific rule by ID
     async fn rule(&self, rule_id: RuleId) -> Option<RuleInfo>;
 
+    /// Get `git blame` for a rule's text and for each reference to it
+    async fn rule_blame(&self, rule_id: RuleId) -> Option<RuleBlameResponse>;
+
+    /// List the IDs of all rules whose base ID matches a glob pattern such as `auth.*`
+    async fn find_rules(&self, pattern: String) -> Vec<RuleId>;
+
     // === Configuration ===
 
     /// Get current configuration
     async fn config(&self) -> ApiConfig;
 
+    // === Batching ===
+
+    /// Answer several queries in one round trip
+    async fn batch(&self, req: BatchRequest) -> BatchResponse;
+
     // === VFS Overlay (for LSP) ===
 
     /// Notify that a file was opened with the given content
@@ -683,6 +1418,12 @@ pub trait TraceyDaemon {
     /// Force a rebuild of the dashboard data
     async fn reload(&self) -> ReloadResponse;
 
+    /// Re-fetch remote (`rules_url` and git `source`) specs, then rebuild
+    async fn refetch(&self) -> ReloadResponse;
+
+    /// Compare remote specs' locked revisions against upstream
+    async fn spec_drift(&self) -> SpecDriftResponse;
+
     /// Get current data version
     async fn version(&self) -> u64;
 
@@ -712,6 +1453,9 @@ pub trait TraceyDaemon {
     /// Get rendered spec content with outline
     async fn spec_content(&self, spec: String, impl_name: String) -> Option<ApiSpecData>;
 
+    /// Get a spec's headings as a section tree with per-section coverage
+    async fn spec_outline(&self, req: SpecOutlineRequest) -> Result<SpecOutlineResponse, String>;
+
     /// Search rules and files
     async fn search(&self, query: String, limit: u32) -> Vec<SearchResult>;
 
@@ -784,6 +1528,9 @@ pub trait TraceyDaemon {
     /// and unknown references.
     async fn validate(&self, req: ValidateRequest) -> ValidationResult;
 
+    /// Validate every spec/impl pair, in the order `status` lists them
+    async fn validate_all(&self) -> Vec<ValidationResult>;
+
     // === Config Modification (for MCP) ===
 
     /// Add an exclude pattern to an implementation
@@ -0,0 +1,130 @@
+//! tracey-runtime - mark requirements as exercised at runtime
+//!
+//! r[impl runtime.requirement]
+//!
+//! Two things live here, both small enough for any crate to depend on:
+//! [`requirement!`], for the code that carries a requirement out, and the
+//! [`verify`] attribute, for the tests that verify it.
+//!
+//! [`requirement!`] emits a `tracing` event on the `tracey` target with the
+//! requirement's ID in a `requirement` field. Log those events, with any
+//! `tracing-subscriber` format, from a test run or a production service, and
+//! `tracey trace-ingest` sets them against the specs to show which
+//! requirements the code exercised in practice.
+//!
+//! ```ignore
+//! fn login(user: &User, password: &str) -> Result<Session> {
+//!     tracey_runtime::requirement!("auth.login");
+//!     // ...
+//! }
+//! ```
+
+pub mod verified;
+
+pub use tracey_macros::verify;
+
+#[doc(hidden)]
+pub use tracing as __tracing;
+
+// So `#[tracey_runtime::verify]` expands to paths that resolve in this crate's tests
+extern crate self as tracey_runtime;
+
+/// The target of [`requirement!`] events.
+pub const TARGET: &str = "tracey";
+
+/// The field [`requirement!`] puts the requirement ID in.
+pub const FIELD: &str = "requirement";
+
+/// Emit an INFO event on the `tracey` target saying the requirement with
+/// this ID was exercised. More fields can follow the ID, as in
+/// `tracing::info!`:
+///
+/// ```ignore
+/// tracey_runtime::requirement!("auth.login", user = %user.name);
+/// ```
+#[macro_export]
+macro_rules! requirement {
+    ($id:literal) => {
+        $crate::__tracing::event!(
+            target: "tracey",
+            $crate::__tracing::Level::INFO,
+            requirement = $id,
+            "requirement exercised"
+        )
+    };
+    ($id:literal, $($fields:tt)+) => {
+        $crate::__tracing::event!(
+            target: "tracey",
+            $crate::__tracing::Level::INFO,
+            requirement = $id,
+            $($fields)+
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+
+    /// Target and fields of an event
+    type Recorded = (String, Vec<(String, String)>);
+
+    /// Records every event.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+    struct Fields(Vec<(String, String)>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Recorder {
+        fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+            let mut fields = Fields(Vec::new());
+            event.record(&mut fields);
+            let target = event.metadata().target().to_string();
+            self.0.lock().unwrap().push((target, fields.0));
+        }
+    }
+
+    // r[verify runtime.requirement]
+    #[test]
+    fn emits_an_event_per_requirement() {
+        let recorder = Recorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            crate::requirement!("auth.login");
+            crate::requirement!("auth.session+2", user = "ada");
+        });
+
+        let events = recorder.0.lock().unwrap().clone();
+        let field = |key: &str, value: &str| (key.to_string(), value.to_string());
+        assert_eq!(
+            events,
+            [
+                (
+                    super::TARGET.to_string(),
+                    vec![
+                        field(super::FIELD, "auth.login"),
+                        field("message", "requirement exercised")
+                    ]
+                ),
+                (
+                    super::TARGET.to_string(),
+                    vec![field(super::FIELD, "auth.session+2"), field("user", "ada")]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! The runtime side of the `#[tracey_runtime::verify]` attribute.
+//!
+//! r[impl ref.attribute.capture]
+//!
+//! A `#[tracey_runtime::verify("auth.login")]` test calls [`enter`] first
+//! thing. Without the `verify-capture` feature that does nothing. With it,
+//! the test's path and rule IDs are printed as `tracey: TEST verifies IDS`,
+//! which the test harness shows with the test's output, appended as a line
+//! of JSON to the file that `TRACEY_VERIFIED` names, if it's set, and kept
+//! for [`all`], for harnesses that report it themselves.
+
+/// Names the file that each test entered is appended to, as a JSON line.
+pub const LOG_ENV: &str = "TRACEY_VERIFIED";
+
+/// A `#[tracey_runtime::verify]` test that ran, and the requirements it
+/// verifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "verify-capture", derive(facet::Facet))]
+pub struct Verified {
+    /// Module path and name, e.g. `auth::tests::logs_in`
+    pub test: String,
+    /// As written in the attribute
+    pub rules: Vec<String>,
+}
+
+#[cfg(feature = "verify-capture")]
+static VERIFIED: std::sync::Mutex<Vec<Verified>> = std::sync::Mutex::new(Vec::new());
+
+/// Called by `#[tracey_runtime::verify]` at the start of the test `test`.
+#[doc(hidden)]
+pub fn enter(test: &str, rules: &[&str]) {
+    #[cfg(feature = "verify-capture")]
+    record(Verified {
+        test: test.to_string(),
+        rules: rules.iter().map(|rule| rule.to_string()).collect(),
+    });
+    #[cfg(not(feature = "verify-capture"))]
+    let _ = (test, rules);
+}
+
+#[cfg(feature = "verify-capture")]
+fn record(verified: Verified) {
+    use std::io::Write;
+
+    println!(
+        "tracey: {} verifies {}",
+        verified.test,
+        verified.rules.join(", ")
+    );
+    if let Some(path) = std::env::var_os(LOG_ENV)
+        && let Ok(line) = facet_json::to_string(&verified)
+    {
+        // Tests run in parallel: one write per line keeps lines whole
+        let appended = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(format!("{line}\n").as_bytes()));
+        if let Err(e) = appended {
+            eprintln!("tracey: failed to append to {}: {e}", path.display());
+        }
+    }
+    VERIFIED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(verified);
+}
+
+/// The `#[tracey_runtime::verify]` tests entered so far in this process, in order.
+#[cfg(feature = "verify-capture")]
+pub fn all() -> Vec<Verified> {
+    VERIFIED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Always empty without the `verify-capture` feature.
+#[cfg(not(feature = "verify-capture"))]
+pub fn all() -> Vec<Verified> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[tracey_runtime::verify("ref.attribute.capture")]
+    fn records_the_rules_of_a_test() {
+        let entered = all()
+            .into_iter()
+            .find(|v| v.test.ends_with("::records_the_rules_of_a_test"));
+        if cfg!(feature = "verify-capture") {
+            let entered = entered.unwrap();
+            assert_eq!(
+                entered.test,
+                "tracey_runtime::verified::tests::records_the_rules_of_a_test"
+            );
+            assert_eq!(entered.rules, ["ref.attribute.capture"]);
+        } else {
+            assert_eq!(entered, None);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! tracey-wasm - tracey's spec and reference parsing for the browser
+//!
+//! r[impl wasm.preview]
+//!
+//! Built with `just wasm` into an ES module (`pkg/tracey_wasm.js`) that a
+//! web page loads to parse specs and annotated code without a daemon:
+//! [`extract_rules`] reads rule definitions out of markdown,
+//! [`extract_references`] reads references out of source code, and
+//! [`preview`] puts both together into the coverage a snippet would give,
+//! so an edit can be shown before it's saved. Every function returns JSON,
+//! which the caller hands to `JSON.parse`.
+//!
+//! Only the parsing part of `tracey-core` is compiled (no `walk` or
+//! `parallel`): there's no file system to scan, the caller passes the
+//! files' content.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use facet::Facet;
+use tracey_core::{Id, ParseWarning, ReqReference, Reqs, Rule, ScanError};
+use wasm_bindgen::prelude::*;
+
+/// The coverage a snippet of code gives a spec.
+#[derive(Debug, Facet)]
+pub struct Preview {
+    pub rules: Vec<Rule>,
+    /// The snippet's references with one of the spec's prefixes
+    pub references: Vec<PreviewReference>,
+    /// Canonical IDs (`auth.login+2`) of the rules the snippet covers,
+    /// sorted
+    pub covered: Vec<String>,
+    /// Canonical IDs of the others, sorted
+    pub uncovered: Vec<String>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// A reference of a [`Preview`], and whether the spec defines its rule.
+#[derive(Debug, Facet)]
+pub struct PreviewReference {
+    pub reference: ReqReference,
+    pub known: bool,
+}
+
+impl Preview {
+    /// The preview of the references in `source` (the content of the file
+    /// at `path`) against the spec in `spec` (the markdown at `spec_path`).
+    pub async fn compute(
+        spec_path: &str,
+        spec: &str,
+        path: &str,
+        source: &str,
+    ) -> Result<Preview, ScanError> {
+        let rules = Rule::extract_from_content(Path::new(spec_path), spec).await?;
+        let reqs = Reqs::extract_from_content(Path::new(path), source);
+        let prefixes: HashSet<&str> = rules.iter().map(|r| r.prefix.as_str()).collect();
+        let known: HashSet<Id> = rules.iter().map(|r| r.id.clone()).collect();
+
+        let references: Vec<PreviewReference> = reqs
+            .references
+            .into_iter()
+            .filter(|r| prefixes.contains(r.prefix.as_str()))
+            .map(|reference| PreviewReference {
+                known: known.contains(&Id::from(&reference.req_id)),
+                reference,
+            })
+            .collect();
+        let covered_ids: HashSet<Id> = references
+            .iter()
+            .filter(|r| r.known)
+            .map(|r| Id::from(&r.reference.req_id))
+            .collect();
+        let mut ids: Vec<&Id> = rules.iter().map(|r| &r.id).collect();
+        ids.sort();
+        ids.dedup();
+        let (covered, uncovered): (Vec<&Id>, Vec<&Id>) =
+            ids.into_iter().partition(|id| covered_ids.contains(*id));
+        let covered = covered.iter().map(|id| id.to_string()).collect();
+        let uncovered = uncovered.iter().map(|id| id.to_string()).collect();
+
+        Ok(Preview {
+            rules,
+            references,
+            covered,
+            uncovered,
+            warnings: reqs.warnings,
+        })
+    }
+}
+
+fn to_json<'a, T: Facet<'a>>(value: &T) -> Result<String, JsError> {
+    facet_json::to_string(value).map_err(|e| JsError::new(&format!("failed to serialize: {e}")))
+}
+
+/// The references and parse warnings in `content`, the source of the file
+/// at `path` (its extension picks the comment syntax), as JSON.
+#[wasm_bindgen(js_name = extractReferences)]
+pub fn extract_references(path: &str, content: &str) -> Result<String, JsError> {
+    to_json(&Reqs::extract_from_content(Path::new(path), content))
+}
+
+/// The rules defined in `markdown`, the spec file at `path`, as a JSON
+/// array.
+#[wasm_bindgen(js_name = extractRules)]
+pub async fn extract_rules(path: String, markdown: String) -> Result<String, JsError> {
+    let rules = Rule::extract_from_content(Path::new(&path), &markdown)
+        .await
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    to_json(&rules)
+}
+
+/// The [`Preview`] of `source` against `spec`, as JSON.
+#[wasm_bindgen]
+pub async fn preview(
+    spec_path: String,
+    spec: String,
+    path: String,
+    source: String,
+) -> Result<String, JsError> {
+    let preview = Preview::compute(&spec_path, &spec, &path, &source)
+        .await
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    to_json(&preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify wasm.preview]
+    #[test]
+    fn previews_the_coverage_of_a_snippet() {
+        let spec =
+            "# Auth\n\nr[auth.login]\nUsers MUST log in.\n\nr[auth.logout]\nUsers MAY log out.\n";
+        let source = "// r[impl auth.login]\nfn login() {}\n\n\
+                      // r[impl auth.gone]\nfn gone() {}\n\n\
+                      // o[impl other.thing]\nfn other() {}\n";
+        let preview =
+            block_on(Preview::compute("docs/spec.md", spec, "src/lib.rs", source)).unwrap();
+
+        let rules: Vec<String> = preview.rules.iter().map(|r| r.id.to_string()).collect();
+        assert_eq!(rules, ["auth.login", "auth.logout"]);
+        let references: Vec<(String, bool)> = preview
+            .references
+            .iter()
+            .map(|r| (r.reference.req_id.to_string(), r.known))
+            .collect();
+        assert_eq!(
+            references,
+            [
+                ("auth.login".to_string(), true),
+                ("auth.gone".to_string(), false)
+            ]
+        );
+        assert_eq!(preview.covered, ["auth.login"]);
+        assert_eq!(preview.uncovered, ["auth.logout"]);
+        assert_eq!(preview.references[0].reference.line, 1);
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::task::{Context, Poll, Waker};
+        let mut future = std::pin::pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+}
@@ -128,6 +128,7 @@ fn generate_typescript_types() {
     generator.add_type::<GitStatus>();
     generator.add_type::<ApiConfig>();
     generator.add_type::<ApiSpecInfo>();
+    generator.add_type::<ApiPolicyStatus>();
     generator.add_type::<ApiForwardData>();
     generator.add_type::<ApiSpecForward>();
     generator.add_type::<ApiRule>();
@@ -136,6 +137,7 @@ fn generate_typescript_types() {
     generator.add_type::<ApiFileEntry>();
     generator.add_type::<ApiFileData>();
     generator.add_type::<ApiCodeUnit>();
+    generator.add_type::<ApiFileReference>();
     generator.add_type::<SpecSection>();
     generator.add_type::<OutlineCoverage>();
     generator.add_type::<OutlineEntry>();
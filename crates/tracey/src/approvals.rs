@@ -0,0 +1,403 @@
+//! Reviewer approvals of rule versions, for `tracey approve`.
+//!
+//! r[impl cli.approve]
+//!
+//! Each record says who approved which version of a rule, when, and carries
+//! a digest of the rule text they approved. Records live in
+//! `.tracey/approvals/`, one JSON file per rule, and are meant to be
+//! committed with the spec so that review history travels with it. An
+//! approval only counts while the rule still has that version and text:
+//! bumping a rule, or editing it without a bump, needs a new approval.
+//!
+//! Records are signed with the approver's SSH key (`ssh-keygen -Y sign`, as
+//! evidence bundles are), and only count once their signature checks out
+//! against the project's `.config/tracey/allowed_signers`, so approvals
+//! can't be made up by editing the JSON.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use tracey_api::ApiApproval;
+use tracey_core::RuleId;
+use tracing::warn;
+
+use crate::bundle::Signers;
+use crate::config::Config;
+
+/// Version of the approval file layout.
+pub const APPROVALS_VERSION: u32 = 1;
+
+/// Namespace of approval signatures, as `ssh-keygen -Y` calls it.
+pub const SIGNATURE_NAMESPACE: &str = "tracey-approval";
+
+/// The `ssh-keygen` allowed signers file naming who may approve, relative to
+/// the project root. Each approver's principal is their `by` name.
+pub const ALLOWED_SIGNERS: &str = ".config/tracey/allowed_signers";
+
+/// All approvals of one rule, across its versions.
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ApprovalFile {
+    pub version: u32,
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Approval {
+    /// The approved rule version, e.g. `auth.login+3`
+    pub rule: String,
+    pub by: String,
+    /// Date of the approval (YYYY-MM-DD)
+    pub date: String,
+    /// BLAKE3 digest of the rule text that was approved
+    pub digest: String,
+    /// Git identity of whoever recorded the approval, if known
+    #[facet(default)]
+    pub recorded_by: Option<String>,
+    /// `by`'s SSH signature of [`Approval::signed_text`], armored
+    #[facet(default)]
+    pub signature: Option<String>,
+}
+
+impl Approval {
+    /// What the signature covers: everything the approval claims.
+    pub fn signed_text(&self) -> String {
+        format!(
+            "tracey approval {}\nrule {}\nby {}\ndate {}\ndigest {}\n",
+            APPROVALS_VERSION, self.rule, self.by, self.date, self.digest
+        )
+    }
+
+    /// Check this approval's signature against `allowed_signers`.
+    fn verify(&self, allowed_signers: &Path) -> Result<()> {
+        let Some(signature) = &self.signature else {
+            eyre::bail!("it is not signed");
+        };
+        let signers = Signers {
+            allowed_signers,
+            identity: &self.by,
+        };
+        crate::bundle::check_signature(
+            self.signed_text().as_bytes(),
+            signature.as_bytes(),
+            &signers,
+            SIGNATURE_NAMESPACE,
+        )
+    }
+}
+
+/// Digests of the signatures checked so far, with the allowed signers they
+/// were checked against, so rebuilds don't run `ssh-keygen` for every
+/// approval again.
+static VERIFIED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Where a project's approvals live.
+pub fn approvals_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".tracey").join("approvals")
+}
+
+fn approval_path(project_root: &Path, base: &str) -> PathBuf {
+    approvals_dir(project_root).join(format!("{}.json", base))
+}
+
+/// Digest of a rule's text, tying an approval to exactly what was reviewed.
+pub fn text_digest(raw: &str) -> String {
+    blake3::hash(raw.trim().as_bytes()).to_hex().to_string()
+}
+
+impl ApprovalFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let file: Self = facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid approvals {}: {}", path.display(), e))?;
+        if file.version != APPROVALS_VERSION {
+            eyre::bail!(
+                "Approvals {} have version {}, expected {}",
+                path.display(),
+                file.version,
+                APPROVALS_VERSION
+            );
+        }
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = facet_json::to_string_pretty(self).expect("JSON serialization failed");
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Every approval in a project, by rule base ID.
+#[derive(Debug, Default)]
+pub struct Approvals {
+    by_base: HashMap<String, Vec<Approval>>,
+}
+
+impl Approvals {
+    /// Load every file in [`approvals_dir`], keeping the approvals whose
+    /// signature checks out against [`ALLOWED_SIGNERS`]; a project without
+    /// either has no approvals.
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let dir = approvals_dir(project_root);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {}", dir.display())),
+        };
+        let mut by_base = HashMap::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(base) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            by_base.insert(base, ApprovalFile::load(&path)?.approvals);
+        }
+
+        let allowed_signers = project_root.join(ALLOWED_SIGNERS);
+        let signers_digest = std::fs::read(&allowed_signers)
+            .map(|content| blake3::hash(&content).to_hex().to_string())
+            .unwrap_or_default();
+        for approvals in by_base.values_mut() {
+            approvals.retain(|approval| {
+                let key = blake3::hash(
+                    format!(
+                        "{}\n{}\n{}",
+                        signers_digest,
+                        approval.signed_text(),
+                        approval.signature.as_deref().unwrap_or("")
+                    )
+                    .as_bytes(),
+                )
+                .to_hex()
+                .to_string();
+                let mut verified = VERIFIED.lock().unwrap_or_else(|e| e.into_inner());
+                if verified.contains(&key) {
+                    return true;
+                }
+                match approval.verify(&allowed_signers) {
+                    Ok(()) => {
+                        verified.insert(key);
+                        true
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Ignoring approval of {} by {}: {}",
+                            approval.rule, approval.by, e
+                        );
+                        false
+                    }
+                }
+            });
+        }
+        Ok(Self { by_base })
+    }
+
+    /// Approvals of exactly this version and text of a rule.
+    pub fn of(&self, id: &RuleId, raw: &str) -> Vec<ApiApproval> {
+        let Some(approvals) = self.by_base.get(&id.base) else {
+            return vec![];
+        };
+        let rule = id.to_string();
+        let digest = text_digest(raw);
+        approvals
+            .iter()
+            .filter(|a| a.rule == rule && a.digest == digest)
+            .map(|a| ApiApproval {
+                by: a.by.clone(),
+                date: a.date.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The `user.name <user.email>` git identity, if configured.
+fn git_identity(project_root: &Path) -> Option<String> {
+    let get = |key: &str| {
+        crate::bump::git_capture(project_root, &["config", key])
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    match (get("user.name"), get("user.email")) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (name, email) => name.or(email),
+    }
+}
+
+/// The SSH key git signs with (`user.signingkey`), if it names a key file.
+pub fn git_signing_key(project_root: &Path) -> Option<PathBuf> {
+    crate::bump::git_capture(project_root, &["config", "user.signingkey"])
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty() && !value.starts_with("key::"))
+        .map(PathBuf::from)
+}
+
+/// Record `by`'s approval of `rule_id`, which must be the current version of
+/// a rule in the spec files on disk, signed with the SSH private key at
+/// `key`. Approving the same version and text twice records nothing new.
+/// Returns the record and the file it's in.
+pub async fn approve(
+    project_root: &Path,
+    config: &Config,
+    rule_id: &RuleId,
+    by: &str,
+    key: &Path,
+) -> Result<(Approval, PathBuf)> {
+    let Some((_, req)) = crate::bump::find_spec_rule(project_root, config, &rule_id.base).await?
+    else {
+        eyre::bail!("Unknown rule {}", rule_id.base);
+    };
+    if req.id.version != rule_id.version {
+        eyre::bail!(
+            "{} is the current version of this rule, not {}; approvals are for the current text",
+            req.id,
+            rule_id
+        );
+    }
+
+    let path = approval_path(project_root, &rule_id.base);
+    let mut file = if path.exists() {
+        ApprovalFile::load(&path)?
+    } else {
+        ApprovalFile {
+            version: APPROVALS_VERSION,
+            approvals: vec![],
+        }
+    };
+    let mut approval = Approval {
+        rule: rule_id.to_string(),
+        by: by.to_string(),
+        date: crate::changelog::today(),
+        digest: text_digest(&req.raw),
+        recorded_by: git_identity(project_root),
+        signature: None,
+    };
+    if let Some(existing) = file.approvals.iter().find(|a| {
+        a.rule == approval.rule
+            && a.digest == approval.digest
+            && a.by == by
+            && a.signature.is_some()
+    }) {
+        return Ok((existing.clone(), path));
+    }
+    let signature = crate::bundle::sign(&approval.signed_text(), key, SIGNATURE_NAMESPACE)?;
+    approval.signature = Some(String::from_utf8_lossy(&signature).into_owned());
+    file.approvals.push(approval.clone());
+    file.save(&path)?;
+    Ok((approval, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::parse_rule_id;
+
+    /// An SSH key in `dir`, allowed to sign approvals as each of `names`.
+    fn signing_key(dir: &Path, names: &[&str]) -> PathBuf {
+        let key = dir.join("id_ed25519");
+        let status = std::process::Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status()
+            .expect("ssh-keygen not found");
+        assert!(status.success());
+        let public = std::fs::read_to_string(key.with_extension("pub")).unwrap();
+        let allowed: String = names
+            .iter()
+            .map(|name| format!("{} {}", name, public.trim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let path = dir.join(ALLOWED_SIGNERS);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, allowed + "\n").unwrap();
+        key
+    }
+
+    fn approval(rule: &str, by: &str, raw: &str, key: &Path) -> Approval {
+        let mut approval = Approval {
+            rule: rule.to_string(),
+            by: by.to_string(),
+            date: "2026-10-14".to_string(),
+            digest: text_digest(raw),
+            recorded_by: None,
+            signature: None,
+        };
+        let signature =
+            crate::bundle::sign(&approval.signed_text(), key, SIGNATURE_NAMESPACE).unwrap();
+        approval.signature = Some(String::from_utf8(signature).unwrap());
+        approval
+    }
+
+    #[test]
+    fn only_the_approved_version_and_text_count() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let key = signing_key(temp.path(), &["alice", "bob"]);
+        let file = ApprovalFile {
+            version: APPROVALS_VERSION,
+            approvals: vec![
+                approval("auth.login+2", "bob", "Users MUST log in.", &key),
+                approval("auth.login+3", "alice", "Users MUST log in with MFA.", &key),
+            ],
+        };
+        file.save(&approval_path(temp.path(), "auth.login"))
+            .unwrap();
+        let approvals = Approvals::load(temp.path()).unwrap();
+
+        let v3 = parse_rule_id("auth.login+3").unwrap();
+        let approved = approvals.of(&v3, "Users MUST log in with MFA.\n");
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].by, "alice");
+        assert!(
+            approvals
+                .of(&v3, "Users MUST log in with a token.")
+                .is_empty()
+        );
+        let v4 = parse_rule_id("auth.login+4").unwrap();
+        assert!(approvals.of(&v4, "Users MUST log in with MFA.").is_empty());
+        let other = parse_rule_id("auth.session").unwrap();
+        assert!(approvals.of(&other, "").is_empty());
+    }
+
+    #[test]
+    fn unsigned_forged_and_untrusted_approvals_do_not_count() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let key = signing_key(temp.path(), &["alice"]);
+        let raw = "Users MUST log in.";
+        let mut unsigned = approval("auth.login", "alice", raw, &key);
+        unsigned.signature = None;
+        let mut forged = approval("auth.login", "alice", raw, &key);
+        forged.date = "2020-01-01".to_string();
+        let untrusted = approval("auth.login", "mallory", raw, &key);
+        let file = ApprovalFile {
+            version: APPROVALS_VERSION,
+            approvals: vec![unsigned, forged, untrusted],
+        };
+        file.save(&approval_path(temp.path(), "auth.login"))
+            .unwrap();
+
+        let approvals = Approvals::load(temp.path()).unwrap();
+        let id = parse_rule_id("auth.login").unwrap();
+        assert!(approvals.of(&id, raw).is_empty());
+    }
+
+    #[test]
+    fn missing_directory_means_no_approvals() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let approvals = Approvals::load(temp.path()).unwrap();
+        let id = parse_rule_id("auth.login").unwrap();
+        assert!(approvals.of(&id, "Text.").is_empty());
+    }
+}
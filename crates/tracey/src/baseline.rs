@@ -0,0 +1,262 @@
+//! Coverage baselines for `tracey baseline record` and `tracey check --baseline`.
+//!
+//! r[impl cli.baseline.record]
+//!
+//! A baseline lists the debt a project has accepted: the rules each impl
+//! leaves uncovered and the stale references it still carries. Checking
+//! against it only fails on debt that isn't listed, so a legacy codebase can
+//! adopt tracey without fixing everything first, and re-recording after a
+//! cleanup ratchets the allowance down.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use tracey_api::ApiPolicyStatus;
+
+use crate::data::DashboardData;
+
+/// Version of the baseline file layout.
+pub const BASELINE_VERSION: u32 = 1;
+
+/// Accepted coverage debt, per spec/impl.
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Baseline {
+    pub version: u32,
+    pub impls: Vec<BaselineImpl>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BaselineImpl {
+    pub spec: String,
+    pub impl_name: String,
+    /// Base IDs of rules without implementation references
+    pub uncovered: Vec<String>,
+    pub stale: Vec<BaselineStaleRef>,
+}
+
+/// A stale reference, identified by file and the outdated rule ID it names
+/// so that unrelated edits moving it to another line don't matter.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct BaselineStaleRef {
+    pub file: String,
+    pub reference: String,
+}
+
+/// Result of comparing current coverage to a baseline.
+#[derive(Debug, Default)]
+pub struct BaselineComparison {
+    /// Debt that isn't in the baseline, per spec
+    pub regressions: Vec<(String, ApiPolicyStatus)>,
+    /// Baseline entries that have been paid off since it was recorded
+    pub resolved: usize,
+}
+
+/// Where a project's baseline lives, next to its config so it gets committed.
+pub fn baseline_path(project_root: &Path) -> PathBuf {
+    project_root
+        .join(".config")
+        .join("tracey")
+        .join("baseline.json")
+}
+
+impl Baseline {
+    /// Capture the current debt of every spec/impl.
+    pub fn from_data(data: &DashboardData) -> Self {
+        let impls = data
+            .forward_by_impl
+            .iter()
+            .map(|((spec, impl_name), forward)| {
+                let uncovered: BTreeSet<String> = forward
+                    .rules
+                    .iter()
+                    .filter(|r| r.impl_refs.is_empty())
+                    .map(|r| r.id.base.clone())
+                    .collect();
+                let stale: BTreeSet<BaselineStaleRef> = forward
+                    .rules
+                    .iter()
+                    .flat_map(|r| &r.stale_refs)
+                    .map(|s| BaselineStaleRef {
+                        file: s.file.clone(),
+                        reference: s.reference_id.to_string(),
+                    })
+                    .collect();
+                BaselineImpl {
+                    spec: spec.clone(),
+                    impl_name: impl_name.clone(),
+                    uncovered: uncovered.into_iter().collect(),
+                    stale: stale.into_iter().collect(),
+                }
+            })
+            .collect();
+        Self {
+            version: BASELINE_VERSION,
+            impls,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).wrap_err_with(|| {
+            format!(
+                "Failed to read baseline {} (create it with `tracey baseline record`)",
+                path.display()
+            )
+        })?;
+        let baseline: Self = facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid baseline {}: {}", path.display(), e))?;
+        if baseline.version != BASELINE_VERSION {
+            eyre::bail!(
+                "Baseline {} has version {}, expected {}; run `tracey baseline record`",
+                path.display(),
+                baseline.version,
+                BASELINE_VERSION
+            );
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = facet_json::to_string_pretty(self).expect("JSON serialization failed");
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Compare `current` debt against this baseline.
+    ///
+    /// r[impl cli.check.baseline]
+    ///
+    /// Everything `current` has that the baseline doesn't is a regression;
+    /// spec/impls missing from the baseline count as having no accepted debt.
+    pub fn compare(&self, current: &Baseline) -> BaselineComparison {
+        let mut comparison = BaselineComparison::default();
+        let empty = BaselineImpl {
+            spec: String::new(),
+            impl_name: String::new(),
+            uncovered: vec![],
+            stale: vec![],
+        };
+
+        for now in &current.impls {
+            let before = self
+                .impls
+                .iter()
+                .find(|b| b.spec == now.spec && b.impl_name == now.impl_name)
+                .unwrap_or(&empty);
+
+            let mut violations = Vec::new();
+            for id in &now.uncovered {
+                if !before.uncovered.contains(id) {
+                    violations.push(format!("{} is uncovered and not in the baseline", id));
+                }
+            }
+            for stale in &now.stale {
+                if !before.stale.contains(stale) {
+                    violations.push(format!(
+                        "stale reference to {} in {} is not in the baseline",
+                        stale.reference, stale.file
+                    ));
+                }
+            }
+            comparison.resolved += before
+                .uncovered
+                .iter()
+                .filter(|id| !now.uncovered.contains(id))
+                .count();
+            comparison.resolved += before
+                .stale
+                .iter()
+                .filter(|stale| !now.stale.contains(stale))
+                .count();
+
+            if !violations.is_empty() {
+                comparison.regressions.push((
+                    now.spec.clone(),
+                    ApiPolicyStatus {
+                        impl_name: now.impl_name.clone(),
+                        passed: false,
+                        violations,
+                    },
+                ));
+            }
+        }
+
+        comparison
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(uncovered: &[&str], stale: &[(&str, &str)]) -> Baseline {
+        Baseline {
+            version: BASELINE_VERSION,
+            impls: vec![BaselineImpl {
+                spec: "spec".to_string(),
+                impl_name: "rust".to_string(),
+                uncovered: uncovered.iter().map(|s| s.to_string()).collect(),
+                stale: stale
+                    .iter()
+                    .map(|(file, reference)| BaselineStaleRef {
+                        file: file.to_string(),
+                        reference: reference.to_string(),
+                    })
+                    .collect(),
+            }],
+        }
+    }
+
+    // r[verify cli.check.baseline]
+    #[test]
+    fn only_new_debt_regresses() {
+        let recorded = baseline(
+            &["auth.login", "auth.logout"],
+            &[("src/a.rs", "auth.token")],
+        );
+
+        // Same debt, minus one paid-off rule: passes and counts the progress.
+        let comparison =
+            recorded.compare(&baseline(&["auth.login"], &[("src/a.rs", "auth.token")]));
+        assert!(comparison.regressions.is_empty());
+        assert_eq!(comparison.resolved, 1);
+
+        // A newly uncovered rule and a stale reference in another file regress.
+        let comparison = recorded.compare(&baseline(
+            &["auth.login", "auth.logout", "data.format"],
+            &[("src/a.rs", "auth.token"), ("src/b.rs", "auth.token")],
+        ));
+        let (spec, status) = &comparison.regressions[0];
+        assert_eq!(spec, "spec");
+        assert_eq!(
+            status.violations,
+            vec![
+                "data.format is uncovered and not in the baseline",
+                "stale reference to auth.token in src/b.rs is not in the baseline",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_impls_have_no_allowance() {
+        let comparison = Baseline::default().compare(&baseline(&["auth.login"], &[]));
+        assert_eq!(comparison.regressions.len(), 1);
+    }
+
+    // r[verify cli.baseline.record]
+    #[test]
+    fn round_trips_through_json() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = baseline_path(temp.path());
+        let recorded = baseline(&["auth.login"], &[("src/a.rs", "auth.token+1")]);
+        recorded.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), recorded);
+    }
+}
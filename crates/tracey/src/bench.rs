@@ -0,0 +1,287 @@
+//! Performance targets, synthetic projects and timings.
+//!
+//! r[impl cli.doctor.bench]
+//!
+//! A change that makes scanning quadratic only shows up on big projects,
+//! long after it was merged. `SyntheticProject` writes a project of a given
+//! size, the `tracey-bench` crate times builds and queries on it, and
+//! `tracey doctor --bench` times the same steps on a real project against
+//! the targets below.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use eyre::{Result, WrapErr};
+
+use crate::config::Config;
+use crate::data::{BuildCache, DashboardData, FileOverlay};
+use crate::server::QueryEngine;
+
+/// Upper bounds a project the size of [`SyntheticProject::MONOREPO`] must
+/// stay under; smaller projects have no excuse to miss them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PerfTargets {
+    /// Cold build with an empty cache
+    pub full_build: Duration,
+    /// Rebuild after one source file changed
+    pub incremental_rebuild: Duration,
+    /// `tracey query status` over the built data
+    pub query: Duration,
+}
+
+pub const TARGETS: PerfTargets = PerfTargets {
+    full_build: Duration::from_secs(30),
+    incremental_rebuild: Duration::from_secs(1),
+    query: Duration::from_millis(100),
+};
+
+/// Size of a generated project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticProject {
+    /// Source files, one in ten of them a test file
+    pub files: usize,
+    /// Rules, spread over spec files of 100 rules each
+    pub rules: usize,
+}
+
+impl SyntheticProject {
+    /// The scale the targets are set for.
+    pub const MONOREPO: Self = Self {
+        files: 50_000,
+        rules: 5_000,
+    };
+
+    /// Write the project's config, spec and sources under `root`.
+    ///
+    /// Source file `i` implements (or, for test files, verifies) rule
+    /// `i % rules` and has one unannotated function, so coverage is full
+    /// when `files >= rules` and every query has real work to do.
+    pub fn generate(&self, root: &Path) -> Result<()> {
+        let write = |path: &Path, content: &str| -> Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(path, content)
+                .wrap_err_with(|| format!("Failed to write {}", path.display()))
+        };
+
+        write(
+            &root.join(".config/tracey/config.styx"),
+            "\
+specs (
+  {
+    name bench
+    include (docs/spec/**/*.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs tests/**/*.rs)
+        test_include (tests/**/*.rs)
+      }
+    )
+  }
+)
+",
+        )?;
+
+        let rules = self.rules.max(1);
+        for area in 0..rules.div_ceil(100) {
+            let mut spec = format!("# Area {area}\n\n");
+            for rule in area * 100..((area + 1) * 100).min(rules) {
+                spec.push_str(&format!(
+                    "r[{}]\nComponent {rule} MUST handle its inputs.\n\n",
+                    rule_id(rule)
+                ));
+            }
+            write(&root.join(format!("docs/spec/area{area}.md")), &spec)?;
+        }
+
+        for file in 0..self.files {
+            let rule = rule_id(file % rules);
+            let dir = file / 500;
+            let (path, content) = if file % 10 == 9 {
+                (
+                    format!("tests/t{dir}/f{file}.rs"),
+                    format!(
+                        "\
+// r[verify {rule}]
+#[test]
+fn test_{file}() {{
+    assert_eq!(helper_{file}(), {file});
+}}
+
+fn helper_{file}() -> usize {{
+    {file}
+}}
+"
+                    ),
+                )
+            } else {
+                (
+                    format!("src/m{dir}/f{file}.rs"),
+                    format!(
+                        "\
+// r[impl {rule}]
+pub fn handle_{file}(input: usize) -> usize {{
+    input + helper_{file}()
+}}
+
+fn helper_{file}() -> usize {{
+    {file}
+}}
+"
+                    ),
+                )
+            };
+            write(&root.join(path), &content)?;
+        }
+        Ok(())
+    }
+}
+
+fn rule_id(rule: usize) -> String {
+    format!("bench.area{}.rule{}", rule / 100, rule)
+}
+
+/// Timings of one project.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Distinct source files scanned across impls
+    pub files: usize,
+    /// Rules across specs and impls
+    pub rules: usize,
+    pub full_build: Duration,
+    /// `None` if no impl has a source file to change
+    pub incremental_rebuild: Option<Duration>,
+    pub query: Duration,
+}
+
+impl BenchReport {
+    /// Whether every step stayed within `targets`.
+    pub fn meets(&self, targets: &PerfTargets) -> bool {
+        self.full_build <= targets.full_build
+            && self
+                .incremental_rebuild
+                .is_none_or(|t| t <= targets.incremental_rebuild)
+            && self.query <= targets.query
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |f: &mut fmt::Formatter<'_>, name: &str, took: Duration, target: Duration| {
+            let verdict = if took <= target { "ok" } else { "OVER TARGET" };
+            writeln!(
+                f,
+                "  {:<22}{:>10}  (target {:?}) {}",
+                name,
+                format!("{:.1?}", took),
+                target,
+                verdict
+            )
+        };
+        writeln!(f, "{} source files, {} rules", self.files, self.rules)?;
+        row(f, "Full build:", self.full_build, TARGETS.full_build)?;
+        match self.incremental_rebuild {
+            Some(took) => row(f, "Incremental rebuild:", took, TARGETS.incremental_rebuild)?,
+            None => writeln!(f, "  {:<22}{:>10}", "Incremental rebuild:", "n/a")?,
+        }
+        row(f, "Status query:", self.query, TARGETS.query)
+    }
+}
+
+/// Time a cold build of `project_root`, a rebuild after an in-memory edit
+/// of one of its source files, and a status query.
+pub async fn run(project_root: &Path, config: &Config) -> Result<BenchReport> {
+    let mut cache = BuildCache::default();
+    let start = Instant::now();
+    let data = build(project_root, config, &FileOverlay::new(), &mut cache, &[]).await?;
+    let full_build = start.elapsed();
+
+    // Edit a source file the way an editor would, through the overlay.
+    let edited = data
+        .reverse_by_impl
+        .values()
+        .flat_map(|reverse| reverse.files.first())
+        .next()
+        .map(|file| tracey_core::canonical_path(&project_root.join(&file.path)));
+    let incremental_rebuild = match edited {
+        Some(path) => {
+            let content = std::fs::read_to_string(&path)
+                .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+            let mut overlay = FileOverlay::new();
+            overlay.insert(path.clone(), content + "\n// edited\n");
+            let start = Instant::now();
+            build(project_root, config, &overlay, &mut cache, &[path]).await?;
+            Some(start.elapsed())
+        }
+        None => None,
+    };
+
+    let start = Instant::now();
+    let query = QueryEngine::new(&data);
+    std::hint::black_box((query.status(), query.status_by_rule()));
+    let query = start.elapsed();
+
+    let files: BTreeSet<&str> = data
+        .reverse_by_impl
+        .values()
+        .flat_map(|reverse| reverse.files.iter().map(|f| f.path.as_str()))
+        .collect();
+    Ok(BenchReport {
+        files: files.len(),
+        rules: data.forward_by_impl.values().map(|f| f.rules.len()).sum(),
+        full_build,
+        incremental_rebuild,
+        query,
+    })
+}
+
+async fn build(
+    project_root: &Path,
+    config: &Config,
+    overlay: &FileOverlay,
+    cache: &mut BuildCache,
+    changed_files: &[std::path::PathBuf],
+) -> Result<DashboardData> {
+    crate::data::build_dashboard_data_with_overlay_and_cache(
+        project_root,
+        config,
+        1,
+        true,
+        overlay,
+        cache,
+        changed_files,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.doctor.bench]
+    #[tokio::test]
+    async fn times_a_generated_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let project = SyntheticProject {
+            files: 40,
+            rules: 20,
+        };
+        project.generate(temp.path()).unwrap();
+        let config = crate::load_config(&temp.path().join(".config/tracey/config.styx")).unwrap();
+
+        let report = run(temp.path(), &config).await.unwrap();
+        assert_eq!(report.files, 40);
+        assert_eq!(report.rules, 20);
+        assert!(report.incremental_rebuild.is_some());
+        assert!(
+            report
+                .to_string()
+                .starts_with("40 source files, 20 rules\n")
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! `cargo tracey`: tracey for cargo workspaces.
+//!
+//! r[impl cli.cargo]
+//!
+//! Cargo runs this as `cargo-tracey tracey ARGS`. Everything but `members`
+//! is handed to the `tracey` binary, with the project root set to the cargo
+//! workspace root.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+
+use eyre::{Result, WrapErr, bail, eyre};
+use tracey::cargo::{Workspace, add_member_includes};
+
+const MEMBERS_USAGE: &str = "usage: cargo tracey members [--spec NAME] [--impl NAME] [--write]";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<ExitCode> {
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "tracey") {
+        args.remove(0);
+    }
+    let cwd = std::env::current_dir().wrap_err("Failed to get current directory")?;
+    let workspace = Workspace::load(&cwd)?;
+
+    if args.first().is_some_and(|arg| arg == "members") {
+        members(&workspace, &args[1..])?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let status = Command::new(tracey_binary())
+        .args(&args)
+        .env(tracey::PROJECT_ROOT_ENV, &workspace.root)
+        .status()
+        .wrap_err("Failed to run tracey")?;
+    Ok(ExitCode::from(
+        status.code().unwrap_or(1).clamp(0, 255) as u8
+    ))
+}
+
+/// The `tracey` installed next to this binary, else the one on `PATH`.
+fn tracey_binary() -> PathBuf {
+    let name = format!("tracey{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(&name))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// Print the include patterns for the workspace members, or with `--write`
+/// add the missing ones to an impl in the config.
+fn members(workspace: &Workspace, args: &[OsString]) -> Result<()> {
+    let mut spec = None;
+    let mut impl_name = None;
+    let mut write = false;
+    let mut args = args.iter().map(|arg| arg.to_string_lossy().into_owned());
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("{arg} needs a value; {MEMBERS_USAGE}"))
+        };
+        match arg.as_str() {
+            "--spec" => spec = Some(value()?),
+            "--impl" => impl_name = Some(value()?),
+            "--write" => write = true,
+            _ => bail!("Unknown argument {arg:?}; {MEMBERS_USAGE}"),
+        }
+    }
+
+    if !write {
+        for pattern in workspace.include_patterns() {
+            println!("{pattern}");
+        }
+        return Ok(());
+    }
+
+    let config_path = workspace.root.join(tracey::CONFIG_PATH);
+    let mut config = tracey::load_config(&config_path)?;
+    let added = add_member_includes(
+        &mut config,
+        workspace,
+        spec.as_deref(),
+        impl_name.as_deref(),
+    )?;
+    if added.is_empty() {
+        println!("Every workspace member is already included");
+        return Ok(());
+    }
+    tracey::save_config(&config_path, &config)?;
+    for pattern in &added {
+        println!("Added include pattern: {pattern}");
+    }
+    Ok(())
+}
@@ -0,0 +1,397 @@
+//! Find the commit that broke a rule's coverage, for `tracey bisect`.
+//!
+//! r[impl cli.bisect]
+//!
+//! Drives `git bisect` in a throwaway worktree so the user's checkout is
+//! never touched. At each step the model is rebuilt from the checked-out
+//! tree with that commit's own config, and the commit is marked good when
+//! the rule has current implementation references, bad when it has none or
+//! only stale ones, and skipped when the rule or a usable config is missing.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+use tracey_core::parse_rule_id;
+
+use crate::bump::git_capture;
+
+/// Give up after this many `git bisect` steps; a linear history of a
+/// million commits needs about 20.
+const MAX_STEPS: usize = 64;
+
+/// What a rule looks like for one impl at one commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleState {
+    /// Has current implementation references
+    Covered,
+    /// Has no implementation references
+    Uncovered,
+    /// Only has references to an older version of the rule
+    Stale,
+    /// The rule or the impl doesn't exist, or the model can't be built
+    Missing,
+}
+
+impl RuleState {
+    fn describe(self) -> &'static str {
+        match self {
+            RuleState::Covered => "covered",
+            RuleState::Uncovered => "uncovered",
+            RuleState::Stale => "stale",
+            RuleState::Missing => "missing",
+        }
+    }
+
+    fn bisect_term(self) -> &'static str {
+        match self {
+            RuleState::Covered => "good",
+            RuleState::Uncovered | RuleState::Stale => "bad",
+            RuleState::Missing => "skip",
+        }
+    }
+}
+
+/// The first commit at which the rule stopped being covered.
+#[derive(Debug)]
+pub struct BisectOutcome {
+    pub spec: String,
+    pub impl_name: String,
+    pub commit: String,
+    /// `git show -s` summary of the commit
+    pub summary: String,
+    /// `Uncovered` if a reference disappeared, `Stale` if the rule text
+    /// changed under its references
+    pub state: RuleState,
+}
+
+/// What to bisect, and between which commits.
+pub struct BisectRequest<'a> {
+    pub project_root: &'a Path,
+    /// Config path relative to the project root
+    pub config: &'a Path,
+    pub rule_id: &'a str,
+    pub spec: Option<&'a str>,
+    pub impl_name: Option<&'a str>,
+    /// Last known commit where the rule was covered (searched for if omitted)
+    pub good: Option<&'a str>,
+    pub bad: &'a str,
+}
+
+/// A detached worktree that's removed (along with its bisect state) on drop.
+struct Worktree {
+    repo: PathBuf,
+    path: PathBuf,
+    /// Private directory the worktree is in, removed after it
+    _dir: tempfile::TempDir,
+}
+
+impl Worktree {
+    fn add(repo: &Path, commit: &str) -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("tracey-bisect-")
+            .tempdir()
+            .wrap_err("Failed to create a temporary directory")?;
+        let path = dir.path().join("worktree");
+        let path_str = path.to_string_lossy().to_string();
+        git_capture(
+            repo,
+            &["worktree", "add", "--detach", "--quiet", &path_str, commit],
+        )?;
+        Ok(Self {
+            repo: repo.to_path_buf(),
+            path,
+            _dir: dir,
+        })
+    }
+
+    fn checkout(&self, commit: &str) -> Result<()> {
+        git_capture(&self.path, &["checkout", "--detach", "--quiet", commit])?;
+        Ok(())
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let _ = git_capture(&self.path, &["bisect", "reset", "--quiet"]);
+        let path_str = self.path.to_string_lossy().to_string();
+        let _ = git_capture(&self.repo, &["worktree", "remove", "--force", &path_str]);
+    }
+}
+
+/// Work out which impl, if the caller didn't say.
+fn resolve_impl(
+    data: &crate::data::DashboardData,
+    base: &str,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+) -> Result<(String, String)> {
+    let candidates: Vec<&(String, String)> = data
+        .forward_by_impl
+        .iter()
+        .filter(|((s, i), forward)| {
+            spec.is_none_or(|spec| spec == s)
+                && impl_name.is_none_or(|impl_name| impl_name == i)
+                && forward.rules.iter().any(|r| r.id.base == base)
+        })
+        .map(|(key, _)| key)
+        .collect();
+    match candidates.as_slice() {
+        [] => bail!("Rule {} is not defined in any matching spec/impl", base),
+        [key] => Ok((*key).clone()),
+        _ => bail!(
+            "Rule {} has several impls ({}); pick one with --spec_impl",
+            base,
+            candidates
+                .iter()
+                .map(|(s, i)| format!("{}/{}", s, i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// The rule's state in an already-built model.
+pub fn rule_state(
+    data: &crate::data::DashboardData,
+    spec: &str,
+    impl_name: &str,
+    base: &str,
+) -> RuleState {
+    let Some(rule) = data
+        .forward_by_impl
+        .get(&(spec.to_string(), impl_name.to_string()))
+        .and_then(|forward| forward.rules.iter().find(|r| r.id.base == base))
+    else {
+        return RuleState::Missing;
+    };
+    if rule.is_stale {
+        RuleState::Stale
+    } else if rule.impl_refs.is_empty() {
+        RuleState::Uncovered
+    } else {
+        RuleState::Covered
+    }
+}
+
+async fn build_at(root: &Path, config: &Path) -> Result<crate::data::DashboardData> {
+    let cfg = crate::load_config(&root.join(config))?;
+    crate::data::build_dashboard_data(root, &cfg, 0, true).await
+}
+
+async fn state_at(
+    root: &Path,
+    config: &Path,
+    spec: &str,
+    impl_name: &str,
+    base: &str,
+) -> RuleState {
+    match build_at(root, config).await {
+        Ok(data) => rule_state(&data, spec, impl_name, base),
+        Err(_) => RuleState::Missing,
+    }
+}
+
+fn short(repo: &Path, commit: &str) -> String {
+    git_capture(repo, &["rev-parse", "--short", commit])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| commit.to_string())
+}
+
+/// The commit `git bisect` reported as the first bad one, if it's done.
+fn first_bad_commit(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find(|line| line.ends_with(" is the first bad commit"))
+        .and_then(|line| line.split_whitespace().next())
+        .map(str::to_string)
+}
+
+pub async fn bisect(req: BisectRequest<'_>) -> Result<BisectOutcome> {
+    let Some(rule) = parse_rule_id(req.rule_id) else {
+        bail!("Invalid rule ID: {}", req.rule_id);
+    };
+    let base = rule.base.as_str();
+
+    let toplevel =
+        PathBuf::from(git_capture(req.project_root, &["rev-parse", "--show-toplevel"])?.trim());
+    let project_root = req
+        .project_root
+        .canonicalize()
+        .unwrap_or_else(|_| req.project_root.to_path_buf());
+    let subdir = project_root
+        .strip_prefix(toplevel.canonicalize().unwrap_or(toplevel.clone()))
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    let bad = git_capture(
+        &toplevel,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", req.bad)],
+    )?
+    .trim()
+    .to_string();
+
+    let worktree = Worktree::add(&toplevel, &bad)?;
+    let root = worktree.path.join(&subdir);
+
+    let bad_data = build_at(&root, req.config).await?;
+    let (spec, impl_name) = resolve_impl(&bad_data, base, req.spec, req.impl_name)?;
+    let bad_state = rule_state(&bad_data, &spec, &impl_name, base);
+    if bad_state == RuleState::Covered {
+        bail!(
+            "{} is covered in {}/{} at {}; nothing to bisect",
+            base,
+            spec,
+            impl_name,
+            short(&toplevel, &bad)
+        );
+    }
+    eprintln!(
+        "{}: {} ({})",
+        short(&toplevel, &bad),
+        bad_state.describe(),
+        req.bad
+    );
+
+    let good = match req.good {
+        Some(good) => {
+            let good = git_capture(
+                &toplevel,
+                &["rev-parse", "--verify", &format!("{}^{{commit}}", good)],
+            )?
+            .trim()
+            .to_string();
+            worktree.checkout(&good)?;
+            let state = state_at(&root, req.config, &spec, &impl_name, base).await;
+            if state != RuleState::Covered {
+                bail!(
+                    "{} is {} at {}, so it can't be the good commit",
+                    base,
+                    state.describe(),
+                    short(&toplevel, &good)
+                );
+            }
+            good
+        }
+        None => {
+            // Step back exponentially along first parents until it's covered.
+            let history = git_capture(&toplevel, &["rev-list", "--first-parent", &bad])?;
+            let history: Vec<&str> = history.lines().collect();
+            let never_covered = || {
+                eyre::eyre!(
+                    "{} was never covered in {}/{}; pass a known good commit with --good",
+                    base,
+                    spec,
+                    impl_name
+                )
+            };
+            let last = history.len().saturating_sub(1);
+            if last == 0 {
+                return Err(never_covered());
+            }
+            let mut step = 1;
+            loop {
+                let candidate = history[step.min(last)];
+                worktree.checkout(candidate)?;
+                let state = state_at(&root, req.config, &spec, &impl_name, base).await;
+                eprintln!("{}: {}", short(&toplevel, candidate), state.describe());
+                if state == RuleState::Covered {
+                    break candidate.to_string();
+                }
+                if step >= last {
+                    return Err(never_covered());
+                }
+                step *= 2;
+            }
+        }
+    };
+
+    let output = git_capture(&worktree.path, &["bisect", "start", &bad, &good])?;
+    let mut first_bad = first_bad_commit(&output);
+    for _ in 0..MAX_STEPS {
+        if first_bad.is_some() {
+            break;
+        }
+        let head = git_capture(&worktree.path, &["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        let state = state_at(&root, req.config, &spec, &impl_name, base).await;
+        eprintln!(
+            "{}: {} → {}",
+            short(&toplevel, &head),
+            state.describe(),
+            state.bisect_term()
+        );
+        let output = git_capture(&worktree.path, &["bisect", state.bisect_term()])?;
+        if output.contains("only 'skip'ped commits left") {
+            bail!("Only skipped commits are left to test:\n{}", output.trim());
+        }
+        first_bad = first_bad_commit(&output);
+    }
+    let Some(commit) = first_bad else {
+        bail!("git bisect didn't converge after {} steps", MAX_STEPS);
+    };
+
+    worktree.checkout(&commit)?;
+    let state = state_at(&root, req.config, &spec, &impl_name, base).await;
+    let summary = git_capture(
+        &toplevel,
+        &[
+            "show",
+            "-s",
+            "--format=%h %s (%an, %ad)",
+            "--date=short",
+            &commit,
+        ],
+    )?
+    .trim()
+    .to_string();
+
+    Ok(BisectOutcome {
+        spec,
+        impl_name,
+        commit,
+        summary,
+        state,
+    })
+}
+
+/// Describe the outcome for the terminal.
+pub fn format_outcome(rule_id: &str, outcome: &BisectOutcome) -> String {
+    let what = match outcome.state {
+        RuleState::Stale => "The rule text changed, leaving its references stale.",
+        _ => "Its last implementation reference disappeared.",
+    };
+    format!(
+        "{} stopped being covered in {}/{} at:\n\n  {}\n\n{}\n",
+        rule_id, outcome.spec, outcome.impl_name, outcome.summary, what
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_first_bad_commit_in_bisect_output() {
+        let output = "\
+3f2a1c9d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f is the first bad commit
+commit 3f2a1c9d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f
+Author: Someone <someone@example.com>
+";
+        assert_eq!(
+            first_bad_commit(output).as_deref(),
+            Some("3f2a1c9d0e8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f")
+        );
+        assert_eq!(
+            first_bad_commit("Bisecting: 3 revisions left to test after this (roughly 2 steps)"),
+            None
+        );
+    }
+
+    #[test]
+    fn states_map_to_bisect_terms() {
+        assert_eq!(RuleState::Covered.bisect_term(), "good");
+        assert_eq!(RuleState::Uncovered.bisect_term(), "bad");
+        assert_eq!(RuleState::Stale.bisect_term(), "bad");
+        assert_eq!(RuleState::Missing.bisect_term(), "skip");
+    }
+}
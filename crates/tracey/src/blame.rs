@@ -0,0 +1,139 @@
+//! `git blame` for rule definitions and references.
+//!
+//! r[impl daemon.cli.query.rule-blame]
+//!
+//! Used by `tracey query rule --blame` to show who last edited a rule's text
+//! and who wrote each reference to it, and by `tracey query dead` to date
+//! rule definitions.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The commit that last touched one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub commit: String,
+    pub author: String,
+    pub author_mail: String,
+    /// Author time in Unix seconds
+    pub author_time: i64,
+    pub summary: String,
+}
+
+/// Blame every line of `file` (1-indexed). Uncommitted lines are left out.
+pub fn blame_file(project_root: &Path, file: &Path) -> eyre::Result<HashMap<usize, BlameLine>> {
+    let file = file.to_string_lossy();
+    let output =
+        crate::bump::git_capture(project_root, &["blame", "--line-porcelain", "--", &file])?;
+    Ok(parse_line_porcelain(&output))
+}
+
+/// Parse `git blame --line-porcelain` output.
+pub fn parse_line_porcelain(output: &str) -> HashMap<usize, BlameLine> {
+    let mut lines = HashMap::new();
+    let mut line: Option<usize> = None;
+    let mut current = BlameLine {
+        commit: String::new(),
+        author: String::new(),
+        author_mail: String::new(),
+        author_time: 0,
+        summary: String::new(),
+    };
+    let mut has_time = false;
+
+    for entry in output.lines() {
+        if entry.starts_with('\t') {
+            let uncommitted = current.commit.chars().all(|c| c == '0');
+            if let (Some(line), true, false) = (line, has_time, uncommitted) {
+                lines.insert(line, current.clone());
+            }
+            line = None;
+            has_time = false;
+            current.summary.clear();
+            continue;
+        }
+        if let Some(author) = entry.strip_prefix("author ") {
+            current.author = author.to_string();
+            continue;
+        }
+        if let Some(mail) = entry.strip_prefix("author-mail ") {
+            current.author_mail = mail.trim_matches(['<', '>']).to_string();
+            continue;
+        }
+        if let Some(time) = entry.strip_prefix("author-time ") {
+            if let Ok(time) = time.trim().parse() {
+                current.author_time = time;
+                has_time = true;
+            }
+            continue;
+        }
+        if let Some(summary) = entry.strip_prefix("summary ") {
+            current.summary = summary.to_string();
+            continue;
+        }
+        // Header: "<sha> <orig-line> <final-line> [<group-size>]"
+        let mut fields = entry.split(' ');
+        if let (Some(sha), Some(_), Some(final_line)) =
+            (fields.next(), fields.next(), fields.next())
+            && sha.len() >= 40
+            && sha.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            line = final_line.parse().ok();
+            current.commit = sha.to_string();
+        }
+    }
+    lines
+}
+
+/// The most recent commit among `first..=last` in a blamed file.
+pub fn latest_in_range(
+    lines: &HashMap<usize, BlameLine>,
+    first: usize,
+    last: usize,
+) -> Option<&BlameLine> {
+    (first..=last)
+        .filter_map(|line| lines.get(&line))
+        .max_by_key(|blame| blame.author_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.cli.query.rule-blame]
+    #[test]
+    fn picks_latest_commit_in_rule_span() {
+        let output = "\
+1111111111111111111111111111111111111111 1 1 1
+author Alice
+author-mail <alice@example.com>
+author-time 1700000000
+summary Add spec
+filename docs/spec.md
+\tr[auth.login]
+2222222222222222222222222222222222222222 2 2 1
+author Bob
+author-mail <bob@example.com>
+author-time 1710000000
+summary Tighten login rule
+filename docs/spec.md
+\tUsers MUST log in.
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-mail <not.committed.yet>
+author-time 1800000000
+summary Version of docs/spec.md from docs/spec.md
+filename docs/spec.md
+\tWork in progress.
+";
+        let lines = parse_line_porcelain(output);
+        assert_eq!(lines.len(), 2, "uncommitted lines are left out");
+        assert_eq!(lines[&1].author, "Alice");
+        assert_eq!(lines[&1].author_mail, "alice@example.com");
+
+        let latest = latest_in_range(&lines, 1, 3).unwrap();
+        assert_eq!(latest.author, "Bob");
+        assert_eq!(latest.summary, "Tighten login rule");
+        assert_eq!(latest_in_range(&lines, 3, 5), None);
+    }
+}
@@ -59,7 +59,7 @@ export interface RuleId {
 /**
  * Error codes for validation errors
  */
-export type ValidationErrorCode = "circular_dependency" | "invalid_naming" | "unknown_requirement" | "stale_requirement" | "duplicate_requirement" | "unknown_prefix" | "impl_in_test_file";
+export type ValidationErrorCode = "circular_dependency" | "invalid_naming" | "unknown_requirement" | "stale_requirement" | "renamed_requirement" | "removed_requirement" | "duplicate_requirement" | "unknown_prefix" | "impl_in_test_file" | "duplicate_reference" | "plugin_error" | "plugin_warning" | "skipped_file";
 
 /**
  * Validation results for a spec/implementation pair
@@ -179,6 +179,38 @@ export interface ApiCodeUnit {
   ruleRefs: string[];
 }
 
+/**
+ * A requirement reference in a file, resolved against the current spec
+ */
+export interface ApiFileReference {
+  /**
+   * `impl`, `verify`, `depends`, ...
+   */
+  verb: string;
+  /**
+   * The rule ID as written, with the version it names
+   */
+  id: RuleId;
+  line: number;
+  /**
+   * Byte offset of the reference in the file
+   */
+  offset: number;
+  /**
+   * Byte length of the reference
+   */
+  length: number;
+  /**
+   * `ok`, `stale` (names an older version), `renamed` (names an ID the
+   * rule was renamed from) or `orphaned` (names no rule)
+   */
+  status: string;
+  /**
+   * The rule's current ID, for stale and renamed references
+   */
+  current?: RuleId;
+}
+
 /**
  * Single file with full coverage details
  */
@@ -193,6 +225,10 @@ export interface ApiFileData {
    * Code units in this file with their coverage
    */
   units: ApiCodeUnit[];
+  /**
+   * References in this file to the spec's rules, with how each resolves
+   */
+  references?: ApiFileReference[];
 }
 
 export interface ApiFileEntry {
@@ -242,9 +278,17 @@ export interface ApiRule {
   html: string;
   status?: string;
   level?: string;
+  /**
+   * Tags from the marker's metadata (`tags=a,b`)
+   */
+  tags?: string[];
   sourceFile?: string;
   sourceLine?: number;
   sourceColumn?: number;
+  /**
+   * Link to the definition, built from the spec's `source_url`
+   */
+  permalink?: string;
   /**
    * Section slug (heading ID) that this rule belongs to
    */
@@ -253,6 +297,18 @@ export interface ApiRule {
    * Section title (heading text) that this rule belongs to
    */
   sectionTitle?: string;
+  /**
+   * IDs this rule was previously known by (`r[new.id <- old.id]`)
+   */
+  renamedFrom?: RuleId[];
+  /**
+   * Rules of other specs this rule's text depends on
+   */
+  dependsOn?: ApiSpecDependency[];
+  /**
+   * Higher-level rules of the same spec this rule refines (`r[refines sys.auth]`)
+   */
+  refines?: RuleId[];
   implRefs: ApiCodeRef[];
   verifyRefs: ApiCodeRef[];
   dependsRefs: ApiCodeRef[];
@@ -265,6 +321,78 @@ export interface ApiRule {
    * Stale references pointing to older versions of this rule.
    */
   staleRefs?: ApiStaleRef[];
+  /**
+   * Teams or people responsible for this rule, from the config's `owners`
+   */
+  owners?: string[];
+  /**
+   * Reviewer approvals of this version of the rule's text
+   */
+  approvals?: ApiApproval[];
+  /**
+   * How the tests behind verifyRefs did in the last ingested test report
+   */
+  testResults?: ApiTestResult[];
+  /**
+   * How much of the code behind implRefs ran, per the last ingested line
+   * coverage report
+   */
+  lineCoverage?: ApiLineCoverage[];
+}
+
+/**
+ * A rule of another spec that a rule depends on (`r[depends other:api.fetch]`)
+ */
+export interface ApiSpecDependency {
+  /**
+   * Name of the spec defining the rule
+   */
+  spec: string;
+  id: RuleId;
+}
+
+/**
+ * Executed lines of the code unit an impl reference annotates
+ */
+export interface ApiLineCoverage {
+  file: string;
+  line: number;
+  /**
+   * Name of the code unit, if it has one
+   */
+  unit?: string;
+  executedLines: number;
+  /**
+   * Lines of the unit the report has data for
+   */
+  totalLines: number;
+}
+
+/**
+ * The test a verify reference is in, and its outcome in the last ingested
+ * test report
+ */
+export interface ApiTestResult {
+  file: string;
+  line: number;
+  /**
+   * Name of the function the reference is in, if it's in one
+   */
+  test?: string;
+  outcome: TestOutcome;
+}
+
+export type TestOutcome = "passed" | "failed" | "skipped" | "not_run";
+
+/**
+ * A reviewer's sign-off on one version of a rule, from `tracey approve`
+ */
+export interface ApiApproval {
+  by: string;
+  /**
+   * Date of the approval (YYYY-MM-DD)
+   */
+  date: string;
 }
 
 /**
@@ -291,6 +419,18 @@ export interface ApiForwardData {
   specs: ApiSpecForward[];
 }
 
+/**
+ * Result of checking a spec/impl pair against its coverage policy
+ */
+export interface ApiPolicyStatus {
+  implName: string;
+  passed: boolean;
+  /**
+   * Human-readable description of each failed check
+   */
+  violations: string[];
+}
+
 export interface ApiSpecInfo {
   name: string;
   /**
@@ -310,6 +450,39 @@ export interface ApiSpecInfo {
    * Available implementations for this spec
    */
   implementations: string[];
+  /**
+   * Policy results for implementations that have a coverage policy
+   */
+  policies?: ApiPolicyStatus[];
+  /**
+   * Implementations that set `no_default_excludes`
+   */
+  noDefaultExcludes?: string[];
+  /**
+   * Code units each implementation leaves out of unmapped coverage
+   */
+  skippedUnits?: ApiSkippedUnits[];
+  /**
+   * Platform or feature-flag variants declared by the implementations
+   */
+  variants?: ApiImplVariant[];
+}
+
+/**
+ * The `skip_units` rules of an implementation, described for display
+ */
+export interface ApiSkippedUnits {
+  implName: string;
+  rules: string[];
+}
+
+/**
+ * A variant of an implementation and the files only compiled for it
+ */
+export interface ApiImplVariant {
+  implName: string;
+  name: string;
+  include: string[];
 }
 
 /**
@@ -318,6 +491,10 @@ export interface ApiSpecInfo {
 export interface ApiConfig {
   projectRoot: string;
   specs: ApiSpecInfo[];
+  /**
+   * Exclude patterns applied to every impl that doesn't opt out
+   */
+  defaultExcludes?: string[];
 }
 
 /**
@@ -1,11 +1,13 @@
 // Custom hooks
 import { useCallback, useEffect, useState } from "preact/hooks";
+import { takePrefetchedFile } from "./router";
 import type {
   ApiData,
   Config,
   FileContent,
   ForwardData,
   HealthData,
+  RecentData,
   ReverseData,
   SpecContent,
 } from "./types";
@@ -210,16 +212,22 @@ export function useApi(): UseApiResult {
   return { data, error, version, configError, refetch: fetchData };
 }
 
-export function useFile(path: string | null): FileContent | null {
+export function useFile(path: string | null, version: string | null): FileContent | null {
   const [file, setFile] = useState<FileContent | null>(null);
+  // Get spec/impl from URL for API call
+  const { spec, impl } = getImplFromUrl();
 
   useEffect(() => {
     if (!path) {
       setFile(null);
       return;
     }
-    // Get spec/impl from URL for API call
-    const { spec, impl } = getImplFromUrl();
+    // A /file/ permalink's page comes with the file
+    const prefetched = takePrefetchedFile(path, spec, impl, version);
+    if (prefetched) {
+      setFile(prefetched);
+      return;
+    }
     const params = new URLSearchParams();
     params.set("path", path);
     if (spec) params.set("spec", spec);
@@ -231,7 +239,7 @@ export function useFile(path: string | null): FileContent | null {
         console.error("Failed to load file:", e);
         setFile(null);
       });
-  }, [path]);
+  }, [path, spec, impl, version]);
 
   return file;
 }
@@ -260,3 +268,18 @@ export function useSpec(name: string | null, version: string | null): SpecConten
 
   return spec;
 }
+
+// Without a coverage history there is no feed, so errors just hide it
+export function useRecent(since: string, version: string | null): RecentData | null {
+  const [recent, setRecent] = useState<RecentData | null>(null);
+
+  useEffect(() => {
+    const params = new URLSearchParams();
+    params.set("since", since);
+    fetchJson<RecentData>(`/api/recent?${params.toString()}`)
+      .then(setRecent)
+      .catch(() => setRecent(null));
+  }, [since, version]);
+
+  return recent;
+}
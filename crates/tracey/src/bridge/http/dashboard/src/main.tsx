@@ -8,7 +8,7 @@ import { getDeviconClass, modKey, TAB_ICON_NAMES } from "./config";
 
 // Modules
 import { type UseApiResult, useApi } from "./hooks";
-import { buildUrl } from "./router";
+import { buildUrl, resolvePermalink } from "./router";
 // Types
 import type {
   ButtonProps,
@@ -552,7 +552,8 @@ function FilePath({
 }
 
 // File reference component
-function FileRef({ file, line, type, onSelectFile }: FileRefProps) {
+// r[impl cli.test-report]
+function FileRef({ file, line, type, outcome, onSelectFile }: FileRefProps) {
   return html`
     <div class="ref-line">
       <${FilePath}
@@ -561,6 +562,7 @@ function FileRef({ file, line, type, onSelectFile }: FileRefProps) {
         type=${type}
         onClick=${() => onSelectFile(file, line)}
       />
+      ${outcome && html`<span class="test-outcome ${outcome}">${outcome.replace("_", " ")}</span>`}
     </div>
   `;
 }
@@ -936,7 +938,7 @@ function SpecViewRoute() {
 function SourcesViewRoute() {
   const { params, query } = useRoute();
   const { route } = useLocation();
-  const { data } = useApiContext();
+  const { data, version } = useApiContext();
 
   if (!data) return html`<div class="loading">Loading...</div>`;
 
@@ -990,6 +992,7 @@ function SourcesViewRoute() {
       data=${reverse}
       forward=${forward}
       config=${config}
+      version=${version}
       search=${search}
       selectedFile=${file}
       selectedLine=${line}
@@ -1005,7 +1008,7 @@ function SourcesViewRoute() {
 function CoverageViewRoute() {
   const { params, query } = useRoute();
   const { route } = useLocation();
-  const { data } = useApiContext();
+  const { data, version } = useApiContext();
 
   if (!data) return html`<div class="loading">Loading...</div>`;
 
@@ -1049,6 +1052,9 @@ function CoverageViewRoute() {
     <${CoverageView}
       data=${forward}
       config=${config}
+      version=${version}
+      spec=${spec}
+      impl=${impl}
       search=${search}
       onSearchChange=${setSearch}
       level=${level}
@@ -1065,14 +1071,24 @@ function CoverageViewRoute() {
 // Mount
 // ========================================================================
 
-render(
-  html`
-    <${LocationProvider}>
-      <${App} />
-    <//>
-  `,
-  document.getElementById("app")!,
-);
+// Permalinks become their dashboard URL first, so the app only ever sees
+// /:spec/:impl/:view
+resolvePermalink()
+  .catch((e) => {
+    console.error("Failed to resolve permalink:", e);
+    return null;
+  })
+  .then((url) => {
+    if (url) history.replaceState(null, "", url);
+    render(
+      html`
+        <${LocationProvider}>
+          <${App} />
+        <//>
+      `,
+      document.getElementById("app")!,
+    );
+  });
 
 // Global keyboard shortcuts
 document.addEventListener("keydown", (e) => {
@@ -8,10 +8,14 @@
 //   /rapace/rust/sources/src/lib.rs:42    -> sources view, file + line
 //   /rapace/rust/coverage                 -> coverage view
 //   /rapace/rust/coverage?filter=impl     -> coverage view with filter
+//
+// Permalinks, resolved to one of the above before the app mounts:
+//   /rule/channel.id.allocation           -> spec view of an impl covering the rule
+//   /file/src/lib.rs#L42                  -> sources view, file + line
 
 export { LocationProvider, Route, Router, useLocation, useRoute } from "preact-iso";
 
-import type { ViewType } from "./types";
+import type { Config, FileContent, Prefetch, ViewType } from "./types";
 
 export interface UrlParams {
   file?: string | null;
@@ -65,3 +69,73 @@ export function buildUrl(
   const query = searchParams.toString();
   return `${base}/coverage${query ? `?${query}` : ""}`;
 }
+
+let prefetch: Prefetch | null | undefined;
+
+// The data the server embedded in a permalink's page, if any
+export function readPrefetch(): Prefetch | null {
+  if (prefetch === undefined) {
+    const script = document.getElementById("tracey-prefetch");
+    prefetch = script?.textContent ? (JSON.parse(script.textContent) as Prefetch) : null;
+  }
+  return prefetch;
+}
+
+// The file a /file/ permalink's page came with, if it's `path` for this
+// spec/impl and the data hasn't changed since. It's handed out once.
+export function takePrefetchedFile(
+  path: string,
+  spec: string | null,
+  impl: string | null,
+  version: string | null,
+): FileContent | null {
+  const target = readPrefetch();
+  const file = target?.file;
+  if (!target || !file) return null;
+  const matches = file.path === path && target.spec === spec && target.impl === impl;
+  // Until the live version arrives, the page's own data is the newest
+  if (matches && version === null) return file;
+  target.file = null;
+  return matches && String(target.version) === version ? file : null;
+}
+
+// r[impl dashboard.url.permalink]
+// The dashboard URL a /rule/ or /file/ permalink stands for, null for any
+// other URL or a rule that doesn't exist. In dev mode there's no prefetched
+// data, so it's fetched here.
+export async function resolvePermalink(): Promise<string | null> {
+  const { pathname, search, hash } = window.location;
+  const ruleMatch = pathname.match(/^\/rule\/([^/]+)$/);
+  const fileMatch = pathname.match(/^\/file\/(.+)$/);
+  if (!ruleMatch && !fileMatch) return null;
+
+  let target = readPrefetch();
+  if (!target) {
+    const config: Config = await fetch("/api/config").then((r) => r.json());
+    const params = new URLSearchParams(search);
+    const spec = params.get("spec") || config.specs?.[0]?.name || "";
+    const specInfo = config.specs?.find((s) => s.name === spec);
+    const impl = params.get("impl") || specInfo?.implementations?.[0] || "";
+    target = { spec, impl };
+    if (ruleMatch) {
+      const res = await fetch(`/api/rule?id=${encodeURIComponent(ruleMatch[1])}`);
+      if (res.ok) {
+        const rule = await res.json();
+        const covering = rule.coverage?.[0];
+        target = covering
+          ? { spec: covering.spec, impl: covering.implName, rule }
+          : { ...target, rule };
+      }
+    }
+  }
+
+  if (ruleMatch) {
+    if (!target.rule) return null;
+    return buildUrl(target.spec, target.impl, "spec", { rule: target.rule.id.base });
+  }
+  const line = hash.match(/^#L(\d+)$/);
+  return buildUrl(target.spec, target.impl, "sources", {
+    file: decodeURIComponent(fileMatch![1]),
+    line: line ? parseInt(line[1], 10) : null,
+  });
+}
@@ -8,7 +8,9 @@ import type {
   ApiSpecData,
   OutlineCoverage,
   OutlineEntry,
+  RuleId,
   SpecSection,
+  TestOutcome,
 } from "./api-types";
 import type { ComponentChildren } from "preact";
 
@@ -33,6 +35,33 @@ export interface HealthData {
   uptimeSecs: number;
 }
 
+// Rules changed since a history snapshot (GET /api/recent)
+export type RecentChange = "added" | "removed" | "changed" | "coverage";
+
+export interface RecentCoverage {
+  implName: string;
+  wasImplemented: boolean;
+  implemented: boolean;
+  wasVerified: boolean;
+  verified: boolean;
+}
+
+export interface RecentRule {
+  spec: string;
+  id: string;
+  previousId?: string | null;
+  change: RecentChange;
+  coverage: RecentCoverage[];
+}
+
+export interface RecentData {
+  since: string;
+  baselineVersion: number;
+  baselineRecordedAt: number;
+  latestVersion: number;
+  rules: RecentRule[];
+}
+
 // Route types
 export type ViewType = "sources" | "spec" | "coverage";
 
@@ -63,6 +92,16 @@ export interface CoverageRoute {
 
 export type Route = SourcesRoute | SpecRoute | CoverageRoute;
 
+// What a /rule/ or /file/ permalink points at, embedded by the server
+export interface Prefetch {
+  spec: string;
+  impl: string;
+  // Data version the page was rendered at
+  version: number;
+  rule?: { id: RuleId } | null;
+  file?: FileContent | null;
+}
+
 // API data types
 export interface FileRef {
   file: string;
@@ -107,6 +146,8 @@ export interface SearchResult {
   content: string;
   highlighted: string;
   score: number;
+  sourceFile?: string;
+  sourceLine?: number;
 }
 
 export interface SearchResults {
@@ -210,6 +251,9 @@ export interface LucideIconProps {
 export interface CoverageViewProps {
   data: ForwardData;
   config: Config;
+  version: string | null;
+  spec: string;
+  impl: string;
   search: string;
   onSearchChange: (search: string) => void;
   level: string;
@@ -224,6 +268,7 @@ export interface SourcesViewProps {
   data: ReverseData;
   forward: ForwardData;
   config: Config;
+  version: string | null;
   search: string;
   selectedFile: string | null;
   selectedLine: number | null;
@@ -259,6 +304,8 @@ export interface FileRefProps {
   file: string;
   line: number;
   type: "impl" | "verify";
+  /** Outcome of the reference's test in the last ingested test report */
+  outcome?: TestOutcome;
   onSelectFile: (path: string, line?: number | null) => void;
 }
 
@@ -1,7 +1,8 @@
 import { useCallback, useEffect, useMemo, useState } from "preact/hooks";
 import { LEVELS } from "../config";
+import { useRecent } from "../hooks";
 import { FileRef, html } from "../main";
-import type { CoverageViewProps } from "../types";
+import type { CoverageViewProps, RecentCoverage } from "../types";
 import { getStatClass, ruleIdToString } from "../utils";
 
 // r[impl dashboard.coverage.table]
@@ -10,8 +11,13 @@ import { getStatClass, ruleIdToString } from "../utils";
 // r[impl dashboard.coverage.stats]
 // r[impl dashboard.coverage.req-links]
 // r[impl dashboard.coverage.ref-links]
+// r[impl dashboard.coverage.by-file]
 export function CoverageView({
 	data,
+	config,
+	version,
+	spec,
+	impl,
 	search,
 	level,
 	onLevelChange,
@@ -21,6 +27,7 @@ export function CoverageView({
 	onSelectFile,
 }: CoverageViewProps) {
 	const [levelOpen, setLevelOpen] = useState(false);
+	const [fileFilter, setFileFilter] = useState<string | null>(null);
 
 	// Close dropdowns when clicking outside
 	useEffect(() => {
@@ -63,6 +70,11 @@ export function CoverageView({
 			rules = rules.filter((r) => inferLevel(r) === level);
 		}
 
+		// Filter by spec file
+		if (fileFilter) {
+			rules = rules.filter((r) => r.sourceFile === fileFilter);
+		}
+
 		// Filter by coverage
 		if (filter === "impl") {
 			rules = rules.filter((r) => r.implRefs.length === 0);
@@ -81,7 +93,7 @@ export function CoverageView({
 		}
 
 		return rules;
-	}, [allRules, search, level, filter, inferLevel]);
+	}, [allRules, search, level, filter, fileFilter, inferLevel]);
 
 	const stats = useMemo(() => {
 		let rules = allRules;
@@ -100,6 +112,45 @@ export function CoverageView({
 		};
 	}, [allRules, level, inferLevel]);
 
+	// Coverage per spec file, only worth showing when the spec spans several
+	const fileStats = useMemo(() => {
+		const byFile = new Map<string, { total: number; impl: number; verify: number }>();
+		for (const r of allRules) {
+			if (!r.sourceFile) continue;
+			if (level !== "all" && inferLevel(r) !== level) continue;
+			const entry = byFile.get(r.sourceFile) ?? { total: 0, impl: 0, verify: 0 };
+			entry.total++;
+			if (r.implRefs.length > 0) entry.impl++;
+			if (r.verifyRefs.length > 0) entry.verify++;
+			byFile.set(r.sourceFile, entry);
+		}
+		if (byFile.size < 2) return [];
+		return [...byFile.entries()]
+			.sort(([a], [b]) => a.localeCompare(b))
+			.map(([file, s]) => ({
+				file,
+				...s,
+				implPct: s.total ? (s.impl / s.total) * 100 : 0,
+				verifyPct: s.total ? (s.verify / s.total) * 100 : 0,
+			}));
+	}, [allRules, level, inferLevel]);
+
+	// r[impl dashboard.coverage.recent]
+	const recent = useRecent("7d", version);
+	const recentRules = useMemo(
+		() => recent?.rules.filter((r) => r.spec === spec) ?? [],
+		[recent, spec],
+	);
+
+	// r[impl dashboard.coverage.policy]
+	const policy = useMemo(
+		() =>
+			config.specs
+				.find((s) => s.name === spec)
+				?.policies?.find((p) => p.implName === impl),
+		[config, spec, impl],
+	);
+
 	const mdIcon = html`<svg
     class="rule-icon"
     viewBox="0 0 24 24"
@@ -134,6 +185,17 @@ export function CoverageView({
           >${stats.verifyPct.toFixed(1)}%</span
         >
       </div>
+      ${
+				policy &&
+				html`
+          <div class="stat" title=${policy.violations.join("\n")}>
+            <span class="stat-label">Policy</span>
+            <span class="stat-value ${policy.passed ? "good" : "bad"}"
+              >${policy.passed ? "Pass" : "Fail"}</span
+            >
+          </div>
+        `
+			}
 
       <div class="custom-dropdown ${levelOpen ? "open" : ""}" id="level-dropdown">
         <div
@@ -179,6 +241,77 @@ export function CoverageView({
     <div class="main">
       <div class="content">
         <div class="content-body">
+          ${
+						fileStats.length > 0 &&
+						html`
+            <table class="rules-table file-coverage-table">
+              <thead>
+                <tr>
+                  <th>Spec file</th>
+                  <th>Rules</th>
+                  <th>Impl</th>
+                  <th>Test</th>
+                </tr>
+              </thead>
+              <tbody>
+                ${fileStats.map(
+									(f) => html`
+                    <tr
+                      key=${f.file}
+                      class=${fileFilter === f.file ? "active" : ""}
+                      onClick=${() => setFileFilter(fileFilter === f.file ? null : f.file)}
+                      style="cursor: pointer;"
+                    >
+                      <td class="file-coverage-path">${f.file}</td>
+                      <td>${f.total}</td>
+                      <td class=${getStatClass(f.implPct)}>${f.impl} (${f.implPct.toFixed(0)}%)</td>
+                      <td class=${getStatClass(f.verifyPct)}>
+                        ${f.verify} (${f.verifyPct.toFixed(0)}%)
+                      </td>
+                    </tr>
+                  `,
+								)}
+              </tbody>
+            </table>
+          `
+					}
+          ${
+						recentRules.length > 0 &&
+						html`
+            <table class="rules-table recent-table">
+              <thead>
+                <tr>
+                  <th>Changed in the last 7 days</th>
+                  <th>Rule</th>
+                  <th>Coverage</th>
+                </tr>
+              </thead>
+              <tbody>
+                ${recentRules.map(
+									(r) => html`
+                    <tr
+                      key=${r.id}
+                      onClick=${() => r.change !== "removed" && onSelectRule(r.id)}
+                      style=${r.change !== "removed" ? "cursor: pointer;" : ""}
+                    >
+                      <td class="recent-change ${r.change}">${r.change}</td>
+                      <td>
+                        <span class="rule-id">${r.id}</span>
+                        ${r.previousId && html` <span class="recent-was">(was ${r.previousId})</span>`}
+                      </td>
+                      <td>
+                        ${r.coverage
+													.filter((c) => c.implName === impl)
+													.map(coverageMoves)
+													.join(", ")}
+                      </td>
+                    </tr>
+                  `,
+								)}
+              </tbody>
+            </table>
+          `
+					}
           <table class="rules-table">
             <thead>
               <tr>
@@ -231,6 +364,11 @@ export function CoverageView({
                                   file=${r.file}
                                   line=${r.line}
                                   type="verify"
+                                  outcome=${
+																		rule.testResults?.find(
+																			(t) => t.file === r.file && t.line === r.line,
+																		)?.outcome
+																	}
                                   onSelectFile=${onSelectFile}
                                 />
                               `,
@@ -250,3 +388,15 @@ export function CoverageView({
     </div>
   `;
 }
+
+// How an impl's coverage of a recently changed rule moved
+function coverageMoves(c: RecentCoverage): string {
+	const moves: string[] = [];
+	if (c.wasImplemented !== c.implemented) {
+		moves.push(c.implemented ? "now implemented" : "no longer implemented");
+	}
+	if (c.wasVerified !== c.verified) {
+		moves.push(c.verified ? "now verified" : "no longer verified");
+	}
+	return moves.join(", ");
+}
@@ -287,6 +287,7 @@ export function SourcesView({
   data,
   forward,
   config,
+  version,
   search,
   selectedFile,
   selectedLine,
@@ -305,7 +306,7 @@ export function SourcesView({
   }, [sidebarCollapsed]);
 
   const fileTree = useMemo(() => buildFileTree(data.files), [data.files]);
-  const file = useFile(selectedFile);
+  const file = useFile(selectedFile, version);
 
   // Find the rule data if we have a context
   const contextRule = useMemo(() => {
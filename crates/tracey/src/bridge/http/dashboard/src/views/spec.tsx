@@ -116,6 +116,18 @@ function isActiveOrHasActiveChild(node: OutlineTreeNode, activeHeading: string |
   return node.children.some((child) => isActiveOrHasActiveChild(child, activeHeading));
 }
 
+// r[impl dashboard.spec.breadcrumbs]
+// Path of headings from a root down to the active heading
+function headingPath(nodes: OutlineTreeNode[], activeHeading: string | null): OutlineTreeNode[] {
+  if (!activeHeading) return [];
+  for (const node of nodes) {
+    if (node.entry.slug === activeHeading) return [node];
+    const rest = headingPath(node.children, activeHeading);
+    if (rest.length > 0) return [node, ...rest];
+  }
+  return [];
+}
+
 // Aggregate coverage stats for a node and all its descendants
 interface AggregatedCoverage {
   total: number;
@@ -295,6 +307,10 @@ export function SpecView({
 
   // Build hierarchical tree from flat outline
   const outlineTree = useMemo(() => buildOutlineTree(outline), [outline]);
+  const breadcrumbs = useMemo(
+    () => headingPath(outlineTree, activeHeading),
+    [outlineTree, activeHeading],
+  );
 
   // r[impl dashboard.spec.outline-totals]
   // Compute overall coverage totals from root-level entries
@@ -1134,6 +1150,25 @@ export function SpecView({
         `}
       </div>
       <div class="content">
+        ${breadcrumbs.length > 0 &&
+        html`
+          <nav class="spec-breadcrumbs">
+            ${breadcrumbs.map(
+              (node, i) => html`
+                ${i > 0 && html`<span class="spec-breadcrumbs-sep">›</span>`}
+                <a
+                  class="spec-breadcrumb ${i === breadcrumbs.length - 1 ? "current" : ""}"
+                  href=${`#${node.entry.slug}`}
+                  onClick=${(e: Event) => {
+                    e.preventDefault();
+                    scrollToHeading(node.entry.slug);
+                  }}
+                  >${node.entry.title}</a
+                >
+              `,
+            )}
+          </nav>
+        `}
         <div class="content-body" ref=${contentBodyRef}>
           <div
             class="markdown"
@@ -253,6 +253,19 @@ test.describe('Search Functionality', () => {
   });
 });
 
+test.describe('Permalinks', () => {
+  // r[verify dashboard.url.permalink]
+  test('should open a rule permalink in the spec view', async ({ page }) => {
+    await page.goto('/rule/dashboard.url.permalink');
+    await expect(page).toHaveURL(/\/spec#r--dashboard\.url\.permalink$/);
+  });
+
+  test('should open a file permalink in the sources view', async ({ page }) => {
+    await page.goto('/file/crates/tracey/src/lib.rs#L3');
+    await expect(page).toHaveURL(/\/sources\/crates\/tracey\/src\/lib\.rs:3$/);
+  });
+});
+
 test.describe('Error Handling', () => {
   test('should handle 404 gracefully', async ({ page }) => {
     await page.goto('/nonexistent/path/that/does/not/exist');
@@ -113,6 +113,7 @@ pub async fn run(
     // r[impl dashboard.api.reverse]
     // r[impl dashboard.api.spec]
     // r[impl dashboard.api.file]
+    // r[impl dashboard.api.validate-all]
     let app = Router::new()
         // WebSocket for live updates
         .route("/ws", get(ws_handler))
@@ -126,18 +127,24 @@ pub async fn run(
         .route("/api/search", get(api_search))
         .route("/api/status", get(api_status))
         .route("/api/validate", get(api_validate))
+        .route("/api/validate-all", get(api_validate_all))
         .route("/api/uncovered", get(api_uncovered))
         .route("/api/untested", get(api_untested))
         .route("/api/unmapped", get(api_unmapped))
         .route("/api/rule", get(api_rule))
+        .route("/api/recent", get(api_recent))
         .route("/api/reload", get(api_reload))
         .route("/api/health", get(api_health));
 
-    // In dev mode, proxy to Vite; otherwise serve embedded assets
+    // In dev mode, proxy to Vite; otherwise serve embedded assets. Vite
+    // serves permalinks as any other page, and the dashboard fetches what
+    // they point at itself.
     let app = if dev {
         app.fallback(vite_proxy)
     } else {
         app.route("/assets/{*path}", get(serve_asset))
+            .route("/rule/{id}", get(permalink_rule))
+            .route("/file/{*path}", get(permalink_file))
             .fallback(spa_fallback)
     };
 
@@ -208,6 +215,102 @@ async fn spa_fallback() -> Html<&'static str> {
     Html(INDEX_HTML)
 }
 
+/// What a permalink points at, embedded in the page so the dashboard can
+/// open it without another round trip.
+#[derive(Debug, Clone, Facet)]
+struct Prefetch {
+    spec: String,
+    #[facet(rename = "impl")]
+    impl_name: String,
+    /// Data version the rule or file is from
+    version: u64,
+    #[facet(default)]
+    rule: Option<tracey_proto::RuleInfo>,
+    #[facet(default)]
+    file: Option<ApiFileData>,
+}
+
+/// index.html with `prefetch` in a `tracey-prefetch` JSON script.
+fn spa_with_prefetch(status: StatusCode, prefetch: &Prefetch) -> Response {
+    // Escaping `<` keeps file content from closing the script element.
+    let json = facet_json::to_string(prefetch)
+        .expect("JSON serialization failed")
+        .replace('<', "\\u003c");
+    let script = format!(r#"<script id="tracey-prefetch" type="application/json">{json}</script>"#);
+    let html = INDEX_HTML.replacen("</head>", &format!("{script}\n</head>"), 1);
+    (status, Html(html)).into_response()
+}
+
+/// GET /rule/{id} - Permalink to a rule, opened in the spec view of the
+/// first implementation covering it.
+///
+/// r[impl dashboard.url.permalink]
+async fn permalink_rule(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    let client = state.client.clone();
+    let Ok(config) = rpc(client.config().await) else {
+        return Html(INDEX_HTML).into_response();
+    };
+    // Read before the rule, so a rebuild in between shows as a newer version
+    let version = rpc(client.version().await).unwrap_or(0);
+    let rule = match parse_rule_id(&id) {
+        Some(rule_id) => rpc(client.rule(rule_id).await).ok().flatten(),
+        None => None,
+    };
+    let (spec, impl_name) = match rule.as_ref().and_then(|r| r.coverage.first()) {
+        Some(coverage) => (coverage.spec.clone(), coverage.impl_name.clone()),
+        None => resolve_spec_impl(None, None, &config),
+    };
+    let status = if rule.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    let prefetch = Prefetch {
+        spec,
+        impl_name,
+        version,
+        rule,
+        file: None,
+    };
+    spa_with_prefetch(status, &prefetch)
+}
+
+/// GET /file/{path} - Permalink to a source file, opened in the sources
+/// view. The line is in the fragment (`#L42`), which only the dashboard
+/// sees.
+async fn permalink_file(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+    Query(query): Query<ImplQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let Ok(config) = rpc(client.config().await) else {
+        return Html(INDEX_HTML).into_response();
+    };
+    let (spec, impl_name) = resolve_spec_impl(query.spec, query.impl_name, &config);
+    let req = tracey_proto::FileRequest {
+        spec: spec.clone(),
+        impl_name: impl_name.clone(),
+        path,
+    };
+    // Read before the file, so a rebuild in between shows as a newer version
+    let version = rpc(client.version().await).unwrap_or(0);
+    let file = rpc(client.file(req).await).ok().flatten();
+    let status = if file.is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    let prefetch = Prefetch {
+        spec,
+        impl_name,
+        version,
+        rule: None,
+        file,
+    };
+    spa_with_prefetch(status, &prefetch)
+}
+
 /// Serve static assets from embedded files.
 async fn serve_asset(Path(path): Path<String>) -> Response {
     match path.as_str() {
@@ -274,9 +377,17 @@ struct UnmappedQuery {
     spec: Option<String>,
     #[serde(rename = "impl")]
     impl_name: Option<String>,
+    /// `spec/impl` (or just `spec`), as on the command line
+    spec_impl: Option<String>,
     path: Option<String>,
 }
 
+/// Query parameters for recent endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct RecentQuery {
+    since: Option<String>,
+}
+
 /// Query parameters for rule endpoint.
 #[derive(Debug, Clone, Deserialize)]
 struct RuleQuery {
@@ -523,6 +634,14 @@ async fn api_validate(
     }
 }
 
+/// GET /api/validate-all - Validate every spec/impl pair.
+async fn api_validate_all(State(state): State<Arc<AppState>>) -> Response {
+    match rpc(state.client.validate_all().await) {
+        Ok(results) => Json(results).into_response(),
+        Err(e) => e,
+    }
+}
+
 /// GET /api/uncovered - Get uncovered rules.
 async fn api_uncovered(
     State(state): State<Arc<AppState>>,
@@ -541,6 +660,10 @@ async fn api_uncovered(
         spec: Some(spec),
         impl_name: Some(impl_name),
         prefix: query.prefix,
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     match rpc(client.uncovered(req).await) {
@@ -567,6 +690,10 @@ async fn api_untested(
         spec: Some(spec),
         impl_name: Some(impl_name),
         prefix: query.prefix,
+        sort: None,
+        limit: None,
+        offset: None,
+        only_implemented: false,
     };
 
     match rpc(client.untested(req).await) {
@@ -575,7 +702,10 @@ async fn api_untested(
     }
 }
 
-/// GET /api/unmapped - Get unmapped code.
+/// GET /api/unmapped - Get unmapped code: the tree under `path`, or, for a
+/// file, its unmapped code units.
+///
+/// r[impl dashboard.api.unmapped]
 async fn api_unmapped(
     State(state): State<Arc<AppState>>,
     Query(query): Query<UnmappedQuery>,
@@ -587,17 +717,25 @@ async fn api_unmapped(
         Err(e) => return e,
     };
 
-    let (spec, impl_name) = resolve_spec_impl(query.spec, query.impl_name, &config);
+    match rpc(client.unmapped(unmapped_request(query, &config)).await) {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => e,
+    }
+}
 
-    let req = tracey_proto::UnmappedRequest {
+/// The unmapped request for `query`: `spec` and `impl` win over `spec_impl`,
+/// and the config's first spec and impl fill in what neither gives.
+fn unmapped_request(query: UnmappedQuery, config: &ApiConfig) -> tracey_proto::UnmappedRequest {
+    let (pair_spec, pair_impl) = crate::bridge::query::parse_spec_impl(query.spec_impl.as_deref());
+    let (spec, impl_name) = resolve_spec_impl(
+        query.spec.or(pair_spec),
+        query.impl_name.or(pair_impl),
+        config,
+    );
+    tracey_proto::UnmappedRequest {
         spec: Some(spec),
         impl_name: Some(impl_name),
         path: query.path,
-    };
-
-    match rpc(client.unmapped(req).await) {
-        Ok(data) => Json(data).into_response(),
-        Err(e) => e,
     }
 }
 
@@ -615,6 +753,25 @@ async fn api_rule(State(state): State<Arc<AppState>>, Query(query): Query<RuleQu
     }
 }
 
+/// GET /api/recent - Rules whose definition or coverage changed since a
+/// history snapshot.
+///
+/// r[impl dashboard.api.recent]
+async fn api_recent(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RecentQuery>,
+) -> Response {
+    let client = state.client.clone();
+    let req = tracey_proto::RecentRequest {
+        since: query.since.unwrap_or_else(|| "7d".to_string()),
+    };
+
+    match rpc(client.recent(req).await) {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => e,
+    }
+}
+
 /// GET /api/reload - Force a rebuild.
 async fn api_reload(State(state): State<Arc<AppState>>) -> Response {
     let client = state.client.clone();
@@ -966,3 +1123,66 @@ async fn handle_vite_ws(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ApiConfig {
+        let spec = |name: &str, implementations: &[&str]| ApiSpecInfo {
+            name: name.to_string(),
+            prefix: "r".to_string(),
+            source: None,
+            source_url: None,
+            implementations: implementations.iter().map(|i| i.to_string()).collect(),
+            policies: vec![],
+            no_default_excludes: vec![],
+            skipped_units: vec![],
+            variants: vec![],
+        };
+        ApiConfig {
+            project_root: "/project".to_string(),
+            specs: vec![spec("core", &["rust"]), spec("wire", &["rust", "ts"])],
+            default_excludes: vec![],
+        }
+    }
+
+    fn query(
+        spec_impl: Option<&str>,
+        spec: Option<&str>,
+        impl_name: Option<&str>,
+    ) -> UnmappedQuery {
+        UnmappedQuery {
+            spec: spec.map(str::to_string),
+            impl_name: impl_name.map(str::to_string),
+            spec_impl: spec_impl.map(str::to_string),
+            path: Some("src/lib.rs".to_string()),
+        }
+    }
+
+    // r[verify dashboard.api.unmapped]
+    #[test]
+    fn unmapped_resolves_spec_impl() {
+        let pair = |q: UnmappedQuery| {
+            let req = unmapped_request(q, &config());
+            (req.spec.unwrap(), req.impl_name.unwrap(), req.path)
+        };
+        let path = Some("src/lib.rs".to_string());
+        assert_eq!(
+            pair(query(Some("wire/ts"), None, None)),
+            ("wire".to_string(), "ts".to_string(), path.clone())
+        );
+        assert_eq!(
+            pair(query(Some("wire"), None, None)),
+            ("wire".to_string(), "rust".to_string(), path.clone())
+        );
+        assert_eq!(
+            pair(query(Some("wire/ts"), None, Some("rust"))),
+            ("wire".to_string(), "rust".to_string(), path.clone())
+        );
+        assert_eq!(
+            pair(query(None, None, None)),
+            ("core".to_string(), "rust".to_string(), path)
+        );
+    }
+}
@@ -368,11 +368,15 @@ impl Backend {
     }
 
     fn ensure_project_for_path(&self, path: &Path) -> (PathBuf, DaemonClient, bool, bool) {
-        let root = {
-            let state = self.project_state.lock().unwrap();
-            Self::best_root_for_path(path, &state.roots)
-        }
-        .unwrap_or_else(|| crate::find_project_root_from(path));
+        // r[impl config.discovery.nearest]
+        // A nested project's config takes precedence over an enclosing
+        // workspace root, even if only the outer root is known so far.
+        let root = crate::find_config_root_from(path)
+            .or_else(|| {
+                let state = self.project_state.lock().unwrap();
+                Self::best_root_for_path(path, &state.roots)
+            })
+            .unwrap_or_else(|| crate::find_project_root_from(path));
 
         self.ensure_project_root(root)
     }
@@ -430,6 +434,7 @@ impl Backend {
                 .follow_links(true)
                 .hidden(false)
                 .git_ignore(true)
+                .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
                 .build();
 
             for entry in walker.flatten() {
@@ -708,6 +713,7 @@ impl Backend {
             .follow_links(true)
             .hidden(false)
             .git_ignore(true)
+            .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
             .build();
 
         for entry in walker.flatten() {
@@ -1015,6 +1021,10 @@ impl LanguageServer for Backend {
         if let Some(url) = &info.spec_url {
             markdown.push_str(&format!(" ([source]({}))", url));
         }
+        // r[impl lsp.hover.permalink]
+        if let Some(url) = &info.permalink {
+            markdown.push_str(&format!("\n\n[Open definition]({})", url));
+        }
 
         // Format impl refs as clickable links
         if !info.impl_refs.is_empty() {
@@ -1115,8 +1125,8 @@ impl LanguageServer for Backend {
                     character: loc.character,
                 },
                 end: Position {
-                    line: loc.line,
-                    character: loc.character,
+                    line: loc.end_line,
+                    character: loc.end_character,
                 },
             },
         })))
@@ -1164,8 +1174,8 @@ impl LanguageServer for Backend {
                             character: loc.character,
                         },
                         end: Position {
-                            line: loc.line,
-                            character: loc.character,
+                            line: loc.end_line,
+                            character: loc.end_character,
                         },
                     },
                 })
@@ -1214,8 +1224,8 @@ impl LanguageServer for Backend {
                             character: loc.character,
                         },
                         end: Position {
-                            line: loc.line,
-                            character: loc.character,
+                            line: loc.end_line,
+                            character: loc.end_character,
                         },
                     },
                 })
@@ -1263,8 +1273,8 @@ impl LanguageServer for Backend {
                         character: loc.character,
                     },
                     end: Position {
-                        line: loc.line,
-                        character: loc.character + 10, // Approximate length
+                        line: loc.end_line,
+                        character: loc.end_character,
                     },
                 },
                 kind: Some(DocumentHighlightKind::READ),
@@ -1415,15 +1425,40 @@ impl LanguageServer for Backend {
                 if a.command == "tracey.renameUnknownRequirement" {
                     arguments.push(serde_json::Value::String(uri.to_string()));
                 }
+                let edit = (!a.edits.is_empty()).then(|| {
+                    let edits = a
+                        .edits
+                        .into_iter()
+                        .map(|edit| TextEdit {
+                            range: Range {
+                                start: Position {
+                                    line: edit.start_line,
+                                    character: edit.start_char,
+                                },
+                                end: Position {
+                                    line: edit.end_line,
+                                    character: edit.end_char,
+                                },
+                            },
+                            new_text: edit.new_text,
+                        })
+                        .collect();
+                    WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), edits)])),
+                        ..Default::default()
+                    }
+                });
+                let command = (!a.command.is_empty()).then(|| Command {
+                    title: String::new(),
+                    command: a.command,
+                    arguments: Some(arguments),
+                });
                 CodeActionOrCommand::CodeAction(CodeAction {
                     title: a.title,
                     kind: Some(a.kind.into()),
                     is_preferred: Some(a.is_preferred),
-                    command: Some(Command {
-                        title: String::new(),
-                        command: a.command,
-                        arguments: Some(arguments),
-                    }),
+                    edit,
+                    command,
                     ..Default::default()
                 })
             })
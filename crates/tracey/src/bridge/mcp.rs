@@ -8,6 +8,7 @@
 
 #![allow(clippy::enum_variant_names)]
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -47,7 +48,7 @@ pub struct StatusTool {
 /// Get rules without implementation references
 #[mcp_tool(
     name = "tracey_uncovered",
-    description = "List rules that have no implementation references ([impl ...] comments). Optionally filter by spec/impl or rule ID prefix. Requires `cwd` (absolute workspace path)."
+    description = "List rules that have no implementation references ([impl ...] comments). Optionally filter by spec/impl, rule ID prefix, or owner (e.g. \"@platform-team\", from the config's `owners`). Requires `cwd` (absolute workspace path)."
 )]
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct UncoveredTool {
@@ -57,12 +58,14 @@ pub struct UncoveredTool {
     pub spec_impl: Option<String>,
     #[serde(default)]
     pub prefix: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
 }
 
 /// Get rules without verification references
 #[mcp_tool(
     name = "tracey_untested",
-    description = "List rules that have implementation but no verification references ([verify ...] comments). These rules are implemented but not tested. Requires `cwd` (absolute workspace path)."
+    description = "List rules without verification references ([verify ...] comments), split into rules that are implemented but not tested and rules that are neither implemented nor tested. Set `only_implemented` to list just the first group. Requires `cwd` (absolute workspace path)."
 )]
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct UntestedTool {
@@ -72,6 +75,9 @@ pub struct UntestedTool {
     pub spec_impl: Option<String>,
     #[serde(default)]
     pub prefix: Option<String>,
+    /// Only list rules that are implemented but not verified
+    #[serde(default)]
+    pub only_implemented: Option<bool>,
 }
 
 /// List stale references (code pointing to older rule versions)
@@ -89,6 +95,49 @@ pub struct StaleTool {
     pub prefix: Option<String>,
 }
 
+/// List rules that have gone unreferenced for a long time
+#[mcp_tool(
+    name = "tracey_dead",
+    description = "List rules with no impl or verify references whose definition is older than `days` (default 90), using git blame on the spec. Candidates for removal or for implementation. Requires `cwd` (absolute workspace path)."
+)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DeadTool {
+    /// Absolute workspace path where Tracey should resolve the project root.
+    pub cwd: String,
+    #[serde(default)]
+    pub spec_impl: Option<String>,
+    #[serde(default)]
+    pub days: Option<u32>,
+}
+
+/// List high-level rules without refinements
+#[mcp_tool(
+    name = "tracey_unrefined",
+    description = "List high-level rules that no lower-level rule refines with an `r[refines ID]` marker in its text. A rule counts as high-level when another rule with the same first ID segment is refined. Requires `cwd` (absolute workspace path)."
+)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UnrefinedTool {
+    /// Absolute workspace path where Tracey should resolve the project root.
+    pub cwd: String,
+    #[serde(default)]
+    pub spec_impl: Option<String>,
+}
+
+/// List rules whose implementing code never ran
+#[mcp_tool(
+    name = "tracey_unexecuted",
+    description = "List rules whose impl-annotated code units were never executed, according to the line coverage ingested with `tracey line-coverage ingest`. A verify comment near such a rule proves nothing. Requires `cwd` (absolute workspace path)."
+)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UnexecutedTool {
+    /// Absolute workspace path where Tracey should resolve the project root.
+    pub cwd: String,
+    #[serde(default)]
+    pub spec_impl: Option<String>,
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
 /// Get code units without rule references
 #[mcp_tool(
     name = "tracey_unmapped",
@@ -116,6 +165,21 @@ pub struct RuleTool {
     pub rule_id: String,
 }
 
+/// Search rule text and source code
+#[mcp_tool(
+    name = "tracey_search",
+    description = "Full-text search over rule text and source code. Every word or \"quoted phrase\" must match; use OR for alternatives, -term to exclude, and level:, status:, tag:, section:, kind: (rule or source) or id: to filter, e.g. `level:MUST tag:security \"session timeout\"`. Requires `cwd` (absolute workspace path)."
+)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SearchTool {
+    /// Absolute workspace path where Tracey should resolve the project root.
+    pub cwd: String,
+    pub query: String,
+    /// Maximum number of results (default 20)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
 /// Display current configuration
 #[mcp_tool(
     name = "tracey_config",
@@ -198,8 +262,12 @@ tool_box!(
         UncoveredTool,
         UntestedTool,
         StaleTool,
+        DeadTool,
+        UnrefinedTool,
+        UnexecutedTool,
         UnmappedTool,
         RuleTool,
+        SearchTool,
         ConfigTool,
         ReloadTool,
         ValidateTool,
@@ -297,6 +365,8 @@ struct TraceyHandler {
     config_path: PathBuf,
     active_project_root: Arc<RwLock<PathBuf>>,
     root_refresh_state: Arc<RwLock<RootRefreshState>>,
+    /// One query client, and so one daemon connection, per project root
+    clients: Arc<Mutex<HashMap<PathBuf, query::QueryClient>>>,
     trace_sink: Option<McpTraceSink>,
 }
 
@@ -317,6 +387,7 @@ impl TraceyHandler {
             config_path,
             active_project_root: Arc::new(RwLock::new(project_root)),
             root_refresh_state: Arc::new(RwLock::new(RootRefreshState::default())),
+            clients: Arc::new(Mutex::new(HashMap::new())),
             trace_sink,
         };
 
@@ -332,9 +403,15 @@ impl TraceyHandler {
         handler
     }
 
+    // r[impl daemon.client.reuse]
     async fn current_client(&self) -> query::QueryClient {
         let root = self.active_project_root.read().await.clone();
-        query::QueryClient::new(root, query::Caller::Mcp)
+        self.clients
+            .lock()
+            .unwrap()
+            .entry(root.clone())
+            .or_insert_with(|| query::QueryClient::new(root, query::Caller::Mcp))
+            .clone()
     }
 
     fn trace_json(&self, event: &str, payload: JsonValue) {
@@ -595,17 +672,50 @@ impl ServerHandler for TraceyHandler {
             "tracey_uncovered" => {
                 let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
                 let prefix = args.get("prefix").and_then(|v| v.as_str());
-                client.uncovered(spec_impl, prefix).await
+                let owner = args.get("owner").and_then(|v| v.as_str());
+                client
+                    .uncovered(spec_impl, prefix, owner, query::ListArgs::default())
+                    .await
             }
             "tracey_untested" => {
                 let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
                 let prefix = args.get("prefix").and_then(|v| v.as_str());
-                client.untested(spec_impl, prefix).await
+                let only_implemented = args
+                    .get("only_implemented")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                client
+                    .untested(
+                        spec_impl,
+                        prefix,
+                        only_implemented,
+                        query::ListArgs::default(),
+                    )
+                    .await
             }
             "tracey_stale" => {
                 let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
                 let prefix = args.get("prefix").and_then(|v| v.as_str());
-                client.stale(spec_impl, prefix).await
+                client
+                    .stale(spec_impl, prefix, query::ListArgs::default())
+                    .await
+            }
+            "tracey_dead" => {
+                let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
+                let days = args
+                    .get("days")
+                    .and_then(|v| v.as_u64())
+                    .map(|d| d.min(u32::MAX as u64) as u32);
+                client.dead(spec_impl, days).await
+            }
+            "tracey_unrefined" => {
+                let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
+                client.unrefined(spec_impl).await
+            }
+            "tracey_unexecuted" => {
+                let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
+                let prefix = args.get("prefix").and_then(|v| v.as_str());
+                client.unexecuted(spec_impl, prefix).await
             }
             "tracey_unmapped" => {
                 let spec_impl = args.get("spec_impl").and_then(|v| v.as_str());
@@ -623,6 +733,21 @@ impl ServerHandler for TraceyHandler {
                     }
                 }
             }
+            "tracey_search" => {
+                let query = args.get("query").and_then(|v| v.as_str());
+                let limit = args
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|l| l.min(u32::MAX as u64) as u32);
+                match query {
+                    Some(q) => client.search(q, limit).await,
+                    None => {
+                        client
+                            .with_config_banner("Error: query is required".to_string())
+                            .await
+                    }
+                }
+            }
             "tracey_config" => client.config().await,
             "tracey_reload" => client.reload().await,
             "tracey_validate" => {
@@ -8,3 +8,5 @@ pub mod http;
 pub mod lsp;
 pub mod mcp;
 pub mod query;
+pub mod table;
+pub mod watch;
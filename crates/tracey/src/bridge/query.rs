@@ -3,11 +3,13 @@
 //! This module contains the actual query-to-client formatting logic so both
 //! MCP and terminal queries print the same markdown-like output.
 
+use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{collections::BTreeMap, collections::BTreeSet};
 
-use crate::daemon::{DaemonClient, new_client};
-use tracey_core::parse_rule_id;
+use crate::daemon::{ClientSettings, Connected, DaemonClient, new_observed_client};
+use tracey_core::{RuleId, parse_rule_id};
 use tracey_proto::*;
 
 /// Who is calling the query client — affects hint formatting.
@@ -27,6 +29,163 @@ fn format_config_error_banner(error: &str) -> String {
     )
 }
 
+/// `--sort`, `--limit` and `--offset` of the list queries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListArgs {
+    pub sort: Option<RuleListSort>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl ListArgs {
+    /// Where this page sits in the full list, when it doesn't show everything.
+    fn page_note(&self, shown: usize, total: usize) -> String {
+        if shown == total {
+            return String::new();
+        }
+        let offset = self.offset.unwrap_or(0) as usize;
+        if shown == 0 {
+            return format!("Nothing left after offset {} ({} total)\n\n", offset, total);
+        }
+        let mut note = format!("Showing {}-{} of {}", offset + 1, offset + shown, total);
+        if offset + shown < total {
+            note.push_str(&format!(" (next page: --offset {})", offset + shown));
+        }
+        note.push_str("\n\n");
+        note
+    }
+}
+
+/// The list a `--fail-on-any` / `--fail-if-more-than` gate counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateList {
+    Uncovered,
+    Untested { only_implemented: bool },
+    Stale,
+}
+
+impl GateList {
+    fn noun(self) -> &'static str {
+        match self {
+            GateList::Uncovered => "uncovered rule(s)",
+            GateList::Untested { .. } => "untested rule(s)",
+            GateList::Stale => "stale reference(s)",
+        }
+    }
+}
+
+/// A threshold that turns a list query into a pass/fail assertion.
+#[derive(Debug, Clone)]
+pub struct FailGate {
+    pub list: GateList,
+    pub spec_impl: Option<String>,
+    pub prefix: Option<String>,
+    /// Only count rules with this owner (uncovered only)
+    pub owner: Option<String>,
+    /// Largest count that still passes
+    pub max: usize,
+}
+
+impl FailGate {
+    /// Build the gate from the CLI flags, or `None` if neither was given.
+    pub fn new(
+        list: GateList,
+        spec_impl: Option<String>,
+        prefix: Option<String>,
+        owner: Option<String>,
+        fail_on_any: bool,
+        fail_if_more_than: Option<u32>,
+    ) -> Option<Self> {
+        let max = match (fail_on_any, fail_if_more_than) {
+            (true, _) => 0,
+            (false, Some(n)) => n as usize,
+            (false, None) => return None,
+        };
+        Some(Self {
+            list,
+            spec_impl,
+            prefix,
+            owner,
+            max,
+        })
+    }
+
+    /// Explain why `count` fails the gate, or `None` if it passes.
+    pub fn violation(&self, count: usize) -> Option<String> {
+        (count > self.max).then(|| {
+            format!(
+                "{} {}, more than the {} allowed",
+                count,
+                self.list.noun(),
+                self.max
+            )
+        })
+    }
+}
+
+/// Render `query untested`, one block per bucket.
+/// One entry of a rule list, with the rule's owners if it has any.
+fn format_rule_ref(rule: &RuleRef) -> String {
+    if rule.owners.is_empty() {
+        format!("  - {}\n", rule.id)
+    } else {
+        format!("  - {} ({})\n", rule.id, rule.owners.join(", "))
+    }
+}
+
+fn format_untested(response: &UntestedResponse, list: &ListArgs) -> String {
+    let mut output = format!(
+        "{}/{}: {} untested out of {} rules ({} implemented, {} not implemented)",
+        response.spec,
+        response.impl_name,
+        response.untested_count,
+        response.total_rules,
+        response.implemented_count,
+        response.untested_count - response.implemented_count
+    );
+    // r[impl cli.test-report]
+    if response.unproven_count > 0 {
+        output.push_str(&format!(
+            ", and {} verified without a passing test",
+            response.unproven_count
+        ));
+    }
+    output.push_str("\n\n");
+
+    let buckets = [
+        ("Implemented, not verified", &response.by_section),
+        (
+            "Neither implemented nor verified",
+            &response.unimplemented_by_section,
+        ),
+        (
+            "Verified, but no passing test",
+            &response.unproven_by_section,
+        ),
+    ];
+    let mut shown = 0;
+    for (title, by_section) in buckets {
+        let rules: usize = by_section.iter().map(|s| s.rules.len()).sum();
+        if rules == 0 {
+            continue;
+        }
+        shown += rules;
+        output.push_str(&format!("# {}\n\n", title));
+        for section in by_section {
+            if !section.rules.is_empty() {
+                output.push_str(&format!("## {}\n", section.section));
+                for rule in &section.rules {
+                    output.push_str(&format_rule_ref(rule));
+                }
+                output.push('\n');
+            }
+        }
+    }
+
+    output.push_str(&list.page_note(shown, response.untested_count + response.unproven_count));
+    output
+}
+
 /// Parse "spec/impl" format into `(Option<spec>, Option<impl>)`.
 pub fn parse_spec_impl(spec_impl: Option<&str>) -> (Option<String>, Option<String>) {
     match spec_impl {
@@ -39,6 +198,28 @@ pub fn parse_spec_impl(spec_impl: Option<&str>) -> (Option<String>, Option<Strin
     }
 }
 
+/// Resolve a source path given on the command line. Paths that exist
+/// relative to the working directory are made absolute so they work from
+/// anywhere inside the project; anything else is left for the daemon to
+/// resolve against the project root.
+pub fn resolve_source_path(file: &str) -> String {
+    std::env::current_dir()
+        .map(|cwd| cwd.join(file))
+        .ok()
+        .filter(|path| path.is_file())
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| file.to_string())
+}
+
+/// Number of hits `query search` shows unless `--limit` is given.
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+
+/// Whether a `query rule` argument is a glob pattern like `auth.*` rather
+/// than a single rule ID.
+pub fn is_rule_pattern(raw_id: &str) -> bool {
+    raw_id.contains(['*', '?', '[', '{'])
+}
+
 fn unknown_rule_reference_from_error(error: &ValidationError) -> Option<(String, String)> {
     let rule_id = error.reference_rule_id.as_ref()?.to_string();
     let reference = error
@@ -50,22 +231,101 @@ fn unknown_rule_reference_from_error(error: &ValidationError) -> Option<(String,
 
 /// Shared query client used by both MCP and CLI.
 #[derive(Clone)]
+/// Most candidates listed for an ambiguous rule ID
+const RULE_CANDIDATE_LIMIT: usize = 10;
+
+/// Outcome of looking up a rule ID typed by hand.
+pub enum RuleLookup {
+    /// A rule has exactly this ID
+    Found(RuleInfo),
+    /// No rule has this ID, but exactly one fuzzily matches it
+    Matched(RuleInfo),
+    /// No rule has this ID and several fuzzily match it, best first
+    Candidates(Vec<RuleId>),
+    NotFound,
+}
+
+fn format_rule_candidates(rule_id: &RuleId, candidates: &[RuleId]) -> String {
+    let mut output = format!("No rule is named {}. Did you mean:\n", rule_id);
+    for candidate in candidates {
+        output.push_str(&format!("  - {}\n", candidate));
+    }
+    output
+}
+
+/// A daemon call that failed, or that didn't finish in time.
+#[derive(Debug)]
+pub enum QueryError<E> {
+    Rpc(E),
+    TimedOut(Duration),
+}
+
+impl<E: fmt::Display> fmt::Display for QueryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Rpc(e) => e.fmt(f),
+            QueryError::TimedOut(limit) => write!(
+                f,
+                "the daemon didn't answer within {}s; check `tracey status` and `tracey logs`",
+                limit.as_secs()
+            ),
+        }
+    }
+}
+
+/// Cheap to clone: clones share one daemon connection.
+#[derive(Clone)]
 pub struct QueryClient {
     pub client: DaemonClient,
     caller: Caller,
+    call_timeout: Option<Duration>,
+    connected: Connected,
 }
 
 impl QueryClient {
     pub fn new(project_root: PathBuf, caller: Caller) -> Self {
+        let settings = ClientSettings::for_project(&project_root);
+        Self::with_settings(project_root, caller, settings)
+    }
+
+    pub fn with_settings(project_root: PathBuf, caller: Caller, settings: ClientSettings) -> Self {
+        let (client, connected) = new_observed_client(project_root, settings);
         Self {
-            client: new_client(project_root),
+            client,
             caller,
+            call_timeout: settings.call_timeout,
+            connected,
+        }
+    }
+
+    /// Await a call on [`Self::client`], giving up after the configured
+    /// timeout. The timeout starts once the client has connected, so the
+    /// first call isn't cut short while it starts the daemon.
+    ///
+    /// r[impl daemon.client.timeouts]
+    pub async fn call<T, E>(
+        &self,
+        call: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, QueryError<E>> {
+        let Some(limit) = self.call_timeout else {
+            return call.await.map_err(QueryError::Rpc);
+        };
+        let mut call = std::pin::pin!(call);
+        let mut connected = self.connected.clone();
+        tokio::select! {
+            result = &mut call => return result.map_err(QueryError::Rpc),
+            // An error means the connector is gone, and the call with it.
+            _ = connected.wait_for(|connected| *connected) => {}
+        }
+        match tokio::time::timeout(limit, call).await {
+            Ok(result) => result.map_err(QueryError::Rpc),
+            Err(_) => Err(QueryError::TimedOut(limit)),
         }
     }
 
     /// Check for config errors and return a warning banner if present.
     async fn get_config_error_banner(&self) -> Option<String> {
-        match self.client.health().await {
+        match self.call(self.client.health()).await {
             Ok(health) => health.config_error.map(|e| format_config_error_banner(&e)),
             Err(_) => None,
         }
@@ -88,11 +348,28 @@ impl QueryClient {
 
     /// Get coverage status for all specs/implementations
     pub async fn status(&self) -> String {
-        // Fetch status first to establish a single connection/startup path.
-        // Running status+config concurrently on a cold client can race daemon
-        // autostart and introduce extra startup delays.
-        let status_result = self.client.status().await;
-        let config_result = self.client.config().await;
+        // r[impl daemon.roam.batch]
+        // The status, the config summary and the config error banner come
+        // in one round trip.
+        let request = BatchRequest {
+            status: true,
+            config: true,
+            health: true,
+            ..Default::default()
+        };
+        let (status_result, config, banner) = match self.call(self.client.batch(request)).await {
+            Ok(batch) => (
+                batch
+                    .status
+                    .ok_or_else(|| "the daemon sent no status".to_string()),
+                batch.config,
+                batch
+                    .health
+                    .and_then(|health| health.config_error)
+                    .map(|e| format_config_error_banner(&e)),
+            ),
+            Err(e) => (Err(e.to_string()), None, None),
+        };
 
         let output = match status_result {
             Ok(status) => {
@@ -104,7 +381,7 @@ impl QueryClient {
 
                 // Render a plain-English config summary for each spec/impl,
                 // so agents and new users understand what is being analyzed.
-                if let Ok(config) = config_result {
+                if let Some(config) = config {
                     for spec in &config.specs {
                         let example_rule =
                             format!("{}[{}.some-requirement]", spec.prefix, spec.name);
@@ -167,6 +444,7 @@ impl QueryClient {
                         " {} of {} have a verification reference.\n",
                         verified, total
                     ));
+                    output.push_str(&format_file_status(&impl_status.by_file));
                 }
 
                 output.push_str("\n---\n");
@@ -196,20 +474,56 @@ impl QueryClient {
             Err(e) => format!("Error: {e}"),
         };
 
+        match banner {
+            Some(banner) => format!("{}{}", banner, output),
+            None => output,
+        }
+    }
+
+    /// Which impls cover each rule, for specs with more than one impl
+    // r[impl daemon.cli.query.status-by-rule]
+    pub async fn status_by_rule(&self) -> String {
+        let output = match self.call(self.client.status()).await {
+            Ok(status) if status.by_rule.is_empty() => {
+                "No spec has more than one implementation; run `tracey query status` instead.\n"
+                    .to_string()
+            }
+            Ok(status) => {
+                let mut output = format_rule_impls(&status.by_rule);
+                output.push_str("\n---\n");
+                output.push_str(&self.hint(
+                    "tracey query uncovered --spec_impl <spec/impl>",
+                    "tracey_uncovered with a spec_impl parameter to list one impl's uncovered rules",
+                ));
+                output
+            }
+            Err(e) => format!("Error: {e}"),
+        };
+
         self.with_config_banner(output).await
     }
 
     /// Get rules without implementation references
-    pub async fn uncovered(&self, spec_impl: Option<&str>, prefix: Option<&str>) -> String {
+    pub async fn uncovered(
+        &self,
+        spec_impl: Option<&str>,
+        prefix: Option<&str>,
+        owner: Option<&str>,
+        list: ListArgs,
+    ) -> String {
         let (spec, impl_name) = parse_spec_impl(spec_impl);
 
         let req = UncoveredRequest {
             spec,
             impl_name,
             prefix: prefix.map(String::from),
+            owner: owner.map(String::from),
+            sort: list.sort,
+            limit: list.limit,
+            offset: list.offset,
         };
 
-        let output = match self.client.uncovered(req).await {
+        let output = match self.call(self.client.uncovered(req)).await {
             Ok(response) => {
                 let mut output = format!(
                     "{}/{}: {} uncovered out of {} rules\n\n",
@@ -223,12 +537,15 @@ impl QueryClient {
                     if !section.rules.is_empty() {
                         output.push_str(&format!("## {}\n", section.section));
                         for rule in &section.rules {
-                            output.push_str(&format!("  - {}\n", rule.id));
+                            output.push_str(&format_rule_ref(rule));
                         }
                         output.push('\n');
                     }
                 }
 
+                let shown: usize = response.by_section.iter().map(|s| s.rules.len()).sum();
+                output.push_str(&list.page_note(shown, response.uncovered_count));
+
                 output.push_str("---\n");
                 output.push_str(&self.hint(
                     "tracey query rule <rule-id>",
@@ -248,35 +565,28 @@ impl QueryClient {
     }
 
     /// Get rules without verification references
-    pub async fn untested(&self, spec_impl: Option<&str>, prefix: Option<&str>) -> String {
+    pub async fn untested(
+        &self,
+        spec_impl: Option<&str>,
+        prefix: Option<&str>,
+        only_implemented: bool,
+        list: ListArgs,
+    ) -> String {
         let (spec, impl_name) = parse_spec_impl(spec_impl);
 
         let req = UntestedRequest {
             spec,
             impl_name,
             prefix: prefix.map(String::from),
+            sort: list.sort,
+            limit: list.limit,
+            offset: list.offset,
+            only_implemented,
         };
 
-        let output = match self.client.untested(req).await {
+        let output = match self.call(self.client.untested(req)).await {
             Ok(response) => {
-                let mut output = format!(
-                    "{}/{}: {} untested (impl but no verify) out of {} rules\n\n",
-                    response.spec,
-                    response.impl_name,
-                    response.untested_count,
-                    response.total_rules
-                );
-
-                for section in &response.by_section {
-                    if !section.rules.is_empty() {
-                        output.push_str(&format!("## {}\n", section.section));
-                        for rule in &section.rules {
-                            output.push_str(&format!("  - {}\n", rule.id));
-                        }
-                        output.push('\n');
-                    }
-                }
-
+                let mut output = format_untested(&response, &list);
                 output.push_str("---\n");
                 output.push_str(&self.hint(
                     "tracey query rule <rule-id>",
@@ -305,7 +615,7 @@ impl QueryClient {
             path: path.map(String::from),
         };
 
-        let output = match self.client.unmapped(req).await {
+        let output = match self.call(self.client.unmapped(req)).await {
             Ok(response) => {
                 let mut output = format!(
                     "{}/{}: {} unmapped code units out of {} total\n\n",
@@ -374,16 +684,24 @@ impl QueryClient {
     }
 
     /// Get stale references (code pointing to older rule versions)
-    pub async fn stale(&self, spec_impl: Option<&str>, prefix: Option<&str>) -> String {
+    pub async fn stale(
+        &self,
+        spec_impl: Option<&str>,
+        prefix: Option<&str>,
+        list: ListArgs,
+    ) -> String {
         let (spec, impl_name) = parse_spec_impl(spec_impl);
 
         let req = StaleRequest {
             spec,
             impl_name,
             prefix: prefix.map(String::from),
+            sort: list.sort,
+            limit: list.limit,
+            offset: list.offset,
         };
 
-        let output = match self.client.stale(req).await {
+        let output = match self.call(self.client.stale(req)).await {
             Ok(response) => {
                 if response.stale_count == 0 {
                     format!(
@@ -417,7 +735,9 @@ impl QueryClient {
                         ));
                     }
 
-                    output.push_str("\n---\n");
+                    output.push('\n');
+                    output.push_str(&list.page_note(response.refs.len(), response.stale_count));
+                    output.push_str("---\n");
                     output.push_str(&self.hint(
                         "tracey query rule <rule-id>",
                         "tracey_rule to see the full rule text and version diff",
@@ -436,58 +756,139 @@ impl QueryClient {
         self.with_config_banner(output).await
     }
 
-    pub async fn rule(&self, rule_id: &str) -> String {
-        let Some(rule_id) = parse_rule_id(rule_id) else {
-            return "Error: invalid rule ID".to_string();
+    /// Count everything a gate's list query matches, without fetching the entries.
+    pub async fn gate_count(&self, gate: &FailGate) -> eyre::Result<usize> {
+        let (spec, impl_name) = parse_spec_impl(gate.spec_impl.as_deref());
+        let prefix = gate.prefix.clone();
+        let count = match gate.list {
+            GateList::Uncovered => {
+                let req = UncoveredRequest {
+                    spec,
+                    impl_name,
+                    prefix,
+                    owner: gate.owner.clone(),
+                    sort: None,
+                    limit: Some(0),
+                    offset: None,
+                };
+                let resp = self
+                    .call(self.client.uncovered(req))
+                    .await
+                    .map_err(|e| eyre::eyre!("{e}"))?;
+                resp.uncovered_count
+            }
+            GateList::Untested { only_implemented } => {
+                let req = UntestedRequest {
+                    spec,
+                    impl_name,
+                    prefix,
+                    sort: None,
+                    limit: Some(0),
+                    offset: None,
+                    only_implemented,
+                };
+                let resp = self
+                    .call(self.client.untested(req))
+                    .await
+                    .map_err(|e| eyre::eyre!("{e}"))?;
+                resp.untested_count
+            }
+            GateList::Stale => {
+                let req = StaleRequest {
+                    spec,
+                    impl_name,
+                    prefix,
+                    sort: None,
+                    limit: Some(0),
+                    offset: None,
+                };
+                let resp = self
+                    .call(self.client.stale(req))
+                    .await
+                    .map_err(|e| eyre::eyre!("{e}"))?;
+                resp.stale_count
+            }
+        };
+        Ok(count)
+    }
+
+    pub async fn matrix(&self, spec: Option<&str>, prefix: Option<&str>) -> String {
+        let req = MatrixRequest {
+            spec: spec.map(String::from),
+            prefix: prefix.map(String::from),
         };
 
-        let output = match self.client.rule(rule_id.clone()).await {
-            Ok(Some(info)) => format_rule_info(&info),
-            Ok(None) => format!("Rule not found: {}", rule_id),
+        let output = match self.call(self.client.matrix(req)).await {
+            Ok(response) if response.specs.is_empty() => match spec {
+                Some(spec) => format!("Unknown spec: {}\n", spec),
+                None => "No specs configured.\n".to_string(),
+            },
+            Ok(response) => {
+                let mut output = String::new();
+                for matrix in &response.specs {
+                    output.push_str(&format_matrix(matrix));
+                    output.push('\n');
+                }
+                output.push_str("✓ implemented, ✗ not implemented, stale: only stale references\n");
+                output.push_str(&self.hint(
+                    "tracey query uncovered --spec_impl <spec/impl>",
+                    "tracey_uncovered with a spec_impl parameter to list the rules behind each ✗",
+                ));
+                output
+            }
             Err(e) => format!("Error: {e}"),
         };
 
         self.with_config_banner(output).await
     }
 
-    pub async fn rules(&self, rule_ids: &[String]) -> String {
-        let mut sections = Vec::new();
+    pub async fn coverage(
+        &self,
+        spec_impl: Option<&str>,
+        by_dir: bool,
+        depth: Option<u32>,
+        sort: Option<DirCoverageSort>,
+    ) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
 
-        for raw_id in rule_ids {
-            let Some(rule_id) = parse_rule_id(raw_id) else {
-                sections.push(format!("Error: invalid rule ID '{}'", raw_id));
-                continue;
-            };
+        let req = DirCoverageRequest {
+            spec,
+            impl_name,
+            depth,
+            sort,
+        };
 
-            match self.client.rule(rule_id.clone()).await {
-                Ok(Some(info)) => sections.push(format_rule_info(&info)),
-                Ok(None) => sections.push(format!("Rule not found: {}", rule_id)),
-                Err(e) => sections.push(format!("Error querying '{}': {e}", rule_id)),
+        let output = match self.call(self.client.dir_coverage(req)).await {
+            Ok(response) => {
+                let mut output = format_dir_coverage(&response, by_dir);
+                output.push_str("\n---\n");
+                output.push_str(&self.hint(
+                    "tracey query unmapped --path <dir>",
+                    "tracey_unmapped with a path parameter to list the unmapped code units",
+                ));
+                output
             }
-        }
+            Err(e) => format!("Error: {e}"),
+        };
 
-        let output = sections.join("\n---\n\n");
         self.with_config_banner(output).await
     }
 
-    /// Display current configuration
-    pub async fn config(&self) -> String {
-        let output = match self.client.config().await {
-            Ok(config) => {
-                let mut output = String::from("# Tracey Configuration\n\n");
-
-                for spec in &config.specs {
-                    output.push_str(&format!("## Spec: {}\n", spec.name));
-                    output.push_str(&format!("  Prefix: {}\n", spec.prefix));
-                    if let Some(source) = &spec.source {
-                        output.push_str(&format!("  Source: {}\n", source));
-                    }
-                    output.push_str(&format!(
-                        "  Implementations: {}\n\n",
-                        spec.implementations.join(", ")
+    pub async fn refs(&self, file: &str) -> String {
+        let path = resolve_source_path(file);
+        let output = match self
+            .call(self.client.file_refs(FileRefsRequest { path }))
+            .await
+        {
+            Ok(response) => {
+                let mut output = format_file_refs(&response);
+                if !response.refs.is_empty() {
+                    output.push_str("\n---\n");
+                    output.push_str(&self.hint(
+                        "tracey query rule <rule-id>",
+                        "tracey_rule to see the full rule text",
                     ));
                 }
-
                 output
             }
             Err(e) => format!("Error: {e}"),
@@ -496,290 +897,2050 @@ impl QueryClient {
         self.with_config_banner(output).await
     }
 
-    pub async fn reload(&self) -> String {
-        let output = match self.client.reload().await {
-            Ok(response) => format!(
-                "Reload complete (version {}, took {}ms)",
-                response.version, response.rebuild_time_ms
-            ),
+    pub async fn search(&self, query: &str, limit: Option<u32>) -> String {
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let output = match self
+            .call(self.client.search(query.to_string(), limit))
+            .await
+        {
+            Ok(results) => {
+                let mut output = format_search_results(query, &results);
+                if results.iter().any(|r| r.kind == "rule") {
+                    output.push_str("\n---\n");
+                    output.push_str(&self.hint(
+                        "tracey query rule <rule-id>",
+                        "tracey_rule to see the full rule text",
+                    ));
+                }
+                output
+            }
             Err(e) => format!("Error: {e}"),
         };
 
         self.with_config_banner(output).await
     }
 
-    pub async fn validate(&self, spec_impl: Option<&str>) -> String {
-        let output = if spec_impl.is_some() {
-            // If a specific spec/impl was requested, validate just that one.
-            let (spec, impl_name) = parse_spec_impl(spec_impl);
-            let req = ValidateRequest { spec, impl_name };
-            match self.client.validate(req).await {
-                Ok(result) => format_validation_result(&result),
-                Err(e) => format!("Error: {e}"),
-            }
-        } else {
-            // No filter provided: validate ALL spec/impl combinations.
-            let status = match self.client.status().await {
-                Ok(s) => s,
-                Err(e) => {
-                    return self
-                        .with_config_banner(format!("Error getting status: {e}"))
-                        .await;
-                }
-            };
-
-            if status.impls.is_empty() {
-                "No spec/impl combinations configured.".to_string()
-            } else {
-                let mut output = String::new();
-                let mut total_errors = 0;
-                let mut unique_unknown_rules: BTreeSet<String> = BTreeSet::new();
-                let mut unknown_reference_counts: BTreeMap<String, usize> = BTreeMap::new();
-
-                for impl_status in &status.impls {
-                    let req = ValidateRequest {
-                        spec: Some(impl_status.spec.clone()),
-                        impl_name: Some(impl_status.impl_name.clone()),
-                    };
+    pub async fn dead(&self, spec_impl: Option<&str>, days: Option<u32>) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
 
-                    match self.client.validate(req).await {
-                        Ok(result) => {
-                            total_errors += result.error_count;
-                            let mut unknown_for_impl = 0usize;
-                            let mut non_unknown_errors = Vec::new();
-                            for error in &result.errors {
-                                if error.code == ValidationErrorCode::UnknownRequirement {
-                                    if let Some((rule_id, reference_text)) =
-                                        unknown_rule_reference_from_error(error)
-                                    {
-                                        unknown_for_impl += 1;
-                                        unique_unknown_rules.insert(rule_id.clone());
-                                        *unknown_reference_counts
-                                            .entry(reference_text)
-                                            .or_insert(0) += 1;
-                                    } else {
-                                        non_unknown_errors.push(error.clone());
-                                    }
-                                } else {
-                                    non_unknown_errors.push(error.clone());
-                                }
-                            }
+        let req = DeadRequest {
+            spec,
+            impl_name,
+            days,
+        };
 
-                            if non_unknown_errors.is_empty() {
-                                if unknown_for_impl == 0 {
-                                    output.push_str(&format!(
-                                        "✓ {}/{}: No validation errors found\n\n",
-                                        result.spec, result.impl_name
-                                    ));
-                                } else {
-                                    output.push_str(&format!(
-                                        "✗ {}/{}: {} unknown rule reference(s), details shown in global summary below\n\n",
-                                        result.spec, result.impl_name, unknown_for_impl
-                                    ));
-                                }
-                            } else {
-                                let non_unknown_result = ValidationResult {
-                                    spec: result.spec.clone(),
-                                    impl_name: result.impl_name.clone(),
-                                    errors: non_unknown_errors,
-                                    warning_count: result.warning_count,
-                                    error_count: result
-                                        .errors
-                                        .iter()
-                                        .filter(|e| {
-                                            e.code != ValidationErrorCode::UnknownRequirement
-                                        })
-                                        .count(),
-                                };
-                                output.push_str(&format_validation_result(&non_unknown_result));
-                                if unknown_for_impl > 0 {
-                                    output.push_str(&format!(
-                                        "\n  - [UnknownRequirement] {} repeated unknown rule reference(s) hidden; see global summary below\n",
-                                        unknown_for_impl
-                                    ));
-                                }
-                                output.push('\n');
-                            }
-                        }
-                        Err(e) => {
-                            output.push_str(&format!(
-                                "✗ {}/{}: Error: {e}\n\n",
-                                impl_status.spec, impl_status.impl_name
-                            ));
-                        }
+        let output = match self.call(self.client.dead(req)).await {
+            Ok(response) => {
+                let mut output = if response.rules.is_empty() {
+                    format!(
+                        "{}/{}: no rules unreferenced for {}+ days ({} rules total)\n",
+                        response.spec, response.impl_name, response.days, response.total_rules
+                    )
+                } else {
+                    let mut output = format!(
+                        "# Dead rules in {}/{}\n\n{} rule(s) with no references for {}+ days\n\n",
+                        response.spec,
+                        response.impl_name,
+                        response.rules.len(),
+                        response.days
+                    );
+                    for rule in &response.rules {
+                        let location = match (&rule.source_file, rule.source_line) {
+                            (Some(file), Some(line)) => format!(" ({}:{})", file, line),
+                            _ => String::new(),
+                        };
+                        output.push_str(&format!(
+                            "  - {}: {} days{}\n",
+                            rule.id, rule.age_days, location
+                        ));
                     }
-                }
+                    output
+                };
 
-                output.push_str("---\n");
-                output.push_str(&format!(
-                    "Validated {} spec/impl combination(s), {} total error(s)\n",
-                    status.impls.len(),
-                    total_errors
-                ));
-                if !unknown_reference_counts.is_empty() {
+                if response.unknown_age > 0 {
                     output.push_str(&format!(
-                        "Unique unknown rules: {} ({} total occurrences)\n",
-                        unique_unknown_rules.len(),
-                        unknown_reference_counts.values().sum::<usize>()
+                        "\n{} unreferenced rule(s) have no git history and were skipped\n",
+                        response.unknown_age
+                    ));
+                }
+
+                if !response.rules.is_empty() {
+                    output.push_str("\n---\n");
+                    output.push_str(&self.hint(
+                        "tracey query rule <rule-id>",
+                        "tracey_rule to see the full rule text",
+                    ));
+                    output.push_str(&self.hint(
+                        "tracey query dead --days <n>",
+                        "tracey_dead with a days parameter to change the age threshold",
                     ));
-                    output.push_str("Top unknown references:\n");
-                    let mut top = unknown_reference_counts.into_iter().collect::<Vec<_>>();
-                    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
-                    for (reference, count) in top.into_iter().take(20) {
-                        output.push_str(&format!("  - {} ({}x)\n", reference, count));
-                    }
                 }
-                output.push_str(&self.hint(
-                    "tracey query validate <spec>/<impl>",
-                    "tracey_validate with a spec_impl parameter to validate a specific one (e.g., \"my-spec/rust\")",
-                ));
                 output
             }
+            Err(e) => format!("Error: {e}"),
         };
 
         self.with_config_banner(output).await
     }
 
-    pub async fn config_exclude(&self, spec_impl: Option<&str>, pattern: &str) -> String {
+    // r[impl daemon.cli.query.unrefined]
+    pub async fn unrefined(&self, spec_impl: Option<&str>) -> String {
         let (spec, impl_name) = parse_spec_impl(spec_impl);
 
-        let req = ConfigPatternRequest {
-            spec,
-            impl_name,
-            pattern: pattern.to_string(),
-        };
+        let req = UnrefinedRequest { spec, impl_name };
 
-        let output = match self.client.config_add_exclude(req).await {
-            Ok(()) => format!("Added exclude pattern: {pattern}"),
+        let output = match self.call(self.client.unrefined(req)).await {
+            Ok(response) => format_unrefined(&response),
             Err(e) => format!("Error: {e}"),
         };
 
         self.with_config_banner(output).await
     }
 
-    pub async fn config_include(&self, spec_impl: Option<&str>, pattern: &str) -> String {
+    // r[impl daemon.cli.query.unexecuted]
+    pub async fn unexecuted(&self, spec_impl: Option<&str>, prefix: Option<&str>) -> String {
         let (spec, impl_name) = parse_spec_impl(spec_impl);
 
-        let req = ConfigPatternRequest {
+        let req = UnexecutedRequest {
             spec,
             impl_name,
-            pattern: pattern.to_string(),
+            prefix: prefix.map(String::from),
         };
 
-        let output = match self.client.config_add_include(req).await {
-            Ok(()) => format!("Added include pattern: {pattern}"),
-            Err(e) => format!("Error: {e}"),
-        };
+        let output = match self.call(self.client.unexecuted(req)).await {
+            Ok(response) => {
+                let mut output = format_unexecuted(&response);
+                if !response.by_section.is_empty() {
+                    output.push_str("\n---\n");
+                    output.push_str(&self.hint(
+                        "tracey query rule <rule-id>",
+                        "tracey_rule to see the full rule text",
+                    ));
+                }
+                output
+            }
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn trend(&self, spec_impl: Option<&str>, since: Option<&str>) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
+        let since_secs = match since.map(crate::history::parse_age).transpose() {
+            Ok(since_secs) => since_secs,
+            Err(e) => return format!("Error: {e}"),
+        };
+
+        let req = TrendRequest {
+            spec,
+            impl_name,
+            since_secs,
+        };
+
+        let output = match self.call(self.client.coverage_trend(req)).await {
+            Ok(response) => {
+                let mut output = format_trend(&response, since);
+                output.push_str("\n---\n");
+                output.push_str(&self.hint(
+                    "tracey snapshot record",
+                    "`tracey snapshot record` in a terminal to add a data point now",
+                ));
+                output
+            }
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn recent(&self, since: &str) -> String {
+        let req = RecentRequest {
+            since: since.to_string(),
+        };
+        let output = match self.call(self.client.recent(req)).await {
+            Ok(response) => format_recent(&response),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    /// Look up a rule, falling back to fuzzy matching (`authlogin`,
+    /// `auth.logn`) when no rule has exactly this ID.
+    ///
+    /// r[impl daemon.cli.query.rule-fuzzy]
+    pub async fn lookup_rule(&self, rule_id: RuleId) -> Result<RuleLookup, String> {
+        match self.call(self.client.rule(rule_id.clone())).await {
+            Ok(Some(info)) => return Ok(RuleLookup::Found(info)),
+            Ok(None) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        let known = self
+            .call(self.client.find_rules("*".to_string()))
+            .await
+            .map_err(|e| e.to_string())?;
+        let matches = crate::rule_suggestions::fuzzy_rule_matches(
+            &rule_id.base,
+            &known,
+            RULE_CANDIDATE_LIMIT,
+        );
+        match matches.as_slice() {
+            [] => Ok(RuleLookup::NotFound),
+            [only] => match self.call(self.client.rule(only.clone())).await {
+                Ok(Some(info)) => Ok(RuleLookup::Matched(info)),
+                Ok(None) => Ok(RuleLookup::NotFound),
+                Err(e) => Err(e.to_string()),
+            },
+            _ => Ok(RuleLookup::Candidates(matches)),
+        }
+    }
+
+    pub async fn rule(&self, rule_id: &str) -> String {
+        let Some(rule_id) = parse_rule_id(rule_id) else {
+            return "Error: invalid rule ID".to_string();
+        };
+
+        let output = match self.lookup_rule(rule_id.clone()).await {
+            Ok(RuleLookup::Found(info)) => format_rule_info(&info),
+            Ok(RuleLookup::Matched(info)) => {
+                format!(
+                    "(Showing {} for {})\n\n{}",
+                    info.id,
+                    rule_id,
+                    format_rule_info(&info)
+                )
+            }
+            Ok(RuleLookup::Candidates(candidates)) => format_rule_candidates(&rule_id, &candidates),
+            Ok(RuleLookup::NotFound) => format!("Rule not found: {}", rule_id),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn rules(&self, rule_ids: &[String], blame: bool) -> String {
+        let mut sections = Vec::new();
+        let mut infos: Vec<RuleInfo> = Vec::new();
+        let mut expanded = false;
+
+        for raw_id in rule_ids {
+            // r[impl daemon.cli.query.rule-glob]
+            let ids = if is_rule_pattern(raw_id) {
+                expanded = true;
+                match self.call(self.client.find_rules(raw_id.clone())).await {
+                    Ok(ids) if ids.is_empty() => {
+                        sections.push(format!("No rules match: {}", raw_id));
+                        continue;
+                    }
+                    Ok(ids) => ids,
+                    Err(e) => {
+                        sections.push(format!("Error querying '{}': {e}", raw_id));
+                        continue;
+                    }
+                }
+            } else {
+                let Some(rule_id) = parse_rule_id(raw_id) else {
+                    sections.push(format!("Error: invalid rule ID '{}'", raw_id));
+                    continue;
+                };
+                vec![rule_id]
+            };
+
+            for rule_id in ids {
+                if infos.iter().any(|info| info.id == rule_id) {
+                    continue;
+                }
+                let mut note = String::new();
+                let found = match self.lookup_rule(rule_id.clone()).await {
+                    Ok(RuleLookup::Found(info)) => Ok(Some(info)),
+                    Ok(RuleLookup::Matched(info)) => {
+                        note = format!("(Showing {} for {})\n\n", info.id, rule_id);
+                        Ok(Some(info))
+                    }
+                    Ok(RuleLookup::Candidates(candidates)) => {
+                        sections.push(format_rule_candidates(&rule_id, &candidates));
+                        continue;
+                    }
+                    Ok(RuleLookup::NotFound) => Ok(None),
+                    Err(e) => Err(e),
+                };
+                match found {
+                    Ok(Some(info)) if infos.iter().any(|known| known.id == info.id) => {}
+                    Ok(Some(info)) => {
+                        let rule_id = info.id.clone();
+                        let mut section = note;
+                        section.push_str(&format_rule_info(&info));
+                        // r[impl daemon.cli.query.rule-blame]
+                        if blame {
+                            match self.call(self.client.rule_blame(rule_id.clone())).await {
+                                Ok(Some(blame)) => section.push_str(&format_rule_blame(&blame)),
+                                Ok(None) => {}
+                                Err(e) => {
+                                    section.push_str(&format!("\nError running git blame: {e}\n"))
+                                }
+                            }
+                        }
+                        sections.push(section);
+                        infos.push(info);
+                    }
+                    Ok(None) => sections.push(format!("Rule not found: {}", rule_id)),
+                    Err(e) => sections.push(format!("Error querying '{}': {e}", rule_id)),
+                }
+            }
+        }
+
+        if expanded && !infos.is_empty() {
+            sections.push(format_rule_summary(&infos));
+        }
+
+        let output = sections.join("\n---\n\n");
+        self.with_config_banner(output).await
+    }
+
+    /// Display current configuration
+    pub async fn config(&self) -> String {
+        let output = match self.call(self.client.config()).await {
+            Ok(config) => {
+                let mut output = String::from("# Tracey Configuration\n\n");
+
+                for spec in &config.specs {
+                    output.push_str(&format!("## Spec: {}\n", spec.name));
+                    output.push_str(&format!("  Prefix: {}\n", spec.prefix));
+                    if let Some(source) = &spec.source {
+                        output.push_str(&format!("  Source: {}\n", source));
+                    }
+                    output.push_str(&format!(
+                        "  Implementations: {}\n",
+                        spec.implementations.join(", ")
+                    ));
+                    if !spec.no_default_excludes.is_empty() {
+                        output.push_str(&format!(
+                            "  Default excludes disabled for: {}\n",
+                            spec.no_default_excludes.join(", ")
+                        ));
+                    }
+                    // r[impl config.impl.skip-units]
+                    for skipped in &spec.skipped_units {
+                        output.push_str(&format!(
+                            "  Not counted as unmapped in {}: {}\n",
+                            skipped.impl_name,
+                            skipped.rules.join("; ")
+                        ));
+                    }
+                    output.push('\n');
+                }
+
+                // r[impl config.impl.default-excludes]
+                if !config.default_excludes.is_empty() {
+                    output.push_str(&format!(
+                        "Default excludes: {}\n",
+                        config.default_excludes.join(", ")
+                    ));
+                }
+
+                output
+            }
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn reload(&self) -> String {
+        let output = match self.call(self.client.reload()).await {
+            Ok(response) => format!(
+                "Reload complete (version {}, took {}ms)",
+                response.version, response.rebuild_time_ms
+            ),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn refetch(&self) -> String {
+        let output = match self.call(self.client.refetch()).await {
+            Ok(response) => format!(
+                "Re-fetched remote specs and reloaded (version {}, took {}ms)",
+                response.version, response.rebuild_time_ms
+            ),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn spec_outline(&self, spec_impl: Option<&str>) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
+        let req = SpecOutlineRequest { spec, impl_name };
+
+        let output = match self.call(self.client.spec_outline(req)).await {
+            Ok(response) => format_spec_outline(&response),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn spec_drift(&self) -> String {
+        let output = match self.call(self.client.spec_drift()).await {
+            Ok(response) => format_spec_drift(&response),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn validate(&self, spec_impl: Option<&str>) -> String {
+        let output = if spec_impl.is_some() {
+            // If a specific spec/impl was requested, validate just that one.
+            let (spec, impl_name) = parse_spec_impl(spec_impl);
+            let req = ValidateRequest { spec, impl_name };
+            match self.call(self.client.validate(req)).await {
+                Ok(result) => format_validation_result(&result),
+                Err(e) => format!("Error: {e}"),
+            }
+        } else {
+            // No filter provided: validate ALL spec/impl combinations.
+            let results = match self.call(self.client.validate_all()).await {
+                Ok(results) => results,
+                Err(e) => {
+                    return self
+                        .with_config_banner(format!("Error validating: {e}"))
+                        .await;
+                }
+            };
+
+            if results.is_empty() {
+                "No spec/impl combinations configured.".to_string()
+            } else {
+                let mut output = String::new();
+                let mut total_errors = 0;
+                let mut unique_unknown_rules: BTreeSet<String> = BTreeSet::new();
+                let mut unknown_reference_counts: BTreeMap<String, usize> = BTreeMap::new();
+                let mut skipped_files: BTreeSet<String> = BTreeSet::new();
+
+                for result in &results {
+                    total_errors += result.error_count;
+                    // r[impl config.file-limits]
+                    skipped_files.extend(
+                        result
+                            .errors
+                            .iter()
+                            .filter(|e| e.code == ValidationErrorCode::SkippedFile)
+                            .filter_map(|e| e.file.clone()),
+                    );
+                    let mut unknown_for_impl = 0usize;
+                    let mut non_unknown_errors = Vec::new();
+                    for error in &result.errors {
+                        if error.code == ValidationErrorCode::UnknownRequirement {
+                            if let Some((rule_id, reference_text)) =
+                                unknown_rule_reference_from_error(error)
+                            {
+                                unknown_for_impl += 1;
+                                unique_unknown_rules.insert(rule_id.clone());
+                                *unknown_reference_counts.entry(reference_text).or_insert(0) += 1;
+                            } else {
+                                non_unknown_errors.push(error.clone());
+                            }
+                        } else {
+                            non_unknown_errors.push(error.clone());
+                        }
+                    }
+
+                    if non_unknown_errors.is_empty() {
+                        if unknown_for_impl == 0 {
+                            output.push_str(&format!(
+                                "✓ {}/{}: No validation errors found\n\n",
+                                result.spec, result.impl_name
+                            ));
+                        } else {
+                            output.push_str(&format!(
+                                "✗ {}/{}: {} unknown rule reference(s), details shown in global summary below\n\n",
+                                result.spec, result.impl_name, unknown_for_impl
+                            ));
+                        }
+                    } else {
+                        let non_unknown_result = ValidationResult {
+                            spec: result.spec.clone(),
+                            impl_name: result.impl_name.clone(),
+                            errors: non_unknown_errors,
+                            warning_count: result.warning_count,
+                            error_count: result
+                                .errors
+                                .iter()
+                                .filter(|e| {
+                                    e.code != ValidationErrorCode::UnknownRequirement
+                                        && !e.code.is_warning()
+                                })
+                                .count(),
+                        };
+                        output.push_str(&format_validation_result(&non_unknown_result));
+                        if unknown_for_impl > 0 {
+                            output.push_str(&format!(
+                                "\n  - [UnknownRequirement] {} repeated unknown rule reference(s) hidden; see global summary below\n",
+                                unknown_for_impl
+                            ));
+                        }
+                        output.push('\n');
+                    }
+                }
+
+                output.push_str("---\n");
+                output.push_str(&format!(
+                    "Validated {} spec/impl combination(s), {} total error(s)\n",
+                    results.len(),
+                    total_errors
+                ));
+                if !skipped_files.is_empty() {
+                    output.push_str(&format!(
+                        "Skipped files: {} (too large or binary, not scanned)\n",
+                        skipped_files.len()
+                    ));
+                }
+                if !unknown_reference_counts.is_empty() {
+                    output.push_str(&format!(
+                        "Unique unknown rules: {} ({} total occurrences)\n",
+                        unique_unknown_rules.len(),
+                        unknown_reference_counts.values().sum::<usize>()
+                    ));
+                    output.push_str("Top unknown references:\n");
+                    let mut top = unknown_reference_counts.into_iter().collect::<Vec<_>>();
+                    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                    for (reference, count) in top.into_iter().take(20) {
+                        output.push_str(&format!("  - {} ({}x)\n", reference, count));
+                    }
+                }
+                output.push_str(&self.hint(
+                    "tracey query validate <spec>/<impl>",
+                    "tracey_validate with a spec_impl parameter to validate a specific one (e.g., \"my-spec/rust\")",
+                ));
+                output
+            }
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn config_exclude(&self, spec_impl: Option<&str>, pattern: &str) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
+
+        let req = ConfigPatternRequest {
+            spec,
+            impl_name,
+            pattern: pattern.to_string(),
+        };
+
+        let output = match self.call(self.client.config_add_exclude(req)).await {
+            Ok(()) => format!("Added exclude pattern: {pattern}"),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+
+    pub async fn config_include(&self, spec_impl: Option<&str>, pattern: &str) -> String {
+        let (spec, impl_name) = parse_spec_impl(spec_impl);
+
+        let req = ConfigPatternRequest {
+            spec,
+            impl_name,
+            pattern: pattern.to_string(),
+        };
+
+        let output = match self.call(self.client.config_add_include(req)).await {
+            Ok(()) => format!("Added include pattern: {pattern}"),
+            Err(e) => format!("Error: {e}"),
+        };
+
+        self.with_config_banner(output).await
+    }
+}
+
+/// Format a single rule's information for display.
+fn format_rule_info(info: &RuleInfo) -> String {
+    let mut output = format!("# {}\n\n{}\n\n", info.id, info.raw);
+
+    if let Some(file) = &info.source_file
+        && let Some(line) = info.source_line
+    {
+        output.push_str(&format!("Defined in: {}:{}\n\n", file, line));
+    }
+
+    // r[impl daemon.cli.query.rule-permalink]
+    if let Some(url) = &info.permalink {
+        output.push_str(&format!("Permalink: {}\n\n", url));
+    }
+
+    // r[impl daemon.cli.query.owner]
+    if !info.owners.is_empty() {
+        output.push_str(&format!("Owners: {}\n\n", info.owners.join(", ")));
+    }
+
+    // r[impl cli.approve]
+    if !info.approvals.is_empty() {
+        let approvals: Vec<String> = info
+            .approvals
+            .iter()
+            .map(|a| format!("{} ({})", a.by, a.date))
+            .collect();
+        output.push_str(&format!("Approved by: {}\n\n", approvals.join(", ")));
+    }
+
+    // r[impl daemon.cli.query.rule-spec-depends]
+    if !info.depends_on.is_empty() {
+        let deps: Vec<String> = info
+            .depends_on
+            .iter()
+            .map(|d| format!("{}:{}", d.spec, d.id))
+            .collect();
+        output.push_str(&format!("Depends on: {}\n\n", deps.join(", ")));
+    }
+
+    // r[impl daemon.cli.query.rule-refines]
+    if !info.refines.is_empty() || !info.refined_by.is_empty() {
+        let ids = |ids: &[RuleId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        if !info.refines.is_empty() {
+            output.push_str(&format!("Refines: {}\n", ids(&info.refines).join(", ")));
+        }
+        if !info.refined_by.is_empty() {
+            output.push_str(&format!(
+                "Refined by: {}\n",
+                ids(&info.refined_by).join(", ")
+            ));
+        }
+        output.push('\n');
+    }
+
+    if let Some(diff) = &info.version_diff {
+        output.push_str(&format!("## Changes from previous version\n\n{diff}\n\n"));
+    }
+
+    for cov in &info.coverage {
+        output.push_str(&format!("\n## {}/{}\n", cov.spec, cov.impl_name));
+        if !cov.impl_refs.is_empty() {
+            output.push_str("Impl references:\n");
+            for r in &cov.impl_refs {
+                output.push_str(&format!("  - {}:{}", r.file, r.line));
+                // r[impl cli.line-coverage]
+                if let Some(lines) = cov
+                    .line_coverage
+                    .iter()
+                    .find(|c| c.file == r.file && c.line == r.line)
+                {
+                    output.push_str(&format!(" ({})", format_executed_lines(lines)));
+                }
+                output.push('\n');
+            }
+        }
+        if !cov.verify_refs.is_empty() {
+            output.push_str("Verify references:\n");
+            for r in &cov.verify_refs {
+                output.push_str(&format!("  - {}:{}", r.file, r.line));
+                // r[impl cli.test-report]
+                if let Some(result) = cov
+                    .test_results
+                    .iter()
+                    .find(|t| t.file == r.file && t.line == r.line)
+                {
+                    let outcome = match result.outcome {
+                        TestOutcome::Passed => "passed",
+                        TestOutcome::Failed => "failed",
+                        TestOutcome::Skipped => "skipped",
+                        TestOutcome::NotRun => "not run",
+                    };
+                    match &result.test {
+                        Some(test) => output.push_str(&format!(" ({test}: {outcome})")),
+                        None => output.push_str(&format!(" ({outcome})")),
+                    }
+                }
+                output.push('\n');
+            }
+        }
+    }
+
+    output
+}
+
+/// E.g. `login: 3/12 lines executed`.
+fn format_executed_lines(lines: &ApiLineCoverage) -> String {
+    let executed = format!(
+        "{}/{} lines executed",
+        lines.executed_lines, lines.total_lines
+    );
+    match &lines.unit {
+        Some(unit) => format!("{unit}: {executed}"),
+        None => executed,
+    }
+}
+
+/// Render `query status --by-rule`: per spec, the rules that no impl or
+/// only some impls cover.
+fn format_rule_impls(by_rule: &[RuleImplsStatus]) -> String {
+    let mut output = String::new();
+    let mut specs: Vec<&str> = by_rule.iter().map(|r| r.spec.as_str()).collect();
+    specs.dedup();
+    for spec in specs {
+        let rules: Vec<&RuleImplsStatus> = by_rule.iter().filter(|r| r.spec == spec).collect();
+        let impls = rules
+            .first()
+            .map_or(0, |r| r.covered_in.len() + r.missing_in.len());
+        let none: Vec<&&RuleImplsStatus> =
+            rules.iter().filter(|r| r.covered_in.is_empty()).collect();
+        let some: Vec<&&RuleImplsStatus> = rules
+            .iter()
+            .filter(|r| !r.covered_in.is_empty() && !r.missing_in.is_empty())
+            .collect();
+
+        output.push_str(&format!(
+            "# {}: {} of {} rules covered by all {} impls\n",
+            spec,
+            rules.len() - none.len() - some.len(),
+            rules.len(),
+            impls
+        ));
+        if !none.is_empty() {
+            output.push_str(&format!("\n## Covered by no impl ({})\n", none.len()));
+            for rule in none {
+                output.push_str(&format!("  - {}\n", rule.id));
+            }
+        }
+        if !some.is_empty() {
+            output.push_str(&format!("\n## Covered by some impls ({})\n", some.len()));
+            for rule in some {
+                output.push_str(&format!(
+                    "  - {}: {} (missing in {})\n",
+                    rule.id,
+                    rule.covered_in.join(", "),
+                    rule.missing_in.join(", ")
+                ));
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Render `query unrefined`: the high-level rules no rule refines yet.
+fn format_unrefined(response: &UnrefinedResponse) -> String {
+    if response.levels.is_empty() {
+        return format!(
+            "{}/{}: no rule refines another rule yet (mark refinements with r[refines ID])\n",
+            response.spec, response.impl_name
+        );
+    }
+
+    let levels = response.levels.join(", ");
+    if response.rules.is_empty() {
+        return format!(
+            "{}/{}: all {} high-level rule(s) are refined (levels: {})\n",
+            response.spec, response.impl_name, response.high_level_rules, levels
+        );
+    }
+
+    let mut output = format!(
+        "# Unrefined rules in {}/{}\n\n{} of {} high-level rule(s) unrefined (levels: {})\n\n",
+        response.spec,
+        response.impl_name,
+        response.rules.len(),
+        response.high_level_rules,
+        levels
+    );
+    for rule in &response.rules {
+        match (&rule.source_file, rule.source_line) {
+            (Some(file), Some(line)) => {
+                output.push_str(&format!("  - {} ({}:{})\n", rule.id, file, line))
+            }
+            _ => output.push_str(&format!("  - {}\n", rule.id)),
+        }
+    }
+    output
+}
+
+fn format_unexecuted(response: &UnexecutedResponse) -> String {
+    if !response.has_report {
+        return "No line coverage report ingested; run `tracey line-coverage ingest lcov.info`\n"
+            .to_string();
+    }
+    let unexecuted: usize = response.by_section.iter().map(|s| s.rules.len()).sum();
+    let mut output = format!(
+        "{}/{}: {} of {} measured rule(s) never executed ({} rules total)\n",
+        response.spec,
+        response.impl_name,
+        unexecuted,
+        response.measured_rules,
+        response.total_rules
+    );
+    for section in &response.by_section {
+        output.push_str(&format!("\n## {}\n", section.section));
+        for rule in &section.rules {
+            output.push_str(&format!("  - {}\n", rule.id));
+            for lines in &rule.line_coverage {
+                output.push_str(&format!(
+                    "      {}:{} ({})\n",
+                    lines.file,
+                    lines.line,
+                    format_executed_lines(lines)
+                ));
+            }
+        }
+    }
+    output
+}
+
+/// Combined coverage summary for the rules a `query rule` pattern expanded to.
+fn format_rule_summary(infos: &[RuleInfo]) -> String {
+    let counts: Vec<(usize, usize)> = infos
+        .iter()
+        .map(|info| {
+            info.coverage.iter().fold((0, 0), |(impls, verifies), cov| {
+                (
+                    impls + cov.impl_refs.len(),
+                    verifies + cov.verify_refs.len(),
+                )
+            })
+        })
+        .collect();
+    let implemented = counts.iter().filter(|(impls, _)| *impls > 0).count();
+    let verified = counts.iter().filter(|(_, verifies)| *verifies > 0).count();
+    let uncovered = counts.iter().filter(|(impls, _)| *impls == 0).count();
+
+    let mut output = format!(
+        "# Summary\n\n{} rule(s): {} implemented, {} verified, {} uncovered\n\n",
+        infos.len(),
+        implemented,
+        verified,
+        uncovered
+    );
+    let id_width = infos
+        .iter()
+        .map(|info| info.id.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for (info, (impls, verifies)) in infos.iter().zip(&counts) {
+        let status = if *impls == 0 {
+            "uncovered".to_string()
+        } else {
+            format!("{} impl, {} verify", impls, verifies)
+        };
+        output.push_str(&format!(
+            "  {:id_width$}  {}\n",
+            info.id.to_string(),
+            status
+        ));
+    }
+    output
+}
+
+/// Format a validation result for display.
+fn format_validation_result(result: &tracey_proto::ValidationResult) -> String {
+    if result.errors.is_empty() {
+        format!(
+            "✓ {}/{}: No validation errors found",
+            result.spec, result.impl_name
+        )
+    } else {
+        let mut output = if result.error_count == 0 {
+            format!(
+                "✓ {}/{}: No validation errors found, {} warning(s)\n",
+                result.spec,
+                result.impl_name,
+                result.errors.len()
+            )
+        } else {
+            format!(
+                "✗ {}/{}: {} error(s) found\n",
+                result.spec, result.impl_name, result.error_count
+            )
+        };
+
+        for error in &result.errors {
+            let location = match (&error.file, error.line) {
+                (Some(f), Some(l)) => format!(" at {}:{}", f, l),
+                (Some(f), None) => format!(" in {}", f),
+                _ => String::new(),
+            };
+
+            if error.code == ValidationErrorCode::StaleRequirement {
+                // r[impl mcp.validation.stale.message-prefix+2]
+                // Build a concise human-readable message from structured fields
+                let stale_msg = if let (Some(ref_id), Some(current_id)) =
+                    (&error.reference_rule_id, error.related_rules.first())
+                {
+                    format!("Reference '{ref_id}' is stale; current rule is '{current_id}'")
+                } else {
+                    error.message.clone()
+                };
+                output.push_str(&format!(
+                    "  - [{:?}] {}{}\n",
+                    error.code, stale_msg, location
+                ));
+                if let Some(current_rule) = error.related_rules.first() {
+                    output.push_str(&format!(
+                        "    Use `tracey query rule '{current_rule}'` to see the full rule and diff.\n",
+                    ));
+                }
+            } else {
+                output.push_str(&format!(
+                    "  - [{:?}] {}{}\n",
+                    error.code, error.message, location
+                ));
+            }
+
+            if !error.related_rules.is_empty()
+                && error.code != ValidationErrorCode::StaleRequirement
+            {
+                output.push_str(&format!(
+                    "    Related rules: {}\n",
+                    error
+                        .related_rules
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        output
+    }
+}
+
+/// Format one spec's rules × impls table.
+fn format_file_refs(response: &FileRefsResponse) -> String {
+    if !response.scanned {
+        return format!(
+            "{} is not a scanned source file of any implementation\n",
+            response.path
+        );
+    }
+    if response.refs.is_empty() {
+        return format!("{}: no requirement references\n", response.path);
+    }
+
+    let rule_count = response
+        .refs
+        .iter()
+        .map(|r| (&r.prefix, &r.rule_id))
+        .collect::<BTreeSet<_>>()
+        .len();
+    let mut output = format!(
+        "# References in {}\n\n{} reference(s) to {} rule(s)\n\n",
+        response.path,
+        response.refs.len(),
+        rule_count
+    );
+
+    let line_width = response
+        .refs
+        .iter()
+        .map(|r| r.line.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let verb_width = response
+        .refs
+        .iter()
+        .map(|r| r.verb.len())
+        .max()
+        .unwrap_or(0);
+    let id_width = response
+        .refs
+        .iter()
+        .map(|r| r.rule_id.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for r in &response.refs {
+        let snippet = r.text.as_deref().map(text_snippet).unwrap_or_default();
+        let detail = match (r.status, &r.current_id) {
+            (FileRefStatus::Current, _) => snippet,
+            (FileRefStatus::Stale, Some(current)) => format!("stale → {current}: {snippet}"),
+            (FileRefStatus::Stale, None) => format!("stale: {snippet}"),
+            (FileRefStatus::Unknown, _) => "unknown rule".to_string(),
+        };
+        let line = format!(
+            "  L{:<line_width$}  {:verb_width$}  {:id_width$}  {}",
+            r.line,
+            r.verb,
+            r.rule_id.to_string(),
+            detail
+        );
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// First non-empty line of `text`, cut down to fit on one terminal line.
+fn text_snippet(text: &str) -> String {
+    const MAX_CHARS: usize = 72;
+
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    if line.chars().count() > MAX_CHARS {
+        let cut: String = line.chars().take(MAX_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+fn format_search_results(query: &str, results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return format!("No results for \"{}\"\n", query);
+    }
+
+    let mut output = format!("# Search: {}\n\n{} result(s)\n\n", query, results.len());
+    for result in results {
+        let content = result.content.as_deref().unwrap_or("");
+        let (kind, location, snippet) = if result.kind == "rule" {
+            let location = match (&result.source_file, result.source_line) {
+                (Some(file), Some(line)) => format!("{}  ({}:{})", result.id, file, line),
+                _ => result.id.clone(),
+            };
+            ("rule", location, text_snippet(content))
+        } else {
+            // Source hits carry up to two lines of context before the match.
+            let matched = content
+                .lines()
+                .nth(result.line.saturating_sub(1).min(2))
+                .unwrap_or(content);
+            (
+                "code",
+                format!("{}:{}", result.id, result.line),
+                text_snippet(matched),
+            )
+        };
+        output.push_str(&format!("  {}  {}\n        {}\n", kind, location, snippet));
+    }
+    output
+}
+
+fn format_dir_coverage(response: &DirCoverageResponse, by_dir: bool) -> String {
+    let total = DirCoverage {
+        path: String::new(),
+        total_units: response.total_units,
+        covered_units: response.covered_units,
+    };
+    let mut output = format!(
+        "{}/{}: {:.0}% of code units mapped ({}/{})\n",
+        response.spec,
+        response.impl_name,
+        total.coverage_percent(),
+        response.covered_units,
+        response.total_units
+    );
+    if !by_dir {
+        return output;
+    }
+
+    output.push('\n');
+    if response.dirs.is_empty() {
+        output.push_str("No directories with code units.\n");
+        return output;
+    }
+    let units_width = response
+        .dirs
+        .iter()
+        .map(|d| format!("{}/{}", d.covered_units, d.total_units).len())
+        .max()
+        .unwrap_or(0);
+    for dir in &response.dirs {
+        let pct = dir.coverage_percent();
+        let filled = (pct / 10.0).round() as usize;
+        let bar: String = "█".repeat(filled) + &"░".repeat(10 - filled);
+        let units = format!("{}/{}", dir.covered_units, dir.total_units);
+        output.push_str(&format!(
+            "{:>4.0}% {}  {:>units_width$}  {}\n",
+            pct, bar, units, dir.path
+        ));
+    }
+    output
+}
+
+/// Render who last touched a rule's text and each of its references.
+fn format_rule_blame(response: &RuleBlameResponse) -> String {
+    let describe = |blame: &Option<BlameInfo>| match blame {
+        Some(blame) => format!(
+            "{} <{}>, {} ({} {})",
+            blame.author,
+            blame.author_mail,
+            crate::history::format_timestamp(blame.author_time.max(0) as u64),
+            &blame.commit[..blame.commit.len().min(8)],
+            blame.summary
+        ),
+        None => "not committed".to_string(),
+    };
+
+    let mut output = String::from("\n## Blame\n\n");
+    output.push_str(&format!("Text: {}\n", describe(&response.text)));
+    if response.refs.is_empty() {
+        output.push_str("No references.\n");
+        return output;
+    }
+    output.push('\n');
+    for r in &response.refs {
+        output.push_str(&format!(
+            "  {} {}/{} {}:{}: {}\n",
+            r.kind,
+            r.spec,
+            r.impl_name,
+            r.file,
+            r.line,
+            describe(&r.blame)
+        ));
+    }
+    output
+}
+
+/// Render recently changed rules, a section per spec.
+fn format_recent(response: &RecentResponse) -> String {
+    let baseline = format!(
+        "snapshot {} ({})",
+        response.baseline_version,
+        crate::history::format_timestamp(response.baseline_recorded_at)
+    );
+    if response.rules.is_empty() {
+        return format!("No rule changed since {baseline}.\n");
+    }
+
+    let mut output = format!(
+        "{} rule(s) changed since {}; the latest snapshot is {}\n",
+        response.rules.len(),
+        baseline,
+        response.latest_version
+    );
+    for spec in response.rules.chunk_by(|a, b| a.spec == b.spec) {
+        output.push_str(&format!("\n## {}\n\n", spec[0].spec));
+        for rule in spec {
+            let change = match rule.change {
+                RecentChange::Added => "added",
+                RecentChange::Removed => "removed",
+                RecentChange::Changed => "changed",
+                RecentChange::Coverage => "coverage",
+            };
+            match &rule.previous_id {
+                Some(previous) => {
+                    output.push_str(&format!("  {:<9} {} (was {})\n", change, rule.id, previous))
+                }
+                None => output.push_str(&format!("  {:<9} {}\n", change, rule.id)),
+            }
+            for coverage in &rule.coverage {
+                let mut moves = Vec::new();
+                if coverage.was_implemented != coverage.implemented {
+                    moves.push(if coverage.implemented {
+                        "now implemented"
+                    } else {
+                        "no longer implemented"
+                    });
+                }
+                if coverage.was_verified != coverage.verified {
+                    moves.push(if coverage.verified {
+                        "now verified"
+                    } else {
+                        "no longer verified"
+                    });
+                }
+                output.push_str(&format!(
+                    "            {}: {}\n",
+                    coverage.impl_name,
+                    moves.join(", ")
+                ));
+            }
+        }
+    }
+    output
+}
+
+/// Render recorded coverage snapshots as one series per spec/impl, each
+/// ending with the change between its first and last point.
+fn format_trend(response: &TrendResponse, since: Option<&str>) -> String {
+    if response.points.is_empty() {
+        return match since {
+            Some(since) => format!("No coverage snapshots recorded in the last {}.\n", since),
+            None => "No coverage snapshots recorded yet.\n".to_string(),
+        };
+    }
+
+    let percent = |count: usize, total: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            count as f64 / total as f64 * 100.0
+        }
+    };
+
+    let mut output = String::new();
+    for series in response
+        .points
+        .chunk_by(|a, b| a.spec == b.spec && a.impl_name == b.impl_name)
+    {
+        let first = &series[0];
+        let last = &series[series.len() - 1];
+        output.push_str(&format!(
+            "## {}/{} ({} snapshot(s))\n\n",
+            first.spec,
+            first.impl_name,
+            series.len()
+        ));
+        for point in series {
+            output.push_str(&format!(
+                "  {}  impl {:>3.0}% ({}/{})  verify {:>3.0}% ({}/{})  stale {}\n",
+                crate::history::format_timestamp(point.recorded_at),
+                percent(point.implemented_rules, point.total_rules),
+                point.implemented_rules,
+                point.total_rules,
+                percent(point.verified_rules, point.total_rules),
+                point.verified_rules,
+                point.total_rules,
+                point.stale_rules
+            ));
+        }
+        output.push_str(&format!(
+            "\n  Change: impl {:+.0}%, verify {:+.0}%, stale {:+}, rules {:+}\n\n",
+            percent(last.implemented_rules, last.total_rules)
+                - percent(first.implemented_rules, first.total_rules),
+            percent(last.verified_rules, last.total_rules)
+                - percent(first.verified_rules, first.total_rules),
+            last.stale_rules as i64 - first.stale_rules as i64,
+            last.total_rules as i64 - first.total_rules as i64
+        ));
+    }
+    output
+}
+
+fn format_matrix(matrix: &SpecMatrix) -> String {
+    let mut output = format!("# Traceability matrix: {}\n\n", matrix.spec);
+    if matrix.rows.is_empty() {
+        output.push_str("No matching rules.\n");
+        return output;
+    }
+
+    let cell_text = |cell: &MatrixCell| {
+        if cell.stale_refs > 0 && cell.impl_refs == 0 {
+            "stale"
+        } else if cell.impl_refs > 0 {
+            "✓"
+        } else {
+            "✗"
+        }
+    };
+    let id_width = matrix
+        .rows
+        .iter()
+        .map(|row| row.id.to_string().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("rule".len());
+    let widths: Vec<usize> = matrix
+        .impls
+        .iter()
+        .map(|name| name.chars().count().max("stale".len()))
+        .collect();
+
+    // Only projects that record approvals get the column.
+    let show_approvals = matrix.rows.iter().any(|row| row.approved);
+
+    let mut line = format!("{:id_width$}", "rule");
+    for (name, width) in matrix.impls.iter().zip(&widths) {
+        line.push_str(&format!("  {:width$}", name));
+    }
+    if show_approvals {
+        line.push_str("  approved");
+    }
+    output.push_str(line.trim_end());
+    output.push('\n');
+    for row in &matrix.rows {
+        let mut line = format!("{:id_width$}", row.id.to_string());
+        for (cell, width) in row.cells.iter().zip(&widths) {
+            line.push_str(&format!("  {:width$}", cell_text(cell)));
+        }
+        if show_approvals {
+            line.push_str(if row.approved { "  ✓" } else { "  ✗" });
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output.push_str(&format!(
+        "\n{} rule(s) × {} impl(s)\n",
+        matrix.rows.len(),
+        matrix.impls.len()
+    ));
+    output
+}
+
+/// Format a spec's section tree with the coverage of each section,
+/// counting nested sections.
+///
+/// r[impl daemon.cli.query.spec]
+fn format_spec_outline(response: &SpecOutlineResponse) -> String {
+    let mut output = format!("# Outline of {}/{}\n\n", response.spec, response.impl_name);
+    if response.sections.is_empty() {
+        output.push_str("No headings\n");
+        return output;
+    }
+
+    let mut depths: Vec<usize> = Vec::with_capacity(response.sections.len());
+    for section in &response.sections {
+        depths.push(section.parent.map_or(0, |p| depths[p] + 1));
+    }
+    let labels: Vec<String> = response
+        .sections
+        .iter()
+        .zip(&depths)
+        .map(|(section, depth)| format!("{}{}", "  ".repeat(*depth), section.title))
+        .collect();
+    let width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+    for (section, label) in response.sections.iter().zip(&labels) {
+        let cov = &section.aggregated;
+        let pad = width - label.chars().count();
+        if cov.total == 0 {
+            output.push_str(&format!("{}{}  -\n", label, " ".repeat(pad)));
+        } else {
+            output.push_str(&format!(
+                "{}{}  {}/{} impl  {}/{} verify\n",
+                label,
+                " ".repeat(pad),
+                cov.impl_count,
+                cov.total,
+                cov.verify_count,
+                cov.total
+            ));
+        }
+    }
+    if !response.unsectioned.is_empty() {
+        output.push_str(&format!(
+            "\n{} rule(s) defined before the first heading\n",
+            response.unsectioned.len()
+        ));
+    }
+    output
+}
+
+/// Format the per-file coverage of a spec/impl, one indented line per file.
+fn format_file_status(files: &[FileStatus]) -> String {
+    let width = files
+        .iter()
+        .map(|f| f.file.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for file in files {
+        output.push_str(&format!(
+            "  {:width$}  {}/{} covered, {} verified",
+            file.file, file.covered_rules, file.total_rules, file.verified_rules,
+        ));
+        if file.stale_rules > 0 {
+            output.push_str(&format!(", {} stale", file.stale_rules));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Format a spec drift report for display.
+fn format_spec_drift(response: &SpecDriftResponse) -> String {
+    if response.specs.is_empty() {
+        return "No remote specs configured (set `rules_url` or `source` on a spec).".to_string();
+    }
+
+    let mut output = String::new();
+    let mut drifted = 0usize;
+    for drift in &response.specs {
+        output.push_str(&format!("## {} ({})\n", drift.spec, drift.source));
+        output.push_str(&format!(
+            "  Locked:   {}\n",
+            drift.locked.as_deref().unwrap_or("(not locked)")
+        ));
+        if let Some(error) = &drift.error {
+            output.push_str(&format!("  Error checking upstream: {}\n\n", error));
+            continue;
+        }
+        output.push_str(&format!(
+            "  Upstream: {}\n",
+            drift.upstream.as_deref().unwrap_or("(unknown)")
+        ));
+
+        if drift.bumped.is_empty() && drift.added.is_empty() && drift.removed.is_empty() {
+            output.push_str("  ✓ Up to date\n\n");
+            continue;
+        }
+        drifted += 1;
+
+        if !drift.bumped.is_empty() {
+            output.push_str(&format!(
+                "  {} rule(s) have newer upstream versions:\n",
+                drift.bumped.len()
+            ));
+            for rule in &drift.bumped {
+                output.push_str(&format!(
+                    "    - {}: +{} → +{}\n",
+                    rule.id, rule.locked_version, rule.upstream_version
+                ));
+            }
+        }
+        if !drift.added.is_empty() {
+            output.push_str(&format!("  {} new upstream rule(s):\n", drift.added.len()));
+            for id in &drift.added {
+                output.push_str(&format!("    - {}\n", id));
+            }
+        }
+        if !drift.removed.is_empty() {
+            output.push_str(&format!(
+                "  {} rule(s) removed upstream:\n",
+                drift.removed.len()
+            ));
+            for id in &drift.removed {
+                output.push_str(&format!("    - {}\n", id));
+            }
+        }
+        output.push('\n');
+    }
+
+    if drifted > 0 {
+        output.push_str(
+            "To sync, bump the spec's `rev` (or run `tracey reload --refetch` for \
+             unpinned sources) and update references to the new rule versions.\n",
+        );
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FailGate, GateList, ListArgs, format_dir_coverage, format_file_refs, format_matrix,
+        format_recent, format_rule_blame, format_rule_info, format_rule_ref, format_rule_summary,
+        format_search_results, format_spec_drift, format_trend, format_untested,
+        format_validation_result, is_rule_pattern,
+    };
+    use tracey_core::parse_rule_id;
+    use tracey_proto::{
+        ApiApproval, ApiCodeRef, ApiLineCoverage, ApiTestResult, BlameInfo, DirCoverage,
+        DirCoverageResponse, FileRef, FileRefStatus, FileRefsResponse, MatrixCell, MatrixRow,
+        RecentChange, RecentCoverage, RecentResponse, RecentRule, RefBlame, RuleBlameResponse,
+        RuleCoverage, RuleInfo, RuleVersionDrift, SearchResult, SpecDrift, SpecDriftResponse,
+        SpecMatrix, TestOutcome, TrendPoint, TrendResponse, ValidationError, ValidationErrorCode,
+        ValidationResult,
+    };
+
+    // r[verify daemon.cli.query.untested-buckets]
+    #[test]
+    fn untested_output_separates_buckets() {
+        let section = |name: &str, id: &str| SectionRules {
+            section: name.to_string(),
+            rules: vec![RuleRef {
+                id: parse_rule_id(id).expect("valid rule id"),
+                text: None,
+                owners: vec![],
+            }],
+        };
+        let response = UntestedResponse {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 5,
+            untested_count: 2,
+            implemented_count: 1,
+            by_section: vec![section("Sessions", "auth.session")],
+            unimplemented_by_section: vec![section("Tokens", "auth.token")],
+            unproven_count: 0,
+            unproven_by_section: vec![],
+        };
+        assert_eq!(
+            format_untested(&response, &ListArgs::default()),
+            "spec/rust: 2 untested out of 5 rules (1 implemented, 1 not implemented)\n\n\
+             # Implemented, not verified\n\n\
+             ## Sessions\n  - auth.session\n\n\
+             # Neither implemented nor verified\n\n\
+             ## Tokens\n  - auth.token\n\n"
+        );
+    }
+
+    // r[verify daemon.cli.query.owner]
+    #[test]
+    fn rule_output_shows_owners() {
+        let rule = |owners: &[&str]| RuleRef {
+            id: parse_rule_id("auth.login").expect("valid rule id"),
+            text: None,
+            owners: owners.iter().map(|o| o.to_string()).collect(),
+        };
+        assert_eq!(format_rule_ref(&rule(&[])), "  - auth.login\n");
+        assert_eq!(
+            format_rule_ref(&rule(&["@platform-team", "alice"])),
+            "  - auth.login (@platform-team, alice)\n"
+        );
+
+        let mut info = make_rule_info("auth.login", 1);
+        info.owners = vec!["@platform-team".to_string()];
+        let output = format_rule_info(&info);
+        assert!(output.contains("Owners: @platform-team\n"), "{output}");
+    }
+
+    // r[verify daemon.cli.query.fail-on]
+    #[test]
+    fn fail_gate_thresholds() {
+        let gate =
+            |any, more_than| FailGate::new(GateList::Stale, None, None, None, any, more_than);
 
-        self.with_config_banner(output).await
-    }
-}
+        assert!(gate(false, None).is_none());
 
-/// Format a single rule's information for display.
-fn format_rule_info(info: &RuleInfo) -> String {
-    let mut output = format!("# {}\n\n{}\n\n", info.id, info.raw);
+        let any = gate(true, Some(5)).expect("gate");
+        assert_eq!(any.violation(0), None);
+        assert_eq!(
+            any.violation(2).as_deref(),
+            Some("2 stale reference(s), more than the 0 allowed")
+        );
 
-    if let Some(file) = &info.source_file
-        && let Some(line) = info.source_line
-    {
-        output.push_str(&format!("Defined in: {}:{}\n\n", file, line));
+        let five = gate(false, Some(5)).expect("gate");
+        assert_eq!(five.violation(5), None);
+        assert!(five.violation(6).is_some());
     }
 
-    if let Some(diff) = &info.version_diff {
-        output.push_str(&format!("## Changes from previous version\n\n{diff}\n\n"));
+    // r[verify daemon.cli.query.window]
+    #[test]
+    fn page_note_points_at_next_page() {
+        let list = ListArgs {
+            sort: None,
+            limit: Some(10),
+            offset: Some(20),
+        };
+        assert_eq!(
+            list.page_note(10, 45),
+            "Showing 21-30 of 45 (next page: --offset 30)\n\n"
+        );
+        assert_eq!(list.page_note(5, 25), "Showing 21-25 of 25\n\n");
+        assert_eq!(
+            list.page_note(0, 15),
+            "Nothing left after offset 20 (15 total)\n\n"
+        );
+        assert_eq!(ListArgs::default().page_note(7, 7), "");
     }
 
-    for cov in &info.coverage {
-        output.push_str(&format!("\n## {}/{}\n", cov.spec, cov.impl_name));
-        if !cov.impl_refs.is_empty() {
-            output.push_str("Impl references:\n");
-            for r in &cov.impl_refs {
-                output.push_str(&format!("  - {}:{}\n", r.file, r.line));
-            }
-        }
-        if !cov.verify_refs.is_empty() {
-            output.push_str("Verify references:\n");
-            for r in &cov.verify_refs {
-                output.push_str(&format!("  - {}:{}\n", r.file, r.line));
-            }
-        }
+    // r[verify daemon.cli.query.search]
+    #[test]
+    fn search_output_shows_rule_definitions_and_matched_code_lines() {
+        let results = vec![
+            SearchResult {
+                kind: "rule".to_string(),
+                id: "auth.session".to_string(),
+                line: 0,
+                content: Some("Sessions MUST time out\nafter 30 minutes.".to_string()),
+                highlighted: None,
+                score: 2.0,
+                source_file: Some("docs/spec/auth.md".to_string()),
+                source_line: Some(12),
+            },
+            SearchResult {
+                kind: "source".to_string(),
+                id: "src/session.rs".to_string(),
+                line: 40,
+                content: Some("use std::time;\n\nconst TIMEOUT: u64 = 1800;\n}".to_string()),
+                highlighted: None,
+                score: 1.0,
+                source_file: None,
+                source_line: None,
+            },
+        ];
+
+        assert_eq!(
+            format_search_results("session timeout", &results),
+            "\
+# Search: session timeout
+
+2 result(s)
+
+  rule  auth.session  (docs/spec/auth.md:12)
+        Sessions MUST time out
+  code  src/session.rs:40
+        const TIMEOUT: u64 = 1800;
+"
+        );
+        assert_eq!(
+            format_search_results("nothing", &[]),
+            "No results for \"nothing\"\n"
+        );
     }
 
-    output
-}
+    // r[verify daemon.cli.query.rule-glob]
+    #[test]
+    fn rule_patterns_are_summarized() {
+        assert!(is_rule_pattern("auth.*"));
+        assert!(is_rule_pattern("auth.{login,logout}"));
+        assert!(!is_rule_pattern("auth.login+2"));
+
+        let code_ref = |line| ApiCodeRef {
+            file: "src/auth.rs".to_string(),
+            line,
+        };
+        let info = |id: &str, impl_refs: Vec<ApiCodeRef>, verify_refs: Vec<ApiCodeRef>| RuleInfo {
+            id: parse_rule_id(id).expect("valid rule id"),
+            raw: String::new(),
+            html: String::new(),
+            source_file: None,
+            source_line: None,
+            permalink: None,
+            owners: vec![],
+            approvals: vec![],
+            coverage: vec![RuleCoverage {
+                spec: "spec".to_string(),
+                impl_name: "rust".to_string(),
+                impl_refs,
+                verify_refs,
+                test_results: vec![],
+                line_coverage: vec![],
+            }],
+            version_diff: None,
+            depends_on: vec![],
+            refines: vec![],
+            refined_by: vec![],
+        };
+        let infos = vec![
+            info(
+                "auth.login",
+                vec![code_ref(1), code_ref(2)],
+                vec![code_ref(3)],
+            ),
+            info("auth.logout+2", vec![code_ref(4)], vec![]),
+            info("auth.session", vec![], vec![]),
+        ];
 
-/// Format a validation result for display.
-fn format_validation_result(result: &tracey_proto::ValidationResult) -> String {
-    if result.errors.is_empty() {
-        format!(
-            "✓ {}/{}: No validation errors found",
-            result.spec, result.impl_name
-        )
-    } else {
-        let mut output = format!(
-            "✗ {}/{}: {} error(s) found\n",
-            result.spec, result.impl_name, result.error_count
+        assert_eq!(
+            format_rule_summary(&infos),
+            "\
+# Summary
+
+3 rule(s): 2 implemented, 1 verified, 1 uncovered
+
+  auth.login     2 impl, 1 verify
+  auth.logout+2  1 impl, 0 verify
+  auth.session   uncovered
+"
         );
+    }
 
-        for error in &result.errors {
-            let location = match (&error.file, error.line) {
-                (Some(f), Some(l)) => format!(" at {}:{}", f, l),
-                (Some(f), None) => format!(" in {}", f),
-                _ => String::new(),
+    // r[verify daemon.cli.query.refs]
+    #[test]
+    fn file_refs_output_marks_stale_and_unknown_refs() {
+        let file_ref =
+            |verb: &str, id: &str, line, status, current: Option<&str>, text: Option<&str>| {
+                FileRef {
+                    verb: verb.to_string(),
+                    prefix: "r".to_string(),
+                    rule_id: parse_rule_id(id).expect("valid rule id"),
+                    line,
+                    status,
+                    current_id: current.map(|id| parse_rule_id(id).expect("valid rule id")),
+                    text: text.map(String::from),
+                }
             };
+        let response = FileRefsResponse {
+            path: "src/auth.rs".to_string(),
+            scanned: true,
+            refs: vec![
+                file_ref(
+                    "impl",
+                    "auth.login",
+                    8,
+                    FileRefStatus::Current,
+                    None,
+                    Some("Users MUST log in\nwith a password."),
+                ),
+                file_ref(
+                    "verify",
+                    "auth.session",
+                    120,
+                    FileRefStatus::Stale,
+                    Some("auth.session+2"),
+                    Some("Sessions MUST expire."),
+                ),
+                file_ref(
+                    "impl",
+                    "auth.bogus",
+                    130,
+                    FileRefStatus::Unknown,
+                    None,
+                    None,
+                ),
+                file_ref(
+                    "impl",
+                    "auth.login",
+                    140,
+                    FileRefStatus::Current,
+                    None,
+                    Some("Users MUST log in\nwith a password."),
+                ),
+            ],
+        };
 
-            if error.code == ValidationErrorCode::StaleRequirement {
-                // r[impl mcp.validation.stale.message-prefix+2]
-                // Build a concise human-readable message from structured fields
-                let stale_msg = if let (Some(ref_id), Some(current_id)) =
-                    (&error.reference_rule_id, error.related_rules.first())
-                {
-                    format!("Reference '{ref_id}' is stale; current rule is '{current_id}'")
-                } else {
-                    error.message.clone()
-                };
-                output.push_str(&format!(
-                    "  - [{:?}] {}{}\n",
-                    error.code, stale_msg, location
-                ));
-                if let Some(current_rule) = error.related_rules.first() {
-                    output.push_str(&format!(
-                        "    Use `tracey query rule '{current_rule}'` to see the full rule and diff.\n",
-                    ));
-                }
-            } else {
-                output.push_str(&format!(
-                    "  - [{:?}] {}{}\n",
-                    error.code, error.message, location
-                ));
-            }
+        assert_eq!(
+            format_file_refs(&response),
+            "\
+# References in src/auth.rs
 
-            if !error.related_rules.is_empty()
-                && error.code != ValidationErrorCode::StaleRequirement
-            {
-                output.push_str(&format!(
-                    "    Related rules: {}\n",
-                    error
-                        .related_rules
-                        .iter()
-                        .map(ToString::to_string)
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ));
-            }
-        }
+4 reference(s) to 3 rule(s)
 
-        output
+  L8    impl    auth.login    Users MUST log in
+  L120  verify  auth.session  stale → auth.session+2: Sessions MUST expire.
+  L130  impl    auth.bogus    unknown rule
+  L140  impl    auth.login    Users MUST log in
+"
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{format_rule_info, format_validation_result};
-    use tracey_core::parse_rule_id;
-    use tracey_proto::{
-        ApiCodeRef, RuleCoverage, RuleInfo, ValidationError, ValidationErrorCode, ValidationResult,
-    };
+    // r[verify daemon.cli.query.coverage-by-dir]
+    #[test]
+    fn dir_coverage_output_lists_dirs_in_response_order() {
+        let dir = |path: &str, total_units, covered_units| DirCoverage {
+            path: path.to_string(),
+            total_units,
+            covered_units,
+        };
+        let response = DirCoverageResponse {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_units: 14,
+            covered_units: 7,
+            dirs: vec![dir("crates/b", 10, 3), dir("crates/a", 4, 4)],
+        };
+
+        assert_eq!(
+            format_dir_coverage(&response, false),
+            "spec/rust: 50% of code units mapped (7/14)\n"
+        );
+        assert_eq!(
+            format_dir_coverage(&response, true),
+            "\
+spec/rust: 50% of code units mapped (7/14)
+
+  30% ███░░░░░░░  3/10  crates/b
+ 100% ██████████   4/4  crates/a
+"
+        );
+    }
+
+    // r[verify daemon.cli.query.rule-blame]
+    #[test]
+    fn rule_blame_lists_text_and_reference_authors() {
+        let blame = |author: &str, summary: &str| BlameInfo {
+            commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            author: author.to_string(),
+            author_mail: format!("{}@example.com", author.to_lowercase()),
+            author_time: 86_400,
+            summary: summary.to_string(),
+        };
+        let response = RuleBlameResponse {
+            id: parse_rule_id("auth.login").expect("valid rule id"),
+            source_file: Some("docs/spec.md".to_string()),
+            source_line: Some(3),
+            text: Some(blame("Alice", "Add login rule")),
+            refs: vec![
+                RefBlame {
+                    spec: "spec".to_string(),
+                    impl_name: "rust".to_string(),
+                    kind: "impl".to_string(),
+                    file: "src/auth.rs".to_string(),
+                    line: 12,
+                    blame: Some(blame("Bob", "Add impl")),
+                },
+                RefBlame {
+                    spec: "spec".to_string(),
+                    impl_name: "rust".to_string(),
+                    kind: "verify".to_string(),
+                    file: "tests/auth.rs".to_string(),
+                    line: 4,
+                    blame: None,
+                },
+            ],
+        };
+        assert_eq!(
+            format_rule_blame(&response),
+            "\
+\n## Blame
+
+Text: Alice <alice@example.com>, 1970-01-02 00:00 (01234567 Add login rule)
+
+  impl spec/rust src/auth.rs:12: Bob <bob@example.com>, 1970-01-02 00:00 (01234567 Add impl)
+  verify spec/rust tests/auth.rs:4: not committed
+"
+        );
+    }
+
+    // r[verify daemon.cli.query.trend]
+    #[test]
+    fn trend_groups_series_and_reports_change() {
+        let point = |recorded_at, implemented, verified, stale| TrendPoint {
+            recorded_at,
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 10,
+            implemented_rules: implemented,
+            verified_rules: verified,
+            stale_rules: stale,
+        };
+        let response = TrendResponse {
+            points: vec![point(0, 4, 2, 1), point(86_400, 6, 2, 0)],
+        };
+
+        assert_eq!(
+            format_trend(&response, Some("30d")),
+            "\
+## spec/rust (2 snapshot(s))
+
+  1970-01-01 00:00  impl  40% (4/10)  verify  20% (2/10)  stale 1
+  1970-01-02 00:00  impl  60% (6/10)  verify  20% (2/10)  stale 0
+
+  Change: impl +20%, verify +0%, stale -1, rules +0
+
+"
+        );
+        assert_eq!(
+            format_trend(&TrendResponse { points: vec![] }, Some("30d")),
+            "No coverage snapshots recorded in the last 30d.\n"
+        );
+    }
+
+    // r[verify daemon.cli.query.recent]
+    #[test]
+    fn recent_lists_changes_per_spec() {
+        let mut response = RecentResponse {
+            since: "7d".to_string(),
+            baseline_version: 3,
+            baseline_recorded_at: 0,
+            latest_version: 5,
+            rules: vec![
+                RecentRule {
+                    spec: "auth".to_string(),
+                    id: "auth.login+2".to_string(),
+                    previous_id: Some("auth.login".to_string()),
+                    change: RecentChange::Changed,
+                    coverage: vec![RecentCoverage {
+                        impl_name: "rust".to_string(),
+                        was_implemented: true,
+                        implemented: false,
+                        was_verified: false,
+                        verified: false,
+                    }],
+                },
+                RecentRule {
+                    spec: "auth".to_string(),
+                    id: "auth.mfa".to_string(),
+                    previous_id: None,
+                    change: RecentChange::Added,
+                    coverage: vec![],
+                },
+            ],
+        };
+
+        assert_eq!(
+            format_recent(&response),
+            "\
+2 rule(s) changed since snapshot 3 (1970-01-01 00:00); the latest snapshot is 5
+
+## auth
+
+  changed   auth.login+2 (was auth.login)
+            rust: no longer implemented
+  added     auth.mfa
+"
+        );
+        response.rules.clear();
+        assert_eq!(
+            format_recent(&response),
+            "No rule changed since snapshot 3 (1970-01-01 00:00).\n"
+        );
+    }
+
+    // r[verify daemon.cli.query.matrix]
+    #[test]
+    fn matrix_output_aligns_cells() {
+        let cell = |impl_refs, stale_refs| MatrixCell {
+            impl_refs,
+            verify_refs: 0,
+            stale_refs,
+        };
+        let matrix = SpecMatrix {
+            spec: "auth".to_string(),
+            impls: vec!["rust".to_string(), "ts".to_string()],
+            rows: vec![
+                MatrixRow {
+                    id: parse_rule_id("auth.login").expect("valid rule id"),
+                    cells: vec![cell(1, 0), cell(2, 1)],
+                    approved: false,
+                },
+                MatrixRow {
+                    id: parse_rule_id("auth.session").expect("valid rule id"),
+                    cells: vec![cell(0, 1), cell(0, 0)],
+                    approved: false,
+                },
+            ],
+        };
+
+        assert_eq!(
+            format_matrix(&matrix),
+            "\
+# Traceability matrix: auth
+
+rule          rust   ts
+auth.login    ✓      ✓
+auth.session  stale  ✗
+
+2 rule(s) × 2 impl(s)
+"
+        );
+    }
+
+    // r[verify cli.approve]
+    #[test]
+    fn approvals_show_in_matrix_and_rule_output() {
+        let cell = MatrixCell {
+            impl_refs: 1,
+            verify_refs: 0,
+            stale_refs: 0,
+        };
+        let row = |id: &str, approved| MatrixRow {
+            id: parse_rule_id(id).expect("valid rule id"),
+            cells: vec![cell.clone()],
+            approved,
+        };
+        let matrix = SpecMatrix {
+            spec: "auth".to_string(),
+            impls: vec!["rust".to_string()],
+            rows: vec![row("auth.login+3", true), row("auth.session", false)],
+        };
+        assert_eq!(
+            format_matrix(&matrix),
+            "\
+# Traceability matrix: auth
+
+rule          rust   approved
+auth.login+3  ✓      ✓
+auth.session  ✓      ✗
+
+2 rule(s) × 1 impl(s)
+"
+        );
+
+        let mut info = make_rule_info("auth.login", 3);
+        info.approvals = vec![ApiApproval {
+            by: "alice".to_string(),
+            date: "2026-10-14".to_string(),
+        }];
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains("Approved by: alice (2026-10-14)\n"),
+            "{output}"
+        );
+    }
+
+    // r[verify daemon.cli.query.unexecuted]
+    // r[verify cli.line-coverage]
+    #[test]
+    fn unexecuted_output_lists_unexecuted_units() {
+        let lines = |unit: Option<&str>, executed_lines, total_lines| ApiLineCoverage {
+            file: "src/auth.rs".to_string(),
+            line: 12,
+            unit: unit.map(str::to_string),
+            executed_lines,
+            total_lines,
+        };
+        let mut response = UnexecutedResponse {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 5,
+            has_report: true,
+            measured_rules: 3,
+            by_section: vec![UnexecutedSection {
+                section: "Login".to_string(),
+                rules: vec![UnexecutedRule {
+                    id: parse_rule_id("auth.login").expect("valid rule id"),
+                    line_coverage: vec![lines(Some("login"), 0, 14)],
+                }],
+            }],
+        };
+        assert_eq!(
+            format_unexecuted(&response),
+            "spec/rust: 1 of 3 measured rule(s) never executed (5 rules total)\n\n\
+             ## Login\n  - auth.login\n      src/auth.rs:12 (login: 0/14 lines executed)\n"
+        );
+        response.has_report = false;
+        assert!(format_unexecuted(&response).starts_with("No line coverage report ingested"));
+
+        let mut info = make_rule_info("auth.login", 1);
+        info.coverage[0].line_coverage = vec![ApiLineCoverage {
+            line: 42,
+            file: "src/lib.rs".to_string(),
+            ..lines(None, 3, 4)
+        }];
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains("Impl references:\n  - src/lib.rs:42 (3/4 lines executed)\n"),
+            "{output}"
+        );
+    }
+
+    // r[verify cli.test-report]
+    #[test]
+    fn test_results_show_in_untested_and_rule_output() {
+        let response = UntestedResponse {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 5,
+            untested_count: 0,
+            implemented_count: 0,
+            by_section: vec![],
+            unimplemented_by_section: vec![],
+            unproven_count: 1,
+            unproven_by_section: vec![SectionRules {
+                section: "Sessions".to_string(),
+                rules: vec![RuleRef {
+                    id: parse_rule_id("auth.session").expect("valid rule id"),
+                    text: None,
+                    owners: vec![],
+                }],
+            }],
+        };
+        assert_eq!(
+            format_untested(&response, &ListArgs::default()),
+            "spec/rust: 0 untested out of 5 rules (0 implemented, 0 not implemented), \
+             and 1 verified without a passing test\n\n\
+             # Verified, but no passing test\n\n\
+             ## Sessions\n  - auth.session\n\n"
+        );
+
+        let mut info = make_rule_info("auth.session", 1);
+        let code_ref = |line| ApiCodeRef {
+            file: "tests/session.rs".to_string(),
+            line,
+        };
+        info.coverage[0].verify_refs = vec![code_ref(3), code_ref(9)];
+        info.coverage[0].test_results = vec![
+            ApiTestResult {
+                file: "tests/session.rs".to_string(),
+                line: 3,
+                test: Some("session_expires".to_string()),
+                outcome: TestOutcome::Failed,
+            },
+            ApiTestResult {
+                file: "tests/session.rs".to_string(),
+                line: 9,
+                test: None,
+                outcome: TestOutcome::NotRun,
+            },
+        ];
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains(
+                "Verify references:\n  - tests/session.rs:3 (session_expires: failed)\n  \
+                 - tests/session.rs:9 (not run)\n"
+            ),
+            "{output}"
+        );
+    }
 
     #[test]
     fn stale_validation_output_is_concise() {
@@ -832,6 +2993,9 @@ mod tests {
             html: format!("<p>Rule text for {}</p>", base),
             source_file: Some("docs/spec.md".to_string()),
             source_line: Some(10),
+            permalink: None,
+            owners: vec![],
+            approvals: vec![],
             coverage: vec![RuleCoverage {
                 spec: "test-spec".to_string(),
                 impl_name: "main".to_string(),
@@ -840,8 +3004,13 @@ mod tests {
                     line: 42,
                 }],
                 verify_refs: vec![],
+                test_results: vec![],
+                line_coverage: vec![],
             }],
             version_diff: None,
+            depends_on: vec![],
+            refines: vec![],
+            refined_by: vec![],
         }
     }
 
@@ -867,6 +3036,39 @@ mod tests {
         );
     }
 
+    // r[verify daemon.cli.query.rule-permalink]
+    #[test]
+    fn format_rule_info_shows_permalink() {
+        let mut info = make_rule_info("foo.bar", 1);
+        assert!(!format_rule_info(&info).contains("Permalink:"));
+        info.permalink =
+            Some("https://github.com/example/api/blob/HEAD/docs/spec.md#L10".to_string());
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains(
+                "Defined in: docs/spec.md:10\n\n\
+                 Permalink: https://github.com/example/api/blob/HEAD/docs/spec.md#L10\n"
+            ),
+            "output:\n{}",
+            output
+        );
+    }
+
+    #[test]
+    fn format_rule_info_lists_spec_dependencies() {
+        let mut info = make_rule_info("checkout.prices", 1);
+        info.depends_on = vec![ApiSpecDependency {
+            spec: "protocol".to_string(),
+            id: parse_rule_id("api.fetch+2").unwrap(),
+        }];
+        let output = format_rule_info(&info);
+        assert!(
+            output.contains("Depends on: protocol:api.fetch+2\n"),
+            "output:\n{}",
+            output
+        );
+    }
+
     #[test]
     fn format_rule_info_shows_version_diff() {
         let mut info = make_rule_info("foo.bar", 2);
@@ -892,6 +3094,8 @@ mod tests {
             impl_name: "main".to_string(),
             impl_refs: vec![],
             verify_refs: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
         }];
         let output = format_rule_info(&info);
         // Should have the spec/impl heading but no "Impl references:" section
@@ -902,4 +3106,141 @@ mod tests {
             output
         );
     }
+
+    #[test]
+    fn spec_outline_indents_nested_sections() {
+        let section =
+            |title: &str, level, parent, impl_count, verify_count, total| OutlineSection {
+                title: title.to_string(),
+                slug: title.to_lowercase(),
+                level,
+                parent,
+                rules: vec![],
+                coverage: OutlineCoverage::default(),
+                aggregated: OutlineCoverage {
+                    impl_count,
+                    verify_count,
+                    total,
+                },
+            };
+        let response = SpecOutlineResponse {
+            spec: "auth".to_string(),
+            impl_name: "rust".to_string(),
+            sections: vec![
+                section("Authentication", 1, None, 3, 1, 4),
+                section("Sessions", 2, Some(0), 2, 1, 2),
+                section("Glossary", 2, Some(0), 0, 0, 0),
+            ],
+            unsectioned: vec![parse_rule_id("auth.preamble").unwrap()],
+        };
+        assert_eq!(
+            format_spec_outline(&response),
+            "# Outline of auth/rust\n\n\
+             Authentication  3/4 impl  1/4 verify\n\
+             \x20 Sessions      2/2 impl  1/2 verify\n\
+             \x20 Glossary      -\n\
+             \n1 rule(s) defined before the first heading\n"
+        );
+    }
+
+    #[test]
+    fn file_status_aligns_paths_and_notes_stale_rules() {
+        let file = |file: &str, total_rules, covered_rules, stale_rules| FileStatus {
+            file: file.to_string(),
+            total_rules,
+            covered_rules,
+            stale_rules,
+            verified_rules: 1,
+        };
+        assert_eq!(
+            format_file_status(&[
+                file("docs/spec/auth.md", 4, 3, 1),
+                file("docs/spec/io.md", 2, 2, 0),
+            ]),
+            "  docs/spec/auth.md  3/4 covered, 1 verified, 1 stale\n\
+             \x20 docs/spec/io.md    2/2 covered, 1 verified\n"
+        );
+    }
+
+    #[test]
+    fn rule_impls_flags_rules_covered_by_none_or_some() {
+        let rule = |id: &str, covered_in: &[&str], missing_in: &[&str]| RuleImplsStatus {
+            spec: "proto".to_string(),
+            id: parse_rule_id(id).unwrap(),
+            covered_in: covered_in.iter().map(|s| s.to_string()).collect(),
+            missing_in: missing_in.iter().map(|s| s.to_string()).collect(),
+        };
+        let output = format_rule_impls(&[
+            rule("auth.login", &["rust", "ts"], &[]),
+            rule("auth.session", &["rust"], &["ts"]),
+            rule("auth.logout", &[], &["rust", "ts"]),
+        ]);
+        assert_eq!(
+            output,
+            "# proto: 1 of 3 rules covered by all 2 impls\n\
+             \n## Covered by no impl (1)\n\
+             \x20 - auth.logout\n\
+             \n## Covered by some impls (1)\n\
+             \x20 - auth.session: rust (missing in ts)\n\n"
+        );
+    }
+
+    #[test]
+    fn unrefined_lists_rules_with_locations() {
+        let response = UnrefinedResponse {
+            spec: "sys".to_string(),
+            impl_name: "rust".to_string(),
+            levels: vec!["sys".to_string()],
+            high_level_rules: 3,
+            rules: vec![UnrefinedRule {
+                id: parse_rule_id("sys.audit").unwrap(),
+                source_file: Some("docs/spec/sys.md".to_string()),
+                source_line: Some(12),
+            }],
+        };
+        let output = format_unrefined(&response);
+        assert!(
+            output.contains("1 of 3 high-level rule(s) unrefined (levels: sys)"),
+            "output:\n{}",
+            output
+        );
+        assert!(
+            output.contains("  - sys.audit (docs/spec/sys.md:12)"),
+            "output:\n{}",
+            output
+        );
+    }
+
+    #[test]
+    fn spec_drift_lists_bumped_rules() {
+        let response = SpecDriftResponse {
+            specs: vec![SpecDrift {
+                spec: "proto".to_string(),
+                source: "https://github.com/org/spec @ v1.2.0".to_string(),
+                locked: Some("3f2a9c".to_string()),
+                upstream: Some("b71e04".to_string()),
+                bumped: vec![RuleVersionDrift {
+                    id: "proto.frame".to_string(),
+                    locked_version: 1,
+                    upstream_version: 2,
+                }],
+                added: vec!["proto.trailer".to_string()],
+                removed: vec![],
+                error: None,
+            }],
+        };
+        let output = format_spec_drift(&response);
+        assert!(
+            output.contains("proto.frame: +1 → +2"),
+            "output:\n{}",
+            output
+        );
+        assert!(
+            output.contains("1 new upstream rule(s)"),
+            "output:\n{}",
+            output
+        );
+        assert!(!output.contains("removed upstream"), "output:\n{}", output);
+        assert!(output.contains("To sync"), "output:\n{}", output);
+    }
 }
@@ -0,0 +1,288 @@
+//! CSV and markdown table output for `tracey query --format`.
+//!
+//! r[impl daemon.cli.query.format]
+//!
+//! Each supported query response is flattened into a [`Table`] with one
+//! row per record, so the output pastes straight into a spreadsheet or a
+//! PR description. `--porcelain` renders the same rows as headerless
+//! tab-separated lines whose columns never change between versions.
+
+use tracey_proto::*;
+
+/// Output format selected with `--format` (or `--porcelain`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+    Porcelain,
+}
+
+impl TableFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "csv" => Some(Self::Csv),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A header row plus data rows, all cells already rendered as text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Csv => self.to_csv(),
+            TableFormat::Markdown => self.to_markdown(),
+            TableFormat::Porcelain => self.to_porcelain(),
+        }
+    }
+
+    // r[impl cli.porcelain]
+    fn to_porcelain(&self) -> String {
+        let mut output = String::new();
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| cell.split_whitespace().collect::<Vec<_>>().join(" "))
+                .collect();
+            output.push_str(&cells.join("\t"));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn to_csv(&self) -> String {
+        let mut output = String::new();
+        let headers: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        for row in std::iter::once(&headers).chain(&self.rows) {
+            let fields: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+            output.push_str(&fields.join(","));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut output = format!("| {} |\n", self.headers.join(" | "));
+        output.push_str(&format!(
+            "|{}\n",
+            self.headers.iter().map(|_| "---|").collect::<String>()
+        ));
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+            output.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        output
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Keep a markdown table cell on one line and escape column separators.
+fn markdown_cell(cell: &str) -> String {
+    cell.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn percent(part: usize, total: usize) -> String {
+    if total == 0 {
+        "0".to_string()
+    } else {
+        format!("{:.0}", part as f64 / total as f64 * 100.0)
+    }
+}
+
+pub fn status_table(status: &StatusResponse) -> Table {
+    Table {
+        headers: vec![
+            "spec",
+            "impl",
+            "rules",
+            "covered",
+            "covered %",
+            "verified",
+            "verified %",
+            "stale",
+        ],
+        rows: status
+            .impls
+            .iter()
+            .map(|s| {
+                vec![
+                    s.spec.clone(),
+                    s.impl_name.clone(),
+                    s.total_rules.to_string(),
+                    s.covered_rules.to_string(),
+                    percent(s.covered_rules, s.total_rules),
+                    s.verified_rules.to_string(),
+                    percent(s.verified_rules, s.total_rules),
+                    s.stale_rules.to_string(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Rows for the rules listed by `uncovered` and `untested`.
+pub fn section_rules_table(spec: &str, impl_name: &str, by_section: &[SectionRules]) -> Table {
+    Table {
+        headers: vec!["spec", "impl", "section", "rule", "text"],
+        rows: by_section
+            .iter()
+            .flat_map(|section| {
+                section.rules.iter().map(|rule| {
+                    vec![
+                        spec.to_string(),
+                        impl_name.to_string(),
+                        section.section.clone(),
+                        rule.id.to_string(),
+                        rule.text.clone().unwrap_or_default(),
+                    ]
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Rows for `untested`, with columns telling the buckets apart.
+pub fn untested_table(untested: &UntestedResponse) -> Table {
+    let buckets = [
+        ("yes", "no", &untested.by_section),
+        ("no", "no", &untested.unimplemented_by_section),
+        ("", "no passing test", &untested.unproven_by_section),
+    ];
+    Table {
+        headers: vec![
+            "spec",
+            "impl",
+            "implemented",
+            "verified",
+            "section",
+            "rule",
+            "text",
+        ],
+        rows: buckets
+            .into_iter()
+            .flat_map(|(implemented, verified, by_section)| {
+                by_section.iter().flat_map(move |section| {
+                    section.rules.iter().map(move |rule| {
+                        vec![
+                            untested.spec.clone(),
+                            untested.impl_name.clone(),
+                            implemented.to_string(),
+                            verified.to_string(),
+                            section.section.clone(),
+                            rule.id.to_string(),
+                            rule.text.clone().unwrap_or_default(),
+                        ]
+                    })
+                })
+            })
+            .collect(),
+    }
+}
+
+pub fn stale_table(stale: &StaleResponse) -> Table {
+    Table {
+        headers: vec!["spec", "impl", "file", "line", "reference", "current"],
+        rows: stale
+            .refs
+            .iter()
+            .map(|entry| {
+                vec![
+                    stale.spec.clone(),
+                    stale.impl_name.clone(),
+                    entry.file.clone(),
+                    entry.line.to_string(),
+                    entry.reference_id.to_string(),
+                    entry.current_id.to_string(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::parse_rule_id;
+
+    fn uncovered() -> Table {
+        section_rules_table(
+            "spec",
+            "rust",
+            &[SectionRules {
+                section: "Sessions".to_string(),
+                rules: vec![RuleRef {
+                    id: parse_rule_id("auth.session+2").expect("valid rule id"),
+                    text: Some("Sessions MUST | expire,\nquietly.".to_string()),
+                    owners: vec![],
+                }],
+            }],
+        )
+    }
+
+    // r[verify daemon.cli.query.format]
+    #[test]
+    fn renders_csv_with_quoting() {
+        assert_eq!(
+            uncovered().render(TableFormat::Csv),
+            "spec,impl,section,rule,text\n\
+             spec,rust,Sessions,auth.session+2,\"Sessions MUST | expire,\nquietly.\"\n"
+        );
+    }
+
+    #[test]
+    fn renders_markdown_on_one_line_per_row() {
+        assert_eq!(
+            uncovered().render(TableFormat::Markdown),
+            "| spec | impl | section | rule | text |\n\
+             |---|---|---|---|---|\n\
+             | spec | rust | Sessions | auth.session+2 | Sessions MUST \\| expire, quietly. |\n"
+        );
+    }
+
+    // r[verify cli.porcelain]
+    #[test]
+    fn renders_porcelain_without_header() {
+        assert_eq!(
+            uncovered().render(TableFormat::Porcelain),
+            "spec\trust\tSessions\tauth.session+2\tSessions MUST | expire, quietly.\n"
+        );
+    }
+
+    #[test]
+    fn status_rows_include_percentages() {
+        let table = status_table(&StatusResponse {
+            impls: vec![ImplStatus {
+                spec: "spec".to_string(),
+                impl_name: "rust".to_string(),
+                total_rules: 8,
+                covered_rules: 6,
+                stale_rules: 1,
+                verified_rules: 2,
+                by_file: vec![],
+            }],
+            by_rule: vec![],
+        });
+        assert_eq!(
+            table.rows,
+            vec![vec!["spec", "rust", "8", "6", "75", "2", "25", "1"]]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Live terminal summary for `tracey watch`.
+//!
+//! r[impl daemon.cli.watch]
+//!
+//! Subscribes to the daemon's data updates and, every time the data version
+//! bumps, clears the screen and reprints the coverage summary followed by
+//! what the latest rebuild picked up.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use eyre::Result;
+use tracey_proto::*;
+
+use super::query::{Caller, QueryClient};
+
+/// Clear the screen and move the cursor to the top-left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Show at most this many changed files per rebuild.
+const MAX_CHANGED_FILES: usize = 10;
+
+pub async fn run(project_root: PathBuf) -> Result<()> {
+    let client = QueryClient::new(project_root, Caller::Cli);
+    let mut last_version: Option<u64> = None;
+
+    // Show the current state right away instead of waiting for a change.
+    print!("{}{}", CLEAR_SCREEN, client.status().await);
+
+    loop {
+        let (tx, mut rx) = roam::channel::<DataUpdate>();
+        let subscribe_client = client.client.clone();
+        let subscribe_task = tokio::spawn(async move { subscribe_client.subscribe(tx).await });
+
+        while let Ok(Some(update)) = rx.recv().await {
+            if last_version == Some(update.version) {
+                continue;
+            }
+            last_version = Some(update.version);
+            print!(
+                "{}{}\n{}",
+                CLEAR_SCREEN,
+                client.status().await,
+                format_update(&update)
+            );
+        }
+
+        // The daemon went away (or restarted); resubscribe once it's back.
+        subscribe_task.abort();
+        let _ = subscribe_task.await;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Describe what a rebuild changed: the files that triggered it and any
+/// rules whose coverage flipped.
+pub fn format_update(update: &DataUpdate) -> String {
+    let mut output = format!("# Rebuild (version {})\n\n", update.version);
+
+    if update.changed_files.is_empty() {
+        output.push_str("Full rebuild\n");
+    } else {
+        output.push_str(&format!(
+            "{} changed file(s):\n",
+            update.changed_files.len()
+        ));
+        for file in update.changed_files.iter().take(MAX_CHANGED_FILES) {
+            output.push_str(&format!("  {}\n", file));
+        }
+        if update.changed_files.len() > MAX_CHANGED_FILES {
+            output.push_str(&format!(
+                "  ... and {} more\n",
+                update.changed_files.len() - MAX_CHANGED_FILES
+            ));
+        }
+    }
+
+    if let Some(delta) = &update.delta {
+        for change in &delta.newly_covered {
+            output.push_str(&format!(
+                "+ {} covered at {}:{}\n",
+                change.rule_id, change.file, change.line
+            ));
+        }
+        for rule_id in &delta.newly_uncovered {
+            output.push_str(&format!("- {} uncovered\n", rule_id));
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::parse_rule_id;
+
+    // r[verify daemon.cli.watch]
+    #[test]
+    fn update_lists_changed_files_and_coverage_changes() {
+        let update = DataUpdate {
+            version: 7,
+            delta: Some(DeltaSummary {
+                newly_covered: vec![CoverageChange {
+                    rule_id: parse_rule_id("auth.login").expect("valid rule id"),
+                    file: "src/auth.rs".to_string(),
+                    line: 12,
+                }],
+                newly_uncovered: vec![parse_rule_id("auth.logout").expect("valid rule id")],
+            }),
+            changed_files: vec!["src/auth.rs".to_string()],
+        };
+        assert_eq!(
+            format_update(&update),
+            "# Rebuild (version 7)\n\n\
+             1 changed file(s):\n  src/auth.rs\n\
+             + auth.login covered at src/auth.rs:12\n\
+             - auth.logout uncovered\n"
+        );
+    }
+
+    #[test]
+    fn full_rebuild_has_no_file_list() {
+        let update = DataUpdate {
+            version: 1,
+            delta: None,
+            changed_files: vec![],
+        };
+        assert_eq!(
+            format_update(&update),
+            "# Rebuild (version 1)\n\nFull rebuild\n"
+        );
+    }
+}
@@ -13,7 +13,7 @@ use marq::{RenderOptions, render};
 use crate::config::Config;
 
 /// A rule whose text changed in the staged index but whose version was not bumped.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ChangedRule {
     /// Spec file path, relative to project root.
     pub file: PathBuf,
@@ -29,6 +29,66 @@ pub struct ChangedRule {
     pub marker_span: marq::SourceSpan,
 }
 
+/// A rule whose version `bump` incremented.
+#[derive(Debug)]
+pub struct BumpedRule {
+    /// Spec file path, relative to project root.
+    pub file: PathBuf,
+    pub old_id: marq::RuleId,
+    pub new_id: marq::RuleId,
+    /// Raw markdown text of the rule before the change (from HEAD).
+    pub old_raw: String,
+    /// Raw markdown text of the rule after the change (from index).
+    pub new_raw: String,
+}
+
+/// Rules allowed to change without a version bump, for editorial fixes.
+///
+/// r[impl cli.pre-commit.no-bump]
+#[derive(Debug, Clone, Default)]
+pub struct NoBumpExemption {
+    /// Base rule IDs
+    pub rules: Vec<String>,
+    /// Why the change doesn't need a bump, recorded in `SPEC-CHANGELOG.md`
+    pub reason: String,
+}
+
+impl NoBumpExemption {
+    /// Exempt comma- or space-separated rule IDs (versions are ignored).
+    pub fn new(rules: &str, reason: &str) -> Self {
+        Self {
+            rules: rules
+                .split([',', ' '])
+                .filter(|id| !id.is_empty())
+                .map(|id| id.split('+').next().unwrap_or(id).to_string())
+                .collect(),
+            reason: reason.trim().to_string(),
+        }
+    }
+
+    /// Exemptions from the `Tracey-No-Bump:` trailers of a commit message,
+    /// with the message's subject line as the reason.
+    pub fn from_commit_message(message: &str) -> Self {
+        let lines: Vec<&str> = message
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        let subject = lines.iter().find(|line| !line.is_empty()).unwrap_or(&"");
+        let rules: Vec<&str> = lines
+            .iter()
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case("Tracey-No-Bump"))
+            .map(|(_, value)| value)
+            .collect();
+        Self::new(&rules.join(","), subject)
+    }
+
+    fn covers(&self, change: &ChangedRule) -> bool {
+        self.rules.iter().any(|base| *base == change.rule_id.base)
+    }
+}
+
 /// Run a git command in the project root and capture stdout.
 pub fn git_capture(project_root: &Path, args: &[&str]) -> Result<String> {
     let out = std::process::Command::new("git")
@@ -71,11 +131,91 @@ pub fn git_cat_file(project_root: &Path, revision: &str, path: &str) -> Result<O
         .wrap_err_with(|| format!("content of {spec} is not valid UTF-8"))
 }
 
+/// The empty tree, to compare a repository's first commit against.
+pub const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// The new side of a comparison against a base commit.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    /// The git index (staged changes)
+    Index,
+    /// The files on disk, staged or not
+    WorkTree,
+    /// A commit
+    Commit(&'a str),
+}
+
+impl Target<'_> {
+    pub(crate) fn read(self, project_root: &Path, path: &str) -> Result<Option<String>> {
+        match self {
+            Target::Index => git_cat_file(project_root, "", path),
+            Target::WorkTree => match std::fs::read(project_root.join(path)) {
+                Ok(bytes) => String::from_utf8(bytes)
+                    .map(Some)
+                    .wrap_err_with(|| format!("content of {path} is not valid UTF-8")),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).wrap_err_with(|| format!("failed to read {path}")),
+            },
+            Target::Commit(rev) => git_cat_file(project_root, rev, path),
+        }
+    }
+
+    /// Every file in the target.
+    pub(crate) fn files(self, project_root: &Path) -> Result<String> {
+        match self {
+            Target::Index => git_capture(project_root, &["ls-files", "--cached"]),
+            Target::WorkTree => git_capture(
+                project_root,
+                &["ls-files", "--cached", "--others", "--exclude-standard"],
+            ),
+            Target::Commit(rev) => {
+                git_capture(project_root, &["ls-tree", "-r", "--name-only", rev])
+            }
+        }
+    }
+
+    /// Tracked files that differ from `base`, limited by a `--diff-filter` if
+    /// given.
+    fn changed_since(
+        self,
+        project_root: &Path,
+        base: &str,
+        filter: Option<&str>,
+    ) -> Result<String> {
+        let filter = filter.map(|f| format!("--diff-filter={}", f));
+        let mut args = match self {
+            Target::Index => vec!["diff-index", "--name-only", "--cached"],
+            Target::WorkTree | Target::Commit(_) => vec!["diff", "--name-only"],
+        };
+        args.extend(filter.as_deref());
+        args.push(base);
+        if let Target::Commit(rev) = self {
+            args.push(rev);
+        }
+        git_capture(project_root, &args)
+    }
+}
+
+/// What staged changes are compared against: HEAD, or the empty tree before
+/// the first commit.
+pub fn index_base(project_root: &Path) -> &'static str {
+    if git_capture(project_root, &["rev-parse", "--verify", "HEAD"]).is_ok() {
+        "HEAD"
+    } else {
+        EMPTY_TREE
+    }
+}
+
 /// Parse a spec markdown string and return a map from rule **base** ID → `ReqDefinition`.
-async fn parse_spec_rules(content: &str) -> Result<HashMap<String, marq::ReqDefinition>> {
-    let doc = render(content, &RenderOptions::default())
-        .await
-        .map_err(|e| eyre::eyre!("failed to parse spec: {e}"))?;
+pub(crate) async fn parse_spec_rules(
+    content: &str,
+) -> Result<HashMap<String, marq::ReqDefinition>> {
+    let doc = render(
+        &crate::redirects::strip_redirects(content),
+        &RenderOptions::default(),
+    )
+    .await
+    .map_err(|e| eyre::eyre!("failed to parse spec: {e}"))?;
 
     Ok(doc
         .reqs
@@ -107,10 +247,17 @@ pub async fn detect_changed_rules(
         return Ok(vec![]);
     }
 
-    let staged_output = git_capture(
-        project_root,
-        &["diff-index", "--name-only", "--cached", "HEAD"],
-    )?;
+    detect_changed_rules_in(project_root, config, "HEAD", Target::Index).await
+}
+
+/// Like [`detect_changed_rules`], but between any base commit and target.
+pub async fn detect_changed_rules_in(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+    target: Target<'_>,
+) -> Result<Vec<ChangedRule>> {
+    let staged_output = target.changed_since(project_root, base, None)?;
 
     // Collect all spec include patterns.
     let spec_patterns: Vec<&str> = config
@@ -136,8 +283,8 @@ pub async fn detect_changed_rules(
             continue;
         }
 
-        let old_content = git_cat_file(project_root, "HEAD", staged_file)?;
-        let new_content = match git_cat_file(project_root, "", staged_file)? {
+        let old_content = git_cat_file(project_root, base, staged_file)?;
+        let new_content = match target.read(project_root, staged_file)? {
             Some(c) => c,
             None => continue, // deleted — nothing to check
         };
@@ -169,26 +316,525 @@ pub async fn detect_changed_rules(
     Ok(changed_rules)
 }
 
+/// A reference added in a staged source file that doesn't resolve to a rule.
+#[derive(Debug, Clone)]
+pub struct BrokenReference {
+    /// Source file path, relative to project root.
+    pub file: PathBuf,
+    pub line: usize,
+    /// The reference as written, e.g. `r[impl auth.login+3]`
+    pub text: String,
+    pub problem: String,
+}
+
+/// A list of glob patterns. Like `.gitignore`, the last matching pattern
+/// wins, so `!pattern` entries carve files out of earlier patterns and
+/// later patterns can add them back.
+///
+/// r[impl config.impl.include.negation]
+pub(crate) struct Globs(Vec<(globset::GlobMatcher, bool)>);
+
+impl Globs {
+    pub(crate) fn is_match(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        self.0
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.is_match(path))
+            .is_some_and(|(_, negated)| !negated)
+    }
+}
+
+pub(crate) fn glob_set(patterns: &[String]) -> Globs {
+    Globs(
+        patterns
+            .iter()
+            .filter_map(|pattern| {
+                let (pattern, negated) = match pattern.strip_prefix('!') {
+                    Some(pattern) => (pattern, true),
+                    None => (pattern.as_str(), false),
+                };
+                let glob = globset::Glob::new(pattern).ok()?;
+                Some((glob.compile_matcher(), negated))
+            })
+            .collect(),
+    )
+}
+
+/// The source files of every impl, as the scan picks them: `include`
+/// (`**/*.rs` when empty) and the effective `test_include`, minus the
+/// effective `exclude`.
+pub(crate) struct ImplSources(Vec<(Globs, Globs)>);
+
+impl ImplSources {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self(
+            config
+                .specs
+                .iter()
+                .flat_map(|spec| &spec.impls)
+                .map(|imp| {
+                    let mut include = if imp.include.is_empty() {
+                        vec!["**/*.rs".to_string()]
+                    } else {
+                        imp.include.clone()
+                    };
+                    include.extend(crate::config::effective_test_include(imp));
+                    let exclude = crate::config::effective_excludes(imp);
+                    (glob_set(&include), glob_set(&exclude))
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn contains(&self, file: &str) -> bool {
+        self.0
+            .iter()
+            .any(|(include, exclude)| include.is_match(file) && !exclude.is_match(file))
+    }
+}
+
+/// Current version of every rule in the target's spec files and the cached
+/// copies of remote specs, by marker prefix and base ID. The old IDs of
+/// renamed rules (`r[new <- old]`) are included, since references to them
+/// still resolve.
+async fn rule_versions(
+    project_root: &Path,
+    config: &Config,
+    target: Target<'_>,
+) -> Result<HashMap<String, HashMap<String, u32>>> {
+    let indexed = target.files(project_root)?;
+    let mut versions: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    for spec in &config.specs {
+        let include = glob_set(&spec.include);
+        for file in indexed.lines().filter(|f| include.is_match(f)) {
+            let Some(content) = target.read(project_root, file)? else {
+                continue;
+            };
+            add_rule_versions(&mut versions, spec.prefix.as_deref(), &content).await?;
+        }
+        for copy in crate::remote::cached_copies(project_root, spec) {
+            let pattern = glob_set(std::slice::from_ref(&copy.pattern));
+            for entry in ignore::WalkBuilder::new(&copy.root).build().flatten() {
+                let path = entry.path();
+                let matches = path
+                    .strip_prefix(&copy.root)
+                    .is_ok_and(|relative| pattern.is_match(relative));
+                if !matches || path.extension().is_none_or(|ext| ext != "md") {
+                    continue;
+                }
+                let Ok(content) = std::fs::read_to_string(path) else {
+                    continue;
+                };
+                add_rule_versions(&mut versions, spec.prefix.as_deref(), &content).await?;
+            }
+        }
+    }
+    Ok(versions)
+}
+
+/// Add the rules of one spec document, and the old IDs they redirect from,
+/// to `versions`.
+async fn add_rule_versions(
+    versions: &mut HashMap<String, HashMap<String, u32>>,
+    spec_prefix: Option<&str>,
+    content: &str,
+) -> Result<()> {
+    let redirects = crate::redirects::parse_redirects(content);
+    for (base, req) in parse_spec_rules(content).await? {
+        let prefix = spec_prefix
+            .map(str::to_string)
+            .or_else(|| crate::extract_marker_prefix(content, req.marker_span));
+        let Some(prefix) = prefix else {
+            continue;
+        };
+        let rules = versions.entry(prefix).or_default();
+        for old in crate::redirects::renamed_from(&redirects, &req.id.to_string()) {
+            let version = rules.entry(old.base).or_insert(old.version);
+            *version = (*version).max(old.version);
+        }
+        rules.insert(base, req.id.version);
+    }
+    Ok(())
+}
+
+/// The definition of the rule with this base ID in the spec files on disk,
+/// with the file it's in.
+pub(crate) async fn find_spec_rule(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+) -> Result<Option<(PathBuf, marq::ReqDefinition)>> {
+    let files = Target::WorkTree.files(project_root)?;
+    for spec in &config.specs {
+        let include = glob_set(&spec.include);
+        for file in files.lines().filter(|f| include.is_match(f)) {
+            let Some(content) = Target::WorkTree.read(project_root, file)? else {
+                continue;
+            };
+            if let Some(req) = parse_spec_rules(&content).await?.remove(base) {
+                return Ok(Some((PathBuf::from(file), req)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Find references that staged source files add but that don't resolve
+/// against the staged specs: unknown prefixes, unknown rules, and versions
+/// newer than the rule's current one. References that were already there
+/// at HEAD, and stale ones, are left to the usual validation.
+///
+/// r[impl cli.pre-commit.references]
+pub async fn detect_broken_references(
+    project_root: &Path,
+    config: &Config,
+) -> Result<Vec<BrokenReference>> {
+    let base = index_base(project_root);
+    detect_broken_references_in(project_root, config, base, Target::Index).await
+}
+
+/// Like [`detect_broken_references`], but between any base commit and target.
+pub async fn detect_broken_references_in(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+    target: Target<'_>,
+) -> Result<Vec<BrokenReference>> {
+    let staged = target.changed_since(project_root, base, Some("ACMR"))?;
+    let sources = ImplSources::new(config);
+    let staged: Vec<&str> = staged
+        .lines()
+        .map(str::trim)
+        .filter(|file| sources.contains(file))
+        .collect();
+    if staged.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let versions = rule_versions(project_root, config, target).await?;
+    let mut broken = Vec::new();
+    for file in staged {
+        // Binary or non-UTF-8 files have no references.
+        let Some(new_content) = target.read(project_root, file).ok().flatten() else {
+            continue;
+        };
+        let existing: std::collections::HashSet<String> =
+            match git_cat_file(project_root, base, file).ok().flatten() {
+                Some(old) => tracey_core::Reqs::extract_from_content(Path::new(file), &old)
+                    .references
+                    .iter()
+                    .map(|r| format!("{}[{} {}]", r.prefix, r.verb, r.req_id))
+                    .collect(),
+                None => Default::default(),
+            };
+
+        let reqs = tracey_core::Reqs::extract_from_content(Path::new(file), &new_content);
+        for reference in &reqs.references {
+            let text = format!(
+                "{}[{} {}]",
+                reference.prefix, reference.verb, reference.req_id
+            );
+            if existing.contains(&text) {
+                continue;
+            }
+            let problem = match versions.get(&reference.prefix) {
+                None => format!("no spec uses the prefix `{}`", reference.prefix),
+                Some(rules) => match rules.get(&reference.req_id.base) {
+                    None => format!("rule `{}` does not exist", reference.req_id.base),
+                    Some(&current) if reference.req_id.version > current => {
+                        format!("`{}` is only at version {}", reference.req_id.base, current)
+                    }
+                    Some(_) => continue,
+                },
+            };
+            broken.push(BrokenReference {
+                file: PathBuf::from(file),
+                line: reference.line,
+                text,
+                problem,
+            });
+        }
+    }
+    Ok(broken)
+}
+
+/// A rule that a spec change removes but sources still refer to.
+#[derive(Debug, Clone)]
+pub struct RemovedRule {
+    /// Spec file the rule was defined in at HEAD, relative to project root.
+    pub file: PathBuf,
+    /// Prefix of the spec the rule belonged to.
+    pub prefix: String,
+    pub rule_id: marq::RuleId,
+    /// Files and lines of the references to it.
+    pub references: Vec<(PathBuf, usize)>,
+}
+
+/// Find rules defined at HEAD that no staged spec file defines any more but
+/// that staged source files still reference. Rules whose HEAD definition was
+/// marked `status=deprecated` may be removed regardless.
+///
+/// r[impl cli.pre-commit.removed-rules]
+pub async fn detect_removed_rules(
+    project_root: &Path,
+    config: &Config,
+) -> Result<Vec<RemovedRule>> {
+    let base = index_base(project_root);
+    detect_removed_rules_in(project_root, config, base, Target::Index).await
+}
+
+/// Like [`detect_removed_rules`], but between any base commit and target.
+pub async fn detect_removed_rules_in(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+    target: Target<'_>,
+) -> Result<Vec<RemovedRule>> {
+    let staged = target.changed_since(project_root, base, None)?;
+    let spec_files: Vec<Globs> = config
+        .specs
+        .iter()
+        .map(|spec| glob_set(&spec.include))
+        .collect();
+    let changed_specs: Vec<&str> = staged
+        .lines()
+        .map(str::trim)
+        .filter(|file| spec_files.iter().any(|include| include.is_match(file)))
+        .collect();
+    if changed_specs.is_empty() {
+        return Ok(vec![]);
+    }
+    let versions = rule_versions(project_root, config, target).await?;
+
+    // Rules gone from every staged spec file.
+    let mut removed: Vec<RemovedRule> = Vec::new();
+    for file in changed_specs {
+        let Some(old_content) = git_cat_file(project_root, base, file)? else {
+            continue;
+        };
+        for (base, req) in parse_spec_rules(&old_content).await? {
+            let deprecated = req
+                .metadata
+                .status
+                .is_some_and(|s| s.as_str().eq_ignore_ascii_case("deprecated"));
+            let Some(prefix) = crate::extract_marker_prefix(&old_content, req.marker_span) else {
+                continue;
+            };
+            let still_defined = versions.get(&prefix).is_some_and(|r| r.contains_key(&base));
+            if !deprecated && !still_defined {
+                removed.push(RemovedRule {
+                    file: PathBuf::from(file),
+                    prefix,
+                    rule_id: req.id.clone(),
+                    references: vec![],
+                });
+            }
+        }
+    }
+    if removed.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let sources = ImplSources::new(config);
+    let indexed = target.files(project_root)?;
+    for file in indexed.lines().filter(|f| sources.contains(f)) {
+        let Some(content) = target.read(project_root, file).ok().flatten() else {
+            continue;
+        };
+        let reqs = tracey_core::Reqs::extract_from_content(Path::new(file), &content);
+        for reference in &reqs.references {
+            for rule in &mut removed {
+                if reference.prefix == rule.prefix && reference.req_id.base == rule.rule_id.base {
+                    rule.references.push((PathBuf::from(file), reference.line));
+                }
+            }
+        }
+    }
+
+    removed.retain(|rule| !rule.references.is_empty());
+    Ok(removed)
+}
+
+fn report_references(broken: &[BrokenReference], removed: &[RemovedRule]) {
+    for reference in broken {
+        eprintln!(
+            "error: {}:{}: `{}`: {}",
+            reference.file.display(),
+            reference.line,
+            reference.text,
+            reference.problem
+        );
+    }
+    for rule in removed {
+        eprintln!(
+            "error: rule `{}` was removed from {} but is still referenced",
+            rule.rule_id,
+            rule.file.display()
+        );
+        for (file, line) in &rule.references {
+            eprintln!("  {}:{}", file.display(), line);
+        }
+    }
+}
+
+fn report_unbumped(changes: &[ChangedRule]) {
+    for change in changes {
+        eprintln!(
+            "error: rule `{}` body changed but version was not bumped",
+            change.rule_id
+        );
+        eprintln!("  file: {}", change.file.display());
+    }
+}
+
+/// The upstream of the current branch, or `origin/HEAD` if it has none.
+pub fn default_upstream(project_root: &Path) -> Result<String> {
+    for rev in ["@{upstream}", "origin/HEAD"] {
+        if let Ok(name) = git_capture(
+            project_root,
+            &["rev-parse", "--abbrev-ref", "--symbolic-full-name", rev],
+        ) && !name.trim().is_empty()
+        {
+            return Ok(name.trim().to_string());
+        }
+    }
+    bail!("the current branch has no upstream and there is no origin/HEAD; pass --upstream REV")
+}
+
+/// Check every commit in `upstream..head` against its parent the way
+/// [`pre_commit`] checks the index, so commits that bypassed the pre-commit
+/// hook (amends, rebases, `--no-verify`) are caught before they're pushed.
+/// `Tracey-No-Bump:` trailers in each commit's message are honored.
+///
+/// Prints diagnostics to stderr and returns whether every commit passed.
+///
+/// r[impl cli.pre-push]
+pub async fn pre_push(
+    project_root: &Path,
+    config: &Config,
+    upstream: &str,
+    head: &str,
+) -> Result<bool> {
+    let range = format!("{}..{}", upstream, head);
+    let commits = git_capture(
+        project_root,
+        &["rev-list", "--reverse", "--no-merges", &range],
+    )?;
+
+    let mut passed = true;
+    for commit in commits.lines().map(str::trim).filter(|c| !c.is_empty()) {
+        let parent = git_capture(
+            project_root,
+            &["rev-parse", "--verify", "--quiet", &format!("{}^", commit)],
+        )
+        .map(|p| p.trim().to_string())
+        .unwrap_or_else(|_| EMPTY_TREE.to_string());
+        let target = Target::Commit(commit);
+
+        let message = git_capture(project_root, &["log", "-1", "--format=%B", commit])?;
+        let exemption = NoBumpExemption::from_commit_message(&message);
+        let changes: Vec<ChangedRule> =
+            detect_changed_rules_in(project_root, config, &parent, target)
+                .await?
+                .into_iter()
+                .filter(|c| !exemption.covers(c))
+                .collect();
+        let broken = detect_broken_references_in(project_root, config, &parent, target).await?;
+        let removed = detect_removed_rules_in(project_root, config, &parent, target).await?;
+        if changes.is_empty() && broken.is_empty() && removed.is_empty() {
+            continue;
+        }
+
+        passed = false;
+        let summary = git_capture(project_root, &["show", "-s", "--format=%h %s", commit])?;
+        eprintln!("commit {}:", summary.trim());
+        report_references(&broken, &removed);
+        report_unbumped(&changes);
+        eprintln!();
+    }
+
+    if !passed {
+        eprintln!(
+            "Hint: fix the commits above (e.g. with `git rebase -i {}`), or add a",
+            upstream
+        );
+        eprintln!("      `Tracey-No-Bump: RULE` trailer to commits with editorial rule changes.");
+        eprintln!("      Or push with --no-verify to skip this check.");
+    }
+    Ok(passed)
+}
+
 /// Check staged spec changes and exit non-zero if any rule text changed without
 /// a version bump. Intended to be called from a git pre-commit hook.
 ///
 /// Prints diagnostics to stderr and returns whether the check passed.
 pub async fn pre_commit(project_root: &Path, config: &Config) -> Result<bool> {
+    pre_commit_with(project_root, config, &[]).await
+}
+
+/// Like [`pre_commit`], but let the rules in `exemptions` through unbumped.
+/// If the check passes, the exempted changes are logged in
+/// `SPEC-CHANGELOG.md` with their reasons and the changelog is staged.
+pub async fn pre_commit_with(
+    project_root: &Path,
+    config: &Config,
+    exemptions: &[NoBumpExemption],
+) -> Result<bool> {
     let changes = detect_changed_rules(project_root, config).await?;
+    let changes = without_skipped(project_root, changes)?;
+    let (exempt, changes): (Vec<ChangedRule>, Vec<ChangedRule>) = changes
+        .into_iter()
+        .partition(|c| exemptions.iter().any(|e| e.covers(c)));
+
+    let broken = detect_broken_references(project_root, config).await?;
+    let removed = detect_removed_rules(project_root, config).await?;
+    report_references(&broken, &removed);
+    let references_ok = broken.is_empty() && removed.is_empty();
+    if !references_ok && changes.is_empty() {
+        eprintln!();
+        eprintln!("Hint: fix or remove the references above, then re-stage.");
+        if !removed.is_empty() {
+            eprintln!("      To retire a rule first, mark it `status=deprecated` and commit that.");
+        }
+        eprintln!("      Or commit with --no-verify to skip this check.");
+        return Ok(false);
+    }
 
     if changes.is_empty() {
+        if !exempt.is_empty() {
+            let exempt: Vec<(&ChangedRule, &str)> = exempt
+                .iter()
+                .map(|c| {
+                    let reason = exemptions.iter().find(|e| e.covers(c)).unwrap();
+                    (c, reason.reason.as_str())
+                })
+                .collect();
+            for (change, reason) in &exempt {
+                eprintln!(
+                    "note: `{}` changed without a bump: {}",
+                    change.rule_id, reason
+                );
+            }
+            let today = crate::changelog::today();
+            crate::changelog::update_exempted(project_root, &today, &exempt)?;
+        }
         return Ok(true);
     }
 
-    for change in &changes {
+    if !references_ok {
+        eprintln!();
+    }
+    report_unbumped(&changes);
+    eprintln!();
+    if crate::config::version_scheme(config)? == tracey_core::VersionScheme::FreeForm {
+        eprintln!("Hint: pick a higher version for each changed rule, then re-stage.");
+    } else {
         eprintln!(
-            "error: rule `{}` body changed but version was not bumped",
-            change.rule_id
+            "Hint: run `tracey bump` to automatically bump all changed rules, then re-stage."
         );
-        eprintln!("  file: {}", change.file.display());
     }
-    eprintln!();
-    eprintln!("Hint: run `tracey bump` to automatically bump all changed rules, then re-stage.");
+    eprintln!("      For an editorial change, pass --allow-no-bump RULE --reason TEXT instead.");
     eprintln!("      Or commit with --no-verify to skip this check.");
 
     Ok(false)
@@ -200,24 +846,98 @@ pub async fn pre_commit(project_root: &Path, config: &Config) -> Result<bool> {
 /// Edits are applied last-to-first within each file so that earlier byte
 /// offsets are not invalidated by preceding edits.
 pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::RuleId>> {
+    Ok(bump_rules(project_root, config)
+        .await?
+        .into_iter()
+        .map(|rule| rule.new_id)
+        .collect())
+}
+
+/// Like [`bump`], but also return each bumped rule's file and old and new text.
+pub async fn bump_rules(project_root: &Path, config: &Config) -> Result<Vec<BumpedRule>> {
     let changes = detect_changed_rules(project_root, config).await?;
+    apply_bumps(project_root, config, &changes)
+}
+
+/// Bump the rules whose text differs between `base` and `target` without a
+/// version bump. With [`Target::WorkTree`] the files on disk are edited and
+/// the index is left alone; with [`Target::Index`] the files are re-staged.
+///
+/// r[impl cli.bump.working-tree]
+pub async fn bump_rules_in(
+    project_root: &Path,
+    config: &Config,
+    base: &str,
+    target: Target<'_>,
+) -> Result<Vec<BumpedRule>> {
+    let changes = detect_changed_rules_in(project_root, config, base, target).await?;
+    apply_bumps_in(project_root, config, &changes, target)
+}
+
+/// Today's date as a `date` scheme version (`YYYYMMDD`).
+pub(crate) fn today_version() -> u32 {
+    crate::changelog::today()
+        .replace('-', "")
+        .parse()
+        .expect("date is all digits")
+}
 
+/// Bump the given changes (as returned by [`detect_changed_rules`]) and
+/// re-stage their files.
+pub fn apply_bumps(
+    project_root: &Path,
+    config: &Config,
+    changes: &[ChangedRule],
+) -> Result<Vec<BumpedRule>> {
+    apply_bumps_in(project_root, config, changes, Target::Index)
+}
+
+/// Like [`apply_bumps`], for changes detected against `target`.
+fn apply_bumps_in(
+    project_root: &Path,
+    config: &Config,
+    changes: &[ChangedRule],
+    target: Target<'_>,
+) -> Result<Vec<BumpedRule>> {
+    if let Target::Commit(rev) = target {
+        bail!("can't bump rules in commit {}", rev);
+    }
     if changes.is_empty() {
         return Ok(vec![]);
     }
 
+    // r[impl config.version-scheme]
+    let scheme = crate::config::version_scheme(config)?;
+    let today = today_version();
+    let new_versions = changes
+        .iter()
+        .map(|change| {
+            scheme
+                .next(change.rule_id.version, today)
+                .map_err(|reason| {
+                    eyre::eyre!(
+                        "can't bump `{}` in {}: {}",
+                        change.rule_id,
+                        change.file.display(),
+                        reason
+                    )
+                })
+        })
+        .collect::<Result<Vec<u32>>>()?;
+
     // Group changes by file.
     let mut by_file: HashMap<PathBuf, Vec<usize>> = HashMap::new();
     for (i, change) in changes.iter().enumerate() {
         by_file.entry(change.file.clone()).or_default().push(i);
     }
 
-    let mut bumped_ids = Vec::new();
+    let mut bumped = Vec::new();
 
     for (file, indices) in &by_file {
         let file_str = file.to_string_lossy();
-        let content = git_cat_file(project_root, "", &file_str)?
-            .ok_or_else(|| eyre::eyre!("file disappeared from index: {}", file.display()))?;
+        let content = target
+            .read(project_root, &file_str)?
+            .ok_or_else(|| eyre::eyre!("file disappeared: {}", file.display()))?;
 
         let mut bytes = content.into_bytes();
 
@@ -232,7 +952,7 @@ pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::Rule
 
         for &idx in &sorted_indices {
             let change = &changes[idx];
-            let new_version = change.rule_id.version + 1;
+            let new_version = new_versions[idx];
 
             // Extract the prefix (chars before `[`) from the current marker bytes.
             let span = change.marker_span;
@@ -245,15 +965,23 @@ pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::Rule
             let prefix = &marker_str[..bracket];
 
             // Build the new marker, e.g. `r[auth.login+2]`.
-            let new_marker = format!("{}[{}+{}]", prefix, change.rule_id.base, new_version);
+            let new_id = tracey_core::RuleId::new(change.rule_id.base.clone(), new_version)
+                .ok_or_else(|| eyre::eyre!("invalid version {}", new_version))?;
+            let new_marker = format!("{}[{}]", prefix, new_id);
 
             let start = span.offset;
             let end = start + span.length;
             bytes.splice(start..end, new_marker.into_bytes());
 
-            bumped_ids.push(marq::RuleId {
-                base: change.rule_id.base.clone(),
-                version: new_version,
+            bumped.push(BumpedRule {
+                file: change.file.clone(),
+                old_id: change.rule_id.clone(),
+                new_id: marq::RuleId {
+                    base: change.rule_id.base.clone(),
+                    version: new_version,
+                },
+                old_raw: change.old_raw.clone(),
+                new_raw: change.new_raw.clone(),
             });
         }
 
@@ -262,9 +990,281 @@ pub async fn bump(project_root: &Path, config: &Config) -> Result<Vec<marq::Rule
         std::fs::write(&full_path, &bytes)
             .wrap_err_with(|| format!("failed to write {}", full_path.display()))?;
 
-        git_capture(project_root, &["update-index", "--add", "--", &file_str])
-            .wrap_err_with(|| format!("failed to re-stage {}", file.display()))?;
+        if let Target::Index = target {
+            git_capture(project_root, &["update-index", "--add", "--", &file_str])
+                .wrap_err_with(|| format!("failed to re-stage {}", file.display()))?;
+        }
+    }
+
+    Ok(bumped)
+}
+
+/// One answer to the `tracey bump -i` prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Bump,
+    Skip,
+    Edit,
+    Quit,
+}
+
+fn parse_choice(answer: &str) -> Option<Choice> {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "b" | "bump" | "y" | "yes" => Some(Choice::Bump),
+        "s" | "skip" | "n" | "no" => Some(Choice::Skip),
+        "e" | "edit" => Some(Choice::Edit),
+        "q" | "quit" => Some(Choice::Quit),
+        _ => None,
+    }
+}
+
+/// 1-based line of byte `offset` in `content`.
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+/// Open `file` at `line` in `$VISUAL` / `$EDITOR` (default `vi`) and wait for it.
+fn edit_in_editor(project_root: &Path, file: &Path, line: usize) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Like git, let the shell split `$EDITOR` so it can carry arguments.
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(&editor)
+        .arg(format!("+{}", line))
+        .arg(file)
+        .current_dir(project_root)
+        .status()
+        .wrap_err_with(|| format!("failed to run editor `{}`", editor))?;
+    if !status.success() {
+        bail!("editor `{}` exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// Where `tracey bump -i` records the rule changes the user chose to skip,
+/// inside the git directory so it's never committed.
+fn skipped_path(project_root: &Path) -> Result<PathBuf> {
+    let path = git_capture(
+        project_root,
+        &["rev-parse", "--git-path", "tracey-skipped-bumps"],
+    )?;
+    Ok(project_root.join(path.trim()))
+}
+
+/// Identifies a skipped change: the rule, its file and a hash of its new
+/// text, so that changing the text again needs a new decision.
+fn skip_key(change: &ChangedRule) -> String {
+    // FNV-1a, which is stable across Rust versions unlike `DefaultHasher`.
+    let hash = change
+        .new_raw
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!(
+        "{}\t{}\t{:016x}",
+        change.file.display(),
+        change.rule_id,
+        hash
+    )
+}
+
+fn load_skipped(project_root: &Path) -> Result<Vec<String>> {
+    match std::fs::read_to_string(skipped_path(project_root)?) {
+        Ok(content) => Ok(content.lines().map(str::to_string).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e).wrap_err("failed to read skipped bumps"),
+    }
+}
+
+/// Leave out changes that `tracey bump -i` was told to skip.
+///
+/// r[impl cli.bump.interactive]
+pub fn without_skipped(project_root: &Path, changes: Vec<ChangedRule>) -> Result<Vec<ChangedRule>> {
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+    let skipped = load_skipped(project_root)?;
+    Ok(changes
+        .into_iter()
+        .filter(|change| !skipped.contains(&skip_key(change)))
+        .collect())
+}
+
+/// Changed rules in file and spec order, for asking about them one by one.
+async fn detect_in_order(project_root: &Path, config: &Config) -> Result<Vec<ChangedRule>> {
+    let mut changes = detect_changed_rules(project_root, config).await?;
+    changes.sort_by(|a, b| (&a.file, a.marker_span.offset).cmp(&(&b.file, b.marker_span.offset)));
+    Ok(changes)
+}
+
+/// Walk through each staged rule change, show its diff and ask whether to
+/// bump it, skip it (for typo fixes that shouldn't make references stale),
+/// or edit it first. Answers are read from `input`, prompts go to `output`.
+///
+/// r[impl cli.bump.interactive]
+pub async fn bump_interactive(
+    project_root: &Path,
+    config: &Config,
+    input: &mut dyn std::io::BufRead,
+    output: &mut dyn std::io::Write,
+) -> Result<Vec<BumpedRule>> {
+    let scheme = crate::config::version_scheme(config)?;
+    let today = today_version();
+    let mut changes = detect_in_order(project_root, config).await?;
+    // Decisions by (file, base ID), which survive re-detection after an edit.
+    let mut decisions: HashMap<(PathBuf, String), Choice> = HashMap::new();
+
+    let mut quit = false;
+    while !quit {
+        let Some(change) = changes
+            .iter()
+            .find(|c| !decisions.contains_key(&(c.file.clone(), c.rule_id.base.clone())))
+        else {
+            break;
+        };
+        let key = (change.file.clone(), change.rule_id.base.clone());
+        let content =
+            git_cat_file(project_root, "", &change.file.to_string_lossy())?.unwrap_or_default();
+        let line = line_of(&content, change.marker_span.offset);
+
+        writeln!(
+            output,
+            "\n{} ({}:{})\n\n{}\n",
+            change.rule_id,
+            change.file.display(),
+            line,
+            marq::diff_markdown_inline(&change.old_raw, &change.new_raw).trim()
+        )?;
+        let target = scheme.next(change.rule_id.version, today);
+        match &target {
+            Ok(version) => write!(
+                output,
+                "Bump to {}? [b]ump, [s]kip, [e]dit, [q]uit: ",
+                tracey_core::RuleId::new(change.rule_id.base.clone(), *version)
+                    .map_or_else(|| version.to_string(), |id| id.to_string())
+            )?,
+            Err(reason) => write!(output, "Can't bump ({}). [s]kip, [e]dit, [q]uit: ", reason)?,
+        }
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            writeln!(output)?;
+            break;
+        }
+        match parse_choice(&answer) {
+            Some(Choice::Bump) if target.is_err() => {
+                writeln!(output, "This rule can't be bumped; skip or edit it.")?;
+            }
+            Some(Choice::Edit) => {
+                edit_in_editor(project_root, &change.file, line)?;
+                let file = change.file.to_string_lossy().to_string();
+                git_capture(project_root, &["add", "--", &file])?;
+                // Offsets of every rule in the file may have moved.
+                changes = detect_in_order(project_root, config).await?;
+                if !changes
+                    .iter()
+                    .any(|c| c.file == key.0 && c.rule_id.base == key.1)
+                {
+                    writeln!(output, "{} no longer needs a bump.", key.1)?;
+                }
+            }
+            Some(Choice::Quit) => quit = true,
+            Some(choice) => {
+                decisions.insert(key, choice);
+            }
+            None => writeln!(output, "Please answer b, s, e or q.")?,
+        }
+    }
+
+    let decided = |choice: Choice| -> Vec<&ChangedRule> {
+        changes
+            .iter()
+            .filter(|c| decisions.get(&(c.file.clone(), c.rule_id.base.clone())) == Some(&choice))
+            .collect()
+    };
+
+    let mut skipped = load_skipped(project_root)?;
+    skipped.retain(|key| changes.iter().any(|c| skip_key(c) == *key));
+    for change in decided(Choice::Skip) {
+        let key = skip_key(change);
+        if !skipped.contains(&key) {
+            skipped.push(key);
+        }
+    }
+    let path = skipped_path(project_root)?;
+    if skipped.is_empty() {
+        let _ = std::fs::remove_file(&path);
+    } else {
+        std::fs::write(&path, skipped.join("\n") + "\n")
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+
+    let to_bump: Vec<ChangedRule> = decided(Choice::Bump).into_iter().cloned().collect();
+    apply_bumps(project_root, config, &to_bump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prompt_answers() {
+        assert_eq!(parse_choice("b\n"), Some(Choice::Bump));
+        assert_eq!(parse_choice(" Skip "), Some(Choice::Skip));
+        assert_eq!(parse_choice("e"), Some(Choice::Edit));
+        assert_eq!(parse_choice("q"), Some(Choice::Quit));
+        assert_eq!(parse_choice(""), None);
+        assert_eq!(parse_choice("maybe"), None);
     }
 
-    Ok(bumped_ids)
+    // r[verify config.impl.include.negation]
+    #[test]
+    fn negated_globs_remove_files_again() {
+        let globs = glob_set(&[
+            "docs/**/*.md".to_string(),
+            "!docs/drafts/**".to_string(),
+            "docs/drafts/ready.md".to_string(),
+        ]);
+        assert!(globs.is_match("docs/spec.md"));
+        assert!(!globs.is_match("docs/drafts/wip.md"));
+        assert!(globs.is_match("docs/drafts/ready.md"));
+        assert!(!globs.is_match("src/lib.rs"));
+    }
+
+    #[test]
+    fn finds_marker_line() {
+        let content = "# Spec\n\nr[auth.login]\nText.\n";
+        assert_eq!(line_of(content, 0), 1);
+        assert_eq!(line_of(content, content.find("r[").unwrap()), 3);
+    }
+
+    #[test]
+    fn reads_no_bump_trailers() {
+        let message = "\
+Fix typos in the auth spec
+
+Some details.
+
+Tracey-No-Bump: auth.login, auth.session+2
+tracey-no-bump: auth.logout
+# Please enter the commit message for your changes.
+# Tracey-No-Bump: auth.ignored
+";
+        let exemption = NoBumpExemption::from_commit_message(message);
+        assert_eq!(
+            exemption.rules,
+            ["auth.login", "auth.session", "auth.logout"]
+        );
+        assert_eq!(exemption.reason, "Fix typos in the auth spec");
+        assert!(
+            NoBumpExemption::from_commit_message("Subject\n")
+                .rules
+                .is_empty()
+        );
+    }
 }
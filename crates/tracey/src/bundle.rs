@@ -0,0 +1,481 @@
+//! Evidence bundles for audits, for `tracey export --bundle` and
+//! `tracey verify-bundle`.
+//!
+//! r[impl cli.export.bundle]
+//!
+//! A bundle is a zstd-compressed tar archive with everything an auditor
+//! needs to see what was traced, at which revision:
+//!
+//! - `revision.json`: git commit, whether the tree was dirty, when the bundle
+//!   was made and by which tracey
+//! - `model.json`: the [`ExportModel`] with every rule, reference, code unit
+//!   and coverage number
+//! - `matrix.csv`: reference counts per rule and impl
+//! - `spec/...`: the spec files the rules were read from
+//! - `MANIFEST.b3`: the BLAKE3 digest of each file above, in `b3sum` format
+//! - `MANIFEST.b3.sig`: optionally, an SSH signature of the manifest
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+
+use crate::bridge::table::{Table, TableFormat};
+use crate::data::DashboardData;
+use crate::export::ExportModel;
+
+/// Version of the bundle layout, stored in `revision.json`.
+pub const BUNDLE_VERSION: u32 = 1;
+
+pub const MANIFEST: &str = "MANIFEST.b3";
+pub const SIGNATURE: &str = "MANIFEST.b3.sig";
+
+/// Namespace of bundle signatures, as `ssh-keygen -Y` calls it.
+pub const SIGNATURE_NAMESPACE: &str = "tracey-evidence";
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Revision {
+    pub bundle_version: u32,
+    pub tracey_version: String,
+    /// Unix time the bundle was made
+    pub created_at: u64,
+    /// `git rev-parse HEAD`, if the project is a git repository
+    #[facet(default)]
+    pub commit: Option<String>,
+    /// True if the working tree had uncommitted changes
+    #[facet(default)]
+    pub dirty: bool,
+}
+
+/// What [`verify`] found in an intact bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verified {
+    pub revision: Revision,
+    /// Files checked against the manifest
+    pub files: usize,
+    /// True if a signature was present and checked
+    pub signature_checked: bool,
+}
+
+/// Who may have signed a bundle, for `ssh-keygen -Y verify`.
+pub struct Signers<'a> {
+    pub allowed_signers: &'a Path,
+    pub identity: &'a str,
+}
+
+fn matrix_csv(model: &ExportModel) -> String {
+    let mut rows = Vec::new();
+    for spec in &model.specs {
+        for rule in &spec.rules {
+            for imp in &spec.impls {
+                let count = |kind: &str| {
+                    imp.references
+                        .iter()
+                        .filter(|r| r.rule_id == rule.id && r.kind == kind)
+                        .count()
+                        .to_string()
+                };
+                rows.push(vec![
+                    spec.name.clone(),
+                    rule.id.clone(),
+                    rule.version.to_string(),
+                    imp.name.clone(),
+                    count("impl"),
+                    count("verify"),
+                    count("stale"),
+                ]);
+            }
+        }
+    }
+    Table {
+        headers: vec![
+            "spec",
+            "rule",
+            "version",
+            "impl",
+            "impl_refs",
+            "verify_refs",
+            "stale_refs",
+        ],
+        rows,
+    }
+    .render(TableFormat::Csv)
+}
+
+fn revision(project_root: &Path) -> Revision {
+    let commit = crate::bump::git_capture(project_root, &["rev-parse", "HEAD"])
+        .ok()
+        .map(|out| out.trim().to_string());
+    let dirty = commit.is_some()
+        && crate::bump::git_capture(project_root, &["status", "--porcelain"])
+            .is_ok_and(|out| !out.trim().is_empty());
+    Revision {
+        bundle_version: BUNDLE_VERSION,
+        tracey_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: crate::history::now_secs(),
+        commit,
+        dirty,
+    }
+}
+
+/// Every file of a bundle except the manifest and signature, by path.
+pub fn contents(project_root: &Path, data: &DashboardData) -> Result<BTreeMap<String, Vec<u8>>> {
+    let model = ExportModel::from_data(data);
+    let mut files = BTreeMap::new();
+    for spec in &model.specs {
+        for source in spec.rules.iter().filter_map(|r| r.source_file.as_deref()) {
+            let path = format!("spec/{}", source.trim_start_matches('/'));
+            if files.contains_key(&path) {
+                continue;
+            }
+            let full = project_root.join(source);
+            let content = std::fs::read(&full)
+                .wrap_err_with(|| format!("Failed to read {}", full.display()))?;
+            files.insert(path, content);
+        }
+    }
+    files.insert("matrix.csv".to_string(), matrix_csv(&model).into_bytes());
+    files.insert(
+        "model.json".to_string(),
+        (model.to_json() + "\n").into_bytes(),
+    );
+    let revision =
+        facet_json::to_string_pretty(&revision(project_root)).expect("JSON serialization failed");
+    files.insert("revision.json".to_string(), (revision + "\n").into_bytes());
+    Ok(files)
+}
+
+/// `b3sum`-style lines for `files`, sorted by path.
+pub fn manifest(files: &BTreeMap<String, Vec<u8>>) -> String {
+    files
+        .iter()
+        .map(|(path, content)| format!("{}  {}\n", blake3::hash(content).to_hex(), path))
+        .collect()
+}
+
+/// Sign `message` with the SSH private key at `key`, in `namespace`.
+pub(crate) fn sign(message: &str, key: &Path, namespace: &str) -> Result<Vec<u8>> {
+    let mut child = Command::new("ssh-keygen")
+        .args(["-Y", "sign", "-n", namespace, "-f"])
+        .arg(key)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("Failed to run ssh-keygen (is OpenSSH installed?)")?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(message.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "Signing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Check that `signature` is `signers.identity`'s signature of `message` in
+/// `namespace`.
+pub(crate) fn check_signature(
+    message: &[u8],
+    signature: &[u8],
+    signers: &Signers,
+    namespace: &str,
+) -> Result<()> {
+    let mut sig_file = tempfile::Builder::new()
+        .prefix("tracey-bundle-")
+        .suffix(".sig")
+        .tempfile()?;
+    sig_file.write_all(signature)?;
+    let mut child = Command::new("ssh-keygen")
+        .args(["-Y", "verify", "-n", namespace, "-I", signers.identity])
+        .arg("-f")
+        .arg(signers.allowed_signers)
+        .arg("-s")
+        .arg(sig_file.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("Failed to run ssh-keygen (is OpenSSH installed?)")?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(message)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "Bad signature: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Write the bundle for `data` to `output`, signing it with `sign_key` if
+/// given. Returns the number of files covered by the manifest.
+pub fn write(
+    project_root: &Path,
+    data: &DashboardData,
+    output: &Path,
+    sign_key: Option<&Path>,
+) -> Result<usize> {
+    let mut files = contents(project_root, data)?;
+    let covered = files.len();
+    let manifest = manifest(&files);
+    if let Some(key) = sign_key {
+        files.insert(
+            SIGNATURE.to_string(),
+            sign(&manifest, key, SIGNATURE_NAMESPACE)?,
+        );
+    }
+    files.insert(MANIFEST.to_string(), manifest.into_bytes());
+
+    let mtime = crate::history::now_secs();
+    let mut archive = Vec::new();
+    for (path, content) in &files {
+        archive.extend(tar_header(path, content.len(), mtime)?);
+        archive.extend(content);
+        archive.resize(archive.len().next_multiple_of(BLOCK), 0);
+    }
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+
+    let compressed = zstd::encode_all(archive.as_slice(), 19)?;
+    std::fs::write(output, compressed)
+        .wrap_err_with(|| format!("Failed to write {}", output.display()))?;
+    Ok(covered)
+}
+
+/// r[impl cli.verify-bundle]
+///
+/// Check that every file of the bundle at `path` matches the manifest and
+/// nothing was added or removed. With `signers`, the manifest must also
+/// carry a valid signature by that identity.
+pub fn verify(path: &Path, signers: Option<&Signers>) -> Result<Verified> {
+    let compressed =
+        std::fs::read(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let archive = zstd::decode_all(compressed.as_slice())
+        .wrap_err_with(|| format!("{} is not a zstd-compressed bundle", path.display()))?;
+    let mut files = read_tar(&archive)?;
+
+    let Some(manifest) = files.remove(MANIFEST) else {
+        bail!("{} has no {}", path.display(), MANIFEST);
+    };
+    let signature = files.remove(SIGNATURE);
+    let signature_checked = match (signers, &signature) {
+        (Some(signers), Some(signature)) => {
+            check_signature(&manifest, signature, signers, SIGNATURE_NAMESPACE)?;
+            true
+        }
+        (Some(_), None) => bail!("{} is not signed", path.display()),
+        (None, _) => false,
+    };
+
+    let revision = files.get("revision.json").cloned().unwrap_or_default();
+    let manifest = String::from_utf8(manifest).wrap_err("Manifest is not valid UTF-8")?;
+    let mut problems = Vec::new();
+    let mut listed = 0;
+    for line in manifest.lines().filter(|line| !line.is_empty()) {
+        let Some((digest, name)) = line.split_once("  ") else {
+            bail!("Malformed manifest line: {}", line);
+        };
+        listed += 1;
+        match files.remove(name) {
+            Some(content) if blake3::hash(&content).to_hex().as_str() == digest => {}
+            Some(_) => problems.push(format!("{} was modified", name)),
+            None => problems.push(format!("{} is missing", name)),
+        }
+    }
+    problems.extend(
+        files
+            .keys()
+            .map(|name| format!("{} is not in the manifest", name)),
+    );
+    if !problems.is_empty() {
+        bail!(
+            "{} failed verification:\n  {}",
+            path.display(),
+            problems.join("\n  ")
+        );
+    }
+
+    let revision: Revision = facet_json::from_str(&String::from_utf8_lossy(&revision))
+        .map_err(|e| eyre::eyre!("Invalid revision.json: {}", e))?;
+    Ok(Verified {
+        revision,
+        files: listed,
+        signature_checked,
+    })
+}
+
+const BLOCK: usize = 512;
+
+/// A ustar header for a regular file.
+fn tar_header(path: &str, size: usize, mtime: u64) -> Result<[u8; BLOCK]> {
+    let mut header = [0u8; BLOCK];
+    let (prefix, name) = if path.len() <= 100 {
+        ("", path)
+    } else {
+        // Split at a `/` so the name fits in 100 bytes and the rest in 155.
+        let split = path
+            .char_indices()
+            .filter(|&(i, c)| c == '/' && i <= 155 && path.len() - i - 1 <= 100)
+            .map(|(i, _)| i)
+            .next();
+        let Some(split) = split else {
+            bail!("Path too long for a bundle: {}", path);
+        };
+        (&path[..split], &path[split + 1..])
+    };
+    let put = |header: &mut [u8; BLOCK], offset: usize, value: &[u8]| {
+        header[offset..offset + value.len()].copy_from_slice(value);
+    };
+    put(&mut header, 0, name.as_bytes());
+    put(&mut header, 100, b"0000644\0");
+    put(&mut header, 108, b"0000000\0");
+    put(&mut header, 116, b"0000000\0");
+    put(&mut header, 124, format!("{:011o}\0", size).as_bytes());
+    put(&mut header, 136, format!("{:011o}\0", mtime).as_bytes());
+    put(&mut header, 148, b"        ");
+    header[156] = b'0';
+    put(&mut header, 257, b"ustar\x0000");
+    put(&mut header, 345, prefix.as_bytes());
+    let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+    put(&mut header, 148, format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+fn tar_field(bytes: &[u8]) -> &str {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..end]).unwrap_or("").trim()
+}
+
+/// The regular files of a tar archive, by path.
+fn read_tar(mut archive: &[u8]) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    let mut header = [0u8; BLOCK];
+    while archive.read_exact(&mut header).is_ok() {
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let name = tar_field(&header[0..100]);
+        let prefix = tar_field(&header[345..500]);
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let size = usize::from_str_radix(tar_field(&header[124..136]), 8)
+            .wrap_err_with(|| format!("Bad size in the tar header of {}", path))?;
+        if archive.len() < size {
+            bail!("Truncated bundle: {} is cut short", path);
+        }
+        let (content, rest) = archive.split_at(size);
+        archive = rest
+            .get(size.next_multiple_of(BLOCK) - size..)
+            .unwrap_or(&[]);
+        if matches!(header[156], b'0' | 0) && files.insert(path.clone(), content.to_vec()).is_some()
+        {
+            bail!("{} appears twice in the bundle", path);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> BTreeMap<String, Vec<u8>> {
+        let deep = format!(
+            "spec/{}/spec.md",
+            "nested/".repeat(20).trim_end_matches('/')
+        );
+        [
+            ("model.json", b"{}\n".to_vec()),
+            (
+                "revision.json",
+                br#"{"bundleVersion": 1, "traceyVersion": "1.0.0", "createdAt": 0}"#.to_vec(),
+            ),
+            (deep.as_str(), b"r[a.b]\nText.\n".to_vec()),
+        ]
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content))
+        .collect()
+    }
+
+    fn archive(files: &BTreeMap<String, Vec<u8>>) -> Vec<u8> {
+        let mut archive = Vec::new();
+        for (path, content) in files {
+            archive.extend(tar_header(path, content.len(), 0).unwrap());
+            archive.extend(content);
+            archive.resize(archive.len().next_multiple_of(BLOCK), 0);
+        }
+        archive.resize(archive.len() + 2 * BLOCK, 0);
+        archive
+    }
+
+    #[test]
+    fn tar_round_trips_long_paths() {
+        let files = files();
+        assert_eq!(read_tar(&archive(&files)).unwrap(), files);
+    }
+
+    #[test]
+    fn manifest_lists_digests_by_path() {
+        let manifest = manifest(&files());
+        let lines: Vec<&str> = manifest.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            format!("{}  model.json", blake3::hash(b"{}\n").to_hex())
+        );
+    }
+
+    #[test]
+    fn verify_reports_tampering() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp.path().join("evidence.tar.zst");
+        let write_bundle = |files: &BTreeMap<String, Vec<u8>>, manifest: String| {
+            let mut files = files.clone();
+            files.insert(MANIFEST.to_string(), manifest.into_bytes());
+            let compressed = zstd::encode_all(archive(&files).as_slice(), 3).unwrap();
+            std::fs::write(&path, compressed).unwrap();
+        };
+
+        let original = files();
+        write_bundle(&original, manifest(&original));
+        let verified = verify(&path, None).unwrap();
+        assert_eq!(verified.files, 3);
+        assert_eq!(verified.revision.bundle_version, 1);
+        assert!(!verified.signature_checked);
+
+        let mut tampered = original.clone();
+        tampered.insert("model.json".to_string(), b"{\"specs\": []}\n".to_vec());
+        tampered.insert("extra.txt".to_string(), b"hi".to_vec());
+        write_bundle(&tampered, manifest(&original));
+        let error = verify(&path, None).unwrap_err().to_string();
+        assert!(error.contains("model.json was modified"), "{error}");
+        assert!(
+            error.contains("extra.txt is not in the manifest"),
+            "{error}"
+        );
+
+        let signers = Signers {
+            allowed_signers: Path::new("allowed_signers"),
+            identity: "auditor@example.com",
+        };
+        write_bundle(&original, manifest(&original));
+        let error = verify(&path, Some(&signers)).unwrap_err().to_string();
+        assert!(error.contains("is not signed"), "{error}");
+    }
+}
@@ -0,0 +1,269 @@
+//! Cargo workspace discovery, for the `cargo tracey` subcommand.
+//!
+//! r[impl cli.cargo]
+//!
+//! `cargo tracey ARGS` runs `tracey ARGS` with the project root set to the
+//! cargo workspace root, so it works from any member crate. `cargo tracey
+//! members` maps workspace members to impl include patterns.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+
+use crate::config::Config;
+
+#[derive(Facet)]
+struct Metadata {
+    workspace_root: String,
+    packages: Vec<Package>,
+}
+
+#[derive(Facet)]
+struct Package {
+    name: String,
+    manifest_path: String,
+}
+
+/// A cargo workspace and its member packages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<Member>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub name: String,
+    /// Package directory relative to the workspace root; empty for a root package
+    pub dir: String,
+}
+
+impl Workspace {
+    /// Run `cargo metadata` in `dir`. Uses `$CARGO` when cargo runs us as a
+    /// subcommand, so the same toolchain answers.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let output = Command::new(cargo)
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .current_dir(dir)
+            .output()
+            .wrap_err("Failed to run cargo metadata")?;
+        if !output.status.success() {
+            bail!(
+                "cargo metadata failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Self::from_metadata(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parse the output of `cargo metadata --format-version 1 --no-deps`.
+    pub fn from_metadata(json: &str) -> Result<Self> {
+        let metadata: Metadata =
+            facet_json::from_str(json).map_err(|e| eyre::eyre!("Invalid cargo metadata: {}", e))?;
+        let root = PathBuf::from(&metadata.workspace_root);
+        let mut members: Vec<Member> = metadata
+            .packages
+            .into_iter()
+            .map(|package| {
+                let manifest = PathBuf::from(&package.manifest_path);
+                let dir = manifest
+                    .parent()
+                    .and_then(|dir| dir.strip_prefix(&root).ok())
+                    .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default();
+                Member {
+                    name: package.name,
+                    dir,
+                }
+            })
+            .collect();
+        members.sort_by(|a, b| a.dir.cmp(&b.dir));
+        Ok(Self { root, members })
+    }
+
+    /// Include patterns covering the sources and tests of every member.
+    pub fn include_patterns(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .flat_map(|member| {
+                let prefix = if member.dir.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", member.dir)
+                };
+                [
+                    format!("{prefix}src/**/*.rs"),
+                    format!("{prefix}tests/**/*.rs"),
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Add the workspace's include patterns that `spec`/`impl_name` lacks to
+/// its `include` list. `spec` and `impl_name` may be left out when the
+/// config has only one. Returns the added patterns.
+pub fn add_member_includes(
+    config: &mut Config,
+    workspace: &Workspace,
+    spec: Option<&str>,
+    impl_name: Option<&str>,
+) -> Result<Vec<String>> {
+    let impls: Vec<(String, String)> = config
+        .specs
+        .iter()
+        .filter(|s| spec.is_none_or(|name| s.name == name))
+        .flat_map(|s| s.impls.iter().map(|i| (s.name.clone(), i.name.clone())))
+        .filter(|(_, i)| impl_name.is_none_or(|name| i == name))
+        .collect();
+    let [(spec_name, impl_name)] = impls.as_slice() else {
+        let available: Vec<String> = config
+            .specs
+            .iter()
+            .flat_map(|s| {
+                s.impls
+                    .iter()
+                    .map(move |i| format!("{}/{}", s.name, i.name))
+            })
+            .collect();
+        if impls.is_empty() {
+            bail!("No matching impl; available: {}", available.join(", "));
+        }
+        bail!(
+            "Several impls match; pick one with --spec and --impl ({})",
+            available.join(", ")
+        );
+    };
+
+    let target = config
+        .specs
+        .iter_mut()
+        .find(|s| &s.name == spec_name)
+        .and_then(|s| s.impls.iter_mut().find(|i| &i.name == impl_name))
+        .expect("impl was just found");
+    let added: Vec<String> = workspace
+        .include_patterns()
+        .into_iter()
+        .filter(|pattern| !target.include.contains(pattern))
+        .collect();
+    target.include.extend(added.iter().cloned());
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Impl, SpecConfig};
+
+    const METADATA: &str = r#"{
+        "packages": [
+            {
+                "name": "app-cli",
+                "version": "0.1.0",
+                "id": "path+file:///work/app/crates/cli#app-cli@0.1.0",
+                "dependencies": [{ "name": "app-core", "req": "*", "kind": null }],
+                "targets": [{ "kind": ["bin"], "name": "app", "src_path": "/work/app/crates/cli/src/main.rs" }],
+                "features": {},
+                "manifest_path": "/work/app/crates/cli/Cargo.toml",
+                "metadata": null
+            },
+            {
+                "name": "app",
+                "version": "0.1.0",
+                "manifest_path": "/work/app/Cargo.toml",
+                "metadata": { "docs": { "rs": { "all-features": true } } }
+            }
+        ],
+        "workspace_members": ["path+file:///work/app/crates/cli#app-cli@0.1.0"],
+        "resolve": null,
+        "target_directory": "/work/app/target",
+        "version": 1,
+        "workspace_root": "/work/app",
+        "metadata": null
+    }"#;
+
+    fn config_with(impls: &[&str]) -> Config {
+        Config {
+            specs: vec![SpecConfig {
+                name: "spec".to_string(),
+                prefix: None,
+                source_url: None,
+                include: vec!["docs/**/*.md".to_string()],
+                rules_url: None,
+                rules_url_refresh: None,
+                source: None,
+                policy: None,
+                impls: impls
+                    .iter()
+                    .map(|name| Impl {
+                        name: name.to_string(),
+                        include: vec!["src/**/*.rs".to_string()],
+                        exclude: vec![],
+                        no_default_excludes: false,
+                        test_include: vec![],
+                        test_conventions: false,
+                        code_units: None,
+                        skip_units: None,
+                        variants: vec![],
+                        policy: None,
+                    })
+                    .collect(),
+            }],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn maps_members_to_include_patterns() {
+        let workspace = Workspace::from_metadata(METADATA).unwrap();
+        assert_eq!(workspace.root, PathBuf::from("/work/app"));
+        assert_eq!(
+            workspace.members,
+            [
+                Member {
+                    name: "app".to_string(),
+                    dir: String::new(),
+                },
+                Member {
+                    name: "app-cli".to_string(),
+                    dir: "crates/cli".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            workspace.include_patterns(),
+            [
+                "src/**/*.rs",
+                "tests/**/*.rs",
+                "crates/cli/src/**/*.rs",
+                "crates/cli/tests/**/*.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_only_missing_patterns() {
+        let workspace = Workspace::from_metadata(METADATA).unwrap();
+        let mut config = config_with(&["rust"]);
+        let added = add_member_includes(&mut config, &workspace, None, None).unwrap();
+        assert_eq!(
+            added,
+            [
+                "tests/**/*.rs",
+                "crates/cli/src/**/*.rs",
+                "crates/cli/tests/**/*.rs",
+            ]
+        );
+        assert_eq!(config.specs[0].impls[0].include.len(), 4);
+        let again = add_member_includes(&mut config, &workspace, None, None).unwrap();
+        assert!(again.is_empty());
+
+        let mut config = config_with(&["rust", "ts"]);
+        let error = add_member_includes(&mut config, &workspace, None, None).unwrap_err();
+        assert!(error.to_string().contains("spec/rust, spec/ts"), "{error}");
+        add_member_includes(&mut config, &workspace, None, Some("rust")).unwrap();
+    }
+}
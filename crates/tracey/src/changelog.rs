@@ -0,0 +1,197 @@
+//! `SPEC-CHANGELOG.md` entries for rules bumped by `tracey bump`.
+//!
+//! r[impl cli.bump.changelog]
+//!
+//! Each bump adds the bumped rules under a heading for the current date,
+//! newest date first, with a word-level diff of each rule's text (the same
+//! `~~removed~~` / `**added**` diff shown in hovers for stale references).
+//! The file is meant to be committed alongside the spec change, so bump
+//! stages it too.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+
+use crate::bump::{BumpedRule, ChangedRule, git_capture};
+
+const TITLE: &str = "# Spec changelog";
+
+/// Where the changelog lives, at the project root.
+pub fn changelog_path(project_root: &Path) -> PathBuf {
+    project_root.join("SPEC-CHANGELOG.md")
+}
+
+/// Today's date (UTC) as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let mut date = crate::history::format_timestamp(crate::history::now_secs());
+    date.truncate("YYYY-MM-DD".len());
+    date
+}
+
+/// Markdown for the bumped rules, one `###` entry per rule.
+pub fn render_entries(bumped: &[BumpedRule]) -> String {
+    let mut out = String::new();
+    for rule in bumped {
+        out.push_str(&format!(
+            "### `{}`\n\n`{}` (was `{}`)\n\n{}\n\n",
+            rule.new_id,
+            rule.file.display(),
+            rule.old_id,
+            marq::diff_markdown_inline(&rule.old_raw, &rule.new_raw).trim()
+        ));
+    }
+    out
+}
+
+/// Markdown for rules that changed without a bump, with the reason for each.
+///
+/// r[impl cli.pre-commit.no-bump]
+pub fn render_exempted(exempted: &[(&ChangedRule, &str)]) -> String {
+    let mut out = String::new();
+    for (rule, reason) in exempted {
+        out.push_str(&format!(
+            "### `{}` (not bumped)\n\n`{}`: {}\n\n{}\n\n",
+            rule.rule_id,
+            rule.file.display(),
+            reason,
+            marq::diff_markdown_inline(&rule.old_raw, &rule.new_raw).trim()
+        ));
+    }
+    out
+}
+
+/// Add `entries` to the changelog text under the heading for `date`,
+/// creating the heading (at the top) or the whole document as needed.
+pub fn insert_entries(existing: &str, date: &str, entries: &str) -> String {
+    let heading = format!("## {}\n", date);
+    let body = existing
+        .strip_prefix(TITLE)
+        .map(|rest| rest.trim_start_matches('\n'))
+        .unwrap_or(existing);
+
+    let rest = match body.strip_prefix(&heading) {
+        // Same day as the latest entry: new rules go first under its heading.
+        Some(rest) => rest.trim_start_matches('\n'),
+        None => body,
+    };
+
+    let mut out = format!("{}\n\n{}\n{}", TITLE, heading, entries);
+    if rest.trim().is_empty() {
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    } else {
+        out.push_str(rest);
+    }
+    out
+}
+
+/// Record `bumped` in the changelog and stage it.
+pub fn update(project_root: &Path, date: &str, bumped: &[BumpedRule]) -> Result<PathBuf> {
+    let path = write(project_root, date, bumped)?;
+    stage(project_root, &path)?;
+    Ok(path)
+}
+
+/// Record `bumped` in the changelog without staging it.
+pub fn write(project_root: &Path, date: &str, bumped: &[BumpedRule]) -> Result<PathBuf> {
+    let path = changelog_path(project_root);
+    let existing = read_existing(&path)?;
+    let updated = insert_entries(&existing, date, &render_entries(bumped));
+    std::fs::write(&path, updated)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Record rules that changed without a bump in the changelog and stage it.
+/// Entries that are already there (from an earlier, aborted commit of the
+/// same change) aren't added again.
+pub fn update_exempted(
+    project_root: &Path,
+    date: &str,
+    exempted: &[(&ChangedRule, &str)],
+) -> Result<PathBuf> {
+    let path = changelog_path(project_root);
+    let existing = read_existing(&path)?;
+    let new: Vec<(&ChangedRule, &str)> = exempted
+        .iter()
+        .filter(|entry| !existing.contains(render_exempted(&[*entry]).trim()))
+        .copied()
+        .collect();
+    if new.is_empty() {
+        return Ok(path);
+    }
+    write_entries(project_root, &existing, date, &render_exempted(&new))
+}
+
+fn read_existing(path: &Path) -> Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn write_entries(
+    project_root: &Path,
+    existing: &str,
+    date: &str,
+    entries: &str,
+) -> Result<PathBuf> {
+    let path = changelog_path(project_root);
+    let updated = insert_entries(existing, date, entries);
+    std::fs::write(&path, updated)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    stage(project_root, &path)?;
+    Ok(path)
+}
+
+fn stage(project_root: &Path, path: &Path) -> Result<()> {
+    let file = path
+        .strip_prefix(project_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string();
+    git_capture(project_root, &["update-index", "--add", "--", &file])
+        .wrap_err_with(|| format!("failed to stage {}", file))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTRY: &str = "### `auth.login+2`\n\n`spec.md` (was `auth.login`)\n\nText.\n\n";
+
+    // r[verify cli.bump.changelog]
+    #[test]
+    fn new_dates_go_on_top_and_same_day_entries_merge() {
+        let first = insert_entries("", "2026-01-01", ENTRY);
+        assert_eq!(
+            first,
+            "# Spec changelog\n\n## 2026-01-01\n\n### `auth.login+2`\n\n\
+             `spec.md` (was `auth.login`)\n\nText.\n"
+        );
+
+        let later = ENTRY.replace("auth.login", "auth.session");
+        let second = insert_entries(&first, "2026-02-01", &later);
+        assert!(
+            second.starts_with("# Spec changelog\n\n## 2026-02-01\n\n### `auth.session+2`"),
+            "{second}"
+        );
+        let older = format!("## 2026-01-01\n\n{}\n", ENTRY.trim_end());
+        assert!(second.ends_with(&older), "{second}");
+
+        let merged = insert_entries(&second, "2026-02-01", &ENTRY.replace("login", "logout"));
+        assert_eq!(merged.matches("## 2026-02-01").count(), 1, "{merged}");
+        let logout = merged.find("auth.logout+2").unwrap();
+        let session = merged.find("auth.session+2").unwrap();
+        assert!(logout < session, "newest entry first:\n{merged}");
+    }
+
+    #[test]
+    fn keeps_files_without_the_title() {
+        let updated = insert_entries("## 2025-12-31\n\nOlder notes.\n", "2026-01-01", ENTRY);
+        assert!(updated.starts_with("# Spec changelog\n\n## 2026-01-01\n"));
+        assert!(updated.ends_with("## 2025-12-31\n\nOlder notes.\n"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Compliance summaries for GRC tooling, for `tracey report --format
+//! compliance-json`.
+//!
+//! r[impl cli.report.compliance]
+//!
+//! The summary lists every requirement with a compliance status, the
+//! evidence behind it (each `impl`, `verify` and stale reference, per impl)
+//! and its approvals. Its layout is described by the JSON Schema in
+//! [`COMPLIANCE_SCHEMA`], which `tracey report --schema` prints; both carry
+//! [`COMPLIANCE_SCHEMA_VERSION`], bumped like the export's schema version.
+
+use std::path::Path;
+
+use facet::Facet;
+use tracey_api::{ApiCodeRef, ApiRule};
+
+use crate::data::DashboardData;
+
+/// Version of the compliance layout, stored in `schemaVersion`.
+pub const COMPLIANCE_SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema (draft 2020-12) of the compliance summary.
+pub const COMPLIANCE_SCHEMA: &str = include_str!("compliance.schema.json");
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ComplianceReport {
+    pub schema_version: u32,
+    /// Always `tracey-compliance`
+    pub format: String,
+    /// UTC time of the report, RFC 3339
+    pub generated_at: String,
+    pub tool: Tool,
+    #[facet(default)]
+    pub commit: Option<String>,
+    pub summary: Summary,
+    pub requirements: Vec<Requirement>,
+}
+
+#[derive(Debug, Clone, Facet)]
+pub struct Tool {
+    pub name: String,
+    pub version: String,
+}
+
+/// Requirement counts by status.
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Summary {
+    pub total: usize,
+    pub compliant: usize,
+    pub partially_compliant: usize,
+    pub non_compliant: usize,
+    pub not_applicable: usize,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Requirement {
+    /// Base rule ID, without the version suffix
+    pub id: String,
+    pub version: u32,
+    pub spec: String,
+    #[facet(default)]
+    pub level: Option<String>,
+    pub text: String,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+    pub status: ComplianceStatus,
+    pub implementations: Vec<Implementation>,
+    pub approvals: Vec<Approval>,
+    pub owners: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum ComplianceStatus {
+    /// Implemented and verified by every impl
+    Compliant,
+    /// Implemented by some impls, or implemented but not verified
+    PartiallyCompliant,
+    /// Implemented by no impl
+    NonCompliant,
+    /// Deprecated
+    NotApplicable,
+}
+
+/// How one impl stands on a requirement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+#[facet(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum ImplStatus {
+    /// Current `impl` and `verify` references
+    Verified,
+    /// Current `impl` references only
+    Implemented,
+    /// References to an older version of the rule
+    Stale,
+    Missing,
+}
+
+#[derive(Debug, Clone, Facet)]
+pub struct Implementation {
+    #[facet(rename = "impl")]
+    pub impl_name: String,
+    pub status: ImplStatus,
+    pub evidence: Vec<Evidence>,
+}
+
+/// A reference backing a status.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct Evidence {
+    /// `impl`, `verify`, `depends`, or `stale` for a reference to an older version
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Facet)]
+pub struct Approval {
+    pub by: String,
+    /// Date of the approval (YYYY-MM-DD)
+    pub date: String,
+}
+
+fn impl_status(rule: &ApiRule) -> ImplStatus {
+    if !rule.stale_refs.is_empty() {
+        ImplStatus::Stale
+    } else if rule.impl_refs.is_empty() {
+        ImplStatus::Missing
+    } else if rule.verify_refs.is_empty() {
+        ImplStatus::Implemented
+    } else {
+        ImplStatus::Verified
+    }
+}
+
+fn evidence(rule: &ApiRule) -> Vec<Evidence> {
+    let current = |kind: &str, refs: &[ApiCodeRef]| {
+        refs.iter()
+            .map(|r| Evidence {
+                kind: kind.to_string(),
+                file: r.file.clone(),
+                line: r.line,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut evidence = current("impl", &rule.impl_refs);
+    evidence.extend(current("verify", &rule.verify_refs));
+    evidence.extend(current("depends", &rule.depends_refs));
+    evidence.extend(rule.stale_refs.iter().map(|r| Evidence {
+        kind: "stale".to_string(),
+        file: r.file.clone(),
+        line: r.line,
+    }));
+    evidence
+}
+
+/// The compliance status of a rule, given how each impl stands on it.
+pub fn compliance_status(deprecated: bool, impls: &[ImplStatus]) -> ComplianceStatus {
+    if deprecated {
+        ComplianceStatus::NotApplicable
+    } else if !impls.is_empty() && impls.iter().all(|s| *s == ImplStatus::Verified) {
+        ComplianceStatus::Compliant
+    } else if impls
+        .iter()
+        .any(|s| matches!(s, ImplStatus::Verified | ImplStatus::Implemented))
+    {
+        ComplianceStatus::PartiallyCompliant
+    } else {
+        ComplianceStatus::NonCompliant
+    }
+}
+
+/// RFC 3339 UTC timestamp of Unix seconds.
+fn rfc3339(secs: u64) -> String {
+    format!(
+        "{}:{:02}Z",
+        crate::history::format_timestamp(secs).replace(' ', "T"),
+        secs % 60
+    )
+}
+
+impl ComplianceReport {
+    pub fn from_data(data: &DashboardData, generated_at: u64) -> Self {
+        let project_root = Path::new(&data.config.project_root);
+        let commit = crate::bump::git_capture(project_root, &["rev-parse", "HEAD"])
+            .ok()
+            .map(|out| out.trim().to_string());
+
+        let mut requirements = Vec::new();
+        let mut summary = Summary::default();
+        for spec in &data.config.specs {
+            let forwards: Vec<_> = spec
+                .implementations
+                .iter()
+                .filter_map(|impl_name| {
+                    data.forward_by_impl
+                        .get(&(spec.name.clone(), impl_name.clone()))
+                        .map(|forward| (impl_name, forward))
+                })
+                .collect();
+            // Rule text is the same for every impl, so take the rules from the first one.
+            let Some((_, first)) = forwards.first() else {
+                continue;
+            };
+            for rule in &first.rules {
+                let implementations: Vec<Implementation> = forwards
+                    .iter()
+                    .filter_map(|(impl_name, forward)| {
+                        let found = forward.rules.iter().find(|r| r.id == rule.id)?;
+                        Some(Implementation {
+                            impl_name: impl_name.to_string(),
+                            status: impl_status(found),
+                            evidence: evidence(found),
+                        })
+                    })
+                    .collect();
+                let statuses: Vec<ImplStatus> = implementations.iter().map(|i| i.status).collect();
+                let deprecated = rule
+                    .status
+                    .as_deref()
+                    .is_some_and(|s| s.eq_ignore_ascii_case("deprecated"));
+                let status = compliance_status(deprecated, &statuses);
+                summary.total += 1;
+                match status {
+                    ComplianceStatus::Compliant => summary.compliant += 1,
+                    ComplianceStatus::PartiallyCompliant => summary.partially_compliant += 1,
+                    ComplianceStatus::NonCompliant => summary.non_compliant += 1,
+                    ComplianceStatus::NotApplicable => summary.not_applicable += 1,
+                }
+                requirements.push(Requirement {
+                    id: rule.id.base.clone(),
+                    version: rule.id.version,
+                    spec: spec.name.clone(),
+                    level: rule.level.clone(),
+                    text: rule.raw.clone(),
+                    source_file: rule.source_file.clone(),
+                    source_line: rule.source_line,
+                    status,
+                    implementations,
+                    approvals: rule
+                        .approvals
+                        .iter()
+                        .map(|a| Approval {
+                            by: a.by.clone(),
+                            date: a.date.clone(),
+                        })
+                        .collect(),
+                    owners: rule.owners.clone(),
+                });
+            }
+        }
+
+        Self {
+            schema_version: COMPLIANCE_SCHEMA_VERSION,
+            format: "tracey-compliance".to_string(),
+            generated_at: rfc3339(generated_at),
+            tool: Tool {
+                name: "tracey".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            commit,
+            summary,
+            requirements,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        facet_json::to_string_pretty(self).expect("JSON serialization failed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_follows_the_weakest_impl() {
+        use ImplStatus::*;
+        assert_eq!(
+            compliance_status(false, &[Verified, Verified]),
+            ComplianceStatus::Compliant
+        );
+        assert_eq!(
+            compliance_status(false, &[Verified, Implemented]),
+            ComplianceStatus::PartiallyCompliant
+        );
+        assert_eq!(
+            compliance_status(false, &[Missing, Implemented]),
+            ComplianceStatus::PartiallyCompliant
+        );
+        assert_eq!(
+            compliance_status(false, &[Stale, Missing]),
+            ComplianceStatus::NonCompliant
+        );
+        assert_eq!(
+            compliance_status(false, &[]),
+            ComplianceStatus::NonCompliant
+        );
+        assert_eq!(
+            compliance_status(true, &[Missing]),
+            ComplianceStatus::NotApplicable
+        );
+    }
+
+    #[test]
+    fn timestamps_are_rfc3339() {
+        assert_eq!(rfc3339(1_791_979_205), "2026-10-14T12:00:05Z");
+    }
+
+    #[test]
+    fn schema_matches_the_layout() {
+        let schema: serde_json::Value = serde_json::from_str(COMPLIANCE_SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schemaVersion"]["const"],
+            COMPLIANCE_SCHEMA_VERSION
+        );
+        for field in [
+            "schemaVersion",
+            "generatedAt",
+            "partiallyCompliant",
+            "notApplicable",
+            "sourceFile",
+            "implementations",
+            "evidence",
+            "approvals",
+            "\"partially-compliant\"",
+            "\"verified\"",
+        ] {
+            assert!(COMPLIANCE_SCHEMA.contains(field), "schema lacks {field}");
+        }
+    }
+}
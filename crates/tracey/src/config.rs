@@ -7,3 +7,317 @@
 
 // Re-export from tracey-config crate so build.rs can access the types
 pub use tracey_config::*;
+
+use eyre::Result;
+use tracey_core::code_units::Granularity;
+
+/// Parse config text, expanding `${VAR}` references first.
+pub fn parse_config(content: &str) -> Result<Config> {
+    let content = interpolate_env(content)?;
+    facet_styx::from_str(&content).map_err(|e| eyre::eyre!("{}", e))
+}
+
+/// Expand `${VAR}` references in config text from the process environment.
+///
+/// r[impl config.env-interpolation]
+///
+/// `$${` produces a literal `${`. Every unset variable is reported in a
+/// single error, together with the line it appears on. `//` comments are
+/// copied through untouched.
+pub fn interpolate_env(content: &str) -> Result<String> {
+    interpolate_with(content, |name| std::env::var(name).ok())
+}
+
+fn interpolate_with(content: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::with_capacity(content.len());
+    let mut unset = Vec::new();
+
+    for (line_idx, line) in content.split_inclusive('\n').enumerate() {
+        let line_no = line_idx + 1;
+        let (code, comment) = split_comment(line);
+        let mut rest = code;
+        while let Some(start) = rest.find('$') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            if let Some(escaped) = after.strip_prefix("${") {
+                out.push_str("${");
+                rest = escaped;
+                continue;
+            }
+            let Some(body) = after.strip_prefix('{') else {
+                out.push('$');
+                rest = after;
+                continue;
+            };
+            let Some(end) = body.find('}') else {
+                eyre::bail!("Unterminated `${{` on line {}", line_no);
+            };
+            let name = &body[..end];
+            let valid = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                eyre::bail!(
+                    "Invalid environment variable name `${{{}}}` on line {}",
+                    name,
+                    line_no
+                );
+            }
+            match lookup(name) {
+                Some(value) => out.push_str(&value),
+                None => unset.push(format!("  ${{{}}} (line {})", name, line_no)),
+            }
+            rest = &body[end + 1..];
+        }
+        out.push_str(rest);
+        out.push_str(comment);
+    }
+
+    if !unset.is_empty() {
+        eyre::bail!(
+            "Config references unset environment variable(s):\n{}",
+            unset.join("\n")
+        );
+    }
+    Ok(out)
+}
+
+/// Whether config text contains `${` outside comments, either as a variable
+/// reference or as an escaped `$${`. Such a file cannot be rewritten from its
+/// parsed form without baking in the expanded values.
+pub fn uses_interpolation(content: &str) -> bool {
+    content
+        .split_inclusive('\n')
+        .any(|line| split_comment(line).0.contains("${"))
+}
+
+/// Split a line at the start of its `//` comment, if any. A comment starts
+/// at the beginning of the line or after whitespace, outside quotes, so the
+/// `//` of a bare URL is not one.
+fn split_comment(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut in_quotes = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_quotes => i += 1,
+            b'"' => in_quotes = !in_quotes,
+            b'/' if !in_quotes
+                && bytes.get(i + 1) == Some(&b'/')
+                && (i == 0 || bytes[i - 1].is_ascii_whitespace()) =>
+            {
+                return line.split_at(i);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    (line, "")
+}
+
+/// The config's `version_scheme`, defaulting to `integer`.
+///
+/// r[impl config.version-scheme]
+pub fn version_scheme(config: &Config) -> Result<tracey_core::VersionScheme> {
+    match config.version_scheme.as_deref() {
+        None => Ok(tracey_core::VersionScheme::default()),
+        Some(name) => tracey_core::VersionScheme::from_name(name).ok_or_else(|| {
+            eyre::eyre!(
+                "Unknown version_scheme '{}', expected integer, date or free-form",
+                name
+            )
+        }),
+    }
+}
+
+/// Whether file walks follow symbolic links, defaulting to `true`.
+///
+/// r[impl config.follow-links]
+pub fn follow_links(config: &Config) -> bool {
+    config.follow_links.unwrap_or(true)
+}
+
+/// The impl's `code_units` granularity, defaulting to `items`.
+///
+/// r[impl config.impl.code-units]
+pub fn code_unit_granularity(impl_config: &Impl) -> Result<Granularity> {
+    match impl_config.code_units.as_deref() {
+        None => Ok(Granularity::default()),
+        Some(name) => Granularity::from_name(name).ok_or_else(|| {
+            eyre::eyre!(
+                "Unknown code_units '{}' for impl '{}', expected items, functions, impls, \
+                 modules or files",
+                name,
+                impl_config.name
+            )
+        }),
+    }
+}
+
+/// The exclude patterns that apply to an impl: its own `exclude` list plus
+/// [`DEFAULT_EXCLUDES`], unless it opted out with `no_default_excludes`.
+///
+/// r[impl config.impl.default-excludes]
+pub fn effective_excludes(impl_config: &Impl) -> Vec<String> {
+    let mut exclude = impl_config.exclude.clone();
+    if !impl_config.no_default_excludes {
+        exclude.extend(DEFAULT_EXCLUDES.iter().map(|p| p.to_string()));
+    }
+    exclude
+}
+
+/// The test file patterns of an impl: its `test_include` list plus
+/// [`TEST_CONVENTION_PATTERNS`] when it sets `test_conventions`.
+///
+/// r[impl config.impl.test-conventions]
+pub fn effective_test_include(impl_config: &Impl) -> Vec<String> {
+    let mut test_include = impl_config.test_include.clone();
+    if impl_config.test_conventions {
+        test_include.extend(TEST_CONVENTION_PATTERNS.iter().map(|p| p.to_string()));
+    }
+    test_include
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "SPEC_DIR" => Some("docs/spec".to_string()),
+            "TOKEN" => Some("abc".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_variables() {
+        let expanded = interpolate_with(
+            "include (${SPEC_DIR}/**/*.md)\nrules_url https://x.test/?t=${TOKEN}\n",
+            lookup,
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            "include (docs/spec/**/*.md)\nrules_url https://x.test/?t=abc\n"
+        );
+    }
+
+    #[test]
+    fn escapes_and_bare_dollars_pass_through() {
+        let expanded = interpolate_with("a $${SPEC_DIR} b $5", lookup).unwrap();
+        assert_eq!(expanded, "a ${SPEC_DIR} b $5");
+    }
+
+    #[test]
+    fn reports_every_unset_variable() {
+        let err = interpolate_with("a ${MISSING}\nb ${ALSO_MISSING}\n", lookup)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("${MISSING} (line 1)"), "{err}");
+        assert!(err.contains("${ALSO_MISSING} (line 2)"), "{err}");
+    }
+
+    #[test]
+    fn leaves_comments_alone() {
+        let expanded = interpolate_with(
+            "// set ${MISSING} first\nurl https://x.test/${TOKEN} // ${OOPS\n",
+            lookup,
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            "// set ${MISSING} first\nurl https://x.test/abc // ${OOPS\n"
+        );
+        assert!(!uses_interpolation("// token ${TOKEN}\nname x\n"));
+        assert!(uses_interpolation("token ${TOKEN} // secret\n"));
+        assert!(uses_interpolation("note \"a $${b}\"\n"));
+    }
+
+    #[test]
+    fn rejects_malformed_references() {
+        assert!(interpolate_with("a ${OOPS", lookup).is_err());
+        assert!(interpolate_with("a ${1BAD}", lookup).is_err());
+    }
+
+    // r[verify config.impl.default-excludes]
+    // r[verify config.impl.default-excludes.opt-out]
+    #[test]
+    fn version_scheme_defaults_to_integer() {
+        let mut config = Config::default();
+        assert_eq!(
+            version_scheme(&config).unwrap(),
+            tracey_core::VersionScheme::Integer
+        );
+        config.version_scheme = Some("date".to_string());
+        assert_eq!(
+            version_scheme(&config).unwrap(),
+            tracey_core::VersionScheme::Date
+        );
+        config.version_scheme = Some("semver".to_string());
+        let error = version_scheme(&config).unwrap_err().to_string();
+        assert!(error.contains("semver"), "{error}");
+    }
+
+    #[test]
+    fn default_excludes_apply_unless_disabled() {
+        let mut impl_config = Impl {
+            name: "main".to_string(),
+            include: vec![],
+            exclude: vec!["gen/**".to_string()],
+            no_default_excludes: false,
+            test_include: vec![],
+            test_conventions: false,
+            code_units: None,
+            skip_units: None,
+            variants: vec![],
+            policy: None,
+        };
+        assert_eq!(
+            effective_excludes(&impl_config),
+            [
+                "gen/**",
+                "**/target/**",
+                "**/node_modules/**",
+                "**/vendor/**"
+            ]
+        );
+
+        impl_config.no_default_excludes = true;
+        assert_eq!(effective_excludes(&impl_config), ["gen/**"]);
+    }
+
+    // r[verify config.impl.code-units]
+    #[test]
+    fn code_unit_granularity_is_parsed_per_impl() {
+        let mut impl_config = Impl {
+            name: "cli".to_string(),
+            include: vec![],
+            exclude: vec![],
+            no_default_excludes: false,
+            test_include: vec![],
+            test_conventions: false,
+            code_units: None,
+            skip_units: None,
+            variants: vec![],
+            policy: None,
+        };
+        assert_eq!(
+            code_unit_granularity(&impl_config).unwrap(),
+            Granularity::Items
+        );
+        impl_config.code_units = Some("files".to_string());
+        assert_eq!(
+            code_unit_granularity(&impl_config).unwrap(),
+            Granularity::Files
+        );
+        impl_config.code_units = Some("classes".to_string());
+        let error = code_unit_granularity(&impl_config).unwrap_err().to_string();
+        assert!(
+            error.contains("classes") && error.contains("cli"),
+            "{error}"
+        );
+    }
+}
@@ -7,9 +7,14 @@ use std::fs::OpenOptions;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tracing::{debug, info, warn};
 
-use super::{is_pid_alive, local_endpoint, pid_file_path, read_pid_file_at};
+use crate::config::QueryConfig;
+
+use super::{
+    connect_endpoint, is_pid_alive, kill_pid, local_endpoint, pid_file_path, read_pid_file_at,
+};
 
 // Re-export the generated client from tracey-proto
 pub use tracey_proto::TraceyDaemonClient;
@@ -24,9 +29,117 @@ pub type DaemonClient = TraceyDaemonClient<roam_stream::Client<DaemonConnector,
 /// - Start the daemon if it's not running
 /// - Reconnect transparently if the connection drops
 pub fn new_client(project_root: PathBuf) -> DaemonClient {
-    let connector = DaemonConnector::new(project_root);
+    let settings = ClientSettings::for_project(&project_root);
+    new_client_with_settings(project_root, settings)
+}
+
+/// Like [`new_client`], with explicit connect retries.
+pub fn new_client_with_settings(project_root: PathBuf, settings: ClientSettings) -> DaemonClient {
+    new_observed_client(project_root, settings).0
+}
+
+/// Whether a client has connected to the daemon yet; see
+/// [`new_observed_client`].
+pub type Connected = watch::Receiver<bool>;
+
+/// Like [`new_client_with_settings`], also returning a [`Connected`] that
+/// turns true once the client's first connect succeeds, so callers can tell
+/// time spent starting the daemon from time spent waiting for an answer.
+pub fn new_observed_client(
+    project_root: PathBuf,
+    settings: ClientSettings,
+) -> (DaemonClient, Connected) {
+    let connector = DaemonConnector::with_settings(project_root, settings);
+    let connected = connector.connected.subscribe();
     let client = connect(connector, HandshakeConfig::default(), NoDispatcher);
-    TraceyDaemonClient::new(client)
+    (TraceyDaemonClient::new(client), connected)
+}
+
+/// Environment variable with the per-call timeout of query clients, in
+/// seconds; `0` turns the timeout off.
+pub const QUERY_TIMEOUT_ENV: &str = "TRACEY_QUERY_TIMEOUT_SECS";
+
+/// Environment variable with how many times a failed connect is retried.
+pub const CONNECT_RETRIES_ENV: &str = "TRACEY_CONNECT_RETRIES";
+
+/// Timeouts and retries of daemon clients.
+///
+/// r[impl daemon.client.timeouts]
+///
+/// A daemon stuck in a rebuild or a deadlock would otherwise leave
+/// `tracey query` and MCP tool calls waiting forever, and a daemon that is
+/// restarting would fail the first call made while it's down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientSettings {
+    /// Longest a query call may take once connected, `None` for no limit
+    pub call_timeout: Option<Duration>,
+    /// How many times a failed connect is retried
+    pub connect_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub retry_backoff: Duration,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            call_timeout: Some(Duration::from_secs(60)),
+            connect_retries: 2,
+            retry_backoff: Duration::from_millis(250),
+        }
+    }
+}
+
+impl ClientSettings {
+    /// The defaults, overridden by the `query` block of the project's config
+    /// and then by [`QUERY_TIMEOUT_ENV`] and [`CONNECT_RETRIES_ENV`].
+    pub fn for_project(project_root: &Path) -> Self {
+        let config = crate::load_config_or_default(&project_root.join(crate::CONFIG_PATH));
+        Self::from_config(config.query.as_ref()).with_vars(|name| std::env::var(name).ok())
+    }
+
+    /// The defaults, overridden by a `query` config block.
+    ///
+    /// r[impl config.query]
+    pub fn from_config(config: Option<&QueryConfig>) -> Self {
+        let mut settings = Self::default();
+        let Some(config) = config else {
+            return settings;
+        };
+        if let Some(secs) = config.timeout_secs {
+            settings.call_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Some(retries) = config.connect_retries {
+            settings.connect_retries = retries;
+        }
+        settings
+    }
+
+    /// The defaults, overridden by the variables `var` looks up.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        Self::default().with_vars(var)
+    }
+
+    /// These settings, overridden by the variables `var` looks up. Values
+    /// that aren't whole numbers are ignored with a warning.
+    pub fn with_vars(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        let number = |name: &str| -> Option<u64> {
+            let value = var(name)?;
+            match value.trim().parse() {
+                Ok(n) => Some(n),
+                Err(_) => {
+                    warn!("Ignoring {}={:?}, expected a whole number", name, value);
+                    None
+                }
+            }
+        };
+        if let Some(secs) = number(QUERY_TIMEOUT_ENV) {
+            self.call_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+        }
+        if let Some(retries) = number(CONNECT_RETRIES_ENV) {
+            self.connect_retries = u32::try_from(retries).unwrap_or(u32::MAX);
+        }
+        self
+    }
 }
 
 /// Connector that establishes connections to the tracey daemon.
@@ -37,6 +150,9 @@ pub fn new_client(project_root: PathBuf) -> DaemonClient {
 /// and wait for it to be ready before connecting.
 pub struct DaemonConnector {
     project_root: PathBuf,
+    settings: ClientSettings,
+    /// Set once a connect succeeds
+    connected: watch::Sender<bool>,
 }
 
 struct StartupLock {
@@ -70,7 +186,7 @@ impl DaemonConnector {
                 return Ok(None);
             }
 
-            match roam_local::connect(endpoint).await {
+            match connect_endpoint(&self.project_root).await {
                 Ok(stream) => return Ok(Some(stream)),
                 Err(e) => {
                     last_error = Some(e.to_string());
@@ -92,7 +208,17 @@ impl DaemonConnector {
 
     /// Create a new connector for the given project root.
     pub fn new(project_root: PathBuf) -> Self {
-        Self { project_root }
+        let settings = ClientSettings::for_project(&project_root);
+        Self::with_settings(project_root, settings)
+    }
+
+    /// Create a connector that retries failed connects as `settings` say.
+    pub fn with_settings(project_root: PathBuf, settings: ClientSettings) -> Self {
+        Self {
+            project_root,
+            settings,
+            connected: watch::Sender::new(false),
+        }
     }
 
     /// Spawn the daemon process in the background.
@@ -186,7 +312,6 @@ impl DaemonConnector {
 
     /// Wait for the daemon endpoint to appear and connect.
     async fn wait_and_connect(&self) -> io::Result<roam_local::LocalStream> {
-        let endpoint = local_endpoint(&self.project_root);
         let start = Instant::now();
         let timeout = Duration::from_secs(5);
         let mut last_print_secs = 0u64;
@@ -208,7 +333,7 @@ impl DaemonConnector {
                 ));
             }
 
-            match roam_local::connect(&endpoint).await {
+            match connect_endpoint(&self.project_root).await {
                 Ok(stream) => return Ok(stream),
                 Err(e) => {
                     last_connect_error = Some(e.to_string());
@@ -239,24 +364,9 @@ fn pid_file_age(project_root: &Path) -> Option<Duration> {
     modified.elapsed().ok()
 }
 
-/// Send SIGTERM to a process.
-#[cfg(unix)]
-fn kill_pid(pid: u32) {
-    unsafe extern "C" {
-        fn kill(pid: i32, sig: i32) -> i32;
-    }
-    unsafe {
-        kill(pid as i32, 15); // SIGTERM
-    }
-}
-
-#[cfg(not(unix))]
-fn kill_pid(_pid: u32) {}
-
-impl Connector for DaemonConnector {
-    type Transport = roam_local::LocalStream;
-
-    async fn connect(&self) -> io::Result<Self::Transport> {
+impl DaemonConnector {
+    /// Connect to the daemon, starting it if needed, without retrying.
+    async fn connect_once(&self) -> io::Result<roam_local::LocalStream> {
         let endpoint = local_endpoint(&self.project_root);
         debug!(
             "DaemonConnector::connect project_root={} endpoint={:?}",
@@ -275,7 +385,7 @@ impl Connector for DaemonConnector {
 
                 if alive && version_ok {
                     // Happy path: daemon should be running.
-                    match roam_local::connect(&endpoint).await {
+                    match connect_endpoint(&self.project_root).await {
                         Ok(stream) => return Ok(stream),
                         Err(e) => {
                             let age = pid_file_age(&self.project_root);
@@ -303,6 +413,13 @@ impl Connector for DaemonConnector {
                         }
                     }
                     // Socket connect failed despite live PID — stale socket.
+                    // A named pipe goes away only with the process holding
+                    // it, so a pipe that's still there belongs to a daemon
+                    // that stopped answering.
+                    #[cfg(windows)]
+                    if roam_local::endpoint_exists(&endpoint) {
+                        kill_pid(pid);
+                    }
                     let _ = roam_local::remove_endpoint(&endpoint);
                     let _ = std::fs::remove_file(pid_file_path(&self.project_root));
                 } else {
@@ -341,7 +458,7 @@ impl Connector for DaemonConnector {
         if let Some((pid, version)) = read_pid_file(&self.project_root)
             && is_pid_alive(pid)
             && version == tracey_proto::PROTOCOL_VERSION
-            && let Ok(stream) = roam_local::connect(&endpoint).await
+            && let Ok(stream) = connect_endpoint(&self.project_root).await
         {
             debug!(
                 "Daemon became available while waiting for startup lock (pid={})",
@@ -358,3 +475,83 @@ impl Connector for DaemonConnector {
         self.wait_and_connect().await
     }
 }
+
+impl Connector for DaemonConnector {
+    type Transport = roam_local::LocalStream;
+
+    // r[impl daemon.client.timeouts]
+    async fn connect(&self) -> io::Result<Self::Transport> {
+        let mut backoff = self.settings.retry_backoff;
+        let mut retries_left = self.settings.connect_retries;
+        loop {
+            match self.connect_once().await {
+                Ok(stream) => {
+                    self.connected.send_replace(true);
+                    return Ok(stream);
+                }
+                Err(e) if retries_left > 0 => {
+                    debug!(
+                        "Connecting to daemon failed ({}), retrying in {:?}",
+                        e, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    retries_left -= 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.client.timeouts]
+    #[test]
+    fn settings_come_from_the_environment() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            ClientSettings::from_vars(vars(&[])),
+            ClientSettings::default()
+        );
+
+        let settings = ClientSettings::from_vars(vars(&[
+            (QUERY_TIMEOUT_ENV, "5"),
+            (CONNECT_RETRIES_ENV, "0"),
+        ]));
+        assert_eq!(settings.call_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(settings.connect_retries, 0);
+
+        let settings = ClientSettings::from_vars(vars(&[
+            (QUERY_TIMEOUT_ENV, "0"),
+            (CONNECT_RETRIES_ENV, "lots"),
+        ]));
+        assert_eq!(settings.call_timeout, None);
+        assert_eq!(settings.connect_retries, 2);
+    }
+
+    // r[verify config.query]
+    #[test]
+    fn environment_overrides_the_config() {
+        let config = QueryConfig {
+            timeout_secs: Some(300),
+            connect_retries: Some(5),
+        };
+        let settings = ClientSettings::from_config(Some(&config));
+        assert_eq!(settings.call_timeout, Some(Duration::from_secs(300)));
+        assert_eq!(settings.connect_retries, 5);
+
+        let settings = settings.with_vars(|name| (name == QUERY_TIMEOUT_ENV).then(|| "0".into()));
+        assert_eq!(settings.call_timeout, None);
+        assert_eq!(settings.connect_retries, 5);
+    }
+}
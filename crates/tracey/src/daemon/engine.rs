@@ -14,7 +14,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, Notify, RwLock, mpsc, watch};
+use tokio::sync::{Mutex, Notify, RwLock, mpsc, oneshot, watch};
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
@@ -61,11 +61,18 @@ pub struct Engine {
     rebuild_notify: Arc<Notify>,
 }
 
+/// r[impl daemon.state.rebuild-queue]
+///
+/// At most one pass runs and at most one is queued: requests that arrive
+/// while a pass runs merge into the queued one.
 #[derive(Default)]
 struct RebuildCoalesceState {
     in_progress: bool,
     pending_full_rebuild: bool,
     pending_changed_files: BTreeSet<PathBuf>,
+    /// Cancels the running pass, if it's a full rebuild that newer changes
+    /// may supersede
+    cancel_in_progress: Option<oneshot::Sender<()>>,
     generation: u64,
     next_ticket: u64,
     pending_ticket: u64,
@@ -93,7 +100,7 @@ impl Engine {
             (Config::default(), Some(err))
         } else {
             match tokio::fs::read_to_string(&config_path).await {
-                Ok(content) => match facet_styx::from_str(&content) {
+                Ok(content) => match crate::config::parse_config(&content) {
                     Ok(config) => (config, None),
                     Err(e) => {
                         // Config has errors - use empty config and record error
@@ -270,6 +277,32 @@ impl Engine {
         self.rebuild_with_changes(&[]).await
     }
 
+    /// Force remote (`rules_url` and git `source`) specs to be re-fetched, then rebuild.
+    ///
+    /// r[impl daemon.cli.reload.refetch]
+    pub async fn refetch_and_rebuild(&self) -> Result<(u64, Duration)> {
+        self.build_cache.lock().await.request_refetch();
+        self.rebuild().await
+    }
+
+    /// Check whether any remote spec's cached copy has outlived its refresh interval.
+    pub async fn remote_specs_due(&self) -> bool {
+        let config = self.config.read().await;
+        config.specs.iter().any(|spec| {
+            let url_due = spec.rules_url.as_ref().is_some_and(|url| {
+                let refresh =
+                    crate::remote::refresh_interval_for(spec.rules_url_refresh.as_deref())
+                        .unwrap_or(crate::remote::DEFAULT_REFRESH_INTERVAL);
+                crate::remote::refresh_due(&self.project_root, url, refresh)
+            });
+            let source_due = spec
+                .source
+                .as_ref()
+                .is_some_and(|source| crate::remote::git_source_due(&self.project_root, source));
+            url_due || source_due
+        })
+    }
+
     pub async fn rebuild_with_changes(&self, changed_files: &[PathBuf]) -> Result<(u64, Duration)> {
         self.run_coalesced_rebuild(changed_files, true).await
     }
@@ -300,6 +333,11 @@ impl Engine {
             state.pending_ticket = state.pending_ticket.max(request_ticket);
 
             if state.in_progress {
+                // The running full rebuild would be stale by the time it
+                // finishes; the queued pass redoes it with these changes.
+                if let Some(cancel) = state.cancel_in_progress.take() {
+                    let _ = cancel.send(());
+                }
                 if wait_for_completion {
                     Some(request_ticket)
                 } else {
@@ -329,29 +367,52 @@ impl Engine {
             }
         }
 
+        // Whether the previous pass was a cancelled full rebuild. Its restart
+        // runs to completion, so a steady stream of saves can't starve it.
+        let mut restarted = false;
         loop {
-            let (changed_batch, batch_ticket) = {
+            let (changed_batch, batch_ticket, cancel) = {
                 let mut state = self.rebuild_state.lock().await;
                 let pending_full = state.pending_full_rebuild;
                 state.pending_full_rebuild = false;
                 let ticket = state.pending_ticket;
 
+                let cancel = (pending_full && !restarted).then(|| {
+                    let (tx, rx) = oneshot::channel();
+                    state.cancel_in_progress = Some(tx);
+                    rx
+                });
                 if pending_full {
                     state.pending_changed_files.clear();
-                    (Vec::new(), ticket)
+                    (Vec::new(), ticket, cancel)
                 } else {
                     (
                         std::mem::take(&mut state.pending_changed_files)
                             .into_iter()
                             .collect::<Vec<_>>(),
                         ticket,
+                        cancel,
                     )
                 }
             };
 
-            let result = self.rebuild_once(&changed_batch).await;
+            let result = match self.rebuild_once(&changed_batch, cancel).await {
+                Ok(Some(done)) => Ok(done),
+                Ok(None) => {
+                    // Superseded: queue the full rebuild again. Newer
+                    // changes queued meanwhile are covered by it.
+                    let mut state = self.rebuild_state.lock().await;
+                    state.pending_full_rebuild = true;
+                    state.pending_changed_files.clear();
+                    restarted = true;
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+            restarted = false;
             let should_continue = {
                 let mut state = self.rebuild_state.lock().await;
+                state.cancel_in_progress = None;
                 state.last_result = Some(match &result {
                     Ok(v) => Ok(*v),
                     Err(e) => Err(e.to_string()),
@@ -374,12 +435,18 @@ impl Engine {
         }
     }
 
-    async fn rebuild_once(&self, changed_files: &[PathBuf]) -> Result<(u64, Duration)> {
+    /// Run one rebuild pass. Returns `None` if `cancel` fired before the new
+    /// data was built, leaving the current data in place.
+    async fn rebuild_once(
+        &self,
+        changed_files: &[PathBuf],
+        cancel: Option<oneshot::Receiver<()>>,
+    ) -> Result<Option<(u64, Duration)>> {
         let start = Instant::now();
 
         // Reload config - record errors but continue with current config
         let (config, new_config_error) = match tokio::fs::read_to_string(&self.config_path).await {
-            Ok(content) => match facet_styx::from_str(&content) {
+            Ok(content) => match crate::config::parse_config(&content) {
                 Ok(config) => (Some(config), None),
                 Err(e) => {
                     let error_msg = Self::format_config_error(&self.config_path, e);
@@ -420,7 +487,7 @@ impl Engine {
 
         // Build new data (this is the expensive part). Semantic config errors
         // should not fail the daemon; keep the previous snapshot and record error.
-        let build_result = build_dashboard_data_with_overlay_and_cache(
+        let build = build_dashboard_data_with_overlay_and_cache(
             &self.project_root,
             &config,
             new_version,
@@ -428,8 +495,20 @@ impl Engine {
             &overlay,
             &mut build_cache,
             changed_files,
-        )
-        .await;
+        );
+        let build_result = match cancel {
+            Some(cancel) => tokio::select! {
+                result = build => result,
+                Ok(()) = cancel => {
+                    info!(
+                        "Full rebuild superseded by newer changes after {:?}, restarting",
+                        start.elapsed()
+                    );
+                    return Ok(None);
+                }
+            },
+            None => build.await,
+        };
         let new_data = match build_result {
             Ok(data) => Arc::new(data),
             Err(e) => {
@@ -440,7 +519,7 @@ impl Engine {
                 );
                 let mut err = self.config_error.write().await;
                 *err = Some(semantic_error);
-                return Ok((self.version(), start.elapsed()));
+                return Ok(Some((self.version(), start.elapsed())));
             }
         };
 
@@ -472,6 +551,8 @@ impl Engine {
             let snapshot = self.data().await;
             self.spawn_search_reindex(snapshot);
         }
+        #[cfg(feature = "sqlite")]
+        self.spawn_history_record(Arc::clone(&new_data));
 
         let elapsed = start.elapsed();
         info!(
@@ -479,7 +560,17 @@ impl Engine {
             elapsed, new_version
         );
 
-        Ok((new_version, elapsed))
+        Ok(Some((new_version, elapsed)))
+    }
+
+    /// Rebuild passes running or queued: 0, 1 or 2, since queued requests
+    /// coalesce into a single pass.
+    ///
+    /// r[impl daemon.state.rebuild-queue]
+    pub async fn rebuild_queue_depth(&self) -> usize {
+        let state = self.rebuild_state.lock().await;
+        let queued = state.pending_full_rebuild || !state.pending_changed_files.is_empty();
+        usize::from(state.in_progress) + usize::from(queued)
     }
 
     /// Get the project root path.
@@ -495,7 +586,6 @@ impl Engine {
     }
 
     /// Get the current config.
-    #[allow(dead_code)]
     pub async fn config(&self) -> Config {
         self.config.read().await.clone()
     }
@@ -527,6 +617,29 @@ impl Engine {
         let _ = self.search_reindex_tx.send(snapshot);
     }
 
+    /// Append the new coverage numbers and rules to `.tracey/history.sqlite`,
+    /// if the project has started a history with `tracey snapshot record`.
+    #[cfg(feature = "sqlite")]
+    fn spawn_history_record(&self, snapshot: Arc<DashboardData>) {
+        let project_root = self.project_root.clone();
+        tokio::task::spawn_blocking(move || {
+            let rows = crate::history::snapshot_rows(&snapshot);
+            let rules = crate::history::snapshot_rule_rows(&snapshot);
+            let recorded =
+                crate::history::History::open_existing(&project_root).and_then(|history| {
+                    match history {
+                        Some(mut history) => {
+                            history.record_if_changed(crate::history::now_secs(), &rows, &rules)
+                        }
+                        None => Ok(false),
+                    }
+                });
+            if let Err(e) = recorded {
+                warn!("Failed to record coverage history: {}", e);
+            }
+        });
+    }
+
     /// Check for deprecated config files (YAML, KDL) and return an error message if found.
     fn check_deprecated_configs(project_root: &Path) -> Option<String> {
         let kdl_config = project_root.join(".config/tracey/config.kdl");
@@ -0,0 +1,505 @@
+//! Rotation and filtering of the daemon's and bridges' log files.
+//!
+//! r[impl daemon.logs.rotation]
+//!
+//! A daemon that stays up for weeks would otherwise grow `daemon.log`
+//! forever. Once the file is over [`MAX_LOG_BYTES`] or older than
+//! [`MAX_LOG_AGE`], it is renamed to `daemon.log.1`, older segments move up
+//! by one, and the one past [`KEEP_ROTATED`] is deleted. `tracey logs` reads
+//! the segments oldest first, so rotation doesn't cut its output short.
+//!
+//! r[impl daemon.cli.logs.filter]
+//!
+//! [`LineFilter`] narrows what `tracey logs` shows by level, text and age.
+//! It reads the timestamp and level tracing puts at the start of each
+//! event; lines without them, like the rest of a multi-line message, go
+//! with the event before them.
+//!
+//! r[impl daemon.logs.bridge-cleanup]
+//!
+//! Each bridge process logs to its own `<bridge>-<pid>.log`, so editors
+//! that relaunch bridges leave one file per launch behind. A starting
+//! bridge and `tracey gc` delete the logs of processes that are no longer
+//! running; `--shared-log` makes a bridge write one rotating `<bridge>.log`
+//! instead.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Size at which the current log file is rotated.
+pub const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Age at which the current log file is rotated, whatever its size.
+pub const MAX_LOG_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Rotated segments kept besides the current file.
+pub const KEEP_ROTATED: usize = 5;
+
+/// A log file that rotates itself between writes.
+///
+/// Each tracing event is written in a single `write` call, so rotating
+/// before a write never splits a line across segments.
+pub struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    started: SystemTime,
+    max_bytes: u64,
+    max_age: Duration,
+    keep: usize,
+}
+
+impl RotatingLog {
+    /// Open `path` for appending with the default limits.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::with_limits(path, MAX_LOG_BYTES, MAX_LOG_AGE, KEEP_ROTATED)
+    }
+
+    pub fn with_limits(
+        path: &Path,
+        max_bytes: u64,
+        max_age: Duration,
+        keep: usize,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        // A file left by an earlier daemon keeps aging from when it was
+        // created, where the platform records that.
+        let started = metadata
+            .created()
+            .or_else(|_| metadata.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            len: metadata.len(),
+            started,
+            max_bytes,
+            max_age,
+            keep,
+        })
+    }
+
+    fn needs_rotation(&self, incoming: usize) -> bool {
+        self.len > 0
+            && (self.len + incoming as u64 > self.max_bytes
+                || self.started.elapsed().is_ok_and(|age| age >= self.max_age))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        remove_if_exists(&segment_path(&self.path, self.keep.max(1)))?;
+        for n in (1..self.keep).rev() {
+            rename_if_exists(
+                &segment_path(&self.path, n),
+                &segment_path(&self.path, n + 1),
+            )?;
+        }
+        if self.keep > 0 {
+            std::fs::rename(&self.path, segment_path(&self.path, 1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.len = 0;
+        self.started = SystemTime::now();
+        Ok(())
+    }
+}
+
+impl Write for RotatingLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // If rotating fails, e.g. because another process holds the file
+        // open on Windows, keep logging to the current file.
+        if self.needs_rotation(buf.len()) && self.rotate().is_err() {
+            self.started = SystemTime::now();
+        }
+        let written = self.file.write(buf)?;
+        self.len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Path of the `n`th rotated segment of `path`, 1 being the newest.
+pub fn segment_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{n}"));
+    path.with_file_name(name)
+}
+
+/// The segments of the log at `path` that exist, oldest first, ending with
+/// the current file.
+pub fn segments(path: &Path) -> Vec<PathBuf> {
+    let mut segments: Vec<PathBuf> = (1..)
+        .map(|n| segment_path(path, n))
+        .take_while(|segment| segment.exists())
+        .collect();
+    segments.reverse();
+    if path.exists() {
+        segments.push(path.to_path_buf());
+    }
+    segments
+}
+
+/// The last `count` lines of the log at `path` that `filter` lets through,
+/// across its segments.
+pub fn last_lines(path: &Path, count: usize, filter: &LogFilter) -> io::Result<Vec<String>> {
+    let mut lines: Vec<String> = Vec::new();
+    for segment in segments(path).iter().rev() {
+        if lines.len() >= count {
+            break;
+        }
+        let content = std::fs::read(segment)?;
+        let content = String::from_utf8_lossy(&content);
+        // Segments start at an event, so each can be filtered on its own.
+        let mut line_filter = LineFilter::new(filter.clone());
+        let mut older: Vec<String> = content
+            .lines()
+            .filter(|line| line_filter.matches(line))
+            .map(str::to_string)
+            .collect();
+        older.append(&mut lines);
+        lines = older;
+    }
+    let start = lines.len().saturating_sub(count);
+    Ok(lines.split_off(start))
+}
+
+/// The most recently written log of a bridge (`mcp`, `lsp`), from the
+/// `<bridge>-<pid>.log` and shared `<bridge>.log` files in `state_dir`.
+pub fn latest_bridge_log(state_dir: &Path, bridge: &str) -> Option<PathBuf> {
+    let shared = format!("{bridge}.log");
+    std::fs::read_dir(state_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name == shared || bridge_log_pid(&name).is_some_and(|(b, _)| b == bridge)
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// The bridge and pid of a `<bridge>-<pid>.log` file name.
+pub fn bridge_log_pid(file_name: &str) -> Option<(&str, u32)> {
+    let (bridge, pid) = file_name.strip_suffix(".log")?.rsplit_once('-')?;
+    Some((bridge, pid.parse().ok()?))
+}
+
+/// The per-process bridge logs in `state_dir`, rotated segments included,
+/// whose process is no longer running.
+pub fn dead_bridge_logs(state_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(state_dir) else {
+        return Vec::new();
+    };
+    let mut dead: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // Rotated segments are named `<bridge>-<pid>.log.<n>`.
+            let current = match name.rsplit_once('.') {
+                Some((current, n)) if n.parse::<usize>().is_ok() => current,
+                _ => &*name,
+            };
+            bridge_log_pid(current).is_some_and(|(bridge, pid)| {
+                matches!(bridge, "mcp" | "lsp")
+                    && pid != std::process::id()
+                    && !super::is_pid_alive(pid)
+            })
+        })
+        .map(|entry| entry.path())
+        .collect();
+    dead.sort();
+    dead
+}
+
+/// Delete the [`dead_bridge_logs`] of `state_dir`, returning how many
+/// files were removed.
+pub fn remove_dead_bridge_logs(state_dir: &Path) -> usize {
+    dead_bridge_logs(state_dir)
+        .iter()
+        .filter(|path| std::fs::remove_file(path).is_ok())
+        .count()
+}
+
+/// Severity of a log event, least severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl FromStr for Level {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> eyre::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(Level::Trace),
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => eyre::bail!(
+                "Invalid level '{}', expected trace, debug, info, warn or error",
+                s
+            ),
+        }
+    }
+}
+
+/// What `tracey logs` lets through. The default lets everything through.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Least severe level to show
+    pub level: Option<Level>,
+    /// Text a line must contain
+    pub grep: Option<String>,
+    /// Oldest event to show, in seconds since the Unix epoch
+    pub since_secs: Option<u64>,
+}
+
+/// Applies a [`LogFilter`] to consecutive lines of one log.
+pub struct LineFilter {
+    filter: LogFilter,
+    /// Timestamp and level of the event the last line belonged to
+    level: Option<Level>,
+    secs: Option<u64>,
+}
+
+impl LineFilter {
+    pub fn new(filter: LogFilter) -> Self {
+        Self {
+            filter,
+            level: None,
+            secs: None,
+        }
+    }
+
+    pub fn matches(&mut self, line: &str) -> bool {
+        let (secs, level) = parse_event_start(line);
+        if secs.is_some() || level.is_some() {
+            self.secs = secs;
+            self.level = level;
+        }
+        if let Some(min) = self.filter.level
+            && self.level.is_none_or(|level| level < min)
+        {
+            return false;
+        }
+        if let Some(since) = self.filter.since_secs
+            && self.secs.is_none_or(|secs| secs < since)
+        {
+            return false;
+        }
+        self.filter
+            .grep
+            .as_deref()
+            .is_none_or(|text| line.contains(text))
+    }
+}
+
+/// Timestamp and level at the start of a tracing event line
+/// (`2026-10-14T12:34:56.123456Z  WARN tracey::daemon: ...`), or the
+/// timestamp of a bridge start marker (`[ts=1791000000 pid=42] starting mcp`).
+fn parse_event_start(line: &str) -> (Option<u64>, Option<Level>) {
+    if let Some(rest) = line.strip_prefix("[ts=") {
+        let secs = rest.split(' ').next().and_then(|ts| ts.parse().ok());
+        return (secs, Some(Level::Info));
+    }
+    let mut words = line.split_whitespace();
+    let Some(secs) = words.next().and_then(parse_rfc3339_utc) else {
+        return (None, None);
+    };
+    (Some(secs), words.next().and_then(|w| w.parse().ok()))
+}
+
+/// Seconds since the Unix epoch of a UTC timestamp such as
+/// `2026-10-14T12:34:56.123456Z`.
+fn parse_rfc3339_utc(ts: &str) -> Option<u64> {
+    let ts = ts.strip_suffix('Z')?;
+    let (date, time) = ts.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let time = time.split('.').next()?;
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days-from-civil conversion (proleptic Gregorian calendar)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.logs.rotation]
+    #[test]
+    fn rotates_by_size_and_keeps_the_newest_segments() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("daemon.log");
+        let mut log = RotatingLog::with_limits(&path, 20, MAX_LOG_AGE, 2).unwrap();
+        for i in 0..8 {
+            log.write_all(format!("line {i:02}\n").as_bytes()).unwrap();
+        }
+
+        // Two 8-byte lines fit in 20 bytes, so each segment holds two.
+        assert_eq!(
+            segments(&path),
+            vec![segment_path(&path, 2), segment_path(&path, 1), path.clone()]
+        );
+        assert!(!segment_path(&path, 3).exists());
+        assert_eq!(
+            std::fs::read_to_string(segment_path(&path, 2)).unwrap(),
+            "line 02\nline 03\n"
+        );
+        assert_eq!(
+            last_lines(&path, 3, &LogFilter::default()).unwrap(),
+            vec!["line 05", "line 06", "line 07"]
+        );
+        assert_eq!(
+            last_lines(&path, 50, &LogFilter::default()).unwrap().len(),
+            6
+        );
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("daemon.log");
+        let mut log = RotatingLog::with_limits(&path, MAX_LOG_BYTES, Duration::ZERO, 1).unwrap();
+        log.write_all(b"first\n").unwrap();
+        log.write_all(b"second\n").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(segment_path(&path, 1)).unwrap(),
+            "first\n"
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\n");
+    }
+
+    // r[verify daemon.logs.bridge-cleanup]
+    #[cfg(unix)]
+    #[test]
+    fn finds_logs_of_dead_bridges() {
+        let temp = tempfile::tempdir().unwrap();
+        // Linux caps pids at 2^22, so this one can't be running.
+        let dead = 4_194_305;
+        let live = std::process::id();
+        for name in [
+            format!("mcp-{dead}.log"),
+            format!("mcp-{dead}.log.1"),
+            format!("lsp-{dead}.log"),
+            format!("mcp-{live}.log"),
+            format!("other-{dead}.log"),
+            "mcp.log".to_string(),
+            "daemon.log".to_string(),
+        ] {
+            std::fs::write(temp.path().join(name), "x\n").unwrap();
+        }
+
+        assert_eq!(
+            dead_bridge_logs(temp.path()),
+            vec![
+                temp.path().join(format!("lsp-{dead}.log")),
+                temp.path().join(format!("mcp-{dead}.log")),
+                temp.path().join(format!("mcp-{dead}.log.1")),
+            ]
+        );
+        assert_eq!(remove_dead_bridge_logs(temp.path()), 3);
+        assert!(dead_bridge_logs(temp.path()).is_empty());
+        assert!(temp.path().join(format!("mcp-{live}.log")).exists());
+        assert!(latest_bridge_log(temp.path(), "mcp").is_some());
+    }
+
+    #[test]
+    fn parses_tracing_timestamps() {
+        assert_eq!(parse_rfc3339_utc("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_utc("2026-10-14T12:34:56.123456Z"),
+            Some(1_791_981_296)
+        );
+        assert_eq!(parse_rfc3339_utc("2026-13-01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339_utc("starting"), None);
+        assert_eq!(bridge_log_pid("mcp-4242.log"), Some(("mcp", 4242)));
+        assert_eq!(bridge_log_pid("daemon.log"), None);
+    }
+
+    // r[verify daemon.cli.logs.filter]
+    #[test]
+    fn filters_by_level_text_and_age() {
+        let log = "\
+[ts=1791981000 pid=42] starting mcp root=/repo
+2026-10-14T12:30:00.000000Z  INFO tracey::bridge: connected
+2026-10-14T12:34:56.000000Z  WARN tracey::daemon: rebuild failed:
+  config has errors
+2026-10-14T12:35:00.000000Z ERROR tracey::bridge: connection lost
+2026-10-14T12:35:01.000000Z DEBUG tracey::bridge: retrying
+";
+        let run = |filter: LogFilter| -> Vec<&str> {
+            let mut line_filter = LineFilter::new(filter);
+            log.lines().filter(|l| line_filter.matches(l)).collect()
+        };
+
+        assert_eq!(run(LogFilter::default()).len(), 6);
+        assert_eq!(
+            run(LogFilter {
+                level: Some(Level::Warn),
+                ..Default::default()
+            }),
+            vec![
+                "2026-10-14T12:34:56.000000Z  WARN tracey::daemon: rebuild failed:",
+                "  config has errors",
+                "2026-10-14T12:35:00.000000Z ERROR tracey::bridge: connection lost",
+            ]
+        );
+        assert_eq!(
+            run(LogFilter {
+                grep: Some("bridge".to_string()),
+                since_secs: parse_rfc3339_utc("2026-10-14T12:34:00Z"),
+                ..Default::default()
+            }),
+            vec![
+                "2026-10-14T12:35:00.000000Z ERROR tracey::bridge: connection lost",
+                "2026-10-14T12:35:01.000000Z DEBUG tracey::bridge: retrying",
+            ]
+        );
+    }
+}
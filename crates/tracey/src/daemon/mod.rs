@@ -28,6 +28,7 @@
 
 pub mod client;
 pub mod engine;
+pub mod logs;
 pub mod service;
 pub mod watcher;
 
@@ -43,7 +44,10 @@ use tracing::{debug, error, info, warn};
 use service::TraceyDaemonDispatcher;
 use watcher::{WatcherEvent, WatcherManager, WatcherState};
 
-pub use client::{DaemonClient, DaemonConnector, new_client};
+pub use client::{
+    ClientSettings, Connected, DaemonClient, DaemonConnector, new_client, new_client_with_settings,
+    new_observed_client,
+};
 pub use engine::Engine;
 pub use service::TraceyService;
 pub use watcher::WatcherState as DaemonWatcherState;
@@ -51,6 +55,17 @@ pub use watcher::WatcherState as DaemonWatcherState;
 /// Default idle timeout in seconds (10 minutes)
 const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
 
+/// How often the daemon checks whether remote specs need revalidation.
+const REMOTE_SPEC_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Quiet period after the last git/file event before a git operation is
+/// considered finished.
+const GIT_OPERATION_SETTLE: Duration = Duration::from_millis(1000);
+
+/// Upper bound on how long a held git lock can defer the rebuild, in case a
+/// crashed git process left a stale lock file behind.
+const GIT_OPERATION_MAX_WAIT: Duration = Duration::from_secs(30);
+
 /// Socket file name within the state directory (Unix only)
 #[cfg(unix)]
 const SOCKET_FILENAME: &str = "daemon.sock";
@@ -148,9 +163,112 @@ pub fn is_pid_alive(pid: u32) -> bool {
 }
 
 /// Check whether a process with the given PID is alive.
-#[cfg(not(unix))]
+///
+/// r[impl daemon.lifecycle.pid-liveness]
+#[cfg(windows)]
+pub fn is_pid_alive(pid: u32) -> bool {
+    use win32::*;
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            // A process we aren't allowed to query still exists.
+            return GetLastError() == ERROR_ACCESS_DENIED;
+        }
+        let mut code = 0;
+        // A process that exited with code 259 looks alive, but tracey never
+        // exits with it.
+        let alive = GetExitCodeProcess(process, &mut code) != 0 && code == STILL_ACTIVE;
+        CloseHandle(process);
+        alive
+    }
+}
+
+/// Check whether a process with the given PID is alive.
+#[cfg(not(any(unix, windows)))]
 pub fn is_pid_alive(_pid: u32) -> bool {
-    true // best-effort on non-Unix; rely on socket connect to detect dead daemon
+    true // best-effort elsewhere; rely on connect to detect a dead daemon
+}
+
+/// Ask a process to stop: SIGTERM on Unix, `TerminateProcess` on Windows.
+#[cfg(unix)]
+pub fn kill_pid(pid: u32) {
+    unsafe extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    unsafe {
+        kill(pid as i32, 15); // SIGTERM
+    }
+}
+
+/// Ask a process to stop: SIGTERM on Unix, `TerminateProcess` on Windows.
+///
+/// Windows has no signal the daemon could clean up on, so its PID file is
+/// left for the caller to remove.
+#[cfg(windows)]
+pub fn kill_pid(pid: u32) {
+    use win32::*;
+    unsafe {
+        let process = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if !process.is_null() {
+            TerminateProcess(process, 1);
+            CloseHandle(process);
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn kill_pid(_pid: u32) {}
+
+/// The few kernel32 calls process liveness and termination need.
+#[cfg(windows)]
+mod win32 {
+    pub type Handle = *mut std::ffi::c_void;
+
+    pub const PROCESS_TERMINATE: u32 = 0x0001;
+    pub const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    pub const STILL_ACTIVE: u32 = 259;
+    pub const ERROR_ACCESS_DENIED: u32 = 5;
+
+    unsafe extern "system" {
+        pub fn OpenProcess(access: u32, inherit_handle: i32, pid: u32) -> Handle;
+        pub fn GetExitCodeProcess(process: Handle, exit_code: *mut u32) -> i32;
+        pub fn TerminateProcess(process: Handle, exit_code: u32) -> i32;
+        pub fn CloseHandle(handle: Handle) -> i32;
+        pub fn GetLastError() -> u32;
+    }
+}
+
+/// How long a connect retries a named pipe whose instances are all busy.
+#[cfg(windows)]
+const PIPE_BUSY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Connect to the daemon's local endpoint without starting one.
+///
+/// r[impl daemon.lifecycle.busy-pipe]
+///
+/// Between accepting a client and creating the next pipe instance, a daemon
+/// on Windows has no instance free and connects fail with `ERROR_PIPE_BUSY`.
+/// Those are retried for a moment instead of taken as a stale endpoint,
+/// which a Unix socket's backlog never needs.
+pub async fn connect_endpoint(project_root: &Path) -> std::io::Result<roam_local::LocalStream> {
+    let endpoint = local_endpoint(project_root);
+    #[cfg(windows)]
+    {
+        const ERROR_PIPE_BUSY: i32 = 231;
+        let deadline = Instant::now() + PIPE_BUSY_TIMEOUT;
+        loop {
+            match roam_local::connect(&endpoint).await {
+                Err(e)
+                    if e.raw_os_error() == Some(ERROR_PIPE_BUSY) && Instant::now() < deadline =>
+                {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+    #[cfg(not(windows))]
+    roam_local::connect(&endpoint).await
 }
 
 /// Read a PID file at the given path and return `(pid, protocol_version)` if it
@@ -223,29 +341,38 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
     // Ensure state directory exists
     ensure_state_dir(&project_root)?;
 
-    // Write PID file; it is removed automatically when this guard drops.
-    let _pid_file = PidFile::create(&project_root)?;
-
     // Get local IPC endpoint
     let endpoint = local_endpoint(&project_root);
 
     // r[impl daemon.lifecycle.stale-socket]
     // Remove stale endpoint if it exists; if it's alive, fail fast instead.
+    // This happens before the PID file is written, so bailing out leaves the
+    // running daemon's PID file alone.
     if roam_local::endpoint_exists(&endpoint) {
-        if roam_local::connect(&endpoint).await.is_ok() {
+        if connect_endpoint(&project_root).await.is_ok() {
             #[cfg(unix)]
             eyre::bail!("Daemon already running at {}", endpoint.display());
             #[cfg(windows)]
-            eyre::bail!("Daemon already running");
+            eyre::bail!("Daemon already running at {}", endpoint);
         } else {
             #[cfg(unix)]
-            info!("Removing stale socket at {}", endpoint.display());
+            {
+                info!("Removing stale socket at {}", endpoint.display());
+                let _ = roam_local::remove_endpoint(&endpoint);
+            }
+            // A named pipe exists only while a process holds it open, so it
+            // can't be removed, only its owner stopped.
             #[cfg(windows)]
-            info!("Removing stale endpoint");
-            let _ = roam_local::remove_endpoint(&endpoint);
+            eyre::bail!(
+                "Daemon at {} is not responding; stop it with `tracey kill`",
+                endpoint
+            );
         }
     }
 
+    // Write PID file; it is removed automatically when this guard drops.
+    let _pid_file = PidFile::create(&project_root)?;
+
     // Create engine
     let engine = Arc::new(
         Engine::new(project_root.clone(), config_path.clone())
@@ -309,101 +436,117 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
     let engine_for_rebuild = Arc::clone(&engine);
     let project_root_for_rebuild = project_root.clone();
     let config_path_for_rebuild = config_path.clone();
+    let watcher_state_for_rebuild = Arc::clone(&watcher_state);
     tokio::spawn(async move {
         // r[impl server.watch.respect-gitignore]
         // Build gitignore matcher for filtering file watcher events
         let mut gitignore = build_gitignore(&project_root_for_rebuild);
+        let mut patterns = WatchPatterns::load(&config_path_for_rebuild, &engine_for_rebuild).await;
+        let git_dir = project_root_for_rebuild.join(".git");
+        let canonical_root = tracey_core::canonical_path(&project_root_for_rebuild);
+
+        // r[impl server.watch.git-operations]
+        // While a git operation is in progress: (started_at, settle_deadline).
+        let mut git_operation: Option<(tokio::time::Instant, tokio::time::Instant)> = None;
+
+        loop {
+            let event = match git_operation {
+                Some((started, deadline)) => {
+                    tokio::select! {
+                        event = watcher_rx.recv() => event,
+                        _ = tokio::time::sleep_until(deadline) => {
+                            // Keep waiting while git still holds its locks, unless
+                            // the lock looks abandoned (e.g. git crashed mid-write).
+                            if watcher::git_lock_held(&git_dir)
+                                && started.elapsed() < GIT_OPERATION_MAX_WAIT
+                            {
+                                let next = tokio::time::Instant::now() + GIT_OPERATION_SETTLE;
+                                git_operation = Some((started, next));
+                                continue;
+                            }
+
+                            git_operation = None;
+                            watcher_state_for_rebuild.set_git_operation_in_progress(false);
+                            info!(
+                                "Git operation settled after {}ms, running full rebuild",
+                                started.elapsed().as_millis()
+                            );
+                            engine_for_rebuild.schedule_rebuild_with_changes(&[]).await;
+                            continue;
+                        }
+                    }
+                }
+                None => watcher_rx.recv().await,
+            };
+            let Some(event) = event else {
+                break;
+            };
 
-        while let Some(event) = watcher_rx.recv().await {
             match event {
+                WatcherEvent::GitOperation => {
+                    let now = tokio::time::Instant::now();
+                    let started = match git_operation {
+                        Some((started, _)) => started,
+                        None => {
+                            info!("Git operation detected, deferring rebuilds until it settles");
+                            watcher_state_for_rebuild.set_git_operation_in_progress(true);
+                            now
+                        }
+                    };
+                    git_operation = Some((started, now + GIT_OPERATION_SETTLE));
+                }
+
                 WatcherEvent::Reconfigure => {
                     info!("Config or gitignore changed, reconfiguring watcher");
 
                     // Rebuild gitignore matcher
                     gitignore = build_gitignore(&project_root_for_rebuild);
-                    debug!("Rebuilt gitignore matcher");
+                    patterns =
+                        WatchPatterns::load(&config_path_for_rebuild, &engine_for_rebuild).await;
+                    debug!("Rebuilt gitignore matcher and watch patterns");
 
                     // Trigger rebuild (watcher reconfiguration happens in the watcher thread)
                     engine_for_rebuild.schedule_rebuild_with_changes(&[]).await;
                 }
 
                 WatcherEvent::FilesChanged(events) => {
+                    // The full rebuild at the end of the git operation covers
+                    // these; each new batch pushes the settle deadline out.
+                    if let Some((started, _)) = git_operation {
+                        debug!(
+                            "Deferring {} file change events during git operation",
+                            events.len()
+                        );
+                        git_operation =
+                            Some((started, tokio::time::Instant::now() + GIT_OPERATION_SETTLE));
+                        continue;
+                    }
+
                     // Extract all paths from the batch of events
                     let changed_files: Vec<PathBuf> = events
                         .iter()
                         .flat_map(|e| e.paths.iter().cloned())
                         .collect();
 
-                    // r[impl server.watch.patterns-from-config]
-                    // Collect all include patterns from config
-                    let mut include_patterns: Vec<String> = Vec::new();
-                    let mut exclude_patterns: Vec<String> = Vec::new();
-
-                    // Get patterns from the raw config file if available
-                    if let Ok(config) = crate::load_config(&config_path_for_rebuild) {
-                        for spec in &config.specs {
-                            for pattern in &spec.include {
-                                include_patterns.push(pattern.clone());
-                            }
-                            for impl_ in &spec.impls {
-                                for pattern in &impl_.include {
-                                    include_patterns.push(pattern.clone());
-                                }
-                                // r[impl server.watch.respect-excludes]
-                                for pattern in &impl_.exclude {
-                                    exclude_patterns.push(pattern.clone());
-                                }
-                            }
-                        }
-                    } else {
-                        // If config not available, get patterns from engine data
-                        let data = engine_for_rebuild.data().await;
-                        for spec in &data.config.specs {
-                            // Add spec include patterns (markdown files)
-                            if let Some(source) = &spec.source {
-                                include_patterns.push(source.clone());
-                            }
-                        }
-                    }
-
                     // Filter changed files
                     let relative_paths: Vec<_> = changed_files
                         .iter()
-                        .filter_map(|p| p.strip_prefix(&project_root_for_rebuild).ok())
+                        .filter_map(|p| {
+                            // r[impl walk.symlinks]
+                            // Watched directories are canonical; map events
+                            // back under the root the scanner walks.
+                            p.strip_prefix(&project_root_for_rebuild)
+                                .or_else(|_| p.strip_prefix(&canonical_root))
+                                .ok()
+                        })
                         .filter(|p| !is_temporary_edit_artifact(p))
                         .filter(|p| {
-                            // Keep paths that are NOT ignored by gitignore
+                            // Keep paths that are NOT ignored by gitignore or
+                            // .tracey-ignore
                             let full_path = project_root_for_rebuild.join(p);
-                            !gitignore
-                                .matched_path_or_any_parents(&full_path, full_path.is_dir())
-                                .is_ignore()
-                        })
-                        .filter(|p| {
-                            // r[impl server.watch.respect-excludes]
-                            // Reject paths that match exclude patterns
-                            for pattern in &exclude_patterns {
-                                if let Ok(glob) = globset::Glob::new(pattern.as_str())
-                                    && glob.compile_matcher().is_match(p)
-                                {
-                                    return false;
-                                }
-                            }
-
-                            // r[impl server.watch.patterns-from-config]
-                            // Accept paths that match include patterns
-                            // If no include patterns, accept all non-excluded files
-                            if include_patterns.is_empty() {
-                                return true;
-                            }
-                            for pattern in &include_patterns {
-                                if let Ok(glob) = globset::Glob::new(pattern.as_str())
-                                    && glob.compile_matcher().is_match(p)
-                                {
-                                    return true;
-                                }
-                            }
-                            false
+                            !gitignore.is_ignored(&full_path, full_path.is_dir())
                         })
+                        .filter(|p| patterns.is_match(p))
                         .collect();
 
                     // Skip rebuild if no relevant files changed
@@ -449,6 +592,20 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
         }
     });
 
+    // r[impl config.spec.rules-url.refresh]
+    // Periodically revalidate remote specs so upstream changes show up
+    // without a local edit. The fetch itself happens during the rebuild.
+    let engine_for_remote = Arc::clone(&engine);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(REMOTE_SPEC_CHECK_INTERVAL).await;
+            if engine_for_remote.remote_specs_due().await {
+                info!("Remote spec refresh interval elapsed, rebuilding");
+                engine_for_remote.schedule_rebuild_with_changes(&[]).await;
+            }
+        }
+    });
+
     // Bind local IPC listener
     // Note: on Windows, accept() takes &mut self (to swap server instances)
     #[cfg(unix)]
@@ -568,10 +725,108 @@ pub async fn run(project_root: PathBuf, config_path: PathBuf) -> Result<()> {
     }
 }
 
-/// Build a gitignore matcher for the project.
+/// Include and exclude patterns of the config, compiled once per
+/// reconfigure rather than for every batch of watcher events.
+///
+/// r[impl server.watch.patterns-from-config]
+/// r[impl server.watch.respect-excludes]
+struct WatchPatterns {
+    include: globset::GlobSet,
+    exclude: globset::GlobSet,
+}
+
+impl WatchPatterns {
+    /// Patterns from the config file, or from the engine's last good config
+    /// if the file doesn't load.
+    async fn load(config_path: &Path, engine: &Engine) -> Self {
+        let mut include_patterns: Vec<String> = Vec::new();
+        let mut exclude_patterns: Vec<String> = Vec::new();
+
+        if let Ok(config) = crate::load_config(config_path) {
+            for spec in &config.specs {
+                include_patterns.extend(spec.include.iter().cloned());
+                for impl_ in &spec.impls {
+                    // Negated patterns only narrow one impl's include list;
+                    // rebuilding for a carved-out file is harmless.
+                    include_patterns.extend(
+                        impl_
+                            .include
+                            .iter()
+                            .filter(|p| !p.starts_with('!'))
+                            .cloned(),
+                    );
+                    exclude_patterns.extend(crate::config::effective_excludes(impl_));
+                }
+            }
+        } else {
+            let data = engine.data().await;
+            for spec in &data.config.specs {
+                // Add spec include patterns (markdown files)
+                if let Some(source) = &spec.source {
+                    include_patterns.push(source.clone());
+                }
+            }
+        }
+
+        Self {
+            include: compile_globs(&include_patterns),
+            exclude: compile_globs(&exclude_patterns),
+        }
+    }
+
+    /// Whether a change to the project-relative `path` is relevant.
+    /// Without include patterns, every non-excluded file is.
+    fn is_match(&self, path: &Path) -> bool {
+        if self.exclude.is_match(path) {
+            return false;
+        }
+        self.include.is_empty() || self.include.is_match(path)
+    }
+}
+
+/// Compile `patterns` into one set, leaving out the invalid ones.
+fn compile_globs(patterns: &[String]) -> globset::GlobSet {
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        match globset::Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => debug!("Ignoring invalid watch pattern {:?}: {}", pattern, e),
+        }
+    }
+    builder.build().unwrap_or_else(|e| {
+        warn!("Failed to compile watch patterns: {}", e);
+        globset::GlobSet::empty()
+    })
+}
+
+/// Ignore rules for watcher events: the project's `.gitignore` plus every
+/// `.tracey-ignore` file, each matching paths relative to its own directory.
+struct WatchIgnore {
+    /// Matchers ordered from the shallowest directory to the deepest.
+    matchers: Vec<ignore::gitignore::Gitignore>,
+}
+
+impl WatchIgnore {
+    /// Whether `path` is ignored. The deepest file with a matching pattern
+    /// decides, so a nested `.tracey-ignore` can re-include with `!`.
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.matchers
+            .iter()
+            .rev()
+            .filter(|m| path.starts_with(m.path()))
+            .map(|m| m.matched_path_or_any_parents(path, is_dir))
+            .find(|m| !m.is_none())
+            .is_some_and(|m| m.is_ignore())
+    }
+}
+
+/// Build the ignore matchers for the project.
 ///
 /// r[impl server.watch.respect-gitignore]
-fn build_gitignore(project_root: &Path) -> ignore::gitignore::Gitignore {
+/// r[impl walk.tracey-ignore]
+fn build_gitignore(project_root: &Path) -> WatchIgnore {
     let mut builder = ignore::gitignore::GitignoreBuilder::new(project_root);
 
     // Add .gitignore from project root if it exists
@@ -583,10 +838,32 @@ fn build_gitignore(project_root: &Path) -> ignore::gitignore::Gitignore {
     // Always ignore .git directory
     let _ = builder.add_line(None, ".git/");
 
-    builder.build().unwrap_or_else(|e| {
+    let mut matchers = vec![builder.build().unwrap_or_else(|e| {
         warn!("Failed to build gitignore matcher: {}", e);
         ignore::gitignore::Gitignore::empty()
-    })
+    })];
+
+    let mut ignore_files: Vec<PathBuf> = ignore::WalkBuilder::new(project_root)
+        .hidden(false)
+        .git_ignore(true)
+        .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_name() == tracey_core::IGNORE_FILENAME)
+        .map(|entry| entry.into_path())
+        .collect();
+    ignore_files.sort_by_key(|path| path.components().count());
+    for path in ignore_files {
+        // Patterns of each file are relative to the directory containing it.
+        let (matcher, err) = ignore::gitignore::Gitignore::new(&path);
+        if let Some(e) = err {
+            warn!("Failed to parse {}: {}", path.display(), e);
+        }
+        matchers.push(matcher);
+    }
+
+    WatchIgnore { matchers }
 }
 
 fn is_temporary_edit_artifact(path: &Path) -> bool {
@@ -611,6 +888,14 @@ fn path_triggers_reconfigure(path: &Path, config_path: &Path, gitignore_path: &P
         return true;
     }
 
+    // r[impl walk.tracey-ignore]
+    if path
+        .file_name()
+        .is_some_and(|name| name == tracey_core::IGNORE_FILENAME)
+    {
+        return true;
+    }
+
     // Some watcher backends report parent directories instead of exact files.
     if config_path.starts_with(path) || gitignore_path.starts_with(path) {
         return true;
@@ -646,6 +931,7 @@ async fn run_smart_watcher(
     // Shared state for the event handler
     let config_path_owned = config_path.to_path_buf();
     let gitignore_path = project_root.join(".gitignore");
+    let git_dir = project_root.join(".git");
     let tx_for_handler = tx.clone();
     let state_for_handler = Arc::clone(&state);
 
@@ -685,6 +971,12 @@ async fn run_smart_watcher(
             let watcher_event = if needs_reconfigure {
                 debug!("Config or gitignore changed, sending Reconfigure event");
                 WatcherEvent::Reconfigure
+            } else if paths
+                .iter()
+                .any(|p| watcher::is_git_operation_path(p, &git_dir))
+            {
+                debug!("Git metadata changed, sending GitOperation event");
+                WatcherEvent::GitOperation
             } else {
                 debug!(
                     event_count = events.len(),
@@ -744,7 +1036,7 @@ pub async fn is_running(project_root: &Path) -> bool {
     }
 
     // Try to connect
-    match roam_local::connect(&endpoint).await {
+    match connect_endpoint(project_root).await {
         Ok(_) => true,
         Err(_) => {
             // Endpoint exists but can't connect - stale
@@ -758,7 +1050,7 @@ pub async fn is_running(project_root: &Path) -> bool {
 #[cfg(unix)]
 pub async fn connect(project_root: &Path) -> Result<roam_local::LocalStream> {
     let endpoint = local_endpoint(project_root);
-    roam_local::connect(&endpoint)
+    connect_endpoint(project_root)
         .await
         .wrap_err_with(|| format!("Failed to connect to daemon at {}", endpoint.display()))
 }
@@ -768,16 +1060,38 @@ pub async fn connect(project_root: &Path) -> Result<roam_local::LocalStream> {
 #[cfg(windows)]
 pub async fn connect(project_root: &Path) -> Result<roam_local::LocalStream> {
     let endpoint = local_endpoint(project_root);
-    roam_local::connect(&endpoint)
+    connect_endpoint(project_root)
         .await
         .wrap_err_with(|| format!("Failed to connect to daemon at {}", endpoint))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::path_triggers_reconfigure;
+    use super::{build_gitignore, path_triggers_reconfigure};
     use std::path::Path;
 
+    // r[verify walk.tracey-ignore]
+    #[test]
+    fn watcher_respects_nested_tracey_ignore_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/generated")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join(".tracey-ignore"), "snapshots/\n").unwrap();
+        std::fs::write(
+            root.join("src/generated/.tracey-ignore"),
+            "*.rs\n!keep.rs\n",
+        )
+        .unwrap();
+
+        let ignore = build_gitignore(root);
+        assert!(ignore.is_ignored(&root.join("debug.log"), false));
+        assert!(ignore.is_ignored(&root.join("snapshots/a.rs"), false));
+        assert!(ignore.is_ignored(&root.join("src/generated/schema.rs"), false));
+        assert!(!ignore.is_ignored(&root.join("src/generated/keep.rs"), false));
+        assert!(!ignore.is_ignored(&root.join("src/schema.rs"), false));
+    }
+
     #[test]
     fn reconfigure_triggers_for_exact_paths() {
         let config = Path::new("/repo/.config/tracey/config.styx");
@@ -813,6 +1127,22 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn reconfigure_triggers_for_tracey_ignore_files() {
+        let config = Path::new("/repo/.config/tracey/config.styx");
+        let gitignore = Path::new("/repo/.gitignore");
+        assert!(path_triggers_reconfigure(
+            Path::new("/repo/.tracey-ignore"),
+            config,
+            gitignore
+        ));
+        assert!(path_triggers_reconfigure(
+            Path::new("/repo/src/generated/.tracey-ignore"),
+            config,
+            gitignore
+        ));
+    }
+
     #[test]
     fn reconfigure_ignores_unrelated_paths() {
         let config = Path::new("/repo/.config/tracey/config.styx");
@@ -823,4 +1153,30 @@ mod tests {
             gitignore
         ));
     }
+
+    #[test]
+    fn watch_patterns_exclude_before_include() {
+        let patterns = WatchPatterns {
+            include: compile_globs(&["src/**/*.rs".to_string(), "src/[".to_string()]),
+            exclude: compile_globs(&["src/generated/**".to_string()]),
+        };
+        assert!(patterns.is_match(Path::new("src/lib.rs")));
+        assert!(!patterns.is_match(Path::new("src/generated/api.rs")));
+        assert!(!patterns.is_match(Path::new("docs/spec.md")));
+
+        let everything = WatchPatterns {
+            include: compile_globs(&[]),
+            exclude: compile_globs(&["target/**".to_string()]),
+        };
+        assert!(everything.is_match(Path::new("docs/spec.md")));
+        assert!(!everything.is_match(Path::new("target/debug/build.rs")));
+    }
+
+    // r[verify daemon.lifecycle.pid-liveness]
+    #[test]
+    fn pid_liveness_asks_the_os() {
+        assert!(super::is_pid_alive(std::process::id()));
+        // Above Linux's pid limit and not a multiple of 4 like Windows pids.
+        assert!(!super::is_pid_alive(4_194_305));
+    }
 }
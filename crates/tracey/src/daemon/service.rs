@@ -11,8 +11,9 @@ use tracing::debug;
 
 use super::engine::Engine;
 use super::watcher::WatcherState;
-use crate::rule_suggestions::suggest_similar_rule_ids;
-use crate::server::QueryEngine;
+use crate::data::{rename_targets, renamed_reference_message};
+use crate::rule_suggestions::{fuzzy_match_tier, suggest_similar_rule_ids};
+use crate::server::{ListWindow, QueryEngine};
 use roam::{Context, Tx};
 
 // Re-export the generated dispatcher from tracey-proto
@@ -232,13 +233,40 @@ impl TraceyDaemon for TraceyService {
         StatusResponse {
             impls: stats
                 .into_iter()
-                .map(|(spec, impl_name, s)| ImplStatus {
-                    spec,
-                    impl_name,
-                    total_rules: s.total_rules,
-                    covered_rules: s.impl_covered,
-                    stale_rules: s.stale_covered,
-                    verified_rules: s.verify_covered,
+                .map(|(spec, impl_name, s)| {
+                    // r[impl daemon.status.by-file]
+                    let mut by_file = query.status_by_file(&spec, &impl_name);
+                    if by_file.len() < 2 {
+                        by_file.clear();
+                    }
+                    ImplStatus {
+                        spec,
+                        impl_name,
+                        total_rules: s.total_rules,
+                        covered_rules: s.impl_covered,
+                        stale_rules: s.stale_covered,
+                        verified_rules: s.verify_covered,
+                        by_file: by_file
+                            .into_iter()
+                            .map(|(file, f)| FileStatus {
+                                file,
+                                total_rules: f.total_rules,
+                                covered_rules: f.impl_covered,
+                                stale_rules: f.stale_covered,
+                                verified_rules: f.verify_covered,
+                            })
+                            .collect(),
+                    }
+                })
+                .collect(),
+            by_rule: query
+                .status_by_rule()
+                .into_iter()
+                .map(|r| RuleImplsStatus {
+                    spec: r.spec,
+                    id: r.id,
+                    covered_in: r.covered_in,
+                    missing_in: r.missing_in,
                 })
                 .collect(),
         }
@@ -253,7 +281,16 @@ impl TraceyDaemon for TraceyService {
         let (spec, impl_name) =
             self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
 
-        if let Some(result) = query.uncovered(&spec, &impl_name, req.prefix.as_deref()) {
+        // r[impl daemon.cli.query.window]
+        let window = ListWindow::new(req.sort, req.limit, req.offset);
+        let result = query.uncovered(
+            &spec,
+            &impl_name,
+            req.prefix.as_deref(),
+            req.owner.as_deref(),
+            window,
+        );
+        if let Some(result) = result {
             UncoveredResponse {
                 spec: result.spec,
                 impl_name: result.impl_name,
@@ -269,6 +306,7 @@ impl TraceyDaemon for TraceyService {
                             .map(|r| tracey_proto::RuleRef {
                                 id: r.id,
                                 text: None, // RuleRef in server.rs doesn't have text
+                                owners: r.owners,
                             })
                             .collect(),
                     })
@@ -293,14 +331,17 @@ impl TraceyDaemon for TraceyService {
         let (spec, impl_name) =
             self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
 
-        if let Some(result) = query.untested(&spec, &impl_name, req.prefix.as_deref()) {
-            UntestedResponse {
-                spec: result.spec,
-                impl_name: result.impl_name,
-                total_rules: result.stats.total_rules,
-                untested_count: result.total_untested,
-                by_section: result
-                    .by_section
+        let window = ListWindow::new(req.sort, req.limit, req.offset);
+        let result = query.untested(
+            &spec,
+            &impl_name,
+            req.prefix.as_deref(),
+            req.only_implemented,
+            window,
+        );
+        if let Some(result) = result {
+            let sections = |by_section: Vec<(String, Vec<crate::server::RuleRef>)>| {
+                by_section
                     .into_iter()
                     .map(|(section, rules)| SectionRules {
                         section,
@@ -309,10 +350,22 @@ impl TraceyDaemon for TraceyService {
                             .map(|r| tracey_proto::RuleRef {
                                 id: r.id,
                                 text: None,
+                                owners: r.owners,
                             })
                             .collect(),
                     })
-                    .collect(),
+                    .collect()
+            };
+            UntestedResponse {
+                spec: result.spec,
+                impl_name: result.impl_name,
+                total_rules: result.stats.total_rules,
+                untested_count: result.total_untested,
+                implemented_count: result.total_implemented,
+                by_section: sections(result.by_section),
+                unimplemented_by_section: sections(result.unimplemented_by_section),
+                unproven_count: result.total_unproven,
+                unproven_by_section: sections(result.unproven_by_section),
             }
         } else {
             UntestedResponse {
@@ -320,7 +373,11 @@ impl TraceyDaemon for TraceyService {
                 impl_name,
                 total_rules: 0,
                 untested_count: 0,
+                implemented_count: 0,
                 by_section: vec![],
+                unimplemented_by_section: vec![],
+                unproven_count: 0,
+                unproven_by_section: vec![],
             }
         }
     }
@@ -333,12 +390,13 @@ impl TraceyDaemon for TraceyService {
         let (spec, impl_name) =
             self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
 
-        if let Some(result) = query.stale(&spec, &impl_name, req.prefix.as_deref()) {
+        let window = ListWindow::new(req.sort, req.limit, req.offset);
+        if let Some(result) = query.stale(&spec, &impl_name, req.prefix.as_deref(), window) {
             StaleResponse {
                 spec: result.spec,
                 impl_name: result.impl_name,
                 total_rules: result.stats.total_rules,
-                stale_count: result.entries.len(),
+                stale_count: result.total_stale,
                 refs: result
                     .entries
                     .into_iter()
@@ -361,6 +419,203 @@ impl TraceyDaemon for TraceyService {
         }
     }
 
+    /// Get the traceability matrix
+    ///
+    /// r[impl daemon.cli.query.matrix]
+    async fn matrix(&self, _cx: &Context, req: MatrixRequest) -> MatrixResponse {
+        let data = self.inner.engine.data().await;
+        let prefix = req.prefix.as_deref().map(str::to_lowercase);
+
+        let specs = data
+            .config
+            .specs
+            .iter()
+            .filter(|spec| req.spec.as_deref().is_none_or(|name| name == spec.name))
+            .map(|spec| {
+                let forwards: Vec<_> = spec
+                    .implementations
+                    .iter()
+                    .filter_map(|impl_name| {
+                        data.forward_by_impl
+                            .get(&(spec.name.clone(), impl_name.clone()))
+                            .map(|forward| (impl_name.clone(), forward))
+                    })
+                    .collect();
+                let rules_by_impl: Vec<std::collections::HashMap<&RuleId, &ApiRule>> = forwards
+                    .iter()
+                    .map(|(_, forward)| forward.rules.iter().map(|r| (&r.id, r)).collect())
+                    .collect();
+                // r[impl daemon.cli.query.matrix.variants]
+                // An impl with variants gets one `impl:variant` column per variant.
+                let variants: Vec<crate::variants::Variants> = forwards
+                    .iter()
+                    .map(|(impl_name, _)| {
+                        crate::variants::Variants::new(&spec.variants, impl_name)
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                let columns: Vec<(usize, Option<&str>)> = variants
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, variants)| {
+                        if variants.is_empty() {
+                            vec![(i, None)]
+                        } else {
+                            variants.names().map(|name| (i, Some(name))).collect()
+                        }
+                    })
+                    .collect();
+                let rows = forwards
+                    .first()
+                    .map(|(_, forward)| forward.rules.as_slice())
+                    .unwrap_or(&[])
+                    .iter()
+                    .filter(|rule| {
+                        prefix
+                            .as_deref()
+                            .is_none_or(|p| rule.id.base.to_lowercase().starts_with(p))
+                    })
+                    .map(|rule| MatrixRow {
+                        id: rule.id.clone(),
+                        approved: !rule.approvals.is_empty(),
+                        cells: columns
+                            .iter()
+                            .map(|&(i, variant)| {
+                                let found = rules_by_impl[i].get(&rule.id);
+                                match (found, variant) {
+                                    (Some(found), Some(variant)) => {
+                                        variants[i].cell(found, variant)
+                                    }
+                                    _ => MatrixCell {
+                                        impl_refs: found.map_or(0, |r| r.impl_refs.len()),
+                                        verify_refs: found.map_or(0, |r| r.verify_refs.len()),
+                                        stale_refs: found.map_or(0, |r| r.stale_refs.len()),
+                                    },
+                                }
+                            })
+                            .collect(),
+                    })
+                    .collect();
+                SpecMatrix {
+                    spec: spec.name.clone(),
+                    impls: columns
+                        .iter()
+                        .map(|&(i, variant)| match variant {
+                            Some(variant) => format!("{}:{}", forwards[i].0, variant),
+                            None => forwards[i].0.clone(),
+                        })
+                        .collect(),
+                    rows,
+                }
+            })
+            .collect();
+
+        MatrixResponse { specs }
+    }
+
+    /// Get dead rules
+    async fn dead(&self, _cx: &Context, req: DeadRequest) -> DeadResponse {
+        let data = self.inner.engine.data().await;
+
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+        let days = req.days.unwrap_or(crate::dead::DEFAULT_DEAD_DAYS);
+
+        let Some(forward) = data.forward_by_impl.get(&(spec.clone(), impl_name.clone())) else {
+            return DeadResponse {
+                spec,
+                impl_name,
+                total_rules: 0,
+                days,
+                rules: vec![],
+                unknown_age: 0,
+            };
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let (rules, unknown_age) = crate::dead::find_dead_rules(
+            self.inner.engine.project_root(),
+            &forward.rules,
+            days,
+            now,
+        );
+        DeadResponse {
+            spec,
+            impl_name,
+            total_rules: forward.rules.len(),
+            days,
+            rules,
+            unknown_age,
+        }
+    }
+
+    /// Get high-level rules without refinements
+    async fn unrefined(&self, _cx: &Context, req: UnrefinedRequest) -> UnrefinedResponse {
+        let data = self.inner.engine.data().await;
+
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+
+        let Some(forward) = data.forward_by_impl.get(&(spec.clone(), impl_name.clone())) else {
+            return UnrefinedResponse {
+                spec,
+                impl_name,
+                levels: vec![],
+                high_level_rules: 0,
+                rules: vec![],
+            };
+        };
+
+        let levels = crate::refinement::refined_levels(&forward.rules);
+        let high_level_rules = forward
+            .rules
+            .iter()
+            .filter(|r| levels.iter().any(|l| l == crate::refinement::level(&r.id)))
+            .count();
+        UnrefinedResponse {
+            spec,
+            impl_name,
+            levels,
+            high_level_rules,
+            rules: crate::refinement::find_unrefined(&forward.rules),
+        }
+    }
+
+    /// Get rules whose implementing code never ran
+    async fn unexecuted(&self, _cx: &Context, req: UnexecutedRequest) -> UnexecutedResponse {
+        let data = self.inner.engine.data().await;
+
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+        let has_report =
+            crate::line_coverage::line_coverage_path(self.inner.engine.project_root()).exists();
+
+        let Some(forward) = data.forward_by_impl.get(&(spec.clone(), impl_name.clone())) else {
+            return UnexecutedResponse {
+                spec,
+                impl_name,
+                total_rules: 0,
+                has_report,
+                measured_rules: 0,
+                by_section: vec![],
+            };
+        };
+
+        let (measured_rules, by_section) =
+            crate::line_coverage::find_unexecuted(&forward.rules, req.prefix.as_deref());
+        UnexecutedResponse {
+            spec,
+            impl_name,
+            total_rules: forward.rules.len(),
+            has_report,
+            measured_rules,
+            by_section,
+        }
+    }
+
     /// Get unmapped code
     async fn unmapped(&self, _cx: &Context, req: UnmappedRequest) -> UnmappedResponse {
         let data = self.inner.engine.data().await;
@@ -388,29 +643,44 @@ impl TraceyDaemon for TraceyService {
                 flatten_tree(node, &mut entries);
             }
 
+            let mut total_units = result.total_units;
+            let mut unmapped_count = result.total_units.saturating_sub(result.covered_units);
+
             // If we have file details, add those units
             if let Some(details) = &result.file_details {
+                let units: Vec<UnmappedUnit> = details
+                    .units
+                    .iter()
+                    .filter(|u| !u.is_covered)
+                    .map(|u| UnmappedUnit {
+                        kind: u.kind.clone(),
+                        name: u.name.clone(),
+                        start_line: u.start_line,
+                        end_line: u.end_line,
+                    })
+                    .collect();
                 // Find the entry for this file and update its units
                 if let Some(entry) = entries.iter_mut().find(|e| e.path == details.path) {
-                    entry.units = details
-                        .units
-                        .iter()
-                        .filter(|u| !u.is_covered)
-                        .map(|u| UnmappedUnit {
-                            kind: u.kind.clone(),
-                            name: u.name.clone(),
-                            start_line: u.start_line,
-                            end_line: u.end_line,
-                        })
-                        .collect();
+                    entry.units = units;
+                } else {
+                    // A file zoom comes without a tree: the file is the only entry.
+                    total_units = details.units.len();
+                    unmapped_count = units.len();
+                    entries.push(UnmappedEntry {
+                        path: details.path.clone(),
+                        is_dir: false,
+                        total_units,
+                        unmapped_units: unmapped_count,
+                        units,
+                    });
                 }
             }
 
             UnmappedResponse {
                 spec: result.spec,
                 impl_name: result.impl_name,
-                total_units: result.total_units,
-                unmapped_count: result.total_units.saturating_sub(result.covered_units),
+                total_units,
+                unmapped_count,
                 entries,
             }
         } else {
@@ -424,6 +694,239 @@ impl TraceyDaemon for TraceyService {
         }
     }
 
+    /// Get code-unit coverage rolled up per directory
+    // r[impl daemon.cli.query.coverage-by-dir]
+    async fn dir_coverage(&self, _cx: &Context, req: DirCoverageRequest) -> DirCoverageResponse {
+        let data = self.inner.engine.data().await;
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+
+        let key = (spec.clone(), impl_name.clone());
+        let Some(reverse) = data.reverse_by_impl.get(&key) else {
+            return DirCoverageResponse {
+                spec,
+                impl_name,
+                total_units: 0,
+                covered_units: 0,
+                dirs: vec![],
+            };
+        };
+
+        let mut dirs: Vec<DirCoverage> =
+            crate::server::roll_up_dirs(&reverse.files, req.depth.map(|d| d as usize))
+                .into_iter()
+                .map(|(path, (total_units, covered_units))| DirCoverage {
+                    path,
+                    total_units,
+                    covered_units,
+                })
+                .collect();
+        // Dirs come out of the roll-up sorted by path, so stable sorts keep
+        // ties alphabetical.
+        match req.sort.unwrap_or(DirCoverageSort::Coverage) {
+            DirCoverageSort::Coverage => {
+                dirs.sort_by(|a, b| a.coverage_percent().total_cmp(&b.coverage_percent()))
+            }
+            DirCoverageSort::Unmapped => dirs
+                .sort_by_key(|d| std::cmp::Reverse(d.total_units.saturating_sub(d.covered_units))),
+            DirCoverageSort::Path => {}
+        }
+
+        DirCoverageResponse {
+            spec,
+            impl_name,
+            total_units: reverse.total_units,
+            covered_units: reverse.covered_units,
+            dirs,
+        }
+    }
+
+    /// List every reference in a source file with the rule it resolves to
+    // r[impl daemon.cli.query.refs]
+    async fn file_refs(&self, _cx: &Context, req: FileRefsRequest) -> FileRefsResponse {
+        let data = self.inner.engine.data().await;
+        let project_root = self.inner.engine.project_root();
+        let path = project_root.join(&req.path);
+        let display_path = path
+            .strip_prefix(project_root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+
+        let Some(reqs) = lookup_source_reqs(&data, &path) else {
+            return FileRefsResponse {
+                path: display_path,
+                scanned: false,
+                refs: vec![],
+            };
+        };
+
+        let mut known_rules_by_prefix: std::collections::HashMap<&str, Vec<RuleId>> =
+            std::collections::HashMap::new();
+        let mut rules_by_id: std::collections::HashMap<&RuleId, &ApiRule> =
+            std::collections::HashMap::new();
+        for spec_cfg in &data.config.specs {
+            let rule_ids = known_rules_by_prefix
+                .entry(spec_cfg.prefix.as_str())
+                .or_default();
+            for ((spec_name, _), forward_data) in &data.forward_by_impl {
+                if spec_name == &spec_cfg.name {
+                    for rule in &forward_data.rules {
+                        rule_ids.push(rule.id.clone());
+                        rules_by_id.entry(&rule.id).or_insert(rule);
+                    }
+                }
+            }
+        }
+
+        let refs = reqs
+            .references
+            .iter()
+            .map(|reference| {
+                let (status, current_id) = match known_rules_by_prefix
+                    .get(reference.prefix.as_str())
+                    .map(|known| classify_reference_against_known_rules(&reference.req_id, known))
+                {
+                    Some(KnownRuleMatch::Exact) => (FileRefStatus::Current, None),
+                    Some(KnownRuleMatch::Stale(current)) => (FileRefStatus::Stale, Some(current)),
+                    Some(KnownRuleMatch::Missing) | None => (FileRefStatus::Unknown, None),
+                };
+                let text = match status {
+                    FileRefStatus::Unknown => None,
+                    _ => rules_by_id
+                        .get(current_id.as_ref().unwrap_or(&reference.req_id))
+                        .map(|rule| rule.raw.clone()),
+                };
+                FileRef {
+                    verb: reference.verb.as_str().to_string(),
+                    prefix: reference.prefix.clone(),
+                    rule_id: reference.req_id.clone(),
+                    line: reference.line,
+                    status,
+                    current_id,
+                    text,
+                }
+            })
+            .collect();
+
+        FileRefsResponse {
+            path: display_path,
+            scanned: true,
+            refs,
+        }
+    }
+
+    /// Get coverage over time from the recorded history snapshots
+    // r[impl daemon.cli.query.trend]
+    async fn coverage_trend(
+        &self,
+        _cx: &Context,
+        req: TrendRequest,
+    ) -> Result<TrendResponse, String> {
+        #[cfg(feature = "sqlite")]
+        {
+            let project_root = self.inner.engine.project_root().to_path_buf();
+            let since = req
+                .since_secs
+                .map(|age| crate::history::now_secs().saturating_sub(age))
+                .unwrap_or(0);
+            tokio::task::spawn_blocking(move || {
+                let Some(history) = crate::history::History::open_existing(&project_root)
+                    .map_err(|e| format!("Error opening coverage history: {}", e))?
+                else {
+                    return Err(
+                        "No coverage history yet; run `tracey snapshot record` to start one"
+                            .to_string(),
+                    );
+                };
+                let points = history
+                    .trend(req.spec.as_deref(), req.impl_name.as_deref(), since)
+                    .map_err(|e| format!("Error reading coverage history: {}", e))?
+                    .into_iter()
+                    .map(|(recorded_at, row)| TrendPoint {
+                        recorded_at,
+                        spec: row.spec,
+                        impl_name: row.impl_name,
+                        total_rules: row.total_rules,
+                        implemented_rules: row.implemented_rules,
+                        verified_rules: row.verified_rules,
+                        stale_rules: row.stale_rules,
+                    })
+                    .collect();
+                Ok(TrendResponse { points })
+            })
+            .await
+            .map_err(|e| format!("Coverage history task failed: {}", e))?
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let _ = req;
+            Err("Coverage history requires tracey built with the `sqlite` feature".to_string())
+        }
+    }
+
+    /// List rules whose definition or coverage changed since a history snapshot
+    // r[impl daemon.cli.query.recent]
+    async fn recent(&self, _cx: &Context, req: RecentRequest) -> Result<RecentResponse, String> {
+        #[cfg(feature = "sqlite")]
+        {
+            let since = crate::history::parse_since(&req.since, crate::history::now_secs())
+                .map_err(|e| e.to_string())?;
+            let data = self.inner.engine.data().await;
+            let current = crate::history::snapshot_rule_rows(&data);
+            let project_root = self.inner.engine.project_root().to_path_buf();
+            tokio::task::spawn_blocking(move || {
+                let Some(history) = crate::history::History::open_existing(&project_root)
+                    .map_err(|e| format!("Error opening coverage history: {}", e))?
+                else {
+                    return Err(
+                        "No coverage history yet; run `tracey snapshot record` to start one"
+                            .to_string(),
+                    );
+                };
+                let read = |e: eyre::Report| format!("Error reading coverage history: {}", e);
+                let Some((baseline_version, baseline_recorded_at)) =
+                    history.baseline(since).map_err(read)?
+                else {
+                    return Err(match since {
+                        crate::history::Since::Version(version) => {
+                            format!("No snapshot with version {version} in the coverage history")
+                        }
+                        crate::history::Since::At(_) => {
+                            "No snapshot records rules yet; run `tracey snapshot record`"
+                                .to_string()
+                        }
+                    });
+                };
+                let (latest_version, _) = history
+                    .latest()
+                    .map_err(read)?
+                    .unwrap_or((baseline_version, baseline_recorded_at));
+                let before = history.rule_rows_of(baseline_version).map_err(read)?;
+                if before.is_empty() && !current.is_empty() {
+                    return Err(format!(
+                        "Snapshot {baseline_version} predates per-rule history; \
+                         pass a later version or an age"
+                    ));
+                }
+                Ok(RecentResponse {
+                    since: req.since,
+                    baseline_version: baseline_version as u64,
+                    baseline_recorded_at,
+                    latest_version: latest_version as u64,
+                    rules: crate::history::recent_changes(&before, &current),
+                })
+            })
+            .await
+            .map_err(|e| format!("Coverage history task failed: {}", e))?
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let _ = req;
+            Err("Coverage history requires tracey built with the `sqlite` feature".to_string())
+        }
+    }
+
     /// Get details for a specific rule
     async fn rule(&self, _cx: &Context, rule_id: RuleId) -> Option<RuleInfo> {
         let data = self.inner.engine.data().await;
@@ -453,6 +956,9 @@ impl TraceyDaemon for TraceyService {
             html: info.html,
             source_file: info.source_file,
             source_line: info.source_line,
+            permalink: info.permalink,
+            owners: info.owners,
+            approvals: info.approvals,
             coverage: info
                 .coverage
                 .into_iter()
@@ -461,18 +967,152 @@ impl TraceyDaemon for TraceyService {
                     impl_name: c.impl_name,
                     impl_refs: c.impl_refs,
                     verify_refs: c.verify_refs,
+                    test_results: c.test_results,
+                    line_coverage: c.line_coverage,
                 })
                 .collect(),
             version_diff,
+            depends_on: info.depends_on,
+            refines: info.refines,
+            refined_by: info.refined_by,
         })
     }
 
+    /// Get `git blame` for a rule's text and for each reference to it
+    // r[impl daemon.cli.query.rule-blame]
+    async fn rule_blame(&self, _cx: &Context, rule_id: RuleId) -> Option<RuleBlameResponse> {
+        let data = self.inner.engine.data().await;
+        let project_root = self.inner.engine.project_root();
+
+        let mut files: std::collections::HashMap<String, _> = std::collections::HashMap::new();
+        let mut blame_at = |file: &str, first: usize, last: usize| -> Option<BlameInfo> {
+            let lines = files
+                .entry(file.to_string())
+                .or_insert_with(|| {
+                    crate::blame::blame_file(project_root, &project_root.join(file)).ok()
+                })
+                .as_ref()?;
+            crate::blame::latest_in_range(lines, first, last).map(|blame| BlameInfo {
+                commit: blame.commit.clone(),
+                author: blame.author.clone(),
+                author_mail: blame.author_mail.clone(),
+                author_time: blame.author_time,
+                summary: blame.summary.clone(),
+            })
+        };
+
+        let mut response: Option<RuleBlameResponse> = None;
+        for ((spec, impl_name), forward) in &data.forward_by_impl {
+            // Exact version first, then the latest version with the same base
+            let Some(rule) = forward.rules.iter().find(|r| r.id == rule_id).or_else(|| {
+                forward
+                    .rules
+                    .iter()
+                    .filter(|r| r.id.base == rule_id.base)
+                    .max_by_key(|r| r.id.version)
+            }) else {
+                continue;
+            };
+            let entry = response.get_or_insert_with(|| {
+                // The marker line plus every line of the rule text
+                let text = match (&rule.source_file, rule.source_line) {
+                    (Some(file), Some(line)) => {
+                        blame_at(file, line, line + rule.raw.lines().count())
+                    }
+                    _ => None,
+                };
+                RuleBlameResponse {
+                    id: rule.id.clone(),
+                    source_file: rule.source_file.clone(),
+                    source_line: rule.source_line,
+                    text,
+                    refs: vec![],
+                }
+            });
+
+            let is_stale_ref = |code_ref: &&ApiCodeRef| {
+                rule.stale_refs
+                    .iter()
+                    .any(|s| s.file == code_ref.file && s.line == code_ref.line)
+            };
+            let refs = rule
+                .impl_refs
+                .iter()
+                .filter(|r| !is_stale_ref(r))
+                .map(|r| ("impl", &r.file, r.line))
+                .chain(rule.verify_refs.iter().map(|r| ("verify", &r.file, r.line)))
+                .chain(rule.stale_refs.iter().map(|r| ("stale", &r.file, r.line)));
+            for (kind, file, line) in refs {
+                entry.refs.push(RefBlame {
+                    spec: spec.clone(),
+                    impl_name: impl_name.clone(),
+                    kind: kind.to_string(),
+                    file: file.clone(),
+                    line,
+                    blame: blame_at(file, line, line),
+                });
+            }
+        }
+        response
+    }
+
+    /// List the IDs of all rules matching a glob pattern
+    // r[impl daemon.cli.query.rule-glob]
+    async fn find_rules(&self, _cx: &Context, pattern: String) -> Vec<RuleId> {
+        let data = self.inner.engine.data().await;
+        let Ok(glob) = globset::Glob::new(&pattern) else {
+            return vec![];
+        };
+        let matcher = glob.compile_matcher();
+
+        let ids: std::collections::BTreeSet<&RuleId> = data
+            .forward_by_impl
+            .values()
+            .flat_map(|forward| &forward.rules)
+            .map(|rule| &rule.id)
+            .filter(|id| matcher.is_match(&id.base))
+            .collect();
+        ids.into_iter().cloned().collect()
+    }
+
     /// Get current configuration
     async fn config(&self, _cx: &Context) -> ApiConfig {
         let data = self.inner.engine.data().await;
         data.config.clone()
     }
 
+    /// Answer several queries in one round trip
+    // r[impl daemon.roam.batch]
+    async fn batch(&self, cx: &Context, req: BatchRequest) -> BatchResponse {
+        let mut uncovered = Vec::with_capacity(req.uncovered.len());
+        for query in req.uncovered {
+            uncovered.push(self.uncovered(cx, query).await);
+        }
+        let mut stale = Vec::with_capacity(req.stale.len());
+        for query in req.stale {
+            stale.push(self.stale(cx, query).await);
+        }
+        BatchResponse {
+            status: if req.status {
+                Some(self.status(cx).await)
+            } else {
+                None
+            },
+            config: if req.config {
+                Some(self.config(cx).await)
+            } else {
+                None
+            },
+            health: if req.health {
+                Some(self.health(cx).await)
+            } else {
+                None
+            },
+            uncovered,
+            stale,
+        }
+    }
+
     /// VFS: file opened
     async fn vfs_open(&self, _cx: &Context, path: String, content: String) {
         self.inner
@@ -514,6 +1154,31 @@ impl TraceyDaemon for TraceyService {
         }
     }
 
+    /// Re-fetch remote specs and rebuild
+    async fn refetch(&self, _cx: &Context) -> ReloadResponse {
+        match self.inner.engine.refetch_and_rebuild().await {
+            Ok((version, duration)) => ReloadResponse {
+                version,
+                rebuild_time_ms: duration.as_millis() as u64,
+            },
+            Err(e) => {
+                tracing::error!("Refetch failed: {}", e);
+                ReloadResponse {
+                    version: self.inner.engine.version(),
+                    rebuild_time_ms: 0,
+                }
+            }
+        }
+    }
+
+    /// Compare remote specs' locked revisions against upstream
+    async fn spec_drift(&self, _cx: &Context) -> SpecDriftResponse {
+        let config = self.inner.engine.config().await;
+        SpecDriftResponse {
+            specs: crate::remote::check_drift(self.inner.engine.project_root(), &config).await,
+        }
+    }
+
     /// Get current version
     async fn version(&self, _cx: &Context) -> u64 {
         self.inner.engine.version()
@@ -534,6 +1199,7 @@ impl TraceyDaemon for TraceyService {
             watcher_last_event_ms,
             watcher_event_count,
             watched_directories,
+            git_operation_in_progress,
         ) = if let Some(ref state) = self.inner.watcher_state {
             (
                 state.is_active(),
@@ -545,11 +1211,23 @@ impl TraceyDaemon for TraceyService {
                     .into_iter()
                     .map(|p| p.display().to_string())
                     .collect(),
+                state.git_operation_in_progress(),
             )
         } else {
             // No watcher state - return defaults
-            (false, None, None, 0, vec![])
+            (false, None, None, 0, vec![], false)
+        };
+
+        let skipped_files = {
+            let data = self.inner.engine.data().await;
+            let paths: std::collections::BTreeSet<&PathBuf> = data
+                .skipped_files_by_impl
+                .values()
+                .flat_map(|skipped| skipped.keys())
+                .collect();
+            paths.len()
         };
+        let rebuild_queue_depth = self.inner.engine.rebuild_queue_depth().await;
 
         HealthResponse {
             version,
@@ -559,6 +1237,9 @@ impl TraceyDaemon for TraceyService {
             watcher_last_event_ms,
             watcher_event_count,
             watched_directories,
+            git_operation_in_progress,
+            skipped_files,
+            rebuild_queue_depth,
             uptime_secs,
         }
     }
@@ -614,6 +1295,7 @@ impl TraceyDaemon for TraceyService {
                 DataUpdate {
                     version: data.version,
                     delta,
+                    changed_files: data.changed_files.clone(),
                 }
             }; // Guard dropped here before the await
 
@@ -705,11 +1387,15 @@ impl TraceyDaemon for TraceyService {
             })
             .collect();
 
+        let references =
+            QueryEngine::new(&data).file_references(&impl_key.0, &impl_key.1, &full_path);
+
         Some(ApiFileData {
             path: relative,
             content,
             html,
             units: api_units,
+            references,
         })
     }
 
@@ -733,9 +1419,11 @@ impl TraceyDaemon for TraceyService {
             .forward_by_impl
             .get(&(spec.clone(), impl_name.clone()))?;
         let include_patterns = data.spec_includes_by_name.get(&spec)?;
+        let config = self.inner.engine.config().await;
         crate::data::render_spec_content_for_impl(
             self.inner.engine.project_root(),
             include_patterns,
+            crate::config::follow_links(&config),
             &spec,
             &impl_name,
             forward,
@@ -744,8 +1432,34 @@ impl TraceyDaemon for TraceyService {
         .ok()
     }
 
+    /// Get a spec's headings as a section tree with per-section coverage
+    async fn spec_outline(
+        &self,
+        cx: &Context,
+        req: SpecOutlineRequest,
+    ) -> Result<SpecOutlineResponse, String> {
+        let data = self.inner.engine.data().await;
+        let (spec, impl_name) =
+            self.resolve_spec_impl(req.spec.as_deref(), req.impl_name.as_deref(), &data.config);
+        let Some(forward) = data.forward_by_impl.get(&(spec.clone(), impl_name.clone())) else {
+            return Err(format!("Unknown spec/impl: {}/{}", spec, impl_name));
+        };
+        let content = self
+            .spec_content(cx, spec.clone(), impl_name.clone())
+            .await
+            .ok_or_else(|| format!("Could not render spec {}", spec))?;
+        let (sections, unsectioned) = crate::data::section_tree(&content.outline, &forward.rules);
+        Ok(SpecOutlineResponse {
+            spec,
+            impl_name,
+            sections,
+            unsectioned,
+        })
+    }
+
     /// Search rules and files
     async fn search(&self, _cx: &Context, query: String, limit: u32) -> Vec<SearchResult> {
+        let data = self.inner.engine.data().await;
         let raw_results: Vec<_> = self
             .inner
             .engine
@@ -773,8 +1487,16 @@ impl TraceyDaemon for TraceyService {
                 r.highlighted.clone()
             };
 
+            let definition = if r.kind == ResultKind::Rule {
+                parse_rule_id(&r.id).and_then(|id| find_rule_in_data(&data, &id))
+            } else {
+                None
+            };
+
             results.push(SearchResult {
                 kind: kind.to_string(),
+                source_file: definition.and_then(|(_, rule)| rule.source_file.clone()),
+                source_line: definition.and_then(|(_, rule)| rule.source_line),
                 id: r.id,
                 line: r.line,
                 content: Some(r.content),
@@ -879,6 +1601,32 @@ impl TraceyDaemon for TraceyService {
             })
     }
 
+    /// Validate every spec/impl pair
+    ///
+    /// r[impl daemon.roam.validate-all]
+    ///
+    /// Validation already ran for every pair when the data was built, so
+    /// this only gathers the results under one snapshot.
+    async fn validate_all(&self, _cx: &Context) -> Vec<ValidationResult> {
+        let data = self.inner.engine.data().await;
+        QueryEngine::new(&data)
+            .status()
+            .into_iter()
+            .map(|(spec, impl_name, _)| {
+                data.validation_by_impl
+                    .get(&(spec.clone(), impl_name.clone()))
+                    .cloned()
+                    .unwrap_or_else(|| ValidationResult {
+                        spec,
+                        impl_name,
+                        errors: Vec::new(),
+                        warning_count: 0,
+                        error_count: 0,
+                    })
+            })
+            .collect()
+    }
+
     // =========================================================================
     // LSP Support Methods
     // =========================================================================
@@ -967,6 +1715,7 @@ impl TraceyDaemon for TraceyService {
             raw: rule.raw.clone(),
             spec_name: spec_name.clone(),
             spec_url,
+            permalink: rule.permalink.clone(),
             source_file: rule.source_file.clone(),
             impl_count,
             verify_count,
@@ -999,15 +1748,10 @@ impl TraceyDaemon for TraceyService {
         };
 
         // Return the definition location (where the rule is defined in the spec)
-        if let (Some(file), Some(line)) = (&rule.source_file, rule.source_line) {
-            vec![LspLocation {
-                path: file.clone(),
-                line: line.saturating_sub(1) as u32, // Convert to 0-indexed
-                character: rule.source_column.unwrap_or(0) as u32,
-            }]
-        } else {
-            vec![]
-        }
+        let project_root = self.inner.engine.project_root();
+        definition_location(&data, project_root, rule)
+            .into_iter()
+            .collect()
     }
 
     /// Get implementation locations for a reference at a position
@@ -1031,13 +1775,10 @@ impl TraceyDaemon for TraceyService {
         };
 
         // Return all impl reference locations
+        let project_root = self.inner.engine.project_root();
         rule.impl_refs
             .iter()
-            .map(|r| LspLocation {
-                path: r.file.clone(),
-                line: r.line.saturating_sub(1) as u32,
-                character: 0,
-            })
+            .map(|r| code_ref_location(&data, project_root, r, &rule.id))
             .collect()
     }
 
@@ -1061,44 +1802,22 @@ impl TraceyDaemon for TraceyService {
             return vec![];
         };
 
+        let project_root = self.inner.engine.project_root();
         let mut locations = Vec::new();
 
         // Include declaration (definition) if requested
-        if req.include_declaration
-            && let (Some(file), Some(line)) = (&rule.source_file, rule.source_line)
-        {
-            locations.push(LspLocation {
-                path: file.clone(),
-                line: line.saturating_sub(1) as u32,
-                character: rule.source_column.unwrap_or(0) as u32,
-            });
-        }
-
-        // Add all impl refs
-        for r in &rule.impl_refs {
-            locations.push(LspLocation {
-                path: r.file.clone(),
-                line: r.line.saturating_sub(1) as u32,
-                character: 0,
-            });
+        if req.include_declaration {
+            locations.extend(definition_location(&data, project_root, rule));
         }
 
-        // Add all verify refs
-        for r in &rule.verify_refs {
-            locations.push(LspLocation {
-                path: r.file.clone(),
-                line: r.line.saturating_sub(1) as u32,
-                character: 0,
-            });
-        }
-
-        // Add all depends refs
-        for r in &rule.depends_refs {
-            locations.push(LspLocation {
-                path: r.file.clone(),
-                line: r.line.saturating_sub(1) as u32,
-                character: 0,
-            });
+        // Add all impl, verify and depends refs
+        for r in rule
+            .impl_refs
+            .iter()
+            .chain(&rule.verify_refs)
+            .chain(&rule.depends_refs)
+        {
+            locations.push(code_ref_location(&data, project_root, r, &rule.id));
         }
 
         locations
@@ -1163,20 +1882,25 @@ impl TraceyDaemon for TraceyService {
                         after_bracket
                     };
 
-                    // Find matching rules
+                    // Find matching rules, best matches first
+                    let mut matches = Vec::new();
                     for ((spec, _), forward_data) in &data.forward_by_impl {
                         for rule in &forward_data.rules {
-                            if rule.id.base_starts_with(query) || query.is_empty() {
-                                completions.push(LspCompletionItem {
-                                    label: rule.id.to_string(),
-                                    kind: "rule".to_string(),
-                                    detail: Some(spec.clone()),
-                                    documentation: Some(rule.raw.clone()),
-                                    insert_text: None,
-                                });
+                            if let Some(tier) = fuzzy_match_tier(query, &rule.id.base) {
+                                matches.push((tier, spec, rule));
                             }
                         }
                     }
+                    matches.sort_by(|(a, _, _), (b, _, _)| b.cmp(a));
+                    completions.extend(matches.into_iter().map(|(_, spec, rule)| {
+                        LspCompletionItem {
+                            label: rule.id.to_string(),
+                            kind: "rule".to_string(),
+                            detail: Some(spec.clone()),
+                            documentation: Some(rule.raw.clone()),
+                            insert_text: None,
+                        }
+                    }));
                 }
                 break;
             }
@@ -1190,6 +1914,7 @@ impl TraceyDaemon for TraceyService {
     /// r[impl lsp.diagnostics.orphaned]
     /// r[impl lsp.diagnostics.duplicate-definition]
     /// r[impl lsp.diagnostics.impl-in-test]
+    /// r[impl lsp.diagnostics.duplicate-ref]
     async fn lsp_diagnostics(&self, _cx: &Context, req: LspDocumentRequest) -> Vec<LspDiagnostic> {
         let data = self.inner.engine.data().await;
         let path = PathBuf::from(&req.path);
@@ -1200,7 +1925,9 @@ impl TraceyDaemon for TraceyService {
         // and validate inline cross-references like `r[foo.bar]`.
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) =
+                marq::render(&crate::redirects::strip_redirects(&req.content), &options).await
+            {
                 for def in &doc.reqs {
                     // Use marker_span for diagnostics (only squiggle the marker, not content)
                     let (start_line, start_char, end_line, end_char) =
@@ -1271,6 +1998,8 @@ impl TraceyDaemon for TraceyService {
                 }
             }
 
+            let renames = rename_targets(rules_by_id.values());
+
             for reference in extract_markdown_rule_references(&req.content) {
                 let (start_line, start_char, end_line, end_char) =
                     span_to_range(&req.content, reference.span_offset, reference.span_length);
@@ -1309,6 +2038,18 @@ impl TraceyDaemon for TraceyService {
                             end_char,
                         });
                     }
+                    KnownRuleMatch::Missing if renames.contains_key(&reference.req_id.base) => {
+                        let current_id = &renames[&reference.req_id.base];
+                        diagnostics.push(LspDiagnostic {
+                            severity: "warning".to_string(),
+                            code: "renamed".to_string(),
+                            message: renamed_reference_message(&reference.req_id, current_id),
+                            start_line,
+                            start_char,
+                            end_line,
+                            end_char,
+                        });
+                    }
                     KnownRuleMatch::Missing => {
                         let message = unknown_rule_message_with_suggestions(
                             &reference.req_id,
@@ -1335,9 +2076,6 @@ impl TraceyDaemon for TraceyService {
             return diagnostics;
         };
 
-        // Check if this is a test file
-        let is_test = data.test_files.contains(&path);
-
         // Build set of known prefixes
         let known_prefixes: std::collections::HashSet<_> = data
             .config
@@ -1378,6 +2116,7 @@ impl TraceyDaemon for TraceyService {
                 }
             }
         }
+        let renames = rename_targets(rules_by_id.values());
         for reference in &reqs.references {
             let (start_line, start_char, end_line, end_char) =
                 span_to_range(&req.content, reference.span.offset, reference.span.length);
@@ -1427,6 +2166,19 @@ impl TraceyDaemon for TraceyService {
                         end_char,
                     });
                 }
+                // r[impl lsp.diagnostics.renamed]
+                KnownRuleMatch::Missing if renames.contains_key(&reference.req_id.base) => {
+                    let current_id = &renames[&reference.req_id.base];
+                    diagnostics.push(LspDiagnostic {
+                        severity: "warning".to_string(),
+                        code: "renamed".to_string(),
+                        message: renamed_reference_message(&reference.req_id, current_id),
+                        start_line,
+                        start_char,
+                        end_line,
+                        end_char,
+                    });
+                }
                 KnownRuleMatch::Missing => {
                     let message =
                         unknown_rule_message_with_suggestions(&reference.req_id, known_for_prefix);
@@ -1442,8 +2194,10 @@ impl TraceyDaemon for TraceyService {
                 }
             }
 
-            // Check for impl in test file
-            if is_test && reference.verb == tracey_core::RefVerb::Impl {
+            // Check for impl in test file or test module
+            if reference.verb == tracey_core::RefVerb::Impl
+                && data.is_test_context(&path, reference.line)
+            {
                 diagnostics.push(LspDiagnostic {
                     severity: "warning".to_string(),
                     code: "impl-in-test".to_string(),
@@ -1457,6 +2211,25 @@ impl TraceyDaemon for TraceyService {
             }
         }
 
+        // Check for references repeated within a code unit
+        for &(dup, first) in lookup_duplicate_refs(&data, &path) {
+            let reference = &reqs.references[dup];
+            let (start_line, start_char, end_line, end_char) =
+                span_to_range(&req.content, reference.span.offset, reference.span.length);
+            diagnostics.push(LspDiagnostic {
+                severity: "hint".to_string(),
+                code: "duplicate-reference".to_string(),
+                message: crate::data::duplicate_reference_message(
+                    reference,
+                    &reqs.references[first],
+                ),
+                start_line,
+                start_char,
+                end_line,
+                end_char,
+            });
+        }
+
         // Check warnings from parsing
         for warning in &reqs.warnings {
             let (start_line, start_char, end_line, end_char) =
@@ -1612,7 +2385,9 @@ impl TraceyDaemon for TraceyService {
         // For markdown spec files, tokenize requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) =
+                marq::render(&crate::redirects::strip_redirects(&req.content), &options).await
+            {
                 for def in &doc.reqs {
                     // Use marker_span for semantic tokens (only color the marker)
                     let (start_line, start_char, _, _) =
@@ -1672,7 +2447,9 @@ impl TraceyDaemon for TraceyService {
         // For markdown spec files, show code lenses for requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) =
+                marq::render(&crate::redirects::strip_redirects(&req.content), &options).await
+            {
                 for def in &doc.reqs {
                     // Use marker_span for code lens positioning
                     let (start_line, start_char, _, end_char) =
@@ -1756,7 +2533,9 @@ impl TraceyDaemon for TraceyService {
         // For markdown spec files, show hints for requirement definitions
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) =
+                marq::render(&crate::redirects::strip_redirects(&req.content), &options).await
+            {
                 for def in &doc.reqs {
                     // Use marker_span for inlay hint positioning (after the marker)
                     let (line, _, _, end_char) =
@@ -1923,6 +2702,7 @@ impl TraceyDaemon for TraceyService {
     /// Get code actions for a position
     ///
     /// r[impl lsp.actions.create-requirement]
+    /// r[impl lsp.actions.remove-duplicate-ref]
     /// r[impl lsp.actions.open-dashboard]
     async fn lsp_code_actions(&self, _cx: &Context, req: LspPositionRequest) -> Vec<LspCodeAction> {
         let data = self.inner.engine.data().await;
@@ -1934,8 +2714,26 @@ impl TraceyDaemon for TraceyService {
         if let Some(rule_at_pos) =
             find_rule_at_position(&data, &path, &req.content, req.line, req.character).await
         {
+            let renames =
+                rename_targets(data.forward_by_impl.values().flat_map(|f| f.rules.iter()));
+
             // Check if it's an orphaned reference
-            if find_rule_in_data(&data, &rule_at_pos.req_id).is_none() {
+            if find_rule_in_data(&data, &rule_at_pos.req_id).is_none()
+                && let Some(current_id) = renames.get(&rule_at_pos.req_id.base)
+            {
+                // r[impl lsp.actions.rename-redirect]
+                actions.push(LspCodeAction {
+                    title: format!(
+                        "Rename '{}' to '{}' (all impl annotations)",
+                        rule_at_pos.req_id, current_id
+                    ),
+                    kind: "quickfix".to_string(),
+                    command: "tracey.renameUnknownRequirement".to_string(),
+                    arguments: vec![rule_at_pos.req_id.to_string(), current_id.to_string()],
+                    is_preferred: true,
+                    edits: vec![],
+                });
+            } else if find_rule_in_data(&data, &rule_at_pos.req_id).is_none() {
                 if let Some(prefix) = rule_at_pos.prefix.as_deref() {
                     let spec_names: std::collections::HashSet<&str> = data
                         .config
@@ -1965,6 +2763,7 @@ impl TraceyDaemon for TraceyService {
                             command: "tracey.renameUnknownRequirement".to_string(),
                             arguments: vec![rule_at_pos.req_id.to_string(), best.to_string()],
                             is_preferred: true,
+                            edits: vec![],
                         });
                     }
                 }
@@ -1974,6 +2773,7 @@ impl TraceyDaemon for TraceyService {
                     command: "tracey.createRequirement".to_string(),
                     arguments: vec![rule_at_pos.req_id.to_string()],
                     is_preferred: false,
+                    edits: vec![],
                 });
             } else {
                 // Open dashboard for this requirement
@@ -1983,6 +2783,44 @@ impl TraceyDaemon for TraceyService {
                     command: "tracey.openDashboard".to_string(),
                     arguments: vec![rule_at_pos.req_id.to_string()],
                     is_preferred: false,
+                    edits: vec![],
+                });
+            }
+        }
+
+        // r[impl lsp.actions.remove-duplicate-ref]
+        if let Some(offset) = line_col_to_offset(&req.content, req.line, req.character)
+            && let Some(reqs) = lookup_source_reqs(&data, &path)
+        {
+            for &(dup, _) in lookup_duplicate_refs(&data, &path) {
+                let reference = &reqs.references[dup];
+                let (start, len) = (reference.span.offset, reference.span.length);
+                // Build data may lag behind unsaved edits.
+                let in_sync = req
+                    .content
+                    .get(start..start + len)
+                    .is_some_and(|text| text.contains(reference.req_id.base.as_str()));
+                if !in_sync || !(start..=start + len).contains(&offset) {
+                    continue;
+                }
+                let (remove_start, remove_end) =
+                    crate::data::reference_removal_range(&req.content, start, len);
+                let (start_line, start_char, end_line, end_char) =
+                    span_to_range(&req.content, remove_start, remove_end - remove_start);
+                actions.push(LspCodeAction {
+                    title: format!("Remove duplicate reference to '{}'", reference.req_id),
+                    kind: "quickfix".to_string(),
+                    command: String::new(),
+                    arguments: vec![],
+                    is_preferred: true,
+                    edits: vec![LspTextEdit {
+                        path: req.path.clone(),
+                        start_line,
+                        start_char,
+                        end_line,
+                        end_char,
+                        new_text: String::new(),
+                    }],
                 });
             }
         }
@@ -2012,7 +2850,9 @@ impl TraceyDaemon for TraceyService {
         // For markdown files, highlight all definitions of the same rule (typically just one)
         if path.extension().is_some_and(|ext| ext == "md") {
             let options = marq::RenderOptions::default();
-            if let Ok(doc) = marq::render(&req.content, &options).await {
+            if let Ok(doc) =
+                marq::render(&crate::redirects::strip_redirects(&req.content), &options).await
+            {
                 return doc
                     .reqs
                     .iter()
@@ -2020,12 +2860,14 @@ impl TraceyDaemon for TraceyService {
                         parse_rule_id(&r.id.to_string()).is_some_and(|id| id == rule_at_pos.req_id)
                     })
                     .map(|r| {
-                        let (start_line, start_char, _, _) =
+                        let (line, character, end_line, end_character) =
                             span_to_range(&req.content, r.span.offset, r.span.length);
                         LspLocation {
                             path: req.path.clone(),
-                            line: start_line,
-                            character: start_char,
+                            line,
+                            character,
+                            end_line,
+                            end_character,
                         }
                     })
                     .collect();
@@ -2041,12 +2883,14 @@ impl TraceyDaemon for TraceyService {
             .iter()
             .filter(|r| r.req_id == rule_at_pos.req_id)
             .map(|r| {
-                let (start_line, start_char, _, _) =
+                let (line, character, end_line, end_character) =
                     span_to_range(&req.content, r.span.offset, r.span.length);
                 LspLocation {
                     path: req.path.clone(),
-                    line: start_line,
-                    character: start_char,
+                    line,
+                    character,
+                    end_line,
+                    end_character,
                 }
             })
             .collect()
@@ -2096,7 +2940,7 @@ impl TraceyDaemon for TraceyService {
         }
 
         // Save config
-        if let Err(e) = save_config(&config_path, &config) {
+        if let Err(e) = crate::save_config(&config_path, &config) {
             return Err(format!("Error saving config: {}", e));
         }
 
@@ -2143,7 +2987,7 @@ impl TraceyDaemon for TraceyService {
         }
 
         // Save config
-        if let Err(e) = save_config(&config_path, &config) {
+        if let Err(e) = crate::save_config(&config_path, &config) {
             return Err(format!("Error saving config: {}", e));
         }
 
@@ -2307,6 +3151,18 @@ fn lookup_source_reqs<'a>(
         .or_else(|| data.source_reqs_by_file.get(path))
 }
 
+fn lookup_duplicate_refs<'a>(
+    data: &'a crate::data::DashboardData,
+    path: &Path,
+) -> &'a [(usize, usize)] {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    data.duplicate_refs_by_file
+        .get(&canonical)
+        .or_else(|| data.duplicate_refs_by_file.get(path))
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
 /// Find a rule (reference or definition) at the given position.
 ///
 /// For markdown spec files, uses marq to extract requirement definitions.
@@ -2323,7 +3179,9 @@ async fn find_rule_at_position(
 
         // Parse markdown to find requirement definitions first.
         let options = marq::RenderOptions::default();
-        let doc = marq::render(content, &options).await.ok()?;
+        let doc = marq::render(&crate::redirects::strip_redirects(content), &options)
+            .await
+            .ok()?;
         if let Some(rule) = doc.reqs.iter().find_map(|r| {
             let start = r.span.offset;
             let end = r.span.offset + r.span.length;
@@ -2453,6 +3311,88 @@ fn span_to_range(content: &str, offset: usize, length: usize) -> (u32, u32, u32,
     }
 }
 
+/// A project file's contents as of the last build, or as on disk.
+fn lookup_file_content(data: &crate::data::DashboardData, path: &Path) -> Option<String> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    data.search_files
+        .get(&canonical)
+        .or_else(|| data.search_files.get(path))
+        .cloned()
+        .or_else(|| std::fs::read_to_string(path).ok())
+}
+
+/// The exact range of the reference to `rule_id` at `code_ref`, or an empty
+/// range at the start of its line if the reference can't be found.
+///
+/// r[impl lsp.ranges.exact]
+fn code_ref_location(
+    data: &crate::data::DashboardData,
+    project_root: &Path,
+    code_ref: &ApiCodeRef,
+    rule_id: &RuleId,
+) -> LspLocation {
+    let line = code_ref.line.saturating_sub(1) as u32;
+    let path = project_root.join(&code_ref.file);
+    let range = lookup_source_reqs(data, &path)
+        .and_then(|reqs| {
+            reqs.references
+                .iter()
+                .find(|r| r.line == code_ref.line && r.req_id.base == rule_id.base)
+        })
+        .and_then(|r| {
+            let content = lookup_file_content(data, &path)?;
+            Some(span_to_range(&content, r.span.offset, r.span.length))
+        });
+    let (line, character, end_line, end_character) = range.unwrap_or((line, 0, line, 0));
+    LspLocation {
+        path: code_ref.file.clone(),
+        line,
+        character,
+        end_line,
+        end_character,
+    }
+}
+
+/// The exact range of a rule's definition marker, such as `r[auth.login]`.
+///
+/// r[impl lsp.goto.precise-location]
+/// r[impl lsp.ranges.exact]
+fn definition_location(
+    data: &crate::data::DashboardData,
+    project_root: &Path,
+    rule: &ApiRule,
+) -> Option<LspLocation> {
+    let (file, line) = (rule.source_file.as_ref()?, rule.source_line?);
+    let line = line.saturating_sub(1);
+    // `source_column` is 1-indexed and may point at a blockquote's `>`.
+    let from = rule.source_column.unwrap_or(1).saturating_sub(1);
+    let (start, end) = lookup_file_content(data, &project_root.join(file))
+        .and_then(|content| {
+            let text = content.lines().nth(line)?;
+            marker_columns(text, from, &rule.id.base)
+        })
+        .unwrap_or((from, from));
+    Some(LspLocation {
+        path: file.clone(),
+        line: line as u32,
+        character: start as u32,
+        end_line: line as u32,
+        end_character: end as u32,
+    })
+}
+
+/// Character columns of the first `PREFIX[BASE...]` marker at or after
+/// column `from` of a line.
+fn marker_columns(text: &str, from: usize, base: &str) -> Option<(usize, usize)> {
+    let from = text.char_indices().nth(from).map_or(text.len(), |(i, _)| i);
+    let open = from + text[from..].find(&format!("[{base}"))?;
+    let start = text[..open]
+        .trim_end_matches(|c: char| c.is_ascii_alphanumeric())
+        .len();
+    let close = open + text[open..].find(']')? + 1;
+    Some((text[..start].chars().count(), text[..close].chars().count()))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum KnownRuleMatch {
     Exact,
@@ -2540,7 +3480,9 @@ fn run_git_capture(project_root: &Path, args: &[&str]) -> Option<String> {
 
 async fn find_rule_text_in_markdown(content: &str, rule_id: &RuleId) -> Option<String> {
     let options = marq::RenderOptions::default();
-    let doc = marq::render(content, &options).await.ok()?;
+    let doc = marq::render(&crate::redirects::strip_redirects(content), &options)
+        .await
+        .ok()?;
     let rule_id = rule_id.to_string();
     doc.reqs
         .iter()
@@ -2588,15 +3530,6 @@ fn stale_diagnostic_message_short(
     message
 }
 
-/// Save config to file
-fn save_config(path: &Path, config: &crate::config::Config) -> eyre::Result<()> {
-    use std::io::Write;
-    let styx_string = facet_styx::to_string(config)?;
-    let mut file = std::fs::File::create(path)?;
-    file.write_all(styx_string.as_bytes())?;
-    Ok(())
-}
-
 /// Check if a rule ID follows the naming convention
 fn is_valid_rule_id(id: &RuleId) -> bool {
     let base_id = &id.base;
@@ -13,11 +13,25 @@
 //!
 //! ## Reconfiguration
 //!
-//! When config.styx or .gitignore changes, the watcher sends a
-//! `Reconfigure` event. The rebuild loop then:
+//! When config.styx, .gitignore or a .tracey-ignore file changes, the
+//! watcher sends a `Reconfigure` event. The rebuild loop then:
 //! 1. Rebuilds the gitignore matcher
 //! 2. Calls `WatcherManager::reconfigure()` to update watches
 //! 3. Triggers a rebuild
+//!
+//! ## Symlinks
+//!
+//! Watched directories are canonical, except for impl include directories
+//! reached through a symlink: those are watched through the link, so events
+//! carry the paths the scanner walked.
+//!
+//! ## Git Operations
+//!
+//! `git checkout`, `rebase`, `merge` and friends rewrite large parts of the
+//! working tree in one go. The `.git` directory is watched non-recursively so
+//! that changes to `HEAD`, the index, or in-progress markers are reported as a
+//! `GitOperation` event; the rebuild loop then holds off until the operation
+//! settles and runs a single full rebuild instead of one per batch.
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -62,6 +76,52 @@ pub enum WatcherEvent {
 
     /// Config or gitignore changed - triggers reconfiguration.
     Reconfigure,
+
+    /// Git metadata changed (HEAD, index, rebase/merge markers) - the working
+    /// tree is likely being rewritten, so rebuilds should wait for it to settle.
+    GitOperation,
+}
+
+// ============================================================================
+// Git Operation Detection
+// ============================================================================
+
+/// Entries directly under `.git/` whose changes indicate that git is
+/// rewriting the working tree (checkout, rebase, merge, reset, ...).
+const GIT_OPERATION_ENTRIES: &[&str] = &[
+    "HEAD",
+    "HEAD.lock",
+    "ORIG_HEAD",
+    "index",
+    "index.lock",
+    "MERGE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "rebase-merge",
+    "rebase-apply",
+];
+
+/// Lock files git holds while it is actively writing.
+const GIT_LOCK_FILES: &[&str] = &["index.lock", "HEAD.lock"];
+
+/// Check if a path is git metadata that signals a working-tree rewrite.
+///
+/// Only direct children of `git_dir` are considered; object and ref writes
+/// deeper in the tree always accompany one of these anyway.
+pub fn is_git_operation_path(path: &Path, git_dir: &Path) -> bool {
+    if path.parent() != Some(git_dir) {
+        return false;
+    }
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| GIT_OPERATION_ENTRIES.contains(&name))
+}
+
+/// Check if git currently holds one of its lock files in `git_dir`.
+pub fn git_lock_held(git_dir: &Path) -> bool {
+    GIT_LOCK_FILES
+        .iter()
+        .any(|name| git_dir.join(name).exists())
 }
 
 // ============================================================================
@@ -87,6 +147,9 @@ pub struct WatcherState {
 
     /// Error message if watcher failed (None if healthy).
     error: RwLock<Option<String>>,
+
+    /// Whether a git operation is in progress and rebuilds are being held back.
+    git_operation: AtomicBool,
 }
 
 impl WatcherState {
@@ -98,6 +161,7 @@ impl WatcherState {
             event_count: AtomicU64::new(0),
             watched_dirs: RwLock::new(Vec::new()),
             error: RwLock::new(None),
+            git_operation: AtomicBool::new(false),
         })
     }
 
@@ -123,6 +187,16 @@ impl WatcherState {
         self.last_event_ms.store(now, Ordering::SeqCst);
     }
 
+    /// Mark whether a git operation is in progress.
+    pub fn set_git_operation_in_progress(&self, in_progress: bool) {
+        self.git_operation.store(in_progress, Ordering::SeqCst);
+    }
+
+    /// Check if a git operation is in progress.
+    pub fn git_operation_in_progress(&self) -> bool {
+        self.git_operation.load(Ordering::SeqCst)
+    }
+
     /// Update the list of watched directories.
     pub fn set_watched_dirs(&self, dirs: Vec<PathBuf>) {
         *self.watched_dirs.write().unwrap() = dirs;
@@ -163,6 +237,7 @@ impl Default for WatcherState {
             event_count: AtomicU64::new(0),
             watched_dirs: RwLock::new(Vec::new()),
             error: RwLock::new(None),
+            git_operation: AtomicBool::new(false),
         }
     }
 }
@@ -213,6 +288,7 @@ pub fn glob_to_watch_dir(pattern: &str) -> PathBuf {
 /// The result is deduplicated and sorted.
 pub fn extract_watch_dirs_from_config(config: &Config, project_root: &Path) -> HashSet<PathBuf> {
     let mut dirs = HashSet::new();
+    let follow_links = crate::config::follow_links(config);
 
     // Canonicalize project root for comparison
     let canonical_project_root = project_root.canonicalize().ok();
@@ -252,19 +328,15 @@ pub fn extract_watch_dirs_from_config(config: &Config, project_root: &Path) -> H
 
         // Impl include and test_include patterns
         for impl_ in &spec.impls {
-            for include in &impl_.include {
+            for include in impl_.include.iter().chain(&impl_.test_include) {
                 let dir = glob_to_watch_dir(include);
-                let full_path = project_root.join(&dir);
-                if let Ok(canonical) = full_path.canonicalize() {
-                    dirs.insert(canonical);
-                }
-            }
-
-            for test_include in &impl_.test_include {
-                let dir = glob_to_watch_dir(test_include);
-                let full_path = project_root.join(&dir);
-                if let Ok(canonical) = full_path.canonicalize() {
-                    dirs.insert(canonical);
+                if let Some(watch_dir) = impl_watch_dir(
+                    project_root,
+                    canonical_project_root.as_deref(),
+                    &dir,
+                    follow_links,
+                ) {
+                    dirs.insert(watch_dir);
                 }
             }
         }
@@ -273,6 +345,38 @@ pub fn extract_watch_dirs_from_config(config: &Config, project_root: &Path) -> H
     dirs
 }
 
+/// Where to watch an impl include directory.
+///
+/// Cross-workspace includes are watched where they resolve. Any other
+/// directory that resolves outside the project root is reached through a
+/// symlink: it is watched through the link, so events carry the same
+/// project-relative paths the scanner walked, or not at all when file walks
+/// don't follow links.
+///
+/// r[impl walk.symlinks]
+fn impl_watch_dir(
+    project_root: &Path,
+    canonical_project_root: Option<&Path>,
+    dir: &Path,
+    follow_links: bool,
+) -> Option<PathBuf> {
+    let canonical = project_root.join(dir).canonicalize().ok()?;
+    let Some(root) = canonical_project_root else {
+        return Some(canonical);
+    };
+    if canonical.starts_with(root) || dir.starts_with("..") {
+        Some(canonical)
+    } else if follow_links {
+        Some(root.join(dir))
+    } else {
+        debug!(
+            "Skipping linked directory outside project root: {}",
+            canonical.display()
+        );
+        None
+    }
+}
+
 // ============================================================================
 // Event Batcher
 // ============================================================================
@@ -366,6 +470,12 @@ pub struct WatcherManager {
 
     /// Gitignore path (always watched if exists).
     gitignore_path: PathBuf,
+
+    /// Root `.tracey-ignore` path (always watched if exists).
+    tracey_ignore_path: PathBuf,
+
+    /// Git directory (watched non-recursively if it exists).
+    git_dir: PathBuf,
 }
 
 impl WatcherManager {
@@ -417,6 +527,8 @@ impl WatcherManager {
         .wrap_err("Failed to create file watcher")?;
 
         let gitignore_path = project_root.join(".gitignore");
+        let tracey_ignore_path = project_root.join(tracey_core::IGNORE_FILENAME);
+        let git_dir = project_root.join(".git");
 
         let mut manager = Self {
             watcher,
@@ -424,6 +536,8 @@ impl WatcherManager {
             project_root,
             config_path,
             gitignore_path,
+            tracey_ignore_path,
+            git_dir,
         };
 
         // Always watch config file
@@ -475,6 +589,41 @@ impl WatcherManager {
             info!("Watching gitignore: {}", self.gitignore_path.display());
         }
 
+        // Watch the root .tracey-ignore if it exists; nested ones live in
+        // watched directories
+        // r[impl walk.tracey-ignore]
+        if self.tracey_ignore_path.exists() {
+            self.watcher
+                .watch(&self.tracey_ignore_path, RecursiveMode::NonRecursive)
+                .wrap_err_with(|| {
+                    format!(
+                        "Failed to watch ignore file: {}",
+                        self.tracey_ignore_path.display()
+                    )
+                })?;
+            info!(
+                "Watching ignore file: {}",
+                self.tracey_ignore_path.display()
+            );
+        }
+
+        // Watch the git directory itself (not its contents) so HEAD/index
+        // updates let us detect checkouts and rebases. Worktrees use a `.git`
+        // file instead of a directory; those are skipped.
+        if self.git_dir.is_dir() {
+            match self
+                .watcher
+                .watch(&self.git_dir, RecursiveMode::NonRecursive)
+            {
+                Ok(()) => info!("Watching git directory: {}", self.git_dir.display()),
+                Err(e) => warn!(
+                    "Failed to watch git directory {}: {}",
+                    self.git_dir.display(),
+                    e
+                ),
+            }
+        }
+
         Ok(())
     }
 
@@ -613,6 +762,30 @@ mod tests {
         assert_eq!(glob_to_watch_dir("src/{foo,bar}.rs"), PathBuf::from("src"));
     }
 
+    // r[verify walk.symlinks]
+    #[cfg(unix)]
+    #[test]
+    fn test_linked_include_dirs_are_watched_through_the_link() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("project");
+        let checkout = temp.path().join("checkout");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(&checkout).unwrap();
+        std::os::unix::fs::symlink(&checkout, root.join("shared")).unwrap();
+        let canonical_root = root.canonicalize().unwrap();
+
+        let watch = |dir: &str, follow_links| {
+            impl_watch_dir(&root, Some(&canonical_root), Path::new(dir), follow_links)
+        };
+        assert_eq!(watch("src", true), Some(canonical_root.join("src")));
+        assert_eq!(watch("shared", true), Some(canonical_root.join("shared")));
+        assert_eq!(watch("shared", false), None);
+        assert_eq!(
+            watch("../checkout", false),
+            Some(checkout.canonicalize().unwrap())
+        );
+    }
+
     #[test]
     fn test_watcher_state_lifecycle() {
         let state = WatcherState::new();
@@ -644,6 +817,41 @@ mod tests {
         assert!(state.error().is_none());
     }
 
+    #[test]
+    fn test_watcher_state_git_operation() {
+        let state = WatcherState::new();
+        assert!(!state.git_operation_in_progress());
+
+        state.set_git_operation_in_progress(true);
+        assert!(state.git_operation_in_progress());
+
+        state.set_git_operation_in_progress(false);
+        assert!(!state.git_operation_in_progress());
+    }
+
+    #[test]
+    fn test_is_git_operation_path() {
+        let git_dir = Path::new("/repo/.git");
+        assert!(is_git_operation_path(Path::new("/repo/.git/HEAD"), git_dir));
+        assert!(is_git_operation_path(
+            Path::new("/repo/.git/index.lock"),
+            git_dir
+        ));
+        assert!(is_git_operation_path(
+            Path::new("/repo/.git/rebase-merge"),
+            git_dir
+        ));
+        assert!(!is_git_operation_path(
+            Path::new("/repo/.git/objects/ab/cdef"),
+            git_dir
+        ));
+        assert!(!is_git_operation_path(
+            Path::new("/repo/.git/config"),
+            git_dir
+        ));
+        assert!(!is_git_operation_path(Path::new("/repo/src/HEAD"), git_dir));
+    }
+
     #[test]
     fn test_watcher_state_watched_dirs() {
         let state = WatcherState::new();
@@ -17,13 +17,13 @@ use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Instant;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tracey_core::code_units::CodeUnit;
+use tracey_core::code_units::{CodeUnit, Granularity};
 use tracey_core::is_supported_extension;
 use tracey_core::{
-    ParseWarning, RefVerb, ReqDefinition, ReqReference, Reqs, RuleId, RuleIdMatch,
+    ParseWarning, RefVerb, ReqDefinition, ReqReference, Reqs, RuleId, RuleIdMatch, VersionScheme,
     classify_reference_for_rule, parse_rule_id,
 };
-use tracing::info;
+use tracing::{info, warn};
 
 // Markdown rendering
 use marq::{
@@ -32,8 +32,10 @@ use marq::{
 };
 
 use crate::config::Config;
+use crate::file_limits::{ScanLimits, SkipReason};
 use crate::rule_suggestions::suggest_similar_rule_ids;
 use crate::search;
+use crate::skip_units::SkippedUnits;
 
 // ============================================================================
 // JSON API Types
@@ -41,11 +43,13 @@ use crate::search;
 
 // Re-export API types from tracey-api crate
 pub use tracey_api::{
-    ApiCodeRef, ApiCodeUnit, ApiConfig, ApiFileData, ApiFileEntry, ApiForwardData, ApiReverseData,
-    ApiRule, ApiSpecData, ApiSpecForward, ApiSpecInfo, ApiStaleRef, GitStatus, OutlineCoverage,
-    OutlineEntry, SpecSection, ValidationError, ValidationErrorCode, ValidationResult,
+    ApiApproval, ApiCodeRef, ApiCodeUnit, ApiConfig, ApiFileData, ApiFileEntry, ApiFileReference,
+    ApiForwardData, ApiImplVariant, ApiLineCoverage, ApiReverseData, ApiRule, ApiSkippedUnits,
+    ApiSpecData, ApiSpecDependency, ApiSpecForward, ApiSpecInfo, ApiStaleRef, ApiTestResult,
+    GitStatus, OutlineCoverage, OutlineEntry, SpecSection, TestOutcome, ValidationError,
+    ValidationErrorCode, ValidationResult,
 };
-use tracey_proto::{LspDiagnostic, LspFileDiagnostics};
+use tracey_proto::{LspDiagnostic, LspFileDiagnostics, OutlineSection};
 
 // ============================================================================
 // Core Types
@@ -76,30 +80,182 @@ pub struct DashboardData {
     pub search_rules: Vec<search::RuleEntry>,
     /// Precomputed validation diagnostics by (spec, impl).
     pub validation_by_impl: BTreeMap<ImplKey, ValidationResult>,
+    /// Files each implementation's scan skipped as too large or binary
+    /// r[impl config.file-limits]
+    pub skipped_files_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, SkipReason>>,
     /// Precomputed workspace diagnostics for LSP publishing.
     pub workspace_diagnostics: Vec<LspFileDiagnostics>,
     /// Version number (incremented only when content actually changes)
     pub version: u64,
-    /// Hash of forward + reverse JSON for change detection
-    pub content_hash: u64,
+    /// BLAKE3 hash of the forward and reverse data, for change detection
+    pub content_hash: blake3::Hash,
     /// Delta from previous build (what changed)
     pub delta: crate::server::Delta,
+    /// Project-relative paths whose changes triggered this build (empty for a full rebuild)
+    pub changed_files: Vec<String>,
     /// Files matched by test_include patterns (only verify allowed)
     /// r[impl config.impl.test_include]
     pub test_files: std::collections::HashSet<PathBuf>,
+    /// Line ranges (1-indexed, inclusive) of `#[cfg(test)]` modules in impls
+    /// that use test conventions
+    pub test_regions: BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    /// References repeated within a code unit, as `(duplicate, first)` indices
+    /// into the file's `source_reqs_by_file` references
+    pub duplicate_refs_by_file: BTreeMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+impl DashboardData {
+    /// Whether `line` of `path` is test code, where only verify annotations
+    /// are allowed.
+    pub fn is_test_context(&self, path: &Path, line: usize) -> bool {
+        is_test_context(&self.test_files, &self.test_regions, path, line)
+    }
+}
+
+fn is_test_context(
+    test_files: &std::collections::HashSet<PathBuf>,
+    test_regions: &BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    path: &Path,
+    line: usize,
+) -> bool {
+    test_files.contains(path)
+        || test_regions
+            .get(path)
+            .is_some_and(|regions| regions.iter().any(|(s, e)| (*s..=*e).contains(&line)))
+}
+
+/// Line ranges of the `#[cfg(test)]` modules among a Rust file's code units.
+///
+/// r[impl config.impl.test-conventions]
+pub(crate) fn cfg_test_module_regions(content: &str, units: &[CodeUnit]) -> Vec<(usize, usize)> {
+    units
+        .iter()
+        .filter(|unit| unit.kind == tracey_core::code_units::CodeUnitKind::Module)
+        .filter(|unit| {
+            // Attributes precede the `mod` keyword, possibly on the same line,
+            // separated by doc comments or other attributes.
+            let before = &content[..unit.start_byte.min(content.len())];
+            for line in before.lines().rev() {
+                let line = line.trim();
+                if line.starts_with("#[") {
+                    if line.contains("cfg(test)") {
+                        return true;
+                    }
+                } else if !(line.is_empty()
+                    || line.starts_with("//")
+                    || line == "pub"
+                    || line.starts_with("pub("))
+                {
+                    return false;
+                }
+            }
+            false
+        })
+        .map(|unit| (unit.start_line, unit.end_line))
+        .collect()
+}
+
+/// References that repeat an earlier reference (same prefix, verb and rule)
+/// within the innermost code unit containing both.
+///
+/// r[impl validation.duplicate-refs]
+///
+/// Returns `(duplicate, first)` index pairs into `references`. References
+/// outside every code unit are never reported.
+fn duplicate_references(references: &[ReqReference], units: &[CodeUnit]) -> Vec<(usize, usize)> {
+    let mut first_seen: HashMap<(usize, &str, RefVerb, &RuleId), usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (idx, reference) in references.iter().enumerate() {
+        let offset = reference.span.offset;
+        let Some(unit) = units
+            .iter()
+            .enumerate()
+            .filter(|(_, u)| u.start_byte <= offset && offset < u.end_byte)
+            .min_by_key(|(_, u)| u.end_byte - u.start_byte)
+            .map(|(unit_idx, _)| unit_idx)
+        else {
+            continue;
+        };
+        let key = (
+            unit,
+            reference.prefix.as_str(),
+            reference.verb,
+            &reference.req_id,
+        );
+        match first_seen.get(&key) {
+            Some(&first) => duplicates.push((idx, first)),
+            None => {
+                first_seen.insert(key, idx);
+            }
+        }
+    }
+    duplicates
+}
+
+/// Diagnostic message for a reference repeated within a code unit.
+pub(crate) fn duplicate_reference_message(
+    duplicate: &ReqReference,
+    first: &ReqReference,
+) -> String {
+    format!(
+        "Duplicate {} reference to '{}' in this code unit (first on line {})",
+        duplicate.verb, duplicate.req_id, first.line
+    )
+}
+
+/// Byte range to delete when removing the reference at `offset..offset + length`.
+///
+/// If only comment markers would be left on the line, the whole line goes;
+/// otherwise just the reference and the whitespace before it.
+pub(crate) fn reference_removal_range(
+    content: &str,
+    offset: usize,
+    length: usize,
+) -> (usize, usize) {
+    let end = (offset + length).min(content.len());
+    let line_start = content[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = content[end..]
+        .find('\n')
+        .map(|i| end + i + 1)
+        .unwrap_or(content.len());
+    let rest = format!(
+        "{}{}",
+        &content[line_start..offset],
+        &content[end..line_end]
+    );
+    if rest
+        .trim()
+        .chars()
+        .all(|c| matches!(c, '/' | '*' | '!' | '#' | '-' | ';' | '%'))
+    {
+        return (line_start, line_end);
+    }
+    let before = content[line_start..offset].trim_end_matches([' ', '\t']);
+    (line_start + before.len(), end)
 }
 
 #[derive(Default)]
 pub struct BuildCache {
     source_files: HashMap<PathBuf, CachedSourceFile>,
+    /// Files that looked binary, with the length and mtime they had then
+    binary_files: HashMap<PathBuf, (u64, Option<u128>)>,
     impl_scan_paths: HashMap<ImplScanKey, CachedScanPaths>,
     spec_scan_paths: HashMap<SpecScanKey, CachedScanPaths>,
     markdown_files: HashMap<PathBuf, CachedMarkdownFile>,
+    /// Re-fetch remote specs on the next build even if their cache is fresh
+    force_refetch: bool,
+}
+
+impl BuildCache {
+    /// Make the next build re-fetch remote specs, ignoring their refresh interval.
+    pub fn request_refetch(&mut self) {
+        self.force_refetch = true;
+    }
 }
 
 #[derive(Clone)]
 struct CachedSourceFile {
-    content_hash: u64,
+    content_hash: blake3::Hash,
     file_len: u64,
     modified_nanos: Option<u128>,
     content: String,
@@ -121,12 +277,14 @@ struct ImplScanKey {
     project_root: PathBuf,
     include: Vec<String>,
     exclude: Vec<String>,
+    follow_links: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct SpecScanKey {
     project_root: PathBuf,
     include: Vec<String>,
+    follow_links: bool,
 }
 
 #[derive(Default, Clone)]
@@ -136,7 +294,7 @@ struct CachedScanPaths {
 
 #[derive(Clone)]
 struct CachedMarkdownFile {
-    content_hash: u64,
+    content_hash: blake3::Hash,
     file_len: u64,
     modified_nanos: Option<u128>,
     extracted_rules: Vec<crate::ExtractedRule>,
@@ -168,6 +326,7 @@ struct RuleCoverage {
     status: &'static str, // "covered", "partial", "stale", "uncovered"
     impl_refs: Vec<ApiCodeRef>,
     verify_refs: Vec<ApiCodeRef>,
+    permalink: Option<String>,
 }
 
 /// Custom rule handler that renders rules with coverage status and refs
@@ -431,17 +590,29 @@ impl ReqHandler for TraceyRuleHandler {
                 source_file
             );
 
+            // r[impl dashboard.links.permalink]
+            let permalink_html = coverage
+                .and_then(|c| c.permalink.as_deref())
+                .map(|url| {
+                    format!(
+                        r#"<a class="req-badge req-permalink" href="{}" target="_blank" rel="noopener" title="Open the canonical definition">Source</a>"#,
+                        url.replace('&', "&amp;").replace('"', "&quot;")
+                    )
+                })
+                .unwrap_or_default();
+
             // Render the opening of the req container
             Ok(format!(
                 r#"<div class="req-container req-{status}" id="{anchor}" data-br="{br_start}-{br_end}">
 <div class="req-badges-left">{badges}</div>
-<div class="req-badges-right">{edit_badge}</div>
+<div class="req-badges-right">{permalink}{edit_badge}</div>
 <div class="req-content">"#,
                 status = status,
                 anchor = rule.anchor_id,
                 br_start = rule.span.offset,
                 br_end = rule.span.offset + rule.span.length,
                 badges = badges_html,
+                permalink = permalink_html,
                 edit_badge = edit_badge_html,
             ))
         })
@@ -467,8 +638,11 @@ impl ReqHandler for TraceyRuleHandler {
 fn get_git_status(project_root: &Path) -> HashMap<String, GitStatus> {
     let mut status_map = HashMap::new();
 
-    // Run git status --porcelain to get file statuses
+    // Run git status --porcelain to get file statuses. `--no-optional-locks`
+    // keeps git from refreshing the index, which the daemon's watcher would
+    // otherwise pick up as a git operation.
     let output = match std::process::Command::new("git")
+        .arg("--no-optional-locks")
         .arg("status")
         .arg("--porcelain")
         .current_dir(project_root)
@@ -570,8 +744,36 @@ fn file_modified_nanos(modified: SystemTime) -> Option<u128> {
         .map(|d| d.as_nanos())
 }
 
-fn compute_content_hash(content: &str) -> u64 {
-    simple_hash(content)
+fn compute_content_hash(content: &str) -> blake3::Hash {
+    blake3::hash(content.as_bytes())
+}
+
+/// Feeds `std::hash::Hash` implementations into a BLAKE3 hasher, so
+/// structured data can be hashed without serializing it first.
+struct ModelHasher(blake3::Hasher);
+
+impl std::hash::Hasher for ModelHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let hash = self.0.finalize();
+        u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap())
+    }
+}
+
+/// Hash of the forward and reverse data of every impl, in key order.
+fn compute_model_hash(
+    forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>,
+    reverse_by_impl: &BTreeMap<ImplKey, ApiReverseData>,
+) -> blake3::Hash {
+    use std::hash::Hash;
+
+    let mut hasher = ModelHasher(blake3::Hasher::new());
+    forward_by_impl.hash(&mut hasher);
+    reverse_by_impl.hash(&mut hasher);
+    hasher.0.finalize()
 }
 
 fn compute_column_for_content(content: &str, byte_offset: usize) -> usize {
@@ -595,9 +797,14 @@ fn extract_marker_prefix_from_content(
     Some(prefix.to_string())
 }
 
+/// Load and parse a source file, from the cache when it hasn't changed.
+///
+/// Files on disk that exceed `limits` fail with an `InvalidData` error
+/// wrapping the [`SkipReason`](crate::file_limits::SkipReason).
 async fn get_cached_source_file(
     path: &Path,
     overlay: &FileOverlay,
+    limits: &ScanLimits,
     cache: &mut BuildCache,
     stats: &mut CacheStats,
 ) -> std::io::Result<CachedSourceFile> {
@@ -638,6 +845,12 @@ async fn get_cached_source_file(
     let file_len = metadata.len();
     let modified_nanos = metadata.modified().ok().and_then(file_modified_nanos);
 
+    // r[impl config.file-limits]
+    if let Some(reason) = limits.check_len(file_len) {
+        cache.source_files.remove(&canonical);
+        return Err(skipped_file_error(reason));
+    }
+
     if let Some(entry) = cache.source_files.get(&canonical)
         && entry.file_len == file_len
         && entry.modified_nanos == modified_nanos
@@ -645,8 +858,24 @@ async fn get_cached_source_file(
         stats.metadata_hits += 1;
         return Ok(entry.clone());
     }
+    if limits.skips_binary()
+        && cache.binary_files.get(&canonical) == Some(&(file_len, modified_nanos))
+    {
+        stats.metadata_hits += 1;
+        return Err(skipped_file_error(SkipReason::Binary));
+    }
 
-    let content = read_file_with_overlay(&canonical, overlay).await?;
+    let bytes = tokio::fs::read(&canonical).await?;
+    if let Some(reason) = limits.check_content(&bytes) {
+        cache.source_files.remove(&canonical);
+        cache
+            .binary_files
+            .insert(canonical, (file_len, modified_nanos));
+        return Err(skipped_file_error(reason));
+    }
+    cache.binary_files.remove(&canonical);
+    let content = String::from_utf8(bytes)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
     let content_hash = compute_content_hash(&content);
 
     if let Some(entry) = cache.source_files.get(&canonical)
@@ -677,10 +906,21 @@ async fn get_cached_source_file(
     Ok(parsed)
 }
 
+fn skipped_file_error(reason: SkipReason) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, reason)
+}
+
+/// The reason a scanner error skipped its file, if it did.
+fn skip_reason(err: &std::io::Error) -> Option<SkipReason> {
+    err.get_ref()?.downcast_ref::<SkipReason>().copied()
+}
+
 #[derive(Clone)]
 struct ScanRootPattern {
     root: PathBuf,
     matcher: globset::GlobMatcher,
+    /// Pattern was written as `!pattern`: matching files are carved out again
+    negated: bool,
 }
 
 /// Split a glob pattern into (directory_prefix, glob_suffix).
@@ -712,11 +952,17 @@ fn build_scan_roots(
             matcher: globset::Glob::new("**/*")
                 .expect("valid glob")
                 .compile_matcher(),
+            negated: false,
         });
         return (roots, warnings);
     }
 
-    for pattern in include {
+    for raw_pattern in include {
+        // r[impl config.impl.include.negation]
+        let (pattern, negated) = match raw_pattern.strip_prefix('!') {
+            Some(rest) => (rest, true),
+            None => (raw_pattern.as_str(), false),
+        };
         let (base_path, glob_suffix) = split_glob_prefix(pattern);
 
         let resolved_root = if base_path.is_empty() {
@@ -726,6 +972,10 @@ fn build_scan_roots(
         };
 
         if !base_path.is_empty() && !resolved_root.exists() {
+            if negated {
+                // Carving out a path that doesn't exist is harmless.
+                continue;
+            }
             warnings.push(format!(
                 "Warning: Path not found: {}\n  Pattern: {}",
                 resolved_root.display(),
@@ -746,7 +996,7 @@ fn build_scan_roots(
             Err(e) => {
                 warnings.push(format!(
                     "Warning: Invalid glob pattern '{}': {}",
-                    pattern, e
+                    raw_pattern, e
                 ));
                 continue;
             }
@@ -755,6 +1005,7 @@ fn build_scan_roots(
         roots.push(ScanRootPattern {
             root: resolved_root,
             matcher,
+            negated,
         });
     }
 
@@ -768,12 +1019,19 @@ fn path_matches_root_pattern(path: &Path, root_pattern: &ScanRootPattern) -> boo
     root_pattern.matcher.is_match(relative)
 }
 
+/// Check whether `path` is selected by an include list. Like `.gitignore`,
+/// the last matching pattern wins, so `!pattern` entries carve files out of
+/// earlier patterns and later patterns can add them back.
 fn path_matches_any_root(path: &Path, roots: &[ScanRootPattern]) -> bool {
-    roots.iter().any(|r| path_matches_root_pattern(path, r))
+    roots
+        .iter()
+        .rev()
+        .find(|r| path_matches_root_pattern(path, r))
+        .is_some_and(|r| !r.negated)
 }
 
 fn path_matches_excludes(path: &Path, roots: &[ScanRootPattern], exclude: &[String]) -> bool {
-    roots.iter().any(|r| {
+    roots.iter().filter(|r| !r.negated).any(|r| {
         let Ok(relative) = path.strip_prefix(&r.root) else {
             return false;
         };
@@ -785,21 +1043,64 @@ fn path_matches_excludes(path: &Path, roots: &[ScanRootPattern], exclude: &[Stri
     })
 }
 
+/// Check whether `path` lies in a nested project: a directory below one of
+/// the scan roots that has its own tracey config.
+///
+/// r[impl config.discovery.nested]
+fn path_in_nested_project(path: &Path, roots: &[ScanRootPattern]) -> bool {
+    roots.iter().filter(|r| !r.negated).any(|r| {
+        path.starts_with(&r.root)
+            && path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| *dir != r.root)
+                .any(|dir| dir.join(crate::CONFIG_PATH).is_file())
+    })
+}
+
+/// Report a symlink loop hit by a walk; other walk errors stay silent.
+///
+/// r[impl walk.symlinks]
+fn warn_on_symlink_loop(err: &ignore::Error) {
+    if let Some((link, target)) = tracey_core::symlink_loop(err) {
+        warn!(
+            "Skipping symlink loop: {} points to {}",
+            link.display(),
+            target.display()
+        );
+    }
+}
+
 fn full_walk_for_roots(
     roots: &[ScanRootPattern],
+    follow_links: bool,
     include_supported_ext_only: bool,
     include_markdown_only: bool,
     exclude: &[String],
 ) -> BTreeSet<PathBuf> {
     let mut out = BTreeSet::new();
-    for root_pattern in roots {
+    for root_pattern in roots.iter().filter(|r| !r.negated) {
+        // Nested projects own their subtree; don't descend into them.
         let walker = ignore::WalkBuilder::new(&root_pattern.root)
-            .follow_links(true)
+            .follow_links(follow_links)
             .hidden(false)
             .git_ignore(true)
+            .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_some_and(|ft| ft.is_dir())
+                    || !entry.path().join(crate::CONFIG_PATH).is_file()
+            })
             .build();
 
-        for entry in walker.flatten() {
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    warn_on_symlink_loop(&err);
+                    continue;
+                }
+            };
             let path = entry.path();
             let Some(ft) = entry.file_type() else {
                 continue;
@@ -817,22 +1118,35 @@ fn full_walk_for_roots(
             {
                 continue;
             }
-            if !path_matches_root_pattern(path, root_pattern) {
+            if !path_matches_root_pattern(path, root_pattern) || !path_matches_any_root(path, roots)
+            {
                 continue;
             }
             if path_matches_excludes(path, roots, exclude) {
                 continue;
             }
-            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-            out.insert(canonical);
+            // Links to the same file collapse into one entry.
+            out.insert(tracey_core::canonical_path(path));
         }
     }
     out
 }
 
+/// Whether `path` is reached through a symlink below one of the scan roots.
+fn path_through_symlink(path: &Path, roots: &[ScanRootPattern]) -> bool {
+    roots.iter().filter(|r| !r.negated).any(|r| {
+        path.starts_with(&r.root)
+            && path
+                .ancestors()
+                .take_while(|dir| *dir != r.root)
+                .any(Path::is_symlink)
+    })
+}
+
 fn update_cached_scan_paths(
     existing: &mut CachedScanPaths,
     roots: &[ScanRootPattern],
+    follow_links: bool,
     changed_files: &[PathBuf],
     include_supported_ext_only: bool,
     include_markdown_only: bool,
@@ -849,10 +1163,10 @@ fn update_cached_scan_paths(
         };
         let included = ext_ok
             && path_matches_any_root(changed, roots)
-            && !path_matches_excludes(changed, roots, exclude);
-        let canonical = changed
-            .canonicalize()
-            .unwrap_or_else(|_| changed.to_path_buf());
+            && !path_matches_excludes(changed, roots, exclude)
+            && !path_in_nested_project(changed, roots)
+            && (follow_links || !path_through_symlink(changed, roots));
+        let canonical = tracey_core::canonical_path(changed);
 
         if exists && included {
             existing.files.insert(canonical);
@@ -866,6 +1180,7 @@ fn get_cached_impl_scan_paths(
     project_root: &Path,
     include: &[String],
     exclude: &[String],
+    follow_links: bool,
     changed_files: &[PathBuf],
     cache: &mut BuildCache,
 ) -> (BTreeSet<PathBuf>, Vec<String>, bool) {
@@ -873,18 +1188,27 @@ fn get_cached_impl_scan_paths(
         project_root: project_root.to_path_buf(),
         include: include.to_vec(),
         exclude: exclude.to_vec(),
+        follow_links,
     };
     let (roots, warnings) = build_scan_roots(project_root, include);
     let entry = cache.impl_scan_paths.entry(key).or_default();
     let did_full_walk;
     if entry.files.is_empty() {
-        entry.files = full_walk_for_roots(&roots, true, false, exclude);
+        entry.files = full_walk_for_roots(&roots, follow_links, true, false, exclude);
         did_full_walk = true;
     } else if !changed_files.is_empty() {
-        update_cached_scan_paths(entry, &roots, changed_files, true, false, exclude);
+        update_cached_scan_paths(
+            entry,
+            &roots,
+            follow_links,
+            changed_files,
+            true,
+            false,
+            exclude,
+        );
         did_full_walk = false;
     } else {
-        entry.files = full_walk_for_roots(&roots, true, false, exclude);
+        entry.files = full_walk_for_roots(&roots, follow_links, true, false, exclude);
         did_full_walk = true;
     }
     (entry.files.clone(), warnings, did_full_walk)
@@ -893,24 +1217,26 @@ fn get_cached_impl_scan_paths(
 fn get_cached_spec_scan_paths(
     project_root: &Path,
     include: &[String],
+    follow_links: bool,
     changed_files: &[PathBuf],
     cache: &mut BuildCache,
 ) -> (BTreeSet<PathBuf>, Vec<String>, bool) {
     let key = SpecScanKey {
         project_root: project_root.to_path_buf(),
         include: include.to_vec(),
+        follow_links,
     };
     let (roots, warnings) = build_scan_roots(project_root, include);
     let entry = cache.spec_scan_paths.entry(key).or_default();
     let did_full_walk;
     if entry.files.is_empty() {
-        entry.files = full_walk_for_roots(&roots, false, true, &[]);
+        entry.files = full_walk_for_roots(&roots, follow_links, false, true, &[]);
         did_full_walk = true;
     } else if !changed_files.is_empty() {
-        update_cached_scan_paths(entry, &roots, changed_files, false, true, &[]);
+        update_cached_scan_paths(entry, &roots, follow_links, changed_files, false, true, &[]);
         did_full_walk = false;
     } else {
-        entry.files = full_walk_for_roots(&roots, false, true, &[]);
+        entry.files = full_walk_for_roots(&roots, follow_links, false, true, &[]);
         did_full_walk = true;
     }
     (entry.files.clone(), warnings, did_full_walk)
@@ -975,9 +1301,13 @@ async fn extract_markdown_rules_cached(
         compute_relative_path(project_root, &canonical)
     };
 
-    let doc = render(&content, &RenderOptions::default())
-        .await
-        .map_err(|e| eyre::eyre!("Failed to process {}: {}", canonical.display(), e))?;
+    let redirects = crate::redirects::parse_redirects(&content);
+    let doc = render(
+        &crate::redirects::strip_redirects(&content),
+        &RenderOptions::default(),
+    )
+    .await
+    .map_err(|e| eyre::eyre!("Failed to process {}: {}", canonical.display(), e))?;
 
     if !quiet && !doc.reqs.is_empty() {
         eprintln!(
@@ -1019,6 +1349,7 @@ async fn extract_markdown_rules_cached(
             let (section, section_title) = rule_sections
                 .remove(&req.id.to_string())
                 .unwrap_or((None, None));
+            let renamed_from = crate::redirects::renamed_from(&redirects, &req.id.to_string());
             extracted.push(crate::ExtractedRule {
                 def: req,
                 source_file: relative_display.clone(),
@@ -1026,6 +1357,7 @@ async fn extract_markdown_rules_cached(
                 column,
                 section,
                 section_title,
+                renamed_from,
             });
         }
     }
@@ -1044,17 +1376,24 @@ async fn extract_markdown_rules_cached(
     Ok(extracted)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn load_rules_from_includes_cached(
     project_root: &Path,
     include_patterns: &[String],
+    follow_links: bool,
     overlay: &FileOverlay,
     cache: &mut BuildCache,
     quiet: bool,
     changed_files: &[PathBuf],
     stats: &mut CacheStats,
 ) -> Result<(Vec<crate::ExtractedRule>, bool)> {
-    let (mut spec_paths, _warnings, did_full_walk) =
-        get_cached_spec_scan_paths(project_root, include_patterns, changed_files, cache);
+    let (mut spec_paths, _warnings, did_full_walk) = get_cached_spec_scan_paths(
+        project_root,
+        include_patterns,
+        follow_links,
+        changed_files,
+        cache,
+    );
     let (spec_roots, _) = build_scan_roots(project_root, include_patterns);
     for overlay_path in overlay.keys() {
         if overlay_path.extension().is_none_or(|ext| ext != "md") {
@@ -1066,31 +1405,42 @@ async fn load_rules_from_includes_cached(
     }
 
     let mut all_rules = Vec::new();
-    let mut seen_ids: BTreeSet<String> = BTreeSet::new();
+    let mut first_file_by_id: HashMap<String, String> = HashMap::new();
     for path in spec_paths {
         let extracted =
             extract_markdown_rules_cached(project_root, &path, overlay, cache, quiet, stats)
                 .await?;
         for rule in extracted {
             let id = rule.def.id.to_string();
-            if seen_ids.contains(&id) {
-                eyre::bail!(
-                    "Duplicate requirement '{}' found in {}",
-                    rule.def.id.red(),
-                    rule.source_file
-                );
+            match first_file_by_id.get(&id) {
+                Some(first_file) if *first_file == rule.source_file => {
+                    eyre::bail!(
+                        "Duplicate requirement '{}' found in {}",
+                        rule.def.id.red(),
+                        rule.source_file
+                    );
+                }
+                // r[impl validation.duplicates.locations]
+                // Keep both definitions so validation can report both locations
+                // instead of one silently shadowing the other.
+                Some(_) => {}
+                None => {
+                    first_file_by_id.insert(id, rule.source_file.clone());
+                }
             }
-            seen_ids.insert(id);
             all_rules.push(rule);
         }
     }
     Ok((all_rules, did_full_walk))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn scan_impl_files(
     project_root: &Path,
     include: &[String],
     exclude: &[String],
+    follow_links: bool,
+    limits: &ScanLimits,
     overlay: &FileOverlay,
     cache: &mut BuildCache,
     changed_files: &[PathBuf],
@@ -1102,10 +1452,17 @@ async fn scan_impl_files(
     BTreeMap<PathBuf, Vec<CodeUnit>>,
     BTreeMap<PathBuf, String>,
     BTreeMap<PathBuf, Reqs>,
+    BTreeMap<PathBuf, SkipReason>,
     bool,
 ) {
-    let (mut files, warnings, did_full_walk) =
-        get_cached_impl_scan_paths(project_root, include, exclude, changed_files, cache);
+    let (mut files, warnings, did_full_walk) = get_cached_impl_scan_paths(
+        project_root,
+        include,
+        exclude,
+        follow_links,
+        changed_files,
+        cache,
+    );
     let (impl_roots, _) = build_scan_roots(project_root, include);
     for overlay_path in overlay.keys() {
         if overlay_path
@@ -1125,22 +1482,30 @@ async fn scan_impl_files(
     let mut code_units_by_file: BTreeMap<PathBuf, Vec<CodeUnit>> = BTreeMap::new();
     let mut file_contents: BTreeMap<PathBuf, String> = BTreeMap::new();
     let mut reqs_by_file: BTreeMap<PathBuf, Reqs> = BTreeMap::new();
+    let mut skipped_files: BTreeMap<PathBuf, SkipReason> = BTreeMap::new();
     for path in files {
-        if let Ok(parsed) = get_cached_source_file(&path, overlay, cache, stats).await {
-            reqs_by_file.insert(
-                path.clone(),
-                Reqs {
-                    references: parsed.refs.clone(),
-                    warnings: parsed.parse_warnings.clone(),
-                },
-            );
-            refs.extend(parsed.refs);
-            parse_warnings.extend(parsed.parse_warnings);
-            if !parsed.code_units.is_empty() {
-                code_units_by_file.insert(path.clone(), parsed.code_units);
+        let parsed = match get_cached_source_file(&path, overlay, limits, cache, stats).await {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                if let Some(reason) = skip_reason(&err) {
+                    skipped_files.insert(path, reason);
+                }
+                continue;
             }
-            file_contents.insert(path, parsed.content);
+        };
+        reqs_by_file.insert(
+            path.clone(),
+            Reqs {
+                references: parsed.refs.clone(),
+                warnings: parsed.parse_warnings.clone(),
+            },
+        );
+        refs.extend(parsed.refs);
+        parse_warnings.extend(parsed.parse_warnings);
+        if !parsed.code_units.is_empty() {
+            code_units_by_file.insert(path.clone(), parsed.code_units);
         }
+        file_contents.insert(path, parsed.content);
     }
     (
         refs,
@@ -1149,6 +1514,7 @@ async fn scan_impl_files(
         code_units_by_file,
         file_contents,
         reqs_by_file,
+        skipped_files,
         did_full_walk,
     )
 }
@@ -1200,6 +1566,34 @@ fn classify_reference_against_known_rules(
     }
 }
 
+/// Old base IDs of renamed rules (`r[new.id <- old.id]`), mapped to the
+/// rule's current ID. Old IDs that a rule is still defined under are left out.
+pub(crate) fn rename_targets<'a>(
+    rules: impl IntoIterator<Item = &'a ApiRule>,
+) -> HashMap<String, RuleId> {
+    let rules: Vec<&ApiRule> = rules.into_iter().collect();
+    let defined: std::collections::HashSet<&str> =
+        rules.iter().map(|rule| rule.id.base.as_str()).collect();
+    let mut targets = HashMap::new();
+    for rule in &rules {
+        for old_id in &rule.renamed_from {
+            if !defined.contains(old_id.base.as_str()) {
+                targets
+                    .entry(old_id.base.clone())
+                    .or_insert_with(|| rule.id.clone());
+            }
+        }
+    }
+    targets
+}
+
+pub(crate) fn renamed_reference_message(reference_id: &RuleId, current_id: &RuleId) -> String {
+    format!(
+        "'{}' was renamed to '{}' - update the reference",
+        reference_id, current_id
+    )
+}
+
 fn stale_diagnostic_message_short(
     reference_rule_id: &RuleId,
     current_rule: Option<&ApiRule>,
@@ -1351,6 +1745,121 @@ fn span_to_range(content: &str, offset: usize, length: usize) -> (u32, u32, u32,
     }
 }
 
+/// Why a rule's dependency on a rule of another spec doesn't resolve, if it doesn't.
+fn spec_dependency_error(
+    config: &ApiConfig,
+    forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>,
+    rule: &ApiRule,
+    dep: &ApiSpecDependency,
+) -> Option<ValidationError> {
+    let target = format!("{}:{}", dep.spec, dep.id);
+    let (code, message) = if !config.specs.iter().any(|s| s.name == dep.spec) {
+        (
+            ValidationErrorCode::UnknownRequirement,
+            format!(
+                "Rule '{}' depends on '{}', but no spec named '{}' is configured",
+                rule.id, target, dep.spec
+            ),
+        )
+    } else {
+        let known: Vec<RuleId> = forward_by_impl
+            .iter()
+            .filter(|((spec, _), _)| *spec == dep.spec)
+            .flat_map(|(_, forward)| forward.rules.iter().map(|r| r.id.clone()))
+            .collect();
+        match classify_reference_against_known_rules(&dep.id, &known) {
+            KnownRuleMatch::Exact => return None,
+            KnownRuleMatch::Stale(current) => (
+                ValidationErrorCode::StaleRequirement,
+                format!(
+                    "Rule '{}' depends on '{}', but spec '{}' is now at '{}'",
+                    rule.id, target, dep.spec, current
+                ),
+            ),
+            KnownRuleMatch::Missing => (
+                ValidationErrorCode::UnknownRequirement,
+                format!(
+                    "Rule '{}' depends on '{}', but spec '{}' has no rule '{}'",
+                    rule.id, target, dep.spec, dep.id
+                ),
+            ),
+        }
+    };
+    Some(ValidationError {
+        code,
+        message,
+        file: rule.source_file.clone(),
+        line: rule.source_line,
+        column: rule.source_column,
+        related_rules: vec![rule.id.clone()],
+        reference_rule_id: Some(dep.id.clone()),
+        reference_text: None,
+    })
+}
+
+/// The error for a rule refining a rule of its own spec that doesn't exist
+/// or is now at a newer version, if any.
+fn refinement_error(known: &[RuleId], rule: &ApiRule, target: &RuleId) -> Option<ValidationError> {
+    let (code, message) = match classify_reference_against_known_rules(target, known) {
+        KnownRuleMatch::Exact => return None,
+        KnownRuleMatch::Stale(current) => (
+            ValidationErrorCode::StaleRequirement,
+            format!(
+                "Rule '{}' refines '{}', which is now at '{}'",
+                rule.id, target, current
+            ),
+        ),
+        KnownRuleMatch::Missing => (
+            ValidationErrorCode::UnknownRequirement,
+            format!("Rule '{}' refines unknown rule '{}'", rule.id, target),
+        ),
+    };
+    Some(ValidationError {
+        code,
+        message,
+        file: rule.source_file.clone(),
+        line: rule.source_line,
+        column: rule.source_column,
+        related_rules: vec![rule.id.clone()],
+        reference_rule_id: Some(target.clone()),
+        reference_text: None,
+    })
+}
+
+/// List the files an impl's scan skipped as validation warnings.
+///
+/// r[impl config.file-limits]
+fn add_skipped_file_warnings(
+    abs_root: &Path,
+    skipped_files_by_impl: &BTreeMap<ImplKey, BTreeMap<PathBuf, SkipReason>>,
+    validation_by_impl: &mut BTreeMap<ImplKey, ValidationResult>,
+) {
+    for (impl_key, skipped) in skipped_files_by_impl {
+        let Some(result) = validation_by_impl.get_mut(impl_key) else {
+            continue;
+        };
+        for (path, reason) in skipped {
+            result.errors.push(ValidationError {
+                code: ValidationErrorCode::SkippedFile,
+                message: format!("Not scanned: {reason}"),
+                file: Some(
+                    path.strip_prefix(abs_root)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string(),
+                ),
+                line: None,
+                column: None,
+                related_rules: vec![],
+                reference_rule_id: None,
+                reference_text: None,
+            });
+        }
+        result.warning_count += skipped.len();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn compute_validation_by_impl(
     abs_root: &Path,
     config: &ApiConfig,
@@ -1358,6 +1867,10 @@ fn compute_validation_by_impl(
     reverse_by_impl: &BTreeMap<ImplKey, ApiReverseData>,
     source_reqs_by_file: &BTreeMap<PathBuf, Reqs>,
     test_files: &std::collections::HashSet<PathBuf>,
+    test_regions: &BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    duplicate_refs_by_file: &BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    removed_rules: &[crate::bump::RemovedRule],
+    version_scheme: VersionScheme,
 ) -> BTreeMap<ImplKey, ValidationResult> {
     let mut out = BTreeMap::new();
     let known_prefixes: std::collections::HashSet<&str> =
@@ -1373,20 +1886,24 @@ fn compute_validation_by_impl(
             .iter()
             .map(|rule| (rule.id.clone(), rule))
             .collect();
+        let renames = rename_targets(&forward_data.rules);
 
         let mut seen_ids: HashMap<RuleId, (&Option<String>, Option<usize>)> = HashMap::new();
         let mut seen_bases: HashMap<String, (&RuleId, &Option<String>, Option<usize>)> =
             HashMap::new();
 
         for rule in &forward_data.rules {
+            // r[impl validation.duplicates.locations]
             if let Some((prev_file, prev_line)) = seen_ids.get(&rule.id) {
                 errors.push(ValidationError {
                     code: ValidationErrorCode::DuplicateRequirement,
                     message: format!(
-                        "Duplicate rule ID '{}' (first defined at {}:{})",
+                        "Duplicate rule ID '{}' defined at both {}:{} and {}:{}",
                         rule.id,
                         prev_file.as_deref().unwrap_or("?"),
-                        prev_line.unwrap_or(0)
+                        prev_line.unwrap_or(0),
+                        rule.source_file.as_deref().unwrap_or("?"),
+                        rule.source_line.unwrap_or(0)
                     ),
                     file: rule.source_file.clone(),
                     line: rule.source_line,
@@ -1402,6 +1919,10 @@ fn compute_validation_by_impl(
             if let Some((prev_rule_id, prev_file, prev_line)) =
                 seen_bases.get(rule.id.base.as_str())
             {
+                // Exact duplicates were already reported above.
+                if **prev_rule_id == rule.id {
+                    continue;
+                }
                 errors.push(ValidationError {
                     code: ValidationErrorCode::DuplicateRequirement,
                     message: format!(
@@ -1427,7 +1948,32 @@ fn compute_validation_by_impl(
             }
         }
 
+        let known_ids: Vec<RuleId> = forward_data.rules.iter().map(|r| r.id.clone()).collect();
         for rule in &forward_data.rules {
+            // r[impl validation.spec-depends]
+            for dep in &rule.depends_on {
+                errors.extend(spec_dependency_error(config, forward_by_impl, rule, dep));
+            }
+
+            // r[impl validation.refines]
+            for target in &rule.refines {
+                errors.extend(refinement_error(&known_ids, rule, target));
+            }
+
+            // r[impl config.version-scheme]
+            if let Err(reason) = version_scheme.check(rule.id.version) {
+                errors.push(ValidationError {
+                    code: ValidationErrorCode::InvalidVersion,
+                    message: format!("Rule ID '{}': {}", rule.id, reason),
+                    file: rule.source_file.clone(),
+                    line: rule.source_line,
+                    column: rule.source_column,
+                    related_rules: vec![rule.id.clone()],
+                    reference_rule_id: None,
+                    reference_text: None,
+                });
+            }
+
             if !is_valid_rule_id(&rule.id) {
                 errors.push(ValidationError {
                     code: ValidationErrorCode::InvalidNaming,
@@ -1446,7 +1992,7 @@ fn compute_validation_by_impl(
 
             for impl_ref in &rule.impl_refs {
                 let ref_path = abs_root.join(&impl_ref.file);
-                if test_files.contains(&ref_path) {
+                if is_test_context(test_files, test_regions, &ref_path, impl_ref.line) {
                     errors.push(ValidationError {
                         code: ValidationErrorCode::ImplInTestFile,
                         message: format!(
@@ -1497,13 +2043,38 @@ fn compute_validation_by_impl(
                 let canonical = file_path
                     .canonicalize()
                     .unwrap_or_else(|_| file_path.clone());
-                let Some(reqs) = source_reqs_by_file
-                    .get(&canonical)
-                    .or_else(|| source_reqs_by_file.get(&file_path))
+                let Some((reqs_path, reqs)) = source_reqs_by_file
+                    .get_key_value(&canonical)
+                    .or_else(|| source_reqs_by_file.get_key_value(&file_path))
                 else {
                     continue;
                 };
 
+                // r[impl validation.duplicate-refs]
+                for &(dup, first) in duplicate_refs_by_file.get(reqs_path).into_iter().flatten() {
+                    let reference = &reqs.references[dup];
+                    if current_spec_prefix != Some(reference.prefix.as_str()) {
+                        continue;
+                    }
+                    let reference_text = format!(
+                        "{}[{} {}]",
+                        reference.prefix, reference.verb, reference.req_id
+                    );
+                    errors.push(ValidationError {
+                        code: ValidationErrorCode::DuplicateReference,
+                        message: format!(
+                            "'{}' repeats the reference on line {} in the same code unit",
+                            reference_text, reqs.references[first].line
+                        ),
+                        file: Some(file_entry.path.clone()),
+                        line: Some(reference.line),
+                        column: None,
+                        related_rules: vec![reference.req_id.clone()],
+                        reference_rule_id: Some(reference.req_id.clone()),
+                        reference_text: Some(reference_text),
+                    });
+                }
+
                 for reference in &reqs.references {
                     if !known_prefixes.contains(reference.prefix.as_str()) {
                         errors.push(ValidationError {
@@ -1541,6 +2112,25 @@ fn compute_validation_by_impl(
                                     reference_text: None,
                                 });
                             }
+                            // r[impl validation.renamed]
+                            KnownRuleMatch::Missing
+                                if renames.contains_key(&reference.req_id.base) =>
+                            {
+                                let current_id = &renames[&reference.req_id.base];
+                                errors.push(ValidationError {
+                                    code: ValidationErrorCode::RenamedRequirement,
+                                    message: renamed_reference_message(
+                                        &reference.req_id,
+                                        current_id,
+                                    ),
+                                    file: Some(file_entry.path.clone()),
+                                    line: Some(reference.line),
+                                    column: None,
+                                    related_rules: vec![current_id.clone()],
+                                    reference_rule_id: Some(reference.req_id.clone()),
+                                    reference_text: None,
+                                });
+                            }
                             KnownRuleMatch::Missing => {
                                 match classify_reference_against_known_rules(
                                     &reference.req_id,
@@ -1548,14 +2138,32 @@ fn compute_validation_by_impl(
                                 ) {
                                     KnownRuleMatch::Exact | KnownRuleMatch::Stale(_) => {}
                                     KnownRuleMatch::Missing => {
-                                        let message = unknown_rule_message_with_context(
-                                            &reference.prefix,
-                                            &reference.verb,
-                                            &reference.req_id,
-                                            &known_rule_ids_for_prefix,
-                                        );
+                                        // r[impl validation.removed]
+                                        let removed = removed_rules.iter().find(|r| {
+                                            r.prefix == reference.prefix
+                                                && r.rule_id.base == reference.req_id.base
+                                        });
+                                        let (code, message) = match removed {
+                                            Some(removed) => (
+                                                ValidationErrorCode::RemovedRequirement,
+                                                format!(
+                                                    "Rule '{}' was removed from {} but is still referenced; mark it `status=deprecated` before removing it",
+                                                    removed.rule_id,
+                                                    removed.file.display()
+                                                ),
+                                            ),
+                                            None => (
+                                                ValidationErrorCode::UnknownRequirement,
+                                                unknown_rule_message_with_context(
+                                                    &reference.prefix,
+                                                    &reference.verb,
+                                                    &reference.req_id,
+                                                    &known_rule_ids_for_prefix,
+                                                ),
+                                            ),
+                                        };
                                         errors.push(ValidationError {
-                                            code: ValidationErrorCode::UnknownRequirement,
+                                            code,
                                             message,
                                             file: Some(file_entry.path.clone()),
                                             line: Some(reference.line),
@@ -1596,14 +2204,15 @@ fn compute_validation_by_impl(
             });
         }
 
-        let error_count = errors.len();
+        let warning_count = errors.iter().filter(|e| e.code.is_warning()).count();
+        let error_count = errors.len() - warning_count;
         out.insert(
             impl_key.clone(),
             ValidationResult {
                 spec: spec.clone(),
                 impl_name: impl_name.clone(),
                 errors,
-                warning_count: 0,
+                warning_count,
                 error_count,
             },
         );
@@ -1619,6 +2228,8 @@ fn compute_workspace_diagnostics(
     source_reqs_by_file: &BTreeMap<PathBuf, Reqs>,
     file_contents: &BTreeMap<PathBuf, String>,
     test_files: &std::collections::HashSet<PathBuf>,
+    test_regions: &BTreeMap<PathBuf, Vec<(usize, usize)>>,
+    duplicate_refs_by_file: &BTreeMap<PathBuf, Vec<(usize, usize)>>,
 ) -> Vec<LspFileDiagnostics> {
     let known_prefixes: std::collections::HashSet<&str> =
         config.specs.iter().map(|s| s.prefix.as_str()).collect();
@@ -1643,13 +2254,13 @@ fn compute_workspace_diagnostics(
             }
         }
     }
+    let renames = rename_targets(rules_by_id.values());
 
     let mut out = Vec::new();
     for (path, reqs) in source_reqs_by_file {
         let Some(content) = file_contents.get(path) else {
             continue;
         };
-        let is_test = test_files.contains(path);
         let mut diagnostics = Vec::new();
 
         for reference in &reqs.references {
@@ -1690,6 +2301,18 @@ fn compute_workspace_diagnostics(
                         end_char,
                     });
                 }
+                KnownRuleMatch::Missing if renames.contains_key(&reference.req_id.base) => {
+                    let current_id = &renames[&reference.req_id.base];
+                    diagnostics.push(LspDiagnostic {
+                        severity: "warning".to_string(),
+                        code: "renamed".to_string(),
+                        message: renamed_reference_message(&reference.req_id, current_id),
+                        start_line,
+                        start_char,
+                        end_line,
+                        end_char,
+                    });
+                }
                 KnownRuleMatch::Missing => {
                     let message = unknown_rule_message_with_context(
                         &reference.prefix,
@@ -1709,7 +2332,9 @@ fn compute_workspace_diagnostics(
                 }
             }
 
-            if is_test && reference.verb == RefVerb::Impl {
+            if reference.verb == RefVerb::Impl
+                && is_test_context(test_files, test_regions, path, reference.line)
+            {
                 diagnostics.push(LspDiagnostic {
                     severity: "warning".to_string(),
                     code: "impl-in-test".to_string(),
@@ -1723,6 +2348,22 @@ fn compute_workspace_diagnostics(
             }
         }
 
+        // r[impl lsp.diagnostics.duplicate-ref]
+        for &(dup, first) in duplicate_refs_by_file.get(path).into_iter().flatten() {
+            let reference = &reqs.references[dup];
+            let (start_line, start_char, end_line, end_char) =
+                span_to_range(content, reference.span.offset, reference.span.length);
+            diagnostics.push(LspDiagnostic {
+                severity: "hint".to_string(),
+                code: "duplicate-reference".to_string(),
+                message: duplicate_reference_message(reference, &reqs.references[first]),
+                start_line,
+                start_char,
+                end_line,
+                end_char,
+            });
+        }
+
         for warning in &reqs.warnings {
             let (start_line, start_char, end_line, end_char) =
                 span_to_range(content, warning.span.offset, warning.span.length);
@@ -1762,6 +2403,7 @@ fn compute_workspace_diagnostics(
     out
 }
 
+#[allow(clippy::too_many_arguments)]
 fn compute_impl_output(
     abs_root: &Path,
     _spec_name: &str,
@@ -1770,45 +2412,65 @@ fn compute_impl_output(
     extracted_rules: &[crate::ExtractedRule],
     refs: Vec<ReqReference>,
     impl_code_units: BTreeMap<PathBuf, Vec<CodeUnit>>,
+    counted_units: BTreeMap<PathBuf, Vec<CodeUnit>>,
+    granularity: Granularity,
 ) -> ImplComputedOutput {
     let impl_start = Instant::now();
     let forward_start = Instant::now();
-    struct IndexedRef {
+    // References borrow their rule ID from `refs`, and all references in a
+    // file share one relative path, resolved once per file rather than once
+    // per reference.
+    struct IndexedRef<'a> {
         verb: RefVerb,
-        req_id: RuleId,
-        code_ref: ApiCodeRef,
-        relative_file: String,
+        req_id: &'a RuleId,
+        relative_file: Arc<str>,
         line: usize,
     }
+    impl IndexedRef<'_> {
+        fn code_ref(&self) -> ApiCodeRef {
+            ApiCodeRef {
+                file: self.relative_file.to_string(),
+                line: self.line,
+            }
+        }
+    }
+    let mut relative_files: HashMap<&Path, Arc<str>> = HashMap::new();
     let mut indexed_refs: Vec<IndexedRef> = Vec::new();
-    let mut refs_by_base: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut refs_by_base: HashMap<&str, Vec<usize>> = HashMap::new();
     for r in &refs {
         if r.prefix != inferred_prefix {
             continue;
         }
-        let canonical_ref = r.file.canonicalize().unwrap_or_else(|_| r.file.clone());
-        let relative_display = if let Ok(rel) = canonical_ref.strip_prefix(abs_root) {
-            rel.display().to_string()
-        } else {
-            compute_relative_path(abs_root, &canonical_ref)
-        };
+        let relative_file = relative_files
+            .entry(r.file.as_path())
+            .or_insert_with(|| {
+                let canonical_ref = r.file.canonicalize().unwrap_or_else(|_| r.file.clone());
+                let relative_display = if let Ok(rel) = canonical_ref.strip_prefix(abs_root) {
+                    rel.display().to_string()
+                } else {
+                    compute_relative_path(abs_root, &canonical_ref)
+                };
+                relative_display.into()
+            })
+            .clone();
         let idx = indexed_refs.len();
         indexed_refs.push(IndexedRef {
             verb: r.verb,
-            req_id: r.req_id.clone(),
-            code_ref: ApiCodeRef {
-                file: relative_display.clone(),
-                line: r.line,
-            },
-            relative_file: relative_display,
+            req_id: &r.req_id,
+            relative_file,
             line: r.line,
         });
         refs_by_base
-            .entry(r.req_id.base.clone())
+            .entry(r.req_id.base.as_str())
             .or_default()
             .push(idx);
     }
 
+    let defined_bases: std::collections::HashSet<String> = extracted_rules
+        .iter()
+        .filter_map(|extracted| parse_rule_id(&extracted.def.id.to_string()))
+        .map(|id| id.base)
+        .collect();
     let mut api_rules = Vec::new();
     for extracted in extracted_rules {
         let Some(rule_id) = parse_rule_id(&extracted.def.id.to_string()) else {
@@ -1820,32 +2482,30 @@ fn compute_impl_output(
         let mut stale_refs = Vec::new();
 
         let candidate_idxs = refs_by_base
-            .get(&rule_id.base)
+            .get(rule_id.base.as_str())
             .map(Vec::as_slice)
             .unwrap_or(&[]);
         for idx in candidate_idxs {
             let entry = &indexed_refs[*idx];
-            match classify_reference_for_rule(&rule_id, &entry.req_id) {
+            match classify_reference_for_rule(&rule_id, entry.req_id) {
                 RuleIdMatch::Exact => match entry.verb {
-                    RefVerb::Impl | RefVerb::Define => impl_refs.push(entry.code_ref.clone()),
-                    RefVerb::Verify => verify_refs.push(entry.code_ref.clone()),
-                    RefVerb::Depends | RefVerb::Related => {
-                        depends_refs.push(entry.code_ref.clone())
-                    }
+                    RefVerb::Impl | RefVerb::Define => impl_refs.push(entry.code_ref()),
+                    RefVerb::Verify => verify_refs.push(entry.code_ref()),
+                    RefVerb::Depends | RefVerb::Related => depends_refs.push(entry.code_ref()),
                 },
                 RuleIdMatch::Stale => match entry.verb {
                     RefVerb::Impl | RefVerb::Define => {
-                        impl_refs.push(entry.code_ref.clone());
+                        impl_refs.push(entry.code_ref());
                         stale_refs.push(ApiStaleRef {
-                            file: entry.relative_file.clone(),
+                            file: entry.relative_file.to_string(),
                             line: entry.line,
                             reference_id: entry.req_id.clone(),
                         });
                     }
                     RefVerb::Verify => {
-                        verify_refs.push(entry.code_ref.clone());
+                        verify_refs.push(entry.code_ref());
                         stale_refs.push(ApiStaleRef {
-                            file: entry.relative_file.clone(),
+                            file: entry.relative_file.to_string(),
                             line: entry.line,
                             reference_id: entry.req_id.clone(),
                         });
@@ -1856,6 +2516,23 @@ fn compute_impl_output(
             }
         }
 
+        // r[impl markdown.syntax.redirect.resolve]
+        // References to an old ID count as references to the renamed rule,
+        // unless some rule is still defined under that ID.
+        for old_id in &extracted.renamed_from {
+            if defined_bases.contains(old_id.base.as_str()) {
+                continue;
+            }
+            for idx in refs_by_base.get(old_id.base.as_str()).into_iter().flatten() {
+                let entry = &indexed_refs[*idx];
+                match entry.verb {
+                    RefVerb::Impl | RefVerb::Define => impl_refs.push(entry.code_ref()),
+                    RefVerb::Verify => verify_refs.push(entry.code_ref()),
+                    RefVerb::Depends | RefVerb::Related => depends_refs.push(entry.code_ref()),
+                }
+            }
+        }
+
         api_rules.push(ApiRule {
             id: rule_id,
             raw: extracted.def.raw.clone(),
@@ -1866,16 +2543,25 @@ fn compute_impl_output(
                 .status
                 .map(|s| s.as_str().to_string()),
             level: extracted.def.metadata.level.map(|l| l.as_str().to_string()),
+            tags: extracted.def.metadata.tags.clone(),
             source_file: Some(extracted.source_file.clone()),
             source_line: Some(extracted.def.line),
             source_column: extracted.column,
+            permalink: None,
             section: extracted.section.clone(),
             section_title: extracted.section_title.clone(),
+            renamed_from: extracted.renamed_from.clone(),
+            depends_on: crate::spec_deps::parse_spec_dependencies(&extracted.def.raw),
+            refines: crate::refinement::parse_refinements(&extracted.def.raw),
             impl_refs,
             verify_refs,
             depends_refs,
             is_stale: !stale_refs.is_empty(),
             stale_refs,
+            owners: Vec::new(),
+            approvals: Vec::new(),
+            test_results: Vec::new(),
+            line_coverage: Vec::new(),
         });
     }
     api_rules.sort_by(|a, b| a.id.cmp(&b.id));
@@ -1884,6 +2570,10 @@ fn compute_impl_output(
         .map(|r| search::RuleEntry {
             id: r.id.to_string(),
             raw: r.raw.clone(),
+            level: r.level.clone(),
+            status: r.status.clone(),
+            tags: r.tags.clone(),
+            section: r.section.clone(),
         })
         .collect::<Vec<_>>();
     let forward_elapsed_ms = forward_start.elapsed().as_millis();
@@ -1898,8 +2588,10 @@ fn compute_impl_output(
         } else {
             compute_relative_path(abs_root, path)
         };
-        let file_total = units.len();
-        let file_covered = units.iter().filter(|u| !u.req_refs.is_empty()).count();
+        // r[impl config.impl.skip-units]
+        let units = counted_units.get(path).unwrap_or(units);
+        // r[impl config.impl.code-units]
+        let (file_total, file_covered) = granularity.count(units);
         total_units += file_total;
         covered_units += file_covered;
         file_entries.push(ApiFileEntry {
@@ -1988,6 +2680,10 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     let mut api_config = ApiConfig {
         project_root: abs_root.display().to_string(),
         specs: Vec::new(),
+        default_excludes: crate::config::DEFAULT_EXCLUDES
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
     };
 
     let mut forward_by_impl: BTreeMap<ImplKey, ApiSpecForward> = BTreeMap::new();
@@ -1998,12 +2694,21 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     let mut spec_includes_by_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
     let mut all_file_contents: BTreeMap<PathBuf, String> = BTreeMap::new();
     let mut all_source_reqs_by_file: BTreeMap<PathBuf, Reqs> = BTreeMap::new();
+    let mut skipped_files_by_impl: BTreeMap<ImplKey, BTreeMap<PathBuf, SkipReason>> =
+        BTreeMap::new();
     let mut all_search_rules: Vec<search::RuleEntry> = Vec::new();
     let mut total_extracted_rules = 0usize;
     let mut total_source_refs = 0usize;
     let mut total_code_files = 0usize;
     let mut total_code_units = 0usize;
     let total_impls: usize = config.specs.iter().map(|s| s.impls.len()).sum();
+    let owners = crate::owners::Owners::new(&config.owners)?;
+    let approvals = crate::approvals::Approvals::load(project_root)?;
+    let test_results = crate::test_report::TestResults::load(project_root)?;
+    let line_coverage = crate::line_coverage::LineCoverage::load(project_root)?;
+    let version_scheme = crate::config::version_scheme(config)?;
+    let follow_links = crate::config::follow_links(config);
+    let scan_limits = ScanLimits::new(config.file_limits.as_ref());
 
     info!(
         "dashboard build start version={} specs={} impls={} overlay_files={}",
@@ -2019,19 +2724,23 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     let mut test_files: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
     for spec_config in &config.specs {
         for impl_config in &spec_config.impls {
-            let test_patterns: Vec<&str> = impl_config
-                .test_include
-                .iter()
-                .map(|t| t.as_str())
-                .collect();
+            let test_patterns = crate::config::effective_test_include(impl_config);
             if !test_patterns.is_empty() {
                 // Walk files and match against test patterns
                 let walker = ignore::WalkBuilder::new(project_root)
-                    .follow_links(true)
+                    .follow_links(follow_links)
                     .hidden(false)
                     .git_ignore(true)
+                    .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
                     .build();
-                for entry in walker.flatten() {
+                for entry in walker {
+                    let entry = match entry {
+                        Ok(entry) => entry,
+                        Err(err) => {
+                            warn_on_symlink_loop(&err);
+                            continue;
+                        }
+                    };
                     let Some(ft) = entry.file_type() else {
                         continue;
                     };
@@ -2042,7 +2751,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                                 if let Ok(glob) = globset::Glob::new(pattern)
                                     && glob.compile_matcher().is_match(relative)
                                 {
-                                    test_files.insert(path.to_path_buf());
+                                    test_files.insert(tracey_core::canonical_path(path));
                                     break;
                                 }
                             }
@@ -2061,14 +2770,47 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     for spec_config in &config.specs {
         let spec_start = Instant::now();
         let spec_name = &spec_config.name;
-        let include_patterns: Vec<String> = spec_config.include.to_vec();
+        let permalinks = crate::permalink::Permalinks::new(spec_config);
+        let mut include_patterns: Vec<String> = spec_config.include.to_vec();
+        let mut remote_copies = Vec::new();
+
+        // r[impl config.spec.rules-url]
+        // Remote specs are cached under the state dir and scanned from there.
+        if let Some(url) = &spec_config.rules_url {
+            let refresh =
+                crate::remote::refresh_interval_for(spec_config.rules_url_refresh.as_deref())
+                    .map_err(|e| eyre::eyre!("Spec '{}': {}", spec_name, e))?;
+            let copy =
+                crate::remote::ensure_rules_url(project_root, url, refresh, cache.force_refetch)
+                    .await
+                    .map_err(|e| eyre::eyre!("Spec '{}': {}", spec_name, e))?;
+            remote_copies.push(copy);
+        }
+
+        // r[impl config.spec.source]
+        if let Some(source) = &spec_config.source {
+            let copy = crate::remote::ensure_git_source(project_root, source, cache.force_refetch)
+                .await
+                .map_err(|e| eyre::eyre!("Spec '{}': {}", spec_name, e))?;
+            remote_copies.push(copy);
+        }
+
+        for copy in &remote_copies {
+            include_patterns.push(copy.include_pattern());
+            if let Err(e) =
+                crate::remote::update_lock(project_root, spec_name, copy, cache.force_refetch).await
+            {
+                warn!("Failed to update {}: {}", crate::remote::LOCKFILE, e);
+            }
+        }
 
         if let Some(prefix) = &spec_config.prefix {
             // r[impl config.spec.prefix+2]
             return Err(eyre::eyre!(
                 "Spec '{}' uses deprecated `prefix {}` in config.\n\n\
-                 Remove the `prefix` field from this spec config. Tracey now infers prefixes \
-                 directly from requirement markers in spec files (for example `r[...]`).",
+                 Remove the `prefix` field from this spec config (or run `tracey config migrate`). \
+                 Tracey now infers prefixes directly from requirement markers in spec files \
+                 (for example `r[...]`).",
                 spec_name,
                 prefix
             ));
@@ -2103,6 +2845,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         let (extracted_rules, spec_walk_full_scan) = load_rules_from_includes_cached(
             project_root,
             &include_patterns,
+            follow_links,
             overlay,
             cache,
             quiet,
@@ -2110,6 +2853,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             &mut cache_stats,
         )
         .await?;
+        // Shared by every impl's compute task instead of copied into each.
+        let extracted_rules: Arc<[crate::ExtractedRule]> = extracted_rules.into();
         total_extracted_rules += extracted_rules.len();
 
         let unique_prefixes: BTreeSet<String> =
@@ -2142,12 +2887,53 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             spec_start.elapsed().as_millis()
         );
 
+        let source = spec_config
+            .include
+            .iter()
+            .chain(spec_config.rules_url.iter())
+            .chain(spec_config.source.iter().map(|source| &source.git))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let variants: Vec<ApiImplVariant> = spec_config
+            .impls
+            .iter()
+            .flat_map(|i| {
+                i.variants.iter().map(|v| ApiImplVariant {
+                    impl_name: i.name.clone(),
+                    name: v.name.clone(),
+                    include: v.include.clone(),
+                })
+            })
+            .collect();
+        for impl_config in &spec_config.impls {
+            crate::variants::Variants::new(&variants, &impl_config.name)?;
+        }
         api_config.specs.push(ApiSpecInfo {
             name: spec_name.clone(),
             prefix: inferred_prefix.clone(),
-            source: Some(include_patterns.join(", ")),
+            source: Some(source),
             source_url: spec_config.source_url.clone(),
             implementations: spec_config.impls.iter().map(|i| i.name.clone()).collect(),
+            policies: Vec::new(),
+            no_default_excludes: spec_config
+                .impls
+                .iter()
+                .filter(|i| i.no_default_excludes)
+                .map(|i| i.name.clone())
+                .collect(),
+            skipped_units: spec_config
+                .impls
+                .iter()
+                .filter_map(|i| {
+                    let rules = SkippedUnits::new(i.skip_units.as_ref()).rules();
+                    (!rules.is_empty()).then(|| ApiSkippedUnits {
+                        impl_name: i.name.clone(),
+                        rules,
+                    })
+                })
+                .collect(),
+            variants,
         });
         spec_includes_by_name.insert(spec_name.clone(), include_patterns.clone());
 
@@ -2162,6 +2948,12 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         let mut impl_compute_tasks = Vec::new();
         let mut impl_compute_meta = Vec::new();
 
+        let known_bases: Vec<String> = extracted_rules
+            .iter()
+            .filter_map(|extracted| parse_rule_id(&extracted.def.id.to_string()))
+            .map(|id| id.base)
+            .collect();
+        let known_bases: Vec<&str> = known_bases.iter().map(String::as_str).collect();
         for impl_config in &spec_config.impls {
             let scan_start = Instant::now();
             let impl_name = impl_config.name.clone();
@@ -2173,7 +2965,9 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             } else {
                 impl_config.include.to_vec()
             };
-            let exclude: Vec<String> = impl_config.exclude.to_vec();
+            let exclude = crate::config::effective_excludes(impl_config);
+            let granularity = crate::config::code_unit_granularity(impl_config)?;
+            let skipped_units = SkippedUnits::new(impl_config.skip_units.as_ref());
             let impl_key: ImplKey = (spec_name.clone(), impl_name.clone());
             let (
                 mut refs,
@@ -2182,11 +2976,14 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 mut impl_code_units,
                 mut impl_file_contents,
                 mut impl_source_reqs_by_file,
+                mut impl_skipped_files,
                 mut impl_walk_full_scan,
             ) = scan_impl_files(
                 project_root,
                 &include,
                 &exclude,
+                follow_links,
+                &scan_limits,
                 overlay,
                 cache,
                 changed_files,
@@ -2195,8 +2992,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             .await;
 
             // r[impl config.impl.test_include.extraction]
-            if !impl_config.test_include.is_empty() {
-                let test_include: Vec<String> = impl_config.test_include.to_vec();
+            let test_include = crate::config::effective_test_include(impl_config);
+            if !test_include.is_empty() {
                 let (
                     test_refs,
                     test_parse_warnings,
@@ -2204,19 +3001,29 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                     test_code_units,
                     test_file_contents,
                     test_source_reqs_by_file,
+                    test_skipped_files,
                     test_walk_full_scan,
                 ) = scan_impl_files(
                     project_root,
                     &test_include,
                     &exclude,
+                    follow_links,
+                    &scan_limits,
                     overlay,
                     cache,
                     changed_files,
                     &mut cache_stats,
                 )
                 .await;
-                refs.extend(test_refs);
-                parse_warnings.extend(test_parse_warnings);
+                // Files matched by both include and test patterns were
+                // already scanned; don't count their references twice.
+                let scanned: BTreeSet<PathBuf> = impl_file_contents.keys().cloned().collect();
+                refs.extend(test_refs.into_iter().filter(|r| !scanned.contains(&r.file)));
+                parse_warnings.extend(
+                    test_parse_warnings
+                        .into_iter()
+                        .filter(|w| !scanned.contains(&w.file)),
+                );
                 for w in test_scan_warnings {
                     if !quiet {
                         eprintln!("{}", w.yellow());
@@ -2227,6 +3034,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 for (path, reqs) in test_source_reqs_by_file {
                     impl_source_reqs_by_file.entry(path).or_insert(reqs);
                 }
+                impl_skipped_files.extend(test_skipped_files);
                 impl_walk_full_scan = impl_walk_full_scan || test_walk_full_scan;
             }
 
@@ -2238,7 +3046,43 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                     eprintln!("{}", warning.yellow());
                 }
             }
+            if !impl_skipped_files.is_empty() {
+                if !quiet {
+                    eprintln!(
+                        "{}",
+                        format!(
+                            "Warning: Skipped {} large or binary file(s) in {}",
+                            impl_skipped_files.len(),
+                            impl_name
+                        )
+                        .yellow()
+                    );
+                }
+                skipped_files_by_impl.insert(impl_key.clone(), impl_skipped_files);
+            }
             total_source_refs += refs.len();
+            // r[impl ref.scope]
+            for (path, units) in impl_code_units.iter_mut() {
+                if let Some(content) = impl_file_contents.get(path) {
+                    crate::scopes::apply_scopes(&inferred_prefix, content, units, &known_bases);
+                }
+            }
+            // r[impl config.impl.skip-units]
+            let counted_units: BTreeMap<PathBuf, Vec<CodeUnit>> = if skipped_units.is_empty() {
+                BTreeMap::new()
+            } else {
+                impl_code_units
+                    .iter()
+                    .filter_map(|(path, units)| {
+                        let content = impl_file_contents.get(path)?;
+                        let is_test_file = test_files.contains(path);
+                        Some((
+                            path.clone(),
+                            skipped_units.counted(path, content, units, is_test_file),
+                        ))
+                    })
+                    .collect()
+            };
             for (path, content) in impl_file_contents {
                 all_file_contents.insert(path, content);
             }
@@ -2257,7 +3101,7 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
             let abs_root_cloned = abs_root.clone();
             let spec_name_cloned = spec_name.clone();
             let inferred_prefix_cloned = inferred_prefix.clone();
-            let extracted_rules_cloned = extracted_rules.clone();
+            let extracted_rules_cloned = Arc::clone(&extracted_rules);
             let impl_name_cloned = impl_name.clone();
             impl_compute_tasks.push(tokio::task::spawn_blocking(move || {
                 compute_impl_output(
@@ -2268,6 +3112,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                     &extracted_rules_cloned,
                     refs,
                     impl_code_units,
+                    counted_units,
+                    granularity,
                 )
             }));
             impl_compute_meta.push(ImplComputeTaskMeta {
@@ -2314,16 +3160,43 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
                 out.reverse_elapsed_ms
             );
 
+            let mut api_rules = out.api_rules;
+            for rule in &mut api_rules {
+                rule.owners = owners.owners_of(&rule.id.base).to_vec();
+                if let Some(permalinks) = &permalinks
+                    && let (Some(file), Some(line)) = (&rule.source_file, rule.source_line)
+                {
+                    rule.permalink = permalinks.link(file, line, &rule.id.to_string());
+                }
+                rule.approvals = approvals.of(&rule.id, &rule.raw);
+                if let Some(results) = &test_results {
+                    rule.test_results = results.results_for(rule, &abs_root, &out.impl_code_units);
+                }
+                if let Some(coverage) = &line_coverage {
+                    rule.line_coverage =
+                        coverage.coverage_for(rule, &abs_root, &out.impl_code_units);
+                }
+            }
             forward_by_impl.insert(
                 meta.impl_key.clone(),
                 ApiSpecForward {
                     name: spec_name.clone(),
-                    rules: out.api_rules,
+                    rules: api_rules,
                 },
             );
             reverse_by_impl.insert(meta.impl_key.clone(), out.reverse_data);
             code_units_by_impl.insert(meta.impl_key, out.impl_code_units);
         }
+        // r[impl config.policy]
+        let policies = crate::policy::check_spec(spec_config, |impl_name| {
+            forward_by_impl
+                .get(&(spec_name.clone(), impl_name.to_string()))
+                .map(|forward| forward.rules.as_slice())
+        });
+        if let Some(spec_info) = api_config.specs.iter_mut().find(|s| &s.name == spec_name) {
+            spec_info.policies = policies;
+        }
+
         info!(
             "dashboard build spec done spec={} impls={} elapsed_ms={}",
             spec_name,
@@ -2332,6 +3205,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         );
     }
 
+    cache.force_refetch = false;
+
     // Deduplicate search rules by ID
     all_search_rules.sort_by(|a, b| a.id.cmp(&b.id));
     all_search_rules.dedup_by(|a, b| a.id == b.id);
@@ -2344,24 +3219,76 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
     );
 
     // Compute content hash for change detection (hash all forward/reverse data)
-    let mut content_hash: u64 = 0;
-    for (key, forward) in &forward_by_impl {
-        let json = facet_json::to_string(forward).unwrap_or_default();
-        content_hash ^= simple_hash(&format!("{:?}:{}", key, json));
+    let content_hash = compute_model_hash(&forward_by_impl, &reverse_by_impl);
+
+    // r[impl config.impl.test-conventions]
+    let mut test_regions: BTreeMap<PathBuf, Vec<(usize, usize)>> = BTreeMap::new();
+    for spec_config in &config.specs {
+        for impl_config in spec_config.impls.iter().filter(|i| i.test_conventions) {
+            let impl_key = (spec_config.name.clone(), impl_config.name.clone());
+            let Some(units_by_file) = code_units_by_impl.get(&impl_key) else {
+                continue;
+            };
+            for (path, units) in units_by_file {
+                if path.extension().is_none_or(|ext| ext != "rs") {
+                    continue;
+                }
+                let Some(content) = all_file_contents.get(path) else {
+                    continue;
+                };
+                let regions = cfg_test_module_regions(content, units);
+                if !regions.is_empty() {
+                    test_regions.insert(path.clone(), regions);
+                }
+            }
+        }
     }
-    for (key, reverse) in &reverse_by_impl {
-        let json = facet_json::to_string(reverse).unwrap_or_default();
-        content_hash ^= simple_hash(&format!("{:?}:{}", key, json));
+
+    let mut duplicate_refs_by_file: BTreeMap<PathBuf, Vec<(usize, usize)>> = BTreeMap::new();
+    for units_by_file in code_units_by_impl.values() {
+        for (path, units) in units_by_file {
+            if duplicate_refs_by_file.contains_key(path) {
+                continue;
+            }
+            let Some(reqs) = all_source_reqs_by_file.get(path) else {
+                continue;
+            };
+            let duplicates = duplicate_references(&reqs.references, units);
+            if !duplicates.is_empty() {
+                duplicate_refs_by_file.insert(path.clone(), duplicates);
+            }
+        }
     }
 
-    let validation_by_impl = compute_validation_by_impl(
+    // Rules the working tree's specs dropped since HEAD while code still
+    // refers to them. Outside a git repository there are none.
+    let removed_rules = crate::bump::detect_removed_rules_in(
+        project_root,
+        config,
+        crate::bump::index_base(project_root),
+        crate::bump::Target::WorkTree,
+    )
+    .await
+    .unwrap_or_default();
+    let mut validation_by_impl = compute_validation_by_impl(
         &abs_root,
         &api_config,
         &forward_by_impl,
         &reverse_by_impl,
         &all_source_reqs_by_file,
         &test_files,
+        &test_regions,
+        &duplicate_refs_by_file,
+        &removed_rules,
+        version_scheme,
     );
+    crate::plugins::run_validation_plugins(
+        &abs_root,
+        &config.plugins,
+        &forward_by_impl,
+        &mut validation_by_impl,
+    );
+    add_skipped_file_warnings(&abs_root, &skipped_files_by_impl, &mut validation_by_impl);
     let workspace_diagnostics = compute_workspace_diagnostics(
         &abs_root,
         &api_config,
@@ -2369,6 +3296,8 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         &all_source_reqs_by_file,
         &all_file_contents,
         &test_files,
+        &test_regions,
+        &duplicate_refs_by_file,
     );
 
     let elapsed = build_start.elapsed();
@@ -2400,27 +3329,32 @@ pub async fn build_dashboard_data_with_overlay_and_cache(
         source_reqs_by_file: all_source_reqs_by_file,
         search_rules: all_search_rules,
         validation_by_impl,
+        skipped_files_by_impl,
         workspace_diagnostics,
         version,
         content_hash,
         delta: crate::server::Delta::default(),
+        changed_files: changed_files
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&abs_root)
+                    .or_else(|_| path.strip_prefix(project_root))
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect(),
         test_files,
+        test_regions,
+        duplicate_refs_by_file,
     })
 }
 
 /// Simple FNV-1a hash for change detection
-fn simple_hash(s: &str) -> u64 {
-    let mut hash: u64 = 0xcbf29ce484222325;
-    for byte in s.bytes() {
-        hash ^= byte as u64;
-        hash = hash.wrapping_mul(0x100000001b3);
-    }
-    hash
-}
-
 async fn load_spec_content(
     root: &Path,
     patterns: &[&str],
+    follow_links: bool,
     spec_name: &str,
     impl_name: &str,
     coverage: &BTreeMap<String, RuleCoverage>,
@@ -2458,28 +3392,68 @@ async fn load_spec_content(
     // Collect all matching files with their content and weight
     let mut files: Vec<(String, String, i32)> = Vec::new(); // (relative_path, content, weight)
 
-    let walker = WalkBuilder::new(root)
-        .follow_links(true)
+    // Absolute patterns (e.g. cached remote specs) live outside the project
+    // root, so walk their base directories too.
+    let mut walk_roots = vec![root.to_path_buf()];
+    for pattern in patterns {
+        if Path::new(pattern).is_absolute() {
+            let (base, _) = split_glob_prefix(pattern);
+            walk_roots.push(PathBuf::from(base));
+        }
+    }
+
+    let mut walk_builder = WalkBuilder::new(&walk_roots[0]);
+    for extra_root in &walk_roots[1..] {
+        walk_builder.add(extra_root);
+    }
+    let walker = walk_builder
+        .follow_links(follow_links)
         .hidden(false)
         .git_ignore(true)
+        .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
         .build();
 
-    for entry in walker.flatten() {
+    let mut seen = BTreeSet::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn_on_symlink_loop(&err);
+                continue;
+            }
+        };
         let path = entry.path();
 
         if path.extension().is_none_or(|ext| ext != "md") {
             continue;
         }
-
         let relative = path.strip_prefix(root).unwrap_or(path);
 
-        // Check if path matches any of the patterns
-        let matches_any = patterns.iter().any(|p| {
-            globset::Glob::new(p)
-                .map(|g| g.compile_matcher().is_match(relative))
-                .unwrap_or(false)
-        });
-        if !matches_any {
+        // The last matching pattern decides, so `!pattern` removes files
+        // r[impl config.impl.include.negation]
+        let included = patterns
+            .iter()
+            .rev()
+            .find_map(|p| {
+                let (p, negated) = match p.strip_prefix('!') {
+                    Some(p) => (p, true),
+                    None => (*p, false),
+                };
+                let candidate = if Path::new(p).is_absolute() {
+                    path
+                } else {
+                    relative
+                };
+                globset::Glob::new(p)
+                    .is_ok_and(|g| g.compile_matcher().is_match(candidate))
+                    .then_some(!negated)
+            })
+            .unwrap_or(false);
+        if !included {
+            continue;
+        }
+        // A spec file linked into several places renders once.
+        if !seen.insert(tracey_core::canonical_path(path)) {
             continue;
         }
 
@@ -2507,7 +3481,7 @@ async fn load_spec_content(
         if i == 0 {
             first_source_file = source_file.clone();
         }
-        combined_markdown.push_str(content);
+        combined_markdown.push_str(&crate::redirects::strip_redirects(content));
         combined_markdown.push_str("\n\n"); // Ensure separation between files
     }
 
@@ -2554,6 +3528,7 @@ async fn load_spec_content(
 pub async fn render_spec_content_for_impl(
     project_root: &Path,
     include_patterns: &[String],
+    follow_links: bool,
     spec_name: &str,
     impl_name: &str,
     forward: &ApiSpecForward,
@@ -2579,6 +3554,7 @@ pub async fn render_spec_content_for_impl(
                 status,
                 impl_refs: rule.impl_refs.clone(),
                 verify_refs: rule.verify_refs.clone(),
+                permalink: rule.permalink.clone(),
             },
         );
     }
@@ -2588,6 +3564,7 @@ pub async fn render_spec_content_for_impl(
     load_spec_content(
         project_root,
         &include_pattern_refs,
+        follow_links,
         spec_name,
         impl_name,
         &coverage,
@@ -2599,6 +3576,52 @@ pub async fn render_spec_content_for_impl(
         .ok_or_else(|| eyre::eyre!("Spec content not found for {spec_name}/{impl_name}"))
 }
 
+/// Nest a flat outline into a section tree and attach each rule to the
+/// heading it is defined under, in document order. Returns the sections and
+/// the rules defined before the first heading.
+///
+/// r[impl daemon.spec-outline]
+pub fn section_tree(
+    outline: &[OutlineEntry],
+    rules: &[ApiRule],
+) -> (Vec<OutlineSection>, Vec<RuleId>) {
+    let mut sections: Vec<OutlineSection> = Vec::with_capacity(outline.len());
+    let mut by_slug: HashMap<&str, usize> = HashMap::new();
+    let mut stack: Vec<usize> = Vec::new();
+    for entry in outline {
+        while stack
+            .last()
+            .is_some_and(|&parent| sections[parent].level >= entry.level)
+        {
+            stack.pop();
+        }
+        by_slug.insert(entry.slug.as_str(), sections.len());
+        stack.push(sections.len());
+        sections.push(OutlineSection {
+            title: entry.title.clone(),
+            slug: entry.slug.clone(),
+            level: entry.level,
+            parent: stack.len().checked_sub(2).map(|i| stack[i]),
+            rules: Vec::new(),
+            coverage: entry.coverage.clone(),
+            aggregated: entry.aggregated.clone(),
+        });
+    }
+
+    let mut ordered: Vec<&ApiRule> = rules.iter().collect();
+    ordered.sort_by(|a, b| {
+        (&a.source_file, a.source_line, &a.id).cmp(&(&b.source_file, b.source_line, &b.id))
+    });
+    let mut unsectioned = Vec::new();
+    for rule in ordered {
+        match rule.section.as_deref().and_then(|slug| by_slug.get(slug)) {
+            Some(&idx) => sections[idx].rules.push(rule.id.clone()),
+            None => unsectioned.push(rule.id.clone()),
+        }
+    }
+    (sections, unsectioned)
+}
+
 /// Build an outline with coverage info from document elements.
 /// Returns a flat list of outline entries with both direct and aggregated coverage.
 fn build_outline(
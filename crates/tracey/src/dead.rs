@@ -0,0 +1,120 @@
+//! Dead-rule detection: requirements that nobody has implemented or verified
+//! for a long time.
+//!
+//! r[impl daemon.cli.query.dead]
+//!
+//! A rule's age is taken from `git blame` on the line of its definition, so a
+//! rule that has been unreferenced since it was written shows up once it is
+//! older than the threshold. Rules without git history (uncommitted files,
+//! specs outside the repository) are counted but not listed.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracey_api::ApiRule;
+use tracey_proto::DeadRule;
+
+/// Threshold used when `tracey query dead` is run without `--days`.
+pub const DEFAULT_DEAD_DAYS: u32 = 90;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Map each line of `file` (1-indexed) to the author time of the commit that
+/// last touched it. Uncommitted lines are left out.
+pub fn blame_times(project_root: &Path, file: &Path) -> eyre::Result<HashMap<usize, i64>> {
+    Ok(crate::blame::blame_file(project_root, file)?
+        .into_iter()
+        .map(|(line, blame)| (line, blame.author_time))
+        .collect())
+}
+
+#[cfg(test)]
+fn parse_line_porcelain(output: &str) -> HashMap<usize, i64> {
+    crate::blame::parse_line_porcelain(output)
+        .into_iter()
+        .map(|(line, blame)| (line, blame.author_time))
+        .collect()
+}
+
+/// Find rules without any references whose definition is older than
+/// `min_days` as of `now` (Unix seconds).
+///
+/// Returns the dead rules, oldest first, and the number of unreferenced rules
+/// whose age could not be determined.
+pub fn find_dead_rules(
+    project_root: &Path,
+    rules: &[ApiRule],
+    min_days: u32,
+    now: i64,
+) -> (Vec<DeadRule>, usize) {
+    let mut blame_by_file: HashMap<&str, Option<HashMap<usize, i64>>> = HashMap::new();
+    let mut dead = Vec::new();
+    let mut unknown_age = 0usize;
+
+    for rule in rules {
+        // A stale reference still means someone is tracking the rule.
+        if !rule.impl_refs.is_empty() || !rule.verify_refs.is_empty() || !rule.stale_refs.is_empty()
+        {
+            continue;
+        }
+        let defined_at = match (rule.source_file.as_deref(), rule.source_line) {
+            (Some(file), Some(line)) => blame_by_file
+                .entry(file)
+                .or_insert_with(|| blame_times(project_root, &project_root.join(file)).ok())
+                .as_ref()
+                .and_then(|times| times.get(&line).copied()),
+            _ => None,
+        };
+        let Some(defined_at) = defined_at else {
+            unknown_age += 1;
+            continue;
+        };
+
+        let age_days = ((now - defined_at).max(0) / SECS_PER_DAY) as u32;
+        if age_days >= min_days {
+            dead.push(DeadRule {
+                id: rule.id.clone(),
+                source_file: rule.source_file.clone(),
+                source_line: rule.source_line,
+                age_days,
+            });
+        }
+    }
+
+    dead.sort_by(|a, b| b.age_days.cmp(&a.age_days).then_with(|| a.id.cmp(&b.id)));
+    (dead, unknown_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.cli.query.dead]
+    #[test]
+    fn parses_line_porcelain() {
+        let output = "\
+1111111111111111111111111111111111111111 1 1 2
+author Someone
+author-time 1700000000
+author-tz +0000
+summary Add spec
+filename docs/spec.md
+\t# Spec
+1111111111111111111111111111111111111111 2 2
+author Someone
+author-time 1700000000
+author-tz +0000
+filename docs/spec.md
+\t
+0000000000000000000000000000000000000000 3 3 1
+author Not Committed Yet
+author-time 1800000000
+filename docs/spec.md
+\tr[new.rule]
+";
+        let times = parse_line_porcelain(output);
+        assert_eq!(times.get(&1), Some(&1_700_000_000));
+        assert_eq!(times.get(&2), Some(&1_700_000_000));
+        assert_eq!(times.get(&3), None, "uncommitted lines have no age");
+    }
+}
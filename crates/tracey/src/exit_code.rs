@@ -0,0 +1,19 @@
+//! Process exit codes shared by `tracey query`, `check` and `pre-commit`.
+//!
+//! r[impl cli.exit-codes]
+//!
+//! Scripts branch on these, so the values are part of tracey's stable
+//! interface and never change meaning between versions.
+
+/// The command ran and found nothing to complain about.
+pub const OK: i32 = 0;
+
+/// The command ran and found what it checks for: a failed policy, a
+/// changed-but-unbumped rule, or a query over its `--fail-*` threshold.
+pub const FINDINGS: i32 = 1;
+
+/// The config file can't be read, doesn't parse, or fails validation.
+pub const CONFIG_ERROR: i32 = 2;
+
+/// The daemon couldn't be started or stopped answering.
+pub const DAEMON_UNREACHABLE: i32 = 3;
@@ -0,0 +1,404 @@
+//! Full-model dumps for `tracey export`.
+//!
+//! r[impl cli.export]
+//!
+//! The export flattens everything tracey knows about a project — rules with
+//! their text and source location, every reference, every code unit, and the
+//! coverage numbers — into an [`ExportModel`], which is written either as a
+//! single JSON document or as a SQLite database for downstream analytics.
+//!
+//! r[impl cli.export.schema]
+//!
+//! Both formats carry [`EXPORT_SCHEMA_VERSION`]. It is bumped whenever a
+//! field or column is renamed or removed, or its meaning changes; adding
+//! fields doesn't bump it.
+
+use std::path::Path;
+
+#[cfg(feature = "sqlite")]
+use eyre::{Result, WrapErr};
+use facet::Facet;
+
+use crate::data::DashboardData;
+use crate::server::CoverageStats;
+
+/// Version of the export layout, stored in the JSON `schemaVersion` field
+/// and in the SQLite `meta` table.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportModel {
+    pub schema_version: u32,
+    /// Version of tracey that wrote the export
+    pub tracey_version: String,
+    pub project_root: String,
+    pub specs: Vec<ExportSpec>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportSpec {
+    pub name: String,
+    pub rules: Vec<ExportRule>,
+    pub impls: Vec<ExportImpl>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportRule {
+    /// Base rule ID, without the version suffix
+    pub id: String,
+    pub version: u32,
+    /// Raw markdown text of the rule
+    pub text: String,
+    #[facet(default)]
+    pub status: Option<String>,
+    #[facet(default)]
+    pub level: Option<String>,
+    #[facet(default)]
+    pub section: Option<String>,
+    #[facet(default)]
+    pub source_file: Option<String>,
+    #[facet(default)]
+    pub source_line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportImpl {
+    pub name: String,
+    pub coverage: ExportCoverage,
+    pub references: Vec<ExportReference>,
+    pub code_units: Vec<ExportCodeUnit>,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportCoverage {
+    pub total_rules: usize,
+    /// Rules with at least one current `impl` reference
+    pub implemented_rules: usize,
+    pub verified_rules: usize,
+    /// Rules with at least one reference to an older version
+    pub stale_rules: usize,
+    pub total_units: usize,
+    /// Code units that reference at least one rule
+    pub covered_units: usize,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportReference {
+    /// Base ID of the referenced rule
+    pub rule_id: String,
+    /// Rule version named by the reference
+    pub rule_version: u32,
+    /// `impl`, `verify`, `depends`, or `stale` for a reference to an older version
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ExportCodeUnit {
+    pub file: String,
+    pub kind: String,
+    #[facet(default)]
+    pub name: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Rule IDs (with version suffix) referenced by the unit
+    pub rule_ids: Vec<String>,
+}
+
+impl ExportModel {
+    /// Flatten a built snapshot into the export layout.
+    pub fn from_data(data: &DashboardData) -> Self {
+        let project_root = Path::new(&data.config.project_root);
+        let relative = |path: &Path| {
+            path.strip_prefix(project_root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+
+        let specs = data
+            .config
+            .specs
+            .iter()
+            .map(|spec| {
+                // Rule text is the same for every impl, so take it from the first one.
+                let rules = spec
+                    .implementations
+                    .iter()
+                    .find_map(|impl_name| {
+                        data.forward_by_impl
+                            .get(&(spec.name.clone(), impl_name.clone()))
+                    })
+                    .map(|forward| forward.rules.iter().map(export_rule).collect())
+                    .unwrap_or_default();
+
+                let impls = spec
+                    .implementations
+                    .iter()
+                    .map(|impl_name| {
+                        let key = (spec.name.clone(), impl_name.clone());
+                        let rules = data
+                            .forward_by_impl
+                            .get(&key)
+                            .map(|forward| forward.rules.as_slice())
+                            .unwrap_or_default();
+                        let code_units: Vec<ExportCodeUnit> = data
+                            .code_units_by_impl
+                            .get(&key)
+                            .into_iter()
+                            .flat_map(|files| files.iter())
+                            .flat_map(|(path, units)| {
+                                let file = relative(path);
+                                units.iter().map(move |unit| ExportCodeUnit {
+                                    file: file.clone(),
+                                    kind: unit.kind.as_str().to_string(),
+                                    name: unit.name.clone(),
+                                    start_line: unit.start_line,
+                                    end_line: unit.end_line,
+                                    rule_ids: unit
+                                        .req_refs
+                                        .iter()
+                                        .map(|id| id.to_string())
+                                        .collect(),
+                                })
+                            })
+                            .collect();
+
+                        let stats = CoverageStats::from_rules(rules);
+                        ExportImpl {
+                            name: impl_name.clone(),
+                            coverage: ExportCoverage {
+                                total_rules: stats.total_rules,
+                                implemented_rules: stats.impl_covered,
+                                verified_rules: stats.verify_covered,
+                                stale_rules: stats.stale_covered,
+                                total_units: code_units.len(),
+                                covered_units: code_units
+                                    .iter()
+                                    .filter(|unit| !unit.rule_ids.is_empty())
+                                    .count(),
+                            },
+                            references: rules.iter().flat_map(export_references).collect(),
+                            code_units,
+                        }
+                    })
+                    .collect();
+
+                ExportSpec {
+                    name: spec.name.clone(),
+                    rules,
+                    impls,
+                }
+            })
+            .collect();
+
+        Self {
+            schema_version: EXPORT_SCHEMA_VERSION,
+            tracey_version: env!("CARGO_PKG_VERSION").to_string(),
+            project_root: data.config.project_root.clone(),
+            specs,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        facet_json::to_string_pretty(self).expect("JSON serialization failed")
+    }
+
+    /// Write the model to a fresh SQLite database at `path`, replacing any
+    /// existing file.
+    #[cfg(feature = "sqlite")]
+    pub fn write_sqlite(&self, path: &Path) -> Result<()> {
+        use rusqlite::params;
+
+        if path.exists() {
+            std::fs::remove_file(path)
+                .wrap_err_with(|| format!("Failed to replace {}", path.display()))?;
+        }
+        let mut conn = rusqlite::Connection::open(path)
+            .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+        let tx = conn.transaction()?;
+        tx.execute_batch(SQLITE_SCHEMA)?;
+
+        {
+            let mut meta = tx.prepare("INSERT INTO meta (key, value) VALUES (?1, ?2)")?;
+            meta.execute(params!["schema_version", self.schema_version.to_string()])?;
+            meta.execute(params!["tracey_version", self.tracey_version])?;
+            meta.execute(params!["project_root", self.project_root])?;
+
+            let mut rule = tx.prepare(
+                "INSERT INTO rules (spec, id, version, text, status, level, section, \
+                 source_file, source_line) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            let mut imp = tx.prepare(
+                "INSERT INTO impls (spec, impl, total_rules, implemented_rules, verified_rules, \
+                 stale_rules, total_units, covered_units) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            let mut reference = tx.prepare(
+                "INSERT INTO refs (spec, impl, rule_id, rule_version, kind, file, line) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            let mut unit = tx.prepare(
+                "INSERT INTO code_units (spec, impl, file, kind, name, start_line, end_line) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            let mut unit_rule =
+                tx.prepare("INSERT INTO code_unit_rules (unit_id, rule_id) VALUES (?1, ?2)")?;
+
+            for spec in &self.specs {
+                for r in &spec.rules {
+                    rule.execute(params![
+                        spec.name,
+                        r.id,
+                        r.version,
+                        r.text,
+                        r.status,
+                        r.level,
+                        r.section,
+                        r.source_file,
+                        r.source_line,
+                    ])?;
+                }
+                for i in &spec.impls {
+                    let c = &i.coverage;
+                    imp.execute(params![
+                        spec.name,
+                        i.name,
+                        c.total_rules,
+                        c.implemented_rules,
+                        c.verified_rules,
+                        c.stale_rules,
+                        c.total_units,
+                        c.covered_units,
+                    ])?;
+                    for r in &i.references {
+                        reference.execute(params![
+                            spec.name,
+                            i.name,
+                            r.rule_id,
+                            r.rule_version,
+                            r.kind,
+                            r.file,
+                            r.line,
+                        ])?;
+                    }
+                    for u in &i.code_units {
+                        unit.execute(params![
+                            spec.name,
+                            i.name,
+                            u.file,
+                            u.kind,
+                            u.name,
+                            u.start_line,
+                            u.end_line,
+                        ])?;
+                        let unit_id = tx.last_insert_rowid();
+                        for rule_id in &u.rule_ids {
+                            unit_rule.execute(params![unit_id, rule_id])?;
+                        }
+                    }
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Table layout of the SQLite export (schema version 1).
+#[cfg(feature = "sqlite")]
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE rules (
+    spec TEXT NOT NULL,
+    id TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    status TEXT,
+    level TEXT,
+    section TEXT,
+    source_file TEXT,
+    source_line INTEGER,
+    PRIMARY KEY (spec, id)
+);
+CREATE TABLE impls (
+    spec TEXT NOT NULL,
+    impl TEXT NOT NULL,
+    total_rules INTEGER NOT NULL,
+    implemented_rules INTEGER NOT NULL,
+    verified_rules INTEGER NOT NULL,
+    stale_rules INTEGER NOT NULL,
+    total_units INTEGER NOT NULL,
+    covered_units INTEGER NOT NULL,
+    PRIMARY KEY (spec, impl)
+);
+CREATE TABLE refs (
+    spec TEXT NOT NULL,
+    impl TEXT NOT NULL,
+    rule_id TEXT NOT NULL,
+    rule_version INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    file TEXT NOT NULL,
+    line INTEGER NOT NULL
+);
+CREATE TABLE code_units (
+    id INTEGER PRIMARY KEY,
+    spec TEXT NOT NULL,
+    impl TEXT NOT NULL,
+    file TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    name TEXT,
+    start_line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL
+);
+CREATE TABLE code_unit_rules (
+    unit_id INTEGER NOT NULL REFERENCES code_units (id),
+    rule_id TEXT NOT NULL
+);
+";
+
+fn export_rule(rule: &tracey_api::ApiRule) -> ExportRule {
+    ExportRule {
+        id: rule.id.base.clone(),
+        version: rule.id.version,
+        text: rule.raw.clone(),
+        status: rule.status.clone(),
+        level: rule.level.clone(),
+        section: rule.section_title.clone(),
+        source_file: rule.source_file.clone(),
+        source_line: rule.source_line,
+    }
+}
+
+fn export_references(rule: &tracey_api::ApiRule) -> Vec<ExportReference> {
+    let current = |kind: &str, refs: &[tracey_api::ApiCodeRef]| {
+        refs.iter()
+            .map(|r| ExportReference {
+                rule_id: rule.id.base.clone(),
+                rule_version: rule.id.version,
+                kind: kind.to_string(),
+                file: r.file.clone(),
+                line: r.line,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut references = current("impl", &rule.impl_refs);
+    references.extend(current("verify", &rule.verify_refs));
+    references.extend(current("depends", &rule.depends_refs));
+    references.extend(rule.stale_refs.iter().map(|r| ExportReference {
+        rule_id: rule.id.base.clone(),
+        rule_version: r.reference_id.version,
+        kind: "stale".to_string(),
+        file: r.file.clone(),
+        line: r.line,
+    }));
+    references
+}
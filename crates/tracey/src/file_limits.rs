@@ -0,0 +1,126 @@
+//! Guardrails that keep non-source files out of the scanner.
+//!
+//! r[impl config.file-limits]
+//!
+//! An include pattern that accidentally matches a directory of bundles or
+//! assets would load every one of those files into memory on each rebuild
+//! and keep them there for search. Files over a size limit, and files that
+//! don't look like text, are skipped instead: validation lists each one as a
+//! warning and the daemon's health output counts them.
+
+use std::fmt;
+
+use tracey_config::FileLimits;
+
+/// Size limit used when the config doesn't set `max_file_mb`.
+pub const DEFAULT_MAX_FILE_MB: u64 = 10;
+
+/// How much of a file the NUL-byte sniff looks at.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Why the scanner skipped a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The file is larger than the limit, both in bytes
+    TooLarge { len: u64, limit: u64 },
+    /// The file contains NUL bytes or isn't valid UTF-8
+    Binary,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::TooLarge { len, limit } => write!(
+                f,
+                "file is {:.1} MB, over the {} MB limit",
+                *len as f64 / (1024.0 * 1024.0),
+                limit / (1024 * 1024)
+            ),
+            SkipReason::Binary => write!(f, "file looks binary"),
+        }
+    }
+}
+
+impl std::error::Error for SkipReason {}
+
+/// The scan limits of a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanLimits {
+    max_bytes: Option<u64>,
+    skip_binary: bool,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ScanLimits {
+    pub fn new(config: Option<&FileLimits>) -> Self {
+        let max_file_mb = config
+            .and_then(|c| c.max_file_mb)
+            .unwrap_or(DEFAULT_MAX_FILE_MB);
+        Self {
+            max_bytes: (max_file_mb > 0).then(|| max_file_mb.saturating_mul(1024 * 1024)),
+            skip_binary: config.and_then(|c| c.skip_binary).unwrap_or(true),
+        }
+    }
+
+    pub fn skips_binary(&self) -> bool {
+        self.skip_binary
+    }
+
+    /// Whether a file of `len` bytes is too large to load.
+    pub fn check_len(&self, len: u64) -> Option<SkipReason> {
+        let limit = self.max_bytes?;
+        (len > limit).then_some(SkipReason::TooLarge { len, limit })
+    }
+
+    /// Whether loaded file contents look binary.
+    pub fn check_content(&self, bytes: &[u8]) -> Option<SkipReason> {
+        let sniff = &bytes[..bytes.len().min(SNIFF_BYTES)];
+        let binary = sniff.contains(&0) || std::str::from_utf8(bytes).is_err();
+        (self.skip_binary && binary).then_some(SkipReason::Binary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify config.file-limits]
+    #[test]
+    fn skips_large_and_binary_files() {
+        let limits = ScanLimits::default();
+        assert_eq!(limits.check_len(1024), None);
+        assert_eq!(
+            limits.check_len(11 * 1024 * 1024),
+            Some(SkipReason::TooLarge {
+                len: 11 * 1024 * 1024,
+                limit: 10 * 1024 * 1024
+            })
+        );
+        assert_eq!(limits.check_content(b"fn main() {}\n"), None);
+        assert_eq!(
+            limits.check_content(b"G@\x00\x10\x00"),
+            Some(SkipReason::Binary)
+        );
+        assert_eq!(limits.check_content(b"caf\xe9"), Some(SkipReason::Binary));
+        assert_eq!(
+            SkipReason::TooLarge {
+                len: 11 * 1024 * 1024,
+                limit: 10 * 1024 * 1024
+            }
+            .to_string(),
+            "file is 11.0 MB, over the 10 MB limit"
+        );
+
+        let relaxed = ScanLimits::new(Some(&FileLimits {
+            max_file_mb: Some(0),
+            skip_binary: Some(false),
+        }));
+        assert_eq!(relaxed.check_len(u64::MAX), None);
+        assert_eq!(relaxed.check_content(b"G@\x00\x10\x00"), None);
+    }
+}
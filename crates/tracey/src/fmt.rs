@@ -0,0 +1,533 @@
+//! Canonical spelling of requirement annotations and rule markers.
+//!
+//! r[impl cli.fmt]
+//!
+//! The same reference can be spelled `r[impl  auth.login+1]`, `r[ impl auth.login ]`
+//! or `r[impl auth.login]`, and annotations sharing a comment can come in
+//! any order. Each spelling that differs from its neighbours' shows up as
+//! diff noise when someone's editor or habit "fixes" it later, and the
+//! loosely spaced ones aren't even recognized as references. `tracey fmt`
+//! rewrites them all one way: one space between verb and ID, no version
+//! suffix for version 1, and `impl` before `verify` before `depends` before
+//! `related`. Only annotations naming a rule of a spec file on disk are
+//! touched, so brackets that merely look like annotations are left alone.
+//! Rewrites never add or remove lines.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use tracey_core::{RefVerb, RuleId, parse_rule_id};
+
+use crate::bump::{Globs, ImplSources, Target, glob_set, parse_spec_rules};
+use crate::config::Config;
+
+/// One rewritten line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// 1-indexed line in the file
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// A file's content in canonical form, and the lines that changed.
+#[derive(Debug, Default)]
+pub struct Formatted {
+    pub content: String,
+    pub changes: Vec<Change>,
+}
+
+impl Formatted {
+    fn new(original: &str, content: String) -> Self {
+        let changes = original
+            .lines()
+            .zip(content.lines())
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(idx, (before, after))| Change {
+                line: idx + 1,
+                before: before.trim().to_string(),
+                after: after.trim().to_string(),
+            })
+            .collect();
+        Self { content, changes }
+    }
+}
+
+/// A file that isn't in canonical form, relative to the project root.
+#[derive(Debug)]
+pub struct FileChanges {
+    pub file: PathBuf,
+    pub formatted: Formatted,
+}
+
+/// Base IDs of the rules defined in spec files, by marker prefix.
+#[derive(Debug, Default)]
+pub struct KnownRules(HashMap<String, HashSet<String>>);
+
+impl KnownRules {
+    pub fn insert(&mut self, prefix: &str, base: &str) {
+        self.0
+            .entry(prefix.to_string())
+            .or_default()
+            .insert(base.to_string());
+    }
+
+    fn defines(&self, prefix: &str, id: &RuleId) -> bool {
+        self.0
+            .get(prefix)
+            .is_some_and(|bases| bases.contains(&id.base))
+    }
+}
+
+/// Format the spec and source files of the work tree, writing them back
+/// unless `check` is set. Returns the files that were (or would be)
+/// rewritten.
+pub async fn format_project(
+    project_root: &Path,
+    config: &Config,
+    check: bool,
+) -> Result<Vec<FileChanges>> {
+    let files = Target::WorkTree.files(project_root)?;
+    let spec_includes: Vec<Globs> = config
+        .specs
+        .iter()
+        .map(|spec| glob_set(&spec.include))
+        .collect();
+    let (spec_files, other_files): (Vec<&str>, Vec<&str>) = files
+        .lines()
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+        .partition(|file| spec_includes.iter().any(|include| include.is_match(file)));
+
+    let mut rules = KnownRules::default();
+    let mut changed = Vec::new();
+    for file in spec_files {
+        let Some(content) = Target::WorkTree.read(project_root, file).ok().flatten() else {
+            continue;
+        };
+        let formatted = format_spec(&content, &mut rules)
+            .await
+            .wrap_err_with(|| format!("failed to parse {}", file))?;
+        if !formatted.changes.is_empty() {
+            changed.push(FileChanges {
+                file: PathBuf::from(file),
+                formatted,
+            });
+        }
+    }
+
+    let sources = ImplSources::new(config);
+    for file in other_files
+        .into_iter()
+        .filter(|file| sources.contains(file))
+    {
+        // Binary or non-UTF-8 files have no annotations.
+        let Some(content) = Target::WorkTree.read(project_root, file).ok().flatten() else {
+            continue;
+        };
+        let formatted = format_source(Path::new(file), &content, &rules);
+        if !formatted.changes.is_empty() {
+            changed.push(FileChanges {
+                file: PathBuf::from(file),
+                formatted,
+            });
+        }
+    }
+
+    if !check {
+        for file in &changed {
+            let path = project_root.join(&file.file);
+            std::fs::write(&path, &file.formatted.content)
+                .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        }
+    }
+    Ok(changed)
+}
+
+/// Canonicalize the rule markers of a spec file, recording its rules in
+/// `rules`.
+pub async fn format_spec(content: &str, rules: &mut KnownRules) -> Result<Formatted> {
+    let mut markers = Vec::new();
+    for req in parse_spec_rules(content).await?.into_values() {
+        let Some(prefix) = crate::extract_marker_prefix(content, req.marker_span) else {
+            continue;
+        };
+        rules.insert(&prefix, &req.id.base);
+
+        // The marker span ends before a `<-` clause, so look for the
+        // closing bracket on the rest of the line.
+        let start = req.marker_span.offset;
+        let line_end = content[start..]
+            .find('\n')
+            .map_or(content.len(), |i| start + i);
+        let Some(open) = content[start..line_end].find('[').map(|i| start + i) else {
+            continue;
+        };
+        let Some(close) = content[open..line_end].find(']').map(|i| open + i) else {
+            continue;
+        };
+        if let Some(inner) = canonical_marker(&content[open + 1..close]) {
+            markers.push((open + 1..close, inner));
+        }
+    }
+    markers.sort_by_key(|(range, _)| range.start);
+    Ok(Formatted::new(content, splice(content, markers)))
+}
+
+/// The contents of a marker's brackets in canonical form: the ID, any
+/// other words as written, and old IDs separated by `, `.
+fn canonical_marker(inner: &str) -> Option<String> {
+    let (head, renamed) = match inner.split_once(" <- ") {
+        Some((head, renamed)) => (head, Some(renamed)),
+        None => (inner, None),
+    };
+    let mut words = head.split_whitespace();
+    let mut out = parse_rule_id(words.next()?)?.to_string();
+    for word in words {
+        out.push(' ');
+        out.push_str(word);
+    }
+    if let Some(renamed) = renamed {
+        let old: Vec<String> = renamed
+            .split([',', ' '])
+            .filter(|id| !id.is_empty())
+            .map(|id| parse_rule_id(id).map_or_else(|| id.to_string(), |id| id.to_string()))
+            .collect();
+        out.push_str(" <- ");
+        out.push_str(&old.join(", "));
+    }
+    Some(out)
+}
+
+/// Canonicalize the annotations in the comments of a source file.
+pub fn format_source(path: &Path, content: &str, rules: &KnownRules) -> Formatted {
+    let respelled = splice(
+        content,
+        annotations(path, content, rules)
+            .into_iter()
+            .map(|a| (a.range, a.text))
+            .collect(),
+    );
+    let annotations = annotations(path, &respelled, rules);
+    Formatted::new(content, reorder(&respelled, &annotations))
+}
+
+/// An annotation found in a comment.
+struct Annotation {
+    /// Byte range in the file, from the prefix to the closing bracket
+    range: Range<usize>,
+    /// The verb as written; `None` means the implicit `impl`
+    verb: Option<RefVerb>,
+    /// The annotation in canonical form
+    text: String,
+}
+
+fn annotations(path: &Path, content: &str, rules: &KnownRules) -> Vec<Annotation> {
+    let mut found = Vec::new();
+    for comment in tracey_core::code_units::comments(path, content) {
+        if comment.ignored {
+            continue;
+        }
+        let text = &content[comment.range.clone()];
+        for (open, _) in text.match_indices('[') {
+            let prefix_start = text[..open]
+                .trim_end_matches(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+                .len();
+            let prefix = &text[prefix_start..open];
+            if prefix.is_empty()
+                || comment
+                    .code_mask
+                    .get(prefix_start)
+                    .copied()
+                    .unwrap_or(false)
+                || text[..prefix_start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            let Some(close) = text[open..].find([']', '\n']).map(|i| open + i) else {
+                continue;
+            };
+            if &text[close..close + 1] != "]" {
+                continue;
+            }
+            let Some((verb, id)) = parse_annotation(&text[open + 1..close]) else {
+                continue;
+            };
+            if !rules.defines(prefix, &id) {
+                continue;
+            }
+            let text = match verb {
+                Some(verb) => format!("{}[{} {}]", prefix, verb, id),
+                None => format!("{}[{}]", prefix, id),
+            };
+            found.push(Annotation {
+                range: comment.range.start + prefix_start..comment.range.start + close + 1,
+                verb,
+                text,
+            });
+        }
+    }
+    found
+}
+
+/// The verb and ID between an annotation's brackets, however spaced.
+fn parse_annotation(inner: &str) -> Option<(Option<RefVerb>, RuleId)> {
+    let words: Vec<&str> = inner.split_whitespace().collect();
+    let (verb, id) = match words[..] {
+        [id] => (None, id),
+        [verb, id] => (Some(RefVerb::parse(verb)?), id),
+        _ => return None,
+    };
+    Some((verb, parse_rule_id(id)?))
+}
+
+/// Where an annotation sorts among the others of its comment.
+fn rank(verb: Option<RefVerb>) -> u8 {
+    match verb.unwrap_or(RefVerb::Impl) {
+        RefVerb::Define => 0,
+        RefVerb::Impl => 1,
+        RefVerb::Verify => 2,
+        RefVerb::Depends => 3,
+        RefVerb::Related => 4,
+    }
+}
+
+/// Put the annotations of lines that hold nothing but a comment delimiter
+/// and annotations in verb order, both within a line and across a run of
+/// such lines that have one annotation each.
+fn reorder(content: &str, annotations: &[Annotation]) -> String {
+    let mut lines: Vec<(String, &str)> = content
+        .split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\n', '\r']);
+            (body.to_string(), &line[body.len()..])
+        })
+        .collect();
+    let mut line_starts = vec![0];
+    line_starts.extend(content.match_indices('\n').map(|(i, _)| i + 1));
+
+    // (line index, delimiter, annotations) of each annotation-only line
+    let mut by_line: Vec<(usize, String, Vec<&Annotation>)> = Vec::new();
+    for annotation in annotations {
+        let idx = line_starts.partition_point(|&start| start <= annotation.range.start) - 1;
+        match by_line.last_mut() {
+            Some((last, _, on_line)) if *last == idx => on_line.push(annotation),
+            _ => by_line.push((idx, String::new(), vec![annotation])),
+        }
+    }
+    by_line.retain_mut(|(idx, leader, on_line)| {
+        let start = line_starts[*idx];
+        let body = &lines[*idx].0;
+        *leader = content[start..on_line[0].range.start].to_string();
+        let delimiter = leader.trim();
+        let only_annotations = on_line.windows(2).all(|pair| {
+            content[pair[0].range.end..pair[1].range.start]
+                .trim()
+                .is_empty()
+        }) && body[on_line[on_line.len() - 1].range.end - start..]
+            .trim()
+            .is_empty();
+        !delimiter.is_empty()
+            && delimiter
+                .chars()
+                .all(|c| matches!(c, '/' | '!' | '#' | '-' | ';' | '%' | '*' | '\''))
+            && only_annotations
+    });
+
+    let mut run_start = 0;
+    while run_start < by_line.len() {
+        let mut run_end = run_start + 1;
+        while run_end < by_line.len()
+            && by_line[run_end].0 == by_line[run_end - 1].0 + 1
+            && by_line[run_end].1 == by_line[run_start].1
+        {
+            run_end += 1;
+        }
+        let run = &by_line[run_start..run_end];
+        let mut sorted: Vec<Vec<&Annotation>> = run
+            .iter()
+            .map(|(_, _, on_line)| {
+                let mut on_line = on_line.clone();
+                on_line.sort_by_key(|a| rank(a.verb));
+                on_line
+            })
+            .collect();
+        if sorted.iter().all(|on_line| on_line.len() == 1) {
+            sorted.sort_by_key(|on_line| rank(on_line[0].verb));
+        }
+        for ((idx, leader, on_line), sorted) in run.iter().zip(sorted) {
+            let unchanged = on_line.iter().zip(&sorted).all(|(a, b)| a.range == b.range);
+            if !unchanged {
+                let texts: Vec<&str> = sorted.iter().map(|a| a.text.as_str()).collect();
+                lines[*idx].0 = format!("{}{}", leader, texts.join(" "));
+            }
+        }
+        run_start = run_end;
+    }
+
+    lines
+        .into_iter()
+        .map(|(body, ending)| body + ending)
+        .collect()
+}
+
+/// `content` with each range replaced, the ranges in order.
+fn splice(content: &str, replacements: Vec<(Range<usize>, String)>) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for (range, text) in replacements {
+        out.push_str(&content[last..range.start]);
+        out.push_str(&text);
+        last = range.end;
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> KnownRules {
+        let mut rules = KnownRules::default();
+        for base in ["auth.login", "auth.token", "auth.session"] {
+            rules.insert("r", base);
+        }
+        rules
+    }
+
+    fn format(content: &str) -> String {
+        format_source(Path::new("src/lib.rs"), content, &rules()).content
+    }
+
+    #[test]
+    fn respaces_annotations_and_drops_version_one() {
+        assert_eq!(
+            format("// r[ impl  auth.login+1 ]\nfn login() {}\n"),
+            "// r[impl auth.login]\nfn login() {}\n"
+        );
+        assert_eq!(
+            format("/// Checks r[verify auth.token+02], see r[auth.session].\nfn t() {}\n"),
+            "/// Checks r[verify auth.token+2], see r[auth.session].\nfn t() {}\n"
+        );
+    }
+
+    // r[verify cli.fmt]
+    #[test]
+    fn orders_annotations_that_share_a_comment() {
+        assert_eq!(
+            format("// r[verify auth.login] r[related auth.token] r[auth.session]\nfn f() {}\n"),
+            "// r[auth.session] r[verify auth.login] r[related auth.token]\nfn f() {}\n"
+        );
+        let source = "\
+    // r[depends auth.token]
+    // r[verify auth.login]
+    // r[impl auth.login]
+    fn f() {}
+";
+        let formatted = format_source(Path::new("src/lib.rs"), source, &rules());
+        assert_eq!(
+            formatted.content,
+            "\
+    // r[impl auth.login]
+    // r[verify auth.login]
+    // r[depends auth.token]
+    fn f() {}
+"
+        );
+        assert_eq!(
+            formatted.changes,
+            vec![
+                Change {
+                    line: 1,
+                    before: "// r[depends auth.token]".into(),
+                    after: "// r[impl auth.login]".into(),
+                },
+                Change {
+                    line: 3,
+                    before: "// r[impl auth.login]".into(),
+                    after: "// r[depends auth.token]".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_other_brackets_alone() {
+        let source = "\
+// r[verify auth.login] is checked before r[impl auth.login]
+// Write `r[ impl auth.login ]` to annotate.
+// r[ impl unknown.rule ]
+fn f(x: &[u8]) -> u8 { x[ 0 ] }
+";
+        assert_eq!(format(source), source);
+    }
+
+    #[tokio::test]
+    async fn canonicalizes_rule_markers() {
+        let content = "\
+r[auth.login+1]
+Users MUST log in.
+
+r[auth.sign-in <- auth.signin,auth.logon]
+Users MUST sign in.
+";
+        let mut rules = KnownRules::default();
+        let formatted = format_spec(content, &mut rules).await.unwrap();
+        assert_eq!(
+            formatted.content,
+            "\
+r[auth.login]
+Users MUST log in.
+
+r[auth.sign-in <- auth.signin, auth.logon]
+Users MUST sign in.
+"
+        );
+        assert!(rules.defines("r", &parse_rule_id("auth.sign-in+3").unwrap()));
+    }
+
+    // r[verify cli.fmt]
+    #[tokio::test]
+    async fn leaves_excluded_sources_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        crate::bump::git_capture(root, &["init", "--quiet"]).unwrap();
+        std::fs::write(root.join("spec.md"), "r[auth.login]\nUsers MUST log in.\n").unwrap();
+        for dir in ["src", "vendor/dep"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(
+                root.join(dir).join("lib.rs"),
+                "// r[ impl  auth.login ]\nfn login() {}\n",
+            )
+            .unwrap();
+        }
+        let config = crate::config::parse_config(
+            r#"specs (
+  {
+    name s
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (**/*.rs)
+      }
+    )
+  }
+)
+"#,
+        )
+        .unwrap();
+
+        let changed = format_project(root, &config, true).await.unwrap();
+        let files: Vec<&Path> = changed.iter().map(|c| c.file.as_path()).collect();
+        assert!(files.contains(&Path::new("src/lib.rs")), "{files:?}");
+        assert!(!files.contains(&Path::new("vendor/dep/lib.rs")), "{files:?}");
+    }
+}
@@ -0,0 +1,677 @@
+//! Coverage history kept in `.tracey/history.sqlite`.
+//!
+//! r[impl cli.snapshot.record]
+//!
+//! Each snapshot stores the coverage numbers of every spec/impl pair at one
+//! point in time, along with each rule's version, text digest and coverage.
+//! `tracey snapshot record` always adds one; once the database exists, the
+//! daemon also adds one after every rebuild that changed the numbers or any
+//! rule. `tracey query trend` reads the numbers back, `tracey query recent`
+//! the rules.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use eyre::Result;
+use tracey_proto::{RecentChange, RecentCoverage, RecentRule};
+
+use crate::data::DashboardData;
+use crate::server::CoverageStats;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Coverage numbers of one spec/impl pair in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageRow {
+    pub spec: String,
+    pub impl_name: String,
+    pub total_rules: usize,
+    pub implemented_rules: usize,
+    pub verified_rules: usize,
+    pub stale_rules: usize,
+}
+
+/// State of one rule of one spec/impl pair in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RuleRow {
+    pub spec: String,
+    pub impl_name: String,
+    /// Canonical ID, with its version
+    pub rule_id: String,
+    /// [`crate::approvals::text_digest`] of the rule's text
+    pub text_digest: String,
+    pub implemented: bool,
+    pub verified: bool,
+}
+
+/// Where the history database of a project lives.
+pub fn history_path(project_root: &Path) -> PathBuf {
+    project_root.join(".tracey").join("history.sqlite")
+}
+
+/// Current coverage of every spec/impl pair, ready to record.
+pub fn snapshot_rows(data: &DashboardData) -> Vec<CoverageRow> {
+    data.forward_by_impl
+        .iter()
+        .map(|((spec, impl_name), forward)| {
+            let stats = CoverageStats::from_rules(&forward.rules);
+            CoverageRow {
+                spec: spec.clone(),
+                impl_name: impl_name.clone(),
+                total_rules: stats.total_rules,
+                implemented_rules: stats.impl_covered,
+                verified_rules: stats.verify_covered,
+                stale_rules: stats.stale_covered,
+            }
+        })
+        .collect()
+}
+
+/// Current state of every rule of every spec/impl pair, ready to record.
+pub fn snapshot_rule_rows(data: &DashboardData) -> Vec<RuleRow> {
+    data.forward_by_impl
+        .iter()
+        .flat_map(|((spec, impl_name), forward)| {
+            forward.rules.iter().map(move |rule| RuleRow {
+                spec: spec.clone(),
+                impl_name: impl_name.clone(),
+                rule_id: rule.id.to_string(),
+                text_digest: crate::approvals::text_digest(&rule.raw),
+                implemented: !rule.impl_refs.is_empty(),
+                verified: !rule.verify_refs.is_empty(),
+            })
+        })
+        .collect()
+}
+
+/// Where `tracey query recent` starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    /// A snapshot, by the version (ID) the history gave it
+    Version(u64),
+    /// A point in time (Unix seconds)
+    At(u64),
+}
+
+/// Parse a `--since` for `tracey query recent`: a snapshot version (`42`),
+/// an age (`30d`, `12h`) or a UTC date (`2026-10-01`).
+pub fn parse_since(since: &str, now: u64) -> Result<Since> {
+    if !since.is_empty() && since.chars().all(|c| c.is_ascii_digit()) {
+        let version = since
+            .parse()
+            .map_err(|_| eyre::eyre!("Invalid snapshot version '{}'", since))?;
+        return Ok(Since::Version(version));
+    }
+    if let Some(secs) = parse_date(since) {
+        return Ok(Since::At(secs));
+    }
+    match parse_age(since) {
+        Ok(age) => Ok(Since::At(now.saturating_sub(age))),
+        Err(_) => eyre::bail!(
+            "Invalid --since '{}', expected a snapshot version (42), an age (30d, 12h) \
+             or a date (2026-10-01)",
+            since
+        ),
+    }
+}
+
+/// Parse a UTC `YYYY-MM-DD` date into Unix seconds at its midnight.
+fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let (year, month, day): (i64, i64, i64) =
+        (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days-from-civil conversion, the inverse of the one in format_timestamp
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days).ok().map(|days| days * SECS_PER_DAY)
+}
+
+/// The rules that changed from `before` to `after`, in spec and rule ID
+/// order: added and removed rules, rules with a new version or text, and
+/// rules whose coverage by some impl changed.
+///
+/// r[impl daemon.cli.query.recent]
+pub fn recent_changes(before: &[RuleRow], after: &[RuleRow]) -> Vec<RecentRule> {
+    let (before, after) = (rows_by_rule(before), rows_by_rule(after));
+
+    let mut keys: Vec<&(&str, String)> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    let mut changes = Vec::new();
+    for key in keys {
+        let (old, new) = (before.get(key), after.get(key));
+        let (old_row, new_row) = (old.map(|rows| rows[0]), new.map(|rows| rows[0]));
+        let change = match (old_row, new_row) {
+            (None, Some(_)) => RecentChange::Added,
+            (Some(_), None) => RecentChange::Removed,
+            (Some(old_row), Some(new_row))
+                if old_row.rule_id != new_row.rule_id
+                    || old_row.text_digest != new_row.text_digest =>
+            {
+                RecentChange::Changed
+            }
+            _ => RecentChange::Coverage,
+        };
+
+        let mut coverage = Vec::new();
+        let empty = Vec::new();
+        let mut impls: Vec<&str> = old
+            .unwrap_or(&empty)
+            .iter()
+            .chain(new.unwrap_or(&empty))
+            .map(|row| row.impl_name.as_str())
+            .collect();
+        impls.sort();
+        impls.dedup();
+        for impl_name in impls {
+            let find = |rows: Option<&Vec<&RuleRow>>| {
+                rows.and_then(|rows| rows.iter().find(|row| row.impl_name == impl_name))
+                    .map_or((false, false), |row| (row.implemented, row.verified))
+            };
+            let (was, is) = (find(old), find(new));
+            if was != is {
+                coverage.push(RecentCoverage {
+                    impl_name: impl_name.to_string(),
+                    was_implemented: was.0,
+                    implemented: is.0,
+                    was_verified: was.1,
+                    verified: is.1,
+                });
+            }
+        }
+        if change == RecentChange::Coverage && coverage.is_empty() {
+            continue;
+        }
+
+        let current = new_row.or(old_row).expect("rule is on at least one side");
+        changes.push(RecentRule {
+            spec: key.0.to_string(),
+            id: current.rule_id.clone(),
+            previous_id: old_row
+                .filter(|old_row| old_row.rule_id != current.rule_id)
+                .map(|old_row| old_row.rule_id.clone()),
+            change,
+            coverage,
+        });
+    }
+    changes
+}
+
+/// The rows of each rule, by spec and base ID.
+fn rows_by_rule(rows: &[RuleRow]) -> BTreeMap<(&str, String), Vec<&RuleRow>> {
+    let mut by_rule: BTreeMap<(&str, String), Vec<&RuleRow>> = BTreeMap::new();
+    for row in rows {
+        let base = tracey_core::parse_rule_id(&row.rule_id)
+            .map_or_else(|| row.rule_id.clone(), |id| id.base);
+        by_rule
+            .entry((row.spec.as_str(), base))
+            .or_default()
+            .push(row);
+    }
+    by_rule
+}
+
+/// Seconds since the Unix epoch.
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parse a `--since` age such as `30d`, `12h`, `2w` or `90m` into seconds.
+pub fn parse_age(age: &str) -> Result<u64> {
+    let (number, unit) = age.split_at(age.find(|c: char| !c.is_ascii_digit()).unwrap_or(age.len()));
+    let number: u64 = number
+        .parse()
+        .map_err(|_| eyre::eyre!("Invalid age '{}', expected e.g. 30d, 12h or 2w", age))?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" | "" => SECS_PER_DAY,
+        "w" => 7 * SECS_PER_DAY,
+        _ => eyre::bail!("Invalid age '{}', expected e.g. 30d, 12h or 2w", age),
+    };
+    Ok(number * unit_secs)
+}
+
+/// Format Unix seconds as a UTC `YYYY-MM-DD HH:MM` timestamp.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / SECS_PER_DAY) as i64;
+    let rem = secs % SECS_PER_DAY;
+
+    // Civil-from-days conversion (proleptic Gregorian calendar)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// An open history database.
+#[cfg(feature = "sqlite")]
+pub struct History {
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl History {
+    /// Open the project's history, creating the database if needed.
+    pub fn open(project_root: &Path) -> Result<Self> {
+        let path = history_path(project_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::open_at(&path)
+    }
+
+    /// Open the project's history only if it has been started already.
+    pub fn open_existing(project_root: &Path) -> Result<Option<Self>> {
+        let path = history_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::open_at(&path).map(Some)
+    }
+
+    fn open_at(path: &Path) -> Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY,
+                recorded_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS coverage (
+                snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+                spec TEXT NOT NULL,
+                impl TEXT NOT NULL,
+                total_rules INTEGER NOT NULL,
+                implemented_rules INTEGER NOT NULL,
+                verified_rules INTEGER NOT NULL,
+                stale_rules INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS rule_states (
+                snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+                spec TEXT NOT NULL,
+                impl TEXT NOT NULL,
+                rule_id TEXT NOT NULL,
+                text_digest TEXT NOT NULL,
+                implemented INTEGER NOT NULL,
+                verified INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Store a snapshot taken at `recorded_at` (Unix seconds).
+    pub fn record(
+        &mut self,
+        recorded_at: u64,
+        rows: &[CoverageRow],
+        rules: &[RuleRow],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO snapshots (recorded_at) VALUES (?1)",
+            [recorded_at as i64],
+        )?;
+        let snapshot_id = tx.last_insert_rowid();
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO coverage (snapshot_id, spec, impl, total_rules, implemented_rules, \
+                 verified_rules, stale_rules) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for row in rows {
+                insert.execute(rusqlite::params![
+                    snapshot_id,
+                    row.spec,
+                    row.impl_name,
+                    row.total_rules as i64,
+                    row.implemented_rules as i64,
+                    row.verified_rules as i64,
+                    row.stale_rules as i64,
+                ])?;
+            }
+            let mut insert = tx.prepare(
+                "INSERT INTO rule_states (snapshot_id, spec, impl, rule_id, text_digest, \
+                 implemented, verified) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for rule in rules {
+                insert.execute(rusqlite::params![
+                    snapshot_id,
+                    rule.spec,
+                    rule.impl_name,
+                    rule.rule_id,
+                    rule.text_digest,
+                    rule.implemented,
+                    rule.verified,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store a snapshot unless the numbers and rules match the latest one.
+    ///
+    /// Returns whether a snapshot was added.
+    pub fn record_if_changed(
+        &mut self,
+        recorded_at: u64,
+        rows: &[CoverageRow],
+        rules: &[RuleRow],
+    ) -> Result<bool> {
+        if let Some((latest, _)) = self.latest()? {
+            let mut previous = self.rows_of(latest)?;
+            let mut current = rows.to_vec();
+            previous.sort_by(|a, b| (&a.spec, &a.impl_name).cmp(&(&b.spec, &b.impl_name)));
+            current.sort_by(|a, b| (&a.spec, &a.impl_name).cmp(&(&b.spec, &b.impl_name)));
+            let mut previous_rules = self.rule_rows_of(latest)?;
+            let mut current_rules = rules.to_vec();
+            previous_rules.sort();
+            current_rules.sort();
+            if previous == current && previous_rules == current_rules {
+                return Ok(false);
+            }
+        }
+        self.record(recorded_at, rows, rules)?;
+        Ok(true)
+    }
+
+    /// Version and time of the latest snapshot.
+    pub fn latest(&self) -> Result<Option<(i64, u64)>> {
+        self.snapshot(
+            "SELECT id, recorded_at FROM snapshots ORDER BY id DESC LIMIT 1",
+            [],
+        )
+    }
+
+    /// The snapshot `since` names: the one with that version, or the last
+    /// one recorded at or before that time. When history starts later
+    /// than that, its first snapshot. Times only pick snapshots with rules,
+    /// which those recorded by older versions of tracey lack.
+    pub fn baseline(&self, since: Since) -> Result<Option<(i64, u64)>> {
+        match since {
+            Since::Version(version) => self.snapshot(
+                "SELECT id, recorded_at FROM snapshots WHERE id = ?1",
+                [version as i64],
+            ),
+            Since::At(secs) => {
+                let before = self.snapshot(
+                    "SELECT id, recorded_at FROM snapshots WHERE recorded_at <= ?1 \
+                     AND id IN (SELECT snapshot_id FROM rule_states) \
+                     ORDER BY recorded_at DESC, id DESC LIMIT 1",
+                    [secs as i64],
+                )?;
+                match before {
+                    Some(snapshot) => Ok(Some(snapshot)),
+                    None => self.snapshot(
+                        "SELECT id, recorded_at FROM snapshots \
+                         WHERE id IN (SELECT snapshot_id FROM rule_states) ORDER BY id LIMIT 1",
+                        [],
+                    ),
+                }
+            }
+        }
+    }
+
+    fn snapshot(&self, sql: &str, params: impl rusqlite::Params) -> Result<Option<(i64, u64)>> {
+        use rusqlite::OptionalExtension;
+        let snapshot = self
+            .conn
+            .query_row(sql, params, |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? as u64))
+            })
+            .optional()?;
+        Ok(snapshot)
+    }
+
+    /// The rules recorded in snapshot `snapshot_id`. Snapshots from before
+    /// rules were recorded have none.
+    pub fn rule_rows_of(&self, snapshot_id: i64) -> Result<Vec<RuleRow>> {
+        let mut select = self.conn.prepare(
+            "SELECT spec, impl, rule_id, text_digest, implemented, verified \
+             FROM rule_states WHERE snapshot_id = ?1",
+        )?;
+        let rows = select
+            .query_map([snapshot_id], |row| {
+                Ok(RuleRow {
+                    spec: row.get(0)?,
+                    impl_name: row.get(1)?,
+                    rule_id: row.get(2)?,
+                    text_digest: row.get(3)?,
+                    implemented: row.get(4)?,
+                    verified: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    fn rows_of(&self, snapshot_id: i64) -> Result<Vec<CoverageRow>> {
+        let mut select = self.conn.prepare(
+            "SELECT spec, impl, total_rules, implemented_rules, verified_rules, stale_rules \
+             FROM coverage WHERE snapshot_id = ?1",
+        )?;
+        let rows = select
+            .query_map([snapshot_id], |row| {
+                Ok(CoverageRow {
+                    spec: row.get(0)?,
+                    impl_name: row.get(1)?,
+                    total_rules: row.get::<_, i64>(2)? as usize,
+                    implemented_rules: row.get::<_, i64>(3)? as usize,
+                    verified_rules: row.get::<_, i64>(4)? as usize,
+                    stale_rules: row.get::<_, i64>(5)? as usize,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Snapshots recorded at or after `since` (Unix seconds), oldest first,
+    /// optionally limited to one spec and/or impl.
+    pub fn trend(
+        &self,
+        spec: Option<&str>,
+        impl_name: Option<&str>,
+        since: u64,
+    ) -> Result<Vec<(u64, CoverageRow)>> {
+        let mut select = self.conn.prepare(
+            "SELECT s.recorded_at, c.spec, c.impl, c.total_rules, c.implemented_rules, \
+             c.verified_rules, c.stale_rules \
+             FROM coverage c JOIN snapshots s ON s.id = c.snapshot_id \
+             WHERE s.recorded_at >= ?1 AND (?2 IS NULL OR c.spec = ?2) \
+             AND (?3 IS NULL OR c.impl = ?3) \
+             ORDER BY c.spec, c.impl, s.recorded_at, s.id",
+        )?;
+        let points = select
+            .query_map(rusqlite::params![since as i64, spec, impl_name], |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as u64,
+                    CoverageRow {
+                        spec: row.get(1)?,
+                        impl_name: row.get(2)?,
+                        total_rules: row.get::<_, i64>(3)? as usize,
+                        implemented_rules: row.get::<_, i64>(4)? as usize,
+                        verified_rules: row.get::<_, i64>(5)? as usize,
+                        stale_rules: row.get::<_, i64>(6)? as usize,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ages() {
+        assert_eq!(parse_age("30d").unwrap(), 30 * SECS_PER_DAY);
+        assert_eq!(parse_age("12h").unwrap(), 12 * 60 * 60);
+        assert_eq!(parse_age("2w").unwrap(), 14 * SECS_PER_DAY);
+        assert_eq!(parse_age("7").unwrap(), 7 * SECS_PER_DAY);
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("3y").is_err());
+    }
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(951_827_696), "2000-02-29 12:34");
+        assert_eq!(format_timestamp(1_735_689_599), "2024-12-31 23:59");
+    }
+
+    #[test]
+    fn parses_since() {
+        let now = 10 * SECS_PER_DAY;
+        assert_eq!(parse_since("42", now).unwrap(), Since::Version(42));
+        assert_eq!(parse_since("2d", now).unwrap(), Since::At(8 * SECS_PER_DAY));
+        assert_eq!(
+            parse_since("1970-01-03", now).unwrap(),
+            Since::At(2 * SECS_PER_DAY)
+        );
+        assert_eq!(
+            parse_since("2000-02-29", now).unwrap(),
+            Since::At(951_782_400)
+        );
+        assert!(parse_since("2000-13-01", now).is_err());
+        assert!(parse_since("yesterday", now).is_err());
+    }
+
+    fn rule_row(id: &str, digest: &str, implemented: bool, verified: bool) -> RuleRow {
+        RuleRow {
+            spec: "auth".to_string(),
+            impl_name: "rust".to_string(),
+            rule_id: id.to_string(),
+            text_digest: digest.to_string(),
+            implemented,
+            verified,
+        }
+    }
+
+    // r[verify daemon.cli.query.recent]
+    #[test]
+    fn lists_rules_that_changed() {
+        let before = [
+            rule_row("auth.login", "a", true, true),
+            rule_row("auth.logout", "b", true, false),
+            rule_row("auth.legacy", "c", false, false),
+            rule_row("auth.session", "d", true, true),
+            rule_row("auth.token", "e", false, false),
+        ];
+        let after = [
+            rule_row("auth.login+2", "a2", false, true),
+            rule_row("auth.logout", "b", true, true),
+            rule_row("auth.mfa", "f", false, false),
+            rule_row("auth.session", "d", true, true),
+            rule_row("auth.token", "e2", false, false),
+        ];
+        let changes = recent_changes(&before, &after);
+        let summary: Vec<(RecentChange, &str, Option<&str>, usize)> = changes
+            .iter()
+            .map(|c| {
+                let previous = c.previous_id.as_deref();
+                (c.change, c.id.as_str(), previous, c.coverage.len())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (RecentChange::Removed, "auth.legacy", None, 0),
+                (RecentChange::Changed, "auth.login+2", Some("auth.login"), 1),
+                (RecentChange::Coverage, "auth.logout", None, 1),
+                (RecentChange::Added, "auth.mfa", None, 0),
+                (RecentChange::Changed, "auth.token", None, 0),
+            ]
+        );
+        assert_eq!(
+            changes[2].coverage,
+            [RecentCoverage {
+                impl_name: "rust".to_string(),
+                was_implemented: true,
+                implemented: true,
+                was_verified: false,
+                verified: true,
+            }]
+        );
+        assert!(recent_changes(&after, &after).is_empty());
+    }
+
+    // r[verify cli.snapshot.record]
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn records_snapshots_and_skips_unchanged_ones() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let row = |implemented| CoverageRow {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            total_rules: 10,
+            implemented_rules: implemented,
+            verified_rules: 2,
+            stale_rules: 0,
+        };
+
+        assert!(History::open_existing(temp.path()).unwrap().is_none());
+        let mut history = History::open(temp.path()).unwrap();
+        assert!(history.record_if_changed(100, &[row(4)], &[]).unwrap());
+        assert!(!history.record_if_changed(200, &[row(4)], &[]).unwrap());
+        assert!(history.record_if_changed(300, &[row(6)], &[]).unwrap());
+
+        let points = history.trend(Some("spec"), None, 0).unwrap();
+        assert_eq!(points, vec![(100, row(4)), (300, row(6))]);
+        assert!(history.trend(Some("other"), None, 0).unwrap().is_empty());
+        assert_eq!(history.trend(None, None, 150).unwrap(), vec![(300, row(6))]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn finds_the_baseline_of_recent_changes() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let mut history = History::open(temp.path()).unwrap();
+        assert_eq!(history.latest().unwrap(), None);
+
+        // Snapshot 1 predates per-rule history.
+        history.record(100, &[], &[]).unwrap();
+        let rules = [rule_row("auth.login", "a", true, false)];
+        assert!(history.record_if_changed(200, &[], &rules).unwrap());
+        assert!(!history.record_if_changed(250, &[], &rules).unwrap());
+        let verified = [rule_row("auth.login", "a", true, true)];
+        assert!(history.record_if_changed(300, &[], &verified).unwrap());
+
+        assert_eq!(history.latest().unwrap(), Some((3, 300)));
+        assert_eq!(history.baseline(Since::Version(1)).unwrap(), Some((1, 100)));
+        assert_eq!(history.baseline(Since::Version(9)).unwrap(), None);
+        assert_eq!(history.baseline(Since::At(299)).unwrap(), Some((2, 200)));
+        assert_eq!(history.baseline(Since::At(150)).unwrap(), Some((2, 200)));
+        assert_eq!(history.rule_rows_of(2).unwrap(), rules);
+        assert!(history.rule_rows_of(1).unwrap().is_empty());
+    }
+}
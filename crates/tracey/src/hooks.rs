@@ -0,0 +1,399 @@
+//! Installing tracey's git hooks, for `tracey hooks install`.
+//!
+//! r[impl cli.hooks.install]
+//!
+//! Plain git hooks get a marked block right after the shebang of the hook
+//! script (created if missing), so existing hooks keep running and
+//! re-installing replaces only tracey's block. Projects that use a hook manager get a stanza in the
+//! manager's config instead, since the manager owns `.git/hooks`.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+
+use crate::bump::git_capture;
+
+const BEGIN: &str = "# >>> tracey >>>";
+const END: &str = "# <<< tracey <<<";
+
+/// A hook tracey can install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Runs `tracey pre-commit`
+    PreCommit,
+    /// Runs `tracey pre-push`
+    PrePush,
+}
+
+impl Hook {
+    /// The git hook name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::PrePush => "pre-push",
+        }
+    }
+
+    fn command(self) -> &'static str {
+        match self {
+            Hook::PreCommit => "tracey pre-commit",
+            Hook::PrePush => "tracey pre-push",
+        }
+    }
+}
+
+/// Who runs the project's git hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookManager {
+    /// Scripts in the git hooks directory
+    Git,
+    /// Scripts in `.husky/`
+    Husky,
+    /// The pre-commit framework (`.pre-commit-config.yaml`)
+    PreCommit,
+    /// Lefthook (`lefthook.yml`)
+    Lefthook,
+}
+
+impl HookManager {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "git" => Some(HookManager::Git),
+            "husky" => Some(HookManager::Husky),
+            "pre-commit" => Some(HookManager::PreCommit),
+            "lefthook" => Some(HookManager::Lefthook),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HookManager::Git => "git",
+            HookManager::Husky => "husky",
+            HookManager::PreCommit => "pre-commit",
+            HookManager::Lefthook => "lefthook",
+        }
+    }
+}
+
+const LEFTHOOK_FILES: &[&str] = &[
+    "lefthook.yml",
+    "lefthook.yaml",
+    ".lefthook.yml",
+    ".lefthook.yaml",
+];
+
+/// Pick the hook manager from the files in the project root.
+pub fn detect_manager(project_root: &Path) -> HookManager {
+    if LEFTHOOK_FILES
+        .iter()
+        .any(|f| project_root.join(f).is_file())
+    {
+        HookManager::Lefthook
+    } else if project_root.join(".pre-commit-config.yaml").is_file() {
+        HookManager::PreCommit
+    } else if project_root.join(".husky").is_dir() {
+        HookManager::Husky
+    } else {
+        HookManager::Git
+    }
+}
+
+/// What `install` did to one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Installed {
+    /// The file was created
+    Created,
+    /// Tracey was added to an existing file, or its entry there was updated
+    Added,
+    /// Tracey was already set up
+    Unchanged,
+    /// The file couldn't be edited safely; the stanza has to be added by hand
+    Manual(String),
+}
+
+/// The result of installing one hook.
+#[derive(Debug)]
+pub struct InstallOutcome {
+    pub hook: Hook,
+    pub path: PathBuf,
+    pub installed: Installed,
+}
+
+/// Install `hook` for `manager` in the project at `project_root`.
+pub fn install(project_root: &Path, manager: HookManager, hook: Hook) -> Result<InstallOutcome> {
+    let path = match manager {
+        HookManager::Git => {
+            // `core.hooksPath` takes precedence over the git dir's hooks.
+            let hooks = git_capture(project_root, &["config", "core.hooksPath"])
+                .ok()
+                .filter(|path| !path.trim().is_empty())
+                .map(Ok)
+                .unwrap_or_else(|| {
+                    git_capture(project_root, &["rev-parse", "--git-path", "hooks"])
+                })?;
+            project_root.join(hooks.trim()).join(hook.name())
+        }
+        HookManager::Husky => project_root.join(".husky").join(hook.name()),
+        HookManager::PreCommit => project_root.join(".pre-commit-config.yaml"),
+        HookManager::Lefthook => LEFTHOOK_FILES
+            .iter()
+            .map(|f| project_root.join(f))
+            .find(|p| p.is_file())
+            .unwrap_or_else(|| project_root.join(LEFTHOOK_FILES[0])),
+    };
+
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    let updated = match manager {
+        HookManager::Git => Ok(add_to_script(existing.as_deref(), hook, true)),
+        HookManager::Husky => Ok(add_to_script(existing.as_deref(), hook, false)),
+        HookManager::PreCommit => add_to_pre_commit_config(existing.as_deref(), hook),
+        HookManager::Lefthook => add_to_lefthook(existing.as_deref(), hook),
+    };
+
+    let installed = match updated {
+        Err(stanza) => Installed::Manual(stanza),
+        Ok(None) => Installed::Unchanged,
+        Ok(Some(content)) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, content)
+                .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+            if matches!(manager, HookManager::Git | HookManager::Husky) {
+                make_executable(&path)?;
+            }
+            if existing.is_some() {
+                Installed::Added
+            } else {
+                Installed::Created
+            }
+        }
+    };
+    Ok(InstallOutcome {
+        hook,
+        path,
+        installed,
+    })
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o111);
+    std::fs::set_permissions(path, permissions)
+        .wrap_err_with(|| format!("failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Add (or refresh) tracey's block in a hook script. Returns `None` if the
+/// script already has the same block. Husky scripts don't need a shebang.
+///
+/// A new block goes right after the shebang, before the script's own
+/// commands, so that it runs even in scripts that end with `exit 0` or
+/// `exec ...`.
+fn add_to_script(existing: Option<&str>, hook: Hook, shebang: bool) -> Option<String> {
+    let block = format!("{}\n{} || exit $?\n{}\n", BEGIN, hook.command(), END);
+    let Some(existing) = existing.filter(|e| !e.trim().is_empty()) else {
+        let header = if shebang { "#!/bin/sh\n" } else { "" };
+        return Some(format!("{}{}", header, block));
+    };
+
+    if let (Some(start), Some(end)) = (existing.find(BEGIN), existing.find(END))
+        && start < end
+    {
+        let end = end + END.len();
+        let end = end + existing[end..].starts_with('\n') as usize;
+        if existing[start..end] == block {
+            return None;
+        }
+        return Some(format!(
+            "{}{}{}",
+            &existing[..start],
+            block,
+            &existing[end..]
+        ));
+    }
+
+    let (header, rest) = if existing.starts_with("#!") {
+        existing.split_at(existing.find('\n').map_or(existing.len(), |i| i + 1))
+    } else {
+        ("", existing)
+    };
+    let mut out = header.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&block);
+    if !rest.is_empty() {
+        out.push('\n');
+        out.push_str(rest);
+    }
+    Some(out)
+}
+
+/// The top-level YAML keys of `content`, in order.
+fn yaml_top_level_keys(content: &str) -> Vec<&str> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '-']) && !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':').map(|(key, _)| key.trim()))
+        .collect()
+}
+
+fn append_yaml(existing: &str, stanza: &str) -> String {
+    let mut out = existing.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(stanza);
+    out
+}
+
+/// Add a local tracey hook to a pre-commit framework config. Returns the
+/// stanza as the error if `repos:` isn't the last top-level key, since
+/// appending would then land in the wrong place.
+fn add_to_pre_commit_config(existing: Option<&str>, hook: Hook) -> Result<Option<String>, String> {
+    let id = format!("tracey-{}", hook.name());
+    let stanza = format!(
+        "  - repo: local\n    hooks:\n      - id: {id}\n        name: {command}\n        \
+         entry: {command}\n        language: system\n        pass_filenames: false\n        \
+         stages: [{stage}]\n",
+        id = id,
+        command = hook.command(),
+        stage = hook.name()
+    );
+    let existing = existing.unwrap_or("");
+    if existing.contains(&format!("id: {}", id)) {
+        return Ok(None);
+    }
+    match yaml_top_level_keys(existing).last() {
+        None => Ok(Some(append_yaml(existing, &format!("repos:\n{}", stanza)))),
+        Some(&"repos") => Ok(Some(append_yaml(existing, &stanza))),
+        Some(_) => Err(stanza),
+    }
+}
+
+/// Add a tracey command to a lefthook config. Returns the stanza as the
+/// error if the config already has a section for this hook, since tracey's
+/// command has to go inside it.
+fn add_to_lefthook(existing: Option<&str>, hook: Hook) -> Result<Option<String>, String> {
+    let name = format!("tracey-{}", hook.name());
+    let stanza = format!(
+        "{}:\n  commands:\n    {}:\n      run: {}\n",
+        hook.name(),
+        name,
+        hook.command()
+    );
+    let existing = existing.unwrap_or("");
+    if existing
+        .lines()
+        .any(|line| line.trim() == format!("{}:", name))
+    {
+        return Ok(None);
+    }
+    if yaml_top_level_keys(existing).contains(&hook.name()) {
+        return Err(stanza);
+    }
+    Ok(Some(append_yaml(existing, &stanza)))
+}
+
+/// Install every hook in `hooks`, failing on the first error.
+pub fn install_all(
+    project_root: &Path,
+    manager: HookManager,
+    hooks: &[Hook],
+) -> Result<Vec<InstallOutcome>> {
+    if manager == HookManager::Git
+        && git_capture(project_root, &["rev-parse", "--git-dir"]).is_err()
+    {
+        bail!("{} is not in a git repository", project_root.display());
+    }
+    hooks
+        .iter()
+        .map(|&hook| install(project_root, manager, hook))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.hooks.install]
+    #[test]
+    fn chains_into_existing_scripts() {
+        let created = add_to_script(None, Hook::PreCommit, true).unwrap();
+        assert_eq!(
+            created,
+            "#!/bin/sh\n# >>> tracey >>>\ntracey pre-commit || exit $?\n# <<< tracey <<<\n"
+        );
+        assert_eq!(add_to_script(Some(&created), Hook::PreCommit, true), None);
+
+        // The block runs before the script's own commands, which may exit.
+        let existing = "#!/bin/sh\ncargo fmt --check\nexit 0\n";
+        let chained = add_to_script(Some(existing), Hook::PreCommit, true).unwrap();
+        assert_eq!(
+            chained,
+            "#!/bin/sh\n# >>> tracey >>>\ntracey pre-commit || exit $?\n# <<< tracey <<<\n\n\
+             cargo fmt --check\nexit 0\n"
+        );
+        let husky = add_to_script(Some("npx lint-staged\n"), Hook::PreCommit, false).unwrap();
+        assert!(husky.starts_with("# >>> tracey >>>\n"), "{husky}");
+        assert!(husky.ends_with("\nnpx lint-staged\n"), "{husky}");
+
+        // A stale block is replaced in place, keeping what surrounds it.
+        let stale = chained.replace("tracey pre-commit", "tracey pre-commit --old") + "echo done\n";
+        let refreshed = add_to_script(Some(&stale), Hook::PreCommit, true).unwrap();
+        assert_eq!(refreshed, format!("{}echo done\n", chained));
+    }
+
+    #[test]
+    fn adds_pre_commit_framework_stanza() {
+        let existing = "repos:\n  - repo: https://github.com/pre-commit/pre-commit-hooks\n    \
+                        rev: v4.6.0\n    hooks:\n      - id: trailing-whitespace\n";
+        let updated = add_to_pre_commit_config(Some(existing), Hook::PreCommit)
+            .unwrap()
+            .unwrap();
+        assert!(updated.starts_with(existing));
+        assert!(
+            updated.contains("      - id: tracey-pre-commit\n"),
+            "{updated}"
+        );
+        assert!(
+            updated.contains("        entry: tracey pre-commit\n"),
+            "{updated}"
+        );
+        assert_eq!(
+            add_to_pre_commit_config(Some(&updated), Hook::PreCommit),
+            Ok(None)
+        );
+
+        let repos_first = format!("{}default_stages: [pre-commit]\n", existing);
+        let manual = add_to_pre_commit_config(Some(&repos_first), Hook::PreCommit).unwrap_err();
+        assert!(manual.contains("id: tracey-pre-commit"), "{manual}");
+    }
+
+    #[test]
+    fn adds_lefthook_commands() {
+        let created = add_to_lefthook(None, Hook::PrePush).unwrap().unwrap();
+        assert_eq!(
+            created,
+            "pre-push:\n  commands:\n    tracey-pre-push:\n      run: tracey pre-push\n"
+        );
+        assert_eq!(add_to_lefthook(Some(&created), Hook::PrePush), Ok(None));
+
+        let existing = "pre-commit:\n  commands:\n    lint:\n      run: cargo clippy\n";
+        let manual = add_to_lefthook(Some(existing), Hook::PreCommit).unwrap_err();
+        assert!(manual.contains("run: tracey pre-commit"), "{manual}");
+    }
+}
@@ -0,0 +1,153 @@
+//! Clickable `file:line` references in terminal output.
+//!
+//! r[impl daemon.cli.query.hyperlink]
+//!
+//! Query output names annotations as `path:line`, relative to the project
+//! root. With `--hyperlink`, each one that names a file of the project is
+//! wrapped in an OSC-8 escape sequence, which most terminals render as a
+//! link. The link target is a `file://` URL by default, or an editor URL
+//! (`vscode://`, `zed://`, ...) that opens the file at the line, as the
+//! config's `editor_links` says.
+
+use std::path::Path;
+
+use eyre::Result;
+
+/// How `file:line` references are linked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorLinks {
+    /// URL with `{path}` (absolute, starting with `/`) and `{line}`
+    /// placeholders
+    template: String,
+}
+
+impl Default for EditorLinks {
+    fn default() -> Self {
+        Self {
+            template: "file://{path}".to_string(),
+        }
+    }
+}
+
+impl EditorLinks {
+    /// r[impl config.editor-links]
+    pub fn new(config: Option<&str>) -> Result<Self> {
+        let template = match config {
+            None | Some("file") => return Ok(Self::default()),
+            Some("vscode") => "vscode://file{path}:{line}",
+            Some("zed") => "zed://file{path}:{line}",
+            Some("cursor") => "cursor://file{path}:{line}",
+            Some(template) if template.contains("{path}") => template,
+            Some(other) => eyre::bail!(
+                "Unknown editor_links '{}', expected file, vscode, zed, cursor or a URL with {{path}}",
+                other
+            ),
+        };
+        Ok(Self {
+            template: template.to_string(),
+        })
+    }
+
+    /// The URL of `line` of the file at the absolute `path`.
+    pub fn url(&self, path: &Path, line: usize) -> String {
+        let mut path = path.display().to_string().replace('\\', "/");
+        if !path.starts_with('/') {
+            path.insert(0, '/');
+        }
+        self.template
+            .replace("{path}", &path.replace(' ', "%20"))
+            .replace("{line}", &line.to_string())
+    }
+}
+
+/// Wrap every `path:line` in `text` whose path is a file under
+/// `project_root` in an OSC-8 hyperlink.
+pub fn hyperlink(text: &str, project_root: &Path, links: &EditorLinks) -> String {
+    let is_path_byte = |b: u8| b.is_ascii_alphanumeric() || b"_-./\\".contains(&b);
+    let bytes = text.as_bytes();
+    let mut output = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b':' {
+            i += 1;
+            continue;
+        }
+        let digits = bytes[i + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        let start = bytes[copied..i]
+            .iter()
+            .rposition(|b| !is_path_byte(*b))
+            .map_or(copied, |p| copied + p + 1);
+        let path = &text[start..i];
+        let line = text[i + 1..i + 1 + digits].parse::<usize>().ok();
+        let file = project_root.join(path);
+        match line {
+            Some(line) if !path.is_empty() && file.is_file() => {
+                let end = i + 1 + digits;
+                output.push_str(&text[copied..start]);
+                output.push_str(&format!(
+                    "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+                    links.url(&tracey_core::canonical_path(&file), line),
+                    &text[start..end]
+                ));
+                copied = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    output.push_str(&text[copied..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify daemon.cli.query.hyperlink]
+    #[test]
+    fn links_references_to_project_files() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("src/lib.rs"), "").unwrap();
+        let root = tracey_core::canonical_path(temp.path());
+
+        let links = EditorLinks::new(Some("vscode")).unwrap();
+        let url = links.url(&root.join("src/lib.rs"), 42);
+        assert!(url.starts_with("vscode://file/") && url.ends_with("/src/lib.rs:42"));
+        assert_eq!(
+            hyperlink(
+                "  - src/lib.rs:42 (impl), at 12:30, src/gone.rs:1",
+                &root,
+                &links
+            ),
+            format!(
+                "  - \x1b]8;;{url}\x1b\\src/lib.rs:42\x1b]8;;\x1b\\ (impl), at 12:30, src/gone.rs:1"
+            )
+        );
+        assert_eq!(
+            EditorLinks::default().url(Path::new("/work/my api/src/lib.rs"), 42),
+            "file:///work/my%20api/src/lib.rs"
+        );
+    }
+
+    // r[verify config.editor-links]
+    #[test]
+    fn reads_the_link_style_from_the_config() {
+        let path = Path::new("/work/src/lib.rs");
+        assert_eq!(
+            EditorLinks::new(Some("zed")).unwrap().url(path, 7),
+            "zed://file/work/src/lib.rs:7"
+        );
+        assert_eq!(
+            EditorLinks::new(Some("idea://open?file={path}&line={line}"))
+                .unwrap()
+                .url(path, 7),
+            "idea://open?file=/work/src/lib.rs&line=7"
+        );
+        assert!(EditorLinks::new(Some("emacs")).is_err());
+    }
+}
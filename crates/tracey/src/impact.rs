@@ -0,0 +1,342 @@
+//! The code a spec change affects, for `tracey impact`.
+//!
+//! r[impl cli.impact]
+//!
+//! The rules whose text changed between a revision and the spec files on
+//! disk are the starting point. Rules whose text depends on one of them
+//! (`r[depends other:api.fetch]`) are affected too, and so on through their
+//! own dependents. For each affected rule, every `impl`, `verify` and
+//! `depends` reference in the code is listed, along with the stale
+//! references still pointing at an older version, since after a bump those
+//! are the places the change hasn't reached yet.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use eyre::{Result, bail};
+use facet::Facet;
+use tracey_api::{ApiCodeRef, ApiRule, ApiSpecForward};
+
+use crate::bump::Target;
+use crate::config::Config;
+use crate::data::ImplKey;
+use crate::spec_diff::{Snapshot, resolve_revision};
+
+/// The rules a spec change affects, and where the code references them.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ImpactReport {
+    pub since: String,
+    /// Rules whose text changed first, then their dependents
+    pub rules: Vec<ImpactedRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ImpactedRule {
+    pub spec: String,
+    /// Canonical ID, with its current version
+    pub id: String,
+    /// The affected rule (`spec:id`) this one depends on, `None` if its
+    /// own text changed
+    #[facet(default)]
+    pub via: Option<String>,
+    /// Word-level diff of the text, for rules whose text changed
+    #[facet(default)]
+    pub diff: Option<String>,
+    pub references: Vec<ImpactReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ImpactReference {
+    pub impl_name: String,
+    /// `impl`, `verify`, `depends` or `stale`
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// A rule whose text changed: its spec, base ID and diff.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedText {
+    pub spec: String,
+    pub base: String,
+    pub diff: Option<String>,
+}
+
+/// The rules whose text changed between `since` and the spec files on
+/// disk.
+pub async fn changed_since(
+    project_root: &Path,
+    config: &Config,
+    since: &str,
+) -> Result<Vec<ChangedText>> {
+    let Some(rev) = resolve_revision(project_root, since) else {
+        bail!("'{}' is not a git revision", since);
+    };
+    let old = Snapshot::at_revision(project_root, config, &rev).await?;
+    let new = Snapshot::at(project_root, config, Target::WorkTree).await?;
+    let diff = crate::spec_diff::diff(since, &old, "the working tree", &new);
+    Ok(diff
+        .specs
+        .into_iter()
+        .flat_map(|spec| {
+            let name = spec.spec;
+            spec.changed.into_iter().map(move |rule| ChangedText {
+                spec: name.clone(),
+                base: tracey_core::parse_rule_id(&rule.to_id)
+                    .map_or(rule.to_id.clone(), |id| id.base),
+                diff: rule.diff,
+            })
+        })
+        .collect())
+}
+
+/// The rules `changed` affects in `forward`, with their references.
+pub fn analyze(
+    since: &str,
+    changed: &[ChangedText],
+    forward: &BTreeMap<ImplKey, ApiSpecForward>,
+) -> ImpactReport {
+    // Every impl's copy of each rule, by spec and base ID.
+    let mut copies: BTreeMap<(&str, &str), Vec<(&str, &ApiRule)>> = BTreeMap::new();
+    for ((spec, impl_name), forward) in forward {
+        for rule in &forward.rules {
+            copies
+                .entry((spec.as_str(), rule.id.base.as_str()))
+                .or_default()
+                .push((impl_name.as_str(), rule));
+        }
+    }
+
+    let mut affected: Vec<(String, String, Option<String>, Option<String>)> = changed
+        .iter()
+        .filter(|c| copies.contains_key(&(c.spec.as_str(), c.base.as_str())))
+        .map(|c| (c.spec.clone(), c.base.clone(), None, c.diff.clone()))
+        .collect();
+    let mut seen: BTreeSet<(String, String)> = affected
+        .iter()
+        .map(|(spec, base, ..)| (spec.clone(), base.clone()))
+        .collect();
+    // Dependents of affected rules, breadth first, until there are no more.
+    let mut next = 0;
+    while next < affected.len() {
+        let (spec, base) = (affected[next].0.clone(), affected[next].1.clone());
+        next += 1;
+        for (&(dependent_spec, dependent_base), rules) in &copies {
+            let depends = rules.iter().any(|(_, rule)| {
+                rule.depends_on
+                    .iter()
+                    .any(|dep| dep.spec == spec && dep.id.base == base)
+            });
+            let key = (dependent_spec.to_string(), dependent_base.to_string());
+            if depends && !seen.contains(&key) {
+                let via = current_id(&copies, &spec, &base);
+                seen.insert(key.clone());
+                affected.push((key.0, key.1, Some(format!("{spec}:{via}")), None));
+            }
+        }
+    }
+
+    let rules = affected
+        .into_iter()
+        .map(|(spec, base, via, diff)| {
+            let mut references = Vec::new();
+            for (impl_name, rule) in &copies[&(spec.as_str(), base.as_str())] {
+                let mut push = |kind: &str, refs: &[ApiCodeRef]| {
+                    references.extend(refs.iter().map(|r| ImpactReference {
+                        impl_name: impl_name.to_string(),
+                        kind: kind.to_string(),
+                        file: r.file.clone(),
+                        line: r.line,
+                    }));
+                };
+                push("impl", &rule.impl_refs);
+                push("verify", &rule.verify_refs);
+                push("depends", &rule.depends_refs);
+                references.extend(rule.stale_refs.iter().map(|r| ImpactReference {
+                    impl_name: impl_name.to_string(),
+                    kind: "stale".to_string(),
+                    file: r.file.clone(),
+                    line: r.line,
+                }));
+            }
+            ImpactedRule {
+                id: current_id(&copies, &spec, &base),
+                spec,
+                via,
+                diff,
+                references,
+            }
+        })
+        .collect();
+
+    ImpactReport {
+        since: since.to_string(),
+        rules,
+    }
+}
+
+fn current_id(
+    copies: &BTreeMap<(&str, &str), Vec<(&str, &ApiRule)>>,
+    spec: &str,
+    base: &str,
+) -> String {
+    copies[&(spec, base)][0].1.id.to_string()
+}
+
+/// The report as text, a section per affected rule.
+pub fn format_report(report: &ImpactReport) -> String {
+    if report.rules.is_empty() {
+        return format!("No rule text changed since {}\n", report.since);
+    }
+    let changed = report.rules.iter().filter(|r| r.via.is_none()).count();
+    let mut out = format!(
+        "{} rule(s) changed since {}, {} more depend on them\n",
+        changed,
+        report.since,
+        report.rules.len() - changed
+    );
+    for rule in &report.rules {
+        match &rule.via {
+            Some(via) => out.push_str(&format!(
+                "\n{}:{} (depends on {})\n",
+                rule.spec, rule.id, via
+            )),
+            None => out.push_str(&format!("\n{}:{}\n", rule.spec, rule.id)),
+        }
+        if let Some(diff) = &rule.diff {
+            for line in diff.lines() {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+        if rule.references.is_empty() {
+            out.push_str("  no references\n");
+        }
+        for reference in &rule.references {
+            out.push_str(&format!(
+                "  {}:{}  {} ({})\n",
+                reference.file, reference.line, reference.kind, reference.impl_name
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::{ApiSpecDependency, ApiStaleRef};
+    use tracey_core::parse_rule_id;
+
+    fn rule(id: &str, depends_on: &[(&str, &str)], impl_refs: &[(&str, usize)]) -> ApiRule {
+        ApiRule {
+            id: parse_rule_id(id).unwrap(),
+            raw: String::new(),
+            html: String::new(),
+            status: None,
+            level: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            permalink: None,
+            section: None,
+            section_title: None,
+            renamed_from: vec![],
+            depends_on: depends_on
+                .iter()
+                .map(|(spec, id)| ApiSpecDependency {
+                    spec: spec.to_string(),
+                    id: parse_rule_id(id).unwrap(),
+                })
+                .collect(),
+            refines: vec![],
+            impl_refs: impl_refs
+                .iter()
+                .map(|(file, line)| ApiCodeRef {
+                    file: file.to_string(),
+                    line: *line,
+                })
+                .collect(),
+            verify_refs: vec![],
+            depends_refs: vec![],
+            is_stale: false,
+            stale_refs: vec![],
+            owners: vec![],
+            approvals: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
+            tags: vec![],
+        }
+    }
+
+    fn forward(spec: &str, rules: Vec<ApiRule>) -> (ImplKey, ApiSpecForward) {
+        (
+            (spec.to_string(), "rust".to_string()),
+            ApiSpecForward {
+                name: spec.to_string(),
+                rules,
+            },
+        )
+    }
+
+    // r[verify cli.impact]
+    #[test]
+    fn follows_depends_to_the_code() {
+        let mut fetch = rule("api.fetch+2", &[], &[("src/fetch.rs", 3)]);
+        fetch.stale_refs.push(ApiStaleRef {
+            file: "src/old.rs".to_string(),
+            line: 9,
+            reference_id: parse_rule_id("api.fetch").unwrap(),
+        });
+        let forward = BTreeMap::from([
+            forward(
+                "api",
+                vec![fetch, rule("api.list", &[], &[("src/list.rs", 1)])],
+            ),
+            forward(
+                "app",
+                vec![
+                    rule("app.sync", &[("api", "api.fetch")], &[("src/sync.rs", 5)]),
+                    rule("app.ui", &[("app", "app.sync")], &[]),
+                    rule("app.other", &[("api", "api.list")], &[("src/other.rs", 2)]),
+                ],
+            ),
+        ]);
+        let changed = [ChangedText {
+            spec: "api".to_string(),
+            base: "api.fetch".to_string(),
+            diff: Some("Clients MUST **retry**.".to_string()),
+        }];
+        let report = analyze("main", &changed, &forward);
+
+        let rules: Vec<(&str, Option<&str>)> = report
+            .rules
+            .iter()
+            .map(|r| (r.id.as_str(), r.via.as_deref()))
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("api.fetch+2", None),
+                ("app.sync", Some("api:api.fetch+2")),
+                ("app.ui", Some("app:app.sync")),
+            ]
+        );
+        let references: Vec<(&str, &str, usize)> = report.rules[0]
+            .references
+            .iter()
+            .map(|r| (r.kind.as_str(), r.file.as_str(), r.line))
+            .collect();
+        assert_eq!(
+            references,
+            [("impl", "src/fetch.rs", 3), ("stale", "src/old.rs", 9)]
+        );
+        assert_eq!(report.rules[1].references[0].file, "src/sync.rs");
+
+        let text = format_report(&report);
+        assert!(text.contains("1 rule(s) changed since main, 2 more depend on them"));
+        assert!(text.contains("app:app.ui (depends on app:app.sync)\n  no references"));
+    }
+}
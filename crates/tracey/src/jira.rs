@@ -0,0 +1,747 @@
+//! Filing requirement debt as Jira issues, for `tracey sync jira`.
+//!
+//! r[impl cli.sync.jira]
+//!
+//! Every MUST-level rule that some impl doesn't implement, or references at
+//! an older version, gets one issue. Issues carry the labels `tracey` and
+//! `tracey-rule-<base id>`, so syncing again finds them whatever their
+//! summary says: it updates issues whose rule or problems changed, closes
+//! issues whose rule is now covered, and reopens them if it regresses.
+
+use std::collections::BTreeMap;
+use std::process::Stdio;
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+use tokio::io::AsyncWriteExt;
+use tracey_api::{ApiRule, ApiSpecForward};
+
+use crate::config::JiraConfig;
+use crate::data::ImplKey;
+
+/// Label on every issue tracey manages.
+pub const LABEL: &str = "tracey";
+
+/// Prefix of the label that ties an issue to a rule.
+pub const RULE_LABEL_PREFIX: &str = "tracey-rule-";
+
+/// What an issue for one rule should say.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket {
+    pub summary: String,
+    pub description: String,
+}
+
+/// A tracey-managed issue that already exists in Jira.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub key: String,
+    /// Base ID of the rule, from its `tracey-rule-` label
+    pub base: String,
+    /// False once the issue is in a "done" status
+    pub open: bool,
+    pub summary: String,
+    pub description: String,
+}
+
+/// One change to make in Jira.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Create {
+        base: String,
+        ticket: Ticket,
+    },
+    Update {
+        key: String,
+        base: String,
+        ticket: Ticket,
+    },
+    Reopen {
+        key: String,
+        base: String,
+        ticket: Ticket,
+    },
+    Close {
+        key: String,
+        base: String,
+    },
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Create { base, .. } => write!(f, "create an issue for {}", base),
+            Action::Update { key, base, .. } => write!(f, "update {} ({})", key, base),
+            Action::Reopen { key, base, .. } => write!(f, "reopen {} ({})", key, base),
+            Action::Close { key, base } => write!(f, "close {} ({}, now covered)", key, base),
+        }
+    }
+}
+
+/// What's wrong with one rule, across impls.
+struct Debt<'a> {
+    rule: &'a ApiRule,
+    /// `spec/impl` pairs without an implementation
+    unimplemented: Vec<String>,
+    /// `file:line (old id, in spec/impl)` for each stale reference
+    stale: Vec<String>,
+}
+
+/// The issue each MUST-level, non-deprecated rule with requirement debt
+/// should have, by base rule ID.
+pub fn tickets(forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>) -> BTreeMap<String, Ticket> {
+    let mut debts: BTreeMap<String, Debt> = BTreeMap::new();
+    for ((spec, impl_name), forward) in forward_by_impl {
+        for rule in &forward.rules {
+            if !crate::policy::is_must_level(rule) || rule.status.as_deref() == Some("deprecated") {
+                continue;
+            }
+            let unimplemented = rule.impl_refs.is_empty() && rule.stale_refs.is_empty();
+            if !unimplemented && rule.stale_refs.is_empty() {
+                continue;
+            }
+            let debt = debts.entry(rule.id.base.clone()).or_insert_with(|| Debt {
+                rule,
+                unimplemented: vec![],
+                stale: vec![],
+            });
+            if unimplemented {
+                debt.unimplemented.push(format!("{}/{}", spec, impl_name));
+            }
+            debt.stale.extend(rule.stale_refs.iter().map(|stale| {
+                format!(
+                    "{}:{} ({}, in {}/{})",
+                    stale.file, stale.line, stale.reference_id, spec, impl_name
+                )
+            }));
+        }
+    }
+    debts
+        .into_iter()
+        .map(|(base, debt)| (base, ticket(&debt)))
+        .collect()
+}
+
+fn ticket(debt: &Debt) -> Ticket {
+    let rule = debt.rule;
+    let problem = match (debt.unimplemented.is_empty(), debt.stale.is_empty()) {
+        (false, true) => "is not implemented",
+        (true, false) => "has stale references",
+        _ => "is not implemented and has stale references",
+    };
+    let source = match (&rule.source_file, rule.source_line) {
+        (Some(file), Some(line)) => format!(" ({}:{})", file, line),
+        (Some(file), None) => format!(" ({})", file),
+        _ => String::new(),
+    };
+
+    let mut description = format!(
+        "MUST-level requirement {}{}:\n\n{{quote}}\n{}\n{{quote}}\n",
+        rule.id,
+        source,
+        rule.raw.trim()
+    );
+    if !debt.unimplemented.is_empty() {
+        description.push_str(&format!(
+            "\n* Not implemented in: {}\n",
+            debt.unimplemented.join(", ")
+        ));
+    }
+    if !debt.stale.is_empty() {
+        description.push_str("\n* Stale references:\n");
+        for stale in &debt.stale {
+            description.push_str(&format!("** {}\n", stale));
+        }
+    }
+    description.push_str(
+        "\nFiled by {{tracey sync jira}}, which closes this issue once the requirement is covered.",
+    );
+
+    Ticket {
+        summary: format!("Requirement {} {}", rule.id, problem),
+        description,
+    }
+}
+
+/// The changes that bring the existing issues in line with `tickets`.
+pub fn plan(tickets: &BTreeMap<String, Ticket>, issues: &[Issue]) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for (base, ticket) in tickets {
+        // Prefer an open issue if someone filed a duplicate.
+        let existing = issues
+            .iter()
+            .filter(|issue| &issue.base == base)
+            .max_by_key(|issue| issue.open);
+        let Some(issue) = existing else {
+            actions.push(Action::Create {
+                base: base.clone(),
+                ticket: ticket.clone(),
+            });
+            continue;
+        };
+        let (key, base, ticket) = (issue.key.clone(), base.clone(), ticket.clone());
+        if !issue.open {
+            actions.push(Action::Reopen { key, base, ticket });
+        } else if issue.summary != ticket.summary
+            || issue.description.trim() != ticket.description.trim()
+        {
+            actions.push(Action::Update { key, base, ticket });
+        }
+    }
+    actions.extend(
+        issues
+            .iter()
+            .filter(|issue| issue.open && !tickets.contains_key(&issue.base))
+            .map(|issue| Action::Close {
+                key: issue.key.clone(),
+                base: issue.base.clone(),
+            }),
+    );
+    actions
+}
+
+#[derive(Facet)]
+#[facet(rename_all = "camelCase")]
+struct SearchResponse {
+    #[facet(default)]
+    issues: Vec<SearchIssue>,
+    #[facet(default)]
+    total: Option<usize>,
+    #[facet(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Facet)]
+struct SearchIssue {
+    key: String,
+    fields: IssueFields,
+}
+
+#[derive(Facet)]
+struct IssueFields {
+    #[facet(default)]
+    summary: Option<String>,
+    #[facet(default)]
+    description: Option<String>,
+    #[facet(default)]
+    labels: Vec<String>,
+    #[facet(default)]
+    status: Option<Status>,
+}
+
+#[derive(Facet)]
+#[facet(rename_all = "camelCase")]
+struct Status {
+    #[facet(default)]
+    status_category: Option<StatusCategory>,
+}
+
+#[derive(Facet)]
+struct StatusCategory {
+    key: String,
+}
+
+#[derive(Facet)]
+struct CreateResponse {
+    key: String,
+}
+
+#[derive(Facet)]
+struct TransitionsResponse {
+    transitions: Vec<Transition>,
+}
+
+#[derive(Facet)]
+struct Transition {
+    id: String,
+    name: String,
+}
+
+#[derive(Facet)]
+struct CreateRequest {
+    fields: CreateFields,
+}
+
+#[derive(Facet)]
+struct CreateFields {
+    project: KeyRef,
+    summary: String,
+    description: String,
+    issuetype: NameRef,
+    labels: Vec<String>,
+}
+
+#[derive(Facet)]
+struct UpdateRequest {
+    fields: UpdateFields,
+}
+
+#[derive(Facet)]
+struct UpdateFields {
+    summary: String,
+    description: String,
+}
+
+#[derive(Facet)]
+struct KeyRef {
+    key: String,
+}
+
+#[derive(Facet)]
+struct NameRef {
+    name: String,
+}
+
+#[derive(Facet)]
+struct TransitionRequest {
+    transition: IdRef,
+}
+
+#[derive(Facet)]
+struct IdRef {
+    id: String,
+}
+
+fn to_json<'a, T: Facet<'a>>(value: &T) -> String {
+    facet_json::to_string(value).expect("JSON serialization failed")
+}
+
+/// Quote a value for a curl config file.
+fn curl_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A Jira REST API client on top of the `curl` binary.
+pub struct JiraClient<'a> {
+    config: &'a JiraConfig,
+}
+
+impl<'a> JiraClient<'a> {
+    pub fn new(config: &'a JiraConfig) -> Self {
+        Self { config }
+    }
+
+    /// Jira Cloud replaced `/search` with `/search/jql`, which pages with a
+    /// token rather than an offset.
+    fn is_cloud(&self) -> bool {
+        self.config.url.contains(".atlassian.net")
+    }
+
+    /// Run curl with `args`. Credentials go in a config file on stdin so
+    /// the token never shows up in the process list.
+    async fn curl(&self, args: &[String]) -> Result<String> {
+        let auth = match &self.config.user {
+            Some(user) => format!(
+                "user = {}\n",
+                curl_quote(&format!("{}:{}", user, self.config.token))
+            ),
+            None => format!(
+                "header = {}\n",
+                curl_quote(&format!("Authorization: Bearer {}", self.config.token))
+            ),
+        };
+        let mut child = tokio::process::Command::new("curl")
+            .args([
+                "--silent",
+                "--show-error",
+                "--fail-with-body",
+                "--config",
+                "-",
+            ])
+            .args(["--header", "Accept: application/json"])
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .wrap_err("Failed to run curl (is it installed?)")?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        stdin.write_all(auth.as_bytes()).await?;
+        drop(stdin);
+        let output = child.wait_with_output().await?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if !output.status.success() {
+            bail!(
+                "Jira request failed: {} {}",
+                String::from_utf8_lossy(&output.stderr).trim(),
+                stdout.trim()
+            );
+        }
+        Ok(stdout)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/rest/api/2/{}",
+            self.config.url.trim_end_matches('/'),
+            path
+        )
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<String> {
+        let mut args = vec!["--get".to_string()];
+        for (name, value) in query {
+            args.push("--data-urlencode".to_string());
+            args.push(format!("{}={}", name, value));
+        }
+        args.push(self.url(path));
+        self.curl(&args).await
+    }
+
+    async fn send(&self, method: &str, path: &str, body: String) -> Result<String> {
+        let args = [
+            "--request".to_string(),
+            method.to_string(),
+            "--header".to_string(),
+            "Content-Type: application/json".to_string(),
+            "--data-binary".to_string(),
+            body,
+            self.url(path),
+        ];
+        self.curl(&args).await
+    }
+
+    /// Every issue in the project with the `tracey` label.
+    pub async fn issues(&self) -> Result<Vec<Issue>> {
+        let jql = format!(
+            "project = \"{}\" AND labels = {}",
+            self.config.project, LABEL
+        );
+        let path = if self.is_cloud() {
+            "search/jql"
+        } else {
+            "search"
+        };
+        let mut issues = Vec::new();
+        let mut page_token: Option<String> = None;
+        let mut fetched = 0;
+        loop {
+            let mut query = vec![
+                ("jql", jql.clone()),
+                ("fields", "summary,description,labels,status".to_string()),
+                ("maxResults", "100".to_string()),
+            ];
+            match &page_token {
+                Some(token) => query.push(("nextPageToken", token.clone())),
+                None if !self.is_cloud() => query.push(("startAt", fetched.to_string())),
+                None => {}
+            }
+            let body = self.get(path, &query).await?;
+            let page: SearchResponse = facet_json::from_str(&body)
+                .map_err(|e| eyre::eyre!("Unexpected Jira search response: {}", e))?;
+            let page_len = page.issues.len();
+            fetched += page_len;
+            issues.extend(page.issues.into_iter().filter_map(|issue| {
+                let base = issue
+                    .fields
+                    .labels
+                    .iter()
+                    .find_map(|label| label.strip_prefix(RULE_LABEL_PREFIX))?
+                    .to_string();
+                let done = issue
+                    .fields
+                    .status
+                    .and_then(|status| status.status_category)
+                    .is_some_and(|category| category.key == "done");
+                Some(Issue {
+                    key: issue.key,
+                    base,
+                    open: !done,
+                    summary: issue.fields.summary.unwrap_or_default(),
+                    description: issue.fields.description.unwrap_or_default(),
+                })
+            }));
+            if self.is_cloud() {
+                page_token = page.next_page_token;
+                if page_token.is_none() {
+                    break;
+                }
+            } else if page_len == 0 || page.total.is_none_or(|total| fetched >= total) {
+                break;
+            }
+        }
+        Ok(issues)
+    }
+
+    async fn transition(&self, key: &str, name: &str) -> Result<()> {
+        let path = format!("issue/{}/transitions", key);
+        let body = self.get(&path, &[]).await?;
+        let available: TransitionsResponse = facet_json::from_str(&body)
+            .map_err(|e| eyre::eyre!("Unexpected Jira transitions response: {}", e))?;
+        let Some(transition) = available
+            .transitions
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+        else {
+            let names: Vec<&str> = available
+                .transitions
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect();
+            bail!(
+                "{} has no '{}' transition (available: {})",
+                key,
+                name,
+                names.join(", ")
+            );
+        };
+        let request = TransitionRequest {
+            transition: IdRef {
+                id: transition.id.clone(),
+            },
+        };
+        self.send("POST", &path, to_json(&request)).await?;
+        Ok(())
+    }
+
+    async fn update(&self, key: &str, ticket: &Ticket) -> Result<()> {
+        let request = UpdateRequest {
+            fields: UpdateFields {
+                summary: ticket.summary.clone(),
+                description: ticket.description.clone(),
+            },
+        };
+        self.send("PUT", &format!("issue/{}", key), to_json(&request))
+            .await?;
+        Ok(())
+    }
+
+    /// Make one change; returns the key of the issue it touched.
+    pub async fn apply(&self, action: &Action) -> Result<String> {
+        match action {
+            Action::Create { base, ticket } => {
+                let request = CreateRequest {
+                    fields: CreateFields {
+                        project: KeyRef {
+                            key: self.config.project.clone(),
+                        },
+                        summary: ticket.summary.clone(),
+                        description: ticket.description.clone(),
+                        issuetype: NameRef {
+                            name: self
+                                .config
+                                .issue_type
+                                .clone()
+                                .unwrap_or_else(|| "Task".to_string()),
+                        },
+                        labels: vec![LABEL.to_string(), format!("{}{}", RULE_LABEL_PREFIX, base)],
+                    },
+                };
+                let body = self.send("POST", "issue", to_json(&request)).await?;
+                let created: CreateResponse = facet_json::from_str(&body)
+                    .map_err(|e| eyre::eyre!("Unexpected Jira create response: {}", e))?;
+                Ok(created.key)
+            }
+            Action::Update { key, ticket, .. } => {
+                self.update(key, ticket).await?;
+                Ok(key.clone())
+            }
+            Action::Reopen { key, ticket, .. } => {
+                let reopen = self.config.reopen_transition.as_deref().unwrap_or("To Do");
+                self.transition(key, reopen).await?;
+                self.update(key, ticket).await?;
+                Ok(key.clone())
+            }
+            Action::Close { key, .. } => {
+                let close = self.config.close_transition.as_deref().unwrap_or("Done");
+                self.transition(key, close).await?;
+                Ok(key.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::{ApiCodeRef, ApiStaleRef};
+    use tracey_core::parse_rule_id;
+
+    fn rule(id: &str, raw: &str, impl_refs: usize, stale: Option<&str>) -> ApiRule {
+        ApiRule {
+            id: parse_rule_id(id).unwrap(),
+            raw: raw.to_string(),
+            html: String::new(),
+            status: None,
+            level: None,
+            source_file: Some("docs/spec.md".to_string()),
+            source_line: Some(3),
+            source_column: None,
+            permalink: None,
+            section: None,
+            section_title: None,
+            renamed_from: vec![],
+            depends_on: vec![],
+            refines: vec![],
+            impl_refs: vec![
+                ApiCodeRef {
+                    file: "src/lib.rs".to_string(),
+                    line: 1,
+                };
+                impl_refs
+            ],
+            verify_refs: vec![],
+            depends_refs: vec![],
+            is_stale: stale.is_some(),
+            stale_refs: stale
+                .map(|old| ApiStaleRef {
+                    file: "src/old.rs".to_string(),
+                    line: 7,
+                    reference_id: parse_rule_id(old).unwrap(),
+                })
+                .into_iter()
+                .collect(),
+            owners: vec![],
+            approvals: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
+            tags: vec![],
+        }
+    }
+
+    fn forward(impls: Vec<(&str, Vec<ApiRule>)>) -> BTreeMap<ImplKey, ApiSpecForward> {
+        impls
+            .into_iter()
+            .map(|(impl_name, rules)| {
+                (
+                    ("proto".to_string(), impl_name.to_string()),
+                    ApiSpecForward {
+                        name: "proto".to_string(),
+                        rules,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tickets_cover_must_rules_with_debt() {
+        let mut deprecated = rule("auth.legacy", "Clients MUST send a cookie.", 0, None);
+        deprecated.status = Some("deprecated".to_string());
+        let data = forward(vec![
+            (
+                "rust",
+                vec![
+                    rule("auth.login+2", "Users MUST log in.", 0, None),
+                    rule("auth.logout", "Users MUST be able to log out.", 1, None),
+                    rule("auth.theme", "Users MAY pick a theme.", 0, None),
+                    deprecated.clone(),
+                ],
+            ),
+            (
+                "ts",
+                vec![
+                    rule("auth.login+2", "Users MUST log in.", 0, Some("auth.login")),
+                    rule("auth.logout", "Users MUST be able to log out.", 1, None),
+                    rule("auth.theme", "Users MAY pick a theme.", 0, None),
+                    deprecated,
+                ],
+            ),
+        ]);
+
+        let tickets = tickets(&data);
+        assert_eq!(tickets.keys().collect::<Vec<_>>(), ["auth.login"]);
+        let ticket = &tickets["auth.login"];
+        assert_eq!(
+            ticket.summary,
+            "Requirement auth.login+2 is not implemented and has stale references"
+        );
+        assert!(
+            ticket.description.contains("auth.login+2 (docs/spec.md:3)"),
+            "{}",
+            ticket.description
+        );
+        assert!(
+            ticket
+                .description
+                .contains("Not implemented in: proto/rust\n")
+        );
+        assert!(
+            ticket
+                .description
+                .contains("** src/old.rs:7 (auth.login, in proto/ts)")
+        );
+    }
+
+    #[test]
+    fn plan_creates_updates_reopens_and_closes() {
+        let ticket = |summary: &str| Ticket {
+            summary: summary.to_string(),
+            description: "Details.".to_string(),
+        };
+        let issue = |key: &str, base: &str, open: bool, summary: &str| Issue {
+            key: key.to_string(),
+            base: base.to_string(),
+            open,
+            summary: summary.to_string(),
+            description: "Details.\n".to_string(),
+        };
+        let tickets: BTreeMap<String, Ticket> = [
+            (
+                "auth.login",
+                ticket("Requirement auth.login is not implemented"),
+            ),
+            (
+                "auth.logout",
+                ticket("Requirement auth.logout+2 is not implemented"),
+            ),
+            (
+                "auth.mfa",
+                ticket("Requirement auth.mfa is not implemented"),
+            ),
+            (
+                "auth.token",
+                ticket("Requirement auth.token has stale references"),
+            ),
+        ]
+        .into_iter()
+        .map(|(base, ticket)| (base.to_string(), ticket))
+        .collect();
+        let issues = [
+            issue(
+                "P-1",
+                "auth.login",
+                true,
+                "Requirement auth.login is not implemented",
+            ),
+            issue(
+                "P-2",
+                "auth.logout",
+                true,
+                "Requirement auth.logout is not implemented",
+            ),
+            issue(
+                "P-3",
+                "auth.mfa",
+                false,
+                "Requirement auth.mfa is not implemented",
+            ),
+            issue(
+                "P-4",
+                "auth.session",
+                true,
+                "Requirement auth.session is not implemented",
+            ),
+            issue(
+                "P-5",
+                "auth.old",
+                false,
+                "Requirement auth.old is not implemented",
+            ),
+        ];
+
+        let actions = plan(&tickets, &issues);
+        let described: Vec<String> = actions.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            described,
+            [
+                "update P-2 (auth.logout)",
+                "reopen P-3 (auth.mfa)",
+                "create an issue for auth.token",
+                "close P-4 (auth.session, now covered)",
+            ]
+        );
+        assert_eq!(plan(&tickets, &[]).len(), 4);
+        assert_eq!(plan(&BTreeMap::new(), &issues).len(), 3);
+    }
+}
@@ -3,20 +3,61 @@
 //! This library exposes the core functionality of tracey for testing
 //! and embedding purposes.
 
+pub mod approvals;
+pub mod baseline;
+pub mod bench;
+pub mod bisect;
+pub mod blame;
 pub mod bridge;
 pub mod bump;
+pub mod bundle;
+pub mod cargo;
+pub mod changelog;
+pub mod compliance;
 pub mod config;
 pub mod daemon;
 pub mod data;
+pub mod dead;
+pub mod exit_code;
+pub mod export;
+pub mod file_limits;
+pub mod fmt;
+pub mod history;
+pub mod hooks;
+pub mod hyperlink;
+pub mod impact;
+pub mod jira;
+pub mod line_coverage;
+pub mod lsp_install;
+pub mod mcp_config;
+pub mod merge;
+pub mod migrate;
+pub mod owners;
+pub mod permalink;
+pub mod plugins;
+pub mod policy;
+pub mod redirects;
+pub mod refinement;
+pub mod release;
+pub mod remote;
 pub(crate) mod rule_suggestions;
+pub mod scopes;
 pub mod search;
 pub mod server;
+pub mod skip_units;
+pub mod spec_deps;
+pub mod spec_diff;
+pub mod spec_lint;
+pub mod test_report;
+pub mod todo;
+pub mod trace_ingest;
+pub mod variants;
 pub mod vite;
 
 use config::Config;
 use eyre::{Result, WrapErr};
 use std::path::PathBuf;
-use tracey_core::ReqDefinition;
+use tracey_core::{ReqDefinition, RuleId};
 
 // Re-export from marq for rule extraction
 use marq::{RenderOptions, render};
@@ -34,6 +75,8 @@ pub struct ExtractedRule {
     pub section: Option<String>,
     /// Section title (heading text) that this rule belongs to
     pub section_title: Option<String>,
+    /// IDs the rule was previously known by (`r[new.id <- old.id]`)
+    pub renamed_from: Vec<RuleId>,
 }
 
 /// Compute 1-indexed column from byte offset in content
@@ -45,7 +88,10 @@ fn compute_column(content: &str, byte_offset: usize) -> usize {
     before[line_start..].chars().count() + 1
 }
 
-fn extract_marker_prefix(content: &str, marker_span: marq::SourceSpan) -> Option<String> {
+pub(crate) fn extract_marker_prefix(
+    content: &str,
+    marker_span: marq::SourceSpan,
+) -> Option<String> {
     let start = marker_span.offset;
     let end = start.checked_add(marker_span.length)?;
     let marker = content.get(start..end)?;
@@ -115,6 +161,7 @@ pub async fn load_rules_from_glob(
         .follow_links(true)
         .hidden(false)
         .git_ignore(true)
+        .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
         .build();
 
     let glob_matcher = globset::Glob::new(&effective_pattern)
@@ -152,9 +199,13 @@ pub async fn load_rules_from_glob(
         let content = std::fs::read_to_string(path)
             .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
 
-        let doc = render(&content, &RenderOptions::default())
-            .await
-            .map_err(|e| eyre::eyre!("Failed to process {}: {}", path.display(), e))?;
+        let redirects = redirects::parse_redirects(&content);
+        let doc = render(
+            &redirects::strip_redirects(&content),
+            &RenderOptions::default(),
+        )
+        .await
+        .map_err(|e| eyre::eyre!("Failed to process {}: {}", path.display(), e))?;
 
         if !doc.reqs.is_empty() {
             if !quiet {
@@ -218,6 +269,7 @@ pub async fn load_rules_from_glob(
                 let (section, section_title) = rule_sections
                     .remove(&req.id.to_string())
                     .unwrap_or((None, None));
+                let renamed_from = redirects::renamed_from(&redirects, &req.id.to_string());
                 rules.push(ExtractedRule {
                     def: req,
                     source_file: display_path.clone(),
@@ -225,6 +277,7 @@ pub async fn load_rules_from_glob(
                     column,
                     section,
                     section_title,
+                    renamed_from,
                 });
             }
         }
@@ -233,7 +286,8 @@ pub async fn load_rules_from_glob(
     Ok(rules)
 }
 
-/// Load rules from multiple glob patterns
+/// Load rules from multiple glob patterns. `!pattern` entries remove the
+/// files they match again, the last matching pattern deciding.
 pub async fn load_rules_from_globs(
     root: &std::path::Path,
     patterns: &[&str],
@@ -244,13 +298,23 @@ pub async fn load_rules_from_globs(
 
     let mut all_rules: Vec<ExtractedRule> = Vec::new();
     let mut seen_ids: HashSet<String> = HashSet::new();
+    // r[impl config.impl.include.negation]
+    let selected = patterns
+        .iter()
+        .any(|p| p.starts_with('!'))
+        .then(|| bump::glob_set(&patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>()));
 
-    for pattern in patterns {
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
         let rules = load_rules_from_glob(root, pattern, quiet).await?;
 
         // r[impl validation.duplicates]
         // Check for duplicates across patterns
         for extracted in rules {
+            if let Some(selected) = &selected
+                && !selected.is_match(&extracted.source_file)
+            {
+                continue;
+            }
             let def_id = extracted.def.id.to_string();
             if seen_ids.contains(&def_id) {
                 eyre::bail!(
@@ -267,9 +331,51 @@ pub async fn load_rules_from_globs(
     Ok(all_rules)
 }
 
-/// Walk upward from `start` looking for a directory containing a `Cargo.toml`.
-/// Falls back to `start` itself if none is found.
+/// Location of the tracey config file, relative to a project root.
+pub const CONFIG_PATH: &str = ".config/tracey/config.styx";
+
+/// Environment variable that overrides project root discovery.
+pub const PROJECT_ROOT_ENV: &str = "TRACEY_PROJECT_ROOT";
+
+/// Walk upward from `start` looking for the nearest directory with its own
+/// tracey config.
+///
+/// r[impl config.discovery.nearest]
+pub fn find_config_root_from(start: &std::path::Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(CONFIG_PATH).is_file())
+        .map(|dir| dir.to_path_buf())
+}
+
+/// List every tracey project at or below `root`, i.e. directories that have
+/// their own config. Respects `.gitignore`.
+///
+/// r[impl config.discovery.nested]
+pub fn discover_projects(root: &std::path::Path) -> Vec<PathBuf> {
+    let mut projects: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .follow_links(false)
+        .hidden(false)
+        .git_ignore(true)
+        .add_custom_ignore_filename(tracey_core::IGNORE_FILENAME)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_dir()))
+        .filter(|entry| entry.path().join(CONFIG_PATH).is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    projects.sort();
+    projects
+}
+
+/// Find the project root for `start`: the nearest directory with a tracey
+/// config, otherwise the nearest directory containing a `Cargo.toml`.
+/// Falls back to `start` itself if neither is found.
 pub fn find_project_root_from(start: &std::path::Path) -> PathBuf {
+    if let Some(root) = find_config_root_from(start) {
+        return root;
+    }
     let mut current = start.to_path_buf();
     loop {
         if current.join("Cargo.toml").exists() {
@@ -281,7 +387,13 @@ pub fn find_project_root_from(start: &std::path::Path) -> PathBuf {
     }
 }
 
+/// Find the project root for the current directory, unless
+/// `TRACEY_PROJECT_ROOT` names one (as `cargo tracey` does with the cargo
+/// workspace root).
 pub fn find_project_root() -> Result<PathBuf> {
+    if let Some(root) = std::env::var_os(PROJECT_ROOT_ENV) {
+        return Ok(PathBuf::from(root));
+    }
     let cwd = std::env::current_dir().wrap_err("Failed to get current directory")?;
     Ok(find_project_root_from(&cwd))
 }
@@ -310,12 +422,34 @@ pub fn load_config(path: &PathBuf) -> Result<Config> {
     let content = std::fs::read_to_string(path)
         .wrap_err_with(|| format!("Failed to read config file: {}", path.display()))?;
 
-    let config: Config = facet_styx::from_str(&content)
+    let config = config::parse_config(&content)
         .wrap_err_with(|| format!("Failed to parse config file: {}", path.display()))?;
 
     Ok(config)
 }
 
+/// Write `config` to `path`. Comments and formatting of the previous file
+/// are not kept.
+///
+/// Refuses to overwrite a file that uses `${VAR}` interpolation, since
+/// `config` holds the expanded values and writing them back would replace
+/// the references (and leak any secrets they pull in).
+pub fn save_config(path: &std::path::Path, config: &Config) -> Result<()> {
+    use std::io::Write;
+    if let Ok(existing) = std::fs::read_to_string(path)
+        && config::uses_interpolation(&existing)
+    {
+        eyre::bail!(
+            "{} uses ${{VAR}} interpolation; edit it by hand instead",
+            path.display()
+        );
+    }
+    let styx_string = facet_styx::to_string(config)?;
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(styx_string.as_bytes())?;
+    Ok(())
+}
+
 /// r[impl config.optional]
 /// Load config if it exists, otherwise return default empty config.
 /// This allows services to start without a config file.
@@ -325,7 +459,7 @@ pub fn load_config_or_default(path: &PathBuf) -> Config {
     }
 
     match std::fs::read_to_string(path) {
-        Ok(content) => facet_styx::from_str(&content).unwrap_or_default(),
+        Ok(content) => config::parse_config(&content).unwrap_or_default(),
         Err(_) => Config::default(),
     }
 }
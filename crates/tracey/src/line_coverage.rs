@@ -0,0 +1,384 @@
+//! Line coverage from LCOV reports, for `tracey line-coverage ingest`.
+//!
+//! r[impl cli.line-coverage]
+//!
+//! An `impl` reference says which code implements a rule; line coverage says
+//! whether the tests ever ran that code. Ingesting a report records the
+//! executed and unexecuted lines of every file, and each `impl` reference is
+//! checked against the lines of the code unit it annotates. The data lives
+//! in `.tracey/line-coverage.json` until the next ingest replaces it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+use tracey_api::{ApiLineCoverage, ApiRule};
+use tracey_core::code_units::CodeUnit;
+use tracey_proto::{UnexecutedRule, UnexecutedSection};
+
+/// Version of the line coverage file layout.
+pub const LINE_COVERAGE_VERSION: u32 = 1;
+
+/// The executed lines of every file in one ingested report.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct LineCoverageReport {
+    pub version: u32,
+    /// The report file, as given to `ingest`
+    pub source: String,
+    /// When the report was ingested (Unix seconds)
+    pub ingested_at: u64,
+    pub files: Vec<FileLines>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct FileLines {
+    /// Path relative to the project root, or as the report gives it for
+    /// files outside the project
+    pub path: String,
+    /// Lines that ran at least once
+    pub executed: Vec<usize>,
+    /// Instrumented lines that never ran
+    pub missed: Vec<usize>,
+}
+
+/// Where the last ingested report is kept.
+pub fn line_coverage_path(project_root: &Path) -> PathBuf {
+    project_root.join(".tracey").join("line-coverage.json")
+}
+
+impl LineCoverageReport {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let report: Self = facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid line coverage {}: {}", path.display(), e))?;
+        if report.version != LINE_COVERAGE_VERSION {
+            bail!(
+                "Line coverage {} has version {}, expected {}; run `tracey line-coverage ingest`",
+                path.display(),
+                report.version,
+                LINE_COVERAGE_VERSION
+            );
+        }
+        Ok(report)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = facet_json::to_string_pretty(self).expect("JSON serialization failed");
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Parse the LCOV `report` and store its line data for the project,
+/// replacing the previous one.
+pub fn ingest(project_root: &Path, report: &Path) -> Result<LineCoverageReport> {
+    let content = std::fs::read_to_string(report)
+        .wrap_err_with(|| format!("Failed to read {}", report.display()))?;
+    let files = parse_lcov(&content, project_root)
+        .wrap_err_with(|| format!("Failed to parse {}", report.display()))?;
+    if files.is_empty() {
+        bail!("No line coverage found in {}", report.display());
+    }
+    let report = LineCoverageReport {
+        version: LINE_COVERAGE_VERSION,
+        source: report.display().to_string(),
+        ingested_at: crate::history::now_secs(),
+        files,
+    };
+    report.save(&line_coverage_path(project_root))?;
+    Ok(report)
+}
+
+/// Parse the `SF:` and `DA:` records of an LCOV report, as written by
+/// `cargo llvm-cov --lcov`, `llvm-cov export -format=lcov`, `c8`, `gcovr`
+/// and others. Records for the same file are merged.
+pub fn parse_lcov(content: &str, project_root: &Path) -> Result<Vec<FileLines>> {
+    let canonical_root = project_root.canonicalize().ok();
+    let roots: Vec<&Path> = canonical_root
+        .as_deref()
+        .into_iter()
+        .chain([project_root])
+        .collect();
+
+    let mut files: BTreeMap<String, BTreeMap<usize, bool>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some(project_path(Path::new(path), &roots));
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let Some(file) = &current else {
+                bail!("Line {}: DA record outside a file record", index + 1);
+            };
+            let mut fields = data.split(',');
+            let (Some(Ok(number)), Some(Ok(count))) = (
+                fields.next().map(str::parse::<usize>),
+                fields.next().map(|c| c.trim().parse::<f64>()),
+            ) else {
+                bail!("Line {}: malformed DA record {:?}", index + 1, line);
+            };
+            let executed = files.entry(file.clone()).or_default().entry(number);
+            *executed.or_default() |= count > 0.0;
+        } else if line == "end_of_record" {
+            current = None;
+        }
+    }
+
+    Ok(files
+        .into_iter()
+        .map(|(path, lines)| {
+            let (executed, missed): (Vec<_>, Vec<_>) = lines.into_iter().partition(|(_, ran)| *ran);
+            FileLines {
+                path,
+                executed: executed.into_iter().map(|(line, _)| line).collect(),
+                missed: missed.into_iter().map(|(line, _)| line).collect(),
+            }
+        })
+        .collect())
+}
+
+/// A report path relative to the project root, as references name files.
+fn project_path(path: &Path, roots: &[&Path]) -> String {
+    let relative = roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let relative = relative.strip_prefix("./").unwrap_or(relative);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// The last ingested report, by file.
+#[derive(Debug, Default)]
+pub struct LineCoverage {
+    by_file: HashMap<String, (BTreeSet<usize>, BTreeSet<usize>)>,
+}
+
+impl LineCoverage {
+    /// Load the project's last ingested report, if there is one.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = line_coverage_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(LineCoverageReport::load(&path)?.files)))
+    }
+
+    pub fn new(files: Vec<FileLines>) -> Self {
+        let by_file = files
+            .into_iter()
+            .map(|f| {
+                let lines = (
+                    f.executed.into_iter().collect(),
+                    f.missed.into_iter().collect(),
+                );
+                (f.path, lines)
+            })
+            .collect();
+        Self { by_file }
+    }
+
+    /// Executed and instrumented lines of `file` between `start` and `end`.
+    pub fn lines_in(&self, file: &str, start: usize, end: usize) -> (usize, usize) {
+        let Some((executed, missed)) = self.by_file.get(file) else {
+            return (0, 0);
+        };
+        let executed = executed.range(start..=end).count();
+        (executed, executed + missed.range(start..=end).count())
+    }
+
+    /// Line coverage of the code unit behind each of the rule's `impl`
+    /// references that the report has data for. `units_by_file` are the
+    /// impl's code units, keyed by path under `project_root`.
+    pub fn coverage_for(
+        &self,
+        rule: &ApiRule,
+        project_root: &Path,
+        units_by_file: &BTreeMap<PathBuf, Vec<CodeUnit>>,
+    ) -> Vec<ApiLineCoverage> {
+        rule.impl_refs
+            .iter()
+            .filter_map(|code_ref| {
+                let units = units_by_file.get(&project_root.join(&code_ref.file))?;
+                let unit = annotated_unit(units, &rule.id.base, code_ref.line)?;
+                let (executed_lines, total_lines) =
+                    self.lines_in(&code_ref.file, unit.start_line, unit.end_line);
+                (total_lines > 0).then(|| ApiLineCoverage {
+                    file: code_ref.file.clone(),
+                    line: code_ref.line,
+                    unit: unit.name.clone(),
+                    executed_lines,
+                    total_lines,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The code unit a reference to `base` on `line` belongs to: the closest one
+/// whose comments reference the rule, else the innermost one containing the
+/// line.
+fn annotated_unit<'a>(units: &'a [CodeUnit], base: &str, line: usize) -> Option<&'a CodeUnit> {
+    let distance = |u: &CodeUnit| {
+        if line < u.start_line {
+            u.start_line - line
+        } else {
+            line.saturating_sub(u.end_line)
+        }
+    };
+    units
+        .iter()
+        .filter(|u| u.req_refs.iter().any(|r| r.base == base))
+        .min_by_key(|u| (distance(u), u.end_line - u.start_line))
+        .or_else(|| {
+            units
+                .iter()
+                .filter(|u| u.start_line <= line && line <= u.end_line)
+                .min_by_key(|u| u.end_line - u.start_line)
+        })
+}
+
+/// Whether the report has data for the rule's implementing code and none of
+/// it ever ran.
+pub fn never_executed(rule: &ApiRule) -> bool {
+    !rule.line_coverage.is_empty() && rule.line_coverage.iter().all(|c| c.executed_lines == 0)
+}
+
+/// Rules whose implementing code never ran, optionally only those whose ID
+/// starts with `prefix`, grouped by section. Also returns how many of the
+/// matching rules the report had data for.
+// r[impl daemon.cli.query.unexecuted]
+pub fn find_unexecuted(rules: &[ApiRule], prefix: Option<&str>) -> (usize, Vec<UnexecutedSection>) {
+    let prefix = prefix.map(str::to_lowercase);
+    let measured: Vec<&ApiRule> = rules
+        .iter()
+        .filter(|r| {
+            prefix
+                .as_deref()
+                .is_none_or(|p| r.id.base.to_lowercase().starts_with(p))
+        })
+        .filter(|r| !r.line_coverage.is_empty())
+        .collect();
+
+    let mut sections: Vec<UnexecutedSection> = Vec::new();
+    for rule in measured.iter().filter(|r| never_executed(r)) {
+        let section = rule.section_title.as_deref().unwrap_or("Uncategorized");
+        let entry = UnexecutedRule {
+            id: rule.id.clone(),
+            line_coverage: rule.line_coverage.clone(),
+        };
+        match sections.last_mut() {
+            Some(last) if last.section == section => last.rules.push(entry),
+            _ => sections.push(UnexecutedSection {
+                section: section.to_string(),
+                rules: vec![entry],
+            }),
+        }
+    }
+    (measured.len(), sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::code_units::CodeUnitKind;
+
+    #[test]
+    fn parses_and_merges_lcov_records() {
+        let root = Path::new("/work/project");
+        let lcov = "\
+TN:
+SF:/work/project/src/auth.rs
+FN:3,login
+DA:3,1
+DA:4,0
+DA:5,2
+end_of_record
+SF:/work/project/src/auth.rs
+DA:4,7
+DA:9,0
+end_of_record
+SF:src/session.rs
+DA:1,0
+LH:0
+LF:1
+end_of_record
+SF:/usr/lib/rustlib/src/core.rs
+DA:10,1
+end_of_record
+";
+        let files = parse_lcov(lcov, root).unwrap();
+        assert_eq!(
+            files,
+            [
+                FileLines {
+                    path: "/usr/lib/rustlib/src/core.rs".to_string(),
+                    executed: vec![10],
+                    missed: vec![],
+                },
+                FileLines {
+                    path: "src/auth.rs".to_string(),
+                    executed: vec![3, 4, 5],
+                    missed: vec![9],
+                },
+                FileLines {
+                    path: "src/session.rs".to_string(),
+                    executed: vec![],
+                    missed: vec![1],
+                },
+            ]
+        );
+
+        let error = parse_lcov("DA:1,1\n", root).unwrap_err();
+        assert!(
+            error.to_string().contains("outside a file record"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn measures_the_annotated_unit() {
+        let unit = |name: &str, lines: (usize, usize), refs: &[&str]| CodeUnit {
+            kind: CodeUnitKind::Function,
+            name: Some(name.to_string()),
+            file: PathBuf::from("src/auth.rs"),
+            start_line: lines.0,
+            end_line: lines.1,
+            start_byte: 0,
+            end_byte: 0,
+            req_refs: refs
+                .iter()
+                .map(|r| tracey_core::parse_rule_id(r).expect("valid rule id"))
+                .collect(),
+            scopes: vec![],
+        };
+        let units = [
+            unit("login", (3, 9), &["auth.login"]),
+            unit("logout", (12, 20), &["auth.logout"]),
+        ];
+        assert_eq!(
+            annotated_unit(&units, "auth.login", 2).and_then(|u| u.name.as_deref()),
+            Some("login")
+        );
+        assert_eq!(
+            annotated_unit(&units, "auth.session", 15).and_then(|u| u.name.as_deref()),
+            Some("logout")
+        );
+        assert!(annotated_unit(&units, "auth.session", 30).is_none());
+
+        let coverage = LineCoverage::new(vec![FileLines {
+            path: "src/auth.rs".to_string(),
+            executed: vec![3, 4],
+            missed: vec![5, 13, 14],
+        }]);
+        assert_eq!(coverage.lines_in("src/auth.rs", 3, 9), (2, 3));
+        assert_eq!(coverage.lines_in("src/auth.rs", 12, 20), (0, 2));
+        assert_eq!(coverage.lines_in("src/other.rs", 1, 10), (0, 0));
+    }
+}
@@ -0,0 +1,380 @@
+//! Editor configuration for the tracey language server.
+//!
+//! r[impl cli.lsp.install]
+//!
+//! `tracey lsp install` registers `tracey lsp` in the project's own config
+//! file of each chosen editor, for the file types the project contains, so
+//! the whole team gets the language server by committing one file. JSON
+//! configs (VS Code, Zed) are merged key by key and left alone if they
+//! have comments; Lua and TOML configs (Neovim, Helix) get a block between
+//! marker comments that later runs replace in place.
+
+use std::path::Path;
+
+use eyre::Result;
+use serde_json::{Map, Value, json};
+
+use crate::bump::Target;
+
+/// An editor `tracey lsp install` can configure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Editor {
+    /// Through the Generic LSP Client (`glspc`) extension
+    VsCode,
+    /// Neovim 0.11+, through the project's `.nvim.lua` (read with `exrc`)
+    Neovim,
+    Helix,
+    /// Configures the tracey extension's `tracey-lsp` server
+    Zed,
+}
+
+impl Editor {
+    pub const ALL: [Editor; 4] = [Editor::VsCode, Editor::Neovim, Editor::Helix, Editor::Zed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Editor::VsCode => "vscode",
+            Editor::Neovim => "neovim",
+            Editor::Helix => "helix",
+            Editor::Zed => "zed",
+        }
+    }
+
+    /// The project config file, relative to the project root.
+    pub fn config_path(self) -> &'static str {
+        match self {
+            Editor::VsCode => ".vscode/settings.json",
+            Editor::Neovim => ".nvim.lua",
+            Editor::Helix => ".helix/languages.toml",
+            Editor::Zed => ".zed/settings.json",
+        }
+    }
+}
+
+/// A file type tracey annotates, with its name in each editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// File extension
+    pub extension: &'static str,
+    /// VS Code language id, which is also the Neovim filetype
+    pub vscode: &'static str,
+    pub helix: &'static str,
+    /// The server Helix runs for the language by default, kept alongside
+    /// tracey since a `language-servers` list replaces the default one
+    pub helix_server: &'static str,
+    pub zed: &'static str,
+}
+
+const fn language(
+    extension: &'static str,
+    vscode: &'static str,
+    helix: &'static str,
+    helix_server: &'static str,
+    zed: &'static str,
+) -> Language {
+    Language {
+        extension,
+        vscode,
+        helix,
+        helix_server,
+        zed,
+    }
+}
+
+pub const LANGUAGES: &[Language] = &[
+    language("rs", "rust", "rust", "rust-analyzer", "Rust"),
+    language(
+        "ts",
+        "typescript",
+        "typescript",
+        "typescript-language-server",
+        "TypeScript",
+    ),
+    language(
+        "tsx",
+        "typescriptreact",
+        "tsx",
+        "typescript-language-server",
+        "TSX",
+    ),
+    language(
+        "js",
+        "javascript",
+        "javascript",
+        "typescript-language-server",
+        "JavaScript",
+    ),
+    language(
+        "jsx",
+        "javascriptreact",
+        "jsx",
+        "typescript-language-server",
+        "JavaScript",
+    ),
+    language("py", "python", "python", "pylsp", "Python"),
+    language("go", "go", "go", "gopls", "Go"),
+    language("swift", "swift", "swift", "sourcekit-lsp", "Swift"),
+    language("java", "java", "java", "jdtls", "Java"),
+    language("md", "markdown", "markdown", "marksman", "Markdown"),
+];
+
+const BLOCK_START: &str = "tracey lsp install: start";
+const BLOCK_END: &str = "tracey lsp install: end";
+
+/// The languages of the files in `files` (one path per line), always
+/// including markdown for the specs.
+pub fn project_languages(files: &str) -> Vec<Language> {
+    LANGUAGES
+        .iter()
+        .filter(|language| {
+            language.extension == "md"
+                || files.lines().any(|file| {
+                    Path::new(file)
+                        .extension()
+                        .is_some_and(|ext| ext == language.extension)
+                })
+        })
+        .copied()
+        .collect()
+}
+
+/// A config file to create or update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// Relative to the project root
+    pub path: &'static str,
+    /// `None` if the file doesn't exist yet
+    pub before: Option<String>,
+    pub after: String,
+}
+
+/// The change that registers the language server with `editor` in the
+/// project at `project_root`, or `None` if it's already registered.
+pub fn configure_project(project_root: &Path, editor: Editor) -> Result<Option<ConfigChange>> {
+    let files = Target::WorkTree.files(project_root).unwrap_or_default();
+    let languages = project_languages(&files);
+    let path = editor.config_path();
+    let before = Target::WorkTree.read(project_root, path)?;
+    let after = configure(editor, before.as_deref(), &languages)?;
+    Ok(after.map(|after| ConfigChange {
+        path,
+        before,
+        after,
+    }))
+}
+
+/// The content of `editor`'s config file once it runs tracey for
+/// `languages`, given its current content. Returns `None` if nothing
+/// changes.
+pub fn configure(
+    editor: Editor,
+    existing: Option<&str>,
+    languages: &[Language],
+) -> Result<Option<String>> {
+    let updated = match editor {
+        Editor::VsCode => {
+            let ids: Vec<Value> = languages.iter().map(|l| json!(l.vscode)).collect();
+            merge_json(
+                existing,
+                &[
+                    (vec!["glspc.server.command"], json!("tracey")),
+                    (vec!["glspc.server.commandArguments"], json!(["lsp"])),
+                    (vec!["glspc.server.languageId"], Value::Array(ids)),
+                ],
+            )?
+        }
+        Editor::Zed => {
+            let mut entries = vec![(
+                vec!["lsp", "tracey-lsp", "binary"],
+                json!({ "path": "tracey", "arguments": ["lsp"] }),
+            )];
+            for language in languages {
+                entries.push((
+                    vec!["languages", language.zed, "language_servers"],
+                    json!(["tracey-lsp", "..."]),
+                ));
+            }
+            merge_json(existing, &entries)?
+        }
+        Editor::Neovim => {
+            let filetypes: Vec<String> = languages
+                .iter()
+                .map(|l| format!("'{}'", l.vscode))
+                .collect();
+            let block = format!(
+                "-- {BLOCK_START}\n\
+                 vim.lsp.config['tracey'] = {{\n    \
+                 cmd = {{ 'tracey', 'lsp' }},\n    \
+                 filetypes = {{ {} }},\n    \
+                 root_markers = {{ '.config/tracey/config.styx' }},\n\
+                 }}\n\
+                 vim.lsp.enable('tracey')\n\
+                 -- {BLOCK_END}\n",
+                filetypes.join(", ")
+            );
+            replace_block(existing.unwrap_or(""), &block)
+        }
+        Editor::Helix => {
+            let mut block = format!(
+                "# {BLOCK_START}\n\
+                 [language-server.tracey]\n\
+                 command = \"tracey\"\n\
+                 args = [\"lsp\"]\n"
+            );
+            let mut seen = Vec::new();
+            for language in languages {
+                if seen.contains(&language.helix) {
+                    continue;
+                }
+                seen.push(language.helix);
+                block.push_str(&format!(
+                    "\n[[language]]\nname = \"{}\"\nlanguage-servers = [\"{}\", \"tracey\"]\n",
+                    language.helix, language.helix_server
+                ));
+            }
+            block.push_str(&format!("# {BLOCK_END}\n"));
+            replace_block(existing.unwrap_or(""), &block)
+        }
+    };
+    Ok((existing != Some(updated.as_str())).then_some(updated))
+}
+
+/// Set each `(path, value)` in a JSON object, creating the objects along
+/// the path. An array value is merged into an existing array, keeping its
+/// items.
+fn merge_json(existing: Option<&str>, entries: &[(Vec<&str>, Value)]) -> Result<String> {
+    let mut root = match existing.map(str::trim) {
+        None | Some("") => Map::new(),
+        Some(content) => match serde_json::from_str::<Value>(content) {
+            Ok(Value::Object(root)) => root,
+            Ok(_) => eyre::bail!("expected a JSON object at the top level"),
+            Err(e) => eyre::bail!("can't parse it as JSON ({e}); add the settings by hand"),
+        },
+    };
+    for (path, value) in entries {
+        let (last, parents) = path.split_last().expect("empty JSON path");
+        let mut object = &mut root;
+        for key in parents {
+            let child = object
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            let Value::Object(child) = child else {
+                eyre::bail!("`{}` is not an object", key);
+            };
+            object = child;
+        }
+        match (object.get_mut(*last), value) {
+            (Some(Value::Array(items)), Value::Array(new)) => {
+                for item in new {
+                    if !items.contains(item) {
+                        items.push(item.clone());
+                    }
+                }
+            }
+            _ => {
+                object.insert(last.to_string(), value.clone());
+            }
+        }
+    }
+    let mut content = serde_json::to_string_pretty(&Value::Object(root))?;
+    content.push('\n');
+    Ok(content)
+}
+
+/// Replace the marked block in `existing` with `block`, or append it.
+fn replace_block(existing: &str, block: &str) -> String {
+    let lines: Vec<&str> = existing.lines().collect();
+    let start = lines.iter().position(|line| line.contains(BLOCK_START));
+    let end = lines.iter().position(|line| line.contains(BLOCK_END));
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end => {
+            let mut content = String::new();
+            for line in &lines[..start] {
+                content.push_str(line);
+                content.push('\n');
+            }
+            content.push_str(block);
+            for line in &lines[end + 1..] {
+                content.push_str(line);
+                content.push('\n');
+            }
+            content
+        }
+        _ if existing.trim().is_empty() => block.to_string(),
+        _ => {
+            let mut content = existing.trim_end().to_string();
+            content.push_str("\n\n");
+            content.push_str(block);
+            content
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.lsp.install]
+    #[test]
+    fn registers_the_server_for_project_languages() {
+        let languages = project_languages("src/lib.rs\ndocs/spec.md\nweb/app.tsx\nREADME\n");
+        let names: Vec<_> = languages.iter().map(|l| l.extension).collect();
+        assert_eq!(names, ["rs", "tsx", "md"]);
+
+        let lua = configure(Editor::Neovim, None, &languages)
+            .unwrap()
+            .unwrap();
+        assert!(lua.contains("filetypes = { 'rust', 'typescriptreact', 'markdown' }"));
+        let user = "vim.opt.number = true\n";
+        let appended = configure(Editor::Neovim, Some(user), &languages)
+            .unwrap()
+            .unwrap();
+        assert!(appended.starts_with(user) && appended.ends_with(&lua));
+        assert_eq!(
+            configure(Editor::Neovim, Some(&appended), &languages).unwrap(),
+            None
+        );
+        let replaced = configure(Editor::Neovim, Some(&appended), &languages[..1])
+            .unwrap()
+            .unwrap();
+        assert!(replaced.starts_with(user) && replaced.contains("filetypes = { 'rust' }"));
+
+        let toml = configure(Editor::Helix, None, &languages).unwrap().unwrap();
+        assert!(toml.contains("[language-server.tracey]\ncommand = \"tracey\"\nargs = [\"lsp\"]"));
+        assert!(toml.contains(
+            "name = \"tsx\"\nlanguage-servers = [\"typescript-language-server\", \"tracey\"]"
+        ));
+    }
+
+    #[test]
+    fn merges_json_settings() {
+        let languages = project_languages("src/lib.rs\n");
+        let existing = r#"{"editor.tabSize": 2, "glspc.server.languageId": ["go"]}"#;
+        let settings = configure(Editor::VsCode, Some(existing), &languages)
+            .unwrap()
+            .unwrap();
+        let settings: Value = serde_json::from_str(&settings).unwrap();
+        assert_eq!(settings["editor.tabSize"], 2);
+        assert_eq!(settings["glspc.server.command"], "tracey");
+        assert_eq!(
+            settings["glspc.server.languageId"],
+            json!(["go", "rust", "markdown"])
+        );
+
+        let zed = configure(Editor::Zed, None, &languages).unwrap().unwrap();
+        assert_eq!(
+            configure(Editor::Zed, Some(&zed), &languages).unwrap(),
+            None
+        );
+        let zed: Value = serde_json::from_str(&zed).unwrap();
+        assert_eq!(zed["lsp"]["tracey-lsp"]["binary"]["path"], "tracey");
+        assert_eq!(
+            zed["languages"]["Rust"]["language_servers"],
+            json!(["tracey-lsp", "..."])
+        );
+
+        let commented = "// Folder-specific settings\n{}\n";
+        let error = configure(Editor::Zed, Some(commented), &languages).unwrap_err();
+        assert!(error.to_string().contains("by hand"));
+    }
+}
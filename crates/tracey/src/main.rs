@@ -12,7 +12,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command as ProcessCommand, Stdio};
 
 // Use the library crate
-use tracey::{bridge, daemon, find_project_root};
+use tracey::{bridge, daemon, exit_code, find_project_root};
 
 /// CLI arguments
 #[derive(Debug, facet::Facet)]
@@ -62,6 +62,10 @@ enum Command {
         /// Path to config file
         #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
         config: PathBuf,
+
+        /// Log to one rotating `mcp.log` shared by all MCP bridges instead of one file per process
+        #[facet(rename = "shared-log", args::named, default)]
+        shared_log: bool,
     },
 
     /// Start the LSP server for editor integration
@@ -73,6 +77,10 @@ enum Command {
         /// Path to config file
         #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
         config: PathBuf,
+
+        /// Log to one rotating `lsp.log` shared by all LSP bridges instead of one file per process
+        #[facet(rename = "shared-log", args::named, default)]
+        shared_log: bool,
     },
 
     /// Start the tracey daemon (persistent server for this workspace)
@@ -99,6 +107,22 @@ enum Command {
         /// Number of lines to show (default: 50)
         #[facet(args::named, args::short = 'n', default)]
         lines: Option<usize>,
+
+        /// Only show entries at this level or above (trace, debug, info, warn, error)
+        #[facet(args::named, default)]
+        level: Option<String>,
+
+        /// Only show lines containing this text
+        #[facet(args::named, default)]
+        grep: Option<String>,
+
+        /// Only show entries from this recently (e.g. 10m, 2h, 1d)
+        #[facet(args::named, default)]
+        since: Option<String>,
+
+        /// Show the latest log of a bridge (`mcp` or `lsp`) instead of the daemon's
+        #[facet(args::named, default)]
+        bridge: Option<String>,
     },
 
     /// Show daemon status
@@ -112,6 +136,21 @@ enum Command {
         json: bool,
     },
 
+    /// Check that the project loads and summarize it; with --bench, time it
+    Doctor {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Time a full build, an incremental rebuild and a query against the performance targets
+        #[facet(args::named, default)]
+        bench: bool,
+    },
+
     /// Stop the running daemon
     Kill {
         /// Project root directory (default: current directory)
@@ -119,6 +158,17 @@ enum Command {
         root: Option<PathBuf>,
     },
 
+    /// Force the running daemon to rebuild its data
+    Reload {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Also re-fetch remote `rules_url` and git `source` specs
+        #[facet(args::named, default)]
+        refetch: bool,
+    },
+
     /// Manage the bundled AI skill
     Skill {
         /// Skill action to perform
@@ -147,14 +197,32 @@ enum Command {
         #[facet(args::named, default)]
         json: bool,
 
+        /// Output a `csv` or `md` table (status, uncovered, untested and stale only)
+        #[facet(args::named, default)]
+        format: Option<String>,
+
+        /// Output stable tab-separated lines for scripts (same queries as `--format`)
+        #[facet(args::named, default)]
+        porcelain: bool,
+
+        /// Make file:line references clickable terminal hyperlinks (see `editor_links`)
+        #[facet(args::named, default)]
+        hyperlink: bool,
+
         /// Query command to run
         #[facet(args::subcommand)]
         query: QueryCommand,
     },
 
-    /// Check staged spec changes and fail if any rule text changed without a version bump.
-    /// Designed to be installed as a git pre-commit hook.
-    PreCommit {
+    /// Keep a live coverage summary on screen, refreshed on every rebuild
+    Watch {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Check coverage against the configured policies and fail if any is violated
+    Check {
         /// Project root directory (default: current directory)
         #[facet(args::positional, default)]
         root: Option<PathBuf>,
@@ -162,10 +230,22 @@ enum Command {
         /// Path to config file
         #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
         config: PathBuf,
+
+        /// Print one stable tab-separated line per policy result on stdout
+        #[facet(args::named, default)]
+        porcelain: bool,
+
+        /// Also fail on any uncovered rule or stale reference missing from the recorded baseline
+        #[facet(args::named, default)]
+        baseline: bool,
+
+        /// Also fail if the code no longer implements the rule versions certified for this release
+        #[facet(args::named, default)]
+        against_release: Option<String>,
     },
 
-    /// Bump version numbers of staged rules whose text changed, then re-stage the files.
-    Bump {
+    /// Rewrite requirement annotations and rule markers in their canonical spelling
+    Fmt {
         /// Project root directory (default: current directory)
         #[facet(args::positional, default)]
         root: Option<PathBuf>,
@@ -173,132 +253,908 @@ enum Command {
         /// Path to config file
         #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
         config: PathBuf,
+
+        /// List what would change without writing, and exit with status 1 if anything would
+        #[facet(args::named, default)]
+        check: bool,
     },
 
-    /// Remove orphaned state directories whose projects no longer exist on disk
-    Gc {
-        /// Show what would be removed without deleting anything
-        #[facet(rename = "dry-run", args::named, default)]
-        dry_run: bool,
+    /// Check spec files for style problems validation doesn't catch
+    Lint {
+        /// What to lint
+        #[facet(args::subcommand)]
+        action: LintAction,
     },
-}
 
-/// Skill subcommands
-#[derive(Debug, facet::Facet)]
-#[repr(u8)]
-enum SkillAction {
-    /// Install the bundled Tracey skill for Claude and/or Codex
-    Install {
-        /// Install only for Claude Code
+    /// Find the commit where a rule lost its implementation references or went stale
+    Bisect {
+        /// Rule that is uncovered or stale now
+        #[facet(args::positional)]
+        rule_id: String,
+
+        /// Spec/impl to check (e.g., "my-spec/rust"). Optional if the rule has only one.
         #[facet(args::named, default)]
-        claude: bool,
+        spec_impl: Option<String>,
 
-        /// Install only for Codex CLI
+        /// A commit where the rule was covered (default: search back from --bad)
         #[facet(args::named, default)]
-        codex: bool,
+        good: Option<String>,
+
+        /// A commit where the rule is broken (default: HEAD)
+        #[facet(args::named, default)]
+        bad: Option<String>,
+
+        /// Path to config file, relative to the project root
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
     },
-}
 
-#[derive(Debug, facet::Facet)]
-#[repr(u8)]
-enum QueryCommand {
-    /// coverage overview
-    Status,
+    /// Install tracey's git hooks
+    Hooks {
+        /// Hooks action to perform
+        #[facet(args::subcommand)]
+        action: HooksAction,
+    },
 
-    /// List rules without implementation references
-    Uncovered {
-        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+    /// Record accepted coverage debt for `tracey check --baseline`
+    Baseline {
+        /// Baseline action to perform
+        #[facet(args::subcommand)]
+        action: BaselineAction,
+    },
+
+    /// Pin the rule versions of a release for `tracey check --against-release`
+    Release {
+        /// Release action to perform
+        #[facet(args::subcommand)]
+        action: ReleaseAction,
+    },
+
+    /// Record test results so `verify` references can be checked against them
+    TestReport {
+        /// Test report action to perform
+        #[facet(args::subcommand)]
+        action: TestReportAction,
+    },
+
+    /// Record line coverage so `impl` references can be checked against it
+    LineCoverage {
+        /// Line coverage action to perform
+        #[facet(args::subcommand)]
+        action: LineCoverageAction,
+    },
+
+    /// List the rules added, removed and changed between two releases or git revisions
+    SpecDiff {
+        /// The old side: a release cut with `tracey release cut`, or a git revision
+        #[facet(args::positional)]
+        from: String,
+
+        /// The new side, likewise
+        #[facet(args::positional)]
+        to: String,
+
+        /// Project root directory (default: current directory)
         #[facet(args::named, default)]
-        spec_impl: Option<String>,
+        root: Option<PathBuf>,
 
-        /// Filter by rule ID prefix
+        /// Path to config file, relative to the project root
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Print the diff as JSON
         #[facet(args::named, default)]
-        prefix: Option<String>,
+        json: bool,
     },
 
-    /// List rules without verification references
-    Untested {
-        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+    /// List the code referencing rules whose text changed since a revision
+    Impact {
+        /// Git revision to compare the spec files on disk against
+        #[facet(args::named)]
+        since: String,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file, relative to the project root
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Print the report as JSON
+        #[facet(args::named, default)]
+        json: bool,
+    },
+
+    /// Show which requirements a log's `requirement!` events exercised
+    TraceIngest {
+        /// The log file, in tracing-subscriber's JSON or text format
+        #[facet(args::positional)]
+        trace: PathBuf,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file, relative to the project root
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Print the report as JSON
+        #[facet(args::named, default)]
+        json: bool,
+    },
+
+    /// List uncovered rules as work items, or file them as GitHub issues
+    Todo {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Spec or spec/impl to list (default: all)
         #[facet(args::named, default)]
         spec_impl: Option<String>,
 
-        /// Filter by rule ID prefix
+        /// Only rules whose ID starts with this prefix (case-insensitive)
         #[facet(args::named, default)]
         prefix: Option<String>,
+
+        /// One item per section instead of one per rule
+        #[facet(rename = "per-section", args::named, default)]
+        per_section: bool,
+
+        /// Print the items as GitHub issue bodies (JSON, one per line, for `gh api`)
+        #[facet(rename = "github-issues", args::named, default)]
+        github_issues: bool,
+
+        /// Comma-separated labels to add to each issue, besides `tracey`
+        #[facet(args::named, default)]
+        labels: Option<String>,
+
+        /// With --github-issues, create the issues with `gh`, skipping open duplicates
+        #[facet(args::named, default)]
+        create: bool,
+
+        /// Write a `TODO(tracey)` comment for each uncovered rule into the file suggested for it
+        #[facet(rename = "emit-stubs", args::named, default)]
+        emit_stubs: bool,
+
+        /// With --emit-stubs, put every stub in this file instead
+        #[facet(rename = "stubs-in", args::named, default)]
+        stubs_in: Option<String>,
     },
 
-    /// Show unmapped code units
-    Unmapped {
-        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+    /// Mirror requirement debt into an issue tracker
+    Sync {
+        /// Sync action to perform
+        #[facet(args::subcommand)]
+        action: SyncAction,
+    },
+
+    /// Dump the full model (rules, references, code units, coverage) as JSON or SQLite
+    Export {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// `json` (default) or `sqlite`
         #[facet(args::named, default)]
-        spec_impl: Option<String>,
+        format: Option<String>,
 
-        /// Directory or file path to zoom into
+        /// File to write (default for JSON: stdout; required for SQLite)
+        #[facet(args::named, args::short = 'o', default)]
+        output: Option<PathBuf>,
+
+        /// Write an audit evidence bundle (`.tar.zst`) here instead
         #[facet(args::named, default)]
-        path: Option<String>,
+        bundle: Option<PathBuf>,
+
+        /// SSH private key to sign the bundle's manifest with
+        #[facet(rename = "sign-key", args::named, default)]
+        sign_key: Option<PathBuf>,
     },
 
-    /// List stale references (code pointing to older rule versions)
-    Stale {
-        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+    /// Write a compliance summary (rule, status, evidence, approvals) for GRC tooling
+    Report {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Report format; only `compliance-json` for now
         #[facet(args::named, default)]
-        spec_impl: Option<String>,
+        format: Option<String>,
 
-        /// Filter by rule ID prefix
+        /// File to write (default: stdout)
+        #[facet(args::named, args::short = 'o', default)]
+        output: Option<PathBuf>,
+
+        /// Print the JSON Schema of the report instead
         #[facet(args::named, default)]
-        prefix: Option<String>,
+        schema: bool,
     },
 
-    /// Show details about one or more rules
-    Rule {
-        /// Rule identifiers to inspect (one or more)
+    /// Check that an evidence bundle from `tracey export --bundle` is intact
+    VerifyBundle {
+        /// The bundle file
         #[facet(args::positional)]
-        rule_ids: Vec<String>,
+        bundle: PathBuf,
+
+        /// An `ssh-keygen` allowed signers file; requires a valid signature
+        #[facet(rename = "allowed-signers", args::named, default)]
+        allowed_signers: Option<PathBuf>,
+
+        /// Signer identity to accept from the allowed signers file
+        #[facet(args::named, default)]
+        identity: Option<String>,
     },
 
-    /// Display current configuration
-    Config,
+    /// Record coverage history snapshots in `.tracey/history.sqlite`
+    Snapshot {
+        /// Snapshot action to perform
+        #[facet(args::subcommand)]
+        action: SnapshotAction,
+    },
 
-    /// Validate the spec and implementation
-    Validate {
-        /// Spec/impl to validate (e.g., "my-spec/rust"). Optional if only one exists.
+    /// Check staged spec changes and fail if any rule text changed without a version bump.
+    /// Designed to be installed as a git pre-commit hook.
+    PreCommit {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Let these rules (comma-separated) change without a version bump
         #[facet(args::named, default)]
-        spec_impl: Option<String>,
+        allow_no_bump: Option<String>,
+
+        /// Why the --allow-no-bump rules need no bump (logged in SPEC-CHANGELOG.md)
+        #[facet(args::named, default)]
+        reason: Option<String>,
+
+        /// Commit message file to read `Tracey-No-Bump:` trailers from (for commit-msg hooks)
+        #[facet(args::named, default)]
+        commit_msg: Option<PathBuf>,
     },
-}
 
-// Embed the config schema for zero-execution discovery by styx tooling
-styx_embed::embed_outdir_file!("schema.styx");
+    /// Check every commit not yet pushed the way `pre-commit` checks staged changes.
+    /// Designed to be installed as a git pre-push hook.
+    PrePush {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let raw_args: Vec<String> = std::env::args().skip(1).collect();
-    if raw_args.first().map(|s| s.as_str()) == Some("mcp")
-        && raw_args.get(1).map(|s| s.as_str()) == Some("register")
-    {
-        return register_mcp_clients(&raw_args[2..]);
-    }
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
 
-    let config = args::builder::<Args>()
-        .map_err(|e| eyre!("failed to initialize CLI parser: {e}"))?
-        .cli(|cli| cli.args(raw_args.clone().into_iter()))
-        .help(|h| {
-            h.program_name(env!("CARGO_PKG_NAME"))
-                .version(cli_version_text())
-        })
-        .build();
-    let args: Args = args::Driver::new(config).run().unwrap();
+        /// Check commits not in this revision (default: the branch's upstream)
+        #[facet(args::named, default)]
+        upstream: Option<String>,
+    },
 
-    match args.command {
-        // r[impl cli.web]
-        // r[impl daemon.cli.web]
-        Command::Web {
-            root,
-            config,
-            port,
-            open,
-            dev,
-        } => {
+    /// Git merge driver for spec files that renumbers rules bumped on both branches
+    MergeDriver {
+        /// `%O %A %B [%P]` from git: the ancestor, current (overwritten with the result) and
+        /// other versions of the file
+        #[facet(args::positional, default)]
+        files: Vec<PathBuf>,
+
+        /// Register the driver in git config and `.gitattributes` instead of merging
+        #[facet(args::named, default)]
+        install: bool,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Record a reviewer's approval of a rule version (e.g. `tracey approve auth.login+3`)
+    Approve {
+        /// Rule version being approved; must be the rule's current version
+        #[facet(args::positional)]
+        rule_id: String,
+
+        /// Who approved it (default: git's user.name)
+        #[facet(args::named, default)]
+        by: Option<String>,
+
+        /// SSH private key to sign the approval with (default: git's user.signingkey)
+        #[facet(args::named, default)]
+        key: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+
+    /// Bump version numbers of staged rules whose text changed, then re-stage the files.
+    Bump {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Don't add the bumped rules to SPEC-CHANGELOG.md
+        #[facet(args::named, default)]
+        no_changelog: bool,
+
+        /// Ask whether to bump, skip or edit each changed rule
+        #[facet(args::named, args::short = 'i', default)]
+        interactive: bool,
+
+        /// Bump edits in the working tree, staged or not, without touching the index
+        #[facet(rename = "working-tree", args::named, default)]
+        working_tree: bool,
+
+        /// Compare against this revision instead of HEAD (e.g. before squashing a branch)
+        #[facet(args::named, default)]
+        since: Option<String>,
+    },
+
+    /// Maintain the config file
+    Config {
+        /// Config action to perform
+        #[facet(args::subcommand)]
+        action: ConfigAction,
+    },
+
+    /// List tracey projects (directories with their own config) at or below a directory
+    Projects {
+        /// Directory to search (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+    },
+
+    /// Remove orphaned state directories and logs of bridges that have exited
+    Gc {
+        /// Show what would be removed without deleting anything
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+    },
+}
+
+/// Skill subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum SkillAction {
+    /// Install the bundled Tracey skill for Claude, Codex, Cursor and/or Windsurf
+    Install {
+        /// Install only for Claude Code
+        #[facet(args::named, default)]
+        claude: bool,
+
+        /// Install only for Codex CLI
+        #[facet(args::named, default)]
+        codex: bool,
+
+        /// Install only for Cursor
+        #[facet(args::named, default)]
+        cursor: bool,
+
+        /// Install only for Windsurf
+        #[facet(args::named, default)]
+        windsurf: bool,
+
+        /// Install in the current project instead of the home directory
+        #[facet(args::named, default)]
+        project: bool,
+    },
+
+    /// Rewrite installed skills with the files bundled in this tracey version
+    Update {
+        /// Update only for Claude Code
+        #[facet(args::named, default)]
+        claude: bool,
+
+        /// Update only for Codex CLI
+        #[facet(args::named, default)]
+        codex: bool,
+
+        /// Update only for Cursor
+        #[facet(args::named, default)]
+        cursor: bool,
+
+        /// Update only for Windsurf
+        #[facet(args::named, default)]
+        windsurf: bool,
+
+        /// Update in the current project instead of the home directory
+        #[facet(args::named, default)]
+        project: bool,
+    },
+
+    /// Remove installed skills
+    Uninstall {
+        /// Uninstall only for Claude Code
+        #[facet(args::named, default)]
+        claude: bool,
+
+        /// Uninstall only for Codex CLI
+        #[facet(args::named, default)]
+        codex: bool,
+
+        /// Uninstall only for Cursor
+        #[facet(args::named, default)]
+        cursor: bool,
+
+        /// Uninstall only for Windsurf
+        #[facet(args::named, default)]
+        windsurf: bool,
+
+        /// Uninstall in the current project instead of the home directory
+        #[facet(args::named, default)]
+        project: bool,
+    },
+}
+
+/// Baseline subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum BaselineAction {
+    /// Write the current uncovered rules and stale references to the baseline file
+    Record {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+}
+
+/// Release subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum ReleaseAction {
+    /// Write every rule's current version to `.tracey/releases/NAME.json`
+    Cut {
+        /// Release name, e.g. `v1.4`
+        #[facet(args::positional)]
+        name: String,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Replace the release if it was already cut
+        #[facet(args::named, default)]
+        force: bool,
+    },
+}
+
+/// Test report subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum TestReportAction {
+    /// Read a JUnit XML or libtest JSON report, replacing the last ingested one
+    Ingest {
+        /// The report file, e.g. `target/nextest/default/junit.xml`
+        #[facet(args::positional)]
+        report: PathBuf,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+    },
+}
+
+/// Line coverage subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum LineCoverageAction {
+    /// Read an LCOV report, replacing the last ingested one
+    Ingest {
+        /// The report file, e.g. from `cargo llvm-cov --lcov --output-path lcov.info`
+        #[facet(args::positional)]
+        report: PathBuf,
+
+        /// Project root directory (default: current directory)
+        #[facet(args::named, default)]
+        root: Option<PathBuf>,
+    },
+}
+
+/// Sync subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum SyncAction {
+    /// Open, update and close Jira issues for uncovered or stale MUST-level rules
+    Jira {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Show what would change without touching any issue
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+    },
+}
+
+/// Hooks subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum HooksAction {
+    /// Run `tracey pre-commit` (and optionally `tracey check` before pushing) from git hooks
+    Install {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Also install a pre-push hook that runs `tracey check`
+        #[facet(rename = "pre-push", args::named, default)]
+        pre_push: bool,
+
+        /// `git`, `husky`, `pre-commit` or `lefthook` (default: detected from the project)
+        #[facet(args::named, default)]
+        manager: Option<String>,
+    },
+}
+
+/// Snapshot subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum SnapshotAction {
+    /// Record the current coverage of every spec/impl as a new history point
+    Record {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+}
+
+/// Lint subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum LintAction {
+    /// Lint spec markdown: empty rules, duplicate headings, numbering gaps, long rules
+    Spec {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+    },
+}
+
+/// Config subcommands
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum ConfigAction {
+    /// Rewrite deprecated config options to the current schema
+    Migrate {
+        /// Project root directory (default: current directory)
+        #[facet(args::positional, default)]
+        root: Option<PathBuf>,
+
+        /// Path to config file
+        #[facet(args::named, args::short = 'c', default = ".config/tracey/config.styx")]
+        config: PathBuf,
+
+        /// Show what would change without writing the file
+        #[facet(rename = "dry-run", args::named, default)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, facet::Facet)]
+#[repr(u8)]
+enum QueryCommand {
+    /// coverage overview
+    Status {
+        /// List, per rule, which impls cover it, flagging rules covered by none or only some
+        #[facet(args::named, default)]
+        by_rule: bool,
+    },
+
+    /// List rules without implementation references
+    Uncovered {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Filter by rule ID prefix
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+
+        /// Only list rules owned by this team or person (e.g., "@platform-team")
+        #[facet(args::named, default)]
+        owner: Option<String>,
+
+        /// Order rules by `section` (default), `id` or `refs`
+        #[facet(args::named, default)]
+        sort: Option<String>,
+
+        /// Show at most this many rules
+        #[facet(args::named, default)]
+        limit: Option<u32>,
+
+        /// Skip this many rules before the first one shown
+        #[facet(args::named, default)]
+        offset: Option<u32>,
+
+        /// Exit non-zero if there is any uncovered rule
+        #[facet(args::named, default)]
+        fail_on_any: bool,
+
+        /// Exit non-zero if there are more than this many uncovered rules
+        #[facet(args::named, default)]
+        fail_if_more_than: Option<u32>,
+    },
+
+    /// List rules without verification references
+    Untested {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Filter by rule ID prefix
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+
+        /// Only list rules that have impl references
+        #[facet(args::named, default)]
+        only_implemented: bool,
+
+        /// Order rules by `section` (default), `id` or `refs`
+        #[facet(args::named, default)]
+        sort: Option<String>,
+
+        /// Show at most this many rules
+        #[facet(args::named, default)]
+        limit: Option<u32>,
+
+        /// Skip this many rules before the first one shown
+        #[facet(args::named, default)]
+        offset: Option<u32>,
+
+        /// Exit non-zero if there is any untested rule
+        #[facet(args::named, default)]
+        fail_on_any: bool,
+
+        /// Exit non-zero if there are more than this many untested rules
+        #[facet(args::named, default)]
+        fail_if_more_than: Option<u32>,
+    },
+
+    /// Show unmapped code units
+    Unmapped {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Directory or file path to zoom into
+        #[facet(args::named, default)]
+        path: Option<String>,
+    },
+
+    /// List stale references (code pointing to older rule versions)
+    Stale {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Filter by rule ID prefix
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+
+        /// Order rules by `section` (default), `id` or `refs`
+        #[facet(args::named, default)]
+        sort: Option<String>,
+
+        /// Show at most this many rules
+        #[facet(args::named, default)]
+        limit: Option<u32>,
+
+        /// Skip this many rules before the first one shown
+        #[facet(args::named, default)]
+        offset: Option<u32>,
+
+        /// Exit non-zero if there is any stale reference
+        #[facet(args::named, default)]
+        fail_on_any: bool,
+
+        /// Exit non-zero if there are more than this many stale references
+        #[facet(args::named, default)]
+        fail_if_more_than: Option<u32>,
+    },
+
+    /// Show code-unit coverage, optionally rolled up per directory
+    Coverage {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// List every directory with its coverage
+        #[facet(args::named, default)]
+        by_dir: bool,
+
+        /// Only show directories at most this many levels deep
+        #[facet(args::named, default)]
+        depth: Option<u32>,
+
+        /// Sort directories by `coverage` (lowest first, default), `unmapped` or `path`
+        #[facet(args::named, default)]
+        sort: Option<String>,
+    },
+
+    /// Print a rules × impls traceability matrix
+    Matrix {
+        /// Spec to show (default: all specs)
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Filter by rule ID prefix
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+    },
+
+    /// Full-text search over rules and source code
+    Search {
+        /// Words, "phrases", OR, -term and field filters like level:MUST or tag:security
+        #[facet(args::positional)]
+        query: String,
+
+        /// Maximum number of results (default: 20)
+        #[facet(args::named, default)]
+        limit: Option<u32>,
+    },
+
+    /// List every requirement reference in a source file
+    Refs {
+        /// Source file to inspect
+        #[facet(args::positional)]
+        file: String,
+    },
+
+    /// List rules with no references whose definition is older than N days
+    Dead {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Minimum age in days, from git blame on the rule definition (default: 90)
+        #[facet(args::named, default)]
+        days: Option<u32>,
+    },
+
+    /// List high-level rules that no rule refines (`r[refines ID]` markers)
+    Unrefined {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+    },
+
+    /// List rules whose implementing code never ran, per `tracey line-coverage ingest`
+    Unexecuted {
+        /// Spec/impl to query (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+
+        /// Filter rules by ID prefix (case-insensitive)
+        #[facet(args::named, default)]
+        prefix: Option<String>,
+    },
+
+    /// Show how coverage changed across recorded history snapshots
+    Trend {
+        /// Spec (or "spec/impl") to show. All recorded pairs if omitted.
+        #[facet(args::named, default)]
+        spec: Option<String>,
+
+        /// Only show snapshots from this far back, e.g. `30d`, `12h` or `2w` (default: all)
+        #[facet(args::named, default)]
+        since: Option<String>,
+    },
+
+    /// List rules whose definition or coverage changed since a history snapshot
+    Recent {
+        /// Snapshot version (`42`), age (`30d`, `12h`) or UTC date (`2026-10-01`) (default: 7d)
+        #[facet(args::named, default)]
+        since: Option<String>,
+    },
+
+    /// Show details about one or more rules
+    Rule {
+        /// Rule identifiers or glob patterns like `auth.*` (one or more)
+        #[facet(args::positional)]
+        rule_ids: Vec<String>,
+
+        /// Show who last edited the rule text and who wrote each reference (git blame)
+        #[facet(args::named, default)]
+        blame: bool,
+    },
+
+    /// Display current configuration
+    Config,
+
+    /// Validate the spec and implementation
+    Validate {
+        /// Spec/impl to validate (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::named, default)]
+        spec_impl: Option<String>,
+    },
+
+    /// Report whether remote specs have newer rule versions upstream than the locked copy
+    SpecDrift,
+
+    /// Show a spec's section outline with the coverage of each section
+    Spec {
+        /// Spec or spec/impl to outline (e.g., "my-spec/rust"). Optional if only one exists.
+        #[facet(args::positional, default)]
+        name: Option<String>,
+    },
+}
+
+// Embed the config schema for zero-execution discovery by styx tooling
+styx_embed::embed_outdir_file!("schema.styx");
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+    if raw_args.first().map(|s| s.as_str()) == Some("mcp")
+        && raw_args.get(1).map(|s| s.as_str()) == Some("register")
+    {
+        return register_mcp_clients(&raw_args[2..]);
+    }
+    if raw_args.first().map(|s| s.as_str()) == Some("lsp")
+        && raw_args.get(1).map(|s| s.as_str()) == Some("install")
+    {
+        return install_lsp_config(&raw_args[2..]);
+    }
+
+    let config = args::builder::<Args>()
+        .map_err(|e| eyre!("failed to initialize CLI parser: {e}"))?
+        .cli(|cli| cli.args(raw_args.clone().into_iter()))
+        .help(|h| {
+            h.program_name(env!("CARGO_PKG_NAME"))
+                .version(cli_version_text())
+        })
+        .build();
+    let args: Args = args::Driver::new(config).run().unwrap();
+
+    match args.command {
+        // r[impl cli.web]
+        // r[impl daemon.cli.web]
+        Command::Web {
+            root,
+            config,
+            port,
+            open,
+            dev,
+        } => {
             init_tracing(TracingConfig {
                 log_file: None,
                 enable_console: true,
@@ -309,9 +1165,13 @@ async fn main() -> Result<()> {
         }
         // r[impl cli.mcp]
         // r[impl daemon.cli.mcp]
-        Command::Mcp { root, config } => {
+        Command::Mcp {
+            root,
+            config,
+            shared_log,
+        } => {
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
-            let log_path = bridge_log_path(&project_root, "mcp");
+            let log_path = bridge_log_path(&project_root, "mcp", shared_log);
             write_bridge_start_marker(&log_path, "mcp", &project_root, &config)?;
             // MCP communicates over stdio, so logging must stay off stdio.
             init_tracing(TracingConfig {
@@ -331,11 +1191,18 @@ async fn main() -> Result<()> {
             bridge::mcp::run(Some(project_root), config).await
         }
         // r[impl daemon.cli.lsp]
-        Command::Lsp { root, config } => {
+        Command::Lsp {
+            root,
+            config,
+            shared_log,
+        } => {
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
-            // LSP uses stdout for the wire protocol, so logs go to stderr.
+            let log_path = bridge_log_path(&project_root, "lsp", shared_log);
+            write_bridge_start_marker(&log_path, "lsp", &project_root, &config)?;
+            // LSP uses stdout for the wire protocol, so logs go to stderr,
+            // and to a file for `tracey logs --bridge lsp`.
             init_tracing(TracingConfig {
-                log_file: None,
+                log_file: Some(log_path),
                 enable_console: true,
                 console_ansi: false,
                 default_filter: "tracey=debug",
@@ -372,57 +1239,1005 @@ async fn main() -> Result<()> {
             root,
             follow,
             lines,
-        } => show_logs(root, follow, lines.unwrap_or(50)),
+            level,
+            grep,
+            since,
+            bridge,
+        } => {
+            // r[impl daemon.cli.logs.filter]
+            let filter = daemon::logs::LogFilter {
+                level: level.as_deref().map(str::parse).transpose()?,
+                grep,
+                since_secs: since
+                    .as_deref()
+                    .map(tracey::history::parse_age)
+                    .transpose()?
+                    .map(|age| tracey::history::now_secs().saturating_sub(age)),
+            };
+            show_logs(root, follow, lines.unwrap_or(50), bridge.as_deref(), filter)
+        }
         // r[impl daemon.cli.status]
         Command::Status { root, json } => show_status(root, json).await,
+        // r[impl cli.doctor]
+        Command::Doctor {
+            root,
+            config,
+            bench,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            if !run_doctor(&project_root, &cfg, bench).await? {
+                std::process::exit(exit_code::FINDINGS);
+            }
+            Ok(())
+        }
         // r[impl daemon.cli.kill]
         Command::Kill { root } => kill_daemon(root).await,
 
+        // r[impl daemon.cli.reload]
+        Command::Reload { root, refetch } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            init_tracing(TracingConfig {
+                log_file: None,
+                enable_console: true,
+                console_ansi: true,
+                default_filter: "tracey=info",
+            })?;
+            let query_client =
+                bridge::query::QueryClient::new(project_root, bridge::query::Caller::Cli);
+            let output = if refetch {
+                query_client.refetch().await
+            } else {
+                query_client.reload().await
+            };
+            println!("{}", output);
+            Ok(())
+        }
+
         // r[impl cli.skill.install]
         Command::Skill { action } => match action {
-            SkillAction::Install { claude, codex } => install_skill(codex, claude),
+            SkillAction::Install {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            } => install_skill(SkillTargets {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            }),
+            // r[impl cli.skill.update]
+            SkillAction::Update {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            } => update_skill(SkillTargets {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            }),
+            // r[impl cli.skill.uninstall]
+            SkillAction::Uninstall {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            } => uninstall_skill(SkillTargets {
+                claude,
+                codex,
+                cursor,
+                windsurf,
+                project,
+            }),
         },
 
-        // r[impl cli.mcp.register]
-        // r[impl cli.skill.install]
-        Command::Ai { claude, codex } => setup_ai_clients(codex, claude),
+        // r[impl cli.mcp.register]
+        // r[impl cli.skill.install]
+        Command::Ai { claude, codex } => setup_ai_clients(codex, claude),
+
+        // r[impl cli.pre-commit]
+        // r[impl cli.exit-codes]
+        Command::PreCommit {
+            root,
+            config,
+            allow_no_bump,
+            reason,
+            commit_msg,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = if config_path.exists() {
+                load_config_or_exit(&config_path)
+            } else {
+                tracey::config::Config::default()
+            };
+            // r[impl cli.pre-commit.no-bump]
+            let mut exemptions = Vec::new();
+            if let Some(rules) = allow_no_bump {
+                let Some(reason) = reason.filter(|r| !r.trim().is_empty()) else {
+                    eyre::bail!("--allow-no-bump needs a --reason");
+                };
+                exemptions.push(tracey::bump::NoBumpExemption::new(&rules, &reason));
+            }
+            if let Some(path) = commit_msg {
+                let message = std::fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+                exemptions.push(tracey::bump::NoBumpExemption::from_commit_message(&message));
+            }
+            let mut passed =
+                tracey::bump::pre_commit_with(&project_root, &cfg, &exemptions).await?;
+            // r[impl cli.check.pre-commit]
+            if tracey::policy::any_configured(&cfg) {
+                passed &= check_policies(&project_root, &cfg, false, None, None).await;
+            }
+            if !passed {
+                std::process::exit(exit_code::FINDINGS);
+            }
+            Ok(())
+        }
+
+        // r[impl cli.pre-push]
+        Command::PrePush {
+            root,
+            config,
+            upstream,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let cfg = if config_path.exists() {
+                load_config_or_exit(&config_path)
+            } else {
+                tracey::config::Config::default()
+            };
+            let upstream = match upstream {
+                Some(upstream) => upstream,
+                None => tracey::bump::default_upstream(&project_root)?,
+            };
+            let mut passed = tracey::bump::pre_push(&project_root, &cfg, &upstream, "HEAD").await?;
+            if tracey::policy::any_configured(&cfg) {
+                passed &= check_policies(&project_root, &cfg, false, None, None).await;
+            }
+            if !passed {
+                std::process::exit(exit_code::FINDINGS);
+            }
+            Ok(())
+        }
+
+        // r[impl cli.fmt]
+        Command::Fmt {
+            root,
+            config,
+            check,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let changed = tracey::fmt::format_project(&project_root, &cfg, check).await?;
+
+            for file in &changed {
+                for change in &file.formatted.changes {
+                    println!(
+                        "  {}:{}: {} -> {}",
+                        file.file.display(),
+                        change.line,
+                        change.before,
+                        change.after
+                    );
+                }
+            }
+            if changed.is_empty() {
+                println!("All annotations and rule markers are formatted.");
+            } else if check {
+                println!("{} file(s) would be reformatted.", changed.len());
+                std::process::exit(exit_code::FINDINGS);
+            } else {
+                println!("Reformatted {} file(s).", changed.len());
+            }
+            Ok(())
+        }
+
+        // r[impl cli.lint.spec]
+        Command::Lint {
+            action: LintAction::Spec { root, config },
+        } => {
+            use tracey::spec_lint::{LintSettings, Severity};
+
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let settings = match LintSettings::new(cfg.spec_lint.as_ref()) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    eprintln!("{}", format!("{e:?}").red());
+                    std::process::exit(exit_code::CONFIG_ERROR);
+                }
+            };
+            let findings = tracey::spec_lint::lint_specs(&project_root, &cfg, &settings).await?;
+
+            for finding in &findings {
+                println!("{}", finding);
+            }
+            let errors = findings
+                .iter()
+                .filter(|f| f.severity == Severity::Error)
+                .count();
+            let warnings = findings.len() - errors;
+            if findings.is_empty() {
+                println!("No spec lint findings.");
+            } else {
+                println!("{} error(s), {} warning(s)", errors, warnings);
+            }
+            if errors > 0 {
+                std::process::exit(exit_code::FINDINGS);
+            }
+            Ok(())
+        }
+
+        // r[impl cli.check]
+        Command::Check {
+            root,
+            config,
+            porcelain,
+            baseline,
+            against_release,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            // r[impl cli.check.baseline]
+            let baseline = if baseline {
+                let path = tracey::baseline::baseline_path(&project_root);
+                match tracey::baseline::Baseline::load(&path) {
+                    Ok(baseline) => Some(baseline),
+                    Err(e) => {
+                        eprintln!("{}", format!("{e:?}").red());
+                        std::process::exit(exit_code::CONFIG_ERROR);
+                    }
+                }
+            } else {
+                None
+            };
+            // r[impl cli.check.against-release]
+            let release = match against_release {
+                Some(name) => {
+                    let loaded = tracey::release::validate_name(&name).and_then(|()| {
+                        let path = tracey::release::release_path(&project_root, &name);
+                        tracey::release::Release::load(&path)
+                    });
+                    match loaded {
+                        Ok(release) => Some(release),
+                        Err(e) => {
+                            eprintln!("{}", format!("{e:?}").red());
+                            std::process::exit(exit_code::CONFIG_ERROR);
+                        }
+                    }
+                }
+                None => None,
+            };
+            if !check_policies(
+                &project_root,
+                &cfg,
+                porcelain,
+                baseline.as_ref(),
+                release.as_ref(),
+            )
+            .await
+            {
+                std::process::exit(exit_code::FINDINGS);
+            }
+            Ok(())
+        }
+
+        // r[impl cli.bisect]
+        Command::Bisect {
+            rule_id,
+            spec_impl,
+            good,
+            bad,
+            config,
+        } => {
+            let project_root = find_project_root()?;
+            let (spec, impl_name) = bridge::query::parse_spec_impl(spec_impl.as_deref());
+            let outcome = tracey::bisect::bisect(tracey::bisect::BisectRequest {
+                project_root: &project_root,
+                config: &config,
+                rule_id: &rule_id,
+                spec: spec.as_deref(),
+                impl_name: impl_name.as_deref(),
+                good: good.as_deref(),
+                bad: bad.as_deref().unwrap_or("HEAD"),
+            })
+            .await?;
+            println!();
+            print!("{}", tracey::bisect::format_outcome(&rule_id, &outcome));
+            Ok(())
+        }
+
+        // r[impl cli.test-report]
+        Command::TestReport {
+            action: TestReportAction::Ingest { report, root },
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let ingested = tracey::test_report::ingest(&project_root, &report)?;
+            println!(
+                "Ingested {} test result(s) from {}: {} passed, {} failed, {} skipped",
+                ingested.tests.len(),
+                report.display(),
+                ingested.count(tracey_proto::TestOutcome::Passed),
+                ingested.count(tracey_proto::TestOutcome::Failed),
+                ingested.count(tracey_proto::TestOutcome::Skipped)
+            );
+            Ok(())
+        }
+
+        // r[impl cli.line-coverage]
+        Command::LineCoverage {
+            action: LineCoverageAction::Ingest { report, root },
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let ingested = tracey::line_coverage::ingest(&project_root, &report)?;
+            let executed: usize = ingested.files.iter().map(|f| f.executed.len()).sum();
+            let missed: usize = ingested.files.iter().map(|f| f.missed.len()).sum();
+            println!(
+                "Ingested line coverage for {} file(s) from {}: {} of {} line(s) executed",
+                ingested.files.len(),
+                report.display(),
+                executed,
+                executed + missed
+            );
+            Ok(())
+        }
+
+        // r[impl cli.release.cut]
+        Command::Release {
+            action:
+                ReleaseAction::Cut {
+                    name,
+                    root,
+                    config,
+                    force,
+                },
+        } => {
+            tracey::release::validate_name(&name)?;
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let path = tracey::release::release_path(&project_root, &name);
+            if path.exists() && !force {
+                eyre::bail!(
+                    "Release {} was already cut in {}; pass --force to replace it",
+                    name,
+                    path.display()
+                );
+            }
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let release =
+                tracey::release::Release::from_data(&name, &data, tracey::history::now_secs());
+            release.save(&path)?;
+            let (rules, implemented) = release.counts();
+            println!(
+                "Cut release {}: {} rule version(s), {} of them implemented, in {}",
+                name,
+                rules,
+                implemented,
+                path.display()
+            );
+            Ok(())
+        }
+
+        // r[impl cli.spec-diff]
+        Command::SpecDiff {
+            from,
+            to,
+            root,
+            config,
+            json,
+        } => {
+            use tracey::spec_diff::{Snapshot, diff, format_diff};
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let old = Snapshot::resolve(&project_root, &cfg, &from).await?;
+            let new = Snapshot::resolve(&project_root, &cfg, &to).await?;
+            let diff = diff(&from, &old, &to, &new);
+            if json {
+                println!(
+                    "{}",
+                    facet_json::to_string_pretty(&diff).expect("JSON serialization failed")
+                );
+            } else {
+                print!("{}", format_diff(&diff));
+            }
+            Ok(())
+        }
+
+        // r[impl cli.impact]
+        Command::Impact {
+            since,
+            root,
+            config,
+            json,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let changed = tracey::impact::changed_since(&project_root, &cfg, &since).await?;
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let report = tracey::impact::analyze(&since, &changed, &data.forward_by_impl);
+            if json {
+                println!(
+                    "{}",
+                    facet_json::to_string_pretty(&report).expect("JSON serialization failed")
+                );
+            } else {
+                print!("{}", tracey::impact::format_report(&report));
+            }
+            Ok(())
+        }
+
+        // r[impl cli.trace-ingest]
+        Command::TraceIngest {
+            trace,
+            root,
+            config,
+            json,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let report = tracey::trace_ingest::ingest(&project_root, &cfg, &trace).await?;
+            if json {
+                println!(
+                    "{}",
+                    facet_json::to_string_pretty(&report).expect("JSON serialization failed")
+                );
+            } else {
+                print!("{}", tracey::trace_ingest::format_report(&report));
+            }
+            Ok(())
+        }
+
+        // r[impl cli.baseline.record]
+        Command::Baseline {
+            action: BaselineAction::Record { root, config },
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let baseline = tracey::baseline::Baseline::from_data(&data);
+            let path = tracey::baseline::baseline_path(&project_root);
+            baseline.save(&path)?;
+            let uncovered: usize = baseline.impls.iter().map(|i| i.uncovered.len()).sum();
+            let stale: usize = baseline.impls.iter().map(|i| i.stale.len()).sum();
+            println!(
+                "Recorded {} uncovered rule(s) and {} stale reference(s) in {}",
+                uncovered,
+                stale,
+                path.display()
+            );
+            Ok(())
+        }
+
+        // r[impl cli.hooks.install]
+        Command::Hooks {
+            action:
+                HooksAction::Install {
+                    root,
+                    pre_push,
+                    manager,
+                },
+        } => {
+            use tracey::hooks::{Hook, HookManager, Installed};
+
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let manager = match manager {
+                Some(name) => HookManager::from_name(&name).ok_or_else(|| {
+                    eyre!(
+                        "Unknown hook manager {:?} (expected git, husky, pre-commit or lefthook)",
+                        name
+                    )
+                })?,
+                None => tracey::hooks::detect_manager(&project_root),
+            };
+            let mut hooks = vec![Hook::PreCommit];
+            if pre_push {
+                hooks.push(Hook::PrePush);
+            }
+
+            for outcome in tracey::hooks::install_all(&project_root, manager, &hooks)? {
+                let path = outcome.path.display();
+                match outcome.installed {
+                    Installed::Created => println!("Created {} ({})", path, outcome.hook.name()),
+                    Installed::Added => {
+                        println!("Added {} to {}", outcome.hook.name(), path)
+                    }
+                    Installed::Unchanged => {
+                        println!("{} already runs tracey ({})", path, outcome.hook.name())
+                    }
+                    Installed::Manual(stanza) => println!(
+                        "Couldn't edit {} safely; add this to it:\n\n{}",
+                        path, stanza
+                    ),
+                }
+            }
+            if manager == HookManager::PreCommit {
+                println!("Run `pre-commit install` to activate the hooks.");
+            }
+            Ok(())
+        }
+
+        // r[impl cli.todo]
+        Command::Todo {
+            root,
+            config,
+            spec_impl,
+            prefix,
+            per_section,
+            github_issues,
+            labels,
+            create,
+            emit_stubs,
+            stubs_in,
+        } => {
+            if create && !github_issues {
+                eyre::bail!("--create needs --github-issues");
+            }
+            if emit_stubs && github_issues {
+                eyre::bail!("--emit-stubs can't be combined with --github-issues");
+            }
+            if stubs_in.is_some() && !emit_stubs {
+                eyre::bail!("--stubs-in needs --emit-stubs");
+            }
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let (spec, impl_name) = bridge::query::parse_spec_impl(spec_impl.as_deref());
+            let options = tracey::todo::TodoOptions {
+                spec,
+                impl_name,
+                prefix,
+                per_section,
+            };
+            if emit_stubs {
+                let prefixes = data
+                    .config
+                    .specs
+                    .iter()
+                    .map(|spec| (spec.name.clone(), spec.prefix.clone()))
+                    .collect();
+                let (stubs, homeless) = tracey::todo::stubs(
+                    &data.forward_by_impl,
+                    &options,
+                    &prefixes,
+                    stubs_in.as_deref(),
+                );
+                let written = tracey::todo::write_stubs(&project_root, &stubs)?;
+                let files: std::collections::BTreeSet<&str> =
+                    stubs.iter().map(|s| s.file.as_str()).collect();
+                println!(
+                    "Wrote {} stub(s) into {} file(s); {} already there",
+                    written,
+                    files.len(),
+                    stubs.len() - written
+                );
+                if !homeless.is_empty() {
+                    println!(
+                        "No file to put a stub in for (pass --stubs-in FILE): {}",
+                        homeless.join(", ")
+                    );
+                }
+                return Ok(());
+            }
+            let items = tracey::todo::work_items(&data.forward_by_impl, &options);
+            let labels: Vec<String> = labels
+                .iter()
+                .flat_map(|l| l.split(','))
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect();
+
+            if !github_issues {
+                for item in &items {
+                    println!("{}\n\n{}", item.title, item.body);
+                }
+                println!("{} work item(s)", items.len());
+            } else if !create {
+                for item in &items {
+                    let issue = item.to_github_issue(&labels);
+                    println!(
+                        "{}",
+                        facet_json::to_string(&issue).expect("JSON serialization failed")
+                    );
+                }
+            } else {
+                let open = tracey::todo::open_issue_keys(&project_root)?;
+                let mut created = 0;
+                for item in items.iter().filter(|item| !open.contains(&item.key)) {
+                    let url =
+                        tracey::todo::create_issue(&project_root, &item.to_github_issue(&labels))?;
+                    println!("{}: {}", url, item.title);
+                    created += 1;
+                }
+                println!(
+                    "Created {} issue(s); {} already open",
+                    created,
+                    items.len() - created
+                );
+            }
+            Ok(())
+        }
+
+        // r[impl cli.sync.jira]
+        Command::Sync {
+            action:
+                SyncAction::Jira {
+                    root,
+                    config,
+                    dry_run,
+                },
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let Some(jira) = &cfg.jira else {
+                eyre::bail!("No `jira` block in {}", config.display());
+            };
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let tickets = tracey::jira::tickets(&data.forward_by_impl);
+            let client = tracey::jira::JiraClient::new(jira);
+            let actions = tracey::jira::plan(&tickets, &client.issues().await?);
+            for action in &actions {
+                if dry_run {
+                    println!("Would {}", action);
+                } else {
+                    let key = client.apply(action).await?;
+                    println!("{}: {}", key, action);
+                }
+            }
+            println!(
+                "{} MUST-level rule(s) with open debt, {} change(s){}",
+                tickets.len(),
+                actions.len(),
+                if dry_run { " (dry run)" } else { "" }
+            );
+            Ok(())
+        }
+
+        // r[impl cli.export]
+        Command::Export {
+            root,
+            config,
+            format,
+            output,
+            bundle,
+            sign_key,
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            // r[impl cli.export.bundle]
+            if let Some(path) = bundle {
+                if format.is_some() || output.is_some() {
+                    eyre::bail!("--bundle can't be combined with --format or --output");
+                }
+                let files =
+                    tracey::bundle::write(&project_root, &data, &path, sign_key.as_deref())?;
+                println!(
+                    "Wrote {} file(s){} to {}",
+                    files,
+                    if sign_key.is_some() {
+                        " and a signed manifest"
+                    } else {
+                        ""
+                    },
+                    path.display()
+                );
+                return Ok(());
+            }
+            if sign_key.is_some() {
+                eyre::bail!("--sign-key needs --bundle");
+            }
+            let model = tracey::export::ExportModel::from_data(&data);
+            match (format.as_deref().unwrap_or("json"), output) {
+                ("json", None) => println!("{}", model.to_json()),
+                ("json", Some(path)) => std::fs::write(&path, model.to_json())
+                    .wrap_err_with(|| format!("Failed to write {}", path.display()))?,
+                #[cfg(feature = "sqlite")]
+                ("sqlite", Some(path)) => model.write_sqlite(&path)?,
+                #[cfg(feature = "sqlite")]
+                ("sqlite", None) => eyre::bail!("--format sqlite needs an --output file"),
+                #[cfg(not(feature = "sqlite"))]
+                ("sqlite", _) => eyre::bail!("This build of tracey has no SQLite support"),
+                (other, _) => eyre::bail!("Unknown format '{}', expected json or sqlite", other),
+            }
+            Ok(())
+        }
+
+        // r[impl cli.report.compliance]
+        Command::Report {
+            root,
+            config,
+            format,
+            output,
+            schema,
+        } => {
+            match format.as_deref().unwrap_or("compliance-json") {
+                "compliance-json" => {}
+                other => eyre::bail!(
+                    "Unknown report format '{}', expected compliance-json",
+                    other
+                ),
+            }
+            let json = if schema {
+                tracey::compliance::COMPLIANCE_SCHEMA.to_string()
+            } else {
+                let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+                let cfg = load_config_or_exit(&project_root.join(&config));
+                let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+                let report = tracey::compliance::ComplianceReport::from_data(
+                    &data,
+                    tracey::history::now_secs(),
+                );
+                report.to_json() + "\n"
+            };
+            match output {
+                None => print!("{}", json),
+                Some(path) => std::fs::write(&path, json)
+                    .wrap_err_with(|| format!("Failed to write {}", path.display()))?,
+            }
+            Ok(())
+        }
+
+        // r[impl cli.verify-bundle]
+        Command::VerifyBundle {
+            bundle,
+            allowed_signers,
+            identity,
+        } => {
+            let signers = match (&allowed_signers, &identity) {
+                (Some(allowed_signers), Some(identity)) => Some(tracey::bundle::Signers {
+                    allowed_signers,
+                    identity,
+                }),
+                (None, None) => None,
+                _ => eyre::bail!("--allowed-signers and --identity go together"),
+            };
+            let verified = tracey::bundle::verify(&bundle, signers.as_ref())?;
+            let revision = &verified.revision;
+            println!(
+                "{} is intact: {} file(s) match the manifest{}",
+                bundle.display(),
+                verified.files,
+                if verified.signature_checked {
+                    format!(", signed by {}", identity.unwrap_or_default())
+                } else {
+                    String::new()
+                }
+            );
+            println!(
+                "Commit {}{}, made by tracey {}",
+                revision.commit.as_deref().unwrap_or("(none)"),
+                if revision.dirty {
+                    " with uncommitted changes"
+                } else {
+                    ""
+                },
+                revision.tracey_version
+            );
+            Ok(())
+        }
 
-        // r[impl cli.pre-commit]
-        Command::PreCommit { root, config } => {
+        // r[impl cli.snapshot.record]
+        Command::Snapshot {
+            action: SnapshotAction::Record { root, config },
+        } => {
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
-            let config_path = project_root.join(&config);
-            let cfg = load_bump_config(&config_path);
-            let passed = tracey::bump::pre_commit(&project_root, &cfg).await?;
-            if !passed {
-                std::process::exit(1);
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let data = tracey::data::build_dashboard_data(&project_root, &cfg, 0, true).await?;
+            let rows = tracey::history::snapshot_rows(&data);
+            let rules = tracey::history::snapshot_rule_rows(&data);
+            #[cfg(feature = "sqlite")]
+            {
+                let mut history = tracey::history::History::open(&project_root)?;
+                history.record(tracey::history::now_secs(), &rows, &rules)?;
+                println!(
+                    "Recorded coverage of {} spec/impl pair(s) in {}",
+                    rows.len(),
+                    tracey::history::history_path(&project_root).display()
+                );
+                Ok(())
+            }
+            #[cfg(not(feature = "sqlite"))]
+            {
+                let _ = (rows, rules);
+                eyre::bail!("This build of tracey has no SQLite support")
             }
-            Ok(())
         }
 
         // r[impl cli.bump]
-        Command::Bump { root, config } => {
+        Command::Bump {
+            root,
+            config,
+            no_changelog,
+            interactive,
+            working_tree,
+            since,
+        } => {
             let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
             let config_path = project_root.join(&config);
             let cfg = load_bump_config(&config_path);
-            let bumped = tracey::bump::bump(&project_root, &cfg).await?;
+            if interactive && (working_tree || since.is_some()) {
+                eyre::bail!("-i only works on staged changes against HEAD");
+            }
+            let bumped = if working_tree || since.is_some() {
+                // r[impl cli.bump.working-tree]
+                let target = if working_tree {
+                    tracey::bump::Target::WorkTree
+                } else {
+                    tracey::bump::Target::Index
+                };
+                let base = since
+                    .as_deref()
+                    .unwrap_or_else(|| tracey::bump::index_base(&project_root));
+                tracey::bump::bump_rules_in(&project_root, &cfg, base, target).await?
+            } else if interactive {
+                // r[impl cli.bump.interactive]
+                let stdin = std::io::stdin();
+                tracey::bump::bump_interactive(
+                    &project_root,
+                    &cfg,
+                    &mut stdin.lock(),
+                    &mut std::io::stdout(),
+                )
+                .await?
+            } else {
+                tracey::bump::bump_rules(&project_root, &cfg).await?
+            };
             if bumped.is_empty() {
-                println!("No staged rule changes require a version bump.");
+                if working_tree {
+                    println!("No rule changes require a version bump.");
+                } else {
+                    println!("No staged rule changes require a version bump.");
+                }
             } else {
                 println!("Bumped {} rule(s):", bumped.len());
-                for id in &bumped {
-                    println!("  {id}");
+                for rule in &bumped {
+                    println!("  {}", rule.new_id);
                 }
                 println!();
-                println!("Affected spec files have been re-staged. Review and commit.");
+                // r[impl cli.bump.changelog]
+                if !no_changelog {
+                    let today = tracey::changelog::today();
+                    let path = if working_tree {
+                        tracey::changelog::write(&project_root, &today, &bumped)?
+                    } else {
+                        tracey::changelog::update(&project_root, &today, &bumped)?
+                    };
+                    println!("Added them to {}.", path.display());
+                }
+                if working_tree {
+                    println!("Affected spec files have been updated but not staged.");
+                } else {
+                    println!("Affected spec files have been re-staged. Review and commit.");
+                }
+            }
+            Ok(())
+        }
+
+        // r[impl cli.approve]
+        Command::Approve {
+            rule_id,
+            by,
+            key,
+            config,
+        } => {
+            let project_root = find_project_root()?;
+            let cfg = load_config_or_exit(&project_root.join(&config));
+            let Some(id) = tracey_core::parse_rule_id(&rule_id) else {
+                eyre::bail!("Invalid rule ID {:?}", rule_id);
+            };
+            let by = match by {
+                Some(by) => by,
+                None => tracey::bump::git_capture(&project_root, &["config", "user.name"])
+                    .map(|name| name.trim().to_string())
+                    .ok()
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| eyre::eyre!("Pass --by NAME (git user.name is not set)"))?,
+            };
+            let key = key
+                .or_else(|| tracey::approvals::git_signing_key(&project_root))
+                .ok_or_else(|| {
+                    eyre::eyre!(
+                        "Pass --key PATH to sign the approval (git user.signingkey is not set)"
+                    )
+                })?;
+            let (approval, path) =
+                tracey::approvals::approve(&project_root, &cfg, &id, &by, &key).await?;
+            let path = path.strip_prefix(&project_root).unwrap_or(&path);
+            println!(
+                "{} approved by {} on {} ({})",
+                approval.rule,
+                approval.by,
+                approval.date,
+                path.display()
+            );
+            Ok(())
+        }
+
+        // r[impl cli.merge-driver]
+        Command::MergeDriver {
+            files,
+            install,
+            config,
+        } => {
+            let project_root = find_project_root().unwrap_or_default();
+            let cfg = load_bump_config(&project_root.join(&config));
+            if install {
+                let added = tracey::merge::install(&project_root, &cfg)?;
+                println!("Registered the tracey merge driver in git config.");
+                for pattern in &added {
+                    println!("  .gitattributes: {} merge=tracey", pattern);
+                }
+                return Ok(());
+            }
+
+            let [ancestor, current, other, ..] = files.as_slice() else {
+                eyre::bail!("usage: tracey merge-driver ANCESTOR CURRENT OTHER [PATH]");
+            };
+            let read = |path: &PathBuf| {
+                std::fs::read_to_string(path)
+                    .wrap_err_with(|| format!("Failed to read {}", path.display()))
+            };
+            let scheme = tracey::config::version_scheme(&cfg).unwrap_or_default();
+            let outcome =
+                tracey::merge::merge_spec(&read(ancestor)?, &read(current)?, &read(other)?, scheme)
+                    .await?;
+            std::fs::write(current, &outcome.merged)
+                .wrap_err_with(|| format!("Failed to write {}", current.display()))?;
+
+            let name = files.get(3).unwrap_or(current).display();
+            for rule in &outcome.renumbered {
+                eprintln!("{}: both sides bumped {}; now {}", name, rule.base, rule);
+            }
+            if outcome.conflicts > 0 {
+                eprintln!("{}: {} conflict(s) in rule text", name, outcome.conflicts);
+                std::process::exit(1);
             }
             Ok(())
         }
 
+        // r[impl daemon.cli.watch]
+        Command::Watch { root } => {
+            let project_root = match root {
+                Some(path) => tracey::find_project_root_from(&path),
+                None => find_project_root().unwrap_or_default(),
+            };
+            // Log lines would scroll the summary off screen.
+            init_tracing(TracingConfig {
+                log_file: None,
+                enable_console: false,
+                console_ansi: false,
+                default_filter: "tracey=info",
+            })?;
+            bridge::watch::run(project_root).await
+        }
+
         // r[impl daemon.cli.query]
-        Command::Query { root, json, query } => {
-            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+        Command::Query {
+            root,
+            json,
+            format,
+            porcelain,
+            hyperlink,
+            query,
+        } => {
+            // r[impl config.discovery.nearest]
+            let project_root = match root {
+                Some(path) => tracey::find_project_root_from(&path),
+                None => find_project_root().unwrap_or_default(),
+            };
             let query_client =
-                bridge::query::QueryClient::new(project_root, bridge::query::Caller::Cli);
+                bridge::query::QueryClient::new(project_root.clone(), bridge::query::Caller::Cli);
             init_tracing(TracingConfig {
                 log_file: None,
                 enable_console: !json,
@@ -430,22 +2245,78 @@ async fn main() -> Result<()> {
                 default_filter: "tracey=info",
             })?;
 
+            // r[impl cli.exit-codes]
+            let config_error = match query_client.call(query_client.client.health()).await {
+                Ok(health) => health.config_error.is_some(),
+                Err(e) => {
+                    eprintln!(
+                        "{}",
+                        format!("Could not reach the tracey daemon: {e}").red()
+                    );
+                    std::process::exit(exit_code::DAEMON_UNREACHABLE);
+                }
+            };
+            let gate = fail_gate(&query);
+
             if json {
                 let output = query_json(&query_client, query).await;
                 println!("{}", output);
-                return Ok(());
+                return finish_query(&query_client, gate, config_error).await;
+            }
+
+            let format = match (format, porcelain) {
+                (Some(_), true) => eyre::bail!("--format and --porcelain can't be combined"),
+                (Some(format), false) => Some(
+                    bridge::table::TableFormat::parse(&format)
+                        .ok_or_else(|| eyre!("Unknown format '{}', expected csv or md", format))?,
+                ),
+                (None, true) => Some(bridge::table::TableFormat::Porcelain),
+                (None, false) => None,
+            };
+            if let Some(format) = format {
+                print!("{}", query_table(&query_client, query, format).await?);
+                return finish_query(&query_client, gate, config_error).await;
             }
 
             let output = match query {
-                QueryCommand::Status => query_client.status().await,
-                QueryCommand::Uncovered { spec_impl, prefix } => {
+                QueryCommand::Status { by_rule: false } => query_client.status().await,
+                QueryCommand::Status { by_rule: true } => query_client.status_by_rule().await,
+                QueryCommand::Uncovered {
+                    spec_impl,
+                    prefix,
+                    owner,
+                    sort,
+                    limit,
+                    offset,
+                    ..
+                } => {
+                    let list = list_args(sort.as_deref(), limit, offset)?;
                     query_client
-                        .uncovered(spec_impl.as_deref(), prefix.as_deref())
+                        .uncovered(
+                            spec_impl.as_deref(),
+                            prefix.as_deref(),
+                            owner.as_deref(),
+                            list,
+                        )
                         .await
                 }
-                QueryCommand::Untested { spec_impl, prefix } => {
+                QueryCommand::Untested {
+                    spec_impl,
+                    prefix,
+                    only_implemented,
+                    sort,
+                    limit,
+                    offset,
+                    ..
+                } => {
+                    let list = list_args(sort.as_deref(), limit, offset)?;
                     query_client
-                        .untested(spec_impl.as_deref(), prefix.as_deref())
+                        .untested(
+                            spec_impl.as_deref(),
+                            prefix.as_deref(),
+                            only_implemented,
+                            list,
+                        )
                         .await
                 }
                 QueryCommand::Unmapped { spec_impl, path } => {
@@ -453,19 +2324,143 @@ async fn main() -> Result<()> {
                         .unmapped(spec_impl.as_deref(), path.as_deref())
                         .await
                 }
-                QueryCommand::Stale { spec_impl, prefix } => {
+                QueryCommand::Stale {
+                    spec_impl,
+                    prefix,
+                    sort,
+                    limit,
+                    offset,
+                    ..
+                } => {
+                    let list = list_args(sort.as_deref(), limit, offset)?;
+                    query_client
+                        .stale(spec_impl.as_deref(), prefix.as_deref(), list)
+                        .await
+                }
+                QueryCommand::Coverage {
+                    spec_impl,
+                    by_dir,
+                    depth,
+                    sort,
+                } => {
+                    let sort = parse_dir_coverage_sort(sort.as_deref())?;
+                    query_client
+                        .coverage(spec_impl.as_deref(), by_dir, depth, sort)
+                        .await
+                }
+                QueryCommand::Matrix { spec, prefix } => {
+                    query_client
+                        .matrix(spec.as_deref(), prefix.as_deref())
+                        .await
+                }
+                QueryCommand::Search { query, limit } => query_client.search(&query, limit).await,
+                QueryCommand::Refs { file } => query_client.refs(&file).await,
+                QueryCommand::Dead { spec_impl, days } => {
+                    query_client.dead(spec_impl.as_deref(), days).await
+                }
+                QueryCommand::Unrefined { spec_impl } => {
+                    query_client.unrefined(spec_impl.as_deref()).await
+                }
+                QueryCommand::Unexecuted { spec_impl, prefix } => {
                     query_client
-                        .stale(spec_impl.as_deref(), prefix.as_deref())
+                        .unexecuted(spec_impl.as_deref(), prefix.as_deref())
                         .await
                 }
-                QueryCommand::Rule { rule_ids } => query_client.rules(&rule_ids).await,
+                QueryCommand::Trend { spec, since } => {
+                    query_client.trend(spec.as_deref(), since.as_deref()).await
+                }
+                QueryCommand::Recent { since } => {
+                    query_client.recent(since.as_deref().unwrap_or("7d")).await
+                }
+                QueryCommand::Rule { rule_ids, blame } => {
+                    query_client.rules(&rule_ids, blame).await
+                }
                 QueryCommand::Config => query_client.config().await,
                 QueryCommand::Validate { spec_impl } => {
                     query_client.validate(spec_impl.as_deref()).await
                 }
+                QueryCommand::SpecDrift => query_client.spec_drift().await,
+                QueryCommand::Spec { name } => query_client.spec_outline(name.as_deref()).await,
             };
 
+            // r[impl daemon.cli.query.hyperlink]
+            let output = if hyperlink {
+                let cfg = tracey::load_config_or_default(&project_root.join(tracey::CONFIG_PATH));
+                let links = match tracey::hyperlink::EditorLinks::new(cfg.editor_links.as_deref()) {
+                    Ok(links) => links,
+                    Err(e) => {
+                        eprintln!("{}", format!("{e:?}").red());
+                        std::process::exit(exit_code::CONFIG_ERROR);
+                    }
+                };
+                tracey::hyperlink::hyperlink(&output, &project_root, &links)
+            } else {
+                output
+            };
             println!("{}", output);
+            finish_query(&query_client, gate, config_error).await
+        }
+
+        // r[impl cli.config.migrate]
+        Command::Config {
+            action:
+                ConfigAction::Migrate {
+                    root,
+                    config,
+                    dry_run,
+                },
+        } => {
+            let project_root = root.unwrap_or_else(|| find_project_root().unwrap_or_default());
+            let config_path = project_root.join(&config);
+            let outcome = tracey::migrate::migrate_file(&config_path, dry_run)?;
+
+            for change in &outcome.changes {
+                println!("  line {}: {}", change.line, change.description);
+            }
+            for manual in &outcome.manual {
+                println!("  line {}: {}", manual.line, manual.description);
+            }
+            if outcome.changes.is_empty() {
+                if outcome.manual.is_empty() {
+                    println!("{} already uses the current schema.", config.display());
+                }
+            } else if dry_run {
+                println!(
+                    "{} change(s) would be made to {} (dry run).",
+                    outcome.changes.len(),
+                    config.display()
+                );
+            } else {
+                println!(
+                    "Migrated {} ({} change(s)).",
+                    config.display(),
+                    outcome.changes.len()
+                );
+            }
+            if !outcome.manual.is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+
+        // r[impl config.discovery.nested]
+        Command::Projects { root } => {
+            let root = match root {
+                Some(r) => r,
+                None => std::env::current_dir().wrap_err("Failed to get current directory")?,
+            };
+            let projects = tracey::discover_projects(&root);
+            if projects.is_empty() {
+                println!("No tracey projects found under {}", root.display());
+            }
+            for project in projects {
+                let display = project.strip_prefix(&root).unwrap_or(&project);
+                if display.as_os_str().is_empty() {
+                    println!(".");
+                } else {
+                    println!("{}", display.display());
+                }
+            }
             Ok(())
         }
 
@@ -488,49 +2483,408 @@ fn json_error(message: &str) -> String {
     .expect("JSON serialization failed")
 }
 
+fn parse_dir_coverage_sort(sort: Option<&str>) -> Result<Option<tracey_proto::DirCoverageSort>> {
+    use tracey_proto::DirCoverageSort;
+
+    Ok(match sort {
+        None => None,
+        Some("coverage") => Some(DirCoverageSort::Coverage),
+        Some("unmapped") => Some(DirCoverageSort::Unmapped),
+        Some("path") => Some(DirCoverageSort::Path),
+        Some(other) => eyre::bail!(
+            "Unknown sort order '{}', expected coverage, unmapped or path",
+            other
+        ),
+    })
+}
+
+/// The `--fail-on-any` / `--fail-if-more-than` gate of a list query, if any.
+fn fail_gate(query: &QueryCommand) -> Option<bridge::query::FailGate> {
+    use bridge::query::{FailGate, GateList};
+
+    let (list, spec_impl, prefix, owner, fail_on_any, fail_if_more_than) = match query {
+        QueryCommand::Uncovered {
+            spec_impl,
+            prefix,
+            owner,
+            fail_on_any,
+            fail_if_more_than,
+            ..
+        } => (
+            GateList::Uncovered,
+            spec_impl,
+            prefix,
+            owner.clone(),
+            fail_on_any,
+            fail_if_more_than,
+        ),
+        QueryCommand::Untested {
+            spec_impl,
+            prefix,
+            only_implemented,
+            fail_on_any,
+            fail_if_more_than,
+            ..
+        } => (
+            GateList::Untested {
+                only_implemented: *only_implemented,
+            },
+            spec_impl,
+            prefix,
+            None,
+            fail_on_any,
+            fail_if_more_than,
+        ),
+        QueryCommand::Stale {
+            spec_impl,
+            prefix,
+            fail_on_any,
+            fail_if_more_than,
+            ..
+        } => (
+            GateList::Stale,
+            spec_impl,
+            prefix,
+            None,
+            fail_on_any,
+            fail_if_more_than,
+        ),
+        _ => return None,
+    };
+    FailGate::new(
+        list,
+        spec_impl.clone(),
+        prefix.clone(),
+        owner,
+        *fail_on_any,
+        *fail_if_more_than,
+    )
+}
+
+/// Exit with the query's status once its output has been printed: a config
+/// error first, then a result count over the query's fail gate.
+///
+/// r[impl daemon.cli.query.fail-on]
+async fn finish_query(
+    qc: &bridge::query::QueryClient,
+    gate: Option<bridge::query::FailGate>,
+    config_error: bool,
+) -> Result<()> {
+    if config_error {
+        std::process::exit(exit_code::CONFIG_ERROR);
+    }
+    let Some(gate) = gate else {
+        return Ok(());
+    };
+    let count = match qc.gate_count(&gate).await {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                format!("Could not reach the tracey daemon: {e}").red()
+            );
+            std::process::exit(exit_code::DAEMON_UNREACHABLE);
+        }
+    };
+    if let Some(violation) = gate.violation(count) {
+        eprintln!("{}", violation.red());
+        std::process::exit(exit_code::FINDINGS);
+    }
+    Ok(())
+}
+
+fn parse_rule_list_sort(sort: Option<&str>) -> Result<Option<tracey_proto::RuleListSort>> {
+    use tracey_proto::RuleListSort;
+
+    Ok(match sort {
+        None => None,
+        Some("id") => Some(RuleListSort::Id),
+        Some("section") => Some(RuleListSort::Section),
+        Some("refs") => Some(RuleListSort::Refs),
+        Some(other) => eyre::bail!(
+            "Unknown sort order '{}', expected id, section or refs",
+            other
+        ),
+    })
+}
+
+fn list_args(
+    sort: Option<&str>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<bridge::query::ListArgs> {
+    Ok(bridge::query::ListArgs {
+        sort: parse_rule_list_sort(sort)?,
+        limit,
+        offset,
+    })
+}
+
+/// Handle `tracey query --format csv|md <subcommand>` for the list-shaped
+/// queries that flatten into a table.
+async fn query_table(
+    qc: &bridge::query::QueryClient,
+    query: QueryCommand,
+    format: bridge::table::TableFormat,
+) -> Result<String> {
+    use bridge::query::parse_spec_impl;
+    use bridge::table;
+    use tracey_proto::*;
+
+    let table = match query {
+        QueryCommand::Status { .. } => table::status_table(
+            &qc.call(qc.client.status())
+                .await
+                .map_err(|e| eyre!("{e}"))?,
+        ),
+        QueryCommand::Uncovered {
+            spec_impl,
+            prefix,
+            owner,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = UncoveredRequest {
+                spec,
+                impl_name,
+                prefix,
+                owner,
+                sort: parse_rule_list_sort(sort.as_deref())?,
+                limit,
+                offset,
+            };
+            let resp = qc
+                .call(qc.client.uncovered(req))
+                .await
+                .map_err(|e| eyre!("{e}"))?;
+            table::section_rules_table(&resp.spec, &resp.impl_name, &resp.by_section)
+        }
+        QueryCommand::Untested {
+            spec_impl,
+            prefix,
+            only_implemented,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = UntestedRequest {
+                spec,
+                impl_name,
+                prefix,
+                sort: parse_rule_list_sort(sort.as_deref())?,
+                limit,
+                offset,
+                only_implemented,
+            };
+            let resp = qc
+                .call(qc.client.untested(req))
+                .await
+                .map_err(|e| eyre!("{e}"))?;
+            table::untested_table(&resp)
+        }
+        QueryCommand::Stale {
+            spec_impl,
+            prefix,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = StaleRequest {
+                spec,
+                impl_name,
+                prefix,
+                sort: parse_rule_list_sort(sort.as_deref())?,
+                limit,
+                offset,
+            };
+            let resp = qc
+                .call(qc.client.stale(req))
+                .await
+                .map_err(|e| eyre!("{e}"))?;
+            table::stale_table(&resp)
+        }
+        _ => eyre::bail!(
+            "--format and --porcelain are only supported for status, uncovered, untested and stale"
+        ),
+    };
+    Ok(table.render(format))
+}
+
 /// Handle `tracey query --json <subcommand>` by calling the daemon client
 /// directly and serializing the typed response as JSON.
 async fn query_json(qc: &bridge::query::QueryClient, query: QueryCommand) -> String {
-    use bridge::query::parse_spec_impl;
+    use bridge::query::{RuleLookup, parse_spec_impl};
     use tracey_proto::*;
 
     match query {
-        QueryCommand::Status => match qc.client.status().await {
+        QueryCommand::Status { .. } => match qc.call(qc.client.status()).await {
             Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
             Err(e) => json_error(&e.to_string()),
         },
-        QueryCommand::Uncovered { spec_impl, prefix } => {
+        QueryCommand::Uncovered {
+            spec_impl,
+            prefix,
+            owner,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let sort = match parse_rule_list_sort(sort.as_deref()) {
+                Ok(sort) => sort,
+                Err(e) => return json_error(&e.to_string()),
+            };
             let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
             let req = UncoveredRequest {
                 spec,
                 impl_name,
                 prefix,
+                owner,
+                sort,
+                limit,
+                offset,
             };
-            match qc.client.uncovered(req).await {
+            match qc.call(qc.client.uncovered(req)).await {
                 Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
                 Err(e) => json_error(&e.to_string()),
             }
         }
-        QueryCommand::Untested { spec_impl, prefix } => {
+        QueryCommand::Untested {
+            spec_impl,
+            prefix,
+            only_implemented,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let sort = match parse_rule_list_sort(sort.as_deref()) {
+                Ok(sort) => sort,
+                Err(e) => return json_error(&e.to_string()),
+            };
             let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
             let req = UntestedRequest {
                 spec,
                 impl_name,
                 prefix,
+                sort,
+                limit,
+                offset,
+                only_implemented,
             };
-            match qc.client.untested(req).await {
+            match qc.call(qc.client.untested(req)).await {
                 Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
                 Err(e) => json_error(&e.to_string()),
             }
         }
-        QueryCommand::Stale { spec_impl, prefix } => {
+        QueryCommand::Stale {
+            spec_impl,
+            prefix,
+            sort,
+            limit,
+            offset,
+            ..
+        } => {
+            let sort = match parse_rule_list_sort(sort.as_deref()) {
+                Ok(sort) => sort,
+                Err(e) => return json_error(&e.to_string()),
+            };
             let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
             let req = StaleRequest {
                 spec,
                 impl_name,
                 prefix,
+                sort,
+                limit,
+                offset,
+            };
+            match qc.call(qc.client.stale(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Coverage {
+            spec_impl,
+            by_dir: _,
+            depth,
+            sort,
+        } => {
+            let sort = match parse_dir_coverage_sort(sort.as_deref()) {
+                Ok(sort) => sort,
+                Err(e) => return json_error(&e.to_string()),
+            };
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = DirCoverageRequest {
+                spec,
+                impl_name,
+                depth,
+                sort,
+            };
+            match qc.call(qc.client.dir_coverage(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Matrix { spec, prefix } => {
+            match qc
+                .call(qc.client.matrix(MatrixRequest { spec, prefix }))
+                .await
+            {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Search { query, limit } => {
+            let limit = limit.unwrap_or(bridge::query::DEFAULT_SEARCH_LIMIT);
+            match qc.call(qc.client.search(query, limit)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Refs { file } => {
+            let req = FileRefsRequest {
+                path: bridge::query::resolve_source_path(&file),
+            };
+            match qc.call(qc.client.file_refs(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Dead { spec_impl, days } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = DeadRequest {
+                spec,
+                impl_name,
+                days,
+            };
+            match qc.call(qc.client.dead(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Unrefined { spec_impl } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = UnrefinedRequest { spec, impl_name };
+            match qc.call(qc.client.unrefined(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Unexecuted { spec_impl, prefix } => {
+            let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
+            let req = UnexecutedRequest {
+                spec,
+                impl_name,
+                prefix,
             };
-            match qc.client.stale(req).await {
+            match qc.call(qc.client.unexecuted(req)).await {
                 Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
                 Err(e) => json_error(&e.to_string()),
             }
@@ -542,31 +2896,101 @@ async fn query_json(qc: &bridge::query::QueryClient, query: QueryCommand) -> Str
                 impl_name,
                 path,
             };
-            match qc.client.unmapped(req).await {
+            match qc.call(qc.client.unmapped(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Trend { spec, since } => {
+            let since_secs = match since.as_deref().map(tracey::history::parse_age).transpose() {
+                Ok(since_secs) => since_secs,
+                Err(e) => return json_error(&e.to_string()),
+            };
+            let (spec, impl_name) = parse_spec_impl(spec.as_deref());
+            let req = TrendRequest {
+                spec,
+                impl_name,
+                since_secs,
+            };
+            match qc.call(qc.client.coverage_trend(req)).await {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
+            }
+        }
+        QueryCommand::Recent { since } => {
+            let req = RecentRequest {
+                since: since.unwrap_or_else(|| "7d".to_string()),
+            };
+            match qc.call(qc.client.recent(req)).await {
                 Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
                 Err(e) => json_error(&e.to_string()),
             }
         }
-        QueryCommand::Rule { rule_ids } => {
-            let mut infos = Vec::new();
+        QueryCommand::Rule { rule_ids, blame } => {
+            let mut infos: Vec<RuleInfo> = Vec::new();
+            let mut expanded = false;
             for raw_id in &rule_ids {
+                if bridge::query::is_rule_pattern(raw_id) {
+                    expanded = true;
+                    let ids = match qc.call(qc.client.find_rules(raw_id.clone())).await {
+                        Ok(ids) => ids,
+                        Err(e) => return json_error(&e.to_string()),
+                    };
+                    for id in ids {
+                        if infos.iter().any(|info| info.id == id) {
+                            continue;
+                        }
+                        match qc.call(qc.client.rule(id)).await {
+                            Ok(Some(info)) => infos.push(info),
+                            Ok(None) => {}
+                            Err(e) => return json_error(&e.to_string()),
+                        }
+                    }
+                    continue;
+                }
                 let Some(parsed) = tracey_core::parse_rule_id(raw_id) else {
                     return json_error(&format!("invalid rule ID: {raw_id}"));
                 };
-                match qc.client.rule(parsed).await {
-                    Ok(Some(info)) => infos.push(info),
-                    Ok(None) => return json_error(&format!("rule not found: {raw_id}")),
-                    Err(e) => return json_error(&e.to_string()),
+                match qc.lookup_rule(parsed).await {
+                    Ok(RuleLookup::Found(info) | RuleLookup::Matched(info)) => infos.push(info),
+                    Ok(RuleLookup::Candidates(candidates)) => {
+                        let candidates: Vec<String> =
+                            candidates.iter().map(ToString::to_string).collect();
+                        return json_error(&format!(
+                            "rule not found: {raw_id} (did you mean: {})",
+                            candidates.join(", ")
+                        ));
+                    }
+                    Ok(RuleLookup::NotFound) => {
+                        return json_error(&format!("rule not found: {raw_id}"));
+                    }
+                    Err(e) => return json_error(&e),
+                }
+            }
+            if blame {
+                let mut blames: Vec<RuleBlameResponse> = Vec::new();
+                for info in &infos {
+                    match qc.call(qc.client.rule_blame(info.id.clone())).await {
+                        Ok(Some(blame)) => blames.push(blame),
+                        Ok(None) => {}
+                        Err(e) => return json_error(&e.to_string()),
+                    }
                 }
+                return if blames.len() == 1 && !expanded {
+                    facet_json::to_string_pretty(&blames.into_iter().next().unwrap())
+                        .expect("JSON serialization failed")
+                } else {
+                    facet_json::to_string_pretty(&blames).expect("JSON serialization failed")
+                };
             }
-            if infos.len() == 1 {
+            if infos.len() == 1 && !expanded {
                 facet_json::to_string_pretty(&infos.into_iter().next().unwrap())
                     .expect("JSON serialization failed")
             } else {
                 facet_json::to_string_pretty(&infos).expect("JSON serialization failed")
             }
         }
-        QueryCommand::Config => match qc.client.config().await {
+        QueryCommand::Config => match qc.call(qc.client.config()).await {
             Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
             Err(e) => json_error(&e.to_string()),
         },
@@ -574,37 +2998,36 @@ async fn query_json(qc: &bridge::query::QueryClient, query: QueryCommand) -> Str
             if spec_impl.is_some() {
                 let (spec, impl_name) = parse_spec_impl(spec_impl.as_deref());
                 let req = ValidateRequest { spec, impl_name };
-                match qc.client.validate(req).await {
+                match qc.call(qc.client.validate(req)).await {
                     Ok(resp) => {
                         facet_json::to_string_pretty(&resp).expect("JSON serialization failed")
                     }
                     Err(e) => json_error(&e.to_string()),
                 }
             } else {
-                // Validate all spec/impl combinations
-                let status = match qc.client.status().await {
-                    Ok(s) => s,
-                    Err(e) => return json_error(&format!("error getting status: {e}")),
-                };
-
-                let mut results = Vec::new();
-                for impl_status in &status.impls {
-                    let req = ValidateRequest {
-                        spec: Some(impl_status.spec.clone()),
-                        impl_name: Some(impl_status.impl_name.clone()),
-                    };
-                    match qc.client.validate(req).await {
-                        Ok(result) => results.push(result),
-                        Err(e) => {
-                            return json_error(&format!(
-                                "error validating {}/{}: {e}",
-                                impl_status.spec, impl_status.impl_name
-                            ));
-                        }
+                match qc.call(qc.client.validate_all()).await {
+                    Ok(results) => {
+                        facet_json::to_string_pretty(&results).expect("JSON serialization failed")
                     }
+                    Err(e) => json_error(&e.to_string()),
                 }
-
-                facet_json::to_string_pretty(&results).expect("JSON serialization failed")
+            }
+        }
+        QueryCommand::SpecDrift => match qc.call(qc.client.spec_drift()).await {
+            Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+            Err(e) => json_error(&e.to_string()),
+        },
+        QueryCommand::Spec { name } => {
+            let (spec, impl_name) = parse_spec_impl(name.as_deref());
+            match qc
+                .call(
+                    qc.client
+                        .spec_outline(SpecOutlineRequest { spec, impl_name }),
+                )
+                .await
+            {
+                Ok(resp) => facet_json::to_string_pretty(&resp).expect("JSON serialization failed"),
+                Err(e) => json_error(&e.to_string()),
             }
         }
     }
@@ -667,15 +3090,13 @@ fn init_tracing(config: TracingConfig) -> Result<()> {
             std::fs::create_dir_all(parent)?;
         }
 
-        let log_file = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)?;
+        // r[impl daemon.logs.rotation]
+        let log_file = daemon::logs::RotatingLog::open(&log_path)?;
 
         Some(
             tracing_subscriber::fmt::layer()
                 .with_ansi(false)
-                .with_writer(log_file),
+                .with_writer(std::sync::Mutex::new(log_file)),
         )
     } else {
         None
@@ -690,9 +3111,17 @@ fn init_tracing(config: TracingConfig) -> Result<()> {
     Ok(())
 }
 
-/// Build a bridge log path with process ID in the filename.
-fn bridge_log_path(project_root: &std::path::Path, bridge: &str) -> PathBuf {
-    daemon::state_dir(project_root).join(format!("{bridge}-{}.log", std::process::id()))
+/// Build a bridge log path, with the process ID in the filename unless the
+/// log is shared, and clean up the logs of bridges that have exited.
+fn bridge_log_path(project_root: &std::path::Path, bridge: &str, shared: bool) -> PathBuf {
+    let state_dir = daemon::state_dir(project_root);
+    // r[impl daemon.logs.bridge-cleanup]
+    daemon::logs::remove_dead_bridge_logs(&state_dir);
+    if shared {
+        state_dir.join(format!("{bridge}.log"))
+    } else {
+        state_dir.join(format!("{bridge}-{}.log", std::process::id()))
+    }
 }
 
 /// Write a startup marker so bridge launches are visible even before first tracing event.
@@ -732,8 +3161,14 @@ fn write_bridge_start_marker(
 }
 
 /// r[impl daemon.cli.logs]
-/// Show daemon logs from the state directory
-fn show_logs(root: Option<PathBuf>, follow: bool, lines: usize) -> Result<()> {
+/// Show daemon or bridge logs from the state directory
+fn show_logs(
+    root: Option<PathBuf>,
+    follow: bool,
+    lines: usize,
+    bridge: Option<&str>,
+    filter: daemon::logs::LogFilter,
+) -> Result<()> {
     use std::io::{BufRead, BufReader, Seek, SeekFrom};
 
     let project_root = match root {
@@ -741,7 +3176,25 @@ fn show_logs(root: Option<PathBuf>, follow: bool, lines: usize) -> Result<()> {
         None => find_project_root()?,
     };
 
-    let log_path = daemon::state_dir(&project_root).join("daemon.log");
+    let state_dir = daemon::state_dir(&project_root);
+    let log_path = match bridge {
+        Some(bridge @ ("mcp" | "lsp")) => {
+            match daemon::logs::latest_bridge_log(&state_dir, bridge) {
+                Some(path) => path,
+                None => {
+                    eprintln!(
+                        "{}: No {} bridge log found in {}",
+                        "Warning".yellow(),
+                        bridge,
+                        state_dir.display()
+                    );
+                    return Ok(());
+                }
+            }
+        }
+        Some(other) => eyre::bail!("Unknown bridge '{}', expected mcp or lsp", other),
+        None => state_dir.join("daemon.log"),
+    };
 
     if !log_path.exists() {
         eprintln!(
@@ -753,34 +3206,41 @@ fn show_logs(root: Option<PathBuf>, follow: bool, lines: usize) -> Result<()> {
         return Ok(());
     }
 
-    let file = std::fs::File::open(&log_path)?;
-    let reader = BufReader::new(file);
+    // Open before reading the history, so lines written meanwhile are
+    // followed rather than lost.
+    let mut reader = BufReader::new(std::fs::File::open(&log_path)?);
+    reader.seek(SeekFrom::End(0))?;
 
     // r[impl daemon.cli.logs.lines]
-    // Read the last N lines
-    let all_lines: Vec<String> = reader.lines().collect::<std::io::Result<_>>()?;
-
-    let start = all_lines.len().saturating_sub(lines);
-    for line in &all_lines[start..] {
+    // Read the last N lines, reaching into rotated segments if needed
+    for line in daemon::logs::last_lines(&log_path, lines, &filter)? {
         println!("{}", line);
     }
 
     // r[impl daemon.cli.logs.follow]
     if follow {
-        // Re-open file for following
-        let file = std::fs::File::open(&log_path)?;
-        let mut reader = BufReader::new(file);
-        reader.seek(SeekFrom::End(0))?;
-
+        let mut filter = daemon::logs::LineFilter::new(filter);
         loop {
             let mut line = String::new();
             match reader.read_line(&mut line) {
                 Ok(0) => {
+                    // r[impl daemon.logs.rotation]
+                    // A file shorter than what was read is the fresh one the
+                    // daemon started after rotating; follow it from the top.
+                    let position = reader.stream_position()?;
+                    if std::fs::metadata(&log_path).is_ok_and(|m| m.len() < position)
+                        && let Ok(file) = std::fs::File::open(&log_path)
+                    {
+                        reader = BufReader::new(file);
+                        continue;
+                    }
                     // No new data, sleep briefly
                     std::thread::sleep(std::time::Duration::from_millis(100));
                 }
                 Ok(_) => {
-                    print!("{}", line);
+                    if filter.matches(line.trim_end_matches('\n')) {
+                        print!("{}", line);
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error reading log: {}", e);
@@ -804,16 +3264,23 @@ async fn show_status(root: Option<PathBuf>, json: bool) -> Result<()> {
         None => find_project_root()?,
     };
 
-    let endpoint = daemon::local_endpoint(&project_root);
-
     // Try to connect without auto-starting
-    let stream = match roam_local::connect(&endpoint).await {
+    let stream = match daemon::connect_endpoint(&project_root).await {
         Ok(s) => s,
         Err(_) => {
-            if json {
-                println!("{}", json_error("no daemon running"));
-            } else {
-                println!("{}: No daemon running", "Status".yellow());
+            let pid = daemon::read_pid_file_at(&daemon::pid_file_path(&project_root))
+                .map(|(pid, _)| pid)
+                .filter(|pid| daemon::is_pid_alive(*pid));
+            match (pid, json) {
+                (Some(_), true) => println!("{}", json_error("daemon not responding")),
+                (Some(pid), false) => println!(
+                    "{}: Daemon process {} is running but not responding; \
+                     stop it with `tracey kill`",
+                    "Status".yellow(),
+                    pid
+                ),
+                (None, true) => println!("{}", json_error("no daemon running")),
+                (None, false) => println!("{}: No daemon running", "Status".yellow()),
             }
             return Ok(());
         }
@@ -867,8 +3334,26 @@ async fn show_status(root: Option<PathBuf>, json: bool) -> Result<()> {
                 if let Some(err) = &health.config_error {
                     println!("  Config error: {}", err.as_str().red());
                 }
+                if health.git_operation_in_progress {
+                    println!(
+                        "  Git operation: {} (rebuild deferred)",
+                        "in progress".yellow()
+                    );
+                }
+                if health.rebuild_queue_depth > 0 {
+                    println!(
+                        "  Rebuilds: {} running or queued",
+                        health.rebuild_queue_depth.to_string().yellow()
+                    );
+                }
                 println!("  File events: {}", health.watcher_event_count);
                 println!("  Watched dirs: {}", health.watched_directories.len());
+                if health.skipped_files > 0 {
+                    println!(
+                        "  Skipped files: {} (too large or binary, see `tracey query validate`)",
+                        health.skipped_files.to_string().yellow()
+                    );
+                }
             }
         }
         Ok(Err(e)) => {
@@ -900,6 +3385,9 @@ async fn show_status(root: Option<PathBuf>, json: bool) -> Result<()> {
 
 const SKILL_MD: &str = include_str!("../../../skill/SKILL.md");
 const SPEC_MD: &str = include_str!("../../../skill/references/tracey-spec.md");
+/// Start of the line in an installed SKILL.md naming the tracey version
+/// that installed it.
+const SKILL_VERSION_MARKER: &str = "<!-- tracey-skill-version: ";
 const CODEX_MCP_REGISTER_ARGS: &[&str] = &["mcp", "add", "tracey", "--", "tracey", "mcp"];
 const CLAUDE_MCP_REGISTER_ARGS: &[&str] = &[
     "mcp",
@@ -916,6 +3404,33 @@ const CLAUDE_MCP_REGISTER_ARGS: &[&str] = &[
 enum AiClient {
     Codex,
     Claude,
+    Cursor,
+    Windsurf,
+    Zed,
+}
+
+/// How tracey is registered as an MCP server with a client.
+enum McpRegistration {
+    /// Running the client's own `mcp add` command
+    Command {
+        program: &'static str,
+        text: &'static str,
+        args: &'static [&'static str],
+    },
+    /// Adding a `tracey` entry to the client's JSON config file
+    ConfigFile {
+        path: PathBuf,
+        format: tracey::mcp_config::Format,
+    },
+}
+
+/// What `tracey mcp register` may do without asking.
+#[derive(Clone, Copy, Debug, Default)]
+struct McpRegisterOptions {
+    /// Register without prompting for consent
+    yes: bool,
+    /// Print what would change, without running or writing anything
+    dry_run: bool,
 }
 
 impl AiClient {
@@ -923,32 +3438,95 @@ impl AiClient {
         match self {
             Self::Codex => "codex",
             Self::Claude => "claude",
+            Self::Cursor => "cursor",
+            Self::Windsurf => "windsurf",
+            Self::Zed => "zed",
         }
     }
 
-    fn skill_dir(self, home: &Path) -> PathBuf {
-        match self {
-            Self::Codex => home.join(".codex/skills/tracey"),
-            Self::Claude => home.join(".claude/skills/tracey"),
-        }
+    /// r[impl cli.skill.clients]
+    /// Where the client looks for the skill, under the home directory or,
+    /// with `project`, under a project root. `None` for clients without
+    /// skills.
+    fn skill_dir(self, base: &Path, project: bool) -> Option<PathBuf> {
+        Some(match self {
+            Self::Codex => base.join(".codex/skills/tracey"),
+            Self::Claude => base.join(".claude/skills/tracey"),
+            Self::Cursor => base.join(".cursor/skills/tracey"),
+            Self::Windsurf if project => base.join(".windsurf/skills/tracey"),
+            Self::Windsurf => base.join(".codeium/windsurf/skills/tracey"),
+            Self::Zed => return None,
+        })
     }
 
-    fn register_command(self) -> (&'static str, &'static str, &'static [&'static str]) {
+    /// r[impl cli.mcp.register.clients]
+    /// How tracey is registered with the client, given the home directory.
+    fn mcp_registration(self, home: &Path) -> McpRegistration {
+        use tracey::mcp_config::Format;
+        let config_file = |path: &str, format| McpRegistration::ConfigFile {
+            path: home.join(path),
+            format,
+        };
         match self {
-            Self::Codex => (
-                "codex",
-                "codex mcp add tracey -- tracey mcp",
-                CODEX_MCP_REGISTER_ARGS,
-            ),
-            Self::Claude => (
-                "claude",
-                "claude mcp add --transport stdio tracey -- tracey mcp",
-                CLAUDE_MCP_REGISTER_ARGS,
-            ),
+            Self::Codex => McpRegistration::Command {
+                program: "codex",
+                text: "codex mcp add tracey -- tracey mcp",
+                args: CODEX_MCP_REGISTER_ARGS,
+            },
+            Self::Claude => McpRegistration::Command {
+                program: "claude",
+                text: "claude mcp add --transport stdio tracey -- tracey mcp",
+                args: CLAUDE_MCP_REGISTER_ARGS,
+            },
+            Self::Cursor => config_file(".cursor/mcp.json", Format::McpServers),
+            Self::Windsurf => config_file(".codeium/windsurf/mcp_config.json", Format::McpServers),
+            Self::Zed => config_file(".config/zed/settings.json", Format::ZedContextServers),
         }
     }
 }
 
+/// The skill directories a `tracey skill` command works on.
+#[derive(Clone, Copy, Debug)]
+struct SkillTargets {
+    claude: bool,
+    codex: bool,
+    cursor: bool,
+    windsurf: bool,
+    /// In the project root rather than the home directory
+    project: bool,
+}
+
+impl SkillTargets {
+    /// The selected clients; Codex and Claude if none is selected.
+    fn clients(self) -> Vec<AiClient> {
+        let any = self.codex || self.claude || self.cursor || self.windsurf;
+        [
+            (self.codex || !any, AiClient::Codex),
+            (self.claude || !any, AiClient::Claude),
+            (self.cursor, AiClient::Cursor),
+            (self.windsurf, AiClient::Windsurf),
+        ]
+        .into_iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, client)| client)
+        .collect()
+    }
+
+    /// Each selected client with its skill directory.
+    fn dirs(self) -> Result<Vec<(AiClient, PathBuf)>> {
+        let base = if self.project {
+            find_project_root()?
+        } else {
+            dirs::home_dir().ok_or_else(|| eyre!("could not determine home directory"))?
+        };
+        Ok(self
+            .clients()
+            .into_iter()
+            .filter_map(|client| Some((client, client.skill_dir(&base, self.project)?)))
+            .collect())
+    }
+}
+
 #[derive(Debug, Default)]
 struct McpRegistrationSummary {
     attempted: usize,
@@ -971,15 +3549,20 @@ fn selected_ai_clients(codex: bool, claude: bool) -> Vec<AiClient> {
 }
 
 /// r[impl cli.skill.install]
-/// Install the bundled Tracey skill for Claude and/or Codex
-fn install_skill(codex: bool, claude: bool) -> Result<()> {
-    let home = dirs::home_dir().ok_or_else(|| eyre!("could not determine home directory"))?;
-    let clients = selected_ai_clients(codex, claude);
-    let installed = install_skill_for_clients(&home, &clients)?;
+/// Install the bundled Tracey skill for the selected clients
+fn install_skill(targets: SkillTargets) -> Result<()> {
+    let skill_dirs = targets.dirs()?;
+    for (_, skill_dir) in &skill_dirs {
+        install_skill_to(skill_dir)?;
+    }
 
     println!("{}: Tracey skill installed", "Success".green());
-    for path in &installed {
-        println!("  {}", path.display());
+    for (_, skill_dir) in &skill_dirs {
+        println!("  {}", skill_dir.display());
+    }
+    // r[impl cli.skill.project]
+    if targets.project {
+        println!("Commit these directories so everyone working on the project gets the skill.");
     }
 
     Ok(())
@@ -996,7 +3579,7 @@ fn setup_ai_clients(codex: bool, claude: bool) -> Result<()> {
         "{}: configuring tracey for AI assistants (MCP + skill)",
         "Info".cyan()
     );
-    let registration = register_mcp_for_clients(&clients)?;
+    let registration = register_mcp_for_clients(&home, &clients, McpRegisterOptions::default())?;
     let installed = install_skill_for_clients(&home, &clients)?;
 
     println!("{}: Tracey skill installed", "Success".green());
@@ -1021,6 +3604,95 @@ fn setup_ai_clients(codex: bool, claude: bool) -> Result<()> {
 }
 
 /// Load config for pre-commit / bump: silently no-op if missing, warn if unparseable.
+/// Load the config, exiting with [`exit_code::CONFIG_ERROR`] if it can't be used.
+fn load_config_or_exit(config_path: &Path) -> tracey::config::Config {
+    match tracey::load_config(&config_path.to_path_buf()) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("{}", format!("{e:?}").red());
+            std::process::exit(exit_code::CONFIG_ERROR);
+        }
+    }
+}
+
+/// Summarize the project and, with `bench`, time it. Returns false if a
+/// timing is over its target.
+async fn run_doctor(
+    project_root: &std::path::Path,
+    cfg: &tracey::config::Config,
+    bench: bool,
+) -> Result<bool> {
+    let data = tracey::data::build_dashboard_data(project_root, cfg, 0, true).await?;
+    let impls: usize = cfg.specs.iter().map(|s| s.impls.len()).sum();
+    println!(
+        "{}: {} specs, {} impls",
+        "Config".green(),
+        cfg.specs.len(),
+        impls
+    );
+    let rules: usize = data.forward_by_impl.values().map(|f| f.rules.len()).sum();
+    let files: std::collections::BTreeSet<&str> = data
+        .reverse_by_impl
+        .values()
+        .flat_map(|reverse| reverse.files.iter().map(|f| f.path.as_str()))
+        .collect();
+    println!("  Rules: {}", rules);
+    println!("  Source files: {}", files.len());
+    let errors: usize = data
+        .validation_by_impl
+        .values()
+        .map(|v| v.error_count)
+        .sum();
+    let warnings: usize = data
+        .validation_by_impl
+        .values()
+        .map(|v| v.warning_count)
+        .sum();
+    if errors + warnings > 0 {
+        println!(
+            "  Validation: {} errors, {} warnings (see `tracey query validate`)",
+            errors.to_string().red(),
+            warnings.to_string().yellow()
+        );
+    }
+    let skipped: std::collections::BTreeSet<&std::path::PathBuf> = data
+        .skipped_files_by_impl
+        .values()
+        .flat_map(|skipped| skipped.keys())
+        .collect();
+    if !skipped.is_empty() {
+        println!(
+            "  Skipped files: {} (too large or binary)",
+            skipped.len().to_string().yellow()
+        );
+    }
+
+    if !bench {
+        return Ok(true);
+    }
+    let report = tracey::bench::run(project_root, cfg).await?;
+    println!();
+    print!("{}: {}", "Benchmark".green(), report);
+    Ok(report.meets(&tracey::bench::TARGETS))
+}
+
+/// Run the policy check; a build failure means the config doesn't validate.
+async fn check_policies(
+    project_root: &std::path::Path,
+    cfg: &tracey::config::Config,
+    porcelain: bool,
+    baseline: Option<&tracey::baseline::Baseline>,
+    release: Option<&tracey::release::Release>,
+) -> bool {
+    match tracey::policy::run_check(project_root, cfg, porcelain, baseline, release).await {
+        Ok(passed) => passed,
+        Err(e) => {
+            eprintln!("{}", format!("{e:?}").red());
+            std::process::exit(exit_code::CONFIG_ERROR);
+        }
+    }
+}
+
 fn load_bump_config(config_path: &std::path::Path) -> tracey::config::Config {
     if !config_path.exists() {
         return tracey::config::Config::default();
@@ -1037,28 +3709,117 @@ fn load_bump_config(config_path: &std::path::Path) -> tracey::config::Config {
     }
 }
 
-fn install_skill_to(skill_dir: &Path) -> Result<()> {
+/// The bundled SKILL.md, with a line naming this tracey version after its
+/// front matter.
+fn bundled_skill_md() -> String {
+    with_version_marker(SKILL_MD, env!("CARGO_PKG_VERSION"))
+}
+
+/// r[impl cli.skill.version-marker]
+/// `skill_md` with a marker line naming `version` after its front matter,
+/// or at the top if it has none.
+fn with_version_marker(skill_md: &str, version: &str) -> String {
+    let marker = format!("{}{} -->\n", SKILL_VERSION_MARKER, version);
+    let front_matter_end = skill_md
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n"))
+        .map(|end| "---\n".len() + end + "\n---\n".len());
+    match front_matter_end {
+        Some(end) => format!("{}{}{}", &skill_md[..end], marker, &skill_md[end..]),
+        None => format!("{}{}", marker, skill_md),
+    }
+}
+
+/// The tracey version that installed the skill in `skill_dir`, if it has
+/// a version marker.
+fn installed_skill_version(skill_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(skill_dir.join("SKILL.md")).ok()?;
+    content.lines().find_map(|line| {
+        line.strip_prefix(SKILL_VERSION_MARKER)
+            .and_then(|rest| rest.strip_suffix(" -->"))
+            .map(str::to_string)
+    })
+}
+
+/// Write the bundled skill files to `skill_dir`. Returns whether any file
+/// was missing or different.
+fn install_skill_to(skill_dir: &Path) -> Result<bool> {
     let refs_dir = skill_dir.join("references");
     std::fs::create_dir_all(&refs_dir)
         .wrap_err_with(|| format!("failed to create {}", refs_dir.display()))?;
 
-    std::fs::write(skill_dir.join("SKILL.md"), SKILL_MD)
-        .wrap_err_with(|| format!("failed to write {}", skill_dir.join("SKILL.md").display()))?;
+    let skill_md = bundled_skill_md();
+    let mut changed = false;
+    for (path, content) in [
+        (skill_dir.join("SKILL.md"), skill_md.as_str()),
+        (refs_dir.join("tracey-spec.md"), SPEC_MD),
+    ] {
+        changed |= std::fs::read_to_string(&path).ok().as_deref() != Some(content);
+        std::fs::write(&path, content)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
 
-    std::fs::write(refs_dir.join("tracey-spec.md"), SPEC_MD).wrap_err_with(|| {
-        format!(
-            "failed to write {}",
-            refs_dir.join("tracey-spec.md").display()
-        )
-    })?;
+    Ok(changed)
+}
+
+fn update_skill(targets: SkillTargets) -> Result<()> {
+    for (client, skill_dir) in targets.dirs()? {
+        if !skill_dir.join("SKILL.md").is_file() {
+            println!(
+                "  {} {}: not installed, skipping (run 'tracey skill install')",
+                "Skip".yellow(),
+                client.name()
+            );
+            continue;
+        }
+        let previous = installed_skill_version(&skill_dir);
+        if install_skill_to(&skill_dir)? {
+            println!(
+                "  {} {}: {} ({} -> {})",
+                "Updated".green(),
+                client.name(),
+                skill_dir.display(),
+                previous.as_deref().unwrap_or("unknown version"),
+                env!("CARGO_PKG_VERSION")
+            );
+        } else {
+            println!(
+                "  {} {}: already up to date ({})",
+                "Ok".green(),
+                client.name(),
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+    }
+    Ok(())
+}
 
+fn uninstall_skill(targets: SkillTargets) -> Result<()> {
+    for (client, skill_dir) in targets.dirs()? {
+        // Only remove a directory that holds a skill, never one that merely
+        // shares the name.
+        if !skill_dir.join("SKILL.md").is_file() {
+            println!("  {} {}: not installed", "Skip".yellow(), client.name());
+            continue;
+        }
+        std::fs::remove_dir_all(&skill_dir)
+            .wrap_err_with(|| format!("failed to remove {}", skill_dir.display()))?;
+        println!(
+            "  {} {}: {}",
+            "Removed".green(),
+            client.name(),
+            skill_dir.display()
+        );
+    }
     Ok(())
 }
 
 fn install_skill_for_clients(home: &Path, clients: &[AiClient]) -> Result<Vec<PathBuf>> {
     let mut installed = Vec::new();
     for client in clients {
-        let skill_dir = client.skill_dir(home);
+        let Some(skill_dir) = client.skill_dir(home, false) else {
+            continue;
+        };
         install_skill_to(&skill_dir)?;
         installed.push(skill_dir);
     }
@@ -1066,21 +3827,35 @@ fn install_skill_for_clients(home: &Path, clients: &[AiClient]) -> Result<Vec<Pa
 }
 
 fn register_mcp_clients(args: &[String]) -> Result<()> {
-    let mut codex_requested = false;
-    let mut claude_requested = false;
+    let mut requested = Vec::new();
+    let mut options = McpRegisterOptions::default();
 
     for arg in args {
         match arg.as_str() {
-            "--codex" => codex_requested = true,
-            "--claude" => claude_requested = true,
+            "--codex" => requested.push(AiClient::Codex),
+            "--claude" => requested.push(AiClient::Claude),
+            "--cursor" => requested.push(AiClient::Cursor),
+            "--windsurf" => requested.push(AiClient::Windsurf),
+            "--zed" => requested.push(AiClient::Zed),
+            "-y" | "--yes" => options.yes = true,
+            "--dry-run" => options.dry_run = true,
             "-h" | "--help" => {
-                println!("Register tracey as an MCP server for Codex and/or Claude.");
+                println!("Register tracey as an MCP server with AI clients.");
                 println!();
                 println!("Usage:");
-                println!("  tracey mcp register [--codex] [--claude]");
+                println!(
+                    "  tracey mcp register [--codex] [--claude] [--cursor] [--windsurf] [--zed] [--yes] [--dry-run]"
+                );
                 println!();
-                println!("If no flags are provided, tracey tries both clients and skips any");
-                println!("client executable that's not found in PATH.");
+                println!("If no client flags are provided, tracey tries every client and skips");
+                println!("any that's not installed. Codex and Claude are registered with their");
+                println!("`mcp add` command; Cursor, Windsurf and Zed by adding tracey to their");
+                println!("JSON config file.");
+                println!();
+                println!("  -y, --yes    register without asking for consent");
+                println!(
+                    "  --dry-run    print the commands and config changes, without making them"
+                );
                 println!();
                 println!("Tip: run 'tracey ai' to register MCP and install the Tracey skill.");
                 return Ok(());
@@ -1093,10 +3868,25 @@ fn register_mcp_clients(args: &[String]) -> Result<()> {
         }
     }
 
-    let clients = selected_ai_clients(codex_requested, claude_requested);
+    let clients = if requested.is_empty() {
+        vec![
+            AiClient::Codex,
+            AiClient::Claude,
+            AiClient::Cursor,
+            AiClient::Windsurf,
+            AiClient::Zed,
+        ]
+    } else {
+        requested
+    };
+    let home = dirs::home_dir().ok_or_else(|| eyre!("could not determine home directory"))?;
 
     println!("{}: registering tracey MCP server", "Info".cyan());
-    let summary = register_mcp_for_clients(&clients)?;
+    let summary = register_mcp_for_clients(&home, &clients, options)?;
+
+    if options.dry_run {
+        return Ok(());
+    }
 
     if summary.succeeded > 0 {
         println!(
@@ -1116,38 +3906,247 @@ fn register_mcp_clients(args: &[String]) -> Result<()> {
     ))
 }
 
-fn register_mcp_for_clients(clients: &[AiClient]) -> Result<McpRegistrationSummary> {
+fn register_mcp_for_clients(
+    home: &Path,
+    clients: &[AiClient],
+    options: McpRegisterOptions,
+) -> Result<McpRegistrationSummary> {
     let mut summary = McpRegistrationSummary::default();
 
     for client in clients {
         summary.attempted += 1;
-        let (program, command_text, command_args) = client.register_command();
+        let registered = match client.mcp_registration(home) {
+            McpRegistration::Command {
+                program,
+                text,
+                args,
+            } => {
+                if !command_in_path(program) {
+                    println!(
+                        "  {} {}: not found in PATH, skipping",
+                        "Skip".yellow(),
+                        client.name()
+                    );
+                    continue;
+                }
+                // r[impl cli.mcp.register.dry-run]
+                if options.dry_run {
+                    println!("  {} {}: {}", "Would run".cyan(), client.name(), text);
+                    continue;
+                }
+                // r[impl cli.mcp.register.yes]
+                if !options.yes && !confirm_consent(client.name(), &format!("run `{text}`"))? {
+                    println!(
+                        "  {} {}: consent not granted, skipping",
+                        "Skip".yellow(),
+                        client.name()
+                    );
+                    continue;
+                }
+                println!("  {} {}: {}", "Running".cyan(), client.name(), text);
+                run_registration_command(program, args)?
+            }
+            McpRegistration::ConfigFile { path, format } => {
+                register_in_config_file(*client, &path, format, options)?
+            }
+        };
+        if registered {
+            summary.succeeded += 1;
+        }
+    }
 
-        if !command_in_path(program) {
+    Ok(summary)
+}
+
+/// Add tracey to a client's JSON config file. A client whose config
+/// directory doesn't exist isn't installed, and is skipped.
+fn register_in_config_file(
+    client: AiClient,
+    path: &Path,
+    format: tracey::mcp_config::Format,
+    options: McpRegisterOptions,
+) -> Result<bool> {
+    if !path.parent().is_some_and(Path::is_dir) {
+        println!(
+            "  {} {}: {} not found, skipping",
+            "Skip".yellow(),
+            client.name(),
+            path.parent().unwrap_or(path).display()
+        );
+        return Ok(false);
+    }
+    let existing = match std::fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    let updated = match tracey::mcp_config::register(existing.as_deref(), format) {
+        Ok(Some(updated)) => updated,
+        Ok(None) => {
             println!(
-                "  {} {}: not found in PATH, skipping",
+                "  {} {}: already registered in {}",
                 "Skip".yellow(),
-                client.name()
+                client.name(),
+                path.display()
             );
-            continue;
+            return Ok(true);
         }
-
-        if !confirm_command_consent(client.name(), command_text)? {
-            println!(
-                "  {} {}: consent not granted, skipping",
+        Err(e) => {
+            eprintln!(
+                "  {} {}: {}: {}",
                 "Skip".yellow(),
-                client.name()
+                client.name(),
+                path.display(),
+                e
             );
-            continue;
+            return Ok(false);
         }
+    };
 
-        println!("  {} {}: {}", "Running".cyan(), client.name(), command_text);
-        if run_registration_command(program, command_args)? {
-            summary.succeeded += 1;
+    let action = if existing.is_some() {
+        "update"
+    } else {
+        "create"
+    };
+    if options.dry_run {
+        println!(
+            "  {} {}: {} {}",
+            "Would".cyan(),
+            client.name(),
+            action,
+            path.display()
+        );
+        for line in tracey::mcp_config::changed_lines(existing.as_deref().unwrap_or(""), &updated) {
+            println!("    {}", line);
         }
+        return Ok(false);
+    }
+    if !options.yes && !confirm_consent(client.name(), &format!("{action} `{}`", path.display()))? {
+        println!(
+            "  {} {}: consent not granted, skipping",
+            "Skip".yellow(),
+            client.name()
+        );
+        return Ok(false);
     }
+    std::fs::write(path, updated)
+        .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    println!(
+        "  {} {}: added tracey to {}",
+        "Updated".green(),
+        client.name(),
+        path.display()
+    );
+    Ok(true)
+}
 
-    Ok(summary)
+fn install_lsp_config(args: &[String]) -> Result<()> {
+    use tracey::lsp_install::Editor;
+
+    let mut editors = Vec::new();
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "--vscode" => editors.push(Editor::VsCode),
+            "--neovim" => editors.push(Editor::Neovim),
+            "--helix" => editors.push(Editor::Helix),
+            "--zed" => editors.push(Editor::Zed),
+            "--all" => editors.extend(Editor::ALL),
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                println!("Register the tracey language server in this project's editor config.");
+                println!();
+                println!("Usage:");
+                println!(
+                    "  tracey lsp install [--vscode] [--neovim] [--helix] [--zed] [--all] [--dry-run]"
+                );
+                println!();
+                println!("Writes or updates, for the file types in the project:");
+                for editor in Editor::ALL {
+                    println!("  --{:<9} {}", editor.name(), editor.config_path());
+                }
+                println!();
+                println!("  --dry-run    print the changes, without writing anything");
+                return Ok(());
+            }
+            unknown => {
+                return Err(eyre!(
+                    "unknown argument for 'tracey lsp install': {unknown}"
+                ));
+            }
+        }
+    }
+    if editors.is_empty() {
+        return Err(eyre!(
+            "choose an editor: --vscode, --neovim, --helix, --zed or --all (see --help)"
+        ));
+    }
+    editors.dedup();
+
+    let project_root = find_project_root()?;
+    for editor in editors {
+        let change = match tracey::lsp_install::configure_project(&project_root, editor) {
+            Ok(Some(change)) => change,
+            Ok(None) => {
+                println!(
+                    "  {} {}: already configured in {}",
+                    "Skip".yellow(),
+                    editor.name(),
+                    editor.config_path()
+                );
+                continue;
+            }
+            Err(e) => {
+                eprintln!(
+                    "  {} {}: {}: {}",
+                    "Skip".yellow(),
+                    editor.name(),
+                    editor.config_path(),
+                    e
+                );
+                continue;
+            }
+        };
+        let action = if change.before.is_some() {
+            "update"
+        } else {
+            "create"
+        };
+        // r[impl cli.lsp.install.dry-run]
+        if dry_run {
+            println!(
+                "  {} {}: {} {}",
+                "Would".cyan(),
+                editor.name(),
+                action,
+                change.path
+            );
+            for line in tracey::mcp_config::changed_lines(
+                change.before.as_deref().unwrap_or(""),
+                &change.after,
+            ) {
+                println!("    {}", line);
+            }
+            continue;
+        }
+        let path = project_root.join(change.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &change.after)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        println!(
+            "  {} {}: {}d {}",
+            "Updated".green(),
+            editor.name(),
+            action,
+            change.path
+        );
+    }
+    if !dry_run {
+        println!("Tip: commit the config files so the whole team gets the language server.");
+    }
+    Ok(())
 }
 
 fn run_registration_command(program: &str, args: &[&str]) -> Result<bool> {
@@ -1175,8 +4174,8 @@ fn run_registration_command(program: &str, args: &[&str]) -> Result<bool> {
     Ok(false)
 }
 
-fn confirm_command_consent(client: &str, command: &str) -> Result<bool> {
-    print!("Detect {client} => run `{command}` [y/N] ? ");
+fn confirm_consent(client: &str, action: &str) -> Result<bool> {
+    print!("Detect {client} => {action} [y/N] ? ");
     std::io::stdout().flush()?;
 
     let mut input = String::new();
@@ -1215,7 +4214,7 @@ async fn kill_daemon(root: Option<PathBuf>) -> Result<()> {
     }
 
     // Try to connect and send shutdown
-    match roam_local::connect(&endpoint).await {
+    match daemon::connect_endpoint(&project_root).await {
         Ok(stream) => {
             use roam_stream::{Connector, HandshakeConfig, NoDispatcher, connect};
 
@@ -1265,6 +4264,16 @@ async fn kill_daemon(root: Option<PathBuf>) -> Result<()> {
                 "{}: Daemon not responding, cleaning up stale socket",
                 "Info".cyan()
             );
+            // r[impl daemon.cli.kill.unresponsive]
+            // On Windows the pipe only goes away with the process holding it.
+            let pid_path = daemon::pid_file_path(&project_root);
+            if let Some((pid, _)) = daemon::read_pid_file_at(&pid_path)
+                && daemon::is_pid_alive(pid)
+            {
+                daemon::kill_pid(pid);
+                let _ = std::fs::remove_file(&pid_path);
+                println!("{}: Stopped daemon process {}", "Info".cyan(), pid);
+            }
             let _ = roam_local::remove_endpoint(&endpoint);
             println!("{}: Cleaned up", "Success".green());
         }
@@ -1294,6 +4303,7 @@ fn run_gc(dry_run: bool) -> Result<()> {
     }
 
     let mut removed = 0usize;
+    let mut removed_logs = 0usize;
     for entry in &entries {
         let dir = entry.path();
         let meta_path = dir.join("project-root");
@@ -1336,6 +4346,21 @@ fn run_gc(dry_run: bool) -> Result<()> {
         };
 
         if !orphaned {
+            // r[impl daemon.cli.gc.bridge-logs]
+            for log in daemon::logs::dead_bridge_logs(&dir) {
+                if dry_run {
+                    println!("Would remove: {}", log.display());
+                } else if let Err(e) = std::fs::remove_file(&log) {
+                    eprintln!(
+                        "{}: Failed to remove {}: {}",
+                        "Error".red(),
+                        log.display(),
+                        e
+                    );
+                    continue;
+                }
+                removed_logs += 1;
+            }
             continue;
         }
 
@@ -1363,16 +4388,122 @@ fn run_gc(dry_run: bool) -> Result<()> {
         removed += 1;
     }
 
-    if removed == 0 {
+    if removed == 0 && removed_logs == 0 {
         println!("Nothing to clean up.");
     } else if dry_run {
         println!(
-            "\n{} orphaned state dir(s) found. Run without --dry-run to remove.",
-            removed
+            "\n{} orphaned state dir(s) and {} stale bridge log(s) found. \
+             Run without --dry-run to remove.",
+            removed, removed_logs
         );
     } else {
-        println!("\nRemoved {} orphaned state dir(s).", removed);
+        println!(
+            "\nRemoved {} orphaned state dir(s) and {} stale bridge log(s).",
+            removed, removed_logs
+        );
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.skill.version-marker]
+    #[test]
+    fn version_marker_goes_after_the_front_matter() {
+        assert_eq!(
+            with_version_marker("---\nname: tracey\n---\n\n# Tracey\n", "1.2.3"),
+            "---\nname: tracey\n---\n<!-- tracey-skill-version: 1.2.3 -->\n\n# Tracey\n"
+        );
+        assert_eq!(
+            with_version_marker("# Tracey\n", "1.2.3"),
+            "<!-- tracey-skill-version: 1.2.3 -->\n# Tracey\n"
+        );
+    }
+
+    // r[verify cli.skill.version-marker]
+    // r[verify cli.skill.update]
+    #[test]
+    fn installed_skill_reports_its_version_and_reinstalls_unchanged() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill_dir = tmp.path().join("tracey");
+
+        assert_eq!(installed_skill_version(&skill_dir), None);
+        assert!(install_skill_to(&skill_dir).unwrap());
+        assert_eq!(
+            installed_skill_version(&skill_dir).as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert!(!install_skill_to(&skill_dir).unwrap());
+
+        std::fs::write(skill_dir.join("references/tracey-spec.md"), "stale").unwrap();
+        assert!(install_skill_to(&skill_dir).unwrap());
+    }
+
+    // r[verify cli.skill.clients]
+    #[test]
+    fn skill_dirs_per_client() {
+        let base = Path::new("/base");
+        let dir = |client: AiClient, project| client.skill_dir(base, project);
+        for project in [false, true] {
+            assert_eq!(
+                dir(AiClient::Codex, project),
+                Some(base.join(".codex/skills/tracey"))
+            );
+            assert_eq!(
+                dir(AiClient::Claude, project),
+                Some(base.join(".claude/skills/tracey"))
+            );
+            assert_eq!(
+                dir(AiClient::Cursor, project),
+                Some(base.join(".cursor/skills/tracey"))
+            );
+            assert_eq!(dir(AiClient::Zed, project), None);
+        }
+        assert_eq!(
+            dir(AiClient::Windsurf, true),
+            Some(base.join(".windsurf/skills/tracey"))
+        );
+        assert_eq!(
+            dir(AiClient::Windsurf, false),
+            Some(base.join(".codeium/windsurf/skills/tracey"))
+        );
+    }
+
+    // r[verify cli.skill.clients]
+    #[test]
+    fn skill_targets_default_to_codex_and_claude() {
+        let none = SkillTargets {
+            claude: false,
+            codex: false,
+            cursor: false,
+            windsurf: false,
+            project: false,
+        };
+        let names = |targets: SkillTargets| {
+            targets
+                .clients()
+                .into_iter()
+                .map(AiClient::name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(none), ["codex", "claude"]);
+        assert_eq!(
+            names(SkillTargets {
+                windsurf: true,
+                ..none
+            }),
+            ["windsurf"]
+        );
+        assert_eq!(
+            names(SkillTargets {
+                claude: true,
+                cursor: true,
+                ..none
+            }),
+            ["claude", "cursor"]
+        );
+    }
+}
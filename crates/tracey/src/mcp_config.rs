@@ -0,0 +1,138 @@
+//! Registering tracey in the JSON config files of MCP clients.
+//!
+//! r[impl cli.mcp.register.config-file]
+//!
+//! Cursor, Windsurf and Zed have no command that adds an MCP server; they
+//! read servers from a JSON file. Registering tracey there means adding a
+//! `tracey` entry under the file's servers key and writing the file back,
+//! keeping every other setting. The file is parsed as plain JSON, so one
+//! with comments or trailing commas is left for the user to edit by hand.
+
+use eyre::Result;
+use serde_json::{Map, Value, json};
+
+/// Where a client keeps its MCP servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `{"mcpServers": {"tracey": ...}}`, as Cursor and Windsurf read it
+    McpServers,
+    /// `{"context_servers": {"tracey": ...}}` in Zed's `settings.json`
+    ZedContextServers,
+}
+
+impl Format {
+    pub fn key(self) -> &'static str {
+        match self {
+            Format::McpServers => "mcpServers",
+            Format::ZedContextServers => "context_servers",
+        }
+    }
+}
+
+/// The server entry tracey is registered with.
+pub fn entry() -> Value {
+    json!({ "command": "tracey", "args": ["mcp"] })
+}
+
+/// The content of a config file once tracey is registered in it, given its
+/// current content (`None` if there's no file yet). Returns `None` if tracey
+/// is already registered with the same entry.
+pub fn register(existing: Option<&str>, format: Format) -> Result<Option<String>> {
+    let mut root = match existing.map(str::trim) {
+        None | Some("") => Map::new(),
+        Some(content) => match serde_json::from_str::<Value>(content) {
+            Ok(Value::Object(root)) => root,
+            Ok(_) => eyre::bail!("expected a JSON object at the top level"),
+            Err(e) => eyre::bail!(
+                "can't parse it as JSON ({e}); add the `tracey` entry under `{}` by hand",
+                format.key()
+            ),
+        },
+    };
+    let servers = root
+        .entry(format.key())
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Value::Object(servers) = servers else {
+        eyre::bail!("`{}` is not an object", format.key());
+    };
+    let entry = entry();
+    if servers.get("tracey") == Some(&entry) {
+        return Ok(None);
+    }
+    servers.insert("tracey".to_string(), entry);
+    let mut content = serde_json::to_string_pretty(&Value::Object(root))?;
+    content.push('\n');
+    Ok(Some(content))
+}
+
+/// The lines removed from `before` (`- `) and added in `after` (`+ `), in
+/// file order.
+pub fn changed_lines(before: &str, after: &str) -> Vec<String> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            changes.push(format!("+ {}", new[j]));
+            j += 1;
+        } else {
+            changes.push(format!("- {}", old[i]));
+            i += 1;
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.mcp.register.config-file]
+    #[test]
+    fn adds_tracey_and_keeps_other_servers() {
+        let created = register(None, Format::McpServers).unwrap().unwrap();
+        assert_eq!(
+            created,
+            "{\n  \"mcpServers\": {\n    \"tracey\": {\n      \"args\": [\n        \"mcp\"\n      ],\n      \
+             \"command\": \"tracey\"\n    }\n  }\n}\n"
+        );
+        assert_eq!(register(Some(&created), Format::McpServers).unwrap(), None);
+
+        let zed = r#"{"theme": "One Dark", "context_servers": {"other": {"command": "other"}}}"#;
+        let updated = register(Some(zed), Format::ZedContextServers)
+            .unwrap()
+            .unwrap();
+        let updated: Value = serde_json::from_str(&updated).unwrap();
+        assert_eq!(updated["theme"], "One Dark");
+        assert_eq!(updated["context_servers"]["other"]["command"], "other");
+        assert_eq!(updated["context_servers"]["tracey"], entry());
+
+        let error = register(Some("{ // comment\n}"), Format::ZedContextServers).unwrap_err();
+        assert!(error.to_string().contains("by hand"));
+        assert!(register(Some(r#"{"mcpServers": []}"#), Format::McpServers).is_err());
+    }
+
+    #[test]
+    fn lists_changed_lines() {
+        assert_eq!(
+            changed_lines("{\n  \"a\": 1\n}\n", "{\n  \"a\": 1,\n  \"b\": 2\n}\n"),
+            vec!["-   \"a\": 1", "+   \"a\": 1,", "+   \"b\": 2"]
+        );
+        assert!(changed_lines("same\n", "same\n").is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Three-way merge of spec files, for `tracey merge-driver`.
+//!
+//! r[impl cli.merge-driver]
+//!
+//! When both branches bump the same rule, a plain text merge conflicts on
+//! the marker line even if the rule bodies merge cleanly. Before merging,
+//! the driver rewrites that marker on both sides to the version after the
+//! higher of the two bumps, so `git merge-file` only reports conflicts where
+//! the rule texts themselves clash.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+use tracey_core::VersionScheme;
+
+use crate::bump::{git_capture, parse_spec_rules};
+use crate::config::Config;
+
+/// The git config name the driver is registered under.
+const DRIVER: &str = "tracey";
+
+/// The result of merging one spec file.
+#[derive(Debug)]
+pub struct MergeOutcome {
+    pub merged: String,
+    /// Number of conflict hunks left in `merged`
+    pub conflicts: usize,
+    /// Rules both sides bumped, with the version they were given
+    pub renumbered: Vec<marq::RuleId>,
+}
+
+/// Replace the version in the rule marker at `span`, keeping its prefix and
+/// any attributes after the ID.
+fn set_version(content: &mut String, span: marq::SourceSpan, base: &str, version: u32) {
+    set_version_at(content, span.offset, span.length, base, version);
+}
+
+fn set_version_at(content: &mut String, offset: usize, length: usize, base: &str, version: u32) {
+    let marker = &content[offset..offset + length];
+    let Some(bracket) = marker.find('[') else {
+        return;
+    };
+    let id_start = bracket + 1;
+    let id_len = marker[id_start..]
+        .find(|c: char| c.is_whitespace() || c == ']')
+        .unwrap_or(marker.len() - id_start);
+    let Some(id) = tracey_core::RuleId::new(base, version) else {
+        return;
+    };
+    let start = offset + id_start;
+    content.replace_range(start..start + id_len, &id.to_string());
+}
+
+/// Merge `ours` and `theirs` (both descended from `base`), renumbering
+/// rules that both bumped. Rules the version scheme can't pick a next
+/// version for are left for the text merge to flag.
+pub async fn merge_spec(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    scheme: VersionScheme,
+) -> Result<MergeOutcome> {
+    let base_rules = parse_spec_rules(base).await?;
+    let our_rules = parse_spec_rules(ours).await?;
+    let their_rules = parse_spec_rules(theirs).await?;
+    let today = crate::bump::today_version();
+
+    // (our marker offset, their marker offset, base ID, new version)
+    let mut renumber = Vec::new();
+    for (id, ours) in &our_rules {
+        let (Some(base), Some(theirs)) = (base_rules.get(id), their_rules.get(id)) else {
+            continue;
+        };
+        let (old, ov, tv) = (base.id.version, ours.id.version, theirs.id.version);
+        let both_bumped = ov > old && tv > old;
+        if !both_bumped || (ov == tv && ours.raw == theirs.raw) {
+            continue;
+        }
+        if let Ok(version) = scheme.next(ov.max(tv), today) {
+            renumber.push((ours.marker_span, theirs.marker_span, id.clone(), version));
+        }
+    }
+
+    let (mut ours, mut theirs) = (ours.to_string(), theirs.to_string());
+    // Last marker first, so earlier offsets stay valid.
+    renumber.sort_by_key(|(our_span, _, _, _)| std::cmp::Reverse(our_span.offset));
+    for (our_span, _, id, version) in &renumber {
+        set_version(&mut ours, *our_span, id, *version);
+    }
+    renumber.sort_by_key(|(_, their_span, _, _)| std::cmp::Reverse(their_span.offset));
+    for (_, their_span, id, version) in &renumber {
+        set_version(&mut theirs, *their_span, id, *version);
+    }
+
+    let (merged, conflicts) = merge_file(base, &ours, &theirs)?;
+    let mut renumbered: Vec<marq::RuleId> = renumber
+        .into_iter()
+        .map(|(_, _, base, version)| marq::RuleId { base, version })
+        .collect();
+    renumbered.sort_by(|a, b| a.base.cmp(&b.base));
+    Ok(MergeOutcome {
+        merged,
+        conflicts,
+        renumbered,
+    })
+}
+
+/// Run `git merge-file` on the three texts, returning the merged text and
+/// the number of conflicts.
+fn merge_file(base: &str, ours: &str, theirs: &str) -> Result<(String, usize)> {
+    let dir = tempfile::Builder::new()
+        .prefix("tracey-merge-")
+        .tempdir()
+        .wrap_err("failed to create a temporary directory")?;
+    let paths: Vec<PathBuf> = ["ours", "base", "theirs"]
+        .iter()
+        .map(|side| dir.path().join(format!("{side}.md")))
+        .collect();
+    for (path, content) in paths.iter().zip([ours, base, theirs]) {
+        std::fs::write(path, content)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+
+    let output = std::process::Command::new("git")
+        .args([
+            "merge-file",
+            "-p",
+            "-L",
+            "ours",
+            "-L",
+            "base",
+            "-L",
+            "theirs",
+        ])
+        .args(&paths)
+        .output()
+        .wrap_err("failed to run git merge-file")?;
+
+    // The exit status is the number of conflicts, or negative on error.
+    let conflicts = match output.status.code() {
+        Some(code @ 0..=127) => code as usize,
+        _ => bail!(
+            "git merge-file failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    };
+    let merged = String::from_utf8(output.stdout).wrap_err("merged spec is not valid UTF-8")?;
+    Ok((merged, conflicts))
+}
+
+/// Register the driver in the repository's git config and route every spec
+/// include pattern to it in `.gitattributes`. Returns the patterns added.
+pub fn install(project_root: &Path, config: &Config) -> Result<Vec<String>> {
+    git_capture(
+        project_root,
+        &[
+            "config",
+            &format!("merge.{}.name", DRIVER),
+            "tracey spec merge (renumbers rules bumped on both sides)",
+        ],
+    )?;
+    git_capture(
+        project_root,
+        &[
+            "config",
+            &format!("merge.{}.driver", DRIVER),
+            "tracey merge-driver %O %A %B %P",
+        ],
+    )?;
+
+    let path = project_root.join(".gitattributes");
+    let mut attributes = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    let mut added = Vec::new();
+    for pattern in config.specs.iter().flat_map(|spec| &spec.include) {
+        let line = format!("{} merge={}", pattern, DRIVER);
+        if attributes.lines().any(|l| l.trim() == line) || added.contains(pattern) {
+            continue;
+        }
+        if !attributes.is_empty() && !attributes.ends_with('\n') {
+            attributes.push('\n');
+        }
+        attributes.push_str(&line);
+        attributes.push('\n');
+        added.push(pattern.clone());
+    }
+    if !added.is_empty() {
+        std::fs::write(&path, attributes)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_version_keeps_prefix_and_attributes() {
+        let mut content = "# Spec\n\nr[auth.login+2 status=draft]\nText.\n".to_string();
+        let offset = content.find("r[").unwrap();
+        let length = "r[auth.login+2 status=draft]".len();
+        set_version_at(&mut content, offset, length, "auth.login", 4);
+        assert_eq!(content, "# Spec\n\nr[auth.login+4 status=draft]\nText.\n");
+
+        let mut plain = "r[auth.login]\n".to_string();
+        set_version_at(&mut plain, 0, "r[auth.login]".len(), "auth.login", 2);
+        assert_eq!(plain, "r[auth.login+2]\n");
+    }
+}
@@ -0,0 +1,302 @@
+//! Rewriting configs that still use deprecated options.
+//!
+//! r[impl cli.config.migrate]
+//!
+//! Migrations work on the config text instead of a parsed [`Config`], so
+//! comments, ordering and formatting survive the rewrite. Each migration
+//! names the block its key lives in (top level, spec or impl); blocks are
+//! tracked by following the `(`/`{` nesting of the file.
+
+use eyre::{Result, WrapErr};
+use std::path::Path;
+
+use crate::config::Config;
+
+/// The kind of block a config key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Root,
+    Spec,
+    Impl,
+}
+
+/// A deprecated config key and what to do with it.
+pub struct Migration {
+    pub scope: Scope,
+    pub key: &'static str,
+    /// New name for the key, or `None` to remove the entry
+    pub rename_to: Option<&'static str>,
+    /// Why the key changed, shown to the user
+    pub reason: &'static str,
+}
+
+/// Every config migration tracey knows about.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    scope: Scope::Spec,
+    key: "prefix",
+    rename_to: None,
+    reason: "prefixes are inferred from requirement markers in spec files",
+}];
+
+/// One rewritten (or not rewritable) line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// 1-indexed line in the original file
+    pub line: usize,
+    pub description: String,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationOutcome {
+    pub content: String,
+    pub changes: Vec<Change>,
+    /// Deprecated entries that have to be fixed by hand
+    pub manual: Vec<Change>,
+}
+
+/// Structural characters of a line, ignoring strings and `//` comments.
+fn structure(line: &str) -> Vec<char> {
+    let mut out = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => break,
+            '(' | ')' | '{' | '}' | ',' => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The key an entry line starts with, e.g. `prefix` for `    prefix r`.
+fn leading_key(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let end = trimmed
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(trimmed.len());
+    let key = &trimmed[..end];
+    let rest = &trimmed[end..];
+    let separated = rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == '(');
+    (!key.is_empty() && separated).then_some(key)
+}
+
+fn scope_of(stack: &[Option<String>]) -> Option<Scope> {
+    let labels: Vec<Option<&str>> = stack.iter().map(|l| l.as_deref()).collect();
+    match labels.as_slice() {
+        [] => Some(Scope::Root),
+        [Some("specs"), None] => Some(Scope::Spec),
+        [Some("specs"), None, Some("impls"), None] => Some(Scope::Impl),
+        _ => None,
+    }
+}
+
+/// Whether `key` appears as an inline entry (after `{` or `,`) in `line`.
+fn has_inline_entry(line: &str, key: &str) -> bool {
+    line.match_indices(key).any(|(idx, _)| {
+        let before = line[..idx].trim_end();
+        let after = &line[idx + key.len()..];
+        (before.ends_with('{') || before.ends_with(','))
+            && after.starts_with(|c: char| c.is_whitespace())
+    })
+}
+
+fn action(migration: &Migration) -> String {
+    match migration.rename_to {
+        Some(new) => format!("renamed to `{}`", new),
+        None => "removed".to_string(),
+    }
+}
+
+/// Apply `migrations` to config text.
+pub fn migrate_str(content: &str, migrations: &[Migration]) -> MigrationOutcome {
+    let mut outcome = MigrationOutcome::default();
+    let mut stack: Vec<Option<String>> = Vec::new();
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let line_no = idx + 1;
+        let shape = structure(line);
+        let key = leading_key(line);
+        let scope = scope_of(&stack);
+
+        let mut replacement = None;
+        if let Some(key) = key
+            && let Some(migration) = migrations
+                .iter()
+                .find(|m| m.key == key && Some(m.scope) == scope)
+        {
+            match migration.rename_to {
+                Some(new) => {
+                    let indent = line.len() - line.trim_start().len();
+                    let rest = &line[indent + key.len()..];
+                    replacement = Some(format!("{}{}{}", &line[..indent], new, rest));
+                }
+                // Dropping the line is only safe when nothing else lives on it.
+                None if shape.is_empty() => replacement = Some(String::new()),
+                None => outcome.manual.push(Change {
+                    line: line_no,
+                    description: format!(
+                        "`{}` must be {} by hand ({})",
+                        migration.key,
+                        action(migration),
+                        migration.reason
+                    ),
+                }),
+            }
+            if replacement.is_some() {
+                outcome.changes.push(Change {
+                    line: line_no,
+                    description: format!(
+                        "`{}` {} ({})",
+                        migration.key,
+                        action(migration),
+                        migration.reason
+                    ),
+                });
+            }
+        }
+        for migration in migrations {
+            if has_inline_entry(line, migration.key) {
+                outcome.manual.push(Change {
+                    line: line_no,
+                    description: format!(
+                        "inline `{}` entry must be {} by hand ({})",
+                        migration.key,
+                        action(migration),
+                        migration.reason
+                    ),
+                });
+            }
+        }
+
+        outcome
+            .content
+            .push_str(replacement.as_deref().unwrap_or(line));
+
+        let mut labelled = false;
+        for c in shape {
+            match c {
+                '(' | '{' => {
+                    let label = if labelled {
+                        None
+                    } else {
+                        labelled = true;
+                        key.map(str::to_string)
+                    };
+                    stack.push(label);
+                }
+                ')' | '}' => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Migrate the config file at `path`, writing the result unless `dry_run`.
+///
+/// The file is left untouched if the rewritten config no longer parses.
+pub fn migrate_file(path: &Path, dry_run: bool) -> Result<MigrationOutcome> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read config file: {}", path.display()))?;
+    let outcome = migrate_str(&content, MIGRATIONS);
+
+    if outcome.changes.is_empty() || dry_run {
+        return Ok(outcome);
+    }
+
+    facet_styx::from_str::<Config>(&outcome.content).map_err(|e| {
+        eyre::eyre!(
+            "Migrated config for {} would not parse, leaving it untouched: {}",
+            path.display(),
+            e
+        )
+    })?;
+    std::fs::write(path, &outcome.content)
+        .wrap_err_with(|| format!("Failed to write config file: {}", path.display()))?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.config.migrate]
+    #[test]
+    fn removes_spec_prefix_and_keeps_comments() {
+        let content = "\
+// project config
+specs (
+  {
+    name my-spec
+    // legacy marker prefix
+    prefix r
+    include (docs/**/*.md)
+    impls (
+      {
+        name rust
+        prefix keep-me
+      }
+    )
+  }
+)
+";
+        let outcome = migrate_str(content, MIGRATIONS);
+        assert_eq!(
+            outcome.content,
+            content.replace("    prefix r\n", ""),
+            "only the spec-level prefix is removed"
+        );
+        assert_eq!(outcome.changes.len(), 1);
+        assert_eq!(outcome.changes[0].line, 6);
+        assert!(outcome.manual.is_empty());
+    }
+
+    #[test]
+    fn renames_keys_in_scope() {
+        const RENAME: &[Migration] = &[Migration {
+            scope: Scope::Impl,
+            key: "tests",
+            rename_to: Some("test_include"),
+            reason: "renamed",
+        }];
+        let content = "\
+specs (
+  {
+    name s
+    impls (
+      {
+        tests (t/**)
+      }
+    )
+  }
+)
+";
+        let outcome = migrate_str(content, RENAME);
+        assert!(outcome.content.contains("        test_include (t/**)\n"));
+        assert_eq!(outcome.changes.len(), 1);
+    }
+
+    #[test]
+    fn reports_entries_it_cannot_rewrite() {
+        let content = "specs (\n  {name my-spec, prefix r}\n)\n";
+        let outcome = migrate_str(content, MIGRATIONS);
+        assert_eq!(outcome.content, content);
+        assert!(outcome.changes.is_empty());
+        assert_eq!(outcome.manual.len(), 1);
+        assert_eq!(outcome.manual[0].line, 2);
+    }
+}
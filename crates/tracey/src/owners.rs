@@ -0,0 +1,96 @@
+//! Requirement ownership from the config's `owners` list.
+//!
+//! r[impl config.owners]
+//!
+//! Works like CODEOWNERS: each entry maps a glob over base requirement IDs
+//! to a list of owners, and when several entries match a requirement the
+//! last one wins, so broad defaults go first and specific overrides after.
+
+use eyre::{Result, WrapErr};
+use globset::{Glob, GlobMatcher};
+
+use crate::config::OwnerRule;
+
+/// Compiled `owners` entries, in config order.
+#[derive(Debug, Default)]
+pub struct Owners {
+    rules: Vec<(GlobMatcher, Vec<String>)>,
+}
+
+impl Owners {
+    pub fn new(rules: &[OwnerRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let glob = Glob::new(&rule.rules)
+                    .wrap_err_with(|| format!("Invalid owners pattern {:?}", rule.rules))?;
+                Ok((glob.compile_matcher(), rule.owners.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Owners of the requirement with this base ID (empty if unowned).
+    pub fn owners_of(&self, base: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.is_match(base))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// Whether `owner` is one of `owners`, ignoring case and a leading `@`.
+pub fn is_owned_by(owners: &[String], owner: &str) -> bool {
+    let wanted = owner.trim_start_matches('@');
+    owners
+        .iter()
+        .any(|o| o.trim_start_matches('@').eq_ignore_ascii_case(wanted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(rules: &str, owners: &[&str]) -> OwnerRule {
+        OwnerRule {
+            rules: rules.to_string(),
+            owners: owners.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    // r[verify config.owners]
+    #[test]
+    fn last_matching_entry_wins() {
+        let owners = Owners::new(&[
+            rule("*", &["@core"]),
+            rule("auth.*", &["@platform-team"]),
+            rule("auth.token.*", &["@security", "alice"]),
+        ])
+        .unwrap();
+
+        assert_eq!(owners.owners_of("data.format"), ["@core"]);
+        assert_eq!(owners.owners_of("auth.login"), ["@platform-team"]);
+        assert_eq!(
+            owners.owners_of("auth.token.expiry"),
+            ["@security", "alice"]
+        );
+        assert!(Owners::default().owners_of("auth.login").is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let error = Owners::new(&[rule("auth.[", &["@core"])]).unwrap_err();
+        assert!(error.to_string().contains("auth.["), "{error}");
+    }
+
+    #[test]
+    fn owner_filter_ignores_at_sign_and_case() {
+        let owners = vec!["@Platform-Team".to_string(), "alice".to_string()];
+        assert!(is_owned_by(&owners, "@platform-team"));
+        assert!(is_owned_by(&owners, "platform-team"));
+        assert!(is_owned_by(&owners, "@alice"));
+        assert!(!is_owned_by(&owners, "@security"));
+    }
+}
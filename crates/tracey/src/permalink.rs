@@ -0,0 +1,111 @@
+//! Links to where a rule is defined, built from its spec's `source_url`.
+//!
+//! r[impl config.spec.source-url.permalink]
+//!
+//! `source_url` says where a spec lives, usually a repository. Rules defined
+//! in one of the spec's `include` files get a link to their definition line
+//! there, so people reading `tracey query rule`, a hover or the dashboard
+//! can jump to the canonical text without a checkout. Rules read from a
+//! `rules_url` or `source` copy get none: the path of the cached copy says
+//! nothing about where the file lives upstream.
+
+use crate::bump::{Globs, glob_set};
+use crate::config::SpecConfig;
+
+/// Permalinks of the rules of one spec.
+pub struct Permalinks {
+    source_url: String,
+    include: Globs,
+}
+
+impl Permalinks {
+    /// `None` if the spec has no `source_url`.
+    pub fn new(spec: &SpecConfig) -> Option<Self> {
+        Some(Self {
+            source_url: spec.source_url.clone()?,
+            include: glob_set(&spec.include),
+        })
+    }
+
+    /// The link to `rule`, defined at `line` of `path` (relative to the
+    /// project root), or `None` if `path` isn't one of the spec's files.
+    pub fn link(&self, path: &str, line: usize, rule: &str) -> Option<String> {
+        let path = path.replace('\\', "/");
+        self.include
+            .is_match(&path)
+            .then(|| permalink(&self.source_url, &path, line, rule))
+    }
+}
+
+/// Build a permalink from a `source_url`.
+///
+/// A URL containing `{path}`, `{line}` or `{rule}` is a template, and those
+/// are substituted. Any other URL is taken to be a repository, linked to
+/// with a GitLab (`/-/blob/HEAD/...`) or GitHub-style (`/blob/HEAD/...`)
+/// file URL and a `#L{line}` anchor.
+pub fn permalink(source_url: &str, path: &str, line: usize, rule: &str) -> String {
+    let path = path.replace(' ', "%20");
+    if ["{path}", "{line}", "{rule}"]
+        .iter()
+        .any(|placeholder| source_url.contains(placeholder))
+    {
+        return source_url
+            .replace("{path}", &path)
+            .replace("{line}", &line.to_string())
+            .replace("{rule}", rule);
+    }
+    let repo = source_url.trim_end_matches('/');
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    let blob = if repo.contains("gitlab") {
+        "-/blob"
+    } else {
+        "blob"
+    };
+    format!("{}/{}/HEAD/{}#L{}", repo, blob, path, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify config.spec.source-url.permalink]
+    #[test]
+    fn links_to_the_definition_line() {
+        assert_eq!(
+            permalink(
+                "https://github.com/example/api/",
+                "docs/spec/auth.md",
+                12,
+                "auth.login"
+            ),
+            "https://github.com/example/api/blob/HEAD/docs/spec/auth.md#L12"
+        );
+        assert_eq!(
+            permalink(
+                "https://gitlab.com/example/api.git",
+                "docs/my spec.md",
+                3,
+                "auth.login"
+            ),
+            "https://gitlab.com/example/api/-/blob/HEAD/docs/my%20spec.md#L3"
+        );
+        assert_eq!(
+            permalink(
+                "https://git.example.com/api/src/branch/main/{path}#L{line}",
+                "docs/spec/auth.md",
+                12,
+                "auth.login"
+            ),
+            "https://git.example.com/api/src/branch/main/docs/spec/auth.md#L12"
+        );
+        assert_eq!(
+            permalink(
+                "https://spec.example.com/#{rule}",
+                "docs/spec/auth.md",
+                12,
+                "auth.login"
+            ),
+            "https://spec.example.com/#auth.login"
+        );
+    }
+}
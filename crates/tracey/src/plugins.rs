@@ -0,0 +1,336 @@
+//! Validation plugins.
+//!
+//! r[impl config.plugins]
+//! r[impl validation.plugins]
+//!
+//! A plugin is a WebAssembly module listed under `plugins` in the config. For
+//! every spec/impl pair, tracey hands it the spec's rules together with their
+//! references as JSON and appends the findings it returns to the pair's
+//! validation results. Plugins get no imports: they can only look at what
+//! they are given, and each call runs on a fixed fuel budget and memory limit
+//! so a runaway plugin fails instead of stalling or exhausting the daemon.
+//!
+//! The module must export:
+//!
+//! - `memory`
+//! - `tracey_alloc(len: i32) -> i32`, returning a buffer tracey writes the
+//!   input into
+//! - `tracey_validate(ptr: i32, len: i32) -> i64`, returning the output
+//!   buffer as `(ptr << 32) | len`
+//!
+//! The input is a [`PluginInput`], the output a JSON array of
+//! [`PluginFinding`]s.
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracey_api::{ApiRule, ApiSpecForward, ValidationError, ValidationErrorCode, ValidationResult};
+use tracey_core::parse_rule_id;
+
+use crate::data::ImplKey;
+
+/// What a plugin is asked to validate.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct PluginInput {
+    pub spec: String,
+    pub impl_name: String,
+    /// Every rule of the spec with its impl, verify and stale references
+    pub rules: Vec<ApiRule>,
+}
+
+/// A finding reported by a plugin.
+#[derive(Debug, Clone, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct PluginFinding {
+    pub message: String,
+    /// ID of the rule the finding is about, e.g. `auth.login+2`
+    #[facet(default)]
+    pub rule: Option<String>,
+    #[facet(default)]
+    pub file: Option<String>,
+    #[facet(default)]
+    pub line: Option<usize>,
+    /// Report as a warning instead of an error
+    #[facet(default)]
+    pub warning: bool,
+}
+
+/// Run every configured plugin against every spec/impl pair and add the
+/// findings to `validation_by_impl`.
+///
+/// A plugin that cannot be loaded or fails is reported as an error for each
+/// pair instead of aborting the build.
+pub fn run_validation_plugins(
+    project_root: &Path,
+    plugins: &[String],
+    forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>,
+    validation_by_impl: &mut BTreeMap<ImplKey, ValidationResult>,
+) {
+    for plugin in plugins {
+        let loaded = load_plugin(&project_root.join(plugin));
+        for (impl_key, forward) in forward_by_impl {
+            let Some(result) = validation_by_impl.get_mut(impl_key) else {
+                continue;
+            };
+            let input = PluginInput {
+                spec: impl_key.0.clone(),
+                impl_name: impl_key.1.clone(),
+                rules: forward.rules.clone(),
+            };
+            let findings = loaded
+                .as_ref()
+                .map_err(|e| eyre::eyre!("{e:#}"))
+                .and_then(|plugin| plugin.validate(&input));
+            match findings {
+                Ok(findings) => {
+                    for finding in findings {
+                        result.errors.push(to_validation_error(plugin, finding));
+                    }
+                }
+                Err(e) => result.errors.push(ValidationError {
+                    code: ValidationErrorCode::PluginError,
+                    message: format!("Plugin {} failed: {:#}", plugin, e),
+                    file: Some(plugin.clone()),
+                    line: None,
+                    column: None,
+                    related_rules: vec![],
+                    reference_rule_id: None,
+                    reference_text: None,
+                }),
+            }
+            result.warning_count = result.errors.iter().filter(|e| e.code.is_warning()).count();
+            result.error_count = result.errors.len() - result.warning_count;
+        }
+    }
+}
+
+fn to_validation_error(plugin: &str, finding: PluginFinding) -> ValidationError {
+    ValidationError {
+        code: if finding.warning {
+            ValidationErrorCode::PluginWarning
+        } else {
+            ValidationErrorCode::PluginError
+        },
+        message: format!("[{}] {}", plugin_name(plugin), finding.message),
+        file: finding.file,
+        line: finding.line,
+        column: None,
+        related_rules: finding
+            .rule
+            .as_deref()
+            .and_then(parse_rule_id)
+            .into_iter()
+            .collect(),
+        reference_rule_id: None,
+        reference_text: None,
+    }
+}
+
+/// Short name for a plugin path, e.g. `safety` for `tools/safety.wasm`.
+fn plugin_name(plugin: &str) -> &str {
+    Path::new(plugin)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(plugin)
+}
+
+fn load_plugin(path: &Path) -> Result<runtime::Plugin> {
+    let bytes = std::fs::read(path)
+        .wrap_err_with(|| format!("Failed to read plugin {}", path.display()))?;
+    runtime::Plugin::new(&bytes)
+}
+
+#[cfg(feature = "plugins")]
+mod runtime {
+    use super::{PluginFinding, PluginInput};
+    use eyre::Result;
+
+    /// Instructions a single `tracey_validate` call may execute.
+    const FUEL: u64 = 1_000_000_000;
+
+    /// Bytes of linear memory a plugin may grow to.
+    const MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+    pub struct Plugin {
+        engine: wasmi::Engine,
+        module: wasmi::Module,
+    }
+
+    impl Plugin {
+        pub fn new(bytes: &[u8]) -> Result<Self> {
+            let mut config = wasmi::Config::default();
+            config.consume_fuel(true);
+            let engine = wasmi::Engine::new(&config);
+            let module = wasmi::Module::new(&engine, bytes)
+                .map_err(|e| eyre::eyre!("Invalid WebAssembly module: {}", e))?;
+            Ok(Self { engine, module })
+        }
+
+        pub fn validate(&self, input: &PluginInput) -> Result<Vec<PluginFinding>> {
+            let input = facet_json::to_string(input)
+                .map_err(|e| eyre::eyre!("Failed to encode plugin input: {}", e))?;
+            let output = self.call(input.as_bytes())?;
+            let output = String::from_utf8(output)
+                .map_err(|_| eyre::eyre!("Plugin output is not valid UTF-8"))?;
+            facet_json::from_str(&output)
+                .map_err(|e| eyre::eyre!("Plugin output is not a list of findings: {}", e))
+        }
+
+        fn call(&self, input: &[u8]) -> Result<Vec<u8>> {
+            let limits = wasmi::StoreLimitsBuilder::new()
+                .memory_size(MEMORY_LIMIT)
+                .build();
+            let mut store = wasmi::Store::new(&self.engine, limits);
+            store.limiter(|limits| limits);
+            store
+                .set_fuel(FUEL)
+                .map_err(|e| eyre::eyre!("Failed to set fuel: {}", e))?;
+            let linker = wasmi::Linker::<wasmi::StoreLimits>::new(&self.engine);
+            let instance = linker
+                .instantiate(&mut store, &self.module)
+                .and_then(|pre| pre.start(&mut store))
+                .map_err(|e| eyre::eyre!("Failed to instantiate plugin: {}", e))?;
+
+            let memory = instance
+                .get_memory(&store, "memory")
+                .ok_or_else(|| eyre::eyre!("Plugin does not export `memory`"))?;
+            let alloc = instance
+                .get_typed_func::<i32, i32>(&store, "tracey_alloc")
+                .map_err(|e| eyre::eyre!("Plugin does not export `tracey_alloc`: {}", e))?;
+            let validate = instance
+                .get_typed_func::<(i32, i32), i64>(&store, "tracey_validate")
+                .map_err(|e| eyre::eyre!("Plugin does not export `tracey_validate`: {}", e))?;
+
+            let len =
+                i32::try_from(input.len()).map_err(|_| eyre::eyre!("Plugin input is too large"))?;
+            let ptr = alloc
+                .call(&mut store, len)
+                .map_err(|e| eyre::eyre!("`tracey_alloc` trapped: {}", e))?;
+            memory
+                .write(&mut store, ptr as u32 as usize, input)
+                .map_err(|e| eyre::eyre!("`tracey_alloc` returned an invalid buffer: {}", e))?;
+
+            let packed = validate
+                .call(&mut store, (ptr, len))
+                .map_err(|e| eyre::eyre!("`tracey_validate` trapped: {}", e))?;
+            let out_ptr = (packed as u64 >> 32) as usize;
+            let out_len = (packed as u64 & 0xffff_ffff) as usize;
+            // The length is the plugin's to pick: check it before allocating
+            let end = out_ptr.checked_add(out_len);
+            if end.is_none_or(|end| end > memory.data_size(&store)) {
+                eyre::bail!(
+                    "`tracey_validate` returned an invalid buffer: {out_len} bytes at {out_ptr} \
+                     are outside its memory"
+                );
+            }
+            let mut output = vec![0u8; out_len];
+            memory
+                .read(&store, out_ptr, &mut output)
+                .map_err(|e| eyre::eyre!("`tracey_validate` returned an invalid buffer: {}", e))?;
+            Ok(output)
+        }
+    }
+}
+
+#[cfg(not(feature = "plugins"))]
+mod runtime {
+    use super::{PluginFinding, PluginInput};
+    use eyre::Result;
+
+    pub struct Plugin;
+
+    impl Plugin {
+        pub fn new(_bytes: &[u8]) -> Result<Self> {
+            eyre::bail!("tracey was built without the `plugins` feature")
+        }
+
+        pub fn validate(&self, _input: &PluginInput) -> Result<Vec<PluginFinding>> {
+            unreachable!("plugins cannot be loaded without the `plugins` feature")
+        }
+    }
+}
+
+#[cfg(all(test, feature = "plugins"))]
+mod tests {
+    use super::*;
+
+    /// A plugin that ignores its input and reports one fixed finding.
+    const FIXED_FINDING: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "[{\"message\":\"needs two verify refs\",\"rule\":\"safety.brakes\"}]")
+  (func (export "tracey_alloc") (param i32) (result i32)
+    i32.const 1024)
+  (func (export "tracey_validate") (param i32 i32) (result i64)
+    i64.const 60))
+"#;
+
+    fn input() -> PluginInput {
+        PluginInput {
+            spec: "spec".to_string(),
+            impl_name: "rust".to_string(),
+            rules: vec![],
+        }
+    }
+
+    // r[verify validation.plugins]
+    #[test]
+    fn runs_plugin_and_decodes_findings() {
+        let wasm = wat::parse_str(FIXED_FINDING).expect("valid wat");
+        let plugin = runtime::Plugin::new(&wasm).expect("plugin loads");
+        let findings = plugin.validate(&input()).expect("plugin runs");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].message, "needs two verify refs");
+        assert_eq!(findings[0].rule.as_deref(), Some("safety.brakes"));
+        assert!(!findings[0].warning);
+    }
+
+    #[test]
+    fn rejects_output_outside_memory() {
+        let wasm = wat::parse_str(
+            r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "tracey_alloc") (param i32) (result i32)
+    i32.const 0)
+  (func (export "tracey_validate") (param i32 i32) (result i64)
+    i64.const 0xffffffff))
+"#,
+        )
+        .expect("valid wat");
+        let plugin = runtime::Plugin::new(&wasm).expect("plugin loads");
+        let err = plugin.validate(&input()).expect_err("output out of bounds");
+        assert!(err.to_string().contains("outside its memory"), "{err}");
+    }
+
+    #[test]
+    fn caps_memory_growth() {
+        let wasm = wat::parse_str(
+            r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "tracey_alloc") (param i32) (result i32)
+    i32.const 0)
+  (func (export "tracey_validate") (param i32 i32) (result i64)
+    ;; 8192 pages are 512 MiB
+    (if (i32.eq (memory.grow (i32.const 8192)) (i32.const -1))
+      (then unreachable))
+    i64.const 0))
+"#,
+        )
+        .expect("valid wat");
+        let plugin = runtime::Plugin::new(&wasm).expect("plugin loads");
+        let err = plugin.validate(&input()).expect_err("growth is refused");
+        assert!(err.to_string().contains("trapped"), "{err}");
+    }
+
+    #[test]
+    fn reports_missing_exports() {
+        let wasm = wat::parse_str(r#"(module (memory (export "memory") 1))"#).expect("valid wat");
+        let plugin = runtime::Plugin::new(&wasm).expect("plugin loads");
+        let err = plugin.validate(&input()).expect_err("missing exports");
+        assert!(err.to_string().contains("tracey_alloc"), "{err}");
+    }
+}
@@ -0,0 +1,535 @@
+//! Coverage policies.
+//!
+//! r[impl config.policy]
+//!
+//! A `policy` block on a spec applies to all of its impls; a `policy` block on
+//! an impl overrides individual fields of the spec's policy. Policies are
+//! evaluated while building dashboard data so the dashboard, `tracey check`
+//! and the pre-commit hook all see the same result.
+
+use eyre::Result;
+use owo_colors::OwoColorize;
+use std::path::Path;
+use tracey_api::{ApiPolicyStatus, ApiRule, ApiSpecInfo};
+
+use crate::baseline::Baseline;
+use crate::config::{Config, Impl, Policy, SpecConfig};
+use crate::release::Release;
+use crate::server::CoverageStats;
+
+/// A spec's policy merged with an impl's overrides.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EffectivePolicy {
+    /// Minimum implementation coverage in percent (0–100)
+    pub min_impl_coverage: Option<f64>,
+    pub max_stale: Option<usize>,
+    pub forbid_unreferenced: bool,
+    pub require_approval: bool,
+}
+
+/// Parse a coverage threshold such as `90%`, `90` or `87.5%`.
+pub fn parse_percent(value: &str) -> Result<f64> {
+    let trimmed = value.trim();
+    let number = trimmed.strip_suffix('%').unwrap_or(trimmed).trim();
+    let percent: f64 = number.parse().map_err(|_| {
+        eyre::eyre!(
+            "Invalid coverage threshold '{}' (expected e.g. \"90%\")",
+            value
+        )
+    })?;
+    if !(0.0..=100.0).contains(&percent) {
+        eyre::bail!("Coverage threshold '{}' must be between 0% and 100%", value);
+    }
+    Ok(percent)
+}
+
+/// Merge the spec's policy with the impl's overrides.
+///
+/// r[impl config.policy.override]
+///
+/// Returns `None` when neither the spec nor the impl configures a policy.
+pub fn effective_policy(spec: &SpecConfig, impl_config: &Impl) -> Result<Option<EffectivePolicy>> {
+    if spec.policy.is_none() && impl_config.policy.is_none() {
+        return Ok(None);
+    }
+    let empty = Policy::default();
+    let base = spec.policy.as_ref().unwrap_or(&empty);
+    let over = impl_config.policy.as_ref().unwrap_or(&empty);
+
+    let min_impl_coverage = over
+        .min_impl_coverage
+        .as_deref()
+        .or(base.min_impl_coverage.as_deref())
+        .map(parse_percent)
+        .transpose()?;
+
+    Ok(Some(EffectivePolicy {
+        min_impl_coverage,
+        max_stale: over.max_stale.or(base.max_stale),
+        forbid_unreferenced: over
+            .forbid_unreferenced
+            .or(base.forbid_unreferenced)
+            .unwrap_or(false),
+        require_approval: over
+            .require_approval
+            .or(base.require_approval)
+            .unwrap_or(false),
+    }))
+}
+
+/// Check a spec/impl's rules against a policy and describe every violation.
+pub fn violations(policy: &EffectivePolicy, rules: &[ApiRule]) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    // r[impl config.policy.min-impl-coverage]
+    if let Some(min) = policy.min_impl_coverage {
+        let stats = CoverageStats::from_rules(rules);
+        if stats.impl_percent < min {
+            violations.push(format!(
+                "impl coverage {:.1}% is below the required {}%",
+                stats.impl_percent, min
+            ));
+        }
+    }
+
+    // r[impl config.policy.max-stale]
+    if let Some(max) = policy.max_stale {
+        let stale: usize = rules.iter().map(|r| r.stale_refs.len()).sum();
+        if stale > max {
+            violations.push(format!(
+                "{} stale reference(s), at most {} allowed",
+                stale, max
+            ));
+        }
+    }
+
+    // r[impl config.policy.forbid-unreferenced]
+    if policy.forbid_unreferenced {
+        let unreferenced: Vec<String> = rules
+            .iter()
+            .filter(|r| {
+                r.impl_refs.is_empty() && r.verify_refs.is_empty() && r.stale_refs.is_empty()
+            })
+            .map(|r| r.id.to_string())
+            .collect();
+        if !unreferenced.is_empty() {
+            violations.push(format!(
+                "{} unreferenced requirement(s): {}",
+                unreferenced.len(),
+                unreferenced.join(", ")
+            ));
+        }
+    }
+
+    // r[impl config.policy.require-approval]
+    if policy.require_approval {
+        let unapproved: Vec<String> = rules
+            .iter()
+            .filter(|r| is_must_level(r) && r.status.as_deref() != Some("deprecated"))
+            .filter(|r| r.approvals.is_empty())
+            .map(|r| r.id.to_string())
+            .collect();
+        if !unapproved.is_empty() {
+            violations.push(format!(
+                "{} MUST-level requirement(s) without approval: {}",
+                unapproved.len(),
+                unapproved.join(", ")
+            ));
+        }
+    }
+
+    violations
+}
+
+/// Whether a rule is MUST-level: its `level` says so, or without one its
+/// text uses MUST, SHALL or REQUIRED (as the dashboard infers it).
+pub(crate) fn is_must_level(rule: &ApiRule) -> bool {
+    match rule.level.as_deref() {
+        Some(level) => level.eq_ignore_ascii_case("must"),
+        None => {
+            let text = rule.raw.to_uppercase();
+            ["MUST", "SHALL", "REQUIRED"]
+                .iter()
+                .any(|word| text.contains(word))
+        }
+    }
+}
+
+/// Evaluate the policies of every impl of `spec` that has one.
+///
+/// A policy that can't be read (such as a malformed threshold) fails that
+/// impl with the problem as its violation, rather than the whole build.
+pub fn check_spec<'a>(
+    spec: &SpecConfig,
+    rules_for_impl: impl Fn(&str) -> Option<&'a [ApiRule]>,
+) -> Vec<ApiPolicyStatus> {
+    let mut statuses = Vec::new();
+    for impl_config in &spec.impls {
+        let violations = match effective_policy(spec, impl_config) {
+            Ok(Some(policy)) => {
+                violations(&policy, rules_for_impl(&impl_config.name).unwrap_or(&[]))
+            }
+            Ok(None) => continue,
+            Err(e) => vec![format!("invalid policy: {e}")],
+        };
+        statuses.push(ApiPolicyStatus {
+            impl_name: impl_config.name.clone(),
+            passed: violations.is_empty(),
+            violations,
+        });
+    }
+    statuses
+}
+
+/// Check whether any spec or impl configures a policy.
+pub fn any_configured(config: &Config) -> bool {
+    config
+        .specs
+        .iter()
+        .any(|spec| spec.policy.is_some() || spec.impls.iter().any(|i| i.policy.is_some()))
+}
+
+/// Build coverage data and report policy results on stderr, or as
+/// [`porcelain_report`] lines on stdout.
+///
+/// r[impl cli.check]
+///
+/// With a `baseline`, debt missing from it is reported as a failure of the
+/// affected spec/impl alongside its policy violations, and so are the
+/// certified rule versions of a `release` the code no longer implements.
+///
+/// Returns whether every policy (and the baseline and release) passed.
+pub async fn run_check(
+    project_root: &Path,
+    config: &Config,
+    porcelain: bool,
+    baseline: Option<&Baseline>,
+    release: Option<&Release>,
+) -> Result<bool> {
+    let mut data = crate::data::build_dashboard_data(project_root, config, 0, true).await?;
+
+    let mut resolved = 0usize;
+    let mut regressions = Vec::new();
+    if let Some(baseline) = baseline {
+        let comparison = baseline.compare(&Baseline::from_data(&data));
+        resolved = comparison.resolved;
+        regressions.extend(comparison.regressions);
+    }
+    if let Some(release) = release {
+        regressions.extend(release.compare(&crate::release::implemented(&data)));
+    }
+    if baseline.is_some() || release.is_some() {
+        for (spec_name, regression) in regressions {
+            let Some(spec) = data.config.specs.iter_mut().find(|s| s.name == spec_name) else {
+                continue;
+            };
+            match spec
+                .policies
+                .iter_mut()
+                .find(|status| status.impl_name == regression.impl_name)
+            {
+                Some(status) => {
+                    status.passed = false;
+                    status.violations.extend(regression.violations);
+                }
+                None => spec.policies.push(regression),
+            }
+        }
+        // Impls with a clean comparison and no policy still pass.
+        for spec in &mut data.config.specs {
+            for impl_name in &spec.implementations {
+                if !spec.policies.iter().any(|s| &s.impl_name == impl_name) {
+                    spec.policies.push(ApiPolicyStatus {
+                        impl_name: impl_name.clone(),
+                        passed: true,
+                        violations: vec![],
+                    });
+                }
+            }
+        }
+    }
+
+    // r[impl cli.porcelain]
+    if porcelain {
+        print!("{}", porcelain_report(&data.config.specs));
+        return Ok(data
+            .config
+            .specs
+            .iter()
+            .all(|spec| spec.policies.iter().all(|status| status.passed)));
+    }
+
+    let mut checked = 0usize;
+    let mut failed = 0usize;
+    for spec in &data.config.specs {
+        for status in &spec.policies {
+            checked += 1;
+            if status.passed {
+                eprintln!("{} {}/{}", "✓".green(), spec.name, status.impl_name);
+                continue;
+            }
+            failed += 1;
+            eprintln!("{} {}/{}", "✗".red(), spec.name, status.impl_name);
+            for violation in &status.violations {
+                eprintln!("  - {}", violation);
+            }
+        }
+    }
+
+    if checked == 0 {
+        eprintln!("No coverage policies configured.");
+    } else if failed > 0 {
+        eprintln!();
+        eprintln!("{} of {} policy check(s) failed.", failed, checked);
+    }
+    if resolved > 0 {
+        eprintln!(
+            "{} baseline item(s) resolved; run `tracey baseline record` to lock in the progress.",
+            resolved
+        );
+    }
+
+    Ok(failed == 0)
+}
+
+/// One tab-separated line per policy: `pass<TAB>spec<TAB>impl`, or
+/// `fail<TAB>spec<TAB>impl<TAB>violation` for each violation of a failed one.
+pub fn porcelain_report(specs: &[ApiSpecInfo]) -> String {
+    let mut output = String::new();
+    for spec in specs {
+        for status in &spec.policies {
+            if status.passed {
+                output.push_str(&format!("pass\t{}\t{}\n", spec.name, status.impl_name));
+            }
+            for violation in &status.violations {
+                output.push_str(&format!(
+                    "fail\t{}\t{}\t{}\n",
+                    spec.name,
+                    status.impl_name,
+                    violation.replace(['\t', '\n'], " ")
+                ));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::{ApiApproval, ApiCodeRef, ApiStaleRef};
+    use tracey_core::parse_rule_id;
+
+    fn rule(id: &str, impl_refs: usize, verify_refs: usize) -> ApiRule {
+        let code_ref = ApiCodeRef {
+            file: "src/lib.rs".to_string(),
+            line: 1,
+        };
+        ApiRule {
+            id: parse_rule_id(id).expect("valid rule id"),
+            raw: String::new(),
+            html: String::new(),
+            status: None,
+            level: None,
+            source_file: None,
+            source_line: None,
+            source_column: None,
+            permalink: None,
+            section: None,
+            section_title: None,
+            renamed_from: vec![],
+            depends_on: vec![],
+            refines: vec![],
+            impl_refs: vec![code_ref.clone(); impl_refs],
+            verify_refs: vec![code_ref; verify_refs],
+            depends_refs: vec![],
+            is_stale: false,
+            stale_refs: vec![],
+            owners: vec![],
+            approvals: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn parses_percentages() {
+        assert_eq!(parse_percent("90%").unwrap(), 90.0);
+        assert_eq!(parse_percent("87.5").unwrap(), 87.5);
+        assert!(parse_percent("101%").is_err());
+        assert!(parse_percent("most").is_err());
+    }
+
+    #[test]
+    fn impl_policy_overrides_spec_policy() {
+        let spec = SpecConfig {
+            name: "spec".to_string(),
+            prefix: None,
+            source_url: None,
+            include: vec!["spec.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: Some(Policy {
+                min_impl_coverage: Some("90%".to_string()),
+                max_stale: Some(0),
+                forbid_unreferenced: None,
+                require_approval: None,
+            }),
+            impls: vec![Impl {
+                name: "main".to_string(),
+                include: vec![],
+                exclude: vec![],
+                no_default_excludes: false,
+                test_include: vec![],
+                test_conventions: false,
+                code_units: None,
+                skip_units: None,
+                variants: vec![],
+                policy: Some(Policy {
+                    min_impl_coverage: Some("50%".to_string()),
+                    ..Default::default()
+                }),
+            }],
+        };
+        let policy = effective_policy(&spec, &spec.impls[0])
+            .unwrap()
+            .expect("policy configured");
+        assert_eq!(
+            policy,
+            EffectivePolicy {
+                min_impl_coverage: Some(50.0),
+                max_stale: Some(0),
+                forbid_unreferenced: false,
+                require_approval: false,
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_policy_fails_its_impl() {
+        let spec = SpecConfig {
+            name: "spec".to_string(),
+            prefix: None,
+            source_url: None,
+            include: vec!["spec.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: Some(Policy {
+                min_impl_coverage: Some("most".to_string()),
+                ..Default::default()
+            }),
+            impls: vec![Impl {
+                name: "main".to_string(),
+                include: vec![],
+                exclude: vec![],
+                no_default_excludes: false,
+                test_include: vec![],
+                test_conventions: false,
+                code_units: None,
+                skip_units: None,
+                variants: vec![],
+                policy: None,
+            }],
+        };
+        let statuses = check_spec(&spec, |_| None);
+        assert_eq!(statuses.len(), 1);
+        assert!(!statuses[0].passed);
+        assert!(
+            statuses[0].violations[0].starts_with("invalid policy: Invalid coverage threshold"),
+            "{statuses:?}"
+        );
+    }
+
+    #[test]
+    fn reports_each_violation() {
+        let mut stale = rule("a.stale+2", 0, 0);
+        stale.is_stale = true;
+        stale.stale_refs = vec![ApiStaleRef {
+            file: "src/lib.rs".to_string(),
+            line: 3,
+            reference_id: parse_rule_id("a.stale").expect("valid rule id"),
+        }];
+        let rules = vec![rule("a.done", 1, 1), rule("a.lonely", 0, 0), stale];
+        let policy = EffectivePolicy {
+            min_impl_coverage: Some(90.0),
+            max_stale: Some(0),
+            forbid_unreferenced: true,
+            require_approval: false,
+        };
+
+        let violations = violations(&policy, &rules);
+        assert_eq!(violations.len(), 3, "{violations:?}");
+        assert!(violations[0].starts_with("impl coverage 33.3%"));
+        assert_eq!(violations[1], "1 stale reference(s), at most 0 allowed");
+        assert_eq!(violations[2], "1 unreferenced requirement(s): a.lonely");
+    }
+
+    // r[verify config.policy.require-approval]
+    #[test]
+    fn requires_approval_of_must_level_rules() {
+        let mut approved = rule("a.approved+3", 1, 0);
+        approved.raw = "Users MUST log in.".to_string();
+        approved.approvals = vec![ApiApproval {
+            by: "alice".to_string(),
+            date: "2026-10-14".to_string(),
+        }];
+        let mut unapproved = rule("a.unapproved", 1, 0);
+        unapproved.raw = "Sessions SHALL expire.".to_string();
+        let mut optional = rule("a.optional", 1, 0);
+        optional.raw = "Clients MAY cache tokens.".to_string();
+        let mut tagged = rule("a.tagged", 1, 0);
+        tagged.level = Some("must".to_string());
+        let mut deprecated = rule("a.deprecated", 1, 0);
+        deprecated.level = Some("must".to_string());
+        deprecated.status = Some("deprecated".to_string());
+        let rules = vec![approved, unapproved, optional, tagged, deprecated];
+        let policy = EffectivePolicy {
+            require_approval: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            violations(&policy, &rules),
+            ["2 MUST-level requirement(s) without approval: a.unapproved, a.tagged"]
+        );
+    }
+
+    // r[verify cli.porcelain]
+    #[test]
+    fn porcelain_report_has_one_line_per_result() {
+        let spec = ApiSpecInfo {
+            name: "spec".to_string(),
+            prefix: "r".to_string(),
+            source: None,
+            source_url: None,
+            implementations: vec!["main".to_string(), "ts".to_string()],
+            policies: vec![
+                ApiPolicyStatus {
+                    impl_name: "main".to_string(),
+                    passed: true,
+                    violations: vec![],
+                },
+                ApiPolicyStatus {
+                    impl_name: "ts".to_string(),
+                    passed: false,
+                    violations: vec![
+                        "1 stale reference(s), at most 0 allowed".to_string(),
+                        "1 unreferenced requirement(s): a.lonely".to_string(),
+                    ],
+                },
+            ],
+            no_default_excludes: vec![],
+            skipped_units: vec![],
+            variants: vec![],
+        };
+        assert_eq!(
+            porcelain_report(&[spec]),
+            "pass\tspec\tmain\n\
+             fail\tspec\tts\t1 stale reference(s), at most 0 allowed\n\
+             fail\tspec\tts\t1 unreferenced requirement(s): a.lonely\n"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Rename redirects in requirement markers.
+//!
+//! r[impl markdown.syntax.redirect]
+//!
+//! A marker like `r[auth.sign-in <- auth.login]` defines `auth.sign-in` and
+//! records that it used to be called `auth.login`, so references to the old
+//! ID keep resolving while they are migrated. Several old IDs can be listed,
+//! separated by commas. The markdown renderer doesn't know the `<-` clause,
+//! so it is cut out of the marker before rendering, padding the line with
+//! spaces so that byte offsets into the document stay the same. Date
+//! versions (`+2024-11-03`) are respelled as integers the same way.
+
+use std::borrow::Cow;
+
+use tracey_core::{RuleId, parse_rule_id};
+
+/// The old IDs of a renamed requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// ID the marker defines, as written
+    pub to: String,
+    /// IDs the requirement was previously known by
+    pub from: Vec<RuleId>,
+}
+
+/// A `<-` clause found on a marker line.
+struct Clause {
+    /// Byte range of the clause within the document, up to the closing `]`
+    start: usize,
+    end: usize,
+    redirect: Redirect,
+}
+
+fn find_clauses(content: &str) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let text = line.trim_start_matches([' ', '\t', '>']);
+        if text.starts_with("```") || text.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let Some(open) = text.find('[') else {
+            continue;
+        };
+        let prefix = &text[..open];
+        if prefix.is_empty()
+            || !prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            continue;
+        }
+        let Some(close) = text[open..].find(']').map(|i| open + i) else {
+            continue;
+        };
+        let inner = &text[open + 1..close];
+        let Some(arrow) = inner.find(" <- ") else {
+            continue;
+        };
+
+        let Some(to) = inner[..arrow].split_whitespace().next() else {
+            continue;
+        };
+        let from: Vec<RuleId> = inner[arrow + 4..]
+            .split([',', ' '])
+            .filter(|id| !id.is_empty())
+            .filter_map(parse_rule_id)
+            .collect();
+        if from.is_empty() {
+            continue;
+        }
+
+        let text_start = line_start + (line.len() - text.len());
+        clauses.push(Clause {
+            start: text_start + open + 1 + inner[..arrow].trim_end().len(),
+            end: text_start + close,
+            redirect: Redirect {
+                to: to.to_string(),
+                from,
+            },
+        });
+    }
+    clauses
+}
+
+/// The redirects declared by the markers in a spec document.
+pub fn parse_redirects(content: &str) -> Vec<Redirect> {
+    find_clauses(content)
+        .into_iter()
+        .map(|clause| clause.redirect)
+        .collect()
+}
+
+/// The old IDs of the requirement defined as `id`.
+pub fn renamed_from(redirects: &[Redirect], id: &str) -> Vec<RuleId> {
+    redirects
+        .iter()
+        .filter(|r| r.to == id)
+        .flat_map(|r| r.from.iter().cloned())
+        .collect()
+}
+
+/// The document with the `<-` clauses cut out of its markers and date
+/// versions spelled as integers, ready for rendering. Each clause moves
+/// behind the marker's `]` as spaces.
+pub fn strip_redirects(content: &str) -> Cow<'_, str> {
+    match tracey_core::normalize_date_versions(content) {
+        Cow::Borrowed(content) => strip_clauses(content),
+        Cow::Owned(normalized) => Cow::Owned(strip_clauses(&normalized).into_owned()),
+    }
+}
+
+fn strip_clauses(content: &str) -> Cow<'_, str> {
+    let clauses = find_clauses(content);
+    if clauses.is_empty() {
+        return Cow::Borrowed(content);
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for clause in clauses {
+        out.push_str(&content[last..clause.start]);
+        out.push(']');
+        out.push_str(&" ".repeat(clause.end - clause.start));
+        last = clause.end + 1;
+    }
+    out.push_str(&content[last..]);
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_old_ids_after_the_arrow() {
+        let spec = "# Auth\n\nr[auth.sign-in <- auth.login, auth.logon+2]\nUsers MUST sign in.\n";
+        assert_eq!(
+            parse_redirects(spec),
+            vec![Redirect {
+                to: "auth.sign-in".to_string(),
+                from: vec![
+                    parse_rule_id("auth.login").unwrap(),
+                    parse_rule_id("auth.logon+2").unwrap()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn stripping_keeps_offsets() {
+        let spec = "> r[auth.sign-in+2 <- auth.login]\n> Users MUST sign in.\n";
+        assert_eq!(
+            strip_redirects(spec),
+            format!(
+                "> r[auth.sign-in+2]{}\n> Users MUST sign in.\n",
+                " ".repeat(" <- auth.login".len())
+            )
+        );
+    }
+
+    #[test]
+    fn ignores_code_blocks_and_plain_markers() {
+        let spec = "```\nr[auth.sign-in <- auth.login]\n```\n\nr[auth.logout]\nBye.\n";
+        assert!(parse_redirects(spec).is_empty());
+        assert!(matches!(strip_redirects(spec), Cow::Borrowed(_)));
+    }
+}
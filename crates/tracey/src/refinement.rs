@@ -0,0 +1,159 @@
+//! Refinement tracing between requirements of the same spec.
+//!
+//! r[impl markdown.syntax.refines]
+//!
+//! A lower-level rule names the higher-level rule it refines in its text, as
+//! in `r[refines sys.auth]` under `auth.login`. The first ID segment of a
+//! refined rule marks a level: once anything refines `sys.auth`, every `sys.*`
+//! rule is expected to be refined by at least one rule, and the ones that
+//! aren't are reported by `tracey query unrefined`.
+
+use std::collections::{BTreeSet, HashSet};
+
+use tracey_api::ApiRule;
+use tracey_core::{RuleId, parse_rule_id};
+use tracey_proto::UnrefinedRule;
+
+use crate::spec_deps::prose;
+
+/// The rules a rule's text says it refines, in order, without duplicates.
+pub fn parse_refinements(raw: &str) -> Vec<RuleId> {
+    let text = prose(raw);
+    let mut refines: Vec<RuleId> = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find("[refines ") {
+        let before = &rest[..open];
+        let after = &rest[open + "[refines ".len()..];
+        rest = after;
+        if !before
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        {
+            continue;
+        }
+        let Some(close) = after.find(']') else {
+            break;
+        };
+        let Some(id) = parse_rule_id(after[..close].trim()) else {
+            continue;
+        };
+        if !refines.contains(&id) {
+            refines.push(id);
+        }
+    }
+    refines
+}
+
+/// The first segment of a rule ID, which names its level.
+pub fn level(id: &RuleId) -> &str {
+    id.base.split('.').next().unwrap_or(&id.base)
+}
+
+/// The levels that are refined by other rules, sorted.
+pub fn refined_levels(rules: &[ApiRule]) -> Vec<String> {
+    let levels: BTreeSet<&str> = rules
+        .iter()
+        .flat_map(|r| r.refines.iter())
+        .filter(|target| rules.iter().any(|r| r.id.base == target.base))
+        .map(level)
+        .collect();
+    levels.into_iter().map(str::to_string).collect()
+}
+
+/// The rules that refine the rule with base ID `base`.
+pub fn refined_by(rules: &[ApiRule], base: &str) -> Vec<RuleId> {
+    rules
+        .iter()
+        .filter(|r| r.refines.iter().any(|target| target.base == base))
+        .map(|r| r.id.clone())
+        .collect()
+}
+
+/// Rules of a refined level that no rule refines, in spec order.
+pub fn find_unrefined(rules: &[ApiRule]) -> Vec<UnrefinedRule> {
+    let levels = refined_levels(rules);
+    let refined: HashSet<&str> = rules
+        .iter()
+        .flat_map(|r| r.refines.iter())
+        .map(|target| target.base.as_str())
+        .collect();
+
+    rules
+        .iter()
+        .filter(|r| levels.iter().any(|l| l == level(&r.id)))
+        .filter(|r| !refined.contains(r.id.base.as_str()))
+        .map(|r| UnrefinedRule {
+            id: r.id.clone(),
+            source_file: r.source_file.clone(),
+            source_line: r.source_line,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, refines: &[&str]) -> ApiRule {
+        ApiRule {
+            id: parse_rule_id(id).unwrap(),
+            raw: String::new(),
+            html: String::new(),
+            status: None,
+            level: None,
+            source_file: Some("docs/spec.md".to_string()),
+            source_line: Some(1),
+            source_column: None,
+            permalink: None,
+            section: None,
+            section_title: None,
+            renamed_from: vec![],
+            depends_on: vec![],
+            refines: refines.iter().map(|r| parse_rule_id(r).unwrap()).collect(),
+            impl_refs: vec![],
+            verify_refs: vec![],
+            depends_refs: vec![],
+            is_stale: false,
+            stale_refs: vec![],
+            owners: vec![],
+            approvals: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn parses_refines_markers_outside_code() {
+        let raw = "Users MUST log in, see r[refines sys.auth+2] and r[refines sys.audit].\n\
+                   Write `r[refines sys.other]` to refine a rule. r[refines sys.auth+2]";
+        assert_eq!(
+            parse_refinements(raw),
+            vec![
+                parse_rule_id("sys.auth+2").unwrap(),
+                parse_rule_id("sys.audit").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_rules_of_refined_levels_without_refinements() {
+        let rules = vec![
+            rule("sys.auth", &[]),
+            rule("sys.audit", &[]),
+            rule("auth.login", &["sys.auth"]),
+            rule("auth.logout", &[]),
+        ];
+        assert_eq!(refined_levels(&rules), vec!["sys".to_string()]);
+        let unrefined: Vec<String> = find_unrefined(&rules)
+            .into_iter()
+            .map(|r| r.id.to_string())
+            .collect();
+        assert_eq!(unrefined, vec!["sys.audit".to_string()]);
+        assert_eq!(
+            refined_by(&rules, "sys.auth"),
+            vec![parse_rule_id("auth.login").unwrap()]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Release snapshots for `tracey release cut` and `tracey check
+//! --against-release`.
+//!
+//! r[impl cli.release.cut]
+//!
+//! A release pins the requirement versions a shipped version of the project
+//! was certified against: every rule of every spec, at its current version,
+//! with the impls that implemented that version. It's written to
+//! `.tracey/releases/NAME.json` and committed with the release, so a
+//! maintenance branch can check, long after the spec has moved on, that its
+//! code still implements exactly those versions.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use facet::Facet;
+use tracey_api::ApiPolicyStatus;
+use tracey_core::parse_rule_id;
+
+use crate::data::DashboardData;
+
+/// Version of the release file layout.
+pub const RELEASE_VERSION: u32 = 1;
+
+/// The requirement versions certified for a release.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct Release {
+    pub version: u32,
+    /// Release name, e.g. `v1.4`
+    pub name: String,
+    /// When it was cut (Unix seconds)
+    pub cut_at: u64,
+    /// Commit it was cut at, outside a git repository `None`
+    #[facet(default)]
+    pub commit: Option<String>,
+    pub specs: Vec<ReleaseSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ReleaseSpec {
+    pub spec: String,
+    /// In spec order
+    pub rules: Vec<ReleaseRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ReleaseRule {
+    /// Canonical ID, with its version (`auth.login+2`)
+    pub id: String,
+    /// Raw markdown of the rule, for `tracey spec-diff`
+    #[facet(default)]
+    pub text: Option<String>,
+    /// Impls whose code implemented this version
+    pub impls: Vec<String>,
+}
+
+/// The rule IDs, with their versions, that the `impl` references of each
+/// spec/impl name, by rule base ID.
+pub type Implemented = BTreeMap<(String, String), BTreeMap<String, BTreeSet<String>>>;
+
+/// Where releases are kept.
+pub fn releases_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".tracey").join("releases")
+}
+
+/// Where the release `name` is kept.
+pub fn release_path(project_root: &Path, name: &str) -> PathBuf {
+    releases_dir(project_root).join(format!("{name}.json"))
+}
+
+/// Check that `name` can be a file name: letters, digits, `.`, `-` and
+/// `_`, like `v1.4` or `2026-10`.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        eyre::bail!(
+            "Invalid release name '{}': use letters, digits, '.', '-' and '_', e.g. v1.4",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// What the `impl` references of `data` name, for [`Release::compare`].
+/// Stale references count too: they name the older version they were
+/// written against.
+pub fn implemented(data: &DashboardData) -> Implemented {
+    let mut implemented = Implemented::new();
+    for ((spec, impl_name), forward) in &data.forward_by_impl {
+        let by_base = implemented
+            .entry((spec.clone(), impl_name.clone()))
+            .or_default();
+        for rule in &forward.rules {
+            if !rule.impl_refs.is_empty() {
+                by_base
+                    .entry(rule.id.base.clone())
+                    .or_default()
+                    .insert(rule.id.to_string());
+            }
+            for stale in &rule.stale_refs {
+                by_base
+                    .entry(stale.reference_id.base.clone())
+                    .or_default()
+                    .insert(stale.reference_id.to_string());
+            }
+        }
+    }
+    implemented
+}
+
+impl Release {
+    /// Pin the current version of every rule in `data` as `name`.
+    pub fn from_data(name: &str, data: &DashboardData, cut_at: u64) -> Self {
+        let mut specs: Vec<ReleaseSpec> = Vec::new();
+        for ((spec, impl_name), forward) in &data.forward_by_impl {
+            let index = match specs.iter().position(|s| &s.spec == spec) {
+                Some(index) => index,
+                None => {
+                    specs.push(ReleaseSpec {
+                        spec: spec.clone(),
+                        rules: Vec::new(),
+                    });
+                    specs.len() - 1
+                }
+            };
+            let rules = &mut specs[index].rules;
+            for rule in &forward.rules {
+                let id = rule.id.to_string();
+                let position = match rules.iter().position(|r| r.id == id) {
+                    Some(position) => position,
+                    None => {
+                        rules.push(ReleaseRule {
+                            id,
+                            text: Some(rule.raw.clone()),
+                            impls: Vec::new(),
+                        });
+                        rules.len() - 1
+                    }
+                };
+                if !rule.impl_refs.is_empty() {
+                    rules[position].impls.push(impl_name.clone());
+                }
+            }
+        }
+        let project_root = Path::new(&data.config.project_root);
+        Self {
+            version: RELEASE_VERSION,
+            name: name.to_string(),
+            cut_at,
+            commit: crate::bump::git_capture(project_root, &["rev-parse", "HEAD"])
+                .ok()
+                .map(|out| out.trim().to_string()),
+            specs,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).wrap_err_with(|| {
+            format!(
+                "Failed to read release {} (create it with `tracey release cut`)",
+                path.display()
+            )
+        })?;
+        let release: Self = facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid release {}: {}", path.display(), e))?;
+        if release.version != RELEASE_VERSION {
+            eyre::bail!(
+                "Release {} has version {}, expected {}",
+                path.display(),
+                release.version,
+                RELEASE_VERSION
+            );
+        }
+        Ok(release)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = facet_json::to_string_pretty(self).expect("JSON serialization failed");
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Number of rules, and of rule versions with at least one impl.
+    pub fn counts(&self) -> (usize, usize) {
+        let rules = self.specs.iter().flat_map(|s| &s.rules);
+        let implemented = rules.clone().filter(|r| !r.impls.is_empty()).count();
+        (rules.count(), implemented)
+    }
+
+    /// The spec/impls whose code no longer implements exactly the rule
+    /// versions certified for this release.
+    ///
+    /// r[impl cli.check.against-release]
+    ///
+    /// A certified version fails when the impl has no reference to that
+    /// rule anymore, or references only other versions of it. Rules added
+    /// to the spec since the release aren't checked.
+    pub fn compare(&self, current: &Implemented) -> Vec<(String, ApiPolicyStatus)> {
+        let none = BTreeMap::new();
+        let mut regressions = Vec::new();
+        for spec in &self.specs {
+            let mut by_impl: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+            for rule in &spec.rules {
+                let base = parse_rule_id(&rule.id).map_or(rule.id.clone(), |id| id.base);
+                for impl_name in &rule.impls {
+                    let refs = current
+                        .get(&(spec.spec.clone(), impl_name.clone()))
+                        .unwrap_or(&none)
+                        .get(&base);
+                    let violation = match refs {
+                        Some(refs) if refs.contains(&rule.id) => continue,
+                        Some(refs) if !refs.is_empty() => format!(
+                            "{} was certified for {} but the code implements {}",
+                            rule.id,
+                            self.name,
+                            refs.iter().cloned().collect::<Vec<_>>().join(", ")
+                        ),
+                        _ => format!(
+                            "{} was certified for {} and is no longer implemented",
+                            rule.id, self.name
+                        ),
+                    };
+                    by_impl.entry(impl_name).or_default().push(violation);
+                }
+            }
+            for (impl_name, violations) in by_impl {
+                regressions.push((
+                    spec.spec.clone(),
+                    ApiPolicyStatus {
+                        impl_name: impl_name.to_string(),
+                        passed: false,
+                        violations,
+                    },
+                ));
+            }
+        }
+        regressions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, impls: &[&str]) -> ReleaseRule {
+        ReleaseRule {
+            id: id.to_string(),
+            text: None,
+            impls: impls.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn implementing(refs: &[&str]) -> Implemented {
+        let mut by_base: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for id in refs {
+            let base = parse_rule_id(id).unwrap().base;
+            by_base.entry(base).or_default().insert(id.to_string());
+        }
+        Implemented::from([(("spec".to_string(), "rust".to_string()), by_base)])
+    }
+
+    // r[verify cli.check.against-release]
+    #[test]
+    fn fails_when_certified_versions_drift() {
+        let release = Release {
+            version: RELEASE_VERSION,
+            name: "v1.4".to_string(),
+            cut_at: 0,
+            commit: None,
+            specs: vec![ReleaseSpec {
+                spec: "spec".to_string(),
+                rules: vec![
+                    rule("auth.login+2", &["rust"]),
+                    rule("auth.logout", &["rust"]),
+                    rule("auth.session", &["rust"]),
+                    rule("auth.audit", &[]),
+                ],
+            }],
+        };
+
+        // The spec moved auth.login to +3, but the code still implements +2.
+        assert!(
+            release
+                .compare(&implementing(&[
+                    "auth.login+2",
+                    "auth.logout",
+                    "auth.session"
+                ]))
+                .is_empty()
+        );
+
+        let regressions = release.compare(&implementing(&["auth.login+3", "auth.session"]));
+        let (spec, status) = &regressions[0];
+        assert_eq!(spec, "spec");
+        assert_eq!(status.impl_name, "rust");
+        assert_eq!(
+            status.violations,
+            vec![
+                "auth.login+2 was certified for v1.4 but the code implements auth.login+3",
+                "auth.logout was certified for v1.4 and is no longer implemented",
+            ]
+        );
+    }
+
+    // r[verify cli.release.cut]
+    #[test]
+    fn round_trips_through_json() {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = release_path(temp.path(), "v1.4");
+        assert!(path.ends_with(".tracey/releases/v1.4.json"));
+        let release = Release {
+            version: RELEASE_VERSION,
+            name: "v1.4".to_string(),
+            cut_at: 1_760_000_000,
+            commit: Some("0123abc".to_string()),
+            specs: vec![ReleaseSpec {
+                spec: "spec".to_string(),
+                rules: vec![
+                    ReleaseRule {
+                        text: Some("Users MUST log in.".to_string()),
+                        ..rule("auth.login+2", &["rust"])
+                    },
+                    rule("auth.audit", &[]),
+                ],
+            }],
+        };
+        release.save(&path).unwrap();
+        assert_eq!(Release::load(&path).unwrap(), release);
+        assert_eq!(release.counts(), (2, 1));
+
+        assert!(validate_name("v1.4").is_ok());
+        assert!(validate_name("../v1.4").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
@@ -0,0 +1,890 @@
+//! Remote spec sources.
+//!
+//! r[impl config.spec.rules-url]
+//!
+//! Specs can point at a markdown document over HTTP(S) via `rules_url`. The
+//! document is downloaded into the per-project state directory and scanned
+//! from there like any other spec file.
+//!
+//! ## Caching
+//!
+//! Each URL gets its own directory under `<state_dir>/remote/<hash>`, where
+//! `<hash>` is a truncated Blake3 hash of the URL. It contains:
+//!
+//! - `rules.md`: the last successfully fetched document
+//! - `meta`: `key=value` lines with the URL, `ETag`, `Last-Modified` and the
+//!   fetch timestamp
+//!
+//! r[impl config.spec.rules-url.refresh]
+//!
+//! A cached document is reused until its refresh interval elapses. After that
+//! the next rebuild revalidates it with `If-None-Match`/`If-Modified-Since`,
+//! so an unchanged upstream costs a single 304. If the fetch fails and a
+//! cached copy exists, the cached copy is used and a warning is logged.
+//!
+//! Downloads go through the `curl` binary, the same way git status comes
+//! from the `git` binary.
+//!
+//! ## Git Sources
+//!
+//! r[impl config.spec.source]
+//!
+//! A spec's `source { git ..., rev ..., path ... }` block is checked out into
+//! `<state_dir>/remote/<hash>/checkout`, keyed by URL and revision, with a
+//! shallow `git fetch` of just that revision.
+//!
+//! r[impl config.spec.source.rev]
+//!
+//! A pinned `rev` is fetched once and never revalidated, since a different
+//! revision gets a different cache directory. Without a `rev` the remote's
+//! HEAD is followed and refreshed on the default interval like `rules_url`.
+
+use eyre::{Result, WrapErr};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use std::collections::BTreeMap;
+use tracey_core::{RuleId, parse_rule_id};
+use tracey_proto::{RuleVersionDrift, SpecDrift};
+
+use crate::config::{Config, SpecConfig, SpecSource};
+
+/// Refresh interval used when a spec doesn't set `rules_url_refresh`.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// File name of the cached document inside a URL's cache directory.
+const CACHED_DOCUMENT: &str = "rules.md";
+
+/// File name of the cache metadata inside a URL's cache directory.
+const CACHE_META: &str = "meta";
+
+/// Parse a refresh interval such as `90`, `30s`, `15m`, `6h` or `1d`.
+///
+/// A bare number is interpreted as seconds.
+pub fn parse_refresh_interval(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((idx, 's')) => (&value[..idx], 1),
+        Some((idx, 'm')) => (&value[..idx], 60),
+        Some((idx, 'h')) => (&value[..idx], 60 * 60),
+        Some((idx, 'd')) => (&value[..idx], 24 * 60 * 60),
+        _ => (value, 1),
+    };
+    let amount: u64 = digits.trim().parse().map_err(|_| {
+        eyre::eyre!(
+            "Invalid refresh interval '{}' (expected e.g. \"30m\", \"6h\" or \"1d\")",
+            value
+        )
+    })?;
+    Ok(Duration::from_secs(amount.saturating_mul(multiplier)))
+}
+
+/// Resolve the refresh interval configured for a spec.
+pub fn refresh_interval_for(refresh: Option<&str>) -> Result<Duration> {
+    match refresh {
+        Some(value) => parse_refresh_interval(value),
+        None => Ok(DEFAULT_REFRESH_INTERVAL),
+    }
+}
+
+/// Directory holding the cached copy of `url` for this project.
+pub fn cache_dir(project_root: &Path, url: &str) -> PathBuf {
+    let hash = blake3::hash(url.as_bytes());
+    crate::daemon::state_dir(project_root)
+        .join("remote")
+        .join(&hash.to_hex()[..16])
+}
+
+/// Cached validators and fetch time for a remote document.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct CacheMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Seconds since the UNIX epoch of the last successful fetch or revalidation.
+    fetched_at: u64,
+}
+
+impl CacheMeta {
+    fn read(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let mut meta = Self::default();
+        for line in content.lines() {
+            if let Some(v) = line.strip_prefix("etag=") {
+                meta.etag = Some(v.to_string());
+            } else if let Some(v) = line.strip_prefix("last_modified=") {
+                meta.last_modified = Some(v.to_string());
+            } else if let Some(v) = line.strip_prefix("fetched_at=") {
+                meta.fetched_at = v.parse().ok()?;
+            }
+        }
+        Some(meta)
+    }
+
+    fn write(&self, path: &Path, url: &str) -> Result<()> {
+        let mut content = format!("url={url}\nfetched_at={}\n", self.fetched_at);
+        if let Some(etag) = &self.etag {
+            content.push_str(&format!("etag={etag}\n"));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            content.push_str(&format!("last_modified={last_modified}\n"));
+        }
+        std::fs::write(path, content)
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn age(&self) -> Duration {
+        Duration::from_secs(unix_now().saturating_sub(self.fetched_at))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Check whether the cached copy of `url` is missing or older than `refresh`.
+pub fn refresh_due(project_root: &Path, url: &str, refresh: Duration) -> bool {
+    let dir = cache_dir(project_root, url);
+    if !dir.join(CACHED_DOCUMENT).exists() {
+        return true;
+    }
+    CacheMeta::read(&dir.join(CACHE_META)).is_none_or(|meta| meta.age() >= refresh)
+}
+
+/// Extract `ETag` and `Last-Modified` from a curl header dump.
+///
+/// With `--location` the dump contains one header block per hop; later
+/// blocks win so the validators belong to the final response.
+fn parse_validators(headers: &str) -> (Option<String>, Option<String>) {
+    let mut etag = None;
+    let mut last_modified = None;
+    for line in headers.lines() {
+        if line.starts_with("HTTP/") {
+            etag = None;
+            last_modified = None;
+            continue;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        if name.eq_ignore_ascii_case("etag") {
+            etag = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("last-modified") {
+            last_modified = Some(value.to_string());
+        }
+    }
+    (etag, last_modified)
+}
+
+/// A locally cached copy of a remote spec.
+#[derive(Debug, Clone)]
+pub struct RemoteCopy {
+    /// Identifies the remote source, e.g. in the lockfile
+    pub source: String,
+    /// Directory the spec files were fetched into
+    pub root: PathBuf,
+    /// Glob selecting spec files, relative to `root`
+    pub pattern: String,
+    is_git: bool,
+}
+
+impl RemoteCopy {
+    /// Absolute include pattern for scanning this copy alongside local specs.
+    pub fn include_pattern(&self) -> String {
+        format!("{}/{}", self.root.display(), self.pattern)
+    }
+
+    /// Revision of this copy: the checked-out commit for git sources, or a
+    /// content hash for `rules_url` documents.
+    async fn resolved(&self) -> Result<String> {
+        if self.is_git {
+            run_git(&self.root, &["rev-parse", "HEAD"]).await
+        } else {
+            let document = self.root.join(CACHED_DOCUMENT);
+            let content = std::fs::read(&document)
+                .wrap_err_with(|| format!("Failed to read {}", document.display()))?;
+            Ok(format!("blake3:{}", blake3::hash(&content).to_hex()))
+        }
+    }
+}
+
+/// The copies of a spec's `rules_url` and `source` that are already cached,
+/// without touching the network.
+pub fn cached_copies(project_root: &Path, spec: &SpecConfig) -> Vec<RemoteCopy> {
+    let mut copies = Vec::new();
+    if let Some(url) = &spec.rules_url {
+        let dir = cache_dir(project_root, url);
+        if dir.join(CACHED_DOCUMENT).exists() {
+            copies.push(RemoteCopy {
+                source: url.clone(),
+                root: dir,
+                pattern: "**/*.md".to_string(),
+                is_git: false,
+            });
+        }
+    }
+    if let Some(source) = &spec.source {
+        let key = git_cache_key(source);
+        let checkout = cache_dir(project_root, &key).join(GIT_CHECKOUT);
+        if checkout.join(".git").exists() {
+            let path = source.path.as_deref().unwrap_or(DEFAULT_SOURCE_PATH);
+            copies.push(RemoteCopy {
+                source: key,
+                root: checkout,
+                pattern: path.trim_start_matches('/').to_string(),
+                is_git: true,
+            });
+        }
+    }
+    copies
+}
+
+/// Make sure a usable copy of `url` is cached.
+///
+/// The network is only touched when the cache is missing, older than
+/// `refresh`, or `force` is set.
+pub async fn ensure_rules_url(
+    project_root: &Path,
+    url: &str,
+    refresh: Duration,
+    force: bool,
+) -> Result<RemoteCopy> {
+    let dir = cache_dir(project_root, url);
+    let document = dir.join(CACHED_DOCUMENT);
+    let meta_path = dir.join(CACHE_META);
+    let meta = if document.exists() {
+        CacheMeta::read(&meta_path)
+    } else {
+        None
+    };
+    let copy = RemoteCopy {
+        source: url.to_string(),
+        root: dir.clone(),
+        pattern: "**/*.md".to_string(),
+        is_git: false,
+    };
+
+    if !force
+        && let Some(meta) = &meta
+        && meta.age() < refresh
+    {
+        return Ok(copy);
+    }
+
+    std::fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+
+    match fetch(url, &dir, meta.as_ref()).await {
+        Ok(Fetched::NotModified) => {
+            info!("Remote spec {} not modified", url);
+            let mut meta = meta.unwrap_or_default();
+            meta.fetched_at = unix_now();
+            meta.write(&meta_path, url)?;
+        }
+        Ok(Fetched::Updated {
+            etag,
+            last_modified,
+        }) => {
+            info!("Fetched remote spec {}", url);
+            CacheMeta {
+                etag,
+                last_modified,
+                fetched_at: unix_now(),
+            }
+            .write(&meta_path, url)?;
+        }
+        Err(e) if document.exists() => {
+            warn!(
+                "Failed to refresh remote spec {}, using cached copy: {e}",
+                url
+            );
+        }
+        Err(e) => return Err(e),
+    }
+
+    Ok(copy)
+}
+
+enum Fetched {
+    NotModified,
+    Updated {
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+async fn fetch(url: &str, dir: &Path, meta: Option<&CacheMeta>) -> Result<Fetched> {
+    let body_path = dir.join(format!("{CACHED_DOCUMENT}.part"));
+    let headers_path = dir.join("headers.part");
+
+    let mut cmd = tokio::process::Command::new("curl");
+    cmd.arg("--silent")
+        .arg("--show-error")
+        .arg("--location")
+        .arg("--fail")
+        .arg("--output")
+        .arg(&body_path)
+        .arg("--dump-header")
+        .arg(&headers_path)
+        .arg("--write-out")
+        .arg("%{http_code}");
+    if let Some(meta) = meta {
+        if let Some(etag) = &meta.etag {
+            cmd.arg("--header").arg(format!("If-None-Match: {etag}"));
+        }
+        if let Some(last_modified) = &meta.last_modified {
+            cmd.arg("--header")
+                .arg(format!("If-Modified-Since: {last_modified}"));
+        }
+    }
+    cmd.arg(url);
+
+    let output = cmd
+        .output()
+        .await
+        .wrap_err("Failed to run curl (is it installed?)")?;
+    let headers = std::fs::read_to_string(&headers_path).unwrap_or_default();
+    let _ = std::fs::remove_file(&headers_path);
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&body_path);
+        eyre::bail!(
+            "Failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if status == "304" {
+        let _ = std::fs::remove_file(&body_path);
+        return Ok(Fetched::NotModified);
+    }
+
+    let document = dir.join(CACHED_DOCUMENT);
+    std::fs::rename(&body_path, &document)
+        .wrap_err_with(|| format!("Failed to write {}", document.display()))?;
+    let (etag, last_modified) = parse_validators(&headers);
+    Ok(Fetched::Updated {
+        etag,
+        last_modified,
+    })
+}
+
+/// Default glob for spec files inside a git source.
+const DEFAULT_SOURCE_PATH: &str = "**/*.md";
+
+/// Directory name of the working tree inside a git source's cache directory.
+const GIT_CHECKOUT: &str = "checkout";
+
+/// Cache key for a git source: one checkout per URL and revision.
+fn git_cache_key(source: &SpecSource) -> String {
+    format!(
+        "git+{}@{}",
+        source.git,
+        source.rev.as_deref().unwrap_or("HEAD")
+    )
+}
+
+/// Whether `rev` is a full commit hash (SHA-1 or SHA-256), which can't
+/// move, unlike a branch or a tag.
+fn is_commit_hash(rev: &str) -> bool {
+    matches!(rev.len(), 40 | 64) && rev.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Check whether a git source needs to be (re-)fetched.
+///
+/// A checkout of a commit hash only needs fetching once; branches, tags
+/// and `HEAD` are revalidated on the default interval.
+pub fn git_source_due(project_root: &Path, source: &SpecSource) -> bool {
+    let dir = cache_dir(project_root, &git_cache_key(source));
+    if !dir.join(GIT_CHECKOUT).join(".git").exists() {
+        return true;
+    }
+    if source.rev.as_deref().is_some_and(is_commit_hash) {
+        return false;
+    }
+    CacheMeta::read(&dir.join(CACHE_META)).is_none_or(|meta| meta.age() >= DEFAULT_REFRESH_INTERVAL)
+}
+
+/// Make sure a checkout of `source` exists.
+pub async fn ensure_git_source(
+    project_root: &Path,
+    source: &SpecSource,
+    force: bool,
+) -> Result<RemoteCopy> {
+    let key = git_cache_key(source);
+    let dir = cache_dir(project_root, &key);
+    let checkout = dir.join(GIT_CHECKOUT);
+    let path = source.path.as_deref().unwrap_or(DEFAULT_SOURCE_PATH);
+    let copy = RemoteCopy {
+        source: key.clone(),
+        root: checkout.clone(),
+        pattern: path.trim_start_matches('/').to_string(),
+        is_git: true,
+    };
+
+    if !force && !git_source_due(project_root, source) {
+        return Ok(copy);
+    }
+
+    let had_checkout = checkout.join(".git").exists();
+    match fetch_git(source, &checkout).await {
+        Ok(()) => {
+            info!("Fetched spec source {}", key);
+            CacheMeta {
+                fetched_at: unix_now(),
+                ..Default::default()
+            }
+            .write(&dir.join(CACHE_META), &key)?;
+        }
+        Err(e) if had_checkout => {
+            warn!(
+                "Failed to refresh spec source {}, using cached checkout: {e}",
+                key
+            );
+        }
+        Err(e) => return Err(e),
+    }
+
+    Ok(copy)
+}
+
+async fn fetch_git(source: &SpecSource, checkout: &Path) -> Result<()> {
+    if !checkout.join(".git").exists() {
+        std::fs::create_dir_all(checkout)
+            .wrap_err_with(|| format!("Failed to create {}", checkout.display()))?;
+        run_git(checkout, &["init", "--quiet"]).await?;
+        run_git(checkout, &["remote", "add", "origin", &source.git]).await?;
+    }
+
+    let rev = source.rev.as_deref().unwrap_or("HEAD");
+    run_git(
+        checkout,
+        &["fetch", "--quiet", "--depth", "1", "origin", rev],
+    )
+    .await?;
+    run_git(
+        checkout,
+        &["checkout", "--quiet", "--force", "--detach", "FETCH_HEAD"],
+    )
+    .await?;
+    Ok(())
+}
+
+/// Run git in `dir` and return its trimmed stdout.
+async fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .wrap_err("Failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        eyre::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Lockfile recording the remote spec revisions that rule versions were last
+/// synced against, relative to the project root.
+pub const LOCKFILE: &str = ".config/tracey/specs.lock";
+
+const LOCKFILE_HEADER: &str = "\
+# Generated by tracey. Records the remote spec revisions that rule versions
+# were last synced against. Check `tracey query spec-drift` for upstream changes.
+";
+
+/// Locked state of one remote source of a spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedSource {
+    pub spec: String,
+    pub source: String,
+    pub resolved: String,
+    /// Rule base ID → version
+    pub rules: BTreeMap<String, u32>,
+}
+
+/// Contents of the spec lockfile.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SpecLock {
+    pub sources: Vec<LockedSource>,
+}
+
+impl SpecLock {
+    /// Read the lockfile, treating a missing or unreadable file as empty.
+    pub fn read(project_root: &Path) -> Self {
+        std::fs::read_to_string(project_root.join(LOCKFILE))
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut sources: Vec<LockedSource> = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(spec) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sources.push(LockedSource {
+                    spec: spec.to_string(),
+                    source: String::new(),
+                    resolved: String::new(),
+                    rules: BTreeMap::new(),
+                });
+                continue;
+            }
+            let Some(current) = sources.last_mut() else {
+                continue;
+            };
+            if let Some(v) = line.strip_prefix("source=") {
+                current.source = v.to_string();
+            } else if let Some(v) = line.strip_prefix("resolved=") {
+                current.resolved = v.to_string();
+            } else if let Some(v) = line.strip_prefix("rule=")
+                && let Some(id) = parse_rule_id(v)
+            {
+                current.rules.insert(id.base, id.version);
+            }
+        }
+        Self { sources }
+    }
+
+    fn render(&self) -> String {
+        let mut out = LOCKFILE_HEADER.to_string();
+        for locked in &self.sources {
+            out.push_str(&format!(
+                "\n[{}]\nsource={}\nresolved={}\n",
+                locked.spec, locked.source, locked.resolved
+            ));
+            for (base, version) in &locked.rules {
+                if let Some(id) = RuleId::new(base.clone(), *version) {
+                    out.push_str(&format!("rule={id}\n"));
+                }
+            }
+        }
+        out
+    }
+
+    fn write(&self, project_root: &Path) -> Result<()> {
+        let path = project_root.join(LOCKFILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, self.render())
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Find the locked entry for a spec's remote source.
+    pub fn get(&self, spec: &str, source: &str) -> Option<&LockedSource> {
+        self.sources
+            .iter()
+            .find(|locked| locked.spec == spec && locked.source == source)
+    }
+}
+
+/// Record the revision and rule versions of `copy` in the lockfile.
+///
+/// r[impl config.spec.lockfile]
+///
+/// An existing entry is kept unless `force` is set, so the lockfile only
+/// moves when the source changes (e.g. a new `rev`) or on an explicit
+/// re-fetch. Entries for the same spec's previous source of the same kind
+/// are replaced.
+pub async fn update_lock(
+    project_root: &Path,
+    spec: &str,
+    copy: &RemoteCopy,
+    force: bool,
+) -> Result<()> {
+    let mut lock = SpecLock::read(project_root);
+    if !force && lock.get(spec, &copy.source).is_some() {
+        return Ok(());
+    }
+
+    let snapshot = snapshot(spec, copy).await?;
+    if lock.get(spec, &copy.source) == Some(&snapshot) {
+        return Ok(());
+    }
+    let is_git = copy.is_git;
+    lock.sources
+        .retain(|locked| locked.spec != spec || locked.source.starts_with("git+") != is_git);
+    lock.sources.push(snapshot);
+    lock.sources
+        .sort_by(|a, b| (&a.spec, &a.source).cmp(&(&b.spec, &b.source)));
+    lock.write(project_root)
+}
+
+async fn snapshot(spec: &str, copy: &RemoteCopy) -> Result<LockedSource> {
+    let rules = crate::load_rules_from_glob(&copy.root, &copy.pattern, true).await?;
+    Ok(LockedSource {
+        spec: spec.to_string(),
+        source: copy.source.clone(),
+        resolved: copy.resolved().await?,
+        rules: rules
+            .iter()
+            .filter_map(|rule| parse_rule_id(&rule.def.id.to_string()))
+            .map(|id| (id.base, id.version))
+            .collect(),
+    })
+}
+
+/// Rules that changed between a locked copy and upstream:
+/// `(bumped, added, removed)`.
+fn diff_rules(
+    locked: &BTreeMap<String, u32>,
+    upstream: &BTreeMap<String, u32>,
+) -> (Vec<RuleVersionDrift>, Vec<String>, Vec<String>) {
+    let mut bumped = Vec::new();
+    let mut added = Vec::new();
+    for (base, &upstream_version) in upstream {
+        match locked.get(base) {
+            Some(&locked_version) if upstream_version > locked_version => {
+                bumped.push(RuleVersionDrift {
+                    id: base.clone(),
+                    locked_version,
+                    upstream_version,
+                });
+            }
+            Some(_) => {}
+            None => added.push(base.clone()),
+        }
+    }
+    let removed = locked
+        .keys()
+        .filter(|base| !upstream.contains_key(*base))
+        .cloned()
+        .collect();
+    (bumped, added, removed)
+}
+
+/// Compare every remote spec's locked copy against upstream.
+///
+/// r[impl daemon.cli.query.spec-drift]
+///
+/// Upstream is the remote's HEAD for git sources and the current document
+/// for `rules_url` specs. It is fetched into a scratch directory, so the
+/// cached copies that builds scan are left alone.
+pub async fn check_drift(project_root: &Path, config: &Config) -> Vec<SpecDrift> {
+    let lock = SpecLock::read(project_root);
+    let mut drifts = Vec::new();
+
+    for spec in &config.specs {
+        let mut checks = Vec::new();
+        if let Some(url) = &spec.rules_url {
+            checks.push((url.clone(), url.clone(), None));
+        }
+        if let Some(source) = &spec.source {
+            let upstream = SpecSource {
+                rev: None,
+                ..source.clone()
+            };
+            let display = match &source.rev {
+                Some(rev) => format!("{} @ {}", source.git, rev),
+                None => source.git.clone(),
+            };
+            checks.push((git_cache_key(source), display, Some(upstream)));
+        }
+
+        for (key, display, git_upstream) in checks {
+            let locked = lock.get(&spec.name, &key);
+            let mut drift = SpecDrift {
+                spec: spec.name.clone(),
+                source: display.clone(),
+                locked: locked.map(|l| l.resolved.clone()),
+                upstream: None,
+                bumped: Vec::new(),
+                added: Vec::new(),
+                removed: Vec::new(),
+                error: None,
+            };
+
+            let scratch = match tempfile::tempdir() {
+                Ok(scratch) => scratch,
+                Err(e) => {
+                    drift.error = Some(format!("Failed to create a scratch directory: {e}"));
+                    drifts.push(drift);
+                    continue;
+                }
+            };
+            match fetch_upstream(&key, &display, git_upstream.as_ref(), scratch.path()).await {
+                Ok(copy) => match snapshot(&spec.name, &copy).await {
+                    Ok(upstream) => {
+                        let empty = BTreeMap::new();
+                        let locked_rules = locked.map(|l| &l.rules).unwrap_or(&empty);
+                        let (bumped, added, removed) = diff_rules(locked_rules, &upstream.rules);
+                        drift.upstream = Some(upstream.resolved);
+                        drift.bumped = bumped;
+                        drift.added = added;
+                        drift.removed = removed;
+                    }
+                    Err(e) => drift.error = Some(e.to_string()),
+                },
+                Err(e) => drift.error = Some(e.to_string()),
+            }
+            drifts.push(drift);
+        }
+    }
+
+    drifts
+}
+
+/// Fetch the upstream of a remote source into `dir`: `git` if set,
+/// otherwise the `rules_url` document at `url`.
+async fn fetch_upstream(
+    key: &str,
+    url: &str,
+    git: Option<&SpecSource>,
+    dir: &Path,
+) -> Result<RemoteCopy> {
+    match git {
+        Some(source) => {
+            fetch_git(source, dir).await?;
+            let path = source.path.as_deref().unwrap_or(DEFAULT_SOURCE_PATH);
+            Ok(RemoteCopy {
+                source: key.to_string(),
+                root: dir.to_path_buf(),
+                pattern: path.trim_start_matches('/').to_string(),
+                is_git: true,
+            })
+        }
+        None => {
+            fetch(url, dir, None).await?;
+            Ok(RemoteCopy {
+                source: key.to_string(),
+                root: dir.to_path_buf(),
+                pattern: "**/*.md".to_string(),
+                is_git: false,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_refresh_intervals() {
+        assert_eq!(
+            parse_refresh_interval("90").unwrap(),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            parse_refresh_interval("30s").unwrap(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            parse_refresh_interval("15m").unwrap(),
+            Duration::from_secs(15 * 60)
+        );
+        assert_eq!(
+            parse_refresh_interval("6h").unwrap(),
+            Duration::from_secs(6 * 60 * 60)
+        );
+        assert_eq!(
+            parse_refresh_interval("1d").unwrap(),
+            Duration::from_secs(24 * 60 * 60)
+        );
+        assert!(parse_refresh_interval("soon").is_err());
+    }
+
+    #[test]
+    fn git_sources_are_keyed_by_revision() {
+        let pinned = SpecSource {
+            git: "https://github.com/org/spec".to_string(),
+            rev: Some("v1.2.0".to_string()),
+            path: None,
+        };
+        let unpinned = SpecSource {
+            rev: None,
+            ..pinned.clone()
+        };
+        assert_eq!(
+            git_cache_key(&pinned),
+            "git+https://github.com/org/spec@v1.2.0"
+        );
+        assert_eq!(
+            git_cache_key(&unpinned),
+            "git+https://github.com/org/spec@HEAD"
+        );
+    }
+
+    // r[verify config.spec.source.rev]
+    #[test]
+    fn only_commit_hashes_are_pinned() {
+        assert!(is_commit_hash("3f2a9c0d1e4b5a6978c8d7e6f5a4b3c2d1e0f9a8"));
+        assert!(!is_commit_hash("3f2a9c0"));
+        assert!(!is_commit_hash("main"));
+        assert!(!is_commit_hash("v1.2.0"));
+    }
+
+    #[test]
+    fn lockfile_round_trips() {
+        let lock = SpecLock {
+            sources: vec![LockedSource {
+                spec: "proto".to_string(),
+                source: "git+https://github.com/org/spec@v1.2.0".to_string(),
+                resolved: "3f2a9c".to_string(),
+                rules: BTreeMap::from([
+                    ("proto.frame".to_string(), 2),
+                    ("proto.header".to_string(), 1),
+                ]),
+            }],
+        };
+        let rendered = lock.render();
+        assert!(rendered.contains("rule=proto.frame+2\n"));
+        assert!(rendered.contains("rule=proto.header\n"));
+        assert_eq!(SpecLock::parse(&rendered), lock);
+    }
+
+    #[test]
+    fn diff_reports_bumped_added_and_removed_rules() {
+        let locked = BTreeMap::from([
+            ("a".to_string(), 1),
+            ("b".to_string(), 2),
+            ("gone".to_string(), 1),
+        ]);
+        let upstream = BTreeMap::from([
+            ("a".to_string(), 1),
+            ("b".to_string(), 3),
+            ("new".to_string(), 1),
+        ]);
+        let (bumped, added, removed) = diff_rules(&locked, &upstream);
+        assert_eq!(bumped.len(), 1);
+        assert_eq!(bumped[0].id, "b");
+        assert_eq!(bumped[0].locked_version, 2);
+        assert_eq!(bumped[0].upstream_version, 3);
+        assert_eq!(added, vec!["new".to_string()]);
+        assert_eq!(removed, vec!["gone".to_string()]);
+    }
+
+    #[test]
+    fn validators_come_from_final_response() {
+        let headers = "HTTP/1.1 301 Moved Permanently\r\n\
+                       Location: https://example.com/spec.md\r\n\
+                       ETag: \"old\"\r\n\
+                       \r\n\
+                       HTTP/2 200\r\n\
+                       etag: \"abc123\"\r\n\
+                       last-modified: Tue, 13 Oct 2026 10:00:00 GMT\r\n\
+                       \r\n";
+        let (etag, last_modified) = parse_validators(headers);
+        assert_eq!(etag.as_deref(), Some("\"abc123\""));
+        assert_eq!(
+            last_modified.as_deref(),
+            Some("Tue, 13 Oct 2026 10:00:00 GMT")
+        );
+    }
+}
@@ -52,3 +52,106 @@ pub(crate) fn suggest_similar_rule_ids(
 fn common_prefix_len(a: &str, b: &str) -> usize {
     a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count()
 }
+
+/// How well `query`, an ID typed by hand, matches the rule ID `base`, from 4
+/// (same ID, ignoring case and separators: `authlogin` for `auth.login`) over
+/// 3 (a prefix: `auth.tok`) and 2 (a typo or two away: `auth.logn`) to 1 (the
+/// characters in order: `aulogin`). `None` when it doesn't match at all.
+pub(crate) fn fuzzy_match_tier(query: &str, base: &str) -> Option<u8> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| !matches!(c, '.' | '-' | '_'))
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let query_norm = normalize(query);
+    let base_norm = normalize(base);
+
+    if query_norm == base_norm {
+        return Some(4);
+    }
+    if base.to_lowercase().starts_with(&query.to_lowercase()) {
+        return Some(3);
+    }
+    let max_typos = if query_norm.len() > 6 { 2 } else { 1 };
+    if levenshtein(&query_norm, &base_norm) <= max_typos {
+        return Some(2);
+    }
+    let mut remaining = base_norm.chars();
+    if query_norm.chars().all(|c| remaining.any(|b| b == c)) {
+        return Some(1);
+    }
+    None
+}
+
+/// The latest version of each rule `query` matches in its best tier (see
+/// [`fuzzy_match_tier`]), closest first. A single entry means the match is
+/// unambiguous.
+pub(crate) fn fuzzy_rule_matches(
+    query: &str,
+    known_rule_ids: &[RuleId],
+    limit: usize,
+) -> Vec<RuleId> {
+    let mut latest_by_base: HashMap<&str, &RuleId> = HashMap::new();
+    for rule_id in known_rule_ids {
+        let entry = latest_by_base
+            .entry(rule_id.base.as_str())
+            .or_insert(rule_id);
+        if rule_id.version > entry.version {
+            *entry = rule_id;
+        }
+    }
+
+    let scored: Vec<(u8, usize, &RuleId)> = latest_by_base
+        .into_values()
+        .filter_map(|rule_id| {
+            let tier = fuzzy_match_tier(query, &rule_id.base)?;
+            Some((tier, levenshtein(query, &rule_id.base), rule_id))
+        })
+        .collect();
+    let Some(best) = scored.iter().map(|(tier, _, _)| *tier).max() else {
+        return Vec::new();
+    };
+    let mut matches: Vec<(usize, &RuleId)> = scored
+        .into_iter()
+        .filter(|(tier, _, _)| *tier == best)
+        .map(|(_, dist, rule_id)| (dist, rule_id))
+        .collect();
+    matches.sort_by(|(da, a), (db, b)| da.cmp(db).then_with(|| a.base.cmp(&b.base)));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, rule_id)| rule_id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_core::parse_rule_id;
+
+    fn ids(ids: &[&str]) -> Vec<RuleId> {
+        ids.iter().map(|id| parse_rule_id(id).unwrap()).collect()
+    }
+
+    #[test]
+    fn fuzzy_matches_pick_the_best_tier() {
+        let known = ids(&[
+            "auth.login",
+            "auth.login.form+2",
+            "auth.login.form",
+            "auth.logout",
+            "auth.token.validation",
+        ]);
+        let matches = |query| fuzzy_rule_matches(query, &known, 10);
+        assert_eq!(matches("authlogin"), ids(&["auth.login"]));
+        assert_eq!(matches("auth.logn"), ids(&["auth.login"]));
+        assert_eq!(matches("auth.tok"), ids(&["auth.token.validation"]));
+        assert_eq!(
+            matches("auth.log"),
+            ids(&["auth.login", "auth.logout", "auth.login.form+2"])
+        );
+        assert_eq!(matches("atokval"), ids(&["auth.token.validation"]));
+        assert!(matches("billing").is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! File- and module-level annotation scopes.
+//!
+//! r[impl ref.scope]
+//!
+//! In config or schema modules, an annotation on every function is noise:
+//! the whole file serves one requirement family. An inner doc comment such
+//! as `//! r[impl-scope config.*]` says so, and every code unit of the file,
+//! or of the inline module the comment opens, counts as covered in reverse
+//! coverage. A scope is not a reference: it never marks a requirement as
+//! implemented.
+
+use tracey_core::code_units::{CodeUnit, CodeUnitKind};
+
+use crate::spec_deps::prose;
+
+/// Whether `pattern`, a base ID or a family like `config.*`, covers `base`.
+pub fn pattern_matches(pattern: &str, base: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(family) => base
+            .strip_prefix(family)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => pattern == base,
+    }
+}
+
+/// The `impl-scope` patterns in a file's inner doc comments, outside inline
+/// code, with 1-indexed line numbers.
+fn parse_markers(prefix: &str, content: &str) -> Vec<(usize, String)> {
+    let marker = format!("{prefix}[impl-scope ");
+    let mut markers = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let Some(doc) = line.trim_start().strip_prefix("//!") else {
+            continue;
+        };
+        let doc = prose(doc);
+        let mut rest = doc.as_str();
+        while let Some(open) = rest.find(&marker) {
+            let preceded_by_word = rest[..open]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+            rest = &rest[open + marker.len()..];
+            let Some(close) = rest.find(']') else {
+                break;
+            };
+            let pattern = rest[..close].trim();
+            let valid = !pattern.is_empty()
+                && pattern.chars().all(|c| {
+                    c.is_ascii_lowercase()
+                        || c.is_ascii_digit()
+                        || matches!(c, '.' | '-' | '_' | '*')
+                });
+            if valid && !preceded_by_word {
+                markers.push((idx + 1, pattern.to_string()));
+            }
+        }
+    }
+    markers
+}
+
+/// Assign the `impl-scope` markers of one file to the code units they
+/// cover. Patterns that match none of `known_bases` are ignored.
+pub fn apply_scopes(prefix: &str, content: &str, units: &mut [CodeUnit], known_bases: &[&str]) {
+    let markers = parse_markers(prefix, content);
+    if markers.is_empty() {
+        return;
+    }
+    for (line, pattern) in markers {
+        if !known_bases
+            .iter()
+            .any(|base| pattern_matches(&pattern, base))
+        {
+            continue;
+        }
+        // A marker inside an inline module scopes that module only.
+        let (start, end) = units
+            .iter()
+            .filter(|u| u.kind == CodeUnitKind::Module)
+            .filter(|u| u.start_line < line && line <= u.end_line)
+            .min_by_key(|u| u.end_line - u.start_line)
+            .map_or((1, usize::MAX), |m| (m.start_line, m.end_line));
+        for unit in units.iter_mut() {
+            if start <= unit.start_line && unit.end_line <= end && !unit.scopes.contains(&pattern) {
+                unit.scopes.push(pattern.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(kind: CodeUnitKind, lines: (usize, usize)) -> CodeUnit {
+        CodeUnit {
+            kind,
+            name: None,
+            file: "src/config.rs".into(),
+            start_line: lines.0,
+            end_line: lines.1,
+            start_byte: 0,
+            end_byte: 0,
+            req_refs: vec![],
+            scopes: vec![],
+        }
+    }
+
+    #[test]
+    fn family_patterns_match_whole_segments() {
+        assert!(pattern_matches("config.*", "config.impl.name"));
+        assert!(!pattern_matches("config.*", "config"));
+        assert!(!pattern_matches("config.*", "configs.name"));
+        assert!(pattern_matches("config.impl", "config.impl"));
+    }
+
+    // r[verify ref.scope]
+    #[test]
+    fn scopes_cover_the_file_or_module_they_open() {
+        let content = "\
+//! Config loading. r[impl-scope config.*]
+
+fn load() {}
+
+mod schema {
+    //! r[impl-scope schema.*]
+    fn field() {}
+}
+
+mod other {
+    //! Write `r[impl-scope schema.*]` to scope a module. r[impl-scope missing.*]
+    fn helper() {}
+}
+";
+        let mut units = vec![
+            unit(CodeUnitKind::Function, (3, 3)),
+            unit(CodeUnitKind::Module, (5, 8)),
+            unit(CodeUnitKind::Function, (7, 7)),
+            unit(CodeUnitKind::Module, (10, 13)),
+            unit(CodeUnitKind::Function, (12, 12)),
+        ];
+        apply_scopes("r", content, &mut units, &["config.load", "schema.field"]);
+
+        let scopes: Vec<Vec<&str>> = units
+            .iter()
+            .map(|u| u.scopes.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![
+                vec!["config.*"],
+                vec!["config.*", "schema.*"],
+                vec!["config.*", "schema.*"],
+                vec!["config.*"],
+                vec!["config.*"],
+            ]
+        );
+        assert!(units.iter().all(|u| u.is_covered()));
+    }
+}
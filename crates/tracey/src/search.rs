@@ -5,6 +5,13 @@
 //! - Spec rules (rule IDs and text)
 //!
 //! When the `search` feature is disabled, it falls back to simple substring matching.
+//!
+//! r[impl dashboard.search.query-syntax]
+//!
+//! Both backends take the same query syntax: every bare word and `"quoted
+//! phrase"` must match, `OR` separates alternatives, `-` or `NOT` excludes
+//! a term, and `field:value` filters on rule metadata (`level`, `status`,
+//! `tag`, `section`) or on the hit's `kind` and `id`.
 
 use facet::Facet;
 use std::collections::BTreeMap;
@@ -45,8 +52,18 @@ pub struct RuleEntry {
     pub id: String,
     /// Raw markdown source (without r[...] marker)
     pub raw: String,
+    /// RFC 2119 level from the marker's metadata (e.g. `must`)
+    pub level: Option<String>,
+    /// Lifecycle status from the marker's metadata (e.g. `stable`)
+    pub status: Option<String>,
+    pub tags: Vec<String>,
+    /// Slug of the heading the rule is under
+    pub section: Option<String>,
 }
 
+/// Metadata fields a query can filter on, besides `kind` and `id`.
+const METADATA_FIELDS: [&str; 4] = ["level", "status", "tag", "section"];
+
 /// Search index abstraction
 pub trait SearchIndex: Send + Sync {
     /// Search for a query string, returning up to `limit` results
@@ -79,23 +96,110 @@ pub fn empty_index() -> Arc<dyn SearchIndex> {
 mod tantivy_impl {
     use super::*;
     use tantivy::collector::TopDocs;
-    use tantivy::query::QueryParser;
+    use tantivy::query::{BooleanQuery, BoostQuery, Occur, Query, QueryParser};
     use tantivy::schema::{
         Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TextFieldIndexing, TextOptions,
         Value,
     };
     use tantivy::snippet::SnippetGenerator;
-    use tantivy::{Index, IndexWriter, ReloadPolicy, doc};
+    use tantivy::tokenizer::{LowerCaser, RawTokenizer, TextAnalyzer};
+    use tantivy::{Index, IndexWriter, ReloadPolicy, TantivyDocument, doc};
+
+    /// Tokenizer for metadata fields: the whole value, lowercased
+    const METADATA_TOKENIZER: &str = "lowercase_raw";
+
+    /// Weight of fuzzy matches relative to exact ones
+    const FUZZY_BOOST: f32 = 0.2;
+
+    /// Bump when the schema or what gets indexed changes, so persisted
+    /// indexes built by older versions are rebuilt.
+    const INDEX_FORMAT_VERSION: u32 = 1;
+
+    /// Marker written into a persisted index directory once it is complete
+    const READY_FILE: &str = "ready";
+
+    /// Serializes builds of persisted indexes, which the reindex worker and a
+    /// first search can start at the same time.
+    static PERSIST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
     pub struct TantivyIndex {
         #[allow(dead_code)]
         index: Index,
         reader: tantivy::IndexReader,
         query_parser: QueryParser,
+        /// Same fields as `query_parser`, with every term matched within one edit
+        fuzzy_parser: QueryParser,
         schema: Schema,
         content_field: Field,
     }
 
+    fn schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+
+        // Text options with stemming and positions (needed for snippet generation)
+        let text_options = TextOptions::default().set_stored().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("en_stem")
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+
+        // Text options for rule IDs - use default tokenizer for split parts
+        let rule_id_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer("default")
+                .set_index_option(IndexRecordOption::WithFreqs),
+        );
+
+        // "kind" field: "source" or "rule"
+        schema_builder.add_text_field("kind", STRING | STORED);
+        // "id" field: file path for source, rule ID for rules
+        schema_builder.add_text_field("id", STRING | STORED);
+        // "line" field: line number for source (0 for rules)
+        schema_builder.add_u64_field("line", INDEXED | STORED);
+        // "content" field: the searchable text content with stemming
+        schema_builder.add_text_field("content", text_options);
+        // "rule_id" field: searchable rule ID with dot-separated parts (not stored)
+        schema_builder.add_text_field("rule_id", rule_id_options);
+        // Rule metadata fields, matched case-insensitively as a whole (not stored)
+        let metadata_options = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(METADATA_TOKENIZER)
+                .set_index_option(IndexRecordOption::Basic),
+        );
+        for name in METADATA_FIELDS {
+            schema_builder.add_text_field(name, metadata_options.clone());
+        }
+        schema_builder.build()
+    }
+
+    /// Hash of everything that goes into an index, naming its directory.
+    fn fingerprint(
+        project_root: &Path,
+        files: &BTreeMap<PathBuf, String>,
+        rules: &[RuleEntry],
+    ) -> String {
+        let mut hasher = blake3::Hasher::new();
+        let mut put = |bytes: &[u8]| {
+            hasher.update(&(bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        put(&INDEX_FORMAT_VERSION.to_le_bytes());
+        put(project_root.as_os_str().as_encoded_bytes());
+        for (path, content) in files {
+            put(path.as_os_str().as_encoded_bytes());
+            put(content.as_bytes());
+        }
+        for rule in rules {
+            put(rule.id.as_bytes());
+            put(rule.raw.as_bytes());
+            for field in [&rule.level, &rule.status, &rule.section] {
+                put(field.as_deref().unwrap_or_default().as_bytes());
+            }
+            put(rule.tags.join(",").as_bytes());
+        }
+        hasher.finalize().to_hex()[..16].to_string()
+    }
+
     impl TantivyIndex {
         /// Build a new tantivy index from source files and rules
         pub fn build(
@@ -103,37 +207,76 @@ mod tantivy_impl {
             files: &BTreeMap<PathBuf, String>,
             rules: &[RuleEntry],
         ) -> eyre::Result<Self> {
-            // Define schema
-            let mut schema_builder = Schema::builder();
-
-            // Text options with stemming and positions (needed for snippet generation)
-            let text_options = TextOptions::default().set_stored().set_indexing_options(
-                TextFieldIndexing::default()
-                    .set_tokenizer("en_stem")
-                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
-            );
+            // Create index in RAM (small enough for most projects)
+            let index = Index::create_in_ram(schema());
+            Self::fill(&index, project_root, files, rules)?;
+            Self::from_index(index)
+        }
 
-            // Text options for rule IDs - use default tokenizer for split parts
-            let rule_id_options = TextOptions::default().set_indexing_options(
-                TextFieldIndexing::default()
-                    .set_tokenizer("default")
-                    .set_index_option(IndexRecordOption::WithFreqs),
-            );
+        /// Open the index for these files and rules under `dir`, building it
+        /// there first if it doesn't exist yet. Indexes for other contents
+        /// are removed, so `dir` only ever holds the latest one.
+        pub fn open_or_build(
+            dir: &Path,
+            project_root: &Path,
+            files: &BTreeMap<PathBuf, String>,
+            rules: &[RuleEntry],
+        ) -> eyre::Result<Self> {
+            let _guard = PERSIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let name = fingerprint(project_root, files, rules);
+            let path = dir.join(&name);
+
+            if path.join(READY_FILE).exists() {
+                match Index::open_in_dir(&path) {
+                    Ok(index) => return Self::from_index(index),
+                    Err(e) => tracing::warn!("Rebuilding unreadable search index: {}", e),
+                }
+            }
 
-            // "kind" field: "source" or "rule"
-            let kind_field = schema_builder.add_text_field("kind", STRING | STORED);
-            // "id" field: file path for source, rule ID for rules
-            let id_field = schema_builder.add_text_field("id", STRING | STORED);
-            // "line" field: line number for source (0 for rules)
-            let line_field = schema_builder.add_u64_field("line", INDEXED | STORED);
-            // "content" field: the searchable text content with stemming
-            let content_field = schema_builder.add_text_field("content", text_options);
-            // "rule_id" field: searchable rule ID with dot-separated parts (not stored)
-            let rule_id_field = schema_builder.add_text_field("rule_id", rule_id_options);
-            let schema = schema_builder.build();
+            if path.exists() {
+                std::fs::remove_dir_all(&path)?;
+            }
+            std::fs::create_dir_all(&path)?;
+            let index = Index::create_in_dir(&path, schema())?;
+            Self::fill(&index, project_root, files, rules)?;
+            std::fs::write(path.join(READY_FILE), b"")?;
+
+            // Best effort: an older index may still be open elsewhere
+            for entry in std::fs::read_dir(dir)?.flatten() {
+                if entry.file_name() != name.as_str() {
+                    let _ = std::fs::remove_dir_all(entry.path());
+                }
+            }
 
-            // Create index in RAM (small enough for most projects)
-            let index = Index::create_in_ram(schema.clone());
+            Self::from_index(index)
+        }
+
+        fn register_tokenizers(index: &Index) {
+            index.tokenizers().register(
+                METADATA_TOKENIZER,
+                TextAnalyzer::builder(RawTokenizer::default())
+                    .filter(LowerCaser)
+                    .build(),
+            );
+        }
+
+        /// Add every source line and rule to an empty index.
+        fn fill(
+            index: &Index,
+            project_root: &Path,
+            files: &BTreeMap<PathBuf, String>,
+            rules: &[RuleEntry],
+        ) -> eyre::Result<()> {
+            Self::register_tokenizers(index);
+            let schema = index.schema();
+            let field = |name: &str| schema.get_field(name);
+            let kind_field = field("kind")?;
+            let id_field = field("id")?;
+            let line_field = field("line")?;
+            let content_field = field("content")?;
+            let rule_id_field = field("rule_id")?;
+            let [level_field, status_field, tag_field, section_field] =
+                METADATA_FIELDS.map(|name| field(name).expect("metadata fields are in the schema"));
 
             // Use single thread to avoid worker thread panics on some platforms
             let mut index_writer: IndexWriter = index.writer_with_num_threads(1, 15_000_000)?;
@@ -177,16 +320,38 @@ mod tantivy_impl {
             // Index rules - use raw markdown directly (rule_id_field handles ID search)
             // r[impl dashboard.search.render-requirements]
             for rule in rules {
-                index_writer.add_document(doc!(
+                let mut document: TantivyDocument = doc!(
                     kind_field => "rule",
                     id_field => rule.id.clone(),
                     line_field => 0u64,
                     content_field => rule.raw.clone(),
                     rule_id_field => rule.id.clone(),
-                ))?;
+                );
+                let single = [
+                    (level_field, &rule.level),
+                    (status_field, &rule.status),
+                    (section_field, &rule.section),
+                ];
+                for (field, value) in single {
+                    if let Some(value) = value {
+                        document.add_text(field, value);
+                    }
+                }
+                for tag in &rule.tags {
+                    document.add_text(tag_field, tag);
+                }
+                index_writer.add_document(document)?;
             }
 
             index_writer.commit()?;
+            Ok(())
+        }
+
+        fn from_index(index: Index) -> eyre::Result<Self> {
+            Self::register_tokenizers(&index);
+            let schema = index.schema();
+            let content_field = schema.get_field("content")?;
+            let rule_id_field = schema.get_field("rule_id")?;
 
             let reader = index
                 .reader_builder()
@@ -197,11 +362,20 @@ mod tantivy_impl {
             let mut query_parser =
                 QueryParser::for_index(&index, vec![content_field, rule_id_field]);
             query_parser.set_field_boost(rule_id_field, 5.0); // Boost rule ID matches
+            // Every term must match unless the query says OR
+            query_parser.set_conjunction_by_default();
+
+            // r[impl dashboard.search.fuzzy]
+            let mut fuzzy_parser = query_parser.clone();
+            for field in [content_field, rule_id_field] {
+                fuzzy_parser.set_field_fuzzy(field, false, 1, true);
+            }
 
             Ok(Self {
                 index,
                 reader,
                 query_parser,
+                fuzzy_parser,
                 schema,
                 content_field,
             })
@@ -224,7 +398,18 @@ mod tantivy_impl {
                 }
             };
 
-            let top_docs = match searcher.search(&parsed_query, &TopDocs::with_limit(limit)) {
+            // Near misses match too, but score below exact matches: every
+            // document the exact query finds also matches the fuzzy one, and
+            // the fuzzy query is scored low and flat.
+            let ranked_query: Box<dyn Query> = match self.fuzzy_parser.parse_query(query) {
+                Ok(fuzzy) => Box::new(BooleanQuery::new(vec![
+                    (Occur::Should, parsed_query.box_clone()),
+                    (Occur::Should, Box::new(BoostQuery::new(fuzzy, FUZZY_BOOST))),
+                ])),
+                Err(_) => parsed_query.box_clone(),
+            };
+
+            let top_docs = match searcher.search(&ranked_query, &TopDocs::with_limit(limit)) {
                 Ok(docs) => docs,
                 Err(_) => return vec![],
             };
@@ -351,6 +536,146 @@ struct SimpleEntry {
     id: String,
     line: usize,
     content: String,
+    level: Option<String>,
+    status: Option<String>,
+    tags: Vec<String>,
+    section: Option<String>,
+}
+
+impl SimpleEntry {
+    fn matches(&self, term: &Term) -> bool {
+        let value = term.value.to_lowercase();
+        let eq = |field: &Option<String>| {
+            field
+                .as_deref()
+                .is_some_and(|f| f.eq_ignore_ascii_case(&term.value))
+        };
+        match term.field.as_deref() {
+            None => {
+                self.content.to_lowercase().contains(&value)
+                    || self.id.to_lowercase().contains(&value)
+            }
+            Some("kind") => match self.kind {
+                ResultKind::Rule => value == "rule",
+                ResultKind::Source => value == "source",
+            },
+            Some("id") => self.id == term.value,
+            Some("level") => eq(&self.level),
+            Some("status") => eq(&self.status),
+            Some("section") => eq(&self.section),
+            Some("tag") => self
+                .tags
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&term.value)),
+            Some(_) => false,
+        }
+    }
+}
+
+/// One word, phrase or `field:value` of a query.
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    field: Option<String>,
+    value: String,
+    negated: bool,
+}
+
+/// A parsed query: alternatives separated by `OR`, each a list of terms
+/// that must all hold.
+#[derive(Debug, Clone, PartialEq)]
+struct Query {
+    alternatives: Vec<Vec<Term>>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        let mut alternatives = vec![Vec::new()];
+        let mut negate_next = false;
+        let mut chars = query.chars().peekable();
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let Some(&first) = chars.peek() else {
+                break;
+            };
+
+            let mut negated = std::mem::take(&mut negate_next);
+            if first == '-' {
+                chars.next();
+                negated = true;
+            }
+            let mut word = String::new();
+            let mut quoted = false;
+            while let Some(&c) = chars.peek() {
+                if c == '"' {
+                    chars.next();
+                    quoted = true;
+                    // A phrase runs to the closing quote, or the end of the query
+                    word.extend(chars.by_ref().take_while(|&c| c != '"'));
+                } else if c.is_whitespace() {
+                    break;
+                } else {
+                    word.push(c);
+                    chars.next();
+                }
+            }
+
+            if !quoted {
+                match word.as_str() {
+                    "AND" => continue,
+                    "OR" => {
+                        alternatives.push(Vec::new());
+                        continue;
+                    }
+                    "NOT" => {
+                        negate_next = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            let term = match word.split_once(':') {
+                Some((field, value))
+                    if !value.is_empty()
+                        && (field == "kind"
+                            || field == "id"
+                            || METADATA_FIELDS.contains(&field)) =>
+                {
+                    Term {
+                        field: Some(field.to_string()),
+                        value: value.trim_matches('"').to_string(),
+                        negated,
+                    }
+                }
+                _ => Term {
+                    field: None,
+                    value: word.replace('"', ""),
+                    negated,
+                },
+            };
+            if !term.value.is_empty() {
+                alternatives.last_mut().expect("never empty").push(term);
+            }
+        }
+        alternatives.retain(|terms| !terms.is_empty());
+        Self { alternatives }
+    }
+
+    fn matches(&self, entry: &SimpleEntry) -> bool {
+        self.alternatives.iter().any(|terms| {
+            // A query of exclusions alone would match nearly everything
+            terms.iter().any(|t| !t.negated) && terms.iter().all(|t| entry.matches(t) != t.negated)
+        })
+    }
+
+    /// The words and phrases to highlight in matching content.
+    fn highlight_terms(&self) -> Vec<&str> {
+        self.alternatives
+            .iter()
+            .flatten()
+            .filter(|t| t.field.is_none() && !t.negated)
+            .map(|t| t.value.as_str())
+            .collect()
+    }
 }
 
 /// Simple substring search fallback when tantivy is not available
@@ -392,6 +717,10 @@ impl SimpleIndex {
                         id: relative.clone(),
                         line: line_num,
                         content: content_with_context,
+                        level: None,
+                        status: None,
+                        tags: Vec::new(),
+                        section: None,
                     });
                 }
             }
@@ -404,6 +733,10 @@ impl SimpleIndex {
                 id: rule.id.clone(),
                 line: 0,
                 content: rule.raw.clone(),
+                level: rule.level.clone(),
+                status: rule.status.clone(),
+                tags: rule.tags.clone(),
+                section: rule.section.clone(),
             });
         }
 
@@ -413,20 +746,18 @@ impl SimpleIndex {
 
 impl SearchIndex for SimpleIndex {
     fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
-        let query_lower = query.to_lowercase();
+        let query = Query::parse(query);
+        let terms = query.highlight_terms();
 
         let mut results: Vec<SearchResult> = self
             .entries
             .iter()
-            // Match against both content and id (for rule ID searches)
-            .filter(|e| {
-                e.content.to_lowercase().contains(&query_lower)
-                    || e.id.to_lowercase().contains(&query_lower)
-            })
+            // Words match against both content and id (for rule ID searches)
+            .filter(|e| query.matches(e))
             .take(limit)
             .map(|e| {
                 // Simple case-insensitive highlighting
-                let highlighted = highlight_simple(&e.content, query);
+                let highlighted = highlight_simple(&e.content, &terms);
                 SearchResult {
                     kind: e.kind,
                     id: e.id.clone(),
@@ -455,21 +786,36 @@ impl SearchIndex for SimpleIndex {
 }
 
 /// Simple case-insensitive highlighting for fallback
-fn highlight_simple(content: &str, query: &str) -> String {
-    let content_lower = content.to_lowercase();
-    let query_lower = query.to_lowercase();
+fn highlight_simple(content: &str, terms: &[&str]) -> String {
+    // ASCII lowercasing keeps byte offsets valid in `content`
+    let content_lower = content.to_ascii_lowercase();
+    let mut ranges: Vec<(usize, usize)> = terms
+        .iter()
+        .flat_map(|term| {
+            let term_lower = term.to_ascii_lowercase();
+            content_lower
+                .match_indices(&term_lower)
+                .map(|(start, m)| (start, start + m.len()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ranges.sort();
 
     let mut result = String::new();
     let mut last_end = 0;
 
-    for (start, _) in content_lower.match_indices(&query_lower) {
+    for (start, end) in ranges {
+        // Skip matches overlapping one already highlighted
+        if start < last_end {
+            continue;
+        }
         // Append text before match (escaped)
         result.push_str(&html_escape(&content[last_end..start]));
         // Append highlighted match
         result.push_str("<mark>");
-        result.push_str(&html_escape(&content[start..start + query.len()]));
+        result.push_str(&html_escape(&content[start..end]));
         result.push_str("</mark>");
-        last_end = start + query.len();
+        last_end = end;
     }
 
     // Append remaining text
@@ -477,14 +823,37 @@ fn highlight_simple(content: &str, query: &str) -> String {
     result
 }
 
+/// Directory persisted search indexes live in, under the project's state dir
+pub fn index_dir(project_root: &Path) -> PathBuf {
+    crate::daemon::state_dir(project_root).join("search")
+}
+
 /// Build the appropriate search index based on feature flags
+///
+/// r[impl daemon.search.persisted-index]
+///
+/// With tantivy, the index is kept in [`index_dir`] and reopened as long as
+/// the indexed files and rules are unchanged, so a restarted daemon doesn't
+/// reindex the whole project.
 #[cfg(feature = "search")]
 pub fn build_index(
     project_root: &Path,
     files: &BTreeMap<PathBuf, String>,
     rules: &[RuleEntry],
 ) -> Box<dyn SearchIndex> {
-    match TantivyIndex::build(project_root, files, rules) {
+    let dir = index_dir(project_root);
+    let persisted = std::fs::create_dir_all(&dir)
+        .map_err(eyre::Report::from)
+        .and_then(|()| TantivyIndex::open_or_build(&dir, project_root, files, rules));
+    let built = persisted.or_else(|e| {
+        eprintln!(
+            "Warning: Failed to persist tantivy index in {}, keeping it in memory: {}",
+            dir.display(),
+            e
+        );
+        TantivyIndex::build(project_root, files, rules)
+    });
+    match built {
         Ok(index) => Box::new(index),
         Err(e) => {
             eprintln!(
@@ -504,3 +873,172 @@ pub fn build_index(
 ) -> Box<dyn SearchIndex> {
     Box::new(SimpleIndex::build(project_root, files, rules))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, raw: &str, level: &str, tags: &[&str]) -> RuleEntry {
+        RuleEntry {
+            id: id.to_string(),
+            raw: raw.to_string(),
+            level: Some(level.to_string()),
+            status: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            section: Some("sessions".to_string()),
+        }
+    }
+
+    fn fixture() -> (BTreeMap<PathBuf, String>, Vec<RuleEntry>) {
+        let files = BTreeMap::from([(
+            Path::new("/project/src/session.rs").to_path_buf(),
+            "// Close the session timeout window\n".to_string(),
+        )]);
+        let rules = vec![
+            rule(
+                "auth.session.timeout",
+                "Sessions MUST end after a session timeout of 30 minutes.",
+                "must",
+                &["security"],
+            ),
+            rule(
+                "auth.session.renew",
+                "Clients SHOULD renew the session before the timeout.",
+                "should",
+                &["security"],
+            ),
+            rule(
+                "ui.session.banner",
+                "The session timeout MUST be shown in a banner.",
+                "must",
+                &[],
+            ),
+        ];
+        (files, rules)
+    }
+
+    fn index() -> SimpleIndex {
+        let (files, rules) = fixture();
+        SimpleIndex::build(Path::new("/project"), &files, &rules)
+    }
+
+    fn ids(query: &str) -> Vec<String> {
+        index()
+            .search(query, 20)
+            .into_iter()
+            .map(|r| r.id)
+            .collect()
+    }
+
+    #[test]
+    fn parses_fields_phrases_and_operators() {
+        let query = Query::parse(r#"level:MUST -tag:ui "session timeout" OR NOT banner id:"a.b""#);
+        let term = |field: Option<&str>, value: &str, negated| Term {
+            field: field.map(str::to_string),
+            value: value.to_string(),
+            negated,
+        };
+        assert_eq!(
+            query.alternatives,
+            [
+                vec![
+                    term(Some("level"), "MUST", false),
+                    term(Some("tag"), "ui", true),
+                    term(None, "session timeout", false),
+                ],
+                vec![term(None, "banner", true), term(Some("id"), "a.b", false)],
+            ]
+        );
+        // Unknown fields are plain words, so `http://` still searches as text
+        assert_eq!(
+            Query::parse("http://x").alternatives,
+            [vec![term(None, "http://x", false)]]
+        );
+    }
+
+    #[test]
+    fn filters_on_metadata_and_phrases() {
+        assert_eq!(
+            ids(r#"level:MUST tag:security "session timeout""#),
+            ["auth.session.timeout"]
+        );
+        assert_eq!(
+            ids(r#""session timeout""#),
+            [
+                "auth.session.timeout",
+                "ui.session.banner",
+                "src/session.rs"
+            ]
+        );
+        assert_eq!(ids("level:must -tag:security"), ["ui.session.banner"]);
+        assert_eq!(
+            ids("renew OR banner"),
+            ["auth.session.renew", "ui.session.banner"]
+        );
+        assert_eq!(ids("kind:source timeout"), ["src/session.rs"]);
+        assert_eq!(ids("-timeout"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn highlights_every_positive_word() {
+        let results = index().search("level:must sessions \"30 minutes\" -banner", 20);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].highlighted,
+            "<mark>Sessions</mark> MUST end after a session timeout of <mark>30 minutes</mark>."
+        );
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn tantivy_ranks_near_misses_below_exact_matches() {
+        let (files, rules) = fixture();
+        let index = TantivyIndex::build(Path::new("/project"), &files, &rules).unwrap();
+        let exact = index.search("renew", 20);
+        assert_eq!(exact[0].id, "auth.session.renew");
+        let fuzzy = index.search("renw", 20);
+        assert_eq!(fuzzy[0].id, "auth.session.renew");
+        assert!(fuzzy[0].score < exact[0].score);
+        let filtered = index.search(r#"level:MUST tag:security "session timeout""#, 20);
+        let hits: Vec<_> = filtered.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(hits, ["auth.session.timeout"]);
+    }
+
+    #[cfg(feature = "search")]
+    #[test]
+    fn tantivy_index_persists_until_contents_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Path::new("/project");
+        let (mut files, rules) = fixture();
+        let subdirs = || {
+            let mut names: Vec<_> = std::fs::read_dir(dir.path())
+                .unwrap()
+                .map(|e| e.unwrap().file_name())
+                .collect();
+            names.sort();
+            names
+        };
+
+        TantivyIndex::open_or_build(dir.path(), root, &files, &rules).unwrap();
+        let first = subdirs();
+        assert_eq!(first.len(), 1);
+        let reopened = TantivyIndex::open_or_build(dir.path(), root, &files, &rules).unwrap();
+        assert_eq!(subdirs(), first);
+        assert_eq!(reopened.search("banner", 20)[0].id, "ui.session.banner");
+
+        files.insert(
+            root.join("src/banner.rs"),
+            "fn show_banner() {}\n".to_string(),
+        );
+        let rebuilt = TantivyIndex::open_or_build(dir.path(), root, &files, &rules).unwrap();
+        let second = subdirs();
+        assert_eq!(second.len(), 1);
+        assert_ne!(second, first);
+        assert!(
+            rebuilt
+                .search("show", 20)
+                .iter()
+                .any(|r| r.id == "src/banner.rs")
+        );
+    }
+}
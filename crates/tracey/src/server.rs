@@ -8,10 +8,15 @@
 //! The actual data building happens in `serve.rs`. This module wraps that
 //! data and provides query methods + formatting.
 
-use std::collections::BTreeMap;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
 use tracey_core::RuleId;
+use tracey_proto::RuleListSort;
 
-use crate::data::{ApiCodeRef, ApiFileEntry, ApiRule, DashboardData, ImplKey};
+use crate::data::{
+    ApiApproval, ApiCodeRef, ApiFileEntry, ApiFileReference, ApiLineCoverage, ApiRule,
+    ApiSpecDependency, ApiTestResult, DashboardData, ImplKey,
+};
 
 // ============================================================================
 // Delta Tracking
@@ -45,7 +50,8 @@ pub struct CoverageStats {
 }
 
 impl CoverageStats {
-    pub fn from_rules(rules: &[ApiRule]) -> Self {
+    pub fn from_rules<R: Borrow<ApiRule>>(rules: &[R]) -> Self {
+        let rules: Vec<&ApiRule> = rules.iter().map(Borrow::borrow).collect();
         let total = rules.len();
         // A rule is stale if is_stale is set; stale rules are NOT counted as impl_covered.
         let stale_covered = rules.iter().filter(|r| r.is_stale).count();
@@ -79,6 +85,23 @@ impl CoverageStats {
     }
 }
 
+impl CoverageStats {
+    /// Coverage of the rules defined in each spec file, sorted by path.
+    /// Rules without a known source file are left out.
+    pub fn by_file(rules: &[ApiRule]) -> Vec<(String, CoverageStats)> {
+        let mut files: BTreeMap<&str, Vec<&ApiRule>> = BTreeMap::new();
+        for rule in rules {
+            if let Some(file) = &rule.source_file {
+                files.entry(file.as_str()).or_default().push(rule);
+            }
+        }
+        files
+            .into_iter()
+            .map(|(file, rules)| (file.to_string(), CoverageStats::from_rules(&rules)))
+            .collect()
+    }
+}
+
 /// Delta for a single spec/impl pair
 #[derive(Debug, Clone, Default)]
 pub struct ImplDelta {
@@ -238,14 +261,73 @@ impl<'a> QueryEngine<'a> {
             .collect()
     }
 
+    /// Coverage per spec file of a spec/impl
+    pub fn status_by_file(&self, spec: &str, impl_name: &str) -> Vec<(String, CoverageStats)> {
+        let key: ImplKey = (spec.to_string(), impl_name.to_string());
+        self.data
+            .forward_by_impl
+            .get(&key)
+            .map(|forward| CoverageStats::by_file(&forward.rules))
+            .unwrap_or_default()
+    }
+
+    /// Which impls cover each rule, for the specs with more than one impl.
+    /// Specs come in config order and their rules in spec order.
+    // r[impl daemon.status.by-rule]
+    pub fn status_by_rule(&self) -> Vec<RuleImplsCoverage> {
+        let mut out = Vec::new();
+        for spec in &self.data.config.specs {
+            let forwards: Vec<(&String, HashMap<&RuleId, &ApiRule>)> = spec
+                .implementations
+                .iter()
+                .filter_map(|impl_name| {
+                    let key: ImplKey = (spec.name.clone(), impl_name.clone());
+                    let forward = self.data.forward_by_impl.get(&key)?;
+                    Some((
+                        impl_name,
+                        forward.rules.iter().map(|r| (&r.id, r)).collect(),
+                    ))
+                })
+                .collect();
+            if forwards.len() < 2 {
+                continue;
+            }
+            let first = &self.data.forward_by_impl[&(spec.name.clone(), forwards[0].0.clone())];
+
+            for rule in &first.rules {
+                let mut coverage = RuleImplsCoverage {
+                    spec: spec.name.clone(),
+                    id: rule.id.clone(),
+                    covered_in: Vec::new(),
+                    missing_in: Vec::new(),
+                };
+                for (impl_name, rules) in &forwards {
+                    let covered = rules
+                        .get(&rule.id)
+                        .is_some_and(|r| !r.is_stale && !r.impl_refs.is_empty());
+                    if covered {
+                        coverage.covered_in.push((*impl_name).clone());
+                    } else {
+                        coverage.missing_in.push((*impl_name).clone());
+                    }
+                }
+                out.push(coverage);
+            }
+        }
+        out
+    }
+
     /// Get uncovered rules (no impl refs) for a spec/impl
     // r[impl mcp.discovery.pagination] - Prefix filtering provides pagination
     // r[impl validation.orphaned]
+    // r[impl daemon.cli.query.owner]
     pub fn uncovered(
         &self,
         spec: &str,
         impl_name: &str,
         prefix_filter: Option<&str>,
+        owner_filter: Option<&str>,
+        window: ListWindow,
     ) -> Option<UncoveredResult> {
         let key: ImplKey = (spec.to_string(), impl_name.to_string());
         let forward = self.data.forward_by_impl.get(&key)?;
@@ -262,9 +344,11 @@ impl<'a> QueryEngine<'a> {
                     .map(|p| r.id.base.to_lowercase().starts_with(&p.to_lowercase()))
                     .unwrap_or(true)
             })
+            .filter(|r| owner_filter.is_none_or(|o| crate::owners::is_owned_by(&r.owners, o)))
             .collect();
 
-        // Build section mapping from outline
+        let total_uncovered = uncovered_rules.len();
+        let uncovered_rules = window.apply_to_rules(uncovered_rules);
         let by_section = group_rules_by_section(&uncovered_rules);
 
         Some(UncoveredResult {
@@ -272,44 +356,81 @@ impl<'a> QueryEngine<'a> {
             impl_name: impl_name.to_string(),
             stats,
             by_section,
-            total_uncovered: uncovered_rules.len(),
+            total_uncovered,
             prefix_filter: prefix_filter.map(|s| s.to_string()),
         })
     }
 
-    /// Get untested rules (have impl but no verify refs) for a spec/impl
+    /// Get untested rules (no verify refs) for a spec/impl, split into
+    /// implemented and not-implemented buckets, plus verified rules whose
+    /// tests didn't pass the last ingested test report
     // r[impl mcp.discovery.pagination] - Prefix filtering provides pagination
+    // r[impl daemon.cli.query.untested-buckets]
     pub fn untested(
         &self,
         spec: &str,
         impl_name: &str,
         prefix_filter: Option<&str>,
+        only_implemented: bool,
+        window: ListWindow,
     ) -> Option<UntestedResult> {
         let key: ImplKey = (spec.to_string(), impl_name.to_string());
         let forward = self.data.forward_by_impl.get(&key)?;
 
         let stats = CoverageStats::from_rules(&forward.rules);
 
-        // Filter untested rules, optionally by ID prefix (case-insensitive)
-        let untested_rules: Vec<&ApiRule> = forward
+        // Filter rules, optionally by ID prefix (case-insensitive)
+        let matching = forward
             .rules
             .iter()
-            .filter(|r| !r.impl_refs.is_empty() && r.verify_refs.is_empty())
+            .filter(|r| !only_implemented || !r.impl_refs.is_empty())
             .filter(|r| {
                 prefix_filter
                     .map(|p| r.id.base.to_lowercase().starts_with(&p.to_lowercase()))
                     .unwrap_or(true)
-            })
+            });
+        let mut unproven: Vec<&ApiRule> = matching
+            .clone()
+            .filter(|r| !r.verify_refs.is_empty())
+            .filter(|r| crate::test_report::lacks_passing_test(r))
             .collect();
-
-        let by_section = group_rules_by_section(&untested_rules);
+        let (mut implemented, mut unimplemented): (Vec<&ApiRule>, Vec<&ApiRule>) = matching
+            .filter(|r| r.verify_refs.is_empty())
+            .partition(|r| !r.impl_refs.is_empty());
+
+        let total_implemented = implemented.len();
+        let total_untested = total_implemented + unimplemented.len();
+        let total_unproven = unproven.len();
+        window.sort_rules(&mut implemented);
+        window.sort_rules(&mut unimplemented);
+        window.sort_rules(&mut unproven);
+
+        // Page through the buckets as one list: implemented rules first, then
+        // unimplemented ones, then verified ones without a passing test
+        let tagged = implemented
+            .into_iter()
+            .map(|r| (0, r))
+            .chain(unimplemented.into_iter().map(|r| (1, r)))
+            .chain(unproven.into_iter().map(|r| (2, r)))
+            .collect();
+        let page: Vec<(u8, &ApiRule)> = window.page(tagged).collect();
+        let bucket = |tag: u8| -> Vec<&ApiRule> {
+            page.iter()
+                .filter(|(t, _)| *t == tag)
+                .map(|(_, r)| *r)
+                .collect()
+        };
 
         Some(UntestedResult {
             spec: spec.to_string(),
             impl_name: impl_name.to_string(),
             stats,
-            by_section,
-            total_untested: untested_rules.len(),
+            by_section: group_rules_by_section(&bucket(0)),
+            unimplemented_by_section: group_rules_by_section(&bucket(1)),
+            unproven_by_section: group_rules_by_section(&bucket(2)),
+            total_untested,
+            total_implemented,
+            total_unproven,
             prefix_filter: prefix_filter.map(|s| s.to_string()),
         })
     }
@@ -320,13 +441,14 @@ impl<'a> QueryEngine<'a> {
         spec: &str,
         impl_name: &str,
         prefix_filter: Option<&str>,
+        window: ListWindow,
     ) -> Option<StaleResult> {
         let key: ImplKey = (spec.to_string(), impl_name.to_string());
         let forward = self.data.forward_by_impl.get(&key)?;
 
         let stats = CoverageStats::from_rules(&forward.rules);
 
-        let mut entries: Vec<StaleEntryResult> = Vec::new();
+        let mut entries: Vec<(&ApiRule, StaleEntryResult)> = Vec::new();
 
         for rule in &forward.rules {
             if rule.stale_refs.is_empty() {
@@ -338,26 +460,105 @@ impl<'a> QueryEngine<'a> {
                 continue;
             }
             for sr in &rule.stale_refs {
-                entries.push(StaleEntryResult {
-                    current_id: rule.id.clone(),
-                    file: sr.file.clone(),
-                    line: sr.line,
-                    reference_id: sr.reference_id.clone(),
-                });
+                entries.push((
+                    rule,
+                    StaleEntryResult {
+                        current_id: rule.id.clone(),
+                        file: sr.file.clone(),
+                        line: sr.line,
+                        reference_id: sr.reference_id.clone(),
+                    },
+                ));
             }
         }
 
-        // Sort by file, then line
-        entries.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        // Sort by file, then line, unless another order was asked for
+        entries.sort_by(|(_, a), (_, b)| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        match window.sort {
+            None => {}
+            Some(RuleListSort::Id) => entries.sort_by(|(a, _), (b, _)| a.id.cmp(&b.id)),
+            Some(RuleListSort::Section) => {
+                entries.sort_by(|(a, _), (b, _)| section_title(a).cmp(section_title(b)))
+            }
+            Some(RuleListSort::Refs) => {
+                entries.sort_by_key(|(rule, _)| std::cmp::Reverse(rule.stale_refs.len()))
+            }
+        }
+        let total_stale = entries.len();
 
         Some(StaleResult {
             spec: spec.to_string(),
             impl_name: impl_name.to_string(),
             stats,
-            entries,
+            entries: window.page(entries).map(|(_, entry)| entry).collect(),
+            total_stale,
         })
     }
 
+    /// The references to the spec's rules in the source file at `path`
+    /// (absolute), each with whether it names the rule's current version.
+    ///
+    /// r[impl dashboard.api.file.references]
+    pub fn file_references(
+        &self,
+        spec: &str,
+        impl_name: &str,
+        path: &std::path::Path,
+    ) -> Vec<ApiFileReference> {
+        let key: ImplKey = (spec.to_string(), impl_name.to_string());
+        let (Some(forward), Some(spec_info)) = (
+            self.data.forward_by_impl.get(&key),
+            self.data.config.specs.iter().find(|s| s.name == spec),
+        ) else {
+            return Vec::new();
+        };
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let Some(reqs) = self
+            .data
+            .source_reqs_by_file
+            .get(&canonical)
+            .or_else(|| self.data.source_reqs_by_file.get(path))
+        else {
+            return Vec::new();
+        };
+
+        let renames = crate::data::rename_targets(&forward.rules);
+        reqs.references
+            .iter()
+            .filter(|r| r.prefix == spec_info.prefix)
+            .map(|reference| {
+                let rule = forward
+                    .rules
+                    .iter()
+                    .find(|rule| rule.id.base == reference.req_id.base);
+                let (status, current) = match rule {
+                    Some(rule) => {
+                        match tracey_core::classify_reference_for_rule(&rule.id, &reference.req_id)
+                        {
+                            tracey_core::RuleIdMatch::Exact => ("ok", None),
+                            tracey_core::RuleIdMatch::Stale => ("stale", Some(rule.id.clone())),
+                            // A version the spec doesn't have yet
+                            tracey_core::RuleIdMatch::NoMatch => ("orphaned", None),
+                        }
+                    }
+                    None => match renames.get(&reference.req_id.base) {
+                        Some(current) => ("renamed", Some(current.clone())),
+                        None => ("orphaned", None),
+                    },
+                };
+                ApiFileReference {
+                    verb: reference.verb.to_string(),
+                    id: reference.req_id.clone(),
+                    line: reference.line,
+                    offset: reference.span.offset,
+                    length: reference.span.length,
+                    status: status.to_string(),
+                    current,
+                }
+            })
+            .collect()
+    }
+
     /// Get unmapped code tree for a spec/impl, optionally filtered by path
     pub fn unmapped(
         &self,
@@ -392,7 +593,7 @@ impl<'a> QueryEngine<'a> {
                             name: u.name.clone(),
                             start_line: u.start_line,
                             end_line: u.end_line,
-                            is_covered: !u.req_refs.is_empty(),
+                            is_covered: u.is_covered(),
                         })
                         .collect();
 
@@ -481,9 +682,15 @@ impl<'a> QueryEngine<'a> {
                         html: rule.html.clone(),
                         source_file: rule.source_file.clone(),
                         source_line: rule.source_line,
+                        permalink: rule.permalink.clone(),
                         status: rule.status.clone(),
                         level: rule.level.clone(),
                         is_stale: rule.is_stale,
+                        owners: rule.owners.clone(),
+                        approvals: rule.approvals.clone(),
+                        depends_on: rule.depends_on.clone(),
+                        refines: rule.refines.clone(),
+                        refined_by: crate::refinement::refined_by(&forward.rules, &rule.id.base),
                         coverage: Vec::new(), // Will be set at the end
                     });
                 }
@@ -494,6 +701,8 @@ impl<'a> QueryEngine<'a> {
                     impl_name: key.1.clone(),
                     impl_refs: rule.impl_refs.clone(),
                     verify_refs: rule.verify_refs.clone(),
+                    test_results: rule.test_results.clone(),
+                    line_coverage: rule.line_coverage.clone(),
                 });
             }
         }
@@ -516,18 +725,37 @@ pub struct UncoveredResult {
     pub spec: String,
     pub impl_name: String,
     pub stats: CoverageStats,
-    pub by_section: BTreeMap<String, Vec<RuleRef>>,
+    pub by_section: Vec<(String, Vec<RuleRef>)>,
     pub total_uncovered: usize,
     pub prefix_filter: Option<String>,
 }
 
+/// Which of a spec's impls cover one of its rules
+#[derive(Debug, Clone)]
+pub struct RuleImplsCoverage {
+    pub spec: String,
+    pub id: RuleId,
+    /// Impls with a current implementation reference
+    pub covered_in: Vec<String>,
+    pub missing_in: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UntestedResult {
     pub spec: String,
     pub impl_name: String,
     pub stats: CoverageStats,
-    pub by_section: BTreeMap<String, Vec<RuleRef>>,
+    /// Implemented but unverified rules
+    pub by_section: Vec<(String, Vec<RuleRef>)>,
+    /// Rules with neither impl nor verify refs
+    pub unimplemented_by_section: Vec<(String, Vec<RuleRef>)>,
+    /// Verified rules without a passing test in the last ingested test report
+    pub unproven_by_section: Vec<(String, Vec<RuleRef>)>,
+    /// Both untested buckets, including rules cut by the window
     pub total_untested: usize,
+    pub total_implemented: usize,
+    /// Verified rules without a passing test, including rules cut by the window
+    pub total_unproven: usize,
     pub prefix_filter: Option<String>,
 }
 
@@ -535,6 +763,50 @@ pub struct UntestedResult {
 pub struct RuleRef {
     pub id: RuleId,
     pub impl_refs: Vec<ApiCodeRef>,
+    pub owners: Vec<String>,
+}
+
+/// Ordering and paging for rule list queries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListWindow {
+    /// `None` keeps the query's natural order
+    pub sort: Option<RuleListSort>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl ListWindow {
+    pub fn new(sort: Option<RuleListSort>, limit: Option<u32>, offset: Option<u32>) -> Self {
+        Self {
+            sort,
+            offset: offset.unwrap_or(0) as usize,
+            limit: limit.map(|limit| limit as usize),
+        }
+    }
+
+    /// Sort rules (by section unless asked otherwise) and cut out the page.
+    fn apply_to_rules<'r>(&self, mut rules: Vec<&'r ApiRule>) -> Vec<&'r ApiRule> {
+        self.sort_rules(&mut rules);
+        self.page(rules).collect()
+    }
+
+    fn sort_rules(&self, rules: &mut [&ApiRule]) {
+        // Sorts are stable, so rules keep spec order within equal keys.
+        match self.sort.unwrap_or(RuleListSort::Section) {
+            RuleListSort::Id => rules.sort_by(|a, b| a.id.cmp(&b.id)),
+            RuleListSort::Section => rules.sort_by(|a, b| section_title(a).cmp(section_title(b))),
+            RuleListSort::Refs => rules.sort_by_key(|r| {
+                std::cmp::Reverse(r.impl_refs.len() + r.verify_refs.len() + r.depends_refs.len())
+            }),
+        }
+    }
+
+    fn page<T>(&self, items: Vec<T>) -> impl Iterator<Item = T> {
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -543,6 +815,8 @@ pub struct StaleResult {
     pub impl_name: String,
     pub stats: CoverageStats,
     pub entries: Vec<StaleEntryResult>,
+    /// All matching entries, including those cut by the window
+    pub total_stale: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -605,6 +879,8 @@ pub struct ImplCoverage {
     pub impl_name: String,
     pub impl_refs: Vec<ApiCodeRef>,
     pub verify_refs: Vec<ApiCodeRef>,
+    pub test_results: Vec<ApiTestResult>,
+    pub line_coverage: Vec<ApiLineCoverage>,
 }
 
 #[derive(Debug, Clone)]
@@ -615,10 +891,20 @@ pub struct RuleInfo {
     pub html: String,
     pub source_file: Option<String>,
     pub source_line: Option<usize>,
+    pub permalink: Option<String>,
     pub status: Option<String>,
     pub level: Option<String>,
     /// True if any reference to this rule is stale
     pub is_stale: bool,
+    pub owners: Vec<String>,
+    /// Reviewer approvals of this version of the rule
+    pub approvals: Vec<ApiApproval>,
+    /// Rules of other specs this rule depends on
+    pub depends_on: Vec<ApiSpecDependency>,
+    /// Higher-level rules this rule refines
+    pub refines: Vec<RuleId>,
+    /// Lower-level rules that refine this rule
+    pub refined_by: Vec<RuleId>,
     /// Coverage across all implementations
     pub coverage: Vec<ImplCoverage>,
 }
@@ -644,20 +930,25 @@ impl RuleInfo {
 // Helpers
 // ============================================================================
 
-fn group_rules_by_section(rules: &[&ApiRule]) -> BTreeMap<String, Vec<RuleRef>> {
-    let mut result: BTreeMap<String, Vec<RuleRef>> = BTreeMap::new();
+fn section_title(rule: &ApiRule) -> &str {
+    rule.section_title.as_deref().unwrap_or("Uncategorized")
+}
 
-    for rule in rules {
-        let section = rule
-            .section_title
-            .as_ref()
-            .cloned()
-            .unwrap_or_else(|| "Uncategorized".to_string());
+/// Group consecutive rules of the same section, keeping the given order.
+fn group_rules_by_section(rules: &[&ApiRule]) -> Vec<(String, Vec<RuleRef>)> {
+    let mut result: Vec<(String, Vec<RuleRef>)> = Vec::new();
 
-        result.entry(section).or_default().push(RuleRef {
+    for rule in rules {
+        let section = section_title(rule);
+        let rule_ref = RuleRef {
             id: rule.id.clone(),
             impl_refs: rule.impl_refs.clone(),
-        });
+            owners: rule.owners.clone(),
+        };
+        match result.last_mut() {
+            Some((last, refs)) if last == section => refs.push(rule_ref),
+            _ => result.push((section.to_string(), vec![rule_ref])),
+        }
     }
 
     result
@@ -675,6 +966,28 @@ fn build_file_tree(files: &[ApiFileEntry]) -> Vec<FileTreeNode> {
     root_children.into_values().collect()
 }
 
+/// Roll file coverage up into every directory that contains files, as
+/// `path -> (total_units, covered_units)`. With `depth`, only directories at
+/// most that many levels deep are kept; deeper files count towards their
+/// ancestor at that depth.
+pub fn roll_up_dirs(
+    files: &[ApiFileEntry],
+    depth: Option<usize>,
+) -> BTreeMap<String, (usize, usize)> {
+    let mut dirs: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for file in files {
+        let parts: Vec<&str> = file.path.split('/').collect();
+        let levels = parts.len() - 1;
+        let levels = depth.map_or(levels, |depth| levels.min(depth));
+        for level in 1..=levels {
+            let entry = dirs.entry(parts[..level].join("/")).or_default();
+            entry.0 += file.total_units;
+            entry.1 += file.covered_units;
+        }
+    }
+    dirs
+}
+
 fn insert_into_tree(
     children: &mut BTreeMap<String, FileTreeNode>,
     parts: &[&str],
@@ -845,8 +1158,8 @@ impl UntestedResult {
             self.stats.verify_percent, self.stats.verify_covered, self.stats.total_rules
         ));
 
-        if self.total_untested == 0 {
-            out.push_str("All implemented rules have verification! 🎉\n");
+        if self.total_untested == 0 && self.total_unproven == 0 {
+            out.push_str("All rules have verification! 🎉\n");
             return out;
         }
 
@@ -870,6 +1183,30 @@ impl UntestedResult {
             out.push('\n');
         }
 
+        for (section, rules) in &self.unimplemented_by_section {
+            out.push_str(&format!(
+                "## {} ({} neither implemented nor verified)\n",
+                section,
+                rules.len()
+            ));
+            for rule in rules {
+                out.push_str(&format!("  {}\n", rule.id));
+            }
+            out.push('\n');
+        }
+
+        for (section, rules) in &self.unproven_by_section {
+            out.push_str(&format!(
+                "## {} ({} verified, but no passing test)\n",
+                section,
+                rules.len()
+            ));
+            for rule in rules {
+                out.push_str(&format!("  {}\n", rule.id));
+            }
+            out.push('\n');
+        }
+
         // r[impl mcp.discovery.drill-down] - Provide hints for drilling down
         out.push_str("---\n→ Use mcp__tracey__tracey_rule to see where rule is implemented\n");
 
@@ -1095,6 +1432,7 @@ fn strip_html(html: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::ValidationErrorCode;
     use std::fs;
     use std::path::PathBuf;
     use tempfile::TempDir;
@@ -1104,6 +1442,35 @@ mod tests {
         parse_rule_id(id).expect("valid rule id")
     }
 
+    // r[verify daemon.cli.query.coverage-by-dir]
+    #[test]
+    fn roll_up_dirs_accumulates_into_ancestors() {
+        let file = |path: &str, total_units, covered_units| ApiFileEntry {
+            path: path.to_string(),
+            total_units,
+            covered_units,
+        };
+        let files = vec![
+            file("crates/a/src/lib.rs", 4, 3),
+            file("crates/a/src/util.rs", 2, 0),
+            file("crates/b/src/lib.rs", 5, 5),
+            file("build.rs", 1, 0),
+        ];
+
+        let dirs = roll_up_dirs(&files, None);
+        assert_eq!(dirs.get("crates"), Some(&(11, 8)));
+        assert_eq!(dirs.get("crates/a"), Some(&(6, 3)));
+        assert_eq!(dirs.get("crates/a/src"), Some(&(6, 3)));
+        assert_eq!(dirs.get("crates/b/src"), Some(&(5, 5)));
+        assert_eq!(dirs.len(), 5);
+
+        let dirs = roll_up_dirs(&files, Some(2));
+        assert_eq!(
+            dirs.keys().collect::<Vec<_>>(),
+            ["crates", "crates/a", "crates/b"]
+        );
+    }
+
     /// Create a test fixture with spec, impl files, and config
     async fn create_test_fixture() -> (TempDir, PathBuf) {
         let tmp = TempDir::new().unwrap();
@@ -1204,6 +1571,287 @@ fn do_foo_baz() {}
         );
     }
 
+    // r[verify validation.spec-depends]
+    #[tokio::test]
+    async fn test_spec_dependencies_are_validated_across_specs() {
+        let (_tmp, root) = create_test_fixture().await;
+        fs::write(
+            root.join("docs/spec/protocol.md"),
+            "# Protocol\n\np[api.fetch+2]\nClients MUST fetch.\n",
+        )
+        .unwrap();
+        let spec = fs::read_to_string(root.join("docs/spec/spec.md")).unwrap();
+        fs::write(
+            root.join("docs/spec/spec.md"),
+            spec.replace(
+                "This is the foo.bar rule.",
+                "This is the foo.bar rule, see r[depends protocol:api.fetch+2].",
+            )
+            .replace(
+                "This is the foo.baz rule.",
+                "This is the foo.baz rule, see r[protocol:api.fetch] and r[nope:x.y].",
+            ),
+        )
+        .unwrap();
+        fs::write(
+            root.join(".config/tracey/config.styx"),
+            r#"specs (
+  {
+    name test-spec
+    include (docs/spec/spec.md)
+    impls (
+      {
+        name main
+        include (src/**/*.rs)
+      }
+    )
+  }
+  {
+    name protocol
+    include (docs/spec/protocol.md)
+    impls (
+      {
+        name main
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+        )
+        .unwrap();
+        let config = crate::load_config(&root.join(".config/tracey/config.styx")).unwrap();
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+
+        let engine = QueryEngine::new(&data);
+        let foo_bar = engine.rule(&rid("foo.bar")).unwrap();
+        assert_eq!(foo_bar.depends_on.len(), 1);
+        assert_eq!(foo_bar.depends_on[0].spec, "protocol");
+
+        let key = ("test-spec".to_string(), "main".to_string());
+        let errors: Vec<_> = data.validation_by_impl[&key]
+            .errors
+            .iter()
+            .filter(|e| e.related_rules == vec![rid("foo.baz")])
+            .map(|e| e.code)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ValidationErrorCode::StaleRequirement,
+                ValidationErrorCode::UnknownRequirement
+            ]
+        );
+        assert!(
+            !data.validation_by_impl[&key]
+                .errors
+                .iter()
+                .any(|e| e.related_rules == vec![rid("foo.bar")])
+        );
+    }
+
+    // r[verify daemon.status.by-rule]
+    #[tokio::test]
+    async fn test_status_by_rule_lists_covering_impls() {
+        let (_tmp, root) = create_test_fixture().await;
+        fs::create_dir_all(root.join("web")).unwrap();
+        fs::write(
+            root.join("web/app.ts"),
+            "// r[impl foo.bar]\nfunction fooBar() {}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join(".config/tracey/config.styx"),
+            r#"specs (
+  {
+    name test-spec
+    include (docs/spec/**/*.md)
+    impls (
+      {
+        name main
+        include (src/**/*.rs)
+      }
+      {
+        name web
+        include (web/**/*.ts)
+      }
+    )
+  }
+)
+"#,
+        )
+        .unwrap();
+        let config = crate::load_config(&root.join(".config/tracey/config.styx")).unwrap();
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+
+        let by_rule = QueryEngine::new(&data).status_by_rule();
+        let summary: Vec<(String, Vec<String>, Vec<String>)> = by_rule
+            .into_iter()
+            .map(|r| (r.id.to_string(), r.covered_in, r.missing_in))
+            .collect();
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("foo.bar".to_string(), names(&["main", "web"]), names(&[])),
+                ("foo.baz".to_string(), names(&["main"]), names(&["web"])),
+                (
+                    "uncovered.rule".to_string(),
+                    names(&[]),
+                    names(&["main", "web"])
+                ),
+            ]
+        );
+    }
+
+    // r[verify markdown.syntax.refines]
+    // r[verify validation.refines]
+    #[tokio::test]
+    async fn test_refinements_are_traced_and_validated() {
+        let (_tmp, root) = create_test_fixture().await;
+        let spec = fs::read_to_string(root.join("docs/spec/spec.md")).unwrap();
+        fs::write(
+            root.join("docs/spec/spec.md"),
+            spec.replace(
+                "This is the foo.bar rule.",
+                "This is the foo.bar rule, refining r[refines uncovered.rule].",
+            )
+            .replace(
+                "This is the foo.baz rule.",
+                "This is the foo.baz rule, refining r[refines gone.rule].",
+            ),
+        )
+        .unwrap();
+        let config = crate::load_config(&root.join(".config/tracey/config.styx")).unwrap();
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+
+        let engine = QueryEngine::new(&data);
+        let foo_bar = engine.rule(&rid("foo.bar")).unwrap();
+        assert_eq!(foo_bar.refines, vec![rid("uncovered.rule")]);
+        let uncovered = engine.rule(&rid("uncovered.rule")).unwrap();
+        assert_eq!(uncovered.refined_by, vec![rid("foo.bar")]);
+
+        let key = ("test-spec".to_string(), "main".to_string());
+        let errors: Vec<_> = data.validation_by_impl[&key]
+            .errors
+            .iter()
+            .filter(|e| e.related_rules == vec![rid("foo.baz")])
+            .map(|e| e.code)
+            .collect();
+        assert_eq!(errors, vec![ValidationErrorCode::UnknownRequirement]);
+        assert!(crate::refinement::find_unrefined(&data.forward_by_impl[&key].rules).is_empty());
+    }
+
+    // r[verify markdown.syntax.redirect.resolve]
+    // r[verify validation.renamed]
+    #[tokio::test]
+    async fn test_references_to_renamed_rules_resolve() {
+        let (_tmp, root) = create_test_fixture().await;
+        let spec = fs::read_to_string(root.join("docs/spec/spec.md")).unwrap();
+        fs::write(
+            root.join("docs/spec/spec.md"),
+            spec.replace("r[foo.bar]", "r[foo.qux <- foo.bar]"),
+        )
+        .unwrap();
+        let config = crate::load_config(&root.join(".config/tracey/config.styx")).unwrap();
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+        let engine = QueryEngine::new(&data);
+
+        assert!(engine.rule(&rid("foo.bar")).is_none());
+        let foo_qux = engine.rule(&rid("foo.qux")).expect("foo.qux should exist");
+        assert!(
+            foo_qux.has_any_impl(),
+            "the old reference should count, got: {:?}",
+            foo_qux.coverage
+        );
+
+        let validation = data.validation_by_impl.values().next().unwrap();
+        let renamed: Vec<_> = validation
+            .errors
+            .iter()
+            .filter(|e| e.code == ValidationErrorCode::RenamedRequirement)
+            .collect();
+        assert_eq!(renamed.len(), 1, "{:?}", validation.errors);
+        assert_eq!(renamed[0].related_rules, vec![rid("foo.qux")]);
+        assert!(
+            validation
+                .errors
+                .iter()
+                .all(|e| e.code != ValidationErrorCode::UnknownRequirement)
+        );
+    }
+
+    // r[verify dashboard.api.file.references]
+    #[tokio::test]
+    async fn test_file_references_carry_their_status() {
+        let (_tmp, root) = create_test_fixture().await;
+        let spec = fs::read_to_string(root.join("docs/spec/spec.md")).unwrap();
+        fs::write(
+            root.join("docs/spec/spec.md"),
+            spec.replace("r[foo.bar]", "r[foo.bar+2]")
+                .replace("r[foo.baz]", "r[foo.qux <- foo.baz]"),
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/more.rs"),
+            "// r[impl foo.bar+2]\n// r[verify gone.rule]\n",
+        )
+        .unwrap();
+        let config = crate::load_config(&root.join(".config/tracey/config.styx")).unwrap();
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+        let engine = QueryEngine::new(&data);
+
+        let statuses = |file: &str| -> Vec<(String, String, Option<String>)> {
+            engine
+                .file_references("test-spec", "main", &root.join(file))
+                .into_iter()
+                .map(|r| {
+                    (
+                        r.id.to_string(),
+                        r.status,
+                        r.current.map(|id| id.to_string()),
+                    )
+                })
+                .collect()
+        };
+        let owned = |id: &str, status: &str, current: Option<&str>| {
+            (
+                id.to_string(),
+                status.to_string(),
+                current.map(str::to_string),
+            )
+        };
+        assert_eq!(
+            statuses("src/lib.rs"),
+            vec![
+                owned("foo.bar", "stale", Some("foo.bar+2")),
+                owned("foo.baz", "renamed", Some("foo.qux")),
+            ]
+        );
+        assert_eq!(
+            statuses("src/more.rs"),
+            vec![
+                owned("foo.bar+2", "ok", None),
+                owned("gone.rule", "orphaned", None)
+            ]
+        );
+        assert!(
+            engine
+                .file_references("other-spec", "main", &root.join("src/lib.rs"))
+                .is_empty()
+        );
+    }
+
     #[tokio::test]
     async fn test_rule_lookup_finds_covered_rules() {
         let (_tmp, root) = create_test_fixture().await;
@@ -1241,6 +1889,43 @@ fn do_foo_baz() {}
         );
     }
 
+    // r[verify daemon.cli.query.owner]
+    #[tokio::test]
+    async fn test_uncovered_filters_by_owner() {
+        let (_tmp, root) = create_test_fixture().await;
+        let config_path = root.join(".config/tracey/config.styx");
+        let mut config = crate::load_config(&config_path).unwrap();
+        config.owners = vec![
+            crate::config::OwnerRule {
+                rules: "*".to_string(),
+                owners: vec!["@core".to_string()],
+            },
+            crate::config::OwnerRule {
+                rules: "uncovered.*".to_string(),
+                owners: vec!["@platform-team".to_string()],
+            },
+        ];
+
+        let data = crate::data::build_dashboard_data(&root, &config, 1, true)
+            .await
+            .unwrap();
+        let engine = QueryEngine::new(&data);
+
+        let foo_bar = engine.rule(&rid("foo.bar")).expect("foo.bar should exist");
+        assert_eq!(foo_bar.owners, ["@core"]);
+
+        let uncovered = |owner| {
+            engine
+                .uncovered("test-spec", "main", None, owner, ListWindow::default())
+                .expect("spec/impl exists")
+        };
+        let owned = uncovered(Some("platform-team"));
+        assert_eq!(owned.total_uncovered, 1);
+        assert_eq!(owned.by_section[0].1[0].owners, ["@platform-team"]);
+        assert_eq!(uncovered(Some("@core")).total_uncovered, 0);
+        assert_eq!(uncovered(None).total_uncovered, 1);
+    }
+
     /// Test with multiple impls to check if rule lookup returns the right one
     #[tokio::test]
     async fn test_multiple_impls() {
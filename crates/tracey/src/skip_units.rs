@@ -0,0 +1,277 @@
+//! Trivially-uninteresting code units left out of unmapped metrics.
+//!
+//! r[impl config.impl.skip-units]
+//!
+//! A `Display` impl or a three-line getter rarely needs a requirement, yet
+//! each one makes the "unmapped" count look worse. An impl can skip such
+//! units: they and everything nested in them drop out of the reverse
+//! coverage denominator, so what stays unmapped is a real traceability gap.
+//! The units still show up in file details and can still be annotated.
+
+use std::path::Path;
+
+use tracey_config::SkipUnits;
+use tracey_core::code_units::{CodeUnit, CodeUnitKind};
+
+/// The skip rules of one impl.
+#[derive(Debug, Default)]
+pub struct SkippedUnits {
+    derived: bool,
+    trait_impls: Vec<String>,
+    test_helpers: bool,
+    min_function_lines: Option<usize>,
+}
+
+impl SkippedUnits {
+    pub fn new(config: Option<&SkipUnits>) -> Self {
+        let Some(config) = config else {
+            return Self::default();
+        };
+        Self {
+            derived: config.derived,
+            trait_impls: config.trait_impls.clone(),
+            test_helpers: config.test_helpers,
+            min_function_lines: config.min_function_lines.filter(|n| *n > 1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules().is_empty()
+    }
+
+    /// Human-readable description of each active rule, for `query config`.
+    pub fn rules(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+        if self.derived {
+            rules.push("derived impls".to_string());
+        }
+        if !self.trait_impls.is_empty() {
+            rules.push(format!("impls of {}", self.trait_impls.join(", ")));
+        }
+        if self.test_helpers {
+            rules.push("test helpers".to_string());
+        }
+        if let Some(n) = self.min_function_lines {
+            rules.push(format!("functions under {} lines", n));
+        }
+        rules
+    }
+
+    /// The units of a file that count towards unmapped coverage.
+    pub fn counted(
+        &self,
+        path: &Path,
+        content: &str,
+        units: &[CodeUnit],
+        is_test_file: bool,
+    ) -> Vec<CodeUnit> {
+        if self.test_helpers && is_test_file {
+            return Vec::new();
+        }
+        let mut skipped_lines: Vec<(usize, usize)> = units
+            .iter()
+            .filter(|u| u.kind == CodeUnitKind::Impl && self.skips_impl(content, u))
+            .map(|u| (u.start_line, u.end_line))
+            .collect();
+        if self.test_helpers && path.extension().is_some_and(|ext| ext == "rs") {
+            skipped_lines.extend(crate::data::cfg_test_module_regions(content, units));
+        }
+        units
+            .iter()
+            .filter(|u| {
+                !skipped_lines
+                    .iter()
+                    .any(|(start, end)| *start <= u.start_line && u.end_line <= *end)
+            })
+            .filter(|u| {
+                u.kind != CodeUnitKind::Function
+                    || self
+                        .min_function_lines
+                        .is_none_or(|n| u.end_line - u.start_line + 1 >= n)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn skips_impl(&self, content: &str, unit: &CodeUnit) -> bool {
+        // A unit starts at the comments attached to it, if any, which may
+        // sit before or after the impl's attributes.
+        let text = content
+            .get(unit.start_byte..unit.end_byte)
+            .unwrap_or_default();
+        let preamble: Vec<&str> = text
+            .split_inclusive('\n')
+            .take_while(|line| {
+                let line = line.trim();
+                line.is_empty() || line.starts_with("//") || line.starts_with("#[")
+            })
+            .collect();
+        if self.derived
+            && (is_automatically_derived(content, unit.start_byte)
+                || preamble
+                    .iter()
+                    .any(|line| line.contains("#[automatically_derived]")))
+        {
+            return true;
+        }
+        let code_start: usize = preamble.iter().map(|line| line.len()).sum();
+        let header = text[code_start..].split('{').next().unwrap_or_default();
+        implemented_trait(header).is_some_and(|t| self.trait_impls.iter().any(|s| s == t))
+    }
+}
+
+/// Whether the attributes right before `start_byte` include
+/// `#[automatically_derived]`.
+fn is_automatically_derived(content: &str, start_byte: usize) -> bool {
+    let before = &content[..start_byte.min(content.len())];
+    for line in before.lines().rev() {
+        let line = line.trim();
+        if line.starts_with("#[") {
+            if line.contains("automatically_derived") {
+                return true;
+            }
+        } else if !(line.is_empty() || line.starts_with("//")) {
+            return false;
+        }
+    }
+    false
+}
+
+/// The last path segment of the trait an impl header implements, as in
+/// `Display` for `impl<T> fmt::Display for Wrapper<T>`.
+fn implemented_trait(header: &str) -> Option<&str> {
+    let rest = header.trim_start().trim_start_matches("unsafe ");
+    let mut rest = rest.strip_prefix("impl")?.trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        rest = &rest[end + 1..];
+    }
+    let (path, _) = rest.split_once(" for ")?;
+    let path = path.trim().trim_start_matches('!');
+    let path = path.split('<').next().unwrap_or(path);
+    path.rsplit("::").next().map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(kind: CodeUnitKind, source: &str, text: &str) -> CodeUnit {
+        let start_byte = source.find(text).unwrap();
+        let end_byte = start_byte + text.len();
+        CodeUnit {
+            kind,
+            name: None,
+            file: "src/lib.rs".into(),
+            start_line: source[..start_byte].lines().count() + 1,
+            end_line: source[..end_byte].lines().count(),
+            start_byte,
+            end_byte,
+            req_refs: vec![],
+            scopes: vec![],
+        }
+    }
+
+    #[test]
+    fn reads_the_implemented_trait() {
+        assert_eq!(
+            implemented_trait("impl<T: Clone> fmt::Display for Wrapper<T> "),
+            Some("Display")
+        );
+        assert_eq!(implemented_trait("impl Debug for Foo"), Some("Debug"));
+        assert_eq!(implemented_trait("impl Foo "), None);
+    }
+
+    #[test]
+    fn reads_the_trait_past_attached_comments() {
+        let source =
+            "/// Shows the name.\n#[allow(unused)]\n// r[impl a.b]\nimpl Display for Foo {\n}";
+        let units = vec![unit(CodeUnitKind::Impl, source, source)];
+        let skip = SkippedUnits::new(Some(&SkipUnits {
+            trait_impls: vec!["Display".to_string()],
+            ..Default::default()
+        }));
+        assert!(
+            skip.counted(Path::new("src/lib.rs"), source, &units, false)
+                .is_empty()
+        );
+    }
+
+    // r[verify config.impl.skip-units]
+    #[test]
+    fn skips_trivial_units_and_what_they_contain() {
+        let source = "\
+impl std::fmt::Display for Foo {
+    fn fmt(&self) {
+        write!(f, \"foo\")
+    }
+}
+
+#[automatically_derived]
+impl Clone for Foo {
+    fn clone(&self) -> Self {
+        Foo
+    }
+}
+
+fn short() {}
+
+fn long() {
+    one();
+    two();
+}
+";
+        let display = "impl std::fmt::Display for Foo {\n    fn fmt(&self) {\n        write!(f, \"foo\")\n    }\n}";
+        let fmt = "fn fmt(&self) {\n        write!(f, \"foo\")\n    }";
+        let clone = "impl Clone for Foo {\n    fn clone(&self) -> Self {\n        Foo\n    }\n}";
+        let clone_fn = "fn clone(&self) -> Self {\n        Foo\n    }";
+        let units = vec![
+            unit(CodeUnitKind::Impl, source, display),
+            unit(CodeUnitKind::Function, source, fmt),
+            unit(CodeUnitKind::Impl, source, clone),
+            unit(CodeUnitKind::Function, source, clone_fn),
+            unit(CodeUnitKind::Function, source, "fn short() {}"),
+            unit(
+                CodeUnitKind::Function,
+                source,
+                "fn long() {\n    one();\n    two();\n}",
+            ),
+        ];
+
+        let path = Path::new("src/lib.rs");
+        let none = SkippedUnits::new(None);
+        assert!(none.is_empty());
+        assert_eq!(none.counted(path, source, &units, false).len(), 6);
+
+        let skip = SkippedUnits::new(Some(&SkipUnits {
+            derived: true,
+            trait_impls: vec!["Display".to_string()],
+            test_helpers: true,
+            min_function_lines: Some(3),
+        }));
+        assert_eq!(
+            skip.rules(),
+            vec![
+                "derived impls",
+                "impls of Display",
+                "test helpers",
+                "functions under 3 lines"
+            ]
+        );
+        let counted: Vec<usize> = skip
+            .counted(path, source, &units, false)
+            .iter()
+            .map(|u| u.start_line)
+            .collect();
+        assert_eq!(counted, vec![16]);
+        assert!(skip.counted(path, source, &units, true).is_empty());
+    }
+}
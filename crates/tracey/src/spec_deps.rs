@@ -0,0 +1,125 @@
+//! Dependencies between rules of different specs.
+//!
+//! r[impl markdown.syntax.spec-depends]
+//!
+//! A rule's text can point at a rule of another configured spec by
+//! qualifying the ID with that spec's name, as in `r[depends other:api.fetch]`
+//! or just `r[other:api.fetch]`. The marker prefix is not checked: the spec
+//! name alone picks the target. Inline code and fenced code blocks are
+//! skipped, so specs can show the syntax without depending on anything.
+
+use tracey_api::ApiSpecDependency;
+use tracey_core::parse_rule_id;
+
+/// The text outside of inline code spans and fenced code blocks.
+pub(crate) fn prose(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut in_fence = false;
+    for line in raw.split_inclusive('\n') {
+        let text = line.trim_start_matches([' ', '\t', '>']);
+        if text.starts_with("```") || text.starts_with("~~~") {
+            in_fence = !in_fence;
+            out.push('\n');
+            continue;
+        }
+        if in_fence {
+            out.push('\n');
+            continue;
+        }
+        let mut in_code = false;
+        for c in line.chars() {
+            if c == '`' {
+                in_code = !in_code;
+                out.push(' ');
+            } else if in_code {
+                out.push(' ');
+            } else {
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+/// The cross-spec dependencies written in a rule's text, in order, without
+/// duplicates.
+pub fn parse_spec_dependencies(raw: &str) -> Vec<ApiSpecDependency> {
+    let text = prose(raw);
+    let mut deps: Vec<ApiSpecDependency> = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find('[') {
+        let before = &rest[..open];
+        let prefix_len = before
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            .count();
+        let after = &rest[open + 1..];
+        rest = after;
+        if prefix_len == 0 {
+            continue;
+        }
+        let Some(close) = after.find(']') else {
+            break;
+        };
+        let inner = after[..close].trim();
+        let target = inner.strip_prefix("depends ").unwrap_or(inner).trim();
+        let Some((spec, id)) = target.split_once(':') else {
+            continue;
+        };
+        if spec.is_empty()
+            || !spec
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            continue;
+        }
+        let Some(id) = parse_rule_id(id) else {
+            continue;
+        };
+        let dep = ApiSpecDependency {
+            spec: spec.to_string(),
+            id,
+        };
+        if !deps.iter().any(|d| d.spec == dep.spec && d.id == dep.id) {
+            deps.push(dep);
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(raw: &str) -> Vec<(String, String)> {
+        parse_spec_dependencies(raw)
+            .into_iter()
+            .map(|d| (d.spec, d.id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_qualified_references() {
+        let raw = "Checkout MUST fetch prices as in r[depends protocol:api.fetch+2], \
+                   retrying per r[protocol:api.retry]. See also r[local.rule].";
+        assert_eq!(
+            parsed(raw),
+            vec![
+                ("protocol".to_string(), "api.fetch+2".to_string()),
+                ("protocol".to_string(), "api.retry".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_code_and_duplicates() {
+        let raw = "Write `r[depends other:api.fetch]` to depend on a rule.\n\
+                   ```\nr[other:api.retry]\n```\n\
+                   r[other:api.list] and again r[depends other:api.list].";
+        assert_eq!(
+            parsed(raw),
+            vec![("other".to_string(), "api.list".to_string())]
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Requirement changes between two versions of the specs, for
+//! `tracey spec-diff`.
+//!
+//! r[impl cli.spec-diff]
+//!
+//! Each side is a release cut with `tracey release cut` or a git revision.
+//! A release gives the rules as they were when it was cut; a revision gives
+//! the rules in the spec files (the current config's `include` patterns)
+//! at that commit. The two are compared by base ID: rules only on the new
+//! side were added, rules only on the old side removed, and rules whose
+//! text changed come with a word-level diff, the `~~removed~~` /
+//! `**added**` markdown `SPEC-CHANGELOG.md` uses.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use eyre::{Result, bail};
+use facet::Facet;
+use tracey_core::parse_rule_id;
+
+use crate::bump::{Target, git_capture, glob_set, parse_spec_rules};
+use crate::config::Config;
+use crate::release::{Release, release_path, validate_name};
+
+/// One side of a diff: the rules of each spec, by base ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub specs: BTreeMap<String, BTreeMap<String, SnapshotRule>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRule {
+    /// Canonical ID, with its version
+    pub id: String,
+    /// Raw markdown, `None` for releases cut before texts were recorded
+    pub text: Option<String>,
+}
+
+/// What changed between two snapshots.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecDiff {
+    pub from: String,
+    pub to: String,
+    /// Specs with at least one change
+    pub specs: Vec<SpecChanges>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecChanges {
+    pub spec: String,
+    pub added: Vec<DiffRule>,
+    pub removed: Vec<DiffRule>,
+    pub changed: Vec<ChangedRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct DiffRule {
+    pub id: String,
+    #[facet(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct ChangedRule {
+    pub from_id: String,
+    pub to_id: String,
+    /// Word-level diff of the text, `None` if a side didn't record it
+    #[facet(default)]
+    pub diff: Option<String>,
+}
+
+impl Snapshot {
+    pub fn from_release(release: &Release) -> Self {
+        let mut snapshot = Self::default();
+        for spec in &release.specs {
+            let rules = snapshot.specs.entry(spec.spec.clone()).or_default();
+            for rule in &spec.rules {
+                let base = parse_rule_id(&rule.id).map_or(rule.id.clone(), |id| id.base);
+                rules.insert(
+                    base,
+                    SnapshotRule {
+                        id: rule.id.clone(),
+                        text: rule.text.clone(),
+                    },
+                );
+            }
+        }
+        snapshot
+    }
+
+    /// The rules in the spec files of `config` at the git revision `rev`.
+    pub async fn at_revision(project_root: &Path, config: &Config, rev: &str) -> Result<Self> {
+        Self::at(project_root, config, Target::Commit(rev)).await
+    }
+
+    /// The rules in the spec files of `config` in `target`.
+    pub async fn at(project_root: &Path, config: &Config, target: Target<'_>) -> Result<Self> {
+        let files = target.files(project_root)?;
+        let mut snapshot = Self::default();
+        for spec in &config.specs {
+            let include = glob_set(&spec.include);
+            let rules = snapshot.specs.entry(spec.name.clone()).or_default();
+            for file in files.lines().filter(|f| include.is_match(f)) {
+                let Some(content) = target.read(project_root, file)? else {
+                    continue;
+                };
+                for (base, req) in parse_spec_rules(&content).await? {
+                    rules.insert(
+                        base,
+                        SnapshotRule {
+                            id: req.id.to_string(),
+                            text: Some(req.raw),
+                        },
+                    );
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// The release `name`, or failing that the git revision `name`.
+    pub async fn resolve(project_root: &Path, config: &Config, name: &str) -> Result<Self> {
+        if validate_name(name).is_ok() {
+            let path = release_path(project_root, name);
+            if path.exists() {
+                return Ok(Self::from_release(&Release::load(&path)?));
+            }
+        }
+        let Some(rev) = resolve_revision(project_root, name) else {
+            bail!(
+                "'{}' is neither a release in .tracey/releases nor a git revision",
+                name
+            );
+        };
+        Self::at_revision(project_root, config, &rev).await
+    }
+}
+
+/// The commit the git revision `rev` names, if it names one.
+pub fn resolve_revision(project_root: &Path, rev: &str) -> Option<String> {
+    let commit = format!("{rev}^{{commit}}");
+    git_capture(project_root, &["rev-parse", "--verify", "--quiet", &commit])
+        .ok()
+        .map(|out| out.trim().to_string())
+}
+
+/// The changes from `old` to `new`, labelled `from` and `to`.
+pub fn diff(from: &str, old: &Snapshot, to: &str, new: &Snapshot) -> SpecDiff {
+    let empty = BTreeMap::new();
+    let mut names: Vec<&String> = old.specs.keys().chain(new.specs.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut specs = Vec::new();
+    for name in names {
+        let old_rules = old.specs.get(name).unwrap_or(&empty);
+        let new_rules = new.specs.get(name).unwrap_or(&empty);
+        let mut changes = SpecChanges {
+            spec: name.clone(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (base, new_rule) in new_rules {
+            let Some(old_rule) = old_rules.get(base) else {
+                changes.added.push(DiffRule {
+                    id: new_rule.id.clone(),
+                    text: new_rule.text.clone(),
+                });
+                continue;
+            };
+            let diff = match (&old_rule.text, &new_rule.text) {
+                (Some(old_text), Some(new_text)) if old_text == new_text => continue,
+                (Some(old_text), Some(new_text)) => Some(
+                    marq::diff_markdown_inline(old_text, new_text)
+                        .trim()
+                        .to_string(),
+                ),
+                // Without both texts, a new version is all there is to go on.
+                _ if old_rule.id == new_rule.id => continue,
+                _ => None,
+            };
+            changes.changed.push(ChangedRule {
+                from_id: old_rule.id.clone(),
+                to_id: new_rule.id.clone(),
+                diff,
+            });
+        }
+        for (base, old_rule) in old_rules {
+            if !new_rules.contains_key(base) {
+                changes.removed.push(DiffRule {
+                    id: old_rule.id.clone(),
+                    text: old_rule.text.clone(),
+                });
+            }
+        }
+        if !changes.added.is_empty() || !changes.removed.is_empty() || !changes.changed.is_empty() {
+            specs.push(changes);
+        }
+    }
+
+    SpecDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        specs,
+    }
+}
+
+/// The diff as text, a section per spec.
+pub fn format_diff(diff: &SpecDiff) -> String {
+    if diff.specs.is_empty() {
+        return format!("No rule changes from {} to {}\n", diff.from, diff.to);
+    }
+    let mut out = format!("Rule changes from {} to {}\n", diff.from, diff.to);
+    for spec in &diff.specs {
+        out.push_str(&format!(
+            "\n{}: {} added, {} removed, {} changed\n",
+            spec.spec,
+            spec.added.len(),
+            spec.removed.len(),
+            spec.changed.len()
+        ));
+        for rule in &spec.added {
+            out.push_str(&format!("\n+ {}\n", rule.id));
+            push_indented(&mut out, rule.text.as_deref());
+        }
+        for rule in &spec.removed {
+            out.push_str(&format!("\n- {}\n", rule.id));
+            push_indented(&mut out, rule.text.as_deref());
+        }
+        for rule in &spec.changed {
+            if rule.from_id == rule.to_id {
+                out.push_str(&format!("\n~ {}\n", rule.to_id));
+            } else {
+                out.push_str(&format!("\n~ {} -> {}\n", rule.from_id, rule.to_id));
+            }
+            push_indented(&mut out, rule.diff.as_deref());
+        }
+    }
+    out
+}
+
+fn push_indented(out: &mut String, text: Option<&str>) {
+    for line in text.unwrap_or_default().trim().lines() {
+        out.push_str("    ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(rules: &[(&str, Option<&str>)]) -> Snapshot {
+        let rules = rules
+            .iter()
+            .map(|(id, text)| {
+                let base = parse_rule_id(id).unwrap().base;
+                let rule = SnapshotRule {
+                    id: id.to_string(),
+                    text: text.map(str::to_string),
+                };
+                (base, rule)
+            })
+            .collect();
+        Snapshot {
+            specs: BTreeMap::from([("auth".to_string(), rules)]),
+        }
+    }
+
+    // r[verify cli.spec-diff]
+    #[test]
+    fn lists_added_removed_and_changed_rules() {
+        let old = snapshot(&[
+            ("auth.login", Some("Users MUST log in.")),
+            ("auth.legacy", Some("Passwords MAY be MD5.")),
+            ("auth.logout", Some("Users MAY log out.")),
+            ("auth.session", None),
+        ]);
+        let new = snapshot(&[
+            ("auth.login+2", Some("Users MUST log in with MFA.")),
+            ("auth.logout", Some("Users MAY log out.")),
+            ("auth.session+2", Some("Sessions MUST expire.")),
+            ("auth.mfa", Some("MFA MUST be offered.")),
+        ]);
+        let diff = diff("v1.3", &old, "v1.4", &new);
+        let [spec] = diff.specs.as_slice() else {
+            panic!("expected one spec, got {:?}", diff.specs);
+        };
+        assert_eq!(
+            spec.added,
+            [DiffRule {
+                id: "auth.mfa".to_string(),
+                text: Some("MFA MUST be offered.".to_string()),
+            }]
+        );
+        assert_eq!(spec.removed[0].id, "auth.legacy");
+        assert_eq!(spec.changed.len(), 2);
+        assert_eq!(spec.changed[0].from_id, "auth.login");
+        assert_eq!(spec.changed[0].to_id, "auth.login+2");
+        assert!(spec.changed[0].diff.as_deref().unwrap().contains("MFA"));
+        assert_eq!(spec.changed[1].to_id, "auth.session+2");
+        assert_eq!(spec.changed[1].diff, None);
+
+        let text = format_diff(&diff);
+        assert!(text.contains("auth: 1 added, 1 removed, 2 changed"));
+        assert!(text.contains("~ auth.login -> auth.login+2"));
+
+        assert!(super::diff("v1.4", &new, "v1.4", &new).specs.is_empty());
+    }
+}
@@ -0,0 +1,444 @@
+//! Style checks for spec markdown that validation doesn't make.
+//!
+//! r[impl cli.lint.spec]
+//!
+//! Validation catches what breaks traceability: duplicate IDs, references
+//! to nothing, dependency cycles. A spec can pass all of it and still be
+//! hard to work with: a marker whose text was never written, two sections
+//! with the same heading, numbered rules that skip a number, or a rule that
+//! runs on for a page. `tracey lint spec` reports these, each at the
+//! severity the config's `spec_lint` block gives its check.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use marq::{RenderOptions, render};
+use tracey_config::SpecLint;
+
+use crate::bump::{Globs, Target, glob_set};
+use crate::config::Config;
+
+/// Word limit of a rule text when the config doesn't set `max_words`.
+pub const DEFAULT_MAX_WORDS: usize = 200;
+
+/// How a check's findings are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Off,
+    Warning,
+    /// Makes `tracey lint spec` exit with status 1
+    Error,
+}
+
+impl Severity {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "off" => Some(Self::Off),
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// One of the spec lint checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// A rule with no text after its marker
+    EmptyBody,
+    /// A heading used twice in one file
+    DuplicateHeading,
+    /// A rule whose marker isn't inside a blockquote
+    OutsideBlockquote,
+    /// A numbered rule whose number doesn't follow the previous one
+    Numbering,
+    /// A rule text over the word limit
+    LongText,
+}
+
+impl Check {
+    pub const ALL: [Check; 5] = [
+        Check::EmptyBody,
+        Check::DuplicateHeading,
+        Check::OutsideBlockquote,
+        Check::Numbering,
+        Check::LongText,
+    ];
+
+    /// The check's key in the `spec_lint` block.
+    pub fn key(self) -> &'static str {
+        match self {
+            Check::EmptyBody => "empty_body",
+            Check::DuplicateHeading => "duplicate_heading",
+            Check::OutsideBlockquote => "outside_blockquote",
+            Check::Numbering => "numbering",
+            Check::LongText => "long_text",
+        }
+    }
+
+    fn default_severity(self) -> Severity {
+        match self {
+            Check::EmptyBody => Severity::Error,
+            Check::OutsideBlockquote => Severity::Off,
+            Check::DuplicateHeading | Check::Numbering | Check::LongText => Severity::Warning,
+        }
+    }
+
+    fn configured(self, config: &SpecLint) -> Option<&str> {
+        match self {
+            Check::EmptyBody => config.empty_body.as_deref(),
+            Check::DuplicateHeading => config.duplicate_heading.as_deref(),
+            Check::OutsideBlockquote => config.outside_blockquote.as_deref(),
+            Check::Numbering => config.numbering.as_deref(),
+            Check::LongText => config.long_text.as_deref(),
+        }
+    }
+}
+
+/// The severity of every check, and the word limit of `long_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintSettings {
+    severities: Vec<(Check, Severity)>,
+    max_words: usize,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            severities: Check::ALL
+                .iter()
+                .map(|check| (*check, check.default_severity()))
+                .collect(),
+            max_words: DEFAULT_MAX_WORDS,
+        }
+    }
+}
+
+impl LintSettings {
+    /// r[impl config.spec-lint]
+    pub fn new(config: Option<&SpecLint>) -> Result<Self> {
+        let mut settings = Self::default();
+        let Some(config) = config else {
+            return Ok(settings);
+        };
+        for (check, severity) in &mut settings.severities {
+            if let Some(name) = check.configured(config) {
+                *severity = Severity::from_name(name).ok_or_else(|| {
+                    eyre::eyre!(
+                        "Unknown spec_lint severity '{}' for {}, expected error, warning or off",
+                        name,
+                        check.key()
+                    )
+                })?;
+            }
+        }
+        if let Some(max_words) = config.max_words {
+            settings.max_words = max_words;
+        }
+        Ok(settings)
+    }
+
+    pub fn severity(&self, check: Check) -> Severity {
+        self.severities
+            .iter()
+            .find(|(c, _)| *c == check)
+            .map_or(Severity::Off, |(_, severity)| *severity)
+    }
+}
+
+/// A problem found in a spec file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub check: Check,
+    pub severity: Severity,
+    /// Spec file, relative to the project root
+    pub file: PathBuf,
+    /// 1-indexed line
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}[{}]: {}",
+            self.file.display(),
+            self.line,
+            self.severity.name(),
+            self.check.key(),
+            self.message
+        )
+    }
+}
+
+/// Lint the spec files of the work tree, in file order.
+pub async fn lint_specs(
+    project_root: &Path,
+    config: &Config,
+    settings: &LintSettings,
+) -> Result<Vec<Finding>> {
+    let files = Target::WorkTree.files(project_root)?;
+    let includes: Vec<Globs> = config
+        .specs
+        .iter()
+        .map(|spec| glob_set(&spec.include))
+        .collect();
+    let mut findings = Vec::new();
+    for file in files.lines().map(str::trim) {
+        if file.is_empty() || !includes.iter().any(|include| include.is_match(file)) {
+            continue;
+        }
+        let Some(content) = Target::WorkTree.read(project_root, file).ok().flatten() else {
+            continue;
+        };
+        findings.extend(
+            lint_spec_file(Path::new(file), &content, settings)
+                .await
+                .wrap_err_with(|| format!("failed to parse {}", file))?,
+        );
+    }
+    Ok(findings)
+}
+
+/// Lint one spec file.
+pub async fn lint_spec_file(
+    file: &Path,
+    content: &str,
+    settings: &LintSettings,
+) -> Result<Vec<Finding>> {
+    let doc = render(
+        &crate::redirects::strip_redirects(content),
+        &RenderOptions::default(),
+    )
+    .await
+    .map_err(|e| eyre::eyre!("failed to parse spec: {e}"))?;
+
+    let mut findings = Vec::new();
+    let mut report = |check: Check, line: usize, message: String| {
+        let severity = settings.severity(check);
+        if severity != Severity::Off {
+            findings.push(Finding {
+                check,
+                severity,
+                file: file.to_path_buf(),
+                line,
+                message,
+            });
+        }
+    };
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (line, title) in headings(content) {
+        match seen.get(title) {
+            Some(first) => report(
+                Check::DuplicateHeading,
+                line,
+                format!("heading `{}` is already used at line {}", title, first),
+            ),
+            None => {
+                seen.insert(title, line);
+            }
+        }
+    }
+
+    // Numbered rules, by ID stem: the last number and rule seen
+    let mut numbered: HashMap<&str, (u64, String)> = HashMap::new();
+    for req in &doc.reqs {
+        let id = req.id.to_string();
+        if req.raw.trim().is_empty() {
+            report(
+                Check::EmptyBody,
+                req.line,
+                format!("rule `{}` has no text", id),
+            );
+        }
+
+        let offset = req.marker_span.offset.min(content.len());
+        let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        if !content[line_start..offset].trim_start().starts_with('>') {
+            report(
+                Check::OutsideBlockquote,
+                req.line,
+                format!("rule `{}` is not inside a blockquote", id),
+            );
+        }
+
+        if let Some((stem, number)) = rule_number(&req.id.base) {
+            if let Some((previous, previous_id)) = numbered.get(stem)
+                && number != previous + 1
+            {
+                report(
+                    Check::Numbering,
+                    req.line,
+                    format!(
+                        "rule `{}` follows `{}`; expected number {}",
+                        id,
+                        previous_id,
+                        previous + 1
+                    ),
+                );
+            }
+            numbered.insert(stem, (number, id.clone()));
+        }
+
+        let words = req.raw.split_whitespace().count();
+        if words > settings.max_words {
+            report(
+                Check::LongText,
+                req.line,
+                format!(
+                    "rule `{}` has {} words, over the limit of {}",
+                    id, words, settings.max_words
+                ),
+            );
+        }
+    }
+    Ok(findings)
+}
+
+/// The ATX headings of a markdown file outside code fences, with their
+/// 1-indexed lines.
+fn headings(content: &str) -> Vec<(usize, &str)> {
+    let mut headings = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+        let rest = &trimmed[level..];
+        if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])) {
+            let title = rest.trim().trim_end_matches('#').trim_end();
+            if !title.is_empty() {
+                headings.push((i + 1, title));
+            }
+        }
+    }
+    headings
+}
+
+/// The stem and number of a numbered rule ID: one whose last segment is a
+/// number (`errors.1`) or ends in one after `-` or `_` (`errors.code-01`).
+fn rule_number(base: &str) -> Option<(&str, u64)> {
+    let last = base.rsplit('.').next()?;
+    let digits = last.len() - last.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let stem_end = base.len() - digits;
+    let numbered = digits > 0 && (digits == last.len() || base[..stem_end].ends_with(['-', '_']));
+    if !numbered {
+        return None;
+    }
+    Some((&base[..stem_end], base[stem_end..].parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_headings_outside_code() {
+        let content = "# Errors\n\n```md\n# Not a heading\n```\n\n## Codes ##\n#hashtag\n";
+        assert_eq!(headings(content), vec![(1, "Errors"), (7, "Codes")]);
+    }
+
+    #[test]
+    fn finds_rule_numbers() {
+        assert_eq!(rule_number("errors.1"), Some(("errors.", 1)));
+        assert_eq!(rule_number("errors.code-07"), Some(("errors.code-", 7)));
+        assert_eq!(rule_number("hash.sha256"), None);
+        assert_eq!(rule_number("auth.login"), None);
+    }
+
+    #[test]
+    fn reads_severities_from_the_config() {
+        let settings = LintSettings::new(Some(&SpecLint {
+            empty_body: Some("warning".into()),
+            outside_blockquote: Some("error".into()),
+            max_words: Some(50),
+            ..Default::default()
+        }))
+        .unwrap();
+        assert_eq!(settings.severity(Check::EmptyBody), Severity::Warning);
+        assert_eq!(settings.severity(Check::OutsideBlockquote), Severity::Error);
+        assert_eq!(settings.severity(Check::Numbering), Severity::Warning);
+        assert_eq!(settings.max_words, 50);
+
+        let error = LintSettings::new(Some(&SpecLint {
+            numbering: Some("fatal".into()),
+            ..Default::default()
+        }))
+        .unwrap_err();
+        assert!(error.to_string().contains("numbering"));
+    }
+
+    // r[verify cli.lint.spec]
+    #[tokio::test]
+    async fn reports_each_check() {
+        let content = "\
+# Errors
+
+r[errors.1]
+Errors MUST carry a code.
+
+r[errors.3]
+Errors MUST carry a message.
+
+> r[errors.4]
+>
+> Errors MAY carry details.
+
+# Errors
+
+r[errors.empty]
+
+r[errors.long]
+One two three four five six seven.
+";
+        let settings = LintSettings::new(Some(&SpecLint {
+            outside_blockquote: Some("warning".into()),
+            max_words: Some(6),
+            ..Default::default()
+        }))
+        .unwrap();
+        let findings = lint_spec_file(Path::new("docs/spec/errors.md"), content, &settings)
+            .await
+            .unwrap();
+        let found: Vec<(Check, usize)> = findings.iter().map(|f| (f.check, f.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Check::DuplicateHeading, 13),
+                (Check::OutsideBlockquote, 3),
+                (Check::OutsideBlockquote, 6),
+                (Check::Numbering, 6),
+                (Check::EmptyBody, 15),
+                (Check::OutsideBlockquote, 15),
+                (Check::OutsideBlockquote, 17),
+                (Check::LongText, 17),
+            ]
+        );
+        assert_eq!(
+            findings[3].to_string(),
+            "docs/spec/errors.md:6: warning[numbering]: rule `errors.3` follows `errors.1`; \
+             expected number 2"
+        );
+    }
+}
@@ -0,0 +1,494 @@
+//! Test results from JUnit XML or libtest JSON reports, for
+//! `tracey test-report ingest`.
+//!
+//! r[impl cli.test-report]
+//!
+//! A `verify` reference only says that a test exists. Ingesting a report
+//! records how each test did, and every `verify` reference is matched to the
+//! test function it's in by the function's name (and by file, when the report
+//! names one). The results live in `.tracey/test-results.json` until the next
+//! ingest replaces them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+use tracey_api::{ApiRule, ApiTestResult, TestOutcome};
+use tracey_core::code_units::{CodeUnit, CodeUnitKind};
+
+/// Version of the test results file layout.
+pub const TEST_RESULTS_VERSION: u32 = 1;
+
+/// The tests of one ingested report.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TestReport {
+    pub version: u32,
+    /// The report file, as given to `ingest`
+    pub source: String,
+    /// When the report was ingested (Unix seconds)
+    pub ingested_at: u64,
+    pub tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TestCase {
+    /// Test name as the report gives it, e.g. `tests::login_rejects_bad_password`
+    pub name: String,
+    #[facet(default)]
+    pub file: Option<String>,
+    pub outcome: TestOutcome,
+}
+
+/// Where the last ingested report is kept.
+pub fn results_path(project_root: &Path) -> PathBuf {
+    project_root.join(".tracey").join("test-results.json")
+}
+
+impl TestReport {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        let report: Self = facet_json::from_str(&content)
+            .map_err(|e| eyre::eyre!("Invalid test results {}: {}", path.display(), e))?;
+        if report.version != TEST_RESULTS_VERSION {
+            bail!(
+                "Test results {} have version {}, expected {}; run `tracey test-report ingest`",
+                path.display(),
+                report.version,
+                TEST_RESULTS_VERSION
+            );
+        }
+        Ok(report)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = facet_json::to_string_pretty(self).expect("JSON serialization failed");
+        std::fs::write(path, json + "\n")
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// How many tests had `outcome`.
+    pub fn count(&self, outcome: TestOutcome) -> usize {
+        self.tests.iter().filter(|t| t.outcome == outcome).count()
+    }
+}
+
+/// Parse `report` and store its results for the project, replacing the
+/// previous ones.
+pub fn ingest(project_root: &Path, report: &Path) -> Result<TestReport> {
+    let content = std::fs::read_to_string(report)
+        .wrap_err_with(|| format!("Failed to read {}", report.display()))?;
+    let tests =
+        parse_report(&content).wrap_err_with(|| format!("Failed to parse {}", report.display()))?;
+    if tests.is_empty() {
+        bail!("No test results found in {}", report.display());
+    }
+    let report = TestReport {
+        version: TEST_RESULTS_VERSION,
+        source: report.display().to_string(),
+        ingested_at: crate::history::now_secs(),
+        tests,
+    };
+    report.save(&results_path(project_root))?;
+    Ok(report)
+}
+
+/// Parse a JUnit XML report, or libtest JSON (`--format json`, or nextest's
+/// `--message-format libtest-json`) one event per line.
+pub fn parse_report(content: &str) -> Result<Vec<TestCase>> {
+    if content.trim_start().starts_with('<') {
+        parse_junit(content)
+    } else {
+        parse_libtest_json(content)
+    }
+}
+
+fn parse_junit(xml: &str) -> Result<Vec<TestCase>> {
+    let mut tests = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<testcase") {
+        let after = &rest[start + "<testcase".len()..];
+        if !after.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            rest = after;
+            continue;
+        }
+        let Some(tag_len) = tag_end(after) else {
+            bail!("Unterminated <testcase> element");
+        };
+        let tag = &after[..tag_len];
+        let attributes = parse_attributes(tag.trim_end_matches('/'));
+        let (body, consumed) = if tag.ends_with('/') {
+            ("", tag_len + 1)
+        } else {
+            let content = &after[tag_len + 1..];
+            let Some(close) = content.find("</testcase>") else {
+                bail!("Missing </testcase>");
+            };
+            (&content[..close], tag_len + 1 + close + "</testcase>".len())
+        };
+        rest = &after[consumed..];
+
+        let Some(name) = attributes.get("name") else {
+            continue;
+        };
+        let outcome = if body.contains("<failure") || body.contains("<error") {
+            TestOutcome::Failed
+        } else if body.contains("<skipped") {
+            TestOutcome::Skipped
+        } else {
+            TestOutcome::Passed
+        };
+        tests.push(TestCase {
+            name: name.clone(),
+            file: attributes.get("file").cloned(),
+            outcome,
+        });
+    }
+    Ok(tests)
+}
+
+/// Length of a tag's contents up to its closing `>`, skipping any `>` in
+/// quoted attribute values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(tag: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = tag.trim_start();
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        attributes.insert(name, unescape_xml(&value[1..1 + end]));
+        rest = value[end + 2..].trim_start();
+    }
+    attributes
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[derive(Facet)]
+struct LibtestEvent {
+    #[facet(rename = "type")]
+    kind: String,
+    #[facet(default)]
+    event: String,
+    #[facet(default)]
+    name: String,
+}
+
+fn parse_libtest_json(content: &str) -> Result<Vec<TestCase>> {
+    let mut tests = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        // Build output and other non-JSON lines may be interleaved.
+        if !line.trim_start().starts_with('{') {
+            continue;
+        }
+        let event: LibtestEvent = facet_json::from_str(line)
+            .map_err(|e| eyre::eyre!("Invalid JSON on line {}: {}", index + 1, e))?;
+        if event.kind != "test" {
+            continue;
+        }
+        let outcome = match event.event.as_str() {
+            "ok" => TestOutcome::Passed,
+            "failed" | "timeout" => TestOutcome::Failed,
+            "ignored" => TestOutcome::Skipped,
+            _ => continue,
+        };
+        tests.push(TestCase {
+            name: event.name,
+            file: None,
+            outcome,
+        });
+    }
+    Ok(tests)
+}
+
+/// The words of a test name, e.g. `tests`, `login` and `valid` for
+/// `tests::login[valid]`; a test matches a function named like one of them.
+fn name_segments(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|segment| !segment.is_empty())
+}
+
+/// The last ingested report, indexed by the function names its tests match.
+#[derive(Debug, Default)]
+pub struct TestResults {
+    by_function: HashMap<String, Vec<TestCase>>,
+}
+
+impl TestResults {
+    /// Load the project's last ingested report, if there is one.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = results_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(TestReport::load(&path)?.tests)))
+    }
+
+    pub fn new(tests: Vec<TestCase>) -> Self {
+        let mut by_function: HashMap<String, Vec<TestCase>> = HashMap::new();
+        for test in tests {
+            let mut segments: Vec<&str> = name_segments(&test.name).collect();
+            segments.sort_unstable();
+            segments.dedup();
+            for segment in segments {
+                by_function
+                    .entry(segment.to_string())
+                    .or_default()
+                    .push(test.clone());
+            }
+        }
+        Self { by_function }
+    }
+
+    /// Outcome of the tests for the function `name` in `file`. A failure of
+    /// any of them wins over passes, and a pass over skips.
+    pub fn outcome(&self, name: &str, file: &str) -> TestOutcome {
+        let tests = self.by_function.get(name).map(Vec::as_slice).unwrap_or(&[]);
+        let outcomes: Vec<TestOutcome> = tests
+            .iter()
+            .filter(|test| {
+                test.file
+                    .as_deref()
+                    .is_none_or(|f| file.ends_with(f) || f.ends_with(file))
+            })
+            .map(|test| test.outcome)
+            .collect();
+        [
+            TestOutcome::Failed,
+            TestOutcome::Passed,
+            TestOutcome::Skipped,
+        ]
+        .into_iter()
+        .find(|outcome| outcomes.contains(outcome))
+        .unwrap_or(TestOutcome::NotRun)
+    }
+
+    /// Results for each of the rule's `verify` references. `units_by_file`
+    /// are the impl's code units, keyed by path under `project_root`.
+    pub fn results_for(
+        &self,
+        rule: &ApiRule,
+        project_root: &Path,
+        units_by_file: &BTreeMap<PathBuf, Vec<CodeUnit>>,
+    ) -> Vec<ApiTestResult> {
+        rule.verify_refs
+            .iter()
+            .map(|code_ref| {
+                let units = units_by_file
+                    .get(&project_root.join(&code_ref.file))
+                    .map(Vec::as_slice)
+                    .unwrap_or(&[]);
+                let test = enclosing_function(units, &rule.id.base, code_ref.line);
+                let outcome = test.map_or(TestOutcome::NotRun, |name| {
+                    self.outcome(name, &code_ref.file)
+                });
+                ApiTestResult {
+                    file: code_ref.file.clone(),
+                    line: code_ref.line,
+                    test: test.map(str::to_string),
+                    outcome,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The function a reference to `base` on `line` belongs to: the closest one
+/// whose comments reference the rule (annotations usually sit just above
+/// the test), else the innermost one containing the line.
+fn enclosing_function<'a>(units: &'a [CodeUnit], base: &str, line: usize) -> Option<&'a str> {
+    let functions = || {
+        units
+            .iter()
+            .filter(|u| u.kind == CodeUnitKind::Function && u.name.is_some())
+    };
+    let distance = |u: &CodeUnit| {
+        if line < u.start_line {
+            u.start_line - line
+        } else {
+            line.saturating_sub(u.end_line)
+        }
+    };
+    functions()
+        .filter(|u| u.req_refs.iter().any(|r| r.base == base))
+        .min_by_key(|u| distance(u))
+        .or_else(|| {
+            functions()
+                .filter(|u| u.start_line <= line && line <= u.end_line)
+                .min_by_key(|u| u.end_line - u.start_line)
+        })
+        .and_then(|u| u.name.as_deref())
+}
+
+/// Whether the rule has a test that passed the last ingested report and
+/// none that failed.
+pub fn has_passing_test(rule: &ApiRule) -> bool {
+    let outcomes = || rule.test_results.iter().map(|r| r.outcome);
+    outcomes().any(|o| o == TestOutcome::Passed) && !outcomes().any(|o| o == TestOutcome::Failed)
+}
+
+/// Whether the rule has `verify` references but, according to the last
+/// ingested report, no passing test.
+pub fn lacks_passing_test(rule: &ApiRule) -> bool {
+    !rule.test_results.is_empty() && !has_passing_test(rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_junit() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="tracey::bump_tests" tests="3">
+    <testcase name="tests::login_works" classname="tracey::bump_tests" time="0.1"/>
+    <testcase name="tests::login_rejects_&quot;bad&quot;" file="tests/login.rs">
+      <failure message="assertion failed: a > b">...</failure>
+    </testcase>
+    <testcase name="slow_test"><skipped/></testcase>
+  </testsuite>
+</testsuites>
+"#;
+        let tests = parse_report(xml).unwrap();
+        assert_eq!(
+            tests,
+            [
+                TestCase {
+                    name: "tests::login_works".to_string(),
+                    file: None,
+                    outcome: TestOutcome::Passed,
+                },
+                TestCase {
+                    name: "tests::login_rejects_\"bad\"".to_string(),
+                    file: Some("tests/login.rs".to_string()),
+                    outcome: TestOutcome::Failed,
+                },
+                TestCase {
+                    name: "slow_test".to_string(),
+                    file: None,
+                    outcome: TestOutcome::Skipped,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_libtest_json() {
+        let json = r#"{ "type": "suite", "event": "started", "test_count": 3 }
+{ "type": "test", "event": "started", "name": "tests::login_works" }
+{ "type": "test", "name": "tests::login_works", "event": "ok" }
+{ "type": "test", "name": "tracey::bump_tests$login_fails", "event": "failed", "stdout": "" }
+   Compiling tracey v1.1.5
+{ "type": "test", "name": "tests::slow", "event": "ignored" }
+"#;
+        let tests = parse_report(json).unwrap();
+        let outcomes: Vec<(&str, TestOutcome)> =
+            tests.iter().map(|t| (t.name.as_str(), t.outcome)).collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("tests::login_works", TestOutcome::Passed),
+                ("tracey::bump_tests$login_fails", TestOutcome::Failed),
+                ("tests::slow", TestOutcome::Skipped),
+            ]
+        );
+    }
+
+    #[test]
+    fn matches_tests_by_function_name_and_file() {
+        let test = |name: &str, file: Option<&str>, outcome| TestCase {
+            name: name.to_string(),
+            file: file.map(str::to_string),
+            outcome,
+        };
+        let results = TestResults::new(vec![
+            test("tests::login_works", None, TestOutcome::Passed),
+            test("test_expiry[short]", None, TestOutcome::Passed),
+            test("test_expiry[long]", None, TestOutcome::Failed),
+            test("test_logout", Some("tests/a.py"), TestOutcome::Passed),
+        ]);
+        assert_eq!(
+            results.outcome("login_works", "src/auth.rs"),
+            TestOutcome::Passed
+        );
+        assert_eq!(
+            results.outcome("test_expiry", "tests/b.py"),
+            TestOutcome::Failed
+        );
+        assert_eq!(
+            results.outcome("test_logout", "tests/a.py"),
+            TestOutcome::Passed
+        );
+        assert_eq!(
+            results.outcome("test_logout", "tests/b.py"),
+            TestOutcome::NotRun
+        );
+        assert_eq!(results.outcome("login", "src/auth.rs"), TestOutcome::NotRun);
+    }
+
+    #[test]
+    fn finds_the_function_a_reference_belongs_to() {
+        let function = |name: &str, lines: (usize, usize), refs: &[&str]| CodeUnit {
+            kind: CodeUnitKind::Function,
+            name: Some(name.to_string()),
+            file: PathBuf::from("tests/auth.rs"),
+            start_line: lines.0,
+            end_line: lines.1,
+            start_byte: 0,
+            end_byte: 0,
+            req_refs: refs
+                .iter()
+                .map(|r| tracey_core::parse_rule_id(r).expect("valid rule id"))
+                .collect(),
+            scopes: vec![],
+        };
+        let units = [
+            function("login_works", (3, 10), &["auth.login"]),
+            function("logout_works", (13, 20), &["auth.logout"]),
+            function("helper", (22, 30), &[]),
+        ];
+        // The annotation above the test is in its doc comments, not its body.
+        assert_eq!(
+            enclosing_function(&units, "auth.login", 2),
+            Some("login_works")
+        );
+        assert_eq!(
+            enclosing_function(&units, "auth.logout", 12),
+            Some("logout_works")
+        );
+        assert_eq!(enclosing_function(&units, "auth.token", 25), Some("helper"));
+        assert_eq!(enclosing_function(&units, "auth.token", 40), None);
+    }
+}
@@ -0,0 +1,612 @@
+//! Turning the coverage backlog into work items, for `tracey todo`.
+//!
+//! r[impl cli.todo]
+//!
+//! Each uncovered rule of an impl, or each section with uncovered rules,
+//! becomes one item with the rule text and a few files to start from: the
+//! files implementing the rules most similar to it. With `--github-issues`
+//! the items are GitHub issue bodies, printed as JSON or created through
+//! `gh api`. Every body ends in a hidden `<!-- tracey:KEY -->` marker, so
+//! creating them again skips the issues that are still open.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+use tracey_api::{ApiRule, ApiSpecForward};
+
+use crate::data::ImplKey;
+use crate::rule_suggestions::suggest_similar_rule_ids;
+
+/// Label on every issue `tracey todo` files.
+pub const LABEL: &str = "tracey";
+
+/// How many files to suggest per rule.
+const SUGGESTED_FILES: usize = 3;
+
+/// The body of a GitHub "create an issue" request.
+#[derive(Debug, Clone, PartialEq, Facet)]
+pub struct GithubIssue {
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+/// One piece of coverage work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkItem {
+    /// Stable identity: `spec/impl:rule` or `spec/impl#section`
+    pub key: String,
+    pub title: String,
+    /// Markdown describing the rules and where to start
+    pub body: String,
+}
+
+impl WorkItem {
+    pub fn to_github_issue(&self, labels: &[String]) -> GithubIssue {
+        let mut all = vec![LABEL.to_string()];
+        all.extend(labels.iter().filter(|l| l.as_str() != LABEL).cloned());
+        GithubIssue {
+            title: self.title.clone(),
+            body: format!("{}\n<!-- tracey:{} -->\n", self.body, self.key),
+            labels: all,
+        }
+    }
+}
+
+/// Which work items to produce.
+#[derive(Debug, Clone, Default)]
+pub struct TodoOptions {
+    pub spec: Option<String>,
+    pub impl_name: Option<String>,
+    /// Only rules whose ID starts with this (case-insensitive)
+    pub prefix: Option<String>,
+    /// One item per section instead of one per rule
+    pub per_section: bool,
+}
+
+/// Files implementing the covered rules most similar to `rule`, with the
+/// rule that suggests each.
+fn suggested_files(rule: &ApiRule, covered: &[&ApiRule]) -> Vec<(String, String)> {
+    let ids: Vec<_> = covered.iter().map(|r| r.id.clone()).collect();
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for similar in suggest_similar_rule_ids(&rule.id, &ids, SUGGESTED_FILES) {
+        let Some(similar_rule) = covered.iter().find(|r| r.id == similar) else {
+            continue;
+        };
+        for code_ref in &similar_rule.impl_refs {
+            if files.len() < SUGGESTED_FILES && seen.insert(code_ref.file.clone()) {
+                files.push((code_ref.file.clone(), similar.to_string()));
+            }
+        }
+    }
+    files
+}
+
+/// Markdown for one uncovered rule: where it's defined, its text, owners
+/// and suggested files.
+fn rule_markdown(rule: &ApiRule, covered: &[&ApiRule], heading: bool) -> String {
+    let mut out = String::new();
+    if heading {
+        out.push_str(&format!("### `{}`\n\n", rule.id));
+    }
+    if let Some(file) = &rule.source_file {
+        match rule.source_line {
+            Some(line) => out.push_str(&format!("Defined in `{}:{}`.\n\n", file, line)),
+            None => out.push_str(&format!("Defined in `{}`.\n\n", file)),
+        }
+    }
+    for line in rule.raw.trim().lines() {
+        match line.trim_start_matches('>').trim() {
+            "" => out.push_str(">\n"),
+            line => out.push_str(&format!("> {}\n", line)),
+        }
+    }
+    out.push('\n');
+    if !rule.owners.is_empty() {
+        out.push_str(&format!("Owned by {}.\n\n", rule.owners.join(", ")));
+    }
+    let files = suggested_files(rule, covered);
+    if !files.is_empty() {
+        out.push_str("Similar requirements are implemented in:\n\n");
+        for (file, similar) in files {
+            out.push_str(&format!("- `{}` (`{}`)\n", file, similar));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// An impl with its covered rules and the uncovered rules asked for.
+type Selected<'a> = (
+    &'a ImplKey,
+    &'a ApiSpecForward,
+    Vec<&'a ApiRule>,
+    Vec<&'a ApiRule>,
+);
+
+/// The impls `options` selects.
+fn selected<'a>(
+    forward_by_impl: &'a BTreeMap<ImplKey, ApiSpecForward>,
+    options: &'a TodoOptions,
+) -> impl Iterator<Item = Selected<'a>> {
+    forward_by_impl
+        .iter()
+        .filter(|((spec, impl_name), _)| {
+            options.spec.as_ref().is_none_or(|s| s == spec)
+                && options.impl_name.as_ref().is_none_or(|i| i == impl_name)
+        })
+        .map(|(key, forward)| {
+            let covered = forward
+                .rules
+                .iter()
+                .filter(|r| !r.impl_refs.is_empty())
+                .collect();
+            let uncovered = forward
+                .rules
+                .iter()
+                .filter(|r| r.impl_refs.is_empty())
+                .filter(|r| {
+                    options
+                        .prefix
+                        .as_ref()
+                        .is_none_or(|p| r.id.base.to_lowercase().starts_with(&p.to_lowercase()))
+                })
+                .collect();
+            (key, forward, covered, uncovered)
+        })
+}
+
+/// The work items for the uncovered rules of every selected impl.
+pub fn work_items(
+    forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>,
+    options: &TodoOptions,
+) -> Vec<WorkItem> {
+    let mut items = Vec::new();
+    for ((spec, impl_name), forward, covered, uncovered) in selected(forward_by_impl, options) {
+        let target = format!("{}/{}", spec, impl_name);
+        let annotate = |rule: &ApiRule| format!("`// r[impl {}]`", rule.id);
+
+        if !options.per_section {
+            for rule in uncovered {
+                items.push(WorkItem {
+                    key: format!("{}:{}", target, rule.id.base),
+                    title: format!("Implement {} ({})", rule.id, target),
+                    body: format!(
+                        "Requirement `{}` has no implementation in `{}`.\n\n{}\
+                         Annotate the code that implements it with {}.\n",
+                        rule.id,
+                        target,
+                        rule_markdown(rule, &covered, false),
+                        annotate(rule)
+                    ),
+                });
+            }
+            continue;
+        }
+
+        // Sections in spec order, keyed by slug.
+        let mut sections: Vec<(String, String, Vec<&ApiRule>)> = Vec::new();
+        for rule in uncovered {
+            let slug = rule.section.clone().unwrap_or_default();
+            match sections.iter_mut().find(|(s, _, _)| *s == slug) {
+                Some((_, _, rules)) => rules.push(rule),
+                None => {
+                    let title = rule
+                        .section_title
+                        .clone()
+                        .unwrap_or_else(|| forward.name.clone());
+                    sections.push((slug, title, vec![rule]));
+                }
+            }
+        }
+        for (slug, title, rules) in sections {
+            let mut body = format!(
+                "{} requirement(s) in \"{}\" have no implementation in `{}`. \
+                 Annotate the code that implements each with `// r[impl ID]`.\n\n",
+                rules.len(),
+                title,
+                target
+            );
+            for rule in &rules {
+                body.push_str(&rule_markdown(rule, &covered, true));
+            }
+            items.push(WorkItem {
+                key: format!("{}#{}", target, slug),
+                title: format!(
+                    "Implement \"{}\" ({} requirement(s), {})",
+                    title,
+                    rules.len(),
+                    target
+                ),
+                body: body.trim_end().to_string() + "\n",
+            });
+        }
+    }
+    items
+}
+
+/// A `TODO(tracey)` comment naming an uncovered rule, to write into a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stub {
+    /// Relative to the project root
+    pub file: String,
+    /// The comment, without a line break
+    pub line: String,
+}
+
+/// A stub for every uncovered rule of the selected impls, in `into` if
+/// given and otherwise in the first file suggested for the rule, with the
+/// marker prefix of each spec from `prefixes` (`r` when missing). Also
+/// returns the rules there was no file for, as `spec/impl:rule`, and those
+/// whose file is in a language tracey doesn't know the comments of.
+///
+/// r[impl cli.todo.stubs]
+pub fn stubs(
+    forward_by_impl: &BTreeMap<ImplKey, ApiSpecForward>,
+    options: &TodoOptions,
+    prefixes: &BTreeMap<String, String>,
+    into: Option<&str>,
+) -> (Vec<Stub>, Vec<String>) {
+    let mut stubs = Vec::new();
+    let mut homeless = Vec::new();
+    for ((spec, impl_name), _, covered, uncovered) in selected(forward_by_impl, options) {
+        let prefix = prefixes.get(spec).map_or("r", String::as_str);
+        for rule in uncovered {
+            let file = match into {
+                Some(into) => into.to_string(),
+                None => match suggested_files(rule, &covered).into_iter().next() {
+                    Some((file, _)) => file,
+                    None => {
+                        homeless.push(format!("{}/{}:{}", spec, impl_name, rule.id));
+                        continue;
+                    }
+                },
+            };
+            let extension = Path::new(&file).extension().and_then(|e| e.to_str());
+            let Some((open, close)) = extension.and_then(tracey_core::comment_delimiters) else {
+                homeless.push(format!(
+                    "{}/{}:{} (no comment syntax for {})",
+                    spec, impl_name, rule.id, file
+                ));
+                continue;
+            };
+            let line = format!(
+                "{} {} implement {}[impl {}]",
+                open,
+                tracey_core::TODO_STUB,
+                prefix,
+                rule.id
+            );
+            let line = match close {
+                "" => line,
+                close => format!("{line} {close}"),
+            };
+            stubs.push(Stub { file, line });
+        }
+    }
+    (stubs, homeless)
+}
+
+/// `content` with the stub `lines` it doesn't have yet appended, after a
+/// blank line, and how many that is.
+pub fn append_stubs(content: &str, lines: &[&str]) -> (String, usize) {
+    let existing: HashSet<&str> = content.lines().map(str::trim).collect();
+    let mut new: Vec<&str> = Vec::new();
+    for &line in lines {
+        if !existing.contains(line) && !new.contains(&line) {
+            new.push(line);
+        }
+    }
+    if new.is_empty() {
+        return (content.to_string(), 0);
+    }
+    let added = new.len();
+    let mut out = content.to_string();
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        if !content
+            .lines()
+            .last()
+            .is_some_and(|last| last.contains(tracey_core::TODO_STUB))
+        {
+            out.push('\n');
+        }
+    }
+    for line in new {
+        out.push_str(line);
+        out.push('\n');
+    }
+    (out, added)
+}
+
+/// Write `stubs` into their files, creating files that don't exist.
+/// Returns the number of stubs written; those already in their file are
+/// skipped.
+pub fn write_stubs(project_root: &Path, stubs: &[Stub]) -> Result<usize> {
+    let mut by_file: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for stub in stubs {
+        by_file.entry(&stub.file).or_default().push(&stub.line);
+    }
+    let mut written = 0;
+    for (file, lines) in by_file {
+        let path = project_root.join(file);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).wrap_err_with(|| format!("Failed to read {}", file)),
+        };
+        let (updated, added) = append_stubs(&content, &lines);
+        if added == 0 {
+            continue;
+        }
+        written += added;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, updated).wrap_err_with(|| format!("Failed to write {}", file))?;
+    }
+    Ok(written)
+}
+
+/// Keys of the open `tracey` issues in the current GitHub repository.
+pub fn open_issue_keys(project_root: &Path) -> Result<HashSet<String>> {
+    let path = format!(
+        "repos/{{owner}}/{{repo}}/issues?labels={}&state=open&per_page=100",
+        LABEL
+    );
+    let output = Command::new("gh")
+        .args([
+            "api",
+            "--paginate",
+            path.as_str(),
+            "--jq",
+            r#".[] | .body // "" | scan("<!-- tracey:(\\S+) -->") | .[0]"#,
+        ])
+        .current_dir(project_root)
+        .output()
+        .wrap_err("Failed to run gh (is the GitHub CLI installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "gh api failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Create `issue` in the current GitHub repository; returns its URL.
+pub fn create_issue(project_root: &Path, issue: &GithubIssue) -> Result<String> {
+    let mut child = Command::new("gh")
+        .args([
+            "api",
+            "repos/{owner}/{repo}/issues",
+            "--method",
+            "POST",
+            "--input",
+            "-",
+            "--jq",
+            ".html_url",
+        ])
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("Failed to run gh (is the GitHub CLI installed?)")?;
+    let json = facet_json::to_string(issue).expect("JSON serialization failed");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(json.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!(
+            "Creating \"{}\" failed: {}",
+            issue.title,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracey_api::ApiCodeRef;
+    use tracey_core::parse_rule_id;
+
+    fn rule(id: &str, section: &str, files: &[&str]) -> ApiRule {
+        ApiRule {
+            id: parse_rule_id(id).unwrap(),
+            raw: format!("The {} requirement MUST hold.", id),
+            html: String::new(),
+            status: None,
+            level: None,
+            source_file: Some("docs/spec.md".to_string()),
+            source_line: Some(10),
+            source_column: None,
+            permalink: None,
+            section: Some(section.to_lowercase()),
+            section_title: Some(section.to_string()),
+            renamed_from: vec![],
+            depends_on: vec![],
+            refines: vec![],
+            impl_refs: files
+                .iter()
+                .map(|file| ApiCodeRef {
+                    file: file.to_string(),
+                    line: 1,
+                })
+                .collect(),
+            verify_refs: vec![],
+            depends_refs: vec![],
+            is_stale: false,
+            stale_refs: vec![],
+            owners: vec![],
+            approvals: vec![],
+            test_results: vec![],
+            line_coverage: vec![],
+            tags: vec![],
+        }
+    }
+
+    fn forward() -> BTreeMap<ImplKey, ApiSpecForward> {
+        let rules = vec![
+            rule("auth.login", "Auth", &["src/auth/login.rs"]),
+            rule("auth.login.mfa", "Auth", &[]),
+            rule("auth.logout", "Auth", &[]),
+            rule("storage.quota", "Storage", &["src/storage.rs"]),
+            rule("storage.encryption", "Storage", &[]),
+        ];
+        [(
+            ("proto".to_string(), "rust".to_string()),
+            ApiSpecForward {
+                name: "proto".to_string(),
+                rules,
+            },
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn one_item_per_uncovered_rule() {
+        let options = TodoOptions {
+            prefix: Some("AUTH.".to_string()),
+            ..TodoOptions::default()
+        };
+        let items = work_items(&forward(), &options);
+        let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Implement auth.login.mfa (proto/rust)",
+                "Implement auth.logout (proto/rust)"
+            ]
+        );
+        assert_eq!(items[0].key, "proto/rust:auth.login.mfa");
+        let body = &items[0].body;
+        assert!(body.contains("Defined in `docs/spec.md:10`."), "{body}");
+        assert!(
+            body.contains("> The auth.login.mfa requirement MUST hold."),
+            "{body}"
+        );
+        assert!(
+            body.contains("- `src/auth/login.rs` (`auth.login`)"),
+            "{body}"
+        );
+        assert!(body.contains("`// r[impl auth.login.mfa]`"), "{body}");
+
+        let issue = items[0].to_github_issue(&["backlog".to_string(), "tracey".to_string()]);
+        assert_eq!(issue.labels, ["tracey", "backlog"]);
+        assert!(
+            issue
+                .body
+                .ends_with("<!-- tracey:proto/rust:auth.login.mfa -->\n")
+        );
+    }
+
+    #[test]
+    fn one_item_per_section() {
+        let options = TodoOptions {
+            per_section: true,
+            ..TodoOptions::default()
+        };
+        let items = work_items(&forward(), &options);
+        let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "Implement \"Auth\" (2 requirement(s), proto/rust)",
+                "Implement \"Storage\" (1 requirement(s), proto/rust)"
+            ]
+        );
+        assert_eq!(items[1].key, "proto/rust#storage");
+        assert!(items[0].body.contains("### `auth.logout`"));
+        assert!(
+            items[1]
+                .body
+                .contains("- `src/storage.rs` (`storage.quota`)")
+        );
+
+        let options = TodoOptions {
+            impl_name: Some("ts".to_string()),
+            ..options
+        };
+        assert!(work_items(&forward(), &options).is_empty());
+    }
+
+    // r[verify cli.todo.stubs]
+    #[test]
+    fn stubs_go_into_suggested_files() {
+        let options = TodoOptions::default();
+        let (stubs, homeless) = stubs(&forward(), &options, &BTreeMap::new(), None);
+        let lines: Vec<(&str, &str)> = stubs
+            .iter()
+            .map(|s| (s.file.as_str(), s.line.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (
+                    "src/auth/login.rs",
+                    "// TODO(tracey): implement r[impl auth.login.mfa]"
+                ),
+                (
+                    "src/auth/login.rs",
+                    "// TODO(tracey): implement r[impl auth.logout]"
+                ),
+                (
+                    "src/storage.rs",
+                    "// TODO(tracey): implement r[impl storage.encryption]"
+                ),
+            ]
+        );
+        assert!(homeless.is_empty());
+
+        let prefixes = BTreeMap::from([("proto".to_string(), "p".to_string())]);
+        let (stubs, _) = stubs_in("todo.py", &prefixes);
+        assert_eq!(stubs[0].file, "todo.py");
+        assert_eq!(
+            stubs[0].line,
+            "# TODO(tracey): implement p[impl auth.login.mfa]"
+        );
+        let (stubs, _) = stubs_in("todo.ml", &prefixes);
+        assert_eq!(
+            stubs[0].line,
+            "(* TODO(tracey): implement p[impl auth.login.mfa] *)"
+        );
+        let (stubs, homeless) = stubs_in("todo.txt", &prefixes);
+        assert!(stubs.is_empty());
+        assert_eq!(
+            homeless[0],
+            "proto/rust:auth.login.mfa (no comment syntax for todo.txt)"
+        );
+
+        let (content, added) = append_stubs("fn login() {}", &[lines[0].1, lines[1].1]);
+        assert_eq!(added, 2);
+        assert_eq!(
+            content,
+            "fn login() {}\n\n\
+             // TODO(tracey): implement r[impl auth.login.mfa]\n\
+             // TODO(tracey): implement r[impl auth.logout]\n"
+        );
+        assert_eq!(append_stubs(&content, &[lines[1].1]).1, 0);
+    }
+
+    fn stubs_in(file: &str, prefixes: &BTreeMap<String, String>) -> (Vec<Stub>, Vec<String>) {
+        stubs(&forward(), &TodoOptions::default(), prefixes, Some(file))
+    }
+}
@@ -0,0 +1,312 @@
+//! Requirement events from tracing output, for `tracey trace-ingest`.
+//!
+//! r[impl cli.trace-ingest]
+//!
+//! `tracey_runtime::requirement!("auth.login")` emits a tracing event with
+//! a `requirement` field each time the code it sits in runs. Ingesting the
+//! logs of a test run or a production service counts those events per
+//! requirement and sets them against the specs: which requirements were
+//! exercised, how often and when, which never were, and which IDs the
+//! events name that no spec defines. Log lines are read as
+//! `tracing-subscriber`'s JSON format, or its default text format.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use eyre::{Result, WrapErr, bail};
+use facet::Facet;
+use serde_json::Value;
+use tracey_core::{RuleId, parse_rule_id};
+
+/// Name of the field `requirement!` puts the ID in.
+pub const REQUIREMENT_FIELD: &str = "requirement";
+
+/// One `requirement!` event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub requirement: String,
+    /// As the log gives it, if it does
+    pub timestamp: Option<String>,
+}
+
+/// The events of a trace, set against the project's specs.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct TraceReport {
+    /// Number of requirement events read
+    pub events: usize,
+    pub specs: Vec<SpecTrace>,
+    /// IDs events named that no spec defines, in ID order
+    pub unknown: Vec<RequirementTrace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct SpecTrace {
+    pub spec: String,
+    /// Requirements with at least one event, in spec order
+    pub exercised: Vec<RequirementTrace>,
+    /// Requirements without any, in spec order
+    pub never_exercised: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[facet(rename_all = "camelCase")]
+pub struct RequirementTrace {
+    pub id: String,
+    pub count: usize,
+    #[facet(default)]
+    pub first_seen: Option<String>,
+    #[facet(default)]
+    pub last_seen: Option<String>,
+}
+
+/// The requirement events in a log, one event per line. Lines without a
+/// `requirement` field are skipped.
+pub fn parse_trace(content: &str) -> Vec<TraceEvent> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('{')
+                && let Ok(event) = serde_json::from_str::<Value>(line)
+            {
+                parse_json_event(&event)
+            } else {
+                parse_text_event(line)
+            }
+        })
+        .collect()
+}
+
+/// An event in `tracing-subscriber`'s JSON format, where fields are under
+/// `fields` (or at the top level with `flatten_event`).
+fn parse_json_event(event: &Value) -> Option<TraceEvent> {
+    let requirement = event
+        .get("fields")
+        .and_then(|fields| fields.get(REQUIREMENT_FIELD))
+        .or_else(|| event.get(REQUIREMENT_FIELD))?
+        .as_str()?;
+    Some(TraceEvent {
+        requirement: requirement.to_string(),
+        timestamp: event
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    })
+}
+
+/// An event in `tracing-subscriber`'s text format:
+/// `2026-10-14T09:12:03.120Z  INFO tracey: requirement exercised requirement="auth.login"`.
+fn parse_text_event(line: &str) -> Option<TraceEvent> {
+    let key = format!("{REQUIREMENT_FIELD}=");
+    let start = line
+        .match_indices(&key)
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || !line[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))?;
+    let value = &line[start + key.len()..];
+    let requirement = match value.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => value.split_whitespace().next()?,
+    };
+    if requirement.is_empty() {
+        return None;
+    }
+    let timestamp = line
+        .split_whitespace()
+        .next()
+        .filter(|first| first.starts_with(|c: char| c.is_ascii_digit()) && first.contains(':'));
+    Some(TraceEvent {
+        requirement: requirement.to_string(),
+        timestamp: timestamp.map(str::to_string),
+    })
+}
+
+/// Count `events` against the rules of each spec, as `(spec name, rule
+/// IDs in spec order)`. An event counts for the rules with its ID's base,
+/// whatever their version, since code rarely names one.
+pub fn correlate(specs: &[(String, Vec<RuleId>)], events: &[TraceEvent]) -> TraceReport {
+    let mut by_base: BTreeMap<String, RequirementTrace> = BTreeMap::new();
+    for event in events {
+        let base = parse_rule_id(&event.requirement)
+            .map(|id| id.base)
+            .unwrap_or_else(|| event.requirement.clone());
+        let trace = by_base.entry(base.clone()).or_insert(RequirementTrace {
+            id: base,
+            count: 0,
+            first_seen: None,
+            last_seen: None,
+        });
+        trace.count += 1;
+        if let Some(timestamp) = &event.timestamp {
+            if trace
+                .first_seen
+                .as_ref()
+                .is_none_or(|first| timestamp < first)
+            {
+                trace.first_seen = Some(timestamp.clone());
+            }
+            if trace.last_seen.as_ref().is_none_or(|last| timestamp > last) {
+                trace.last_seen = Some(timestamp.clone());
+            }
+        }
+    }
+
+    let mut known = std::collections::HashSet::new();
+    let specs = specs
+        .iter()
+        .map(|(spec, rules)| {
+            let mut exercised = Vec::new();
+            let mut never_exercised = Vec::new();
+            for rule in rules {
+                known.insert(rule.base.clone());
+                match by_base.get(&rule.base) {
+                    Some(trace) => exercised.push(RequirementTrace {
+                        id: rule.to_string(),
+                        ..trace.clone()
+                    }),
+                    None => never_exercised.push(rule.to_string()),
+                }
+            }
+            SpecTrace {
+                spec: spec.clone(),
+                exercised,
+                never_exercised,
+            }
+        })
+        .collect();
+    TraceReport {
+        events: events.len(),
+        specs,
+        unknown: by_base
+            .into_values()
+            .filter(|trace| !known.contains(&trace.id))
+            .collect(),
+    }
+}
+
+/// Read the log at `trace` and set its events against the specs of
+/// `config`.
+pub async fn ingest(
+    project_root: &Path,
+    config: &crate::config::Config,
+    trace: &Path,
+) -> Result<TraceReport> {
+    let content = std::fs::read_to_string(trace)
+        .wrap_err_with(|| format!("Failed to read {}", trace.display()))?;
+    let events = parse_trace(&content);
+    if events.is_empty() {
+        bail!(
+            "No `{REQUIREMENT_FIELD}` events found in {}; are they logged at INFO for the `tracey` target?",
+            trace.display()
+        );
+    }
+    let mut specs = Vec::new();
+    for spec in &config.specs {
+        let patterns: Vec<&str> = spec.include.iter().map(String::as_str).collect();
+        let rules = crate::load_rules_from_globs(project_root, &patterns, true).await?;
+        let ids = rules
+            .iter()
+            .filter_map(|rule| parse_rule_id(&rule.def.id.to_string()))
+            .collect();
+        specs.push((spec.name.clone(), ids));
+    }
+    Ok(correlate(&specs, &events))
+}
+
+/// The report as text, a section per spec.
+pub fn format_report(report: &TraceReport) -> String {
+    let mut out = format!("{} requirement event(s)\n", report.events);
+    for spec in &report.specs {
+        let total = spec.exercised.len() + spec.never_exercised.len();
+        out.push_str(&format!(
+            "\n{}: {} of {} requirement(s) exercised\n",
+            spec.spec,
+            spec.exercised.len(),
+            total
+        ));
+        for trace in &spec.exercised {
+            out.push_str(&format!(
+                "  {}  {}×{}\n",
+                trace.id,
+                trace.count,
+                seen(trace)
+            ));
+        }
+        if !spec.never_exercised.is_empty() {
+            out.push_str("  never exercised:\n");
+            for id in &spec.never_exercised {
+                out.push_str(&format!("    {id}\n"));
+            }
+        }
+    }
+    if !report.unknown.is_empty() {
+        out.push_str("\nNot defined by any spec:\n");
+        for trace in &report.unknown {
+            out.push_str(&format!(
+                "  {}  {}×{}\n",
+                trace.id,
+                trace.count,
+                seen(trace)
+            ));
+        }
+    }
+    out
+}
+
+fn seen(trace: &RequirementTrace) -> String {
+    match (&trace.first_seen, &trace.last_seen) {
+        (Some(first), Some(last)) if first == last => format!(" at {first}"),
+        (Some(first), Some(last)) => format!(" from {first} to {last}"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // r[verify cli.trace-ingest]
+    #[test]
+    fn counts_events_per_requirement() {
+        let log = r#"
+{"timestamp":"2026-10-14T09:12:03Z","level":"INFO","fields":{"message":"requirement exercised","requirement":"auth.login"},"target":"tracey"}
+{"timestamp":"2026-10-14T09:12:04Z","level":"INFO","fields":{"message":"request served"},"target":"server"}
+2026-10-14T09:13:00Z  INFO tracey: requirement exercised requirement="auth.login"
+2026-10-14T09:14:00Z  INFO tracey: requirement exercised requirement="auth.gone"
+  INFO tracey: requirement exercised some_requirement="not.this"
+"#;
+        let events = parse_trace(log);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[1].requirement, "auth.login");
+        assert_eq!(events[1].timestamp.as_deref(), Some("2026-10-14T09:13:00Z"));
+
+        let specs = vec![(
+            "auth".to_string(),
+            vec![
+                parse_rule_id("auth.login+2").unwrap(),
+                parse_rule_id("auth.logout").unwrap(),
+            ],
+        )];
+        let report = correlate(&specs, &events);
+        assert_eq!(report.events, 3);
+        assert_eq!(
+            report.specs[0].exercised,
+            [RequirementTrace {
+                id: "auth.login+2".to_string(),
+                count: 2,
+                first_seen: Some("2026-10-14T09:12:03Z".to_string()),
+                last_seen: Some("2026-10-14T09:13:00Z".to_string()),
+            }]
+        );
+        assert_eq!(report.specs[0].never_exercised, ["auth.logout"]);
+        assert_eq!(report.unknown.len(), 1);
+        assert_eq!(report.unknown[0].id, "auth.gone");
+
+        let text = format_report(&report);
+        assert!(text.contains("auth: 1 of 2 requirement(s) exercised"));
+        assert!(
+            text.contains("auth.login+2  2× from 2026-10-14T09:12:03Z to 2026-10-14T09:13:00Z")
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Platform and feature-flag variants of an implementation.
+//!
+//! r[impl config.impl.variants]
+//!
+//! An impl compiled differently per platform lists its variants, each with
+//! the files only built for it. A reference counts for every variant whose
+//! files contain it; files that belong to no variant are shared and count
+//! for all of them. That way `tracey query matrix` can tell a rule
+//! implemented in shared code from one implemented for Linux only.
+
+use eyre::{Result, WrapErr};
+use globset::{Glob, GlobMatcher};
+use tracey_api::{ApiImplVariant, ApiRule};
+use tracey_proto::MatrixCell;
+
+/// Compiled variants of one impl, in config order.
+#[derive(Debug, Default)]
+pub struct Variants {
+    variants: Vec<(String, Vec<GlobMatcher>)>,
+}
+
+impl Variants {
+    /// The variants of `impl_name` among all of a spec's variants.
+    pub fn new(variants: &[ApiImplVariant], impl_name: &str) -> Result<Self> {
+        let variants = variants
+            .iter()
+            .filter(|v| v.impl_name == impl_name)
+            .map(|v| {
+                let globs = v
+                    .include
+                    .iter()
+                    .map(|pattern| {
+                        Glob::new(pattern)
+                            .map(|glob| glob.compile_matcher())
+                            .wrap_err_with(|| {
+                                format!(
+                                    "Invalid include pattern {:?} of variant {}",
+                                    pattern, v.name
+                                )
+                            })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((v.name.clone(), globs))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { variants })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.variants.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Whether a reference in `file` counts for `variant`.
+    fn counts_for(&self, file: &str, variant: &str) -> bool {
+        let mut matched = self
+            .variants
+            .iter()
+            .filter(|(_, globs)| globs.iter().any(|g| g.is_match(file)))
+            .peekable();
+        matched.peek().is_none() || matched.any(|(name, _)| name == variant)
+    }
+
+    /// Reference counts of `rule` as seen by `variant`.
+    pub fn cell(&self, rule: &ApiRule, variant: &str) -> MatrixCell {
+        MatrixCell {
+            impl_refs: rule
+                .impl_refs
+                .iter()
+                .filter(|r| self.counts_for(&r.file, variant))
+                .count(),
+            verify_refs: rule
+                .verify_refs
+                .iter()
+                .filter(|r| self.counts_for(&r.file, variant))
+                .count(),
+            stale_refs: rule
+                .stale_refs
+                .iter()
+                .filter(|r| self.counts_for(&r.file, variant))
+                .count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(name: &str, include: &str) -> ApiImplVariant {
+        ApiImplVariant {
+            impl_name: "rust".to_string(),
+            name: name.to_string(),
+            include: vec![include.to_string()],
+        }
+    }
+
+    // r[verify config.impl.variants]
+    #[test]
+    fn shared_files_count_for_every_variant() {
+        let variants = Variants::new(
+            &[
+                variant("linux", "src/sys/linux/**"),
+                variant("windows", "src/sys/windows/**"),
+            ],
+            "rust",
+        )
+        .unwrap();
+        assert_eq!(variants.names().collect::<Vec<_>>(), ["linux", "windows"]);
+
+        assert!(variants.counts_for("src/lib.rs", "linux"));
+        assert!(variants.counts_for("src/lib.rs", "windows"));
+        assert!(variants.counts_for("src/sys/linux/fs.rs", "linux"));
+        assert!(!variants.counts_for("src/sys/linux/fs.rs", "windows"));
+
+        assert!(Variants::new(&[variant("linux", "src/[")], "rust").is_err());
+        assert!(
+            Variants::new(&[variant("linux", "src/[")], "ts")
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
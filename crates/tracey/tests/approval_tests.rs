@@ -0,0 +1,114 @@
+//! Integration tests for `tracey approve`.
+//!
+//! Each test creates a git repository with a spec file and an SSH key that
+//! may sign as `alice` in a temp directory, and records approvals through
+//! the library API.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tracey::approvals::{ALLOWED_SIGNERS, Approvals, approvals_dir, approve};
+use tracey::config::{Config, SpecConfig};
+use tracey_core::parse_rule_id;
+
+const SPEC: &str = "\
+# Spec
+
+r[auth.login+3]
+Users MUST provide valid credentials and MFA to log in.
+";
+
+fn setup(root: &Path) -> (Config, PathBuf) {
+    let status = Command::new("git")
+        .args(["init", "--initial-branch=main"])
+        .current_dir(root)
+        .status()
+        .expect("git not found");
+    assert!(status.success());
+    fs::write(root.join("spec.md"), SPEC).unwrap();
+
+    let key = root.join("id_ed25519");
+    let status = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status()
+        .expect("ssh-keygen not found");
+    assert!(status.success());
+    let public = fs::read_to_string(key.with_extension("pub")).unwrap();
+    let allowed_signers = root.join(ALLOWED_SIGNERS);
+    fs::create_dir_all(allowed_signers.parent().unwrap()).unwrap();
+    fs::write(allowed_signers, format!("alice {}", public)).unwrap();
+
+    let config = Config {
+        specs: vec![SpecConfig {
+            name: "test".to_string(),
+            prefix: None,
+            source_url: None,
+            include: vec!["spec.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: None,
+            impls: vec![],
+        }],
+        ..Config::default()
+    };
+    (config, key)
+}
+
+// r[verify cli.approve]
+#[tokio::test]
+async fn test_approval_lapses_when_the_rule_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let (config, key) = setup(root);
+    let id = parse_rule_id("auth.login+3").unwrap();
+
+    let (approval, path) = approve(root, &config, &id, "alice", &key).await.unwrap();
+    assert_eq!(approval.rule, "auth.login+3");
+    assert!(approval.signature.is_some());
+    assert_eq!(path, approvals_dir(root).join("auth.login.json"));
+    approve(root, &config, &id, "alice", &key).await.unwrap();
+
+    let text = "Users MUST provide valid credentials and MFA to log in.";
+    let approvals = Approvals::load(root).unwrap();
+    let approved = approvals.of(&id, text);
+    assert_eq!(approved.len(), 1, "approving twice records one approval");
+    assert_eq!(approved[0].by, "alice");
+    assert!(approvals.of(&id, "Users MUST log in.").is_empty());
+    let next = parse_rule_id("auth.login+4").unwrap();
+    assert!(approvals.of(&next, text).is_empty());
+
+    // Signed by a key that isn't allowed to sign as bob.
+    approve(root, &config, &id, "bob", &key).await.unwrap();
+    let approvals = Approvals::load(root).unwrap();
+    assert_eq!(
+        approvals.of(&id, text).len(),
+        1,
+        "bob's approval is not trusted"
+    );
+}
+
+#[tokio::test]
+async fn test_only_the_current_version_can_be_approved() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let (config, key) = setup(root);
+
+    let old = parse_rule_id("auth.login+2").unwrap();
+    let error = approve(root, &config, &old, "alice", &key)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("auth.login+3"), "{error}");
+
+    let unknown = parse_rule_id("auth.logout").unwrap();
+    let error = approve(root, &config, &unknown, "alice", &key)
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("Unknown rule auth.logout"),
+        "{error}"
+    );
+    assert!(!approvals_dir(root).exists());
+}
@@ -0,0 +1,139 @@
+//! Integration tests for `tracey bisect`.
+//!
+//! Each test builds a small git history in a temp directory in which a rule
+//! loses its coverage at a known commit, then bisects it via the library API.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use tracey::bisect::{BisectRequest, RuleState, bisect};
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git not found");
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Write `src/lib.rs` and commit everything, returning the commit hash.
+fn commit_source(dir: &Path, source: &str, message: &str) -> String {
+    fs::write(dir.join("src/lib.rs"), source).unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", message]);
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+const CONFIG: &str = "\
+specs (
+  {
+    name test
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+";
+
+const SPEC: &str = "\
+# Spec
+
+r[auth.login]
+Users MUST provide valid credentials to log in.
+
+r[auth.logout]
+Users MUST be able to log out.
+";
+
+fn init_repo(dir: &Path) {
+    git(dir, &["init", "-q", "--initial-branch=main"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+    git(dir, &["config", "user.name", "Test"]);
+    fs::create_dir_all(dir.join(".config/tracey")).unwrap();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join(".config/tracey/config.styx"), CONFIG).unwrap();
+    fs::write(dir.join("spec.md"), SPEC).unwrap();
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+// r[verify cli.bisect]
+#[tokio::test]
+async fn test_bisect_finds_commit_that_dropped_reference() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    init_repo(root);
+
+    let both = "// r[impl auth.login]\nfn login() {}\n// r[impl auth.logout]\nfn logout() {}\n";
+    let logout_only = "fn login() {}\n// r[impl auth.logout]\nfn logout() {}\n";
+    commit_source(root, both, "add auth");
+    commit_source(root, &format!("{both}// more\n"), "unrelated change");
+    let culprit = commit_source(root, logout_only, "refactor login");
+    commit_source(root, &format!("{logout_only}// more\n"), "another change");
+    let head = commit_source(
+        root,
+        &format!("{logout_only}// more\n// again\n"),
+        "yet another",
+    );
+
+    let outcome = bisect(BisectRequest {
+        project_root: root,
+        config: Path::new(".config/tracey/config.styx"),
+        rule_id: "auth.login",
+        spec: None,
+        impl_name: None,
+        good: None,
+        bad: "HEAD",
+    })
+    .await
+    .expect("bisect should find the culprit");
+
+    assert_eq!(outcome.commit, culprit);
+    assert_eq!(outcome.state, RuleState::Uncovered);
+    assert!(
+        outcome.summary.contains("refactor login"),
+        "{}",
+        outcome.summary
+    );
+
+    // The user's checkout is untouched and the worktree is gone.
+    assert_eq!(git(root, &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(root, &["worktree", "list"]).lines().count(), 1);
+}
+
+#[tokio::test]
+async fn test_bisect_refuses_covered_rule() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    init_repo(root);
+    commit_source(root, "// r[impl auth.login]\nfn login() {}\n", "add login");
+
+    let result = bisect(BisectRequest {
+        project_root: root,
+        config: Path::new(".config/tracey/config.styx"),
+        rule_id: "auth.login",
+        spec: None,
+        impl_name: None,
+        good: None,
+        bad: "HEAD",
+    })
+    .await;
+
+    let error = result
+        .expect_err("a covered rule has nothing to bisect")
+        .to_string();
+    assert!(error.contains("nothing to bisect"), "{error}");
+}
@@ -8,7 +8,11 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
-use tracey::bump::{bump, detect_changed_rules, pre_commit};
+use tracey::bump::{
+    NoBumpExemption, Target, bump, bump_interactive, bump_rules, bump_rules_in,
+    detect_broken_references, detect_changed_rules, detect_removed_rules, git_capture, pre_commit,
+    pre_commit_with, pre_push,
+};
 use tracey::config::{Config, SpecConfig};
 
 // ============================================================================
@@ -63,8 +67,21 @@ fn simple_config() -> Config {
             prefix: None,
             source_url: None,
             include: vec!["spec.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: None,
             impls: vec![],
         }],
+        plugins: vec![],
+        owners: vec![],
+        version_scheme: None,
+        jira: None,
+        follow_links: None,
+        file_limits: None,
+        spec_lint: None,
+        editor_links: None,
+        query: None,
     }
 }
 
@@ -344,7 +361,18 @@ async fn test_no_config_is_noop() {
     fs::write(root.join("spec.md"), &modified).unwrap();
     git_add(root, "spec.md");
 
-    let empty_config = Config { specs: vec![] };
+    let empty_config = Config {
+        specs: vec![],
+        plugins: vec![],
+        owners: vec![],
+        version_scheme: None,
+        jira: None,
+        follow_links: None,
+        file_limits: None,
+        spec_lint: None,
+        editor_links: None,
+        query: None,
+    };
     let changes = detect_changed_rules(root, &empty_config).await.unwrap();
     assert!(changes.is_empty(), "empty config should produce no changes");
 }
@@ -427,8 +455,21 @@ async fn test_renamed_spec_file_not_flagged() {
             prefix: None,
             source_url: None,
             include: vec!["**/*.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: None,
             impls: vec![],
         }],
+        plugins: vec![],
+        owners: vec![],
+        version_scheme: None,
+        jira: None,
+        follow_links: None,
+        file_limits: None,
+        spec_lint: None,
+        editor_links: None,
+        query: None,
     };
     let changes = detect_changed_rules(root, &wildcard_config).await.unwrap();
     assert!(
@@ -526,3 +567,628 @@ async fn test_bump_then_pre_commit_passes() {
     let passed = pre_commit(root, &config).await.unwrap();
     assert!(passed, "pre-commit should pass after bump");
 }
+
+/// The changelog lists each bumped rule with a diff of its text and is staged.
+// r[verify cli.bump.changelog]
+#[tokio::test]
+async fn test_bump_writes_changelog() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let modified = INITIAL_SPEC.replace(
+        "Users MUST provide valid credentials to log in.",
+        "Users MUST provide valid credentials and MFA to log in.",
+    );
+    fs::write(root.join("spec.md"), &modified).unwrap();
+    git_add(root, "spec.md");
+
+    let config = simple_config();
+    let bumped = bump_rules(root, &config).await.unwrap();
+    assert_eq!(bumped.len(), 1);
+    assert_eq!(bumped[0].old_id.version, 1);
+    assert_eq!(bumped[0].new_id.version, 2);
+
+    let path = tracey::changelog::update(root, "2026-01-01", &bumped).unwrap();
+    let changelog = fs::read_to_string(&path).unwrap();
+    assert!(
+        changelog.starts_with("# Spec changelog\n\n## 2026-01-01\n\n### `auth.login+2`\n"),
+        "{changelog}"
+    );
+    assert!(
+        changelog.contains("`spec.md` (was `auth.login`)"),
+        "{changelog}"
+    );
+    assert!(
+        changelog.contains("**"),
+        "diff should mark the added words:\n{changelog}"
+    );
+    assert!(changelog.contains("MFA"), "{changelog}");
+
+    let staged = Command::new("git")
+        .args(["diff", "--cached", "--name-only"])
+        .current_dir(root)
+        .output()
+        .expect("git not found");
+    let staged = String::from_utf8(staged.stdout).unwrap();
+    assert!(
+        staged.lines().any(|f| f == "SPEC-CHANGELOG.md"),
+        "changelog should be staged, got:\n{staged}"
+    );
+}
+
+/// With `version_scheme date`, bumped rules get the current date as version,
+/// written with dashes.
+// r[verify config.version-scheme]
+#[tokio::test]
+async fn test_bump_with_date_scheme() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let modified = INITIAL_SPEC.replace(
+        "Users MUST provide valid credentials to log in.",
+        "Users MUST provide valid credentials and MFA to log in.",
+    );
+    fs::write(root.join("spec.md"), &modified).unwrap();
+    git_add(root, "spec.md");
+
+    let mut config = simple_config();
+    config.version_scheme = Some("date".to_string());
+    let bumped = bump(root, &config).await.unwrap();
+
+    let today = tracey::changelog::today();
+    assert_eq!(bumped.len(), 1);
+    assert_eq!(bumped[0].version.to_string(), today.replace('-', ""));
+    let content = fs::read_to_string(root.join("spec.md")).unwrap();
+    assert!(
+        content.contains(&format!("r[auth.login+{today}]")),
+        "expected date-versioned marker, got:\n{content}"
+    );
+}
+
+/// With `version_scheme free-form`, bump refuses to pick versions.
+#[tokio::test]
+async fn test_bump_with_free_form_scheme_is_manual() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let modified = INITIAL_SPEC.replace(
+        "Sessions MUST expire after 24 hours of inactivity.",
+        "Sessions MUST expire after 8 hours of inactivity.",
+    );
+    fs::write(root.join("spec.md"), &modified).unwrap();
+    git_add(root, "spec.md");
+
+    let mut config = simple_config();
+    config.version_scheme = Some("free-form".to_string());
+    let error = bump(root, &config).await.unwrap_err().to_string();
+    assert!(error.contains("auth.session"), "{error}");
+    assert!(error.contains("free-form"), "{error}");
+
+    // Nothing was rewritten.
+    let content = fs::read_to_string(root.join("spec.md")).unwrap();
+    assert_eq!(content, modified);
+}
+
+/// `bump -i` bumps only the rules the user picks, and `pre_commit` accepts
+/// the skipped ones until their text changes again.
+// r[verify cli.bump.interactive]
+#[tokio::test]
+async fn test_interactive_bump_skips_and_bumps() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let edited = INITIAL_SPEC
+        .replace("valid credentials", "correct credentials")
+        .replace("24 hours", "8 hours");
+    fs::write(root.join("spec.md"), &edited).unwrap();
+    git_add(root, "spec.md");
+
+    let config = simple_config();
+    let mut input = "what\ns\nb\n".as_bytes();
+    let mut output = Vec::new();
+    let bumped = bump_interactive(root, &config, &mut input, &mut output)
+        .await
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("auth.login (spec.md:3)"), "{output}");
+    assert!(output.contains("Please answer b, s, e or q."), "{output}");
+    assert!(output.contains("Bump to auth.session+2?"), "{output}");
+    assert_eq!(bumped.len(), 1);
+    assert_eq!(bumped[0].new_id.base, "auth.session");
+
+    let content = fs::read_to_string(root.join("spec.md")).unwrap();
+    assert!(content.contains("r[auth.login]\n"), "{content}");
+    assert!(content.contains("r[auth.session+2]"), "{content}");
+    assert!(
+        pre_commit(root, &config).await.unwrap(),
+        "the skipped change should not block the commit"
+    );
+
+    // Changing the skipped rule's text again needs a new decision.
+    fs::write(
+        root.join("spec.md"),
+        content.replace("to log in", "to sign in"),
+    )
+    .unwrap();
+    git_add(root, "spec.md");
+    assert!(!pre_commit(root, &config).await.unwrap());
+}
+
+/// An exempted rule passes `pre_commit_with` unbumped and is logged in the
+/// changelog with its reason; other changed rules still fail the check.
+// r[verify cli.pre-commit.no-bump]
+#[tokio::test]
+async fn test_no_bump_exemption_is_logged() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let typo = INITIAL_SPEC.replace("valid credentials", "valid credentails");
+    fs::write(root.join("spec.md"), &typo).unwrap();
+    git_add(root, "spec.md");
+
+    let config = simple_config();
+    let typo_fix = NoBumpExemption::new("auth.login", "typo");
+    assert!(
+        pre_commit_with(root, &config, &[typo_fix.clone()])
+            .await
+            .unwrap()
+    );
+    let changelog = fs::read_to_string(root.join("SPEC-CHANGELOG.md")).unwrap();
+    assert!(
+        changelog.contains("### `auth.login` (not bumped)\n\n`spec.md`: typo\n"),
+        "{changelog}"
+    );
+    let staged = git_capture(root, &["diff", "--cached", "--name-only"]).unwrap();
+    assert!(staged.contains("SPEC-CHANGELOG.md"), "{staged}");
+
+    // Re-running (say, after an aborted commit) doesn't log it twice.
+    pre_commit_with(root, &config, &[typo_fix.clone()])
+        .await
+        .unwrap();
+    let again = fs::read_to_string(root.join("SPEC-CHANGELOG.md")).unwrap();
+    assert_eq!(again, changelog);
+
+    fs::write(root.join("spec.md"), typo.replace("24 hours", "8 hours")).unwrap();
+    git_add(root, "spec.md");
+    assert!(!pre_commit_with(root, &config, &[typo_fix]).await.unwrap());
+}
+
+/// Newly staged references to unknown rules or to versions that don't exist
+/// yet fail the pre-commit check; references already committed don't.
+// r[verify cli.pre-commit.references]
+#[tokio::test]
+async fn test_pre_commit_rejects_new_broken_references() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    // Already broken at HEAD: not this commit's problem.
+    fs::write(
+        root.join("src/old.rs"),
+        "// r[impl auth.gone]\nfn old() {}\n",
+    )
+    .unwrap();
+    git_commit_all(root, "initial");
+
+    let mut config = simple_config();
+    config.specs[0].impls.push(tracey::config::Impl {
+        name: "rust".to_string(),
+        include: vec!["src/**/*.rs".to_string()],
+        exclude: vec![],
+        no_default_excludes: false,
+        test_include: vec![],
+        test_conventions: false,
+        code_units: None,
+        skip_units: None,
+        variants: vec![],
+        policy: None,
+    });
+
+    fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]\n// r[impl auth.lgoin]\n// r[impl auth.session+2]\nfn f() {}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/old.rs"),
+        "// r[impl auth.gone]\nfn old() { todo!() }\n",
+    )
+    .unwrap();
+    git_add(root, "src");
+
+    let broken = detect_broken_references(root, &config).await.unwrap();
+    let found: Vec<(&str, usize)> = broken.iter().map(|b| (b.text.as_str(), b.line)).collect();
+    assert_eq!(
+        found,
+        [("r[impl auth.lgoin]", 2), ("r[impl auth.session+2]", 3)]
+    );
+    assert!(
+        broken[1].problem.contains("only at version 1"),
+        "{broken:?}"
+    );
+    assert!(!pre_commit(root, &config).await.unwrap());
+
+    // Bumping the rule in the same commit makes `auth.session+2` valid.
+    fs::write(
+        root.join("spec.md"),
+        INITIAL_SPEC.replace("r[auth.session]", "r[auth.session+2]"),
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]\n// r[impl auth.session+2]\nfn f() {}\n",
+    )
+    .unwrap();
+    git_add(root, "spec.md");
+    git_add(root, "src");
+    assert!(pre_commit(root, &config).await.unwrap());
+}
+
+/// References to the old ID of a renamed rule still resolve, so adding one
+/// doesn't fail the pre-commit check.
+// r[verify cli.pre-commit.references]
+#[tokio::test]
+async fn test_pre_commit_accepts_references_to_renamed_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(
+        root.join("spec.md"),
+        INITIAL_SPEC.replace("r[auth.login]", "r[auth.sign-in <- auth.login]"),
+    )
+    .unwrap();
+    git_commit_all(root, "initial");
+
+    let mut config = simple_config();
+    config.specs[0].impls.push(tracey::config::Impl {
+        name: "rust".to_string(),
+        include: vec!["src/**/*.rs".to_string()],
+        exclude: vec![],
+        no_default_excludes: false,
+        test_include: vec![],
+        test_conventions: false,
+        code_units: None,
+        skip_units: None,
+        variants: vec![],
+        policy: None,
+    });
+
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]
+// r[impl auth.sign-in]
+// r[impl auth.logn]
+fn f() {}
+",
+    )
+    .unwrap();
+    git_add(root, "src");
+
+    let broken = detect_broken_references(root, &config).await.unwrap();
+    let found: Vec<&str> = broken.iter().map(|b| b.text.as_str()).collect();
+    assert_eq!(found, ["r[impl auth.logn]"]);
+}
+
+/// The reference checks look at the files the scan would: the default
+/// include and excludes, and convention test files.
+// r[verify cli.pre-commit.references]
+#[tokio::test]
+async fn test_pre_commit_checks_the_files_the_scan_does() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    for (path, content) in [
+        ("src/lib.rs", "// r[impl auth.logn]\nfn f() {}\n"),
+        ("target/debug/gen.rs", "// r[impl auth.lgoin]\nfn f() {}\n"),
+        ("tests/login.rs", "// r[verify auth.sesion]\nfn t() {}\n"),
+    ] {
+        fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+        fs::write(root.join(path), content).unwrap();
+        git_add(root, path);
+    }
+    let found = |config: &Config| {
+        let config = config.clone();
+        async move {
+            let broken = detect_broken_references(root, &config).await.unwrap();
+            let mut found: Vec<String> = broken.iter().map(|b| b.text.clone()).collect();
+            found.sort();
+            found
+        }
+    };
+
+    // An empty include means `**/*.rs`, and `target/` is excluded by default.
+    let mut config = simple_config();
+    config.specs[0].impls.push(tracey::config::Impl {
+        name: "rust".to_string(),
+        include: vec![],
+        exclude: vec![],
+        no_default_excludes: false,
+        test_include: vec![],
+        test_conventions: false,
+        code_units: None,
+        skip_units: None,
+        variants: vec![],
+        policy: None,
+    });
+    assert_eq!(
+        found(&config).await,
+        ["r[impl auth.logn]", "r[verify auth.sesion]"]
+    );
+
+    // `test_conventions` adds `tests/`.
+    let imp = &mut config.specs[0].impls[0];
+    imp.include = vec!["src/**/*.rs".to_string()];
+    assert_eq!(found(&config).await, ["r[impl auth.logn]"]);
+    config.specs[0].impls[0].test_conventions = true;
+    assert_eq!(
+        found(&config).await,
+        ["r[impl auth.logn]", "r[verify auth.sesion]"]
+    );
+}
+
+/// Removing a rule that code still references fails the pre-commit check,
+/// unless the rule was deprecated first.
+// r[verify cli.pre-commit.removed-rules]
+#[tokio::test]
+async fn test_pre_commit_rejects_removing_referenced_rule() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]\nfn login() {}\n\n// r[impl auth.session]\nfn session() {}\n",
+    )
+    .unwrap();
+    git_commit_all(root, "initial");
+
+    let mut config = simple_config();
+    config.specs[0].impls.push(tracey::config::Impl {
+        name: "rust".to_string(),
+        include: vec!["src/**/*.rs".to_string()],
+        exclude: vec![],
+        no_default_excludes: false,
+        test_include: vec![],
+        test_conventions: false,
+        code_units: None,
+        skip_units: None,
+        variants: vec![],
+        policy: None,
+    });
+
+    let without_session =
+        "# Spec\n\nr[auth.login]\nUsers MUST provide valid credentials to log in.\n";
+    fs::write(root.join("spec.md"), without_session).unwrap();
+    git_add(root, "spec.md");
+
+    let removed = detect_removed_rules(root, &config).await.unwrap();
+    assert_eq!(removed.len(), 1, "{removed:?}");
+    assert_eq!(removed[0].rule_id.base, "auth.session");
+    assert_eq!(
+        removed[0].references,
+        [(std::path::PathBuf::from("src/lib.rs"), 4)]
+    );
+    assert!(!pre_commit(root, &config).await.unwrap());
+
+    // Moving the rule to another spec file is not a removal.
+    fs::write(
+        root.join("other.md"),
+        "r[auth.session]\nSessions MUST expire after 24 hours of inactivity.\n",
+    )
+    .unwrap();
+    git_add(root, "other.md");
+    config.specs[0].include.push("other.md".to_string());
+    assert!(
+        detect_removed_rules(root, &config)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // A deprecated rule may be removed while references remain.
+    fs::remove_file(root.join("other.md")).unwrap();
+    git_add(root, "other.md");
+    fs::write(
+        root.join("spec.md"),
+        INITIAL_SPEC.replace("r[auth.session]", "r[auth.session status=deprecated]"),
+    )
+    .unwrap();
+    git_commit_all(root, "deprecate auth.session");
+    fs::write(root.join("spec.md"), without_session).unwrap();
+    git_add(root, "spec.md");
+    assert!(
+        detect_removed_rules(root, &config)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+/// Validation names the spec file a still-referenced rule was removed from,
+/// whether or not the removal is staged.
+// r[verify validation.removed]
+#[tokio::test]
+async fn test_validation_reports_removed_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/lib.rs"),
+        "// r[impl auth.login]\nfn login() {}\n\n// r[impl auth.session]\nfn session() {}\n",
+    )
+    .unwrap();
+    git_commit_all(root, "initial");
+
+    let mut config = simple_config();
+    config.specs[0].impls.push(tracey::config::Impl {
+        name: "rust".to_string(),
+        include: vec!["src/**/*.rs".to_string()],
+        exclude: vec![],
+        no_default_excludes: false,
+        test_include: vec![],
+        test_conventions: false,
+        code_units: None,
+        skip_units: None,
+        variants: vec![],
+        policy: None,
+    });
+    fs::write(
+        root.join("spec.md"),
+        "# Spec\n\nr[auth.login]\nUsers MUST provide valid credentials to log in.\n",
+    )
+    .unwrap();
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .unwrap();
+    let errors: Vec<_> = data
+        .validation_by_impl
+        .values()
+        .flat_map(|v| &v.errors)
+        .filter(|e| {
+            e.reference_rule_id
+                .as_ref()
+                .is_some_and(|id| id.base == "auth.session")
+        })
+        .collect();
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert_eq!(
+        errors[0].code,
+        tracey_api::ValidationErrorCode::RemovedRequirement
+    );
+    assert!(
+        errors[0].message.contains("spec.md"),
+        "{}",
+        errors[0].message
+    );
+    assert_eq!(errors[0].line, Some(4));
+}
+
+/// `pre_push` checks every commit since the upstream, not just the index,
+/// and honors `Tracey-No-Bump:` trailers.
+// r[verify cli.pre-push]
+#[tokio::test]
+async fn test_pre_push_checks_each_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+    let upstream = git_capture(root, &["rev-parse", "HEAD"]).unwrap();
+    let upstream = upstream.trim();
+    let config = simple_config();
+
+    // An editorial change with a trailer passes.
+    let typo = INITIAL_SPEC.replace("valid credentials", "valid, correct credentials");
+    fs::write(root.join("spec.md"), &typo).unwrap();
+    git_commit_all(root, "Reword login rule\n\nTracey-No-Bump: auth.login");
+    assert!(pre_push(root, &config, upstream, "HEAD").await.unwrap());
+
+    // A later commit that slipped past the pre-commit hook doesn't.
+    fs::write(root.join("spec.md"), typo.replace("24 hours", "8 hours")).unwrap();
+    git_commit_all(root, "Shorten sessions");
+    assert!(!pre_push(root, &config, upstream, "HEAD").await.unwrap());
+    assert!(pre_push(root, &config, upstream, "HEAD~1").await.unwrap());
+
+    // Nothing left to check once that commit is upstream.
+    assert!(pre_push(root, &config, "HEAD", "HEAD").await.unwrap());
+}
+
+/// `--working-tree` bumps unstaged edits on disk and leaves the index alone.
+// r[verify cli.bump.working-tree]
+#[tokio::test]
+async fn test_bump_working_tree_leaves_index_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+
+    let edited = INITIAL_SPEC.replace("24 hours", "8 hours");
+    fs::write(root.join("spec.md"), &edited).unwrap();
+
+    let config = simple_config();
+    assert!(
+        bump_rules(root, &config).await.unwrap().is_empty(),
+        "nothing is staged"
+    );
+    let bumped = bump_rules_in(root, &config, "HEAD", Target::WorkTree)
+        .await
+        .unwrap();
+    assert_eq!(bumped.len(), 1);
+    assert_eq!(bumped[0].new_id.to_string(), "auth.session+2");
+
+    let on_disk = fs::read_to_string(root.join("spec.md")).unwrap();
+    assert_eq!(
+        on_disk,
+        edited.replace("r[auth.session]", "r[auth.session+2]")
+    );
+    let staged = git_capture(root, &["diff", "--cached", "--name-only"]).unwrap();
+    assert!(staged.trim().is_empty(), "{staged}");
+}
+
+/// `--since` compares against an older revision, for a branch of commits
+/// that will be squashed.
+#[tokio::test]
+async fn test_bump_since_revision() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+
+    git_init(root);
+    fs::write(root.join("spec.md"), INITIAL_SPEC).unwrap();
+    git_commit_all(root, "initial");
+    let base = git_capture(root, &["rev-parse", "HEAD"]).unwrap();
+
+    let edited = INITIAL_SPEC.replace("valid credentials", "correct credentials");
+    fs::write(root.join("spec.md"), &edited).unwrap();
+    git_commit_all(root, "wip 1");
+    fs::write(root.join("src.txt"), "unrelated").unwrap();
+    git_commit_all(root, "wip 2");
+
+    let config = simple_config();
+    assert!(bump_rules(root, &config).await.unwrap().is_empty());
+    let bumped = bump_rules_in(root, &config, base.trim(), Target::Index)
+        .await
+        .unwrap();
+    assert_eq!(bumped.len(), 1);
+    assert_eq!(bumped[0].new_id.to_string(), "auth.login+2");
+
+    let staged = git_capture(root, &["diff", "--cached", "--name-only"]).unwrap();
+    assert_eq!(staged.trim(), "spec.md");
+}
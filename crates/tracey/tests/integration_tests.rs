@@ -135,6 +135,54 @@ async fn test_status_coverage_percentages() {
     }
 }
 
+// r[verify daemon.roam.batch]
+#[tokio::test]
+async fn test_batch_answers_like_single_queries() {
+    let service = create_test_service().await;
+    let uncovered = UncoveredRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+        prefix: None,
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
+    };
+    let stale = StaleRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+        prefix: None,
+        sort: None,
+        limit: None,
+        offset: None,
+    };
+
+    let batch = rpc(service
+        .client
+        .batch(BatchRequest {
+            status: true,
+            uncovered: vec![uncovered.clone(), uncovered.clone()],
+            stale: vec![stale.clone()],
+            ..Default::default()
+        })
+        .await);
+
+    let status = rpc(service.client.status().await);
+    let batch_status = batch.status.expect("Expected a status answer");
+    assert_eq!(batch_status.impls.len(), status.impls.len());
+    assert!(batch.config.is_none());
+    assert!(batch.health.is_none());
+
+    let single = rpc(service.client.uncovered(uncovered).await);
+    assert_eq!(batch.uncovered.len(), 2);
+    for answer in &batch.uncovered {
+        assert_eq!(answer.uncovered_count, single.uncovered_count);
+    }
+    let single = rpc(service.client.stale(stale).await);
+    assert_eq!(batch.stale.len(), 1);
+    assert_eq!(batch.stale[0].stale_count, single.stale_count);
+}
+
 // ============================================================================
 // Uncovered/Untested API Tests
 // ============================================================================
@@ -146,6 +194,10 @@ async fn test_uncovered_returns_rules() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: None,
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     let response = rpc(service.client.uncovered(req).await);
@@ -166,6 +218,10 @@ async fn test_uncovered_with_prefix_filter() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: Some("auth".to_string()),
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     let response = rpc(service.client.uncovered(req).await);
@@ -182,6 +238,96 @@ async fn test_uncovered_with_prefix_filter() {
     }
 }
 
+// r[verify daemon.cli.query.untested-buckets]
+#[tokio::test]
+async fn test_untested_splits_buckets() {
+    let service = create_test_service().await;
+    let req = |only_implemented| UntestedRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+        prefix: None,
+        sort: None,
+        limit: None,
+        offset: None,
+        only_implemented,
+    };
+    let ids = |by_section: &[SectionRules]| -> Vec<String> {
+        by_section
+            .iter()
+            .flat_map(|section| section.rules.iter().map(|rule| rule.id.to_string()))
+            .collect()
+    };
+
+    let both = rpc(service.client.untested(req(false)).await);
+    // data.format and error.logging have no references at all
+    let unimplemented = ids(&both.unimplemented_by_section);
+    assert!(unimplemented.contains(&"data.format".to_string()));
+    assert!(unimplemented.contains(&"error.logging".to_string()));
+    // auth.session is implemented but not verified
+    assert!(ids(&both.by_section).contains(&"auth.session".to_string()));
+    assert_eq!(
+        both.untested_count,
+        both.implemented_count + unimplemented.len()
+    );
+
+    let implemented = rpc(service.client.untested(req(true)).await);
+    assert!(implemented.unimplemented_by_section.is_empty());
+    assert_eq!(implemented.untested_count, both.implemented_count);
+    assert_eq!(ids(&implemented.by_section), ids(&both.by_section));
+}
+
+// r[verify daemon.cli.query.window]
+#[tokio::test]
+async fn test_uncovered_sort_limit_offset() {
+    let service = create_test_service().await;
+    let page = |sort, limit, offset| UncoveredRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+        prefix: None,
+        owner: None,
+        sort,
+        limit,
+        offset,
+    };
+    let ids = |response: &UncoveredResponse| -> Vec<String> {
+        response
+            .by_section
+            .iter()
+            .flat_map(|section| section.rules.iter().map(|rule| rule.id.to_string()))
+            .collect()
+    };
+
+    let all = rpc(service
+        .client
+        .uncovered(page(Some(RuleListSort::Id), None, None))
+        .await);
+    let all_ids = ids(&all);
+    assert_eq!(all_ids.len(), all.uncovered_count);
+    assert!(
+        all_ids.len() >= 2,
+        "fixture should have several uncovered rules"
+    );
+    let mut sorted = all_ids.clone();
+    sorted.sort();
+    assert_eq!(all_ids, sorted, "--sort id should order rules by ID");
+
+    let second = rpc(service
+        .client
+        .uncovered(page(Some(RuleListSort::Id), Some(1), Some(1)))
+        .await);
+    assert_eq!(ids(&second), vec![all_ids[1].clone()]);
+    assert_eq!(
+        second.uncovered_count, all.uncovered_count,
+        "count should cover the full list, not the page"
+    );
+
+    let past_end = rpc(service
+        .client
+        .uncovered(page(None, None, Some(all.uncovered_count as u32)))
+        .await);
+    assert!(ids(&past_end).is_empty());
+}
+
 #[tokio::test]
 async fn test_untested_returns_rules() {
     let service = create_test_service().await;
@@ -189,6 +335,10 @@ async fn test_untested_returns_rules() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: None,
+        sort: None,
+        limit: None,
+        offset: None,
+        only_implemented: false,
     };
 
     let response = rpc(service.client.untested(req).await);
@@ -199,6 +349,64 @@ async fn test_untested_returns_rules() {
     assert!(response.untested_count > 0, "Expected some untested rules");
 }
 
+// ============================================================================
+// Unmapped API Tests
+// ============================================================================
+
+// r[verify dashboard.api.unmapped]
+#[tokio::test]
+async fn test_unmapped_lists_the_units_of_a_file() {
+    let temp = common::create_temp_project();
+    let project_root = temp.path().to_path_buf();
+    std::fs::write(
+        project_root.join("src/plain.rs"),
+        r#"pub fn bare() {}
+
+pub struct Plain {
+    pub x: u32,
+}
+
+// r[impl auth.login]
+pub fn covered() {}
+"#,
+    )
+    .expect("Failed to write source");
+    let engine = Arc::new(
+        tracey::daemon::Engine::new(project_root.clone(), project_root.join("config.styx"))
+            .await
+            .expect("Failed to create engine"),
+    );
+    let service = common::create_test_rpc_service(tracey::daemon::TraceyService::new(engine)).await;
+
+    let response = rpc(service
+        .client
+        .unmapped(UnmappedRequest {
+            spec: Some("test".to_string()),
+            impl_name: Some("rust".to_string()),
+            path: Some("src/plain.rs".to_string()),
+        })
+        .await);
+    assert_eq!(response.total_units, 3);
+    assert_eq!(response.unmapped_count, 2);
+    assert_eq!(response.entries.len(), 1, "{:?}", response.entries);
+    let entry = &response.entries[0];
+    assert_eq!(entry.path, "src/plain.rs");
+    assert!(!entry.is_dir);
+    assert_eq!((entry.total_units, entry.unmapped_units), (3, 2));
+    let units: Vec<(&str, Option<&str>, usize, usize)> = entry
+        .units
+        .iter()
+        .map(|u| (u.kind.as_str(), u.name.as_deref(), u.start_line, u.end_line))
+        .collect();
+    assert_eq!(
+        units,
+        [
+            ("function", Some("bare"), 1, 1),
+            ("struct", Some("Plain"), 3, 5),
+        ]
+    );
+}
+
 // ============================================================================
 // Rule Details API Tests
 // ============================================================================
@@ -337,6 +545,40 @@ specs (
     );
 }
 
+#[tokio::test]
+async fn test_spec_outline_nests_sections_and_places_rules() {
+    let service = create_test_service().await;
+
+    let req = SpecOutlineRequest {
+        spec: Some("test".to_string()),
+        impl_name: Some("rust".to_string()),
+    };
+    let outline = rpc(service.client.spec_outline(req).await);
+
+    let titles: Vec<_> = outline
+        .sections
+        .iter()
+        .map(|s| (s.title.as_str(), s.parent))
+        .collect();
+    assert_eq!(
+        titles,
+        [
+            ("Test Specification", None),
+            ("Authentication", Some(0)),
+            ("Data Validation", Some(0)),
+            ("Error Handling", Some(0)),
+        ]
+    );
+    let auth: Vec<String> = outline.sections[1]
+        .rules
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(auth, ["auth.login", "auth.session", "auth.logout"]);
+    assert_eq!(outline.sections[0].aggregated.total, 8);
+    assert!(outline.unsectioned.is_empty());
+}
+
 #[tokio::test]
 async fn test_spec_content_orders_files_by_weight_then_path() {
     let temp = common::create_temp_project();
@@ -498,6 +740,73 @@ async fn test_lsp_definition() {
     );
 }
 
+// r[verify lsp.ranges.exact]
+// r[verify lsp.goto.precise-location]
+#[tokio::test]
+async fn test_lsp_ranges_span_whole_markers() {
+    let service = create_test_service().await;
+
+    let content = std::fs::read_to_string(fixtures_dir().join("src/lib.rs")).unwrap();
+    let position = LspPositionRequest {
+        path: fixtures_dir().join("src/lib.rs").display().to_string(),
+        content: content.to_string(),
+        line: 4,      // /// r[impl auth.login]
+        character: 8, // inside "auth.login"
+    };
+
+    let definition = rpc(service.client.lsp_definition(position.clone()).await);
+    let definition = &definition[0];
+    assert_eq!(
+        (definition.line, definition.character),
+        (6, 0),
+        "r[auth.login] is on line 7 of spec.md"
+    );
+    assert_eq!((definition.end_line, definition.end_character), (6, 13));
+
+    let highlights = rpc(service
+        .client
+        .lsp_document_highlight(position.clone())
+        .await);
+    assert_eq!(highlights.len(), 1);
+    assert_eq!(
+        (highlights[0].character, highlights[0].end_character),
+        (4, 22),
+        "Highlight should span r[impl auth.login]"
+    );
+
+    let references = rpc(service
+        .client
+        .lsp_references(LspReferencesRequest {
+            path: position.path.clone(),
+            content: position.content.clone(),
+            line: position.line,
+            character: position.character,
+            include_declaration: false,
+        })
+        .await);
+    let in_lib = references
+        .iter()
+        .find(|r| r.path.ends_with("src/lib.rs"))
+        .expect("Expected the impl reference in src/lib.rs");
+    assert_eq!(
+        (
+            in_lib.line,
+            in_lib.character,
+            in_lib.end_line,
+            in_lib.end_character
+        ),
+        (4, 4, 4, 22)
+    );
+    let in_tests = references
+        .iter()
+        .find(|r| r.path.ends_with("src/tests.rs"))
+        .expect("Expected a verify reference in src/tests.rs");
+    assert_eq!(
+        in_tests.end_character - in_tests.character,
+        "r[verify auth.login]".len() as u32
+    );
+}
+
 #[tokio::test]
 async fn test_lsp_hover_on_markdown_backtick_reference() {
     let service = create_test_service().await;
@@ -646,6 +955,28 @@ async fn test_lsp_completions() {
     );
 }
 
+#[tokio::test]
+async fn test_lsp_completions_match_typos_best_first() {
+    let service = create_test_service().await;
+
+    let content = "// r[impl auth.logn";
+    let req = LspPositionRequest {
+        path: fixtures_dir().join("src/lib.rs").display().to_string(),
+        content: content.to_string(),
+        line: 0,
+        character: content.len() as u32,
+    };
+
+    let completions = rpc(service.client.lsp_completions(req).await);
+    let rules: Vec<_> = completions
+        .iter()
+        .filter(|c| c.kind == "rule")
+        .map(|c| c.label.as_str())
+        .collect();
+    assert_eq!(rules.first(), Some(&"auth.login"), "got: {:?}", rules);
+    assert!(!rules.contains(&"api.fetch"), "got: {:?}", rules);
+}
+
 #[tokio::test]
 async fn test_lsp_diagnostics_orphaned_reference() {
     let service = create_test_service_named("orphaned").await;
@@ -763,6 +1094,31 @@ async fn test_validate_returns_results() {
     // The fixture has valid data, so should have no errors (or minimal)
 }
 
+// r[verify daemon.roam.validate-all]
+#[tokio::test]
+async fn test_validate_all_matches_validate() {
+    let service = create_test_service().await;
+
+    let results = rpc(service.client.validate_all().await);
+    let status = rpc(service.client.status().await);
+    assert_eq!(results.len(), status.impls.len());
+
+    for (result, impl_status) in results.iter().zip(&status.impls) {
+        assert_eq!(result.spec, impl_status.spec);
+        assert_eq!(result.impl_name, impl_status.impl_name);
+        let single = rpc(service
+            .client
+            .validate(ValidateRequest {
+                spec: Some(result.spec.clone()),
+                impl_name: Some(result.impl_name.clone()),
+            })
+            .await);
+        assert_eq!(result.error_count, single.error_count);
+        assert_eq!(result.warning_count, single.warning_count);
+        assert_eq!(result.errors.len(), single.errors.len());
+    }
+}
+
 // ============================================================================
 // Semantic Tokens Tests
 // ============================================================================
@@ -1231,3 +1587,646 @@ async fn test_test_include_counts_toward_verified() {
         impl_status.verified_rules
     );
 }
+
+/// r[verify config.discovery.nearest]
+/// r[verify config.discovery.nested]
+#[test]
+fn test_nested_project_discovery() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+    let nested = root.join("packages/api");
+    let deep = nested.join("src/handlers");
+
+    std::fs::create_dir_all(root.join(".config/tracey")).expect("Failed to create config dir");
+    std::fs::create_dir_all(nested.join(".config/tracey")).expect("Failed to create config dir");
+    std::fs::create_dir_all(&deep).expect("Failed to create src dir");
+    std::fs::write(root.join(tracey::CONFIG_PATH), "specs ()\n").expect("Failed to write config");
+    std::fs::write(nested.join(tracey::CONFIG_PATH), "specs ()\n").expect("Failed to write config");
+
+    assert_eq!(
+        tracey::find_config_root_from(&deep).as_deref(),
+        Some(nested.as_path())
+    );
+    assert_eq!(tracey::find_project_root_from(&deep), nested);
+    assert_eq!(
+        tracey::find_config_root_from(&root.join("packages")).as_deref(),
+        Some(root)
+    );
+    assert_eq!(
+        tracey::discover_projects(root),
+        vec![root.to_path_buf(), nested.clone()]
+    );
+}
+
+/// r[verify config.impl.include.negation]
+#[tokio::test]
+async fn test_include_negation_last_match_wins() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("src/generated")).expect("Failed to create src dir");
+    std::fs::write(
+        root.join("spec.md"),
+        r#"
+> r[neg.handwritten]
+> Handwritten code.
+
+> r[neg.generated]
+> Generated code.
+
+> r[neg.restored]
+> Generated code that is scanned anyway.
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::write(root.join("src/lib.rs"), "// r[impl neg.handwritten]\n")
+        .expect("Failed to write source file");
+    std::fs::write(
+        root.join("src/generated/gen.rs"),
+        "// r[impl neg.generated]\n",
+    )
+    .expect("Failed to write source file");
+    std::fs::write(
+        root.join("src/generated/keep.rs"),
+        "// r[impl neg.restored]\n",
+    )
+    .expect("Failed to write source file");
+
+    let config = tracey::config::parse_config(
+        r#"
+specs (
+  {
+    name neg
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs "!src/generated/**" src/generated/keep.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to parse config");
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build data");
+    let forward = &data.forward_by_impl[&("neg".to_string(), "rust".to_string())];
+    let covered = |id: &str| {
+        forward
+            .rules
+            .iter()
+            .find(|r| r.id.to_string() == id)
+            .is_some_and(|r| !r.impl_refs.is_empty())
+    };
+
+    assert!(covered("neg.handwritten"));
+    assert!(
+        !covered("neg.generated"),
+        "negated pattern should carve out src/generated"
+    );
+    assert!(
+        covered("neg.restored"),
+        "later pattern should re-include keep.rs"
+    );
+}
+
+/// r[verify walk.symlinks]
+/// r[verify config.follow-links]
+#[cfg(unix)]
+#[tokio::test]
+async fn test_symlinks_are_followed_once_and_can_be_turned_off() {
+    use std::os::unix::fs::symlink;
+
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("src/nested")).expect("Failed to create src dir");
+    std::fs::create_dir_all(root.join("checkout")).expect("Failed to create checkout dir");
+    std::fs::write(
+        root.join("spec.md"),
+        r#"
+> r[link.local]
+> Code in the project.
+
+> r[link.shared]
+> Code in a symlinked checkout.
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::write(root.join("src/lib.rs"), "// r[impl link.local]\n")
+        .expect("Failed to write source file");
+    std::fs::write(root.join("checkout/dep.rs"), "// r[impl link.shared]\n")
+        .expect("Failed to write source file");
+    symlink("lib.rs", root.join("src/alias.rs")).expect("Failed to link file");
+    symlink("../checkout", root.join("src/shared")).expect("Failed to link dir");
+    symlink("..", root.join("src/nested/up")).expect("Failed to link loop");
+
+    let config_text = r#"
+specs (
+  {
+    name link
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#;
+    let impl_refs = |data: &tracey::data::DashboardData, id: &str| {
+        data.forward_by_impl[&("link".to_string(), "rust".to_string())]
+            .rules
+            .iter()
+            .find(|r| r.id.to_string() == id)
+            .map_or(0, |r| r.impl_refs.len())
+    };
+
+    let config = tracey::config::parse_config(config_text).expect("Failed to parse config");
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build data");
+    assert_eq!(
+        impl_refs(&data, "link.local"),
+        1,
+        "a file reachable through a link should count once"
+    );
+    assert_eq!(impl_refs(&data, "link.shared"), 1);
+
+    let config = tracey::config::parse_config(&format!("follow_links false\n{config_text}"))
+        .expect("Failed to parse config");
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build data");
+    assert_eq!(impl_refs(&data, "link.local"), 1);
+    assert_eq!(
+        impl_refs(&data, "link.shared"),
+        0,
+        "linked checkouts should not be scanned"
+    );
+
+    let canonical_root = root.canonicalize().expect("Failed to canonicalize root");
+    assert_eq!(
+        tracey_core::canonical_path(&root.join("src/deleted.rs")),
+        canonical_root.join("src/deleted.rs"),
+        "deleted files should keep their canonical directory"
+    );
+}
+
+/// r[verify config.file-limits]
+#[tokio::test]
+async fn test_large_and_binary_files_are_skipped_and_reported() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("src/assets")).expect("Failed to create src dir");
+    std::fs::write(
+        root.join("spec.md"),
+        r#"
+> r[limits.code]
+> Real code.
+
+> r[limits.bundle]
+> Code inside a huge bundle.
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::write(root.join("src/lib.rs"), "// r[impl limits.code]\n")
+        .expect("Failed to write source file");
+    let mut bundle = "// r[impl limits.bundle]\n".to_string();
+    bundle.push_str(&"x".repeat(2 * 1024 * 1024));
+    std::fs::write(root.join("src/bundle.js"), bundle).expect("Failed to write bundle");
+    // An MPEG transport stream that happens to share TypeScript's extension
+    std::fs::write(root.join("src/assets/clip.ts"), b"G@\x00\x10\x00\x00\xb0")
+        .expect("Failed to write clip");
+
+    let config = tracey::config::parse_config(
+        r#"
+file_limits {
+  max_file_mb 1
+}
+specs (
+  {
+    name limits
+    include (spec.md)
+    impls (
+      {
+        name main
+        include (src/**/*.rs src/**/*.js src/**/*.ts)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to parse config");
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build data");
+    let key = ("limits".to_string(), "main".to_string());
+    let forward = &data.forward_by_impl[&key];
+    let covered = |id: &str| {
+        forward
+            .rules
+            .iter()
+            .find(|r| r.id.to_string() == id)
+            .is_some_and(|r| !r.impl_refs.is_empty())
+    };
+    assert!(covered("limits.code"));
+    assert!(
+        !covered("limits.bundle"),
+        "oversized files should not be scanned"
+    );
+
+    let validation = &data.validation_by_impl[&key];
+    let skipped: Vec<(&str, &str)> = validation
+        .errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::SkippedFile)
+        .map(|e| (e.file.as_deref().unwrap_or_default(), e.message.as_str()))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            ("src/assets/clip.ts", "Not scanned: file looks binary"),
+            (
+                "src/bundle.js",
+                "Not scanned: file is 2.0 MB, over the 1 MB limit"
+            ),
+        ]
+    );
+    assert_eq!(validation.error_count, 0, "{:?}", validation.errors);
+    assert_eq!(data.skipped_files_by_impl[&key].len(), 2);
+}
+
+/// r[verify config.impl.test-conventions]
+#[tokio::test]
+async fn test_test_conventions_classify_tests_dir_and_cfg_test_modules() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("src")).expect("Failed to create src dir");
+    std::fs::create_dir_all(root.join("tests")).expect("Failed to create tests dir");
+    std::fs::write(
+        root.join("spec.md"),
+        r#"
+> r[conv.feature]
+> A feature.
+
+> r[conv.misplaced]
+> A feature whose impl annotation sits in a test module.
+"#,
+    )
+    .expect("Failed to write spec");
+    std::fs::write(
+        root.join("src/lib.rs"),
+        r#"// r[impl conv.feature]
+pub fn feature() {}
+
+#[cfg(test)]
+mod tests {
+    // r[verify conv.feature]
+    // r[impl conv.misplaced]
+    #[test]
+    fn works() {}
+}
+"#,
+    )
+    .expect("Failed to write source file");
+    std::fs::write(
+        root.join("tests/feature.rs"),
+        "// r[verify conv.misplaced]\n#[test]\nfn misplaced() {}\n",
+    )
+    .expect("Failed to write test file");
+
+    let config = tracey::config::parse_config(
+        r#"
+specs (
+  {
+    name conv
+    include (spec.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+        test_conventions true
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to parse config");
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build data");
+    let key = ("conv".to_string(), "rust".to_string());
+    let rule = |id: &str| {
+        data.forward_by_impl[&key]
+            .rules
+            .iter()
+            .find(|r| r.id.to_string() == id)
+            .cloned()
+            .expect("rule exists")
+    };
+
+    assert!(!rule("conv.feature").verify_refs.is_empty());
+    assert!(
+        !rule("conv.misplaced").verify_refs.is_empty(),
+        "tests/ files should be scanned without a test_include pattern"
+    );
+
+    let errors = &data.validation_by_impl[&key].errors;
+    let impl_in_test: Vec<_> = errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::ImplInTestFile)
+        .collect();
+    assert_eq!(impl_in_test.len(), 1, "{errors:?}");
+    assert_eq!(
+        impl_in_test[0].related_rules[0].to_string(),
+        "conv.misplaced"
+    );
+}
+
+/// r[verify validation.duplicates.locations]
+#[tokio::test]
+async fn test_duplicate_definitions_across_files_report_both_locations() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("docs")).expect("Failed to create docs dir");
+    std::fs::create_dir_all(root.join("src")).expect("Failed to create src dir");
+    std::fs::write(
+        root.join("docs/auth.md"),
+        "# Auth\n\n> r[auth.login]\n> Users log in.\n",
+    )
+    .expect("Failed to write spec");
+    std::fs::write(
+        root.join("docs/auth-copy.md"),
+        "# Auth (copy)\n\n\n> r[auth.login]\n> Users log in, restructured.\n",
+    )
+    .expect("Failed to write spec");
+    std::fs::write(root.join("src/lib.rs"), "// r[impl auth.login]\n")
+        .expect("Failed to write source file");
+
+    let config = tracey::config::parse_config(
+        r#"
+specs (
+  {
+    name auth
+    include (docs/**/*.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to parse config");
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Duplicate definitions should not abort the build");
+    let errors = &data.validation_by_impl[&("auth".to_string(), "rust".to_string())].errors;
+    let duplicates: Vec<_> = errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::DuplicateRequirement)
+        .collect();
+
+    assert_eq!(duplicates.len(), 1, "{errors:?}");
+    assert!(
+        duplicates[0].message.contains("docs/auth.md:3"),
+        "{}",
+        duplicates[0].message
+    );
+    assert!(
+        duplicates[0].message.contains("docs/auth-copy.md:4"),
+        "{}",
+        duplicates[0].message
+    );
+}
+
+// r[verify validation.duplicate-refs]
+// r[verify lsp.diagnostics.duplicate-ref]
+#[tokio::test]
+async fn test_duplicate_references_within_code_unit_are_warnings() {
+    let temp = tempfile::tempdir().expect("Failed to create temp dir");
+    let root = temp.path();
+
+    std::fs::create_dir_all(root.join("docs")).expect("Failed to create docs dir");
+    std::fs::create_dir_all(root.join("src")).expect("Failed to create src dir");
+    std::fs::write(
+        root.join("docs/auth.md"),
+        "# Auth\n\n> r[auth.login]\n> Users log in.\n",
+    )
+    .expect("Failed to write spec");
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "\
+/// r[impl auth.login]
+/// r[verify auth.login]
+pub fn login() {
+    // r[impl auth.login]
+}
+
+/// r[impl auth.login]
+pub fn logout() {}
+",
+    )
+    .expect("Failed to write source file");
+
+    let config = tracey::config::parse_config(
+        r#"
+specs (
+  {
+    name auth
+    include (docs/**/*.md)
+    impls (
+      {
+        name rust
+        include (src/**/*.rs)
+      }
+    )
+  }
+)
+"#,
+    )
+    .expect("Failed to parse config");
+
+    let data = tracey::data::build_dashboard_data(root, &config, 1, true)
+        .await
+        .expect("Failed to build dashboard data");
+    let result = &data.validation_by_impl[&("auth".to_string(), "rust".to_string())];
+    let duplicates: Vec<_> = result
+        .errors
+        .iter()
+        .filter(|e| e.code == ValidationErrorCode::DuplicateReference)
+        .collect();
+
+    // Only the second impl ref inside `login` repeats one in the same unit.
+    assert_eq!(duplicates.len(), 1, "{:?}", result.errors);
+    assert_eq!(duplicates[0].line, Some(4));
+    assert!(
+        duplicates[0].message.contains("line 1"),
+        "{}",
+        duplicates[0].message
+    );
+    assert_eq!(result.warning_count, 1);
+    assert_eq!(result.error_count, result.errors.len() - 1);
+
+    let hints: Vec<_> = data
+        .workspace_diagnostics
+        .iter()
+        .flat_map(|file| &file.diagnostics)
+        .filter(|d| d.code == "duplicate-reference")
+        .collect();
+    assert_eq!(hints.len(), 1, "{:?}", data.workspace_diagnostics);
+    assert_eq!(hints[0].severity, "hint");
+    assert_eq!(hints[0].start_line, 3);
+}
+
+// r[verify cli.export]
+// r[verify cli.export.schema]
+#[tokio::test]
+async fn test_export_model_covers_rules_references_and_units() {
+    let engine = create_test_engine().await;
+    let data = engine.data().await;
+    let model = tracey::export::ExportModel::from_data(&data);
+
+    assert_eq!(model.schema_version, tracey::export::EXPORT_SCHEMA_VERSION);
+    let spec = model
+        .specs
+        .iter()
+        .find(|spec| spec.name == "test")
+        .expect("test spec should be exported");
+    assert!(spec.rules.iter().any(|rule| rule.id == "auth.session"));
+
+    let rust = spec
+        .impls
+        .iter()
+        .find(|i| i.name == "rust")
+        .expect("rust impl should be exported");
+    assert_eq!(rust.coverage.total_rules, spec.rules.len());
+    assert!(
+        rust.references
+            .iter()
+            .any(|r| r.rule_id == "auth.session" && r.kind == "impl")
+    );
+    assert!(!rust.code_units.is_empty());
+    assert!(
+        rust.code_units
+            .iter()
+            .all(|unit| !unit.file.starts_with(&model.project_root)),
+        "code unit paths should be project-relative"
+    );
+
+    #[cfg(feature = "sqlite")]
+    {
+        let temp = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = temp.path().join("export.sqlite");
+        model.write_sqlite(&path).expect("SQLite export failed");
+
+        let conn = rusqlite::Connection::open(&path).expect("open export");
+        let schema_version: String = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'schema_version'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("schema version row");
+        assert_eq!(schema_version, model.schema_version.to_string());
+        let rules: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM rules WHERE spec = 'test'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("rule count");
+        assert_eq!(rules as usize, spec.rules.len());
+    }
+}
+
+// r[verify cli.snapshot.record]
+// r[verify daemon.cli.query.trend]
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_daemon_records_history_once_started() {
+    let (temp, service) = create_isolated_test_service().await;
+    let req = || TrendRequest {
+        spec: Some("test".to_string()),
+        impl_name: None,
+        since_secs: Some(60 * 60),
+    };
+
+    // Without a history database the daemon neither records nor answers.
+    let _reload = rpc(service.client.reload().await);
+    assert!(service.client.coverage_trend(req()).await.is_err());
+    assert!(!tracey::history::history_path(temp.path()).exists());
+
+    // Once started, rebuilds append snapshots on their own.
+    tracey::history::History::open(temp.path()).expect("Failed to start history");
+    let _reload = rpc(service.client.reload().await);
+    let mut points = Vec::new();
+    for _ in 0..50 {
+        points = rpc(service.client.coverage_trend(req()).await).points;
+        if !points.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(points.len(), 1, "{points:?}");
+    assert_eq!(points[0].spec, "test");
+    assert_eq!(points[0].impl_name, "rust");
+    assert!(points[0].implemented_rules > 0);
+}
+
+// r[verify daemon.cli.query.recent]
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_daemon_lists_rules_changed_since_a_snapshot() {
+    let (temp, service) = create_isolated_test_service().await;
+    let req = |since: &str| RecentRequest {
+        since: since.to_string(),
+    };
+    assert!(service.client.recent(req("1")).await.is_err());
+
+    let old_rule = tracey::history::RuleRow {
+        spec: "test".to_string(),
+        impl_name: "rust".to_string(),
+        rule_id: "old.rule".to_string(),
+        text_digest: String::new(),
+        implemented: true,
+        verified: false,
+    };
+    let mut history = tracey::history::History::open(temp.path()).expect("Failed to start history");
+    history
+        .record(0, &[], &[old_rule])
+        .expect("Failed to record");
+
+    let response = rpc(service.client.recent(req("1")).await);
+    assert_eq!(response.baseline_version, 1);
+    assert!(response.latest_version >= 1);
+    let removed: Vec<&str> = response
+        .rules
+        .iter()
+        .filter(|rule| rule.change == RecentChange::Removed)
+        .map(|rule| rule.id.as_str())
+        .collect();
+    assert_eq!(removed, ["old.rule"]);
+    assert!(
+        response
+            .rules
+            .iter()
+            .any(|rule| rule.change == RecentChange::Added)
+    );
+    assert!(service.client.recent(req("9")).await.is_err());
+}
@@ -81,6 +81,10 @@ async fn test_mcp_uncovered_tool_no_filter() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: None,
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     let response = rpc(service.client.uncovered(req).await);
@@ -99,6 +103,10 @@ async fn test_mcp_uncovered_tool_with_prefix() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: Some("data".to_string()),
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     let response = rpc(service.client.uncovered(req).await);
@@ -124,6 +132,10 @@ async fn test_mcp_uncovered_tool_auto_select() {
         spec: None,
         impl_name: None,
         prefix: None,
+        owner: None,
+        sort: None,
+        limit: None,
+        offset: None,
     };
 
     let response = rpc(service.client.uncovered(req).await);
@@ -144,6 +156,10 @@ async fn test_mcp_untested_tool() {
         spec: Some("test".to_string()),
         impl_name: Some("rust".to_string()),
         prefix: None,
+        sort: None,
+        limit: None,
+        offset: None,
+        only_implemented: false,
     };
 
     let response = rpc(service.client.untested(req).await);
@@ -0,0 +1,136 @@
+//! Integration tests for `tracey merge-driver`.
+//!
+//! The merges run through the library API; `git merge-file` does the text
+//! merge, so git must be on the PATH.
+
+use std::fs;
+use std::process::Command;
+
+use tracey::config::{Config, SpecConfig};
+use tracey::merge::{install, merge_spec};
+use tracey_core::VersionScheme;
+
+const BASE: &str = "\
+# Spec
+
+r[auth.login]
+Users MUST provide valid credentials.
+Failed attempts MUST be logged.
+Accounts are locked after repeated failures.
+Locked accounts MUST be reported.
+
+r[auth.session]
+Sessions MUST expire after 24 hours of inactivity.
+";
+
+// r[verify cli.merge-driver]
+#[tokio::test]
+async fn test_both_sides_bumped_are_renumbered() {
+    let ours = BASE
+        .replace("r[auth.login]", "r[auth.login+2]")
+        .replace("MUST be logged", "MUST be logged with the client address");
+    let theirs = BASE
+        .replace("r[auth.login]", "r[auth.login+2]")
+        .replace("MUST be reported", "MUST be reported to admins");
+
+    let outcome = merge_spec(BASE, &ours, &theirs, VersionScheme::default())
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.conflicts, 0, "{}", outcome.merged);
+    assert!(
+        outcome.merged.contains("r[auth.login+3]\n"),
+        "{}",
+        outcome.merged
+    );
+    assert!(outcome.merged.contains("with the client address"));
+    assert!(outcome.merged.contains("reported to admins"));
+    assert!(outcome.merged.contains("r[auth.session]\n"));
+    let renumbered: Vec<String> = outcome.renumbered.iter().map(|id| id.to_string()).collect();
+    assert_eq!(renumbered, ["auth.login+3"]);
+}
+
+#[tokio::test]
+async fn test_conflicting_rule_text_is_left_for_review() {
+    let ours = BASE
+        .replace("r[auth.session]", "r[auth.session+2]")
+        .replace("24 hours", "12 hours");
+    let theirs = BASE
+        .replace("r[auth.session]", "r[auth.session+2]")
+        .replace("24 hours", "48 hours");
+
+    let outcome = merge_spec(BASE, &ours, &theirs, VersionScheme::default())
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.conflicts, 1, "{}", outcome.merged);
+    assert!(
+        outcome.merged.contains("r[auth.session+3]\n<<<<<<< ours"),
+        "{}",
+        outcome.merged
+    );
+    assert!(outcome.merged.contains("r[auth.login]\n"));
+}
+
+#[tokio::test]
+async fn test_bump_on_one_side_is_a_plain_merge() {
+    let ours = BASE.replace("r[auth.login]", "r[auth.login+2]");
+    let theirs = BASE.replace("24 hours", "a day");
+
+    let outcome = merge_spec(BASE, &ours, &theirs, VersionScheme::default())
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.conflicts, 0, "{}", outcome.merged);
+    assert!(outcome.renumbered.is_empty());
+    assert!(outcome.merged.contains("r[auth.login+2]\n"));
+    assert!(outcome.merged.contains("after a day"));
+}
+
+#[test]
+fn test_install_registers_driver_and_attributes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let status = Command::new("git")
+        .args(["init", "--initial-branch=main"])
+        .current_dir(root)
+        .status()
+        .expect("git not found");
+    assert!(status.success());
+    fs::write(root.join(".gitattributes"), "*.png binary").unwrap();
+
+    let config = Config {
+        specs: vec![SpecConfig {
+            name: "test".to_string(),
+            prefix: None,
+            source_url: None,
+            include: vec!["docs/**/*.md".to_string()],
+            rules_url: None,
+            rules_url_refresh: None,
+            source: None,
+            policy: None,
+            impls: vec![],
+        }],
+        ..Config::default()
+    };
+
+    assert_eq!(install(root, &config).unwrap(), ["docs/**/*.md"]);
+    assert!(
+        install(root, &config).unwrap().is_empty(),
+        "second install adds nothing"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join(".gitattributes")).unwrap(),
+        "*.png binary\ndocs/**/*.md merge=tracey\n"
+    );
+
+    let driver = Command::new("git")
+        .args(["config", "merge.tracey.driver"])
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&driver.stdout).trim(),
+        "tracey merge-driver %O %A %B %P"
+    );
+}
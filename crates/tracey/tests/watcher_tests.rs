@@ -225,6 +225,19 @@ async fn test_health_reports_watcher_error() {
     assert_eq!(health.watcher_error, Some("Connection lost".to_string()));
 }
 
+#[tokio::test]
+async fn test_health_reports_git_operation() {
+    let (service, state) = create_test_service_with_watcher().await;
+    let service = Arc::new(service);
+
+    let health = rpc(service.client.health().await);
+    assert!(!health.git_operation_in_progress);
+
+    state.set_git_operation_in_progress(true);
+    let health = rpc(service.client.health().await);
+    assert!(health.git_operation_in_progress);
+}
+
 // ============================================================================
 // Integration Tests for File Change Detection
 // ============================================================================
@@ -252,6 +265,49 @@ async fn test_engine_rebuild_increments_version() {
     );
 }
 
+// r[verify daemon.state.rebuild-queue]
+#[tokio::test]
+async fn test_concurrent_rebuilds_coalesce() {
+    use tracey::daemon::Engine;
+
+    let fixtures = common::fixtures_dir();
+    let config_path = fixtures.join("config.styx");
+
+    let engine = Arc::new(
+        Engine::new(fixtures, config_path)
+            .await
+            .expect("Failed to create engine"),
+    );
+    let version1 = engine.version();
+
+    let rebuilds: Vec<_> = (0..5)
+        .map(|_| {
+            let engine = Arc::clone(&engine);
+            tokio::spawn(async move { engine.rebuild().await })
+        })
+        .collect();
+    for rebuild in rebuilds {
+        rebuild
+            .await
+            .expect("Rebuild task panicked")
+            .expect("Rebuild failed");
+    }
+
+    // One running pass plus at most one queued pass (possibly restarted
+    // after a cancellation) cover all five requests.
+    let version2 = engine.version();
+    assert!(
+        version2 > version1,
+        "Version should increment after rebuild"
+    );
+    assert!(
+        version2 <= version1 + 2,
+        "Queued rebuilds should coalesce, got {} passes",
+        version2 - version1
+    );
+    assert_eq!(engine.rebuild_queue_depth().await, 0);
+}
+
 // ============================================================================
 // Helper: create a temp project for engine rebuild tests
 // ============================================================================